@@ -0,0 +1,148 @@
+use crate::{Backup, Config, Pca9685Error, Pca9685Result, SequenceConfig, Snapshot};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::Read;
+
+/// The entries of a backup archive
+const CONFIG_ENTRY: &str = "config.yaml";
+const SOURCE_ENTRY: &str = "config.source.yaml";
+const SEQUENCES_DIR: &str = "sequences/";
+const STATE_ENTRY: &str = "state.yaml";
+
+fn invalid(error: impl ToString) -> Pca9685Error {
+    Pca9685Error::InvalidConfiguration(format!("Invalid backup: {}", error.to_string()))
+}
+
+fn to_yaml<T: Serialize>(entry: &str, value: &T) -> Pca9685Result<String> {
+    serde_yaml::to_string(value).map_err(|error| invalid(format!("{}: {}", entry, error)))
+}
+
+fn from_yaml<T: DeserializeOwned>(entry: &str, yaml: &str) -> Pca9685Result<T> {
+    serde_yaml::from_str(yaml).map_err(|error| invalid(format!("{}: {}", entry, error)))
+}
+
+impl Backup {
+    /// Bundles the backup as a tar archive of `config.yaml` (as by
+    /// [Config::to_yaml], i.e. a configuration file), `config.source.yaml`
+    /// (the source, if any), `sequences/<name>.yaml` for each sequence, and
+    /// `state.yaml` (the [Snapshot]).
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if an entry cannot be
+    ///   serialized
+    pub fn to_tar(&self) -> Pca9685Result<Vec<u8>> {
+        let mut entries = vec![(CONFIG_ENTRY.to_string(), self.config.to_yaml()?)];
+        if let Some(source) = &self.source {
+            entries.push((SOURCE_ENTRY.to_string(), source.clone()));
+        }
+        for sequence in &self.sequences {
+            let entry = format!("{}{}.yaml", SEQUENCES_DIR, sequence.name);
+            let yaml = to_yaml(&entry, sequence)?;
+            entries.push((entry, yaml));
+        }
+        entries.push((
+            STATE_ENTRY.to_string(),
+            to_yaml(STATE_ENTRY, &self.snapshot)?,
+        ));
+
+        let mut builder = tar::Builder::new(Vec::new());
+        for (entry, contents) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            builder
+                .append_data(&mut header, &entry, contents.as_bytes())
+                .map_err(|error| invalid(format!("{}: {}", entry, error)))?;
+        }
+
+        builder.into_inner().map_err(invalid)
+    }
+
+    /// Reads a backup from a tar archive written by [Backup::to_tar],
+    /// ignoring any other entries.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if the archive cannot be read,
+    ///   lacks `config.yaml` or `state.yaml`, or an entry cannot be parsed
+    pub fn from_tar(bytes: &[u8]) -> Pca9685Result<Backup> {
+        let mut config: Option<Config> = None;
+        let mut source: Option<String> = None;
+        let mut snapshot: Option<Snapshot> = None;
+        let mut sequences: Vec<SequenceConfig> = Vec::new();
+
+        let mut archive = tar::Archive::new(bytes);
+        for entry in archive.entries().map_err(invalid)? {
+            let mut entry = entry.map_err(invalid)?;
+            let path = entry.path().map_err(invalid)?;
+            let path = path.to_string_lossy().into_owned();
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+
+            let mut yaml = String::new();
+            entry
+                .read_to_string(&mut yaml)
+                .map_err(|error| invalid(format!("{}: {}", path, error)))?;
+            if path == CONFIG_ENTRY {
+                config = Some(from_yaml(&path, &yaml)?);
+            } else if path == SOURCE_ENTRY {
+                source = Some(yaml);
+            } else if path == STATE_ENTRY {
+                snapshot = Some(from_yaml(&path, &yaml)?);
+            } else if path.starts_with(SEQUENCES_DIR) && path.ends_with(".yaml") {
+                sequences.push(from_yaml(&path, &yaml)?);
+            }
+        }
+
+        Ok(Backup {
+            config: config.ok_or_else(|| invalid(format!("{} is missing", CONFIG_ENTRY)))?,
+            source,
+            sequences,
+            snapshot: snapshot.ok_or_else(|| invalid(format!("{} is missing", STATE_ENTRY)))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Backup, CommandType, Config, Keyframe, Pca9685Error, SequenceConfig, Snapshot};
+
+    #[test]
+    fn tar() {
+        let config: Config =
+            serde_yaml::from_str("device: /dev/i2c-1\naddress: 0x40\noutput_frequency_hz: 200\n")
+                .unwrap();
+        let backup = Backup {
+            config,
+            source: Some("include: [base.yaml]\n".to_string()),
+            sequences: vec![SequenceConfig {
+                name: "wave".to_string(),
+                duration_ms: None,
+                keyframes: vec![Keyframe {
+                    t_ms: 0,
+                    channel: 0,
+                    command_type: CommandType::PulseWidth,
+                    value: Some(1.5),
+                }],
+                steps: Vec::new(),
+            }],
+            snapshot: Snapshot {
+                channels: Vec::new(),
+                mixer_inputs: Default::default(),
+                teleop_profile: Some("drive".to_string()),
+            },
+        };
+
+        let restored = Backup::from_tar(&backup.to_tar().unwrap()).unwrap();
+        assert_eq!(restored.config.address, 0x40);
+        assert_eq!(restored.config.output_frequency_hz, 200);
+        assert_eq!(restored.source, backup.source);
+        assert_eq!(restored.sequences, backup.sequences);
+        assert_eq!(restored.snapshot.teleop_profile.as_deref(), Some("drive"));
+
+        assert!(matches!(
+            Backup::from_tar(b"not a tar archive"),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+    }
+}
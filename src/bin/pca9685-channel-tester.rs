@@ -1,33 +1,2067 @@
-use clap::Parser;
-use env_logger;
-use pca9685::{Config, Pca9685};
+use clap::{Parser, Subcommand, ValueEnum};
+#[cfg(feature = "stress")]
+use pca9685::stress::{self, StressReport, StressTest};
+use pca9685::{
+    utils, ChannelCommand, ChannelConfig, ChannelLimits, ChannelPulseWidthLimits, CommandType,
+    Config, DisplayUnit, FindingSeverity, GpioPin, LoopbackReport, Pca9685, Pca9685Error,
+    PulseStats, StepTest, TestVectors, INTERNAL_OSC_HZ, LOOPBACK_GLITCH_TOLERANCE,
+    PCA_FREQUENCY_HZ, PCA_PWM_RESOLUTION, SERVO_SAFE_FREQUENCY_HZ,
+};
 use pwm_pca9685::Channel;
+use serde_yaml::{Mapping, Value};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::process;
+#[cfg(feature = "stress")]
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 /// Simple program to interact with a PCA9685
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
+#[command(subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Channel
-    #[arg(value_parser = clap::value_parser!(u8).range(..16))]
-    channel: u8,
+    #[arg(required = true, value_parser = clap::value_parser!(u8).range(..16))]
+    channel: Option<u8>,
 
     /// Pulse width (ms)
-    #[arg()]
-    pulse_width_ms: f64,
+    #[arg(required = true)]
+    pulse_width_ms: Option<f64>,
+
+    /// Print the count/pulse width that would be written (after applying the
+    /// channel's configured limits) without opening the device
+    #[arg(long)]
+    check: bool,
+
+    /// Print the derivation of the count written, from the output frequency
+    /// to the bytes of the channel's registers
+    #[arg(long)]
+    explain: bool,
 
     /// Path to configuration file
-    #[arg(long, default_value = "/etc/pca9685.yaml")]
+    #[arg(long, global = true, default_value = "/etc/pca9685.yaml")]
     config_file_path: String,
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Repeatedly sweep a channel between two pulse widths, e.g. to qualify
+    /// a servo before installing it
+    BurnIn {
+        /// Channel
+        #[arg(long, value_parser = clap::value_parser!(u8).range(..16))]
+        channel: u8,
+
+        /// Number of (min -> max -> min) cycles
+        #[arg(long, default_value_t = 1000)]
+        cycles: u64,
+
+        /// Minimum pulse width (ms)
+        #[arg(long)]
+        min: f64,
+
+        /// Maximum pulse width (ms)
+        #[arg(long)]
+        max: f64,
+
+        /// Time to hold each end of the sweep (ms)
+        #[arg(long, default_value_t = 500)]
+        dwell_ms: u64,
+
+        /// Print progress/statistics every this many cycles
+        #[arg(long, default_value_t = 100)]
+        report_every: u64,
+    },
+
+    /// Output a staircase of counts, alternating across channels, on a fixed
+    /// schedule, printing the expected timings for comparison with a logic
+    /// analyzer
+    Pattern {
+        /// Channels to alternate between (e.g., 0,1)
+        #[arg(long, required = true, value_delimiter = ',', value_parser = clap::value_parser!(u8).range(..16))]
+        channels: Vec<u8>,
+
+        /// Number of steps in the staircase
+        #[arg(long, default_value_t = 8, value_parser = clap::value_parser!(u16).range(1..4096))]
+        steps: u16,
+
+        /// Time to hold each step (ms)
+        #[arg(long, default_value_t = 100)]
+        step_ms: u64,
+
+        /// Number of times to repeat the staircase
+        #[arg(long, default_value_t = 1)]
+        repeat: u32,
+    },
+
+    /// Step a channel between two counts, printing its response (including
+    /// any configured feedback) as CSV, e.g. to measure rise time and
+    /// overshoot
+    StepTest {
+        /// Channel
+        #[arg(long, value_parser = clap::value_parser!(u8).range(..16))]
+        channel: u8,
+
+        /// Count to settle at before the step
+        #[arg(long)]
+        from: u16,
+
+        /// Count to step to
+        #[arg(long)]
+        to: u16,
+
+        /// Time to settle at --from before the step (ms)
+        #[arg(long, default_value_t = StepTest::default_settle_ms())]
+        settle_ms: u64,
+
+        /// Time to record after the step (ms)
+        #[arg(long, default_value_t = StepTest::default_duration_ms())]
+        duration_ms: u64,
+
+        /// Time between samples (us)
+        #[arg(long, default_value_t = StepTest::default_sample_interval_us())]
+        sample_interval_us: u64,
+    },
+
+    /// Report the actual output frequency (and prescale rounding error) for
+    /// each requested frequency in a range, to help pick a frequency whose
+    /// prescale lands closest to its target
+    FrequencySweep {
+        /// Lowest requested frequency (Hz)
+        #[arg(long, default_value_t = *SERVO_SAFE_FREQUENCY_HZ.start(), value_parser = frequency_hz_parser)]
+        from: u16,
+
+        /// Highest requested frequency (Hz)
+        #[arg(long, default_value_t = *SERVO_SAFE_FREQUENCY_HZ.end(), value_parser = frequency_hz_parser)]
+        to: u16,
+
+        /// Frequency step (Hz)
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+        step: u16,
+    },
+
+    /// Merge channel limits from a CSV file (channel,min,max[,neutral,reversed])
+    /// into the configuration file, replacing the `custom_limits` of each
+    /// listed channel.  A given neutral sets the channel's `trim_counts`, and
+    /// a given reversed its `inverted`.  Comments in the configuration file
+    /// are not preserved
+    ImportLimits {
+        /// Path to CSV file; a header row and lines starting with '#' are
+        /// skipped
+        csv_file_path: String,
+
+        /// Interpret min/max as counts rather than pulse widths (ms)
+        #[arg(long)]
+        counts: bool,
+    },
+
+    /// Print a channel's conversion table (input -> count -> actual pulse
+    /// width) as CSV, under its configured limits and the configured output
+    /// frequency, without opening the device
+    Table {
+        /// Channel
+        #[arg(long, value_parser = clap::value_parser!(u8).range(..16))]
+        channel: u8,
+
+        /// Unit of the inputs
+        #[arg(long, value_enum)]
+        unit: TableUnit,
+
+        /// Interval between inputs (in --unit)
+        #[arg(long)]
+        step: f64,
+
+        /// Degrees spanned by the channel's limits, with --unit deg (by
+        /// default, those of its `display_unit`, otherwise 180)
+        #[arg(long)]
+        range: Option<f64>,
+    },
+
+    /// Execute the commands of a test vectors file (its own configuration,
+    /// commands, and the writes each must produce) against a mock device,
+    /// reporting any whose writes differ, without opening the device
+    Verify {
+        /// Path to YAML test vectors file
+        vectors_file_path: String,
+    },
+
+    /// Measure command latency (from the call to the completion of its I2C
+    /// write) and the sustainable update rate, of a single channel and of
+    /// batches of all 16, printing a report.  Each channel alternates
+    /// between its current output and the adjacent count.
+    Bench {
+        /// Channel to measure alone
+        #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(..16))]
+        channel: u8,
+
+        /// Number of commands (and of batches)
+        #[arg(long, default_value_t = 1000, value_parser = clap::value_parser!(u32).range(1..))]
+        iterations: u32,
+
+        /// Measure against a mock device rather than opening the device,
+        /// i.e. the overhead of everything but the I2C bus
+        #[arg(long)]
+        mock: bool,
+    },
+
+    /// Stress the library with concurrent clients issuing randomized (valid)
+    /// commands to every channel accepting them, verifying that none
+    /// deadlock and that each channel ends in a state some client commanded
+    #[cfg(feature = "stress")]
+    Stress {
+        /// Number of concurrent clients
+        #[arg(long, default_value_t = 8, value_parser = clap::value_parser!(u16).range(1..))]
+        clients: u16,
+
+        /// Number of commands issued by each client
+        #[arg(long, default_value_t = 1000)]
+        commands: usize,
+
+        /// Seed of the clients' commands (by default, random)
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Seconds without any command completing before declaring a
+        /// deadlock
+        #[arg(long, default_value_t = 10)]
+        stall_timeout_s: u64,
+
+        /// Stress a mock device rather than opening the device
+        #[arg(long)]
+        mock: bool,
+    },
+
+    /// Measure a channel's output looped back (by a jumper) to a GPIO input,
+    /// reporting the pulse widths and periods seen over many cycles, e.g. to
+    /// quantify the oscillator's error and glitches induced by the I2C bus
+    Loopback {
+        /// Channel
+        #[arg(long, value_parser = clap::value_parser!(u8).range(..16))]
+        channel: u8,
+
+        /// GPIO input the channel is looped back to: a pin number (BCM
+        /// numbering, on a Raspberry Pi), a line of a character device
+        /// (e.g., /dev/gpiochip0:17), or a file (e.g., a sysfs value)
+        #[arg(long, value_parser = gpio_pin_parser)]
+        input: GpioPin,
+
+        /// Pulse width (ms) to output while measuring (by default, the
+        /// channel's current output)
+        #[arg(long)]
+        pulse_width_ms: Option<f64>,
+
+        /// Number of cycles to measure
+        #[arg(long, default_value_t = 1000, value_parser = clap::value_parser!(u32).range(1..))]
+        cycles: u32,
+    },
+
+    /// Walk through creating the configuration file: the device (from a
+    /// scan of the I2C buses), the output frequency and, optionally, a name
+    /// for each channel
+    Init {
+        /// Overwrite an existing configuration file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum TableUnit {
+    Counts,
+    Ms,
+    Us,
+    Pct,
+    Deg,
+}
+
+fn frequency_hz_parser(value: &str) -> Result<u16, String> {
+    let frequency_hz: u16 = value
+        .parse()
+        .map_err(|_| format!("invalid frequency: {}", value))?;
+
+    if SERVO_SAFE_FREQUENCY_HZ.contains(&frequency_hz) {
+        Ok(frequency_hz)
+    } else {
+        Err(format!(
+            "{}Hz is outside the servo-safe range [{}, {}]",
+            frequency_hz,
+            SERVO_SAFE_FREQUENCY_HZ.start(),
+            SERVO_SAFE_FREQUENCY_HZ.end()
+        ))
+    }
+}
+
+fn gpio_pin_parser(value: &str) -> Result<GpioPin, String> {
+    if let Ok(pin) = value.parse() {
+        return Ok(GpioPin::Pin(pin));
+    }
+
+    match value.rsplit_once(':') {
+        Some((chip, line)) if chip.starts_with("/dev/") => Ok(GpioPin::Line {
+            chip: chip.to_string(),
+            line: line
+                .parse()
+                .map_err(|_| format!("invalid line: {}", line))?,
+        }),
+        _ if value.starts_with('/') => Ok(GpioPin::Path(value.to_string())),
+        _ => Err(format!("invalid GPIO input: {}", value)),
+    }
+}
+
+/// Write latency statistics gathered during a burn-in
+#[derive(Debug, Default)]
+struct WriteStats {
+    count: u64,
+    total: Duration,
+    min: Option<Duration>,
+    max: Duration,
+}
+
+impl WriteStats {
+    fn record(&mut self, latency: Duration) {
+        self.count += 1;
+        self.total += latency;
+        self.min = Some(self.min.map_or(latency, |min| min.min(latency)));
+        self.max = self.max.max(latency);
+    }
+
+    fn mean(&self) -> Duration {
+        match self.count {
+            0 => Duration::ZERO,
+            count => self.total / count as u32,
+        }
+    }
+}
+
+/// The latencies of a [bench] run, in order
+#[derive(Debug)]
+struct BenchRun {
+    latencies: Vec<Duration>,
+    elapsed: Duration,
+}
+
+impl BenchRun {
+    /// Returns the latency below which `pct` percent of the latencies fall
+    fn percentile(&self, pct: f64) -> Duration {
+        let mut sorted = self.latencies.clone();
+        sorted.sort_unstable();
+        let index = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+
+        sorted[index.clamp(1, sorted.len()) - 1]
+    }
+
+    /// Returns the number of commands (or batches) completed per second
+    fn rate(&self) -> f64 {
+        self.latencies.len() as f64 / self.elapsed.as_secs_f64()
+    }
+
+    fn report(&self, title: &str, updates_per_run: usize) -> String {
+        let mut stats = WriteStats::default();
+        for latency in &self.latencies {
+            stats.record(*latency);
+        }
+
+        format!(
+            "{}: {} in {:0.3}s\n  \
+             latency min/mean/p50/p99/max: {:?}/{:?}/{:?}/{:?}/{:?}\n  \
+             sustainable rate: {:0.1}/s ({:0.1} channel updates/s)\n",
+            title,
+            self.latencies.len(),
+            self.elapsed.as_secs_f64(),
+            stats.min.unwrap_or_default(),
+            stats.mean(),
+            self.percentile(50.0),
+            self.percentile(99.0),
+            stats.max,
+            self.rate(),
+            self.rate() * updates_per_run as f64
+        )
+    }
+}
+
+/// Returns the counts `channel_config` alternates between while
+/// benchmarking: its current output (or the middle of its limits) and the
+/// adjacent count within its limits
+fn bench_counts(channel_config: &ChannelConfig) -> [u16; 2] {
+    let (min, max) = channel_config.limits();
+    let max = max.min(PCA_PWM_RESOLUTION - 1);
+    let count = channel_config
+        .current_count
+        .filter(|count| (min..=max).contains(count))
+        .unwrap_or(min + (max - min) / 2);
+
+    if count < max {
+        [count, count + 1]
+    } else {
+        [count, count - 1]
+    }
+}
+
+/// Returns `report` (see [StressTest::run]) of a test seeded by `seed` as
+/// text
+#[cfg(feature = "stress")]
+fn stress_report(report: &StressReport, seed: u64) -> String {
+    let mut text = format!(
+        "{} commands in {:0.3}s (seed {}): {}\n",
+        report.completed,
+        report.elapsed.as_secs_f64(),
+        seed,
+        if report.passed() { "passed" } else { "FAILED" }
+    );
+    for (client, command) in &report.stalled {
+        text += &format!("  deadlock: client {} stalled in {:?}\n", client, command);
+    }
+    if report.error_count > 0 {
+        text += &format!("  {} command(s) failed, e.g.:\n", report.error_count);
+    }
+    for error in &report.errors {
+        text += &format!("    {}\n", error);
+    }
+    for inconsistency in &report.inconsistencies {
+        text += &format!("  inconsistent: {}\n", inconsistency);
+    }
+
+    text
+}
+
+/// Returns `report` (see [Pca9685::measure_loopback]) as text
+fn loopback_report(report: &LoopbackReport) -> String {
+    let row = |name: &str, expected_ms: f64, stats: &PulseStats| {
+        format!(
+            "{:<11}  {:>11.4}  {:>9.4}  {:>9.4}  {:>9.4}  {:>10.4}\n",
+            name, expected_ms, stats.min_ms, stats.mean_ms, stats.max_ms, stats.std_dev_ms
+        )
+    };
+
+    format!(
+        "Channel {}: {} cycles, sampled every {:0.2}us\n\
+         {:<11}  {:>11}  {:>9}  {:>9}  {:>9}  {:>10}\n\
+         {}{}\
+         Frequency error: {:+0.3}%\n\
+         Glitches: {} (straying over {:0.0}% from the median)\n",
+        report.channel as u8,
+        report.cycles,
+        report.sample_interval_us,
+        "",
+        "expected_ms",
+        "min_ms",
+        "mean_ms",
+        "max_ms",
+        "std_dev_ms",
+        row(
+            "pulse width",
+            report.expected_pulse_width_ms,
+            &report.pulse_width
+        ),
+        row("period", report.expected_period_ms, &report.period),
+        report.frequency_error_pct,
+        report.glitches,
+        100.0 * LOOPBACK_GLITCH_TOLERANCE
+    )
+}
+
+/// Times `iterations` commands to `channel`, then `iterations` atomic
+/// batches of commands to every channel (see [Pca9685::apply])
+fn bench(
+    pca: &Pca9685,
+    channel: Channel,
+    iterations: u32,
+) -> Result<(BenchRun, BenchRun), Pca9685Error> {
+    let time = |run: &mut dyn FnMut(usize) -> Result<(), Pca9685Error>| {
+        let mut latencies = Vec::with_capacity(iterations as usize);
+        let started = Instant::now();
+        for iteration in 0..iterations as usize {
+            let command_started = Instant::now();
+            run(iteration)?;
+            latencies.push(command_started.elapsed());
+        }
+
+        Ok(BenchRun {
+            latencies,
+            elapsed: started.elapsed(),
+        })
+    };
+
+    let counts = bench_counts(&pca.config(channel)?);
+    let single = time(&mut |iteration| {
+        pca.set_pwm_count(channel, counts[iteration % 2])
+            .map(|_| ())
+    })?;
+
+    let all_counts: Vec<(Channel, [u16; 2])> = (0..16_u8)
+        .map(|raw_channel| {
+            let channel = Channel::try_from(raw_channel).unwrap();
+            pca.config(channel)
+                .map(|channel_config| (channel, bench_counts(&channel_config)))
+        })
+        .collect::<Result<_, _>>()?;
+    let batch = time(&mut |iteration| {
+        let commands: Vec<ChannelCommand> = all_counts
+            .iter()
+            .map(|(channel, counts)| ChannelCommand {
+                channel: *channel,
+                command_type: CommandType::PulseCount,
+                value: Some(counts[iteration % 2] as f64),
+                basis: None,
+                client_id: None,
+            })
+            .collect();
+        pca.apply(&commands, true).map(|_| ())
+    })?;
+
+    Ok((single, batch))
+}
+
+fn burn_in(
+    pca: &Pca9685,
+    channel: Channel,
+    cycles: u64,
+    pulse_widths_ms: (f64, f64),
+    dwell: Duration,
+    report_every: u64,
+) -> Result<WriteStats, Pca9685Error> {
+    let mut stats = WriteStats::default();
+    let started = Instant::now();
+
+    for cycle in 1..=cycles {
+        for pulse_width_ms in [pulse_widths_ms.1, pulse_widths_ms.0] {
+            let write_started = Instant::now();
+            pca.set_pw_ms(channel, pulse_width_ms)?;
+            stats.record(write_started.elapsed());
+
+            thread::sleep(dwell);
+        }
+
+        if cycle % report_every == 0 || cycle == cycles {
+            println!(
+                "{}/{} cycles ({:0.1}%) in {:0.1}s; write latency min/mean/max: {:?}/{:?}/{:?}",
+                cycle,
+                cycles,
+                100.0 * cycle as f64 / cycles as f64,
+                started.elapsed().as_secs_f64(),
+                stats.min.unwrap_or_default(),
+                stats.mean(),
+                stats.max
+            );
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Returns the count written at `step` of a staircase with `steps` steps,
+/// evenly dividing (0, 4096)
+fn staircase_count(step: u16, steps: u16) -> u16 {
+    ((step as u32 + 1) * PCA_PWM_RESOLUTION as u32 / (steps as u32 + 1)) as u16
+}
+
+fn pattern(
+    pca: &Pca9685,
+    channels: &[Channel],
+    steps: u16,
+    step: Duration,
+    repeat: u32,
+) -> Result<(), Pca9685Error> {
+    let count_duration_ms = pca.single_count_duration_ms();
+
+    println!(
+        "Period: {:0.4}ms ({:0.3}Hz); each count: {:0.6}ms; each step: {:?}",
+        pca.max_pw_ms(),
+        pca.actual_output_frequency_hz(),
+        count_duration_ms,
+        step
+    );
+    println!("expected_at_ms  actual_at_ms  channel  count  expected_high_ms");
+
+    let started = Instant::now();
+    let mut deadline = started;
+    for iteration in 0..repeat {
+        for index in 0..steps {
+            let channel = channels[(index as usize) % channels.len()];
+            let count = staircase_count(index, steps);
+
+            // Sleep until the scheduled time (rather than for a fixed duration)
+            // so that timing errors don't accumulate
+            thread::sleep(deadline.saturating_duration_since(Instant::now()));
+            let written_at = started.elapsed();
+            pca.set_pwm_count(channel, count)?;
+
+            println!(
+                "{:>14.3}  {:>12.3}  {:>7}  {:>5}  {:>16.4}",
+                (deadline - started).as_secs_f64() * 1000.0,
+                written_at.as_secs_f64() * 1000.0,
+                channel as u8,
+                count,
+                count as f64 * count_duration_ms
+            );
+
+            deadline += step;
+        }
+
+        log::debug!("Completed pattern {}/{}", iteration + 1, repeat);
+    }
+
+    thread::sleep(deadline.saturating_duration_since(Instant::now()));
+    for channel in channels {
+        pca.full_off(*channel)?;
+    }
+
+    Ok(())
+}
+
+fn frequency_sweep(from_hz: u16, to_hz: u16, step_hz: u16) {
+    let steps = utils::frequency_sweep(from_hz, to_hz, step_hz);
+
+    println!("requested_hz  prescale  actual_hz  error_pct");
+    for step in &steps {
+        println!(
+            "{:>12}  {:>8}  {:>9.3}  {:>+9.3}",
+            step.requested_hz, step.prescale, step.actual_hz, step.error_pct
+        );
+    }
+
+    if let Some(best) = steps
+        .iter()
+        .min_by(|a, b| a.error_pct.abs().total_cmp(&b.error_pct.abs()))
+    {
+        println!(
+            "Closest: {}Hz (prescale {}, actual {:0.3}Hz, error {:+0.3}%)",
+            best.requested_hz, best.prescale, best.actual_hz, best.error_pct
+        );
+    }
+}
+
+/// Resolves a pulse width for `channel` against its configured limits,
+/// returning the [ChannelConfig] that would result, without writing it
+fn check(
+    pca: &Pca9685,
+    channel: Channel,
+    pulse_width_ms: f64,
+) -> Result<ChannelConfig, Pca9685Error> {
+    let command = ChannelCommand {
+        channel,
+        command_type: CommandType::PulseWidth,
+        value: Some(pulse_width_ms),
+        basis: None,
+        client_id: None,
+    };
+
+    pca.preview(channel, &command)
+}
+
+/// The first of the registers (ON_L, ON_H, OFF_L, OFF_H) of LED0; those of
+/// each further channel follow
+const LED0_ON_L_REGISTER: u8 = 0x06;
+
+/// Returns the derivation of the count written to `channel` for
+/// `pulse_width_ms`, resulting in `channel_config`: the output frequency,
+/// the length of its cycle and of each count, the count (as adjusted by the
+/// channel's configuration) and the bytes written to its registers
+fn explain(
+    pca: &Pca9685,
+    channel: Channel,
+    pulse_width_ms: f64,
+    channel_config: &ChannelConfig,
+) -> String {
+    let output_frequency_hz = pca.output_frequency_hz();
+    let prescale = pca.prescale();
+    let oscillator_hz = pca.oscillator_hz();
+    let actual_hz = pca.actual_output_frequency_hz();
+    let cycle_ms = pca.max_pw_ms();
+    let count_ms = pca.single_count_duration_ms();
+    let exact_count = pulse_width_ms / count_ms;
+
+    let mut lines = vec![
+        format!("Output frequency:  {}Hz", output_frequency_hz),
+        format!(
+            "Prescale:          round({} / ({} x {}Hz)) - 1 = {}",
+            oscillator_hz, PCA_PWM_RESOLUTION, output_frequency_hz, prescale
+        ),
+        format!(
+            "Actual frequency:  {} / ({} x ({} + 1)) = {:0.3}Hz",
+            oscillator_hz, PCA_PWM_RESOLUTION, prescale, actual_hz
+        ),
+        format!(
+            "Cycle:             1000 / {:0.3}Hz = {:0.4}ms",
+            actual_hz, cycle_ms
+        ),
+        format!(
+            "Each count:        {:0.4}ms / {} = {:0.6}ms",
+            cycle_ms, PCA_PWM_RESOLUTION, count_ms
+        ),
+        format!("Requested:         {:0.4}ms", pulse_width_ms),
+        format!(
+            "Count:             {:0.4}ms / {:0.6}ms = {:0.2} -> {}",
+            pulse_width_ms,
+            count_ms,
+            exact_count,
+            exact_count.round()
+        ),
+    ];
+
+    let Some(count) = channel_config.current_count else {
+        lines.push("Output:            full off".to_string());
+        return lines.join("\n") + "\n";
+    };
+    if count as f64 != exact_count.round() {
+        lines.push(format!(
+            "Adjusted:          {} (per the channel's configuration)",
+            count
+        ));
+    }
+    let written = if count >= PCA_PWM_RESOLUTION {
+        count
+    } else {
+        channel_config.trimmed(count)
+    };
+    if written != count {
+        lines.push(format!(
+            "Trimmed:           {} {:+} = {}",
+            count, channel_config.trim_counts, written
+        ));
+    }
+    lines.push(format!(
+        "Actual pulse:      {} x {:0.6}ms = {:0.4}ms",
+        written,
+        count_ms,
+        written as f64 * count_ms
+    ));
+
+    // ON at the channel's ON count (0 unless staggered), OFF the count later,
+    // wrapping around the end of the cycle (bit 4 of ON_H alone means full on)
+    let (on, off) = if written >= PCA_PWM_RESOLUTION {
+        (0x1000_u16, 0)
+    } else {
+        let on = pca.on_count(channel);
+        if on != 0 {
+            lines.push(format!(
+                "Staggered:         ON at {}, OFF at ({} + {}) % {} = {}",
+                on,
+                on,
+                written,
+                PCA_PWM_RESOLUTION,
+                (on + written) % PCA_PWM_RESOLUTION
+            ));
+        }
+        (on, (on + written) % PCA_PWM_RESOLUTION)
+    };
+    let register = LED0_ON_L_REGISTER + 4 * channel as u8;
+    lines.push(format!(
+        "Registers:         LED{}_ON_L..LED{}_OFF_H ({:#04x}..{:#04x}) = {:02x} {:02x} {:02x} {:02x}",
+        channel as u8,
+        channel as u8,
+        register,
+        register + 3,
+        on & 0xff,
+        on >> 8,
+        off & 0xff,
+        off >> 8
+    ));
+
+    lines.join("\n") + "\n"
+}
+
+/// A row of a limits CSV file
+#[derive(Debug, PartialEq)]
+struct LimitsRow {
+    channel: u8,
+    limits: ChannelLimits,
+
+    /// Where the servo is centered, in the unit of `limits`; imported as the
+    /// Channel's `trim_counts`
+    neutral: Option<f64>,
+
+    /// Imported as the Channel's `inverted`
+    reversed: Option<bool>,
+}
+
+/// Parses `channel,min,max[,neutral,reversed]` rows into the limits of each
+/// channel.  A blank `neutral` or `reversed` leaves the Channel's
+/// `trim_counts` or `inverted` as configured.
+fn parse_limits_csv(csv: &str, counts: bool) -> Result<Vec<LimitsRow>, String> {
+    let mut rows = Vec::new();
+    let mut seen = HashSet::new();
+
+    for (index, line) in csv.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if rows.is_empty() && fields[0].eq_ignore_ascii_case("channel") {
+            continue;
+        }
+        if !(3..=5).contains(&fields.len()) {
+            return Err(format!(
+                "line {}: expected channel,min,max[,neutral,reversed]",
+                line_number
+            ));
+        }
+
+        let channel = fields[0]
+            .parse::<u8>()
+            .ok()
+            .filter(|channel| *channel < 16)
+            .ok_or(format!(
+                "line {}: invalid channel '{}'",
+                line_number, fields[0]
+            ))?;
+        if !seen.insert(channel) {
+            return Err(format!(
+                "line {}: duplicate channel {}",
+                line_number, channel
+            ));
+        }
+
+        let invalid = |field: &str| format!("line {}: invalid limit '{}'", line_number, field);
+        let limits = if counts {
+            let min = fields[1].parse::<u16>().map_err(|_| invalid(fields[1]))?;
+            let max = fields[2].parse::<u16>().map_err(|_| invalid(fields[2]))?;
+            ChannelLimits::from_count_limits(min, max)
+        } else {
+            let min = fields[1].parse::<f64>().map_err(|_| invalid(fields[1]))?;
+            let max = fields[2].parse::<f64>().map_err(|_| invalid(fields[2]))?;
+            ChannelLimits {
+                count_limits: None,
+                pw_limits: Some(ChannelPulseWidthLimits {
+                    min_on_ms: min,
+                    max_on_ms: max,
+                }),
+            }
+        };
+
+        let neutral = match fields.get(3) {
+            Some(neutral) if !neutral.is_empty() => {
+                let value = neutral
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|value| value.is_finite());
+                Some(value.ok_or(format!(
+                    "line {}: invalid neutral '{}'",
+                    line_number, neutral
+                ))?)
+            }
+            _ => None,
+        };
+        let reversed = match fields.get(4) {
+            Some(reversed) if !reversed.is_empty() => {
+                Some(reversed.to_ascii_lowercase().parse::<bool>().map_err(|_| {
+                    format!("line {}: invalid reversed '{}'", line_number, reversed)
+                })?)
+            }
+            _ => None,
+        };
+
+        rows.push(LimitsRow {
+            channel,
+            limits,
+            neutral,
+            reversed,
+        });
+    }
+
+    Ok(rows)
+}
+
+/// Merges `rows` into the `channels` of the YAML configuration `yaml`,
+/// returning the merged configuration once it passes
+/// [Pca9685::validate_config].  A row's `neutral` becomes the `trim_counts`
+/// moving the middle of its limits onto it, at the configured output frequency.
+fn merge_limits(yaml: &str, rows: &[LimitsRow]) -> Result<String, String> {
+    let mut root: Value = serde_yaml::from_str(yaml).map_err(|error| error.to_string())?;
+    let config: Config = serde_yaml::from_value(root.clone()).map_err(|error| error.to_string())?;
+    let count_ms = 1000.0
+        / utils::prescale_output_frequency_hz(config.oscillator_hz(), config.effective_prescale())
+        / PCA_PWM_RESOLUTION as f64;
+
+    let root_mapping = root
+        .as_mapping_mut()
+        .ok_or("configuration is not a mapping")?;
+    let channels = root_mapping
+        .entry(Value::from("channels"))
+        .or_insert_with(|| Value::Sequence(Vec::new()))
+        .as_sequence_mut()
+        .ok_or("channels is not a sequence")?;
+
+    for row in rows {
+        let (channel, channel_limits) = (&row.channel, &row.limits);
+        let mut custom_limits = Mapping::new();
+        if let Some(count_limits) = &channel_limits.count_limits {
+            custom_limits.insert(
+                Value::from("count_limits"),
+                serde_yaml::to_value(count_limits).map_err(|error| error.to_string())?,
+            );
+        }
+        if let Some(pw_limits) = &channel_limits.pw_limits {
+            custom_limits.insert(
+                Value::from("pw_limits"),
+                serde_yaml::to_value(pw_limits).map_err(|error| error.to_string())?,
+            );
+        }
+
+        let existing = channels.iter_mut().find_map(|entry| {
+            entry
+                .as_mapping_mut()
+                .filter(|entry| entry.get("channel") == Some(&Value::from(*channel)))
+        });
+        let entry = match existing {
+            Some(entry) => entry,
+            None => {
+                let mut entry = Mapping::new();
+                entry.insert(Value::from("channel"), Value::from(*channel));
+                channels.push(Value::Mapping(entry));
+                channels.last_mut().unwrap().as_mapping_mut().unwrap()
+            }
+        };
+        entry.insert(Value::from("custom_limits"), Value::Mapping(custom_limits));
+
+        if let Some(neutral) = row.neutral {
+            let trim_counts = match (&channel_limits.count_limits, &channel_limits.pw_limits) {
+                (Some(count_limits), _) => {
+                    neutral
+                        - (count_limits.min_on_count as f64 + count_limits.max_on_count as f64)
+                            / 2.0
+                }
+                (None, Some(pw_limits)) => {
+                    (neutral - (pw_limits.min_on_ms + pw_limits.max_on_ms) / 2.0) / count_ms
+                }
+                (None, None) => 0.0,
+            }
+            .round();
+            if trim_counts.abs() >= PCA_PWM_RESOLUTION as f64 {
+                return Err(format!(
+                    "channel {}: neutral {} is outside the output range",
+                    channel, neutral
+                ));
+            }
+            entry.insert(Value::from("trim_counts"), Value::from(trim_counts as i16));
+        }
+        if let Some(reversed) = row.reversed {
+            entry.insert(Value::from("inverted"), Value::from(reversed));
+        }
+    }
+
+    let config: Config = serde_yaml::from_value(root.clone()).map_err(|error| error.to_string())?;
+    let errors: Vec<String> = Pca9685::validate_config(&config)
+        .findings
+        .into_iter()
+        .filter(|finding| finding.severity == FindingSeverity::Error)
+        .map(|finding| format!("{}: {}", finding.path, finding.message))
+        .collect();
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    serde_yaml::to_string(&root).map_err(|error| error.to_string())
+}
+
+fn import_limits(
+    config_file_path: &str,
+    csv_file_path: &str,
+    counts: bool,
+) -> Result<usize, String> {
+    let csv = fs::read_to_string(csv_file_path)
+        .map_err(|error| format!("{}: {}", csv_file_path, error))?;
+    let yaml = fs::read_to_string(config_file_path)
+        .map_err(|error| format!("{}: {}", config_file_path, error))?;
+
+    let limits = parse_limits_csv(&csv, counts)?;
+    let merged = merge_limits(&yaml, &limits)?;
+    fs::write(config_file_path, merged)
+        .map_err(|error| format!("{}: {}", config_file_path, error))?;
+
+    Ok(limits.len())
+}
+
+/// Returns the conversion table of `channel` as CSV (see
+/// [Pca9685::conversion_table])
+fn table(
+    pca: &Pca9685,
+    channel: Channel,
+    unit: TableUnit,
+    step: f64,
+    range: Option<f64>,
+) -> Result<String, Pca9685Error> {
+    let unit = match unit {
+        TableUnit::Counts => DisplayUnit::Counts,
+        TableUnit::Ms => DisplayUnit::Ms,
+        TableUnit::Us => DisplayUnit::Us,
+        TableUnit::Pct => DisplayUnit::Percent,
+        TableUnit::Deg => {
+            DisplayUnit::Degrees(range.unwrap_or(match pca.config(channel)?.display_unit {
+                Some(DisplayUnit::Degrees(range)) => range,
+                _ => 180.0,
+            }))
+        }
+    };
+
+    Ok(utils::conversion_table_csv(
+        &pca.conversion_table(channel, unit, step)?,
+    ))
+}
+
+/// Verifies the test vectors file at `path`, printing each failing vector
+/// and returning the number of vectors passed, of the total
+fn verify(path: &str) -> Result<(usize, usize), Pca9685Error> {
+    let vectors = TestVectors::load_from_file(path)?;
+    let failures = vectors.verify()?;
+
+    for failure in &failures {
+        println!("vectors[{}]: {}", failure.index, failure.message);
+    }
+
+    Ok((
+        vectors.vectors.len() - failures.len(),
+        vectors.vectors.len(),
+    ))
+}
+
+/// Prints `question` (with its `default`, if any) and reads the answer
+/// until `parse` accepts it, printing why it did not; a blank answer is
+/// taken as the `default`
+fn ask<T>(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    question: &str,
+    default: &str,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> Result<T, String> {
+    loop {
+        if default.is_empty() {
+            write!(output, "{}: ", question)
+        } else {
+            write!(output, "{} [{}]: ", question, default)
+        }
+        .and_then(|()| output.flush())
+        .map_err(|error| error.to_string())?;
+
+        let mut answer = String::new();
+        if input
+            .read_line(&mut answer)
+            .map_err(|error| error.to_string())?
+            == 0
+        {
+            return Err("unexpected end of input".to_string());
+        }
+        let answer = match answer.trim() {
+            "" => default,
+            answer => answer,
+        };
+
+        match parse(answer) {
+            Ok(value) => return Ok(value),
+            Err(error) => writeln!(output, "  {}", error).map_err(|error| error.to_string())?,
+        }
+    }
+}
+
+/// Parses an I2C address (e.g., `0x40`) at which a PCA9685 may be strapped
+fn parse_address(answer: &str) -> Result<u8, String> {
+    u8::from_str_radix(answer.trim_start_matches("0x"), 16)
+        .ok()
+        .filter(|address| (0x40..=0x7f).contains(address))
+        .ok_or_else(|| format!("{} is not an address from 0x40 to 0x7f", answer))
+}
+
+/// Walks through choosing the device (among those `found` by
+/// [utils::scan_i2c], or entered by hand), the output frequency and the
+/// names of the channels, returning the content of the configuration file
+/// once it passes [Pca9685::validate_config]
+fn init_wizard(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    found: &[(String, u8)],
+) -> Result<String, String> {
+    let say = |output: &mut dyn Write, text: &str| {
+        writeln!(output, "{}", text).map_err(|error| error.to_string())
+    };
+
+    // Device
+    let choice = if found.is_empty() {
+        say(output, "No PCA9685 found; enter its I2C bus and address.")?;
+        0
+    } else {
+        say(output, "PCA9685s found:")?;
+        for (index, (bus, address)) in found.iter().enumerate() {
+            say(
+                output,
+                &format!("  {}) {:#04x} on {}", index + 1, address, bus),
+            )?;
+        }
+        say(output, "  0) Another (enter its I2C bus and address)")?;
+        ask(input, output, "Device", "1", |answer| {
+            answer
+                .parse::<usize>()
+                .ok()
+                .filter(|choice| *choice <= found.len())
+                .ok_or_else(|| format!("Choose 0 to {}", found.len()))
+        })?
+    };
+    let (device, address) = match choice {
+        0 => (
+            ask(input, output, "I2C bus", "/dev/i2c-1", |answer| {
+                Ok(answer.to_string())
+            })?,
+            ask(input, output, "Address", "0x40", parse_address)?,
+        ),
+        choice => found[choice - 1].clone(),
+    };
+
+    // Output frequency
+    say(
+        output,
+        "\nA servo's position is set by the width of a pulse, repeated at the
+output frequency shared by all channels.  Analog servos expect 50Hz (a
+pulse every 20ms); some digital servos accept up to 400Hz, but higher
+frequencies can overheat or damage those that don't.  LEDs have no such
+limit, and flicker less at higher frequencies (e.g., 1000Hz).
+  1) 50Hz, for servos
+  2) 1000Hz, for LEDs
+  3) Other",
+    )?;
+    let output_frequency_hz =
+        match ask(
+            input,
+            output,
+            "Output frequency",
+            "1",
+            |answer| match answer {
+                "1" | "2" | "3" => Ok(answer.to_string()),
+                _ => Err("Choose 1, 2 or 3".to_string()),
+            },
+        )?
+        .as_str()
+        {
+            "1" => 50,
+            "2" => 1000,
+            _ => ask(input, output, "Output frequency (Hz)", "50", |answer| {
+                answer
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|frequency_hz| PCA_FREQUENCY_HZ.contains(frequency_hz))
+                    .ok_or_else(|| {
+                        format!(
+                            "Choose a frequency from {} to {}Hz",
+                            PCA_FREQUENCY_HZ.start(),
+                            PCA_FREQUENCY_HZ.end()
+                        )
+                    })
+            })?,
+        };
+    let prescale = utils::calculate_prescale(INTERNAL_OSC_HZ, output_frequency_hz);
+    say(
+        output,
+        &format!(
+            "Actual output frequency: {:0.3}Hz (prescale {})",
+            utils::prescale_output_frequency_hz(INTERNAL_OSC_HZ, prescale),
+            prescale
+        ),
+    )?;
+    if !SERVO_SAFE_FREQUENCY_HZ.contains(&output_frequency_hz) {
+        say(
+            output,
+            &format!(
+                "Outside the servo-safe range [{}, {}]Hz: use it for LEDs only.",
+                SERVO_SAFE_FREQUENCY_HZ.start(),
+                SERVO_SAFE_FREQUENCY_HZ.end()
+            ),
+        )?;
+    }
+
+    // Channel names
+    let unnamed: Config = serde_yaml::from_str(&Config::starter_yaml(
+        &device,
+        address,
+        output_frequency_hz,
+        &[],
+    ))
+    .map_err(|error| error.to_string())?;
+    let mut names: Vec<(u8, String)> = Vec::new();
+    say(output, "")?;
+    let name_channels = ask(
+        input,
+        output,
+        "Name the channels? (y/n)",
+        "n",
+        |answer| match answer {
+            "y" | "Y" => Ok(true),
+            "n" | "N" => Ok(false),
+            _ => Err("Answer y or n".to_string()),
+        },
+    )?;
+    if name_channels {
+        for channel_config in &unnamed.channels {
+            let channel = channel_config.channel as u8;
+            let name = ask(
+                input,
+                output,
+                &format!("Name of channel {} (blank for none)", channel),
+                "",
+                |answer| {
+                    if answer.is_empty() {
+                        return Ok(None);
+                    }
+                    if names.iter().any(|(_, name)| name == answer) {
+                        return Err(format!("{} names another channel", answer));
+                    }
+                    let mut named = channel_config.clone();
+                    named.name = Some(answer.to_string());
+                    named.validate_name().map_err(|error| error.to_string())?;
+                    Ok(Some(answer.to_string()))
+                },
+            )?;
+            names.extend(name.map(|name| (channel, name)));
+        }
+    }
+
+    let yaml = Config::starter_yaml(&device, address, output_frequency_hz, &names);
+    let config: Config = serde_yaml::from_str(&yaml).map_err(|error| error.to_string())?;
+    let errors: Vec<String> = Pca9685::validate_config(&config)
+        .findings
+        .into_iter()
+        .filter(|finding| finding.severity == FindingSeverity::Error)
+        .map(|finding| format!("{}: {}", finding.path, finding.message))
+        .collect();
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    Ok(yaml)
+}
+
 fn main() {
     env_logger::init();
 
     let args = Args::parse();
 
+    // Diagnostics that don't require the device
+    if let Some(Command::FrequencySweep { from, to, step }) = args.command {
+        if from > to {
+            eprintln!("--from ({}) must not exceed --to ({}).", from, to);
+            process::exit(exitcode::USAGE);
+        }
+
+        frequency_sweep(from, to, step);
+        return;
+    }
+
+    if let Some(Command::ImportLimits {
+        csv_file_path,
+        counts,
+    }) = &args.command
+    {
+        match import_limits(&args.config_file_path, csv_file_path, *counts) {
+            Ok(imported) => println!(
+                "Imported limits of {} channel(s) into {}",
+                imported, args.config_file_path
+            ),
+            Err(error) => {
+                eprintln!("Import failed: {}", error);
+                process::exit(exitcode::DATAERR);
+            }
+        }
+        return;
+    }
+
+    if let Some(Command::Verify { vectors_file_path }) = &args.command {
+        match verify(vectors_file_path) {
+            Ok((passed, total)) if passed == total => {
+                println!("{}/{} vectors passed", passed, total)
+            }
+            Ok((passed, total)) => {
+                println!("{}/{} vectors passed", passed, total);
+                process::exit(exitcode::DATAERR);
+            }
+            Err(error) => {
+                eprintln!("Verification failed: {}", error);
+                process::exit(exitcode::DATAERR);
+            }
+        }
+        return;
+    }
+
+    if let Some(Command::Init { force }) = args.command {
+        if !force && Path::new(&args.config_file_path).exists() {
+            eprintln!(
+                "{} exists; use --force to overwrite it.",
+                args.config_file_path
+            );
+            process::exit(exitcode::CANTCREAT);
+        }
+
+        println!("Scanning the I2C buses...");
+        let found = utils::scan_i2c();
+        let yaml = match init_wizard(&mut io::stdin().lock(), &mut io::stdout(), &found) {
+            Ok(yaml) => yaml,
+            Err(error) => {
+                eprintln!("Init failed: {}", error);
+                process::exit(exitcode::DATAERR);
+            }
+        };
+        if let Err(error) = fs::write(&args.config_file_path, yaml) {
+            eprintln!("{}: {}", args.config_file_path, error);
+            process::exit(exitcode::CANTCREAT);
+        }
+        println!("Wrote {}", args.config_file_path);
+        return;
+    }
+
     let config: Config = Config::load_from_file(&args.config_file_path);
+
+    if let Some(Command::Loopback {
+        channel,
+        input,
+        pulse_width_ms,
+        cycles,
+    }) = &args.command
+    {
+        let pca = Pca9685::new(&config);
+        let channel = Channel::try_from(*channel).unwrap();
+        let result = pulse_width_ms
+            .map_or(Ok(()), |pulse_width_ms| {
+                pca.set_pw_ms(channel, pulse_width_ms).map(|_| ())
+            })
+            .and_then(|()| input.open_input(config.gpio))
+            .and_then(|mut input| pca.measure_loopback(channel, input.as_mut(), *cycles as usize));
+        match result {
+            Ok(report) => print!("{}", loopback_report(&report)),
+            Err(error) => {
+                eprintln!("Measurement failed: {}", error);
+                process::exit(exitcode::DATAERR);
+            }
+        }
+        return;
+    }
+
+    if let Some(Command::Bench {
+        channel,
+        iterations,
+        mock,
+    }) = args.command
+    {
+        let pca = if mock {
+            Pca9685::null(&config)
+        } else {
+            Pca9685::new(&config)
+        };
+        let channel = Channel::try_from(channel).unwrap();
+        match bench(&pca, channel, iterations) {
+            Ok((single, batch)) => {
+                print!(
+                    "{}",
+                    single.report(&format!("Channel {} commands", channel as u8), 1)
+                );
+                print!("{}", batch.report("16-channel batches", 16));
+            }
+            Err(error) => {
+                eprintln!("Benchmark aborted: {}", error);
+                process::exit(exitcode::IOERR);
+            }
+        }
+        return;
+    }
+
+    #[cfg(feature = "stress")]
+    if let Some(Command::Stress {
+        clients,
+        commands,
+        seed,
+        stall_timeout_s,
+        mock,
+    }) = args.command
+    {
+        let pca = Arc::new(if mock {
+            Pca9685::null(&config)
+        } else {
+            Pca9685::new(&config)
+        });
+        let channels = stress::commandable_channels(&pca, &config);
+        if channels.is_empty() {
+            eprintln!("No channel accepts commands (configure their custom_limits).");
+            process::exit(exitcode::CONFIG);
+        }
+
+        let seed = seed.unwrap_or_else(rand::random);
+        let test = StressTest {
+            clients: clients as usize,
+            commands,
+            seed,
+            stall_timeout: Duration::from_secs(stall_timeout_s),
+        };
+        let report = test.run(&pca, &channels);
+        print!("{}", stress_report(&report, seed));
+        // Deadlocked clients never return
+        process::exit(if report.passed() {
+            exitcode::OK
+        } else {
+            exitcode::SOFTWARE
+        });
+    }
+
+    if let Some(Command::Table {
+        channel,
+        unit,
+        step,
+        range,
+    }) = args.command
+    {
+        let pca = Pca9685::null(&config);
+        match table(&pca, Channel::try_from(channel).unwrap(), unit, step, range) {
+            Ok(csv) => print!("{}", csv),
+            Err(error) => {
+                eprintln!("Table failed: {}", error);
+                process::exit(exitcode::DATAERR);
+            }
+        }
+        return;
+    }
+
+    if args.check {
+        let channel = Channel::try_from(args.channel.unwrap()).unwrap();
+        let pca = Pca9685::null(&config);
+        match check(&pca, channel, args.pulse_width_ms.unwrap()) {
+            Ok(channel_config) => {
+                let count = channel_config.current_count.unwrap_or_default();
+                let display = match (channel_config.display_unit, channel_config.display_value) {
+                    (Some(display_unit), Some(display_value)) => {
+                        format!(" = {}", display_unit.format(display_value))
+                    }
+                    _ => String::new(),
+                };
+
+                println!(
+                    "Channel {}: {} counts ({:0.4}ms){}",
+                    channel as u8,
+                    count,
+                    count as f64 * pca.single_count_duration_ms(),
+                    display
+                );
+                if args.explain {
+                    print!(
+                        "{}",
+                        explain(&pca, channel, args.pulse_width_ms.unwrap(), &channel_config)
+                    );
+                }
+            }
+            Err(error) => {
+                eprintln!("{}", error);
+                process::exit(exitcode::DATAERR);
+            }
+        }
+        return;
+    }
+
     let pca = Pca9685::new(&config);
 
-    let channel = Channel::try_from(args.channel).unwrap();
-    pca.set_pw_ms(channel, args.pulse_width_ms).unwrap();
+    match args.command {
+        Some(Command::BurnIn {
+            channel,
+            cycles,
+            min,
+            max,
+            dwell_ms,
+            report_every,
+        }) => {
+            if min >= max {
+                eprintln!("--min ({}) must be less than --max ({}).", min, max);
+                process::exit(exitcode::USAGE);
+            }
+
+            let channel = Channel::try_from(channel).unwrap();
+            let dwell = Duration::from_millis(dwell_ms);
+            if let Err(error) = burn_in(
+                &pca,
+                channel,
+                cycles,
+                (min, max),
+                dwell,
+                report_every.max(1),
+            ) {
+                eprintln!("Burn-in aborted: {}", error);
+                process::exit(exitcode::IOERR);
+            }
+        }
+        Some(Command::Pattern {
+            channels,
+            steps,
+            step_ms,
+            repeat,
+        }) => {
+            let channels: Vec<Channel> = channels
+                .into_iter()
+                .map(|channel| Channel::try_from(channel).unwrap())
+                .collect();
+            if let Err(error) = pattern(
+                &pca,
+                &channels,
+                steps,
+                Duration::from_millis(step_ms),
+                repeat,
+            ) {
+                eprintln!("Pattern aborted: {}", error);
+                process::exit(exitcode::IOERR);
+            }
+        }
+        Some(Command::StepTest {
+            channel,
+            from,
+            to,
+            settle_ms,
+            duration_ms,
+            sample_interval_us,
+        }) => {
+            let test = StepTest {
+                from_count: from,
+                to_count: to,
+                settle_ms,
+                duration_ms,
+                sample_interval_us,
+            };
+            match pca.step_test(Channel::try_from(channel).unwrap(), &test) {
+                Ok(samples) => print!("{}", utils::step_response_csv(&samples)),
+                Err(error) => {
+                    eprintln!("Step test aborted: {}", error);
+                    process::exit(exitcode::DATAERR);
+                }
+            }
+        }
+        Some(Command::FrequencySweep { .. })
+        | Some(Command::ImportLimits { .. })
+        | Some(Command::Verify { .. })
+        | Some(Command::Table { .. })
+        | Some(Command::Init { .. })
+        | Some(Command::Bench { .. })
+        | Some(Command::Loopback { .. }) => {
+            unreachable!()
+        }
+        #[cfg(feature = "stress")]
+        Some(Command::Stress { .. }) => unreachable!(),
+        None => {
+            let channel = Channel::try_from(args.channel.unwrap()).unwrap();
+            let channel_config = pca
+                .set_pw_ms(channel, args.pulse_width_ms.unwrap())
+                .unwrap();
+            if args.explain {
+                print!(
+                    "{}",
+                    explain(&pca, channel, args.pulse_width_ms.unwrap(), &channel_config)
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "stress")]
+    use super::stress_report;
+    use super::{
+        bench, bench_counts, check, explain, gpio_pin_parser, init_wizard, loopback_report,
+        merge_limits, parse_address, parse_limits_csv, staircase_count, Args, Command, LimitsRow,
+        TableUnit,
+    };
+    use clap::Parser;
+    #[cfg(feature = "stress")]
+    use pca9685::stress::{StressCommand, StressReport};
+    use pca9685::{ChannelLimits, Config, GpioPin, LoopbackReport, Pca9685, PulseStats};
+    use pwm_pca9685::Channel;
+    #[cfg(feature = "stress")]
+    use std::time::Duration;
+
+    #[test]
+    fn parse_set_pulse_width() {
+        let args = Args::try_parse_from(["pca9685-channel-tester", "0", "1.5"]).unwrap();
+        assert!(args.command.is_none());
+        assert_eq!(args.channel, Some(0));
+        assert_eq!(args.pulse_width_ms, Some(1.5));
+
+        assert!(Args::try_parse_from(["pca9685-channel-tester", "0"]).is_err());
+    }
+
+    #[test]
+    fn parse_check() {
+        let args = Args::try_parse_from(["pca9685-channel-tester", "--check", "0", "1.5"]).unwrap();
+        assert!(args.check);
+        assert_eq!(args.channel, Some(0));
+
+        assert!(
+            Args::try_parse_from(["pca9685-channel-tester", "--check", "frequency-sweep"]).is_err()
+        );
+    }
+
+    #[test]
+    fn parse_burn_in() {
+        let args = Args::try_parse_from([
+            "pca9685-channel-tester",
+            "burn-in",
+            "--channel",
+            "3",
+            "--cycles",
+            "10000",
+            "--min",
+            "1.0",
+            "--max",
+            "2.0",
+        ])
+        .unwrap();
+
+        match args.command {
+            Some(Command::BurnIn {
+                channel,
+                cycles,
+                min,
+                max,
+                ..
+            }) => {
+                assert_eq!((channel, cycles, min, max), (3, 10000, 1.0, 2.0));
+            }
+            _ => panic!("expected burn-in"),
+        }
+    }
+
+    #[test]
+    fn parse_frequency_sweep() {
+        let args =
+            Args::try_parse_from(["pca9685-channel-tester", "frequency-sweep", "--to", "60"])
+                .unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::FrequencySweep {
+                from: 40,
+                to: 60,
+                step: 1
+            })
+        ));
+
+        assert!(Args::try_parse_from([
+            "pca9685-channel-tester",
+            "frequency-sweep",
+            "--to",
+            "1000"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parse_pattern() {
+        let args = Args::try_parse_from([
+            "pca9685-channel-tester",
+            "pattern",
+            "--channels",
+            "0,1",
+            "--steps",
+            "4",
+        ])
+        .unwrap();
+
+        match args.command {
+            Some(Command::Pattern {
+                channels, steps, ..
+            }) => assert_eq!((channels, steps), (vec![0, 1], 4)),
+            _ => panic!("expected pattern"),
+        }
+    }
+
+    #[test]
+    fn parse_step_test() {
+        let args = Args::try_parse_from([
+            "pca9685-channel-tester",
+            "step-test",
+            "--channel",
+            "2",
+            "--from",
+            "1000",
+            "--to",
+            "2000",
+        ])
+        .unwrap();
+
+        assert!(matches!(
+            args.command,
+            Some(Command::StepTest {
+                channel: 2,
+                from: 1000,
+                to: 2000,
+                settle_ms: 500,
+                duration_ms: 1000,
+                sample_interval_us: 1000
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_table() {
+        let args = Args::try_parse_from([
+            "pca9685-channel-tester",
+            "table",
+            "--channel",
+            "3",
+            "--unit",
+            "deg",
+            "--step",
+            "5",
+        ])
+        .unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Table {
+                channel: 3,
+                unit: TableUnit::Deg,
+                range: None,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_verify() {
+        let args =
+            Args::try_parse_from(["pca9685-channel-tester", "verify", "vectors.yaml"]).unwrap();
+        match args.command {
+            Some(Command::Verify { vectors_file_path }) => {
+                assert_eq!(vectors_file_path, "vectors.yaml")
+            }
+            _ => panic!("expected verify"),
+        }
+    }
+
+    #[test]
+    fn parse_init() {
+        let args = Args::try_parse_from(["pca9685-channel-tester", "init", "--force"]).unwrap();
+        assert!(matches!(args.command, Some(Command::Init { force: true })));
+    }
+
+    #[test]
+    fn address() {
+        assert_eq!(parse_address("0x41"), Ok(0x41));
+        assert_eq!(parse_address("7f"), Ok(0x7f));
+        assert!(parse_address("0x20").is_err());
+        assert!(parse_address("forty").is_err());
+    }
+
+    #[test]
+    fn wizard() {
+        let found = vec![("/dev/i2c-1".to_string(), 0x40)];
+
+        // The device found, for servos, naming channels 0 and 2 (after an
+        // invalid name and a duplicate)
+        let answers = "\n1\ny\npan\n\nbad name\npan\ntilt\n".to_string() + &"\n".repeat(13);
+        let mut output = Vec::new();
+        let yaml = init_wizard(&mut answers.as_bytes(), &mut output, &found).unwrap();
+        let config: Config = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(config.device, "/dev/i2c-1");
+        assert_eq!(config.address, 0x40);
+        assert_eq!(config.output_frequency_hz, 50);
+        assert!(config.strict);
+        assert_eq!(config.channels[0].name.as_deref(), Some("pan"));
+        assert_eq!(config.channels[1].name, None);
+        assert_eq!(config.channels[2].name.as_deref(), Some("tilt"));
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("pan names another channel"));
+
+        // Another device, for LEDs at a frequency of choice, unnamed
+        let answers = "0\n/dev/i2c-0\n0x10\n0x41\n3\n2000\n1000\nn\n";
+        let yaml = init_wizard(&mut answers.as_bytes(), &mut Vec::new(), &found).unwrap();
+        let config: Config = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(config.device, "/dev/i2c-0");
+        assert_eq!(config.address, 0x41);
+        assert_eq!(config.output_frequency_hz, 1000);
+        assert!(config.channels.iter().all(|channel| channel.name.is_none()));
+
+        // Input ends early
+        assert!(init_wizard(&mut "1\n".as_bytes(), &mut Vec::new(), &found).is_err());
+    }
+
+    #[test]
+    fn explanation() {
+        let config: Config = serde_yaml::from_str(
+            "device: /dev/i2c-1
+address: 0x40
+output_frequency_hz: 50
+channels:
+  - channel: 1
+    custom_limits:
+      count_limits:
+        min_on_count: 200
+        max_on_count: 400
+    trim_counts: 2
+",
+        )
+        .unwrap();
+        let pca = Pca9685::null(&config);
+
+        // 1.5ms is 307 counts of 0.004880ms at (nearly) 50Hz
+        let channel_config = check(&pca, Channel::C0, 1.5).unwrap();
+        let explanation = explain(&pca, Channel::C0, 1.5, &channel_config);
+        assert!(explanation.contains("= 121"), "{}", explanation);
+        assert!(explanation.contains("-> 307"), "{}", explanation);
+        assert!(
+            explanation.contains("LED0_ON_L..LED0_OFF_H (0x06..0x09) = 00 00 33 01"),
+            "{}",
+            explanation
+        );
+        assert!(!explanation.contains("Trimmed"));
+
+        // Trimmed
+        let channel_config = check(&pca, Channel::C1, 1.5).unwrap();
+        let explanation = explain(&pca, Channel::C1, 1.5, &channel_config);
+        assert!(explanation.contains("307 +2 = 309"), "{}", explanation);
+        assert!(
+            explanation.contains("(0x0a..0x0d) = 00 00 35 01"),
+            "{}",
+            explanation
+        );
+
+        // Staggered, Channel 1 rises 256 counts into the cycle
+        let pca = Pca9685::null(&Config {
+            stagger_on_counts: true,
+            ..config
+        });
+        let channel_config = check(&pca, Channel::C1, 1.5).unwrap();
+        let explanation = explain(&pca, Channel::C1, 1.5, &channel_config);
+        assert!(
+            explanation.contains("ON at 256, OFF at (256 + 309) % 4096 = 565"),
+            "{}",
+            explanation
+        );
+        assert!(
+            explanation.contains("(0x0a..0x0d) = 00 01 35 02"),
+            "{}",
+            explanation
+        );
+    }
+
+    #[test]
+    fn parse_loopback() {
+        let args = Args::try_parse_from([
+            "pca9685-channel-tester",
+            "loopback",
+            "--channel",
+            "2",
+            "--input",
+            "/dev/gpiochip0:17",
+        ])
+        .unwrap();
+        match args.command {
+            Some(Command::Loopback {
+                channel,
+                input,
+                pulse_width_ms,
+                cycles,
+            }) => {
+                assert_eq!(channel, 2);
+                assert_eq!(
+                    input,
+                    GpioPin::Line {
+                        chip: "/dev/gpiochip0".to_string(),
+                        line: 17
+                    }
+                );
+                assert_eq!(pulse_width_ms, None);
+                assert_eq!(cycles, 1000);
+            }
+            _ => panic!("expected loopback"),
+        }
+
+        assert_eq!(gpio_pin_parser("17"), Ok(GpioPin::Pin(17)));
+        assert_eq!(
+            gpio_pin_parser("/sys/class/gpio/gpio17/value"),
+            Ok(GpioPin::Path("/sys/class/gpio/gpio17/value".to_string()))
+        );
+        assert!(gpio_pin_parser("/dev/gpiochip0:x").is_err());
+        assert!(gpio_pin_parser("seventeen").is_err());
+    }
+
+    #[test]
+    fn loopback_text() {
+        let stats = |mean_ms: f64| PulseStats {
+            min_ms: mean_ms - 0.01,
+            mean_ms,
+            max_ms: mean_ms + 0.01,
+            std_dev_ms: 0.005,
+        };
+        let report = LoopbackReport {
+            channel: Channel::C2,
+            cycles: 1000,
+            expected_pulse_width_ms: 1.5,
+            expected_period_ms: 20.0,
+            pulse_width: stats(1.49),
+            period: stats(20.2),
+            frequency_error_pct: -0.99,
+            glitches: 3,
+            sample_interval_us: 0.5,
+        };
+        let text = loopback_report(&report);
+        assert!(text.starts_with("Channel 2: 1000 cycles"), "{}", text);
+        assert!(
+            text.contains("pulse width       1.5000     1.4800"),
+            "{}",
+            text
+        );
+        assert!(text.contains("Frequency error: -0.990%"), "{}", text);
+        assert!(text.contains("Glitches: 3"), "{}", text);
+    }
+
+    #[cfg(feature = "stress")]
+    #[test]
+    fn stress_text() {
+        let mut report = StressReport {
+            completed: 8000,
+            elapsed: Duration::from_millis(1500),
+            ..Default::default()
+        };
+        assert_eq!(
+            stress_report(&report, 7),
+            "8000 commands in 1.500s (seed 7): passed\n"
+        );
+
+        report.stalled = vec![(3, Some(StressCommand::Batch))];
+        report.error_count = 12;
+        report.errors = vec!["SetPct: oops".to_string()];
+        let text = stress_report(&report, 7);
+        assert!(text.contains("FAILED"), "{}", text);
+        assert!(text.contains("client 3 stalled in Some(Batch)"), "{}", text);
+        assert!(text.contains("12 command(s) failed"), "{}", text);
+    }
+
+    #[test]
+    fn parse_bench() {
+        let args = Args::try_parse_from([
+            "pca9685-channel-tester",
+            "bench",
+            "--iterations",
+            "10",
+            "--mock",
+        ])
+        .unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Bench {
+                channel: 0,
+                iterations: 10,
+                mock: true
+            })
+        ));
+        assert!(
+            Args::try_parse_from(["pca9685-channel-tester", "bench", "--iterations", "0"]).is_err()
+        );
+    }
+
+    #[test]
+    fn benchmark() {
+        let config: Config = serde_yaml::from_str(
+            "device: /dev/i2c-1
+address: 0x40
+output_frequency_hz: 50
+channels:
+  - channel: 1
+    custom_limits:
+      count_limits:
+        min_on_count: 200
+        max_on_count: 400
+",
+        )
+        .unwrap();
+        let pca = Pca9685::null(&config);
+        pca.set_pwm_count(Channel::C1, 400).unwrap();
+
+        // Around the current output (or the middle of the limits), within
+        // the limits
+        assert_eq!(
+            bench_counts(&pca.config(Channel::C0).unwrap()),
+            [2047, 2048]
+        );
+        assert_eq!(bench_counts(&pca.config(Channel::C1).unwrap()), [400, 399]);
+
+        let (single, batch) = bench(&pca, Channel::C1, 10).unwrap();
+        assert_eq!(single.latencies.len(), 10);
+        assert!(single.percentile(50.0) <= single.percentile(99.0));
+        assert!(single
+            .report("Channel 1 commands", 1)
+            .contains("Channel 1 commands: 10 in"));
+        assert_eq!(batch.latencies.len(), 10);
+
+        // Left at 399 by the single commands, then back and forth from there
+        assert_eq!(pca.config(Channel::C1).unwrap().current_count, Some(400));
+    }
+
+    #[test]
+    fn staircase() {
+        let counts: Vec<u16> = (0..3).map(|step| staircase_count(step, 3)).collect();
+        assert_eq!(counts, vec![1024, 2048, 3072]);
+    }
+
+    #[test]
+    fn limits_csv() {
+        let limits = parse_limits_csv(
+            "channel,min,max\n# servos\n0,1.0,2.0\n3, 0.9, 2.1,,false\n",
+            false,
+        )
+        .unwrap();
+        assert_eq!(limits.len(), 2);
+        assert_eq!(limits[1].channel, 3);
+        assert_eq!(limits[1].limits.pw_limits.unwrap().max_on_ms, 2.1);
+        assert_eq!(limits[1].reversed, Some(false));
+
+        let limits = parse_limits_csv("1,1000,3000,2010,TRUE", true).unwrap();
+        assert_eq!(
+            limits,
+            vec![LimitsRow {
+                channel: 1,
+                limits: ChannelLimits::from_count_limits(1000, 3000),
+                neutral: Some(2010.0),
+                reversed: Some(true),
+            }]
+        );
+
+        assert!(parse_limits_csv("16,1.0,2.0", false).is_err());
+        assert!(parse_limits_csv("0,1.0", false).is_err());
+        assert!(parse_limits_csv("0,1.0,2.0\n0,1.0,2.0", false).is_err());
+        assert!(parse_limits_csv("0,1.0,2.0,centre", false).is_err());
+        assert!(parse_limits_csv("0,1.0,2.0,,yes", false).is_err());
+    }
+
+    #[test]
+    fn merge_limits_into_config() {
+        let yaml = "device: /dev/i2c-1
+address: 0x40
+output_frequency_hz: 50
+channels:
+  - channel: 0
+    calibration: null
+    custom_limits:
+      count_limits:
+        min_on_count: 0
+        max_on_count: 4096
+";
+        let limits = parse_limits_csv("0,1.0,2.0\n5,100,200", false).unwrap();
+        assert!(merge_limits(yaml, &limits).is_err());
+
+        let limits = parse_limits_csv("0,1.0,2.0\n5,0.5,2.5", false).unwrap();
+        let config: Config = serde_yaml::from_str(&merge_limits(yaml, &limits).unwrap()).unwrap();
+        assert_eq!(config.channels.len(), 2);
+        assert_eq!(config.channels[0].custom_limits, Some(limits[0].limits));
+        assert_eq!(config.channels[1].channel as u8, 5);
+        assert_eq!(config.channels[1].custom_limits, Some(limits[1].limits));
+        assert_eq!(config.channels[1].trim_counts, 0);
+        assert!(!config.channels[1].inverted);
+
+        // 50Hz: 20ms over 4096 counts, so 1.55ms is 10 counts above 1.5ms
+        let limits = parse_limits_csv("0,1.0,2.0,1.55,true", false).unwrap();
+        let config: Config = serde_yaml::from_str(&merge_limits(yaml, &limits).unwrap()).unwrap();
+        assert_eq!(config.channels[0].trim_counts, 10);
+        assert!(config.channels[0].inverted);
+        let limits = parse_limits_csv("5,1000,3000,1990", true).unwrap();
+        let config: Config = serde_yaml::from_str(&merge_limits(yaml, &limits).unwrap()).unwrap();
+        assert_eq!(config.channels[1].trim_counts, -10);
+    }
 }
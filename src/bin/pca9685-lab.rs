@@ -0,0 +1,438 @@
+use clap::Parser;
+use pca9685::{
+    ChannelLimits, ChannelPulseWidthLimits, Config, JitterConfig, Pca9685, PCA_PWM_RESOLUTION,
+};
+use pwm_pca9685::Channel;
+use std::io::{self, BufRead, Write};
+use std::process;
+
+/// Steps through exercises on driving servos with a PCA9685: computing its
+/// prescale, the count of a 1.5ms pulse, finding a servo's limits and
+/// observing jitter, checking each answer against the device (or a mock)
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Channel the servo is connected to
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(..16))]
+    channel: u8,
+
+    /// Lesson to start at
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=LESSONS.len() as i64))]
+    lesson: u8,
+
+    /// Check answers against a mock device rather than opening the device,
+    /// e.g. to work through the lessons without hardware
+    #[arg(long)]
+    mock: bool,
+
+    /// Path to configuration file
+    #[arg(long, default_value = "/etc/pca9685.yaml")]
+    config_file_path: String,
+}
+
+/// Attempts allowed at each question before the answer is given
+const ATTEMPTS: usize = 3;
+
+/// Counts added to (or subtracted from) the output by `+`/`-` (and ten times
+/// as many by `++`/`--`) while finding a servo's limits
+const NUDGE_COUNTS: u16 = 5;
+
+/// The jitter observed in the last lesson
+const LESSON_JITTER: JitterConfig = JitterConfig {
+    amplitude_counts: 20,
+    bandwidth_hz: 2.0,
+};
+
+/// A lesson: its title and its exercise, returning true if passed
+type Lesson = (&'static str, fn(&mut Lab) -> Result<bool, String>);
+
+const LESSONS: [Lesson; 4] = [
+    ("Compute the prescale", prescale_lesson),
+    ("Set a 1.5ms pulse", center_lesson),
+    ("Find the servo's limits", limits_lesson),
+    ("Observe jitter", jitter_lesson),
+];
+
+/// The device (or mock) the exercises are checked against, the channel of
+/// the servo, and the student's terminal
+struct Lab<'a> {
+    pca: &'a Pca9685,
+    channel: Channel,
+    input: &'a mut dyn BufRead,
+    output: &'a mut dyn Write,
+}
+
+impl Lab<'_> {
+    fn say(&mut self, text: &str) -> Result<(), String> {
+        writeln!(self.output, "{}", text).map_err(|error| error.to_string())
+    }
+
+    /// Prints `question` and reads the (trimmed) answer
+    fn ask(&mut self, question: &str) -> Result<String, String> {
+        write!(self.output, "{} ", question)
+            .and_then(|()| self.output.flush())
+            .map_err(|error| error.to_string())?;
+
+        let mut answer = String::new();
+        if self
+            .input
+            .read_line(&mut answer)
+            .map_err(|error| error.to_string())?
+            == 0
+        {
+            return Err("unexpected end of input".to_string());
+        }
+
+        Ok(answer.trim().to_string())
+    }
+
+    /// Asks `question` (up to [ATTEMPTS] times) for a number within
+    /// `tolerance` of `expected`, giving the `hint` after a wrong answer and
+    /// the answer after the last.  Returns true if answered correctly.
+    fn quiz(
+        &mut self,
+        question: &str,
+        expected: f64,
+        tolerance: f64,
+        hint: &str,
+    ) -> Result<bool, String> {
+        for attempt in 1..=ATTEMPTS {
+            let answer = self.ask(question)?;
+            match answer.parse::<f64>() {
+                Ok(value) if (value - expected).abs() <= tolerance => {
+                    self.say("Correct!")?;
+                    return Ok(true);
+                }
+                Ok(_) if attempt < ATTEMPTS => self.say(&format!("Not quite.  Hint: {}", hint))?,
+                Ok(_) => {}
+                Err(_) => self.say(&format!("{} is not a number.", answer))?,
+            }
+        }
+
+        self.say(&format!("The answer is {}.", expected))?;
+        Ok(false)
+    }
+}
+
+fn prescale_lesson(lab: &mut Lab) -> Result<bool, String> {
+    let output_frequency_hz = lab.pca.output_frequency_hz();
+    let oscillator_hz = lab.pca.oscillator_hz();
+    lab.say(&format!(
+        "The PCA9685 divides its {}Hz oscillator down to the output frequency
+({}Hz here) by its prescale register, counting {} steps per cycle:
+
+    prescale = round({} / ({} x output_frequency_hz)) - 1",
+        oscillator_hz, output_frequency_hz, PCA_PWM_RESOLUTION, oscillator_hz, PCA_PWM_RESOLUTION
+    ))?;
+
+    let passed = lab.quiz(
+        &format!("What is the prescale for {}Hz?", output_frequency_hz),
+        lab.pca.prescale() as f64,
+        0.0,
+        "divide first, then round, then subtract 1",
+    )?;
+    lab.say(&format!(
+        "The device's prescale is {}, so its actual output frequency is {:0.3}Hz.",
+        lab.pca.prescale(),
+        lab.pca.actual_output_frequency_hz()
+    ))?;
+
+    Ok(passed)
+}
+
+fn center_lesson(lab: &mut Lab) -> Result<bool, String> {
+    let count_ms = lab.pca.single_count_duration_ms();
+    lab.say(&format!(
+        "Each cycle lasts 1000 / {:0.3}Hz = {:0.4}ms, split into {} counts of
+{:0.6}ms each.  A servo's position is set by how many counts its pulse
+lasts, most servos centering at 1.5ms.",
+        lab.pca.actual_output_frequency_hz(),
+        lab.pca.max_pw_ms(),
+        PCA_PWM_RESOLUTION,
+        count_ms
+    ))?;
+
+    let expected = (1.5 / count_ms).round();
+    let passed = lab.quiz(
+        "How many counts make a 1.5ms pulse?",
+        expected,
+        0.0,
+        "divide 1.5ms by the length of a count, and round",
+    )?;
+
+    let config = lab
+        .pca
+        .set_pwm_count(lab.channel, expected as u16)
+        .map_err(|error| error.to_string())?;
+    lab.say(&format!(
+        "Wrote {} counts ({:0.4}ms) to channel {}: the servo should be centered.",
+        config.current_count.unwrap_or_default(),
+        expected * count_ms,
+        lab.channel as u8
+    ))?;
+
+    Ok(passed)
+}
+
+fn limits_lesson(lab: &mut Lab) -> Result<bool, String> {
+    lab.say(&format!(
+        "Driven past its limits, a servo stalls against its end stops, drawing
+current and heating up.  Nudge the servo with + or - ({} counts; ++ or --
+for {}) until it stops moving (or buzzes), then back off a little and enter
+min (or max) to record it; enter done once both are recorded.",
+        NUDGE_COUNTS,
+        NUDGE_COUNTS * 10
+    ))?;
+
+    let count_ms = lab.pca.single_count_duration_ms();
+    let mut count = (1.5 / count_ms).round() as u16;
+    let (mut min, mut max) = (None, None);
+    loop {
+        let next = match lab
+            .ask(&format!(
+                "[{} counts, {:0.4}ms]",
+                count,
+                count as f64 * count_ms
+            ))?
+            .as_str()
+        {
+            "+" => count.saturating_add(NUDGE_COUNTS),
+            "++" => count.saturating_add(NUDGE_COUNTS * 10),
+            "-" => count.saturating_sub(NUDGE_COUNTS),
+            "--" => count.saturating_sub(NUDGE_COUNTS * 10),
+            "min" => {
+                min = Some(count);
+                continue;
+            }
+            "max" => {
+                max = Some(count);
+                continue;
+            }
+            "done" if min.is_some() && max.is_some() => break,
+            "done" => {
+                lab.say("Record both min and max first.")?;
+                continue;
+            }
+            answer => {
+                lab.say(&format!("Unknown command: {}", answer))?;
+                continue;
+            }
+        };
+
+        match lab.pca.set_pwm_count(lab.channel, next) {
+            Ok(_) => count = next,
+            Err(error) => lab.say(&format!("Refused: {}", error))?,
+        }
+    }
+
+    let (min_ms, max_ms) = (
+        min.unwrap() as f64 * count_ms,
+        max.unwrap() as f64 * count_ms,
+    );
+    if !(0.4..1.5).contains(&min_ms) || !(1.5..2.6).contains(&max_ms) {
+        lab.say(&format!(
+            "Servo limits are usually around 0.5-1.0ms and 2.0-2.5ms, either side of
+1.5ms; {:0.4}ms and {:0.4}ms are not.  Try again another time.",
+            min_ms, max_ms
+        ))?;
+        return Ok(false);
+    }
+
+    let mut config = lab
+        .pca
+        .config(lab.channel)
+        .map_err(|error| error.to_string())?;
+    config.custom_limits = Some(ChannelLimits {
+        count_limits: None,
+        pw_limits: Some(ChannelPulseWidthLimits {
+            min_on_ms: (min_ms * 10000.0).round() / 10000.0,
+            max_on_ms: (max_ms * 10000.0).round() / 10000.0,
+        }),
+    });
+    lab.pca
+        .configure_channel(&config)
+        .map_err(|error| error.to_string())?;
+    lab.say(&format!(
+        "Applied for the rest of the lab; add them to the channel's configuration
+to keep them:
+
+  - channel: {}
+    custom_limits:
+      pw_limits: {{ min_on_ms: {:0.4}, max_on_ms: {:0.4} }}",
+        lab.channel as u8, min_ms, max_ms
+    ))?;
+
+    Ok(true)
+}
+
+fn jitter_lesson(lab: &mut Lab) -> Result<bool, String> {
+    lab.say(&format!(
+        "Jitter is random noise on a servo's output, e.g. from a noisy supply or
+signal; injecting it on purpose shakes out loose linkages.  Up to {}
+counts of jitter, varying at up to {}Hz, is now applied to channel {}.",
+        LESSON_JITTER.amplitude_counts, LESSON_JITTER.bandwidth_hz, lab.channel as u8
+    ))?;
+
+    match lab.pca.set_jitter(lab.channel, Some(LESSON_JITTER)) {
+        Ok(_) => {
+            lab.ask("Watch the servo twitch, then press Enter to stop the jitter.")?;
+            lab.pca
+                .set_jitter(lab.channel, None)
+                .map_err(|error| error.to_string())?;
+        }
+        Err(error) => lab.say(&format!(
+            "(Unable to apply jitter: {}; `allow_jitter` and the channel's limits
+are required.)",
+            error
+        ))?,
+    }
+
+    let count_ms = lab.pca.single_count_duration_ms();
+    lab.quiz(
+        &format!(
+            "By up to how many ms does {} counts of jitter vary the pulse?",
+            LESSON_JITTER.amplitude_counts
+        ),
+        (LESSON_JITTER.amplitude_counts as f64 * count_ms * 10000.0).round() / 10000.0,
+        0.0005,
+        "multiply by the length of a count",
+    )
+}
+
+/// Runs the lessons from `first` (1-based) on, printing progress, and
+/// returning the number passed
+fn run(lab: &mut Lab, first: usize) -> Result<usize, String> {
+    let mut passed = 0;
+    for (number, (title, lesson)) in LESSONS.iter().enumerate().skip(first - 1) {
+        lab.say(&format!(
+            "\n=== Lesson {}/{}: {} ===",
+            number + 1,
+            LESSONS.len(),
+            title
+        ))?;
+        if lesson(lab)? {
+            passed += 1;
+            lab.say(&format!("Lesson {} passed.", number + 1))?;
+        } else {
+            lab.say(&format!("Lesson {} not passed; moving on.", number + 1))?;
+        }
+    }
+    lab.say(&format!(
+        "\n{}/{} lessons passed.",
+        passed,
+        LESSONS.len() - (first - 1)
+    ))?;
+
+    Ok(passed)
+}
+
+fn main() {
+    env_logger::init();
+
+    let args = Args::parse();
+
+    let mut config: Config = Config::load_from_file(&args.config_file_path);
+    let pca = if args.mock {
+        config.allow_jitter = true;
+        Pca9685::null(&config)
+    } else {
+        Pca9685::new(&config)
+    };
+
+    let mut lab = Lab {
+        pca: &pca,
+        channel: Channel::try_from(args.channel).unwrap(),
+        input: &mut io::stdin().lock(),
+        output: &mut io::stdout(),
+    };
+    if let Err(error) = run(&mut lab, args.lesson as usize) {
+        eprintln!("Lab aborted: {}", error);
+        process::exit(exitcode::IOERR);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, Args, Lab};
+    use clap::Parser;
+    use pca9685::{Config, Pca9685};
+    use pwm_pca9685::Channel;
+
+    fn create_mock() -> Pca9685 {
+        let mut config: Config = serde_yaml::from_str(
+            "device: /dev/i2c-1
+address: 0x40
+output_frequency_hz: 50
+",
+        )
+        .unwrap();
+        config.allow_jitter = true;
+
+        Pca9685::null(&config)
+    }
+
+    #[test]
+    fn parse_args() {
+        let args = Args::try_parse_from(["pca9685-lab", "--mock", "--lesson", "3"]).unwrap();
+        assert!(args.mock);
+        assert_eq!(args.lesson, 3);
+        assert_eq!(args.channel, 0);
+        assert!(Args::try_parse_from(["pca9685-lab", "--lesson", "5"]).is_err());
+    }
+
+    #[test]
+    fn lessons() {
+        let pca = create_mock();
+
+        // Prescale 121, then 307 counts (after a wrong answer), limits of
+        // 307 - 50 and 307 + 100 counts (after an unknown command), and
+        // 20 x 0.00488ms of jitter
+        let answers = "121\n300\n307\n--\nmin\n++\n++\n++\nsideways\nmax\ndone\n\n0.0976\n";
+        let mut output = Vec::new();
+        let mut lab = Lab {
+            pca: &pca,
+            channel: Channel::C0,
+            input: &mut answers.as_bytes(),
+            output: &mut output,
+        };
+        assert_eq!(run(&mut lab, 1).unwrap(), 4);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Hint:"), "{}", output);
+        assert!(output.contains("Unknown command: sideways"), "{}", output);
+        assert!(output.contains("4/4 lessons passed"), "{}", output);
+
+        // The limits found are applied
+        let limits = pca.config(Channel::C0).unwrap().custom_limits.unwrap();
+        assert_eq!(limits.count_limits.unwrap().min_on_count, 257);
+        assert_eq!(limits.count_limits.unwrap().max_on_count, 407);
+    }
+
+    #[test]
+    fn wrong_answers() {
+        let pca = create_mock();
+
+        // Out of attempts at the prescale, and limits on the same side of
+        // 1.5ms
+        let answers = "1\n2\nthree\nmin\nmax\ndone\n";
+        let mut output = Vec::new();
+        let mut lab = Lab {
+            pca: &pca,
+            channel: Channel::C0,
+            input: &mut answers.as_bytes(),
+            output: &mut output,
+        };
+        assert!(run(&mut lab, 1).is_err());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("The answer is 121."), "{}", output);
+        assert!(output.contains("three is not a number."), "{}", output);
+
+        let answers = "min\nmax\ndone\n";
+        let mut lab = Lab {
+            pca: &pca,
+            channel: Channel::C0,
+            input: &mut answers.as_bytes(),
+            output: &mut Vec::new(),
+        };
+        assert!(!super::limits_lesson(&mut lab).unwrap());
+    }
+}
@@ -0,0 +1,205 @@
+use rocket::serde::json::Value;
+use rocket::serde::{Deserialize, Serialize};
+
+/// A JSON Patch (RFC 6902) operation; `path` and `from` are JSON Pointers
+/// (RFC 6901), e.g. `/custom_limits/count_limits/max_on_count`
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+/// Applies `patch` to `document`: all or nothing, so that a failing
+/// operation leaves `document` unchanged
+pub fn apply(document: &mut Value, patch: &[PatchOperation]) -> Result<(), String> {
+    let mut patched = document.clone();
+    for operation in patch {
+        match operation {
+            PatchOperation::Add { path, value } => add(&mut patched, path, value.clone())?,
+            PatchOperation::Remove { path } => {
+                remove(&mut patched, path)?;
+            }
+            PatchOperation::Replace { path, value } => {
+                *pointee_mut(&mut patched, &parse_pointer(path)?)
+                    .ok_or_else(|| format!("No value at '{}'", path))? = value.clone();
+            }
+            PatchOperation::Move { from, path } => {
+                if path.starts_with(&format!("{}/", from)) {
+                    return Err(format!("Cannot move '{}' into itself", from));
+                }
+                let value = remove(&mut patched, from)?;
+                add(&mut patched, path, value)?;
+            }
+            PatchOperation::Copy { from, path } => {
+                let value = pointee(&patched, &parse_pointer(from)?)
+                    .ok_or_else(|| format!("No value at '{}'", from))?
+                    .clone();
+                add(&mut patched, path, value)?;
+            }
+            PatchOperation::Test { path, value } => {
+                if pointee(&patched, &parse_pointer(path)?) != Some(value) {
+                    return Err(format!("Test of '{}' failed", path));
+                }
+            }
+        }
+    }
+
+    *document = patched;
+    Ok(())
+}
+
+/// Splits a JSON Pointer into its (unescaped) reference tokens
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    match pointer.strip_prefix('/') {
+        Some(tokens) => Ok(tokens
+            .split('/')
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .collect()),
+        None => Err(format!("Invalid JSON Pointer '{}'", pointer)),
+    }
+}
+
+fn pointee<'a>(document: &'a Value, tokens: &[String]) -> Option<&'a Value> {
+    tokens
+        .iter()
+        .try_fold(document, |value, token| match value {
+            Value::Object(object) => object.get(token),
+            Value::Array(array) => array.get(token.parse::<usize>().ok()?),
+            _ => None,
+        })
+}
+
+fn pointee_mut<'a>(document: &'a mut Value, tokens: &[String]) -> Option<&'a mut Value> {
+    tokens
+        .iter()
+        .try_fold(document, |value, token| match value {
+            Value::Object(object) => object.get_mut(token),
+            Value::Array(array) => array.get_mut(token.parse::<usize>().ok()?),
+            _ => None,
+        })
+}
+
+fn add(document: &mut Value, path: &str, value: Value) -> Result<(), String> {
+    let mut tokens = parse_pointer(path)?;
+    let Some(last) = tokens.pop() else {
+        *document = value;
+        return Ok(());
+    };
+
+    match pointee_mut(document, &tokens) {
+        Some(Value::Object(object)) => {
+            object.insert(last, value);
+            Ok(())
+        }
+        Some(Value::Array(array)) => {
+            let index = match last.as_str() {
+                "-" => array.len(),
+                index => index
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|index| *index <= array.len())
+                    .ok_or_else(|| format!("Invalid index in '{}'", path))?,
+            };
+            array.insert(index, value);
+            Ok(())
+        }
+        _ => Err(format!("No container at '{}'", path)),
+    }
+}
+
+fn remove(document: &mut Value, path: &str) -> Result<Value, String> {
+    let mut tokens = parse_pointer(path)?;
+    let last = tokens
+        .pop()
+        .ok_or_else(|| "Cannot remove the whole document".to_string())?;
+
+    let removed = match pointee_mut(document, &tokens) {
+        Some(Value::Object(object)) => object.remove(&last),
+        Some(Value::Array(array)) => match last.parse::<usize>() {
+            Ok(index) if index < array.len() => Some(array.remove(index)),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    removed.ok_or_else(|| format!("No value at '{}'", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply, PatchOperation};
+    use rocket::serde::json::{self, Value};
+
+    fn patch(operations: &str) -> Vec<PatchOperation> {
+        json::from_str(operations).unwrap()
+    }
+
+    #[test]
+    fn apply_patch() {
+        let mut document: Value =
+            json::from_str(r#"{"limits": {"min": 1, "max": 2}, "points": [1, 3], "a~/b": 0}"#)
+                .unwrap();
+
+        apply(
+            &mut document,
+            &patch(
+                r#"[
+                    {"op": "test", "path": "/limits/max", "value": 2},
+                    {"op": "replace", "path": "/limits/max", "value": 5},
+                    {"op": "add", "path": "/points/1", "value": 2},
+                    {"op": "add", "path": "/points/-", "value": 4},
+                    {"op": "copy", "from": "/limits/min", "path": "/minimum"},
+                    {"op": "move", "from": "/a~0~1b", "path": "/ab"},
+                    {"op": "remove", "path": "/limits/min"}
+                ]"#,
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            document,
+            json::from_str::<Value>(
+                r#"{"limits": {"max": 5}, "points": [1, 2, 3, 4], "minimum": 1, "ab": 0}"#
+            )
+            .unwrap()
+        );
+
+        // All or nothing
+        let original = document.clone();
+        assert!(apply(
+            &mut document,
+            &patch(
+                r#"[
+                    {"op": "replace", "path": "/limits/max", "value": 6},
+                    {"op": "test", "path": "/limits/max", "value": 5}
+                ]"#
+            ),
+        )
+        .is_err());
+        assert_eq!(document, original);
+
+        assert!(apply(
+            &mut document,
+            &patch(r#"[{"op": "replace", "path": "/nothing", "value": 1}]"#)
+        )
+        .is_err());
+        assert!(apply(
+            &mut document,
+            &patch(r#"[{"op": "remove", "path": "/points/9"}]"#)
+        )
+        .is_err());
+        assert!(apply(
+            &mut document,
+            &patch(r#"[{"op": "remove", "path": "points"}]"#)
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,108 @@
+use log::{Level, Log, Metadata, Record};
+use rocket::serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How many of the most recent lines are kept per log target
+pub const LOG_LINES_CAPACITY: usize = 100;
+
+/// A logged message, at a point in time (milliseconds since the UNIX epoch)
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct LogLine {
+    pub timestamp_ms: u64,
+    pub level: String,
+    pub message: String,
+}
+
+/// The most recent [LogLine]s (Info and above) of each log target, e.g. the
+/// name of a Channel
+#[derive(Debug, Default)]
+pub struct LogLines {
+    lines: Mutex<HashMap<String, VecDeque<LogLine>>>,
+}
+
+impl LogLines {
+    pub fn record(&self, target: &str, level: Level, message: String) {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+
+        let mut lines = self.lines.lock().unwrap();
+        let target_lines = lines.entry(target.to_string()).or_default();
+        if target_lines.len() == LOG_LINES_CAPACITY {
+            target_lines.pop_front();
+        }
+        target_lines.push_back(LogLine {
+            timestamp_ms,
+            level: level.to_string(),
+            message,
+        });
+    }
+
+    /// Returns the recent lines of `target`, oldest first
+    pub fn get(&self, target: &str) -> Vec<LogLine> {
+        match self.lines.lock().unwrap().get(target) {
+            Some(lines) => lines.iter().cloned().collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Logs via env_logger (i.e., per `RUST_LOG`), additionally recording lines
+/// of Info and above into [LogLines] regardless of `RUST_LOG`
+struct RecordingLogger {
+    inner: env_logger::Logger,
+    lines: Arc<LogLines>,
+}
+
+impl Log for RecordingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if record.level() <= Level::Info {
+            self.lines
+                .record(record.target(), record.level(), record.args().to_string());
+        }
+
+        self.inner.log(record);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Installs the global logger, in place of `env_logger::init()`
+pub fn init(lines: Arc<LogLines>) {
+    let inner = env_logger::Builder::from_default_env().build();
+    let max_level = inner.filter().max(log::LevelFilter::Info);
+
+    log::set_boxed_logger(Box::new(RecordingLogger { inner, lines }))
+        .expect("logger already installed");
+    log::set_max_level(max_level);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LogLines, LOG_LINES_CAPACITY};
+    use log::Level;
+
+    #[test]
+    fn record() {
+        let lines = LogLines::default();
+        for index in 0..LOG_LINES_CAPACITY + 1 {
+            lines.record("left_elbow", Level::Info, format!("line {}", index));
+        }
+        lines.record("right_elbow", Level::Warn, "other".to_string());
+
+        let left_elbow = lines.get("left_elbow");
+        assert_eq!(left_elbow.len(), LOG_LINES_CAPACITY);
+        assert_eq!(left_elbow[0].message, "line 1");
+        assert_eq!(lines.get("right_elbow")[0].level, "WARN");
+        assert!(lines.get("Channel C0").is_empty());
+    }
+}
@@ -0,0 +1,6517 @@
+use clap::Parser;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use pca9685::{
+    utils, Backup, ChannelCalibration, ChannelCommand, ChannelConfig, ChannelHistoryEntry,
+    ChannelSelector, ChannelStats, CommandType, Config, ConfigDifference, ConfigFinding,
+    ConfigValidation, DebugReport, DisabledOutput, DriverErrorRecord, Easing, FindingSeverity,
+    GaitConfig, GamepadState, JitterConfig, Keyframe, LinearAxisStatus, LoopRegion, MixerConfig,
+    PauseMode, Pca9685, Pca9685Error, Pca9685Result, PercentBasis, PlaybackClock, PlaybackStatus,
+    PresetConfig, Rate, RecordingStatus, Replay, SceneLayer, SequenceConfig, Snapshot, StepTest,
+    Storage, TeleopProfile,
+};
+use pwm_pca9685::Channel;
+use rocket::data::{Data, ToByteUnit};
+use rocket::http::{ContentType, Header, Status};
+use rocket::request::{self, FromRequest, Request};
+use rocket::response::status;
+use rocket::response::stream::{Event, EventStream};
+use rocket::serde::json::{self, Json};
+use rocket::serde::{Deserialize, Serialize};
+use rocket::tokio::select;
+use rocket::tokio::signal::unix::{signal, SignalKind};
+use rocket::tokio::time::sleep;
+use rocket::{Build, Rocket, Shutdown, State};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use strum::EnumString;
+
+use pca9685::utils::{deserialize_channel, serialize_channel};
+#[cfg(feature = "gimbal")]
+use pca9685::GimbalStatus;
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct ErrorResponse {
+    error: String,
+}
+
+#[derive(Debug, PartialEq, EnumString, Serialize, Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
+enum StatusType {
+    HEALTHY,
+    DEGRADED,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct SoftwareStatus {
+    version: String,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct StatusResponse {
+    status: StatusType,
+    software: SoftwareStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    paused: Option<PauseMode>,
+    /// Whether idle behaviors are playing (see [pca9685::IdleConfig])
+    idling: bool,
+    uptime_seconds: u64,
+    /// Milliseconds since the UNIX epoch
+    started_at_ms: u64,
+    reinit_count: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_driver_error: Option<DriverErrorRecord>,
+}
+
+/// When the service started
+struct ProcessStart {
+    at: Instant,
+    timestamp_ms: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct DeviceResponse {
+    device: String,
+    address: u8,
+    output_type: String,
+    disabled_output: DisabledOutput,
+    prescale: u8,
+    output_frequency_hz: u16,
+    actual_output_frequency_hz: f64,
+    max_pw_ms: f64,
+    single_count_duration_ms: f64,
+}
+
+/// The configuration file the service was started with
+struct ConfigFile {
+    path: String,
+    autopersist: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct PauseResponse {
+    paused: Option<PauseMode>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct HeartbeatResponse {
+    watchdog_timeout_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct RateSelection {
+    selector: ChannelSelector,
+    rate: Rate,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct TeleopResponse {
+    active_profile: Option<String>,
+    profiles: Vec<TeleopProfile>,
+}
+
+#[derive(Debug, PartialEq, EnumString, Serialize, Deserialize)]
+enum TransactionUnit {
+    PulseCount,
+    Percent,
+}
+
+/// A conditional, relative update evaluated server-side under the channel
+/// lock, e.g. "increase by 5% but not above 80%":
+/// `{"channel": 0, "unit": "Percent", "delta": 0.05, "max": 0.8}`
+///
+/// `min`/`max` are expressed in `unit` (Percent is relative to the channel's
+/// limits) and only ever stop the output from moving past them; they never
+/// move the output against the direction of `delta`.
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+struct ChannelTransaction {
+    #[serde(
+        serialize_with = "serialize_channel",
+        deserialize_with = "deserialize_channel"
+    )]
+    channel: Channel,
+    unit: TransactionUnit,
+    delta: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+struct ChannelCommands {
+    /// Validate every command before writing any, so that an error never
+    /// leaves a pose partially applied
+    #[serde(default)]
+    atomic: bool,
+    commands: Vec<ChannelCommand>,
+}
+
+/// A command for every selected channel (see [Pca9685::command_selected])
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+struct SelectedCommand {
+    command_type: CommandType,
+    value: Option<f64>,
+    #[serde(default)]
+    basis: Option<PercentBasis>,
+}
+
+/// RESTful interface to PCA9685
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to configuration file
+    #[arg(long, default_value = "/etc/pca9685.yaml")]
+    config_file_path: String,
+
+    /// If the configuration file doesn't exist, scan the I2C buses and write
+    /// a starter configuration for the PCA9685 found (in strict mode)
+    #[arg(long)]
+    bootstrap: bool,
+}
+
+#[macro_use]
+extern crate rocket;
+
+mod json_patch;
+mod log_lines;
+mod metrics;
+mod network_policy;
+
+use json_patch::PatchOperation;
+use log_lines::{LogLine, LogLines};
+use network_policy::NetworkPolicy;
+
+/// The (optional) identity of the client issuing a request, taken from the
+/// `X-Client-Id` header, for attributing movements in logs and history
+struct ClientId(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ClientId {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let client_id = request.headers().get_one("X-Client-Id").map(String::from);
+        if let Some(client_id) = &client_id {
+            log::debug!(target: "server", "{} {} (client: {})", request.method(), request.uri(), client_id);
+        }
+
+        request::Outcome::Success(ClientId(client_id))
+    }
+}
+
+/// The entity tags listed by the `If-None-Match` header, if any
+struct IfNoneMatch(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfNoneMatch {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        request::Outcome::Success(IfNoneMatch(
+            request.headers().get_one("If-None-Match").map(String::from),
+        ))
+    }
+}
+
+impl IfNoneMatch {
+    /// Returns true if any listed tag (or `*`) matches `etag`, using the weak
+    /// comparison that `If-None-Match` calls for
+    fn matches(&self, etag: &str) -> bool {
+        let opaque = |tag: &str| tag.strip_prefix("W/").unwrap_or(tag).to_string();
+
+        match &self.0 {
+            Some(tags) => tags
+                .split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || opaque(tag) == opaque(etag)),
+            None => false,
+        }
+    }
+}
+
+/// A resource along with its `ETag`, or 304 Not Modified if the client's copy
+/// (per [IfNoneMatch]) is current
+#[derive(Responder)]
+enum Conditional<T> {
+    Modified(Json<T>, Header<'static>),
+    #[response(status = 304)]
+    NotModified((), Header<'static>),
+}
+
+/// Whether the client prefers YAML (e.g., `Accept: application/yaml`) to JSON
+struct AcceptYaml(bool);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AcceptYaml {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let preferred = request
+            .accept()
+            .map(|accept| accept.preferred().media_type().sub().as_str());
+
+        request::Outcome::Success(AcceptYaml(matches!(preferred, Some("yaml" | "x-yaml"))))
+    }
+}
+
+/// A response as JSON, or as YAML in the format of the configuration file
+/// (per [AcceptYaml])
+#[derive(Responder)]
+enum Negotiated<T> {
+    Json(Json<T>),
+    #[response(content_type = "application/yaml")]
+    Yaml(String),
+}
+
+impl<T> Negotiated<T> {
+    fn yaml(yaml: Pca9685Result<String>) -> Result<Negotiated<T>, HttpError> {
+        match yaml {
+            Ok(yaml) => Ok(Negotiated::Yaml(yaml)),
+            Err(error) => Err(status::Custom(
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: error.to_string(),
+                }),
+            )),
+        }
+    }
+}
+
+type HttpError = status::Custom<Json<ErrorResponse>>;
+type HttpResult<T> = Result<Json<T>, HttpError>;
+
+#[get("/status")]
+fn get_status(
+    process_start: &State<ProcessStart>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<StatusResponse> {
+    let error = pca.device_error();
+
+    Ok(Json(StatusResponse {
+        status: match error {
+            Some(_) => StatusType::DEGRADED,
+            None => StatusType::HEALTHY,
+        },
+        software: SoftwareStatus {
+            version: utils::built_info::PKG_VERSION.to_string(),
+        },
+        error,
+        paused: pca.paused(),
+        idling: pca.idling(),
+        uptime_seconds: process_start.at.elapsed().as_secs(),
+        started_at_ms: process_start.timestamp_ms,
+        reinit_count: pca.reinit_count(),
+        last_driver_error: pca.last_driver_error(),
+    }))
+}
+
+#[get("/device")]
+fn get_device(pca: &State<Arc<Pca9685>>) -> HttpResult<DeviceResponse> {
+    Ok(Json(DeviceResponse {
+        device: pca.device(),
+        address: pca.address(),
+        output_type: format!("{:?}", pca.output_type()),
+        disabled_output: pca.disabled_output(),
+        prescale: pca.prescale(),
+        output_frequency_hz: pca.output_frequency_hz(),
+        actual_output_frequency_hz: pca.actual_output_frequency_hz(),
+        max_pw_ms: pca.max_pw_ms(),
+        single_count_duration_ms: pca.single_count_duration_ms(),
+    }))
+}
+
+/// Changes what every output does while disabled by the OE pin (see
+/// [Pca9685::set_disabled_output]), e.g., `"high_impedance"`
+#[put(
+    "/device/disabled-output",
+    format = "application/json",
+    data = "<disabled_output>"
+)]
+fn put_device_disabled_output(
+    disabled_output: Json<DisabledOutput>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<DeviceResponse> {
+    assert_device_available(pca)?;
+
+    match pca.set_disabled_output(disabled_output.into_inner()) {
+        Ok(()) => get_device(pca),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Target of mutating requests rejected by [NetworkPolicy]
+#[get("/network-policy/denied")]
+fn get_network_policy_denied() -> HttpResult<()> {
+    Err(status::Custom(
+        Status::Forbidden,
+        Json(ErrorResponse {
+            error: "Client network is not permitted to modify this device.".to_string(),
+        }),
+    ))
+}
+
+fn extract_channel(path_channel: u8, body_channel: Channel) -> Result<Channel, HttpError> {
+    if path_channel != (body_channel as u8) {
+        return Err(status::Custom(
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: format!(
+                    "Request body channel ({:?}) doesn't match resource channel ({:?}).",
+                    body_channel, path_channel
+                ),
+            }),
+        ));
+    }
+
+    Ok(Channel::try_from(path_channel).unwrap())
+}
+
+fn extract_error(error: &Pca9685Error) -> status::Custom<Json<ErrorResponse>> {
+    let error_code = match error {
+        Pca9685Error::NoSuchChannelNameError(_)
+        | Pca9685Error::NoSuchGroupError(_)
+        | Pca9685Error::NoSuchTagError(_)
+        | Pca9685Error::NoSuchMixerError(_)
+        | Pca9685Error::NoSuchProfileError(_)
+        | Pca9685Error::NoSuchSequenceError(_)
+        | Pca9685Error::NoSuchPresetError(_)
+        | Pca9685Error::NoSuchGaitError(_)
+        | Pca9685Error::NoSuchLinearAxisError(_) => Status::NotFound,
+        Pca9685Error::ChannelFaultedError(_)
+        | Pca9685Error::ChannelMaintenanceError(_)
+        | Pca9685Error::ChannelUnconfiguredError(_)
+        | Pca9685Error::ChannelDisarmedError(_)
+        | Pca9685Error::PausedError => Status::Conflict,
+        Pca9685Error::StorageError(_) | Pca9685Error::Pca9685DriverError(_) => {
+            Status::InternalServerError
+        }
+        _ => Status::BadRequest,
+    };
+
+    status::Custom(
+        error_code,
+        Json(ErrorResponse {
+            error: error.to_string(),
+        }),
+    )
+}
+
+/// Fails with 503 Service Unavailable while the device could not be
+/// initialized (the service is read-only until it can).  Every route that
+/// changes the output, or the runtime state driving it, calls this first;
+/// only routes that stop something (a move, playback, recording or linear
+/// axis), validate, record, send heartbeats or edit the stored sequences are
+/// exempt, so that a degraded rig can still be brought to rest and prepared.
+fn assert_device_available(pca: &State<Arc<Pca9685>>) -> Result<(), HttpError> {
+    match pca.device_error() {
+        Some(error) => Err(status::Custom(
+            Status::ServiceUnavailable,
+            Json(ErrorResponse { error }),
+        )),
+        None => Ok(()),
+    }
+}
+
+fn get_channel_config(channel: Channel, pca: &State<Arc<Pca9685>>) -> HttpResult<ChannelConfig> {
+    match pca.config(channel) {
+        Ok(config) => match config.custom_limits {
+            Some(_) => Ok(Json(config)),
+            None => Err(status::Custom(
+                Status::NotFound,
+                Json(ErrorResponse {
+                    error: format!("Channel {:?} not configured.", channel),
+                }),
+            )),
+        },
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Conditions that `GET /channel/<n>?wait_for=` can block on
+#[derive(Debug, PartialEq, FromFormField)]
+enum WaitFor {
+    #[field(value = "settled")]
+    Settled,
+}
+
+const DEFAULT_WAIT_TIMEOUT_MS: u64 = 5000;
+const WAIT_POLL_INTERVAL_MS: u64 = 10;
+
+/// Returns the channel's configuration.  With `wait_for=settled`, blocks
+/// until the channel's motion completes, or fails with 408 Request Timeout
+/// after `timeout` milliseconds (default 5000).
+///
+/// The response's `ETag` is derived from the channel's revision; given a
+/// matching `If-None-Match`, responds 304 Not Modified without a body.
+#[get("/channel/<channel>?<wait_for>&<timeout>")]
+async fn get_channel(
+    channel: u8,
+    wait_for: Option<WaitFor>,
+    timeout: Option<u64>,
+    if_none_match: IfNoneMatch,
+    process_start: &State<ProcessStart>,
+    pca: &State<Arc<Pca9685>>,
+) -> Result<Conditional<ChannelConfig>, HttpError> {
+    let channel = Channel::try_from(channel).unwrap();
+
+    if wait_for == Some(WaitFor::Settled) {
+        let timeout = Duration::from_millis(timeout.unwrap_or(DEFAULT_WAIT_TIMEOUT_MS));
+        let deadline = Instant::now() + timeout;
+
+        while !pca
+            .is_settled(channel)
+            .map_err(|error| extract_error(&error))?
+        {
+            if Instant::now() >= deadline {
+                return Err(status::Custom(
+                    Status::RequestTimeout,
+                    Json(ErrorResponse {
+                        error: format!(
+                            "Channel {:?} did not settle within {}ms.",
+                            channel,
+                            timeout.as_millis()
+                        ),
+                    }),
+                ));
+            }
+            sleep(Duration::from_millis(WAIT_POLL_INTERVAL_MS)).await;
+        }
+    }
+
+    // Assert channel is configured/exists
+    get_channel_config(channel, pca)?;
+
+    let (config, revision) = pca
+        .revised_config(channel)
+        .map_err(|error| extract_error(&error))?;
+    // Qualified by the start of the service, as revisions restart with it
+    let etag = format!("\"{}-{}\"", process_start.timestamp_ms, revision);
+    let etag_header = Header::new("ETag", etag.clone());
+
+    if if_none_match.matches(&etag) {
+        Ok(Conditional::NotModified((), etag_header))
+    } else {
+        Ok(Conditional::Modified(Json(config), etag_header))
+    }
+}
+
+/// Returns the configuration of the channel named `name` (see
+/// [Pca9685::config_by_name])
+#[get("/named-channel/<name>")]
+fn get_named_channel(name: &str, pca: &State<Arc<Pca9685>>) -> HttpResult<ChannelConfig> {
+    let channel = pca
+        .channel_by_name(name)
+        .map_err(|error| extract_error(&error))?;
+
+    get_channel_config(channel, pca)
+}
+
+/// Sets the output of the channel named `name` to `pct` of its range (see
+/// [Pca9685::set_pct_by_name])
+#[put("/named-channel/<name>/pct?<pct>")]
+fn put_named_channel_pct(
+    name: &str,
+    pct: f64,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<ChannelConfig> {
+    assert_device_available(pca)?;
+
+    match pca.set_pct_by_name(name, pct) {
+        Ok(config) => Ok(Json(config)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Sets every channel of a group together, e.g. `/group/legs/pct?pct=0.3`;
+/// none changes unless all can
+#[put("/group/<name>/pct?<pct>")]
+fn put_group_pct(
+    name: &str,
+    pct: f64,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<Vec<ChannelConfig>> {
+    assert_device_available(pca)?;
+
+    match pca.set_group_pct(name, pct) {
+        Ok(configs) => Ok(Json(configs)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[get("/channel/<channel>/history?<resolution_ms>")]
+fn get_channel_history(
+    channel: u8,
+    resolution_ms: Option<u64>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<Vec<ChannelHistoryEntry>> {
+    match pca.history(Channel::try_from(channel).unwrap(), resolution_ms) {
+        Ok(history) => Ok(Json(history)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[get("/channel/<channel>/log")]
+fn get_channel_log(
+    channel: u8,
+    pca: &State<Arc<Pca9685>>,
+    log_lines: &State<Arc<LogLines>>,
+) -> HttpResult<Vec<LogLine>> {
+    match pca.log_target(Channel::try_from(channel).unwrap()) {
+        Ok(log_target) => Ok(Json(log_lines.get(&log_target))),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[post("/channel/<channel>/clear-fault")]
+fn post_channel_clear_fault(channel: u8, pca: &State<Arc<Pca9685>>) -> HttpResult<ChannelStats> {
+    assert_device_available(pca)?;
+    let channel = Channel::try_from(channel).unwrap();
+
+    // Assert channel is configured/exists
+    get_channel_config(channel, pca)?;
+
+    match pca.clear_fault(channel) {
+        Ok(stats) => Ok(Json(stats)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Returns the (raw) channels under maintenance
+#[get("/maintenance")]
+fn get_maintenance(pca: &State<Arc<Pca9685>>) -> HttpResult<Vec<u8>> {
+    Ok(Json(
+        pca.maintenance()
+            .into_iter()
+            .map(|channel| channel as u8)
+            .collect(),
+    ))
+}
+
+#[post("/channel/<channel>/maintenance")]
+fn post_channel_maintenance(channel: u8, pca: &State<Arc<Pca9685>>) -> HttpResult<ChannelConfig> {
+    set_channel_maintenance(channel, true, pca)
+}
+
+#[delete("/channel/<channel>/maintenance")]
+fn delete_channel_maintenance(channel: u8, pca: &State<Arc<Pca9685>>) -> HttpResult<ChannelConfig> {
+    set_channel_maintenance(channel, false, pca)
+}
+
+fn set_channel_maintenance(
+    channel: u8,
+    under_maintenance: bool,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<ChannelConfig> {
+    assert_device_available(pca)?;
+    let channel = Channel::try_from(channel).unwrap();
+
+    // Assert channel is configured/exists
+    get_channel_config(channel, pca)?;
+
+    match pca.set_maintenance(channel, under_maintenance) {
+        Ok(config) => Ok(Json(config)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Arms the channel's ESC (see [Pca9685::arm]): throttle is accepted once
+/// minimum throttle has been held for the ESC's `arming_ms`
+#[post("/channel/<channel>/arm")]
+fn post_channel_arm(channel: u8, pca: &State<Arc<Pca9685>>) -> HttpResult<ChannelConfig> {
+    assert_device_available(pca)?;
+    let channel = Channel::try_from(channel).unwrap();
+
+    // Assert channel is configured/exists
+    get_channel_config(channel, pca)?;
+
+    match pca.arm(channel) {
+        Ok(config) => Ok(Json(config)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[delete("/channel/<channel>/arm")]
+fn delete_channel_arm(channel: u8, pca: &State<Arc<Pca9685>>) -> HttpResult<ChannelConfig> {
+    assert_device_available(pca)?;
+    let channel = Channel::try_from(channel).unwrap();
+
+    // Assert channel is configured/exists
+    get_channel_config(channel, pca)?;
+
+    match pca.disarm(channel) {
+        Ok(config) => Ok(Json(config)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Moves the channel to `pct` over `duration_ms`, ramping through the counts
+/// in between per `easing` (`linear` by default, `ease_in_out`, `cubic` or
+/// `sine`; see [Pca9685::move_to_eased])
+#[put("/channel/<channel>/move?<pct>&<duration_ms>&<easing>")]
+fn put_channel_move(
+    channel: u8,
+    pct: f64,
+    duration_ms: u64,
+    easing: Option<&str>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<ChannelConfig> {
+    assert_device_available(pca)?;
+    let channel = Channel::try_from(channel).unwrap();
+    let easing = easing.map(parse_easing).transpose()?.unwrap_or_default();
+
+    // Assert channel is configured/exists
+    get_channel_config(channel, pca)?;
+
+    match pca.move_to_eased(channel, pct, Duration::from_millis(duration_ms), easing) {
+        Ok(config) => Ok(Json(config)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+fn parse_easing(easing: &str) -> Result<Easing, HttpError> {
+    json::from_value(json::Value::String(easing.to_string())).map_err(|_| {
+        status::Custom(
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: format!("Invalid easing '{}'.", easing),
+            }),
+        )
+    })
+}
+
+/// Stops the channel's move where it is
+#[delete("/channel/<channel>/move")]
+fn delete_channel_move(channel: u8, pca: &State<Arc<Pca9685>>) -> HttpResult<ChannelConfig> {
+    let channel = Channel::try_from(channel).unwrap();
+
+    match pca.stop_move(channel) {
+        Ok(config) => Ok(Json(config)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Returns the jitter injected into the channel (404 if none)
+#[get("/channel/<channel>/jitter")]
+fn get_channel_jitter(channel: u8, pca: &State<Arc<Pca9685>>) -> HttpResult<JitterConfig> {
+    let channel = Channel::try_from(channel).unwrap();
+
+    match pca.jitter(channel) {
+        Ok(Some(jitter)) => Ok(Json(jitter)),
+        Ok(None) => Err(status::Custom(
+            Status::NotFound,
+            Json(ErrorResponse {
+                error: format!("No jitter on channel {:?}.", channel),
+            }),
+        )),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Injects jitter into the channel for testing, if the configuration's
+/// `allow_jitter` permits (see [Pca9685::set_jitter])
+#[put(
+    "/channel/<channel>/jitter",
+    format = "application/json",
+    data = "<jitter>"
+)]
+fn put_channel_jitter(
+    channel: u8,
+    jitter: Json<JitterConfig>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<ChannelConfig> {
+    set_channel_jitter(channel, Some(jitter.into_inner()), pca)
+}
+
+#[delete("/channel/<channel>/jitter")]
+fn delete_channel_jitter(channel: u8, pca: &State<Arc<Pca9685>>) -> HttpResult<ChannelConfig> {
+    set_channel_jitter(channel, None, pca)
+}
+
+fn set_channel_jitter(
+    channel: u8,
+    jitter: Option<JitterConfig>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<ChannelConfig> {
+    assert_device_available(pca)?;
+    let channel = Channel::try_from(channel).unwrap();
+
+    // Assert channel is configured/exists
+    get_channel_config(channel, pca)?;
+
+    match pca.set_jitter(channel, jitter) {
+        Ok(config) => Ok(Json(config)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Returns whether the camera is being stabilized (404 if no gimbal is
+/// configured)
+#[cfg(feature = "gimbal")]
+#[get("/gimbal")]
+fn get_gimbal(pca: &State<Arc<Pca9685>>) -> HttpResult<GimbalStatus> {
+    match pca.gimbal() {
+        Some(status) => Ok(Json(status)),
+        None => Err(status::Custom(
+            Status::NotFound,
+            Json(ErrorResponse {
+                error: "No gimbal is configured.".to_string(),
+            }),
+        )),
+    }
+}
+
+/// Starts stabilizing the camera (see [Pca9685::set_gimbal_enabled])
+#[cfg(feature = "gimbal")]
+#[post("/gimbal")]
+fn post_gimbal(pca: &State<Arc<Pca9685>>) -> HttpResult<GimbalStatus> {
+    set_gimbal_enabled(true, pca)
+}
+
+#[cfg(feature = "gimbal")]
+#[delete("/gimbal")]
+fn delete_gimbal(pca: &State<Arc<Pca9685>>) -> HttpResult<GimbalStatus> {
+    set_gimbal_enabled(false, pca)
+}
+
+#[cfg(feature = "gimbal")]
+fn set_gimbal_enabled(enabled: bool, pca: &State<Arc<Pca9685>>) -> HttpResult<GimbalStatus> {
+    assert_device_available(pca)?;
+    get_gimbal(pca)?;
+
+    match pca.set_gimbal_enabled(enabled) {
+        Ok(status) => Ok(Json(status)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+const DEFAULT_PID_STREAM_INTERVAL_MS: u64 = 20;
+
+/// Streams the iterations of a Channel's PID controller (see
+/// [pca9685::PidGains]) as Server-Sent Events, at most one every
+/// `interval_ms` (default 20), e.g. to watch the response while tuning its
+/// gains with `PATCH /channel/<channel>`.  The stream ends after `limit`
+/// events, if given.
+#[get("/channel/<channel>/pid/stream?<interval_ms>&<limit>")]
+fn get_channel_pid_stream(
+    channel: u8,
+    interval_ms: Option<u64>,
+    limit: Option<usize>,
+    pca: &State<Arc<Pca9685>>,
+    mut shutdown: Shutdown,
+) -> Result<EventStream![Event + '_], HttpError> {
+    let channel = Channel::try_from(channel).unwrap();
+
+    // Assert channel is configured/exists
+    if get_channel_config(channel, pca)?.pid.is_none() {
+        return Err(status::Custom(
+            Status::NotFound,
+            Json(ErrorResponse {
+                error: format!("Channel {:?} has no PID controller.", channel),
+            }),
+        ));
+    }
+
+    let interval =
+        Duration::from_millis(interval_ms.unwrap_or(DEFAULT_PID_STREAM_INTERVAL_MS).max(1));
+    Ok(EventStream! {
+        let mut previous = None;
+        let mut sent = 0;
+        while limit.map_or(true, |limit| sent < limit) {
+            let Ok(sample) = pca.pid_sample(channel) else {
+                break;
+            };
+            if sample.is_some() && sample != previous {
+                yield Event::json(&sample.unwrap());
+                previous = sample;
+                sent += 1;
+            }
+
+            select! {
+                _ = sleep(interval) => (),
+                _ = &mut shutdown => break,
+            }
+        }
+    })
+}
+
+#[get("/channel/<channel>/stats")]
+fn get_channel_stats(channel: u8, pca: &State<Arc<Pca9685>>) -> HttpResult<ChannelStats> {
+    let channel = Channel::try_from(channel).unwrap();
+
+    // Assert channel is configured/exists
+    get_channel_config(channel, pca)?;
+
+    match pca.stats(channel) {
+        Ok(stats) => Ok(Json(stats)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Returns `value` as a CSV field, quoted (per RFC 4180) if it contains a
+/// comma, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[get("/channel/<channel>/history.csv?<resolution_ms>")]
+fn get_channel_history_csv(
+    channel: u8,
+    resolution_ms: Option<u64>,
+    pca: &State<Arc<Pca9685>>,
+) -> Result<(ContentType, String), HttpError> {
+    match pca.history(Channel::try_from(channel).unwrap(), resolution_ms) {
+        Ok(history) => {
+            let mut csv = String::from("timestamp_ms,count,client_id\n");
+            for entry in history {
+                let count = entry.count.map(|count| count.to_string());
+                csv.push_str(&format!(
+                    "{},{},{}\n",
+                    entry.timestamp_ms,
+                    count.unwrap_or_default(),
+                    csv_field(&entry.client_id.unwrap_or_default())
+                ));
+            }
+            Ok((ContentType::CSV, csv))
+        }
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Steps a Channel between two counts (see [Pca9685::step_test]), returning
+/// its response as CSV
+#[post(
+    "/channel/<channel>/step-test",
+    format = "application/json",
+    data = "<test>"
+)]
+fn post_channel_step_test(
+    channel: u8,
+    test: Json<StepTest>,
+    pca: &State<Arc<Pca9685>>,
+) -> Result<(ContentType, String), HttpError> {
+    assert_device_available(pca)?;
+    let channel = Channel::try_from(channel).unwrap();
+
+    // Assert channel is configured/exists
+    get_channel_config(channel, pca)?;
+
+    match pca.step_test(channel, &test) {
+        Ok(samples) => Ok((ContentType::CSV, utils::step_response_csv(&samples))),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Exports the output history of every Channel (see
+/// [Pca9685::history_records]) as gzip-compressed NDJSON, one record per
+/// line, optionally limited to `since_ms`..=`until_ms`
+#[get("/history/export?<since_ms>&<until_ms>")]
+fn get_history_export(
+    since_ms: Option<u64>,
+    until_ms: Option<u64>,
+    pca: &State<Arc<Pca9685>>,
+) -> Result<(ContentType, Vec<u8>), HttpError> {
+    if let (Some(since_ms), Some(until_ms)) = (since_ms, until_ms) {
+        if since_ms > until_ms {
+            return Err(status::Custom(
+                Status::BadRequest,
+                Json(ErrorResponse {
+                    error: format!("since_ms ({}) is after until_ms ({}).", since_ms, until_ms),
+                }),
+            ));
+        }
+    }
+
+    let internal_error =
+        |error: String| status::Custom(Status::InternalServerError, Json(ErrorResponse { error }));
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    for record in pca.history_records(since_ms, until_ms) {
+        let line = json::to_string(&record).map_err(|error| internal_error(error.to_string()))?;
+        writeln!(encoder, "{}", line).map_err(|error| internal_error(error.to_string()))?;
+    }
+    let gzip = encoder
+        .finish()
+        .map_err(|error| internal_error(error.to_string()))?;
+
+    Ok((ContentType::GZIP, gzip))
+}
+
+/// Saves `config` into the configuration file if `persist` (by default,
+/// per `Config.autopersist`; see [Config::persist_channel])
+fn persist_channel(
+    persist: Option<bool>,
+    config: &ChannelConfig,
+    config_file: &ConfigFile,
+) -> Result<(), HttpError> {
+    if !persist.unwrap_or(config_file.autopersist) {
+        return Ok(());
+    }
+
+    Config::persist_channel(&config_file.path, config).map_err(|error| {
+        status::Custom(
+            Status::InternalServerError,
+            Json(ErrorResponse {
+                error: error.to_string(),
+            }),
+        )
+    })
+}
+
+/// Configures a Channel; with `?persist=true`, also saves it into the
+/// configuration file
+#[post("/channel?<persist>", format = "application/json", data = "<command>")]
+fn post_channel(
+    command: Json<ChannelConfig>,
+    persist: Option<bool>,
+    config_file: &State<ConfigFile>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<ChannelConfig> {
+    assert_device_available(pca)?;
+
+    match pca.config(command.channel) {
+        Ok(existing_config) => match existing_config.custom_limits {
+            Some(_) => Err(status::Custom(
+                Status::Conflict,
+                Json(ErrorResponse {
+                    error: format!("Channel {:?} already configured.", command.channel),
+                }),
+            )),
+            None => match pca.configure_channel(&command.into_inner()) {
+                Ok(new_config) => {
+                    persist_channel(persist, &new_config, config_file)?;
+                    Ok(Json(new_config))
+                }
+                Err(error) => Err(extract_error(&error)),
+            },
+        },
+        Err(_) => Err(status::Custom(
+            Status::NotFound,
+            Json(ErrorResponse {
+                error: format!("Channel {:?} not found.", command.channel),
+            }),
+        )),
+    }
+}
+
+/// Executes a command; with `?preview=true`, only evaluates it (validation,
+/// filters, and limits), returning the resulting config without writing
+#[put(
+    "/channel/<channel>?<preview>",
+    format = "application/json",
+    data = "<command>"
+)]
+fn put_channel(
+    channel: u8,
+    preview: Option<bool>,
+    command: Json<ChannelCommand>,
+    client: ClientId,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<ChannelConfig> {
+    let channel = extract_channel(channel, command.channel)?;
+
+    // Assert channel is configured/exists
+    get_channel_config(channel, pca)?;
+
+    if preview.unwrap_or(false) {
+        return match pca.preview(channel, &command) {
+            Ok(config) => Ok(Json(config)),
+            Err(error) => Err(extract_error(&error)),
+        };
+    }
+    assert_device_available(pca)?;
+
+    let mut command = command.into_inner();
+    command.client_id = client.0;
+
+    match pca.execute(&command) {
+        Ok(config) => Ok(Json(config)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[put(
+    "/channel/<channel>/calibration?<persist>",
+    format = "application/json",
+    data = "<calibration>"
+)]
+fn put_channel_calibration(
+    channel: u8,
+    calibration: Json<ChannelCalibration>,
+    persist: Option<bool>,
+    config_file: &State<ConfigFile>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<ChannelConfig> {
+    assert_device_available(pca)?;
+    let channel = Channel::try_from(channel).unwrap();
+
+    // Assert channel is configured/exists
+    get_channel_config(channel, pca)?;
+
+    match pca.calibrate(channel, Some(calibration.into_inner())) {
+        Ok(config) => {
+            persist_channel(persist, &config, config_file)?;
+            Ok(Json(config))
+        }
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[delete("/channel/<channel>/calibration?<persist>")]
+fn delete_channel_calibration(
+    channel: u8,
+    persist: Option<bool>,
+    config_file: &State<ConfigFile>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<ChannelConfig> {
+    assert_device_available(pca)?;
+    let channel = Channel::try_from(channel).unwrap();
+
+    // Assert channel is configured/exists
+    get_channel_config(channel, pca)?;
+
+    match pca.calibrate(channel, None) {
+        Ok(config) => {
+            persist_channel(persist, &config, config_file)?;
+            Ok(Json(config))
+        }
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Updates individual fields of a Channel's configuration per a JSON Patch
+/// (RFC 6902), e.g. only `/custom_limits/count_limits/max_on_count`; the
+/// patched config is validated as a whole.  With `?persist=true`, also saves
+/// it into the configuration file
+#[patch(
+    "/channel/<channel>?<persist>",
+    format = "application/json-patch+json",
+    data = "<patch>"
+)]
+fn patch_channel(
+    channel: u8,
+    patch: Json<Vec<PatchOperation>>,
+    persist: Option<bool>,
+    config_file: &State<ConfigFile>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<ChannelConfig> {
+    assert_device_available(pca)?;
+    let channel = Channel::try_from(channel).unwrap();
+
+    let bad_request =
+        |error: String| status::Custom(Status::BadRequest, Json(ErrorResponse { error }));
+
+    let mut config = get_channel_config(channel, pca)?.into_inner();
+    config.custom_limits = config.given_limits();
+
+    let mut document = json::to_value(&config).map_err(|error| bad_request(error.to_string()))?;
+    json_patch::apply(&mut document, &patch).map_err(bad_request)?;
+    let patched: ChannelConfig =
+        json::from_value(document).map_err(|error| bad_request(error.to_string()))?;
+
+    if patched.channel != config.channel
+        || patched.current_count != config.current_count
+        || patched.owner != config.owner
+        || patched.display_value != config.display_value
+    {
+        return Err(bad_request(
+            "Only configuration fields may be patched (not channel, current_count, owner, or display_value).".to_string(),
+        ));
+    }
+
+    match pca.configure_channel(&patched) {
+        Ok(new_config) => {
+            persist_channel(persist, &new_config, config_file)?;
+            Ok(Json(new_config))
+        }
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+impl ChannelTransaction {
+    fn evaluate(&self, config: &ChannelConfig) -> Result<u16, Pca9685Error> {
+        let current_count = config
+            .current_count
+            .ok_or(Pca9685Error::ChannelInactiveError(config.channel as u8))?;
+        let (min_on_count, max_on_count) = config.limits();
+
+        let to_count = |value: f64| match self.unit {
+            TransactionUnit::PulseCount => value,
+            TransactionUnit::Percent => {
+                min_on_count as f64 + (max_on_count - min_on_count) as f64 * value
+            }
+        };
+        let delta = match self.unit {
+            TransactionUnit::PulseCount => self.delta,
+            TransactionUnit::Percent => (max_on_count - min_on_count) as f64 * self.delta,
+        };
+
+        let current = current_count as f64;
+        let mut target = current + delta;
+        if let Some(max) = self.max.map(to_count) {
+            if delta > 0.0 {
+                target = target.min(max.max(current));
+            }
+        }
+        if let Some(min) = self.min.map(to_count) {
+            if delta < 0.0 {
+                target = target.max(min.min(current));
+            }
+        }
+
+        Ok(target
+            .round()
+            .clamp(min_on_count as f64, max_on_count as f64) as u16)
+    }
+}
+
+#[post(
+    "/channel/<channel>/transaction",
+    format = "application/json",
+    data = "<transaction>"
+)]
+fn post_channel_transaction(
+    channel: u8,
+    transaction: Json<ChannelTransaction>,
+    client: ClientId,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<ChannelConfig> {
+    assert_device_available(pca)?;
+    let channel = extract_channel(channel, transaction.channel)?;
+
+    // Assert channel is configured/exists
+    get_channel_config(channel, pca)?;
+
+    match pca.transaction(channel, client.0.as_deref(), |config| {
+        transaction.evaluate(config)
+    }) {
+        Ok(config) => Ok(Json(config)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[put("/channels", format = "application/json", data = "<batch>")]
+fn put_channels(
+    batch: Json<ChannelCommands>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<Vec<ChannelConfig>> {
+    assert_device_available(pca)?;
+
+    // Assert channels are configured/exist
+    for command in &batch.commands {
+        get_channel_config(command.channel, pca)?;
+    }
+
+    match pca.apply(&batch.commands, batch.atomic) {
+        Ok(configs) => Ok(Json(configs)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Applies one command to every channel tagged `tag` together, e.g.
+/// `/channels?tag=left` with `{"command_type": "Percent", "value": 0.5}`;
+/// none changes unless all can
+#[put("/channels?<tag>", format = "application/json", data = "<command>")]
+fn put_tagged_channels(
+    tag: &str,
+    command: Json<SelectedCommand>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<Vec<ChannelConfig>> {
+    assert_device_available(pca)?;
+
+    match pca.command_selected(
+        &ChannelSelector::Tag(tag.to_string()),
+        command.command_type,
+        command.value,
+        command.basis,
+    ) {
+        Ok(configs) => Ok(Json(configs)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// De-energizes the selected channels (e.g., `"all"` or `{"group": "legs"}`)
+/// so that they can be posed by hand
+#[post("/relax", format = "application/json", data = "<selector>")]
+fn post_relax(
+    selector: Json<ChannelSelector>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<Vec<ChannelConfig>> {
+    assert_device_available(pca)?;
+
+    match pca.relax(&selector) {
+        Ok(configs) => Ok(Json(configs)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Parses a device configuration: YAML (as in the config file) given a YAML
+/// content type, otherwise JSON
+fn parse_config(content_type: Option<&ContentType>, body: &str) -> Result<Config, String> {
+    let is_yaml = matches!(content_type, Some(content_type) if content_type.sub() == "yaml" || content_type.sub() == "x-yaml");
+    if is_yaml {
+        serde_yaml::from_str::<Config>(body).map_err(|error| error.to_string())
+    } else {
+        rocket::serde::json::from_str::<Config>(body).map_err(|error| error.to_string())
+    }
+}
+
+/// Validates a device configuration (JSON, or YAML as in the config file)
+/// without applying it, responding 422 Unprocessable Entity if it has errors
+#[post("/config/validate", data = "<body>")]
+fn post_config_validate(
+    content_type: Option<&ContentType>,
+    body: String,
+) -> status::Custom<Json<ConfigValidation>> {
+    let validation = match parse_config(content_type, &body) {
+        Ok(config) => Pca9685::validate_config(&config),
+        Err(message) => ConfigValidation {
+            valid: false,
+            findings: vec![ConfigFinding {
+                severity: FindingSeverity::Error,
+                path: String::new(),
+                message,
+            }],
+        },
+    };
+
+    validation_response(validation)
+}
+
+/// Responds with `validation`: 200 OK if valid, otherwise 422 Unprocessable
+/// Entity
+fn validation_response(validation: ConfigValidation) -> status::Custom<Json<ConfigValidation>> {
+    let status = if validation.valid {
+        Status::Ok
+    } else {
+        Status::UnprocessableEntity
+    };
+    status::Custom(status, Json(validation))
+}
+
+/// Stops writing to the device until `POST /resume`; with `reject=true`,
+/// mutating commands are rejected (409) rather than queued meanwhile
+#[post("/pause?<reject>")]
+fn post_pause(reject: Option<bool>, pca: &State<Arc<Pca9685>>) -> HttpResult<PauseResponse> {
+    assert_device_available(pca)?;
+
+    pca.pause(match reject {
+        Some(true) => PauseMode::Reject,
+        _ => PauseMode::Queue,
+    });
+
+    Ok(Json(PauseResponse {
+        paused: pca.paused(),
+    }))
+}
+
+#[post("/resume")]
+fn post_resume(pca: &State<Arc<Pca9685>>) -> HttpResult<Vec<ChannelConfig>> {
+    assert_device_available(pca)?;
+
+    match pca.resume() {
+        Ok(configs) => Ok(Json(configs)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Stops everything under way and carries out the rules awaiting an emergency
+/// stop (see [Pca9685::emergency_stop]), returning every Channel's resulting
+/// configuration and output
+#[post("/emergency_stop")]
+fn post_emergency_stop(pca: &State<Arc<Pca9685>>) -> HttpResult<Vec<ChannelConfig>> {
+    assert_device_available(pca)?;
+
+    pca.emergency_stop();
+
+    Ok(Json(pca.snapshot().channels))
+}
+
+/// Captures every Channel's configuration and output (including its owner),
+/// the mixer inputs and the active teleop profile (see [Pca9685::snapshot])
+#[get("/snapshot")]
+fn get_snapshot(pca: &State<Arc<Pca9685>>) -> Json<Snapshot> {
+    Json(pca.snapshot())
+}
+
+/// Reapplies a snapshot from `GET /snapshot` (see [Pca9685::restore])
+#[post("/snapshot", format = "application/json", data = "<snapshot>")]
+fn post_snapshot(
+    snapshot: Json<Snapshot>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<Vec<ChannelConfig>> {
+    assert_device_available(pca)?;
+
+    match pca.restore(&snapshot) {
+        Ok(configs) => Ok(Json(configs)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Reports the state of the controller for debugging (see
+/// [Pca9685::debug_report]); also logged on SIGUSR1
+#[get("/debug/state")]
+fn get_debug_state(pca: &State<Arc<Pca9685>>) -> Json<DebugReport> {
+    Json(pca.debug_report())
+}
+
+/// The largest backup accepted by `POST /restore`
+const BACKUP_LIMIT_MIB: usize = 16;
+
+/// Bundles the running configuration, the configuration file as written,
+/// sequences and state as a tar archive (see [Pca9685::backup]), e.g. to
+/// migrate to fresh hardware
+#[get("/backup")]
+fn get_backup(
+    config: &State<Config>,
+    config_file: &State<ConfigFile>,
+    pca: &State<Arc<Pca9685>>,
+) -> Result<(ContentType, Vec<u8>), HttpError> {
+    let backup = Backup {
+        source: fs::read_to_string(&config_file.path).ok(),
+        ..pca.backup(config)
+    };
+    match backup.to_tar() {
+        Ok(tar) => Ok((ContentType::TAR, tar)),
+        Err(error) => Err(status::Custom(
+            Status::InternalServerError,
+            Json(ErrorResponse {
+                error: error.to_string(),
+            }),
+        )),
+    }
+}
+
+/// Applies a backup from `GET /backup` (see [Pca9685::restore_backup]),
+/// validated and staged in full first, so that nothing is changed unless all
+/// of it can be: its state and sequences are applied at once (replacing any
+/// saved sequences), and its configuration file as written (or, lacking one,
+/// its running configuration) replaces the configuration file, taking effect
+/// on restart
+#[post("/restore", data = "<data>")]
+async fn post_restore(
+    data: Data<'_>,
+    config_file: &State<ConfigFile>,
+    sequence_storage: &State<SequenceStorage>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<Vec<ChannelConfig>> {
+    assert_device_available(pca)?;
+
+    let bad_request =
+        |error: String| status::Custom(Status::BadRequest, Json(ErrorResponse { error }));
+    let tar = data
+        .open(BACKUP_LIMIT_MIB.mebibytes())
+        .into_bytes()
+        .await
+        .map_err(|error| bad_request(error.to_string()))?;
+    if !tar.is_complete() {
+        return Err(bad_request(format!(
+            "Backup exceeds {}MiB.",
+            BACKUP_LIMIT_MIB
+        )));
+    }
+    let backup = Backup::from_tar(&tar).map_err(|error| extract_error(&error))?;
+    pca.check_backup(&backup)
+        .map_err(|error| extract_error(&error))?;
+
+    let internal_error =
+        |error: String| status::Custom(Status::InternalServerError, Json(ErrorResponse { error }));
+    let staged_path = format!("{}.tmp", config_file.path);
+    let source = match &backup.source {
+        Some(source) => source.clone(),
+        None => backup
+            .config
+            .to_yaml()
+            .map_err(|error| internal_error(error.to_string()))?,
+    };
+    fs::write(&staged_path, source)
+        .map_err(|error| internal_error(format!("Unable to save to {}: {}", staged_path, error)))?;
+    // Loaded in place of the configuration file, so its includes resolve alike
+    if let Err(error) = check_config_file(&staged_path) {
+        let _ = fs::remove_file(&staged_path);
+        return Err(bad_request(error));
+    }
+
+    let replaced = pca.sequences();
+    if let Err(error) = sequence_storage.replace(&replaced, &backup.sequences) {
+        let _ = fs::remove_file(&staged_path);
+        return Err(error);
+    }
+    let configs = match pca.restore_backup(&backup) {
+        Ok(configs) => configs,
+        Err(error) => {
+            let _ = sequence_storage.replace(&backup.sequences, &replaced);
+            let _ = fs::remove_file(&staged_path);
+            return Err(extract_error(&error));
+        }
+    };
+    fs::rename(&staged_path, &config_file.path).map_err(|error| {
+        internal_error(format!("Unable to save to {}: {}", config_file.path, error))
+    })?;
+
+    Ok(Json(configs))
+}
+
+/// Fails with a description of the first problem if the configuration file
+/// at `path` cannot be loaded, or is invalid
+fn check_config_file(path: &str) -> Result<(), String> {
+    let config = Config::try_load_from_file(path).map_err(|error| error.to_string())?;
+    let errors: Vec<String> = Pca9685::validate_config(&config)
+        .findings
+        .into_iter()
+        .filter(|finding| finding.severity == FindingSeverity::Error)
+        .map(|finding| format!("{}: {}", finding.path, finding.message))
+        .collect();
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(errors.join("; ")),
+    }
+}
+
+/// Returns the running configuration (see [Pca9685::running_config]), its
+/// interpolated strings as written (see [Config::uninterpolated]); as YAML,
+/// it can be saved as a configuration file
+#[get("/config")]
+fn get_config(
+    accept_yaml: AcceptYaml,
+    config: &State<Config>,
+    pca: &State<Arc<Pca9685>>,
+) -> Result<Negotiated<Config>, HttpError> {
+    let running_config = pca.running_config(config);
+
+    if accept_yaml.0 {
+        Negotiated::yaml(running_config.to_yaml())
+    } else {
+        match running_config.uninterpolated() {
+            Ok(running_config) => Ok(Negotiated::Json(Json(running_config))),
+            Err(error) => Err(status::Custom(
+                Status::InternalServerError,
+                Json(ErrorResponse {
+                    error: error.to_string(),
+                }),
+            )),
+        }
+    }
+}
+
+/// Returns the configured Channels; as YAML, the `channels` of a
+/// configuration file (without their outputs)
+#[get("/channels")]
+fn get_channels(
+    accept_yaml: AcceptYaml,
+    config: &State<Config>,
+    pca: &State<Arc<Pca9685>>,
+) -> Result<Negotiated<Vec<ChannelConfig>>, HttpError> {
+    if accept_yaml.0 {
+        return Negotiated::yaml(
+            pca.running_config(config)
+                .channels
+                .iter()
+                .map(ChannelConfig::file_entry)
+                .collect::<Pca9685Result<Vec<_>>>()
+                .and_then(|entries| {
+                    serde_yaml::to_string(&entries)
+                        .map_err(|error| Pca9685Error::InvalidConfiguration(error.to_string()))
+                }),
+        );
+    }
+
+    Ok(Negotiated::Json(Json(
+        pca.snapshot()
+            .channels
+            .into_iter()
+            .filter(|channel_config| channel_config.custom_limits.is_some())
+            .collect(),
+    )))
+}
+
+/// Reports how the runtime configuration differs from the configuration file
+/// on disk (see [Pca9685::diff_config]), i.e. what a restart would change and
+/// what needs persisting
+#[get("/config/diff")]
+fn get_config_diff(
+    config_file: &State<ConfigFile>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<Vec<ConfigDifference>> {
+    let config = Config::try_load_from_file(&config_file.path).map_err(|error| {
+        status::Custom(
+            Status::InternalServerError,
+            Json(ErrorResponse {
+                error: error.to_string(),
+            }),
+        )
+    })?;
+
+    match pca.diff_config(&config) {
+        Ok(differences) => Ok(Json(differences)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Per-Channel command statistics, for Prometheus (see [Pca9685::metrics])
+#[get("/metrics")]
+fn get_metrics(pca: &State<Arc<Pca9685>>) -> (ContentType, String) {
+    (ContentType::Plain, metrics::render(&pca.metrics()))
+}
+
+/// Feeds the command watchdog (on behalf of the `X-Client-Id`, if given)
+/// without changing any output
+#[post("/heartbeat")]
+fn post_heartbeat(client_id: ClientId, pca: &State<Arc<Pca9685>>) -> Json<HeartbeatResponse> {
+    pca.heartbeat(client_id.0.as_deref());
+
+    Json(HeartbeatResponse {
+        watchdog_timeout_ms: pca
+            .watchdog_timeout()
+            .map(|timeout| timeout.as_millis() as u64),
+    })
+}
+
+/// Applies the configured failsafe outputs for `seconds`, then restores the
+/// prior outputs, so that failsafe positions can be verified
+#[post("/failsafe/test?<seconds>")]
+fn post_failsafe_test(seconds: f64, pca: &State<Arc<Pca9685>>) -> HttpResult<Vec<ChannelConfig>> {
+    assert_device_available(pca)?;
+
+    let duration = match Duration::try_from_secs_f64(seconds) {
+        Ok(duration) => duration,
+        Err(_) => {
+            return Err(status::Custom(
+                Status::BadRequest,
+                Json(ErrorResponse {
+                    error: format!("Invalid duration: {} seconds.", seconds),
+                }),
+            ))
+        }
+    };
+
+    match pca.rehearse_failsafe(duration) {
+        Ok(configs) => Ok(Json(configs)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Selects the low or high dual rate of the selected channels (e.g.,
+/// `{"selector": "all", "rate": "low"}`)
+#[put("/rate", format = "application/json", data = "<selection>")]
+fn put_rate(
+    selection: Json<RateSelection>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<Vec<ChannelConfig>> {
+    assert_device_available(pca)?;
+
+    match pca.select_rate(&selection.selector, selection.rate) {
+        Ok(configs) => Ok(Json(configs)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[get("/teleop")]
+fn get_teleop(pca: &State<Arc<Pca9685>>) -> Json<TeleopResponse> {
+    Json(TeleopResponse {
+        active_profile: pca.teleop_profile(),
+        profiles: pca.teleop_profiles(),
+    })
+}
+
+/// Activates a teleop profile by name (e.g., `"fine"`)
+#[put("/teleop/profile", format = "application/json", data = "<name>")]
+fn put_teleop_profile(name: Json<String>, pca: &State<Arc<Pca9685>>) -> HttpResult<TeleopResponse> {
+    assert_device_available(pca)?;
+
+    match pca.select_teleop_profile(&name) {
+        Ok(()) => Ok(get_teleop(pca)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Applies a gamepad snapshot (e.g., `{"axes": [0.5, -0.2], "buttons":
+/// [false, true]}`) per the active teleop profile
+#[put("/teleop/gamepad", format = "application/json", data = "<state>")]
+fn put_teleop_gamepad(
+    state: Json<GamepadState>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<Vec<ChannelConfig>> {
+    assert_device_available(pca)?;
+
+    match pca.teleop(&state) {
+        Ok(configs) => Ok(Json(configs)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[get("/mixer")]
+fn get_mixers(pca: &State<Arc<Pca9685>>) -> Json<Vec<MixerConfig>> {
+    Json(pca.mixers())
+}
+
+/// Replaces the configuration of every mixer, applying it immediately (see
+/// [Pca9685::configure_mixers])
+#[put("/mixer", format = "application/json", data = "<mixers>")]
+fn put_mixers(
+    mixers: Json<Vec<MixerConfig>>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<Vec<MixerConfig>> {
+    assert_device_available(pca)?;
+
+    match pca.configure_mixers(&mixers) {
+        Ok(mixers) => Ok(Json(mixers)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[get("/mixer/<name>")]
+fn get_mixer(name: &str, pca: &State<Arc<Pca9685>>) -> HttpResult<HashMap<String, f64>> {
+    match pca.mixer_inputs(name) {
+        Ok(inputs) => Ok(Json(inputs)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Updates inputs of a configured mixer (e.g., `{"pitch": 0.5}`), positioning
+/// its output channels accordingly
+#[put("/mixer/<name>", format = "application/json", data = "<inputs>")]
+fn put_mixer(
+    name: &str,
+    inputs: Json<HashMap<String, f64>>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<Vec<ChannelConfig>> {
+    assert_device_available(pca)?;
+
+    match pca.set_mixer_inputs(name, &inputs) {
+        Ok(configs) => Ok(Json(configs)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[get("/virtual-channel")]
+fn get_virtual_channels(pca: &State<Arc<Pca9685>>) -> Json<HashMap<String, f64>> {
+    Json(pca.virtual_channels())
+}
+
+/// Updates virtual channels (e.g., `{"convergence": 0.5}`), positioning the
+/// channels derived from them accordingly
+#[put("/virtual-channel", format = "application/json", data = "<values>")]
+fn put_virtual_channels(
+    values: Json<HashMap<String, f64>>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<Vec<ChannelConfig>> {
+    assert_device_available(pca)?;
+
+    match pca.set_virtual_channels(&values) {
+        Ok(configs) => Ok(Json(configs)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Where sequences are saved, if anywhere (see `Config.storage`)
+struct SequenceStorage(Option<Box<dyn Storage>>);
+
+impl SequenceStorage {
+    fn new(config: &Config, config_file_path: &str) -> SequenceStorage {
+        SequenceStorage(
+            config
+                .open_storage(config_file_path)
+                .unwrap_or_else(|error| panic!("{}", error)),
+        )
+    }
+
+    /// Configures each saved sequence, skipping (with a warning) any that
+    /// are invalid
+    fn load(&self, pca: &Pca9685) {
+        let Some(storage) = &self.0 else {
+            return;
+        };
+        let mut pending = match storage.sequences() {
+            Ok(sequences) => sequences,
+            Err(error) => {
+                log::warn!(target: "server", "Unable to load sequences: {}", error);
+                return;
+            }
+        };
+
+        // Sequences may include one another, so retry those that fail until
+        // no more can be configured
+        loop {
+            let count = pending.len();
+            pending.retain(|sequence| pca.configure_sequence(sequence).is_err());
+            if pending.is_empty() || pending.len() == count {
+                break;
+            }
+        }
+        for sequence in pending {
+            if let Err(error) = pca.configure_sequence(&sequence) {
+                log::warn!(target: "server", "Skipping sequence {}: {}", sequence.name, error);
+            }
+        }
+    }
+
+    fn save(&self, sequence: &SequenceConfig) -> Result<(), HttpError> {
+        match &self.0 {
+            Some(storage) => storage
+                .save_sequence(sequence)
+                .map_err(|error| extract_error(&error)),
+            None => Ok(()),
+        }
+    }
+
+    fn remove(&self, name: &str) -> Result<(), HttpError> {
+        match &self.0 {
+            Some(storage) => storage
+                .remove_sequence(name)
+                .map_err(|error| extract_error(&error)),
+            None => Ok(()),
+        }
+    }
+
+    /// Replaces the saved sequences `replaced` with `sequences`, putting
+    /// `replaced` back if any cannot be saved
+    fn replace(
+        &self,
+        replaced: &[SequenceConfig],
+        sequences: &[SequenceConfig],
+    ) -> Result<(), HttpError> {
+        let result = replaced
+            .iter()
+            .try_for_each(|sequence| self.remove(&sequence.name))
+            .and_then(|_| {
+                sequences
+                    .iter()
+                    .try_for_each(|sequence| self.save(sequence))
+            });
+        if result.is_err() {
+            for sequence in sequences {
+                let _ = self.remove(&sequence.name);
+            }
+            for sequence in replaced {
+                let _ = self.save(sequence);
+            }
+        }
+        result
+    }
+}
+
+/// Fails with 400 Bad Request if the body names a different sequence than
+/// the resource
+fn extract_sequence(name: &str, sequence: Json<SequenceConfig>) -> HttpResult<SequenceConfig> {
+    if sequence.name != name {
+        return Err(status::Custom(
+            Status::BadRequest,
+            Json(ErrorResponse {
+                error: format!(
+                    "Request body sequence ({}) doesn't match resource sequence ({}).",
+                    sequence.name, name
+                ),
+            }),
+        ));
+    }
+
+    Ok(sequence)
+}
+
+#[get("/sequence")]
+fn get_sequences(pca: &State<Arc<Pca9685>>) -> Json<Vec<SequenceConfig>> {
+    Json(pca.sequences())
+}
+
+#[get("/sequence/<name>")]
+fn get_sequence(name: &str, pca: &State<Arc<Pca9685>>) -> HttpResult<SequenceConfig> {
+    match pca.sequence(name) {
+        Ok(sequence) => Ok(Json(sequence)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Returns the sequence's keyframes as it would be played, i.e. with its
+/// steps resolved (see [Pca9685::resolve_sequence])
+#[get("/sequence/<name>/keyframes")]
+fn get_sequence_keyframes(name: &str, pca: &State<Arc<Pca9685>>) -> HttpResult<Vec<Keyframe>> {
+    match pca.resolve_sequence(name) {
+        Ok(keyframes) => Ok(Json(keyframes)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Adds a sequence, validated against the configured Channels (see
+/// [Pca9685::validate_sequence]), saving it if `storage` is configured
+#[post("/sequence/<name>", format = "application/json", data = "<sequence>")]
+fn post_sequence(
+    name: &str,
+    sequence: Json<SequenceConfig>,
+    sequence_storage: &State<SequenceStorage>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<SequenceConfig> {
+    let sequence = extract_sequence(name, sequence)?;
+    if pca.sequence(name).is_ok() {
+        return Err(status::Custom(
+            Status::Conflict,
+            Json(ErrorResponse {
+                error: format!("Sequence {} already exists.", name),
+            }),
+        ));
+    }
+
+    pca.validate_sequence(&sequence)
+        .map_err(|error| extract_error(&error))?;
+    sequence_storage.save(&sequence)?;
+    match pca.configure_sequence(&sequence) {
+        Ok(sequence) => Ok(Json(sequence)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Replaces an existing sequence (see `POST /sequence/<name>`)
+#[put("/sequence/<name>", format = "application/json", data = "<sequence>")]
+fn put_sequence(
+    name: &str,
+    sequence: Json<SequenceConfig>,
+    sequence_storage: &State<SequenceStorage>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<SequenceConfig> {
+    let sequence = extract_sequence(name, sequence)?;
+    pca.sequence(name).map_err(|error| extract_error(&error))?;
+
+    pca.validate_sequence(&sequence)
+        .map_err(|error| extract_error(&error))?;
+    sequence_storage.save(&sequence)?;
+    match pca.configure_sequence(&sequence) {
+        Ok(sequence) => Ok(Json(sequence)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[delete("/sequence/<name>")]
+fn delete_sequence(
+    name: &str,
+    sequence_storage: &State<SequenceStorage>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<SequenceConfig> {
+    pca.sequence(name).map_err(|error| extract_error(&error))?;
+
+    sequence_storage.remove(name)?;
+    match pca.remove_sequence(name) {
+        Ok(sequence) => Ok(Json(sequence)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Plays the sequence; given `sync_timeout_ms`, slaved to an external clock
+/// which reports its time via `POST /sequence/<name>/seek` (see
+/// [PlaybackClock::External]).  To reuse a sequence authored on another rig,
+/// `rate` scales its pace and `remap` moves channels, e.g. `0:4,1:5` plays
+/// channel 0's keyframes on channel 4 and 1's on 5 (see [Replay]).
+#[post("/sequence/<name>/play?<sync_timeout_ms>&<rate>&<remap>")]
+fn post_sequence_play(
+    name: &str,
+    sync_timeout_ms: Option<u64>,
+    rate: Option<f64>,
+    remap: Option<&str>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<PlaybackStatus> {
+    assert_device_available(pca)?;
+
+    let clock = match sync_timeout_ms {
+        Some(timeout_ms) => PlaybackClock::External { timeout_ms },
+        None => PlaybackClock::Internal,
+    };
+    let replay = Replay {
+        channel_map: parse_channel_map(remap.unwrap_or_default())?,
+        rate: rate.unwrap_or_else(Replay::default_rate),
+    };
+    match pca.replay_sequence(name, clock, &replay) {
+        Ok(status) => Ok(Json(status)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// The channel configuration that `POST /sequence/<name>/validate?against=`
+/// checks against
+#[derive(Debug, PartialEq, FromFormField)]
+enum Against {
+    /// The running configuration
+    #[field(value = "current")]
+    Current,
+
+    /// A device configuration given as the request body (see `POST
+    /// /config/validate`)
+    #[field(value = "config")]
+    Config,
+}
+
+/// Returns the configuration to check against, if not the running one
+fn target_config(
+    against: Option<Against>,
+    content_type: Option<&ContentType>,
+    body: &str,
+) -> Result<Option<Config>, HttpError> {
+    match against {
+        Some(Against::Config) => parse_config(content_type, body)
+            .map(Some)
+            .map_err(|error| status::Custom(Status::BadRequest, Json(ErrorResponse { error }))),
+        _ => Ok(None),
+    }
+}
+
+/// Checks every keyframe of the sequence against the running channel
+/// configuration or, with `against=config`, the one in the body (see
+/// [Pca9685::check_sequence]), responding 422 Unprocessable Entity with the
+/// violations, if any
+#[post("/sequence/<name>/validate?<against>", data = "<body>")]
+fn post_sequence_validate(
+    name: &str,
+    against: Option<Against>,
+    content_type: Option<&ContentType>,
+    body: String,
+    pca: &State<Arc<Pca9685>>,
+) -> Result<status::Custom<Json<ConfigValidation>>, HttpError> {
+    let config = target_config(against, content_type, &body)?;
+
+    match pca.check_sequence(name, config.as_ref()) {
+        Ok(validation) => Ok(validation_response(validation)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Checks every output of the preset, as `POST /sequence/<name>/validate`
+/// does a sequence's keyframes
+#[post("/preset/<name>/validate?<against>", data = "<body>")]
+fn post_preset_validate(
+    name: &str,
+    against: Option<Against>,
+    content_type: Option<&ContentType>,
+    body: String,
+    pca: &State<Arc<Pca9685>>,
+) -> Result<status::Custom<Json<ConfigValidation>>, HttpError> {
+    let config = target_config(against, content_type, &body)?;
+
+    match pca.check_preset(name, config.as_ref()) {
+        Ok(validation) => Ok(validation_response(validation)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Parses a comma-separated list of `<from>:<to>` channel pairs
+fn parse_channel_map(remap: &str) -> Result<BTreeMap<u8, u8>, HttpError> {
+    remap
+        .split(',')
+        .filter(|pair| !pair.trim().is_empty())
+        .map(|pair| {
+            pair.split_once(':')
+                .and_then(|(from, to)| Some((from.trim().parse().ok()?, to.trim().parse().ok()?)))
+                .ok_or_else(|| {
+                    status::Custom(
+                        Status::BadRequest,
+                        Json(ErrorResponse {
+                            error: format!(
+                                "Invalid channel mapping '{}' (expected <from>:<to>).",
+                                pair
+                            ),
+                        }),
+                    )
+                })
+        })
+        .collect()
+}
+
+#[post("/sequence/<name>/seek?<t_ms>")]
+fn post_sequence_seek(
+    name: &str,
+    t_ms: u64,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<PlaybackStatus> {
+    assert_device_available(pca)?;
+
+    match pca.seek_sequence(name, t_ms) {
+        Ok(status) => Ok(Json(status)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Sets the rate (0.25 to 4.0) at which the playing sequence advances
+#[post("/sequence/<name>/rate?<rate>")]
+fn post_sequence_rate(
+    name: &str,
+    rate: f64,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<PlaybackStatus> {
+    assert_device_available(pca)?;
+
+    match pca.set_playback_rate(name, rate) {
+        Ok(status) => Ok(Json(status)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Repeats a region of the playing sequence (see [Pca9685::set_loop_region])
+#[put(
+    "/sequence/<name>/loop",
+    format = "application/json",
+    data = "<region>"
+)]
+fn put_sequence_loop(
+    name: &str,
+    region: Json<LoopRegion>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<PlaybackStatus> {
+    assert_device_available(pca)?;
+
+    match pca.set_loop_region(name, Some(region.into_inner())) {
+        Ok(status) => Ok(Json(status)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[delete("/sequence/<name>/loop")]
+fn delete_sequence_loop(name: &str, pca: &State<Arc<Pca9685>>) -> HttpResult<PlaybackStatus> {
+    assert_device_available(pca)?;
+
+    match pca.set_loop_region(name, None) {
+        Ok(status) => Ok(Json(status)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Returns the sequence being played (404 if none)
+#[get("/playback")]
+fn get_playback(pca: &State<Arc<Pca9685>>) -> Option<Json<PlaybackStatus>> {
+    pca.playback().map(Json)
+}
+
+#[post("/playback/stop")]
+fn post_playback_stop(pca: &State<Arc<Pca9685>>) -> Option<Json<PlaybackStatus>> {
+    pca.stop_sequence().map(Json)
+}
+
+/// Starts recording manual commands as a macro, to be saved as the sequence
+/// (see [Pca9685::start_recording])
+#[post("/sequence/<name>/record")]
+fn post_sequence_record(name: &str, pca: &State<Arc<Pca9685>>) -> HttpResult<RecordingStatus> {
+    match pca.start_recording(name) {
+        Ok(status) => Ok(Json(status)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[get("/recording")]
+fn get_recording(pca: &State<Arc<Pca9685>>) -> Option<Json<RecordingStatus>> {
+    pca.recording().map(Json)
+}
+
+/// Stops recording the macro, adding the recorded sequence (see
+/// [Pca9685::stop_recording]) and saving it if `storage` is configured
+#[post("/recording/stop")]
+fn post_recording_stop(
+    sequence_storage: &State<SequenceStorage>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<SequenceConfig> {
+    let sequence = pca
+        .stop_recording()
+        .map_err(|error| extract_error(&error))?;
+    sequence_storage.save(&sequence)?;
+
+    Ok(Json(sequence))
+}
+
+#[get("/presets")]
+fn get_presets(pca: &State<Arc<Pca9685>>) -> Json<Vec<PresetConfig>> {
+    Json(pca.presets())
+}
+
+/// Applies the preset, cross-fading each of its Channels from its current
+/// output over `fade_ms` (by default, immediately; see
+/// [Pca9685::apply_preset])
+#[post("/preset/<name>/apply?<fade_ms>")]
+fn post_preset_apply(
+    name: &str,
+    fade_ms: Option<u64>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<PlaybackStatus> {
+    assert_device_available(pca)?;
+
+    match pca.apply_preset(name, Duration::from_millis(fade_ms.unwrap_or(0))) {
+        Ok(status) => Ok(Json(status)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[get("/scenes")]
+fn get_scenes(pca: &State<Arc<Pca9685>>) -> Json<Vec<SceneLayer>> {
+    Json(pca.scenes())
+}
+
+/// Layers the preset as a scene at `priority` (by default, 0), fading its
+/// Channels over `fade_ms` (by default, immediately; see
+/// [Pca9685::apply_scene])
+#[post("/scene/<name>/apply?<priority>&<fade_ms>")]
+fn post_scene_apply(
+    name: &str,
+    priority: Option<i32>,
+    fade_ms: Option<u64>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<Vec<SceneLayer>> {
+    assert_device_available(pca)?;
+
+    let fade = Duration::from_millis(fade_ms.unwrap_or(0));
+    match pca.apply_scene(name, priority.unwrap_or(0), fade) {
+        Ok(scenes) => Ok(Json(scenes)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Removes the scene, fading its Channels over `fade_ms` (by default,
+/// immediately; see [Pca9685::release_scene])
+#[post("/scene/<name>/release?<fade_ms>")]
+fn post_scene_release(
+    name: &str,
+    fade_ms: Option<u64>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<Vec<SceneLayer>> {
+    assert_device_available(pca)?;
+
+    match pca.release_scene(name, Duration::from_millis(fade_ms.unwrap_or(0))) {
+        Ok(scenes) => Ok(Json(scenes)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[get("/gaits")]
+fn get_gaits(pca: &State<Arc<Pca9685>>) -> Json<Vec<GaitConfig>> {
+    Json(pca.gaits())
+}
+
+/// Starts walking the gait at `speed` (0.25 to 4.0; by default, 1.0) times
+/// its configured pace (see [Pca9685::start_gait])
+#[post("/gait/<name>/start?<speed>")]
+fn post_gait_start(
+    name: &str,
+    speed: Option<f64>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<PlaybackStatus> {
+    assert_device_available(pca)?;
+
+    match pca.start_gait(name, speed.unwrap_or(1.0)) {
+        Ok(status) => Ok(Json(status)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[post("/gait/<name>/speed?<speed>")]
+fn post_gait_speed(
+    name: &str,
+    speed: f64,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<PlaybackStatus> {
+    assert_device_available(pca)?;
+
+    match pca.set_gait_speed(name, speed) {
+        Ok(status) => Ok(Json(status)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[post("/gait/<name>/stop")]
+fn post_gait_stop(name: &str, pca: &State<Arc<Pca9685>>) -> HttpResult<PlaybackStatus> {
+    assert_device_available(pca)?;
+
+    match pca.stop_gait(name) {
+        Ok(status) => Ok(Json(status)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[get("/linear-axes")]
+fn get_linear_axes(pca: &State<Arc<Pca9685>>) -> Json<Vec<LinearAxisStatus>> {
+    Json(pca.linear_axes())
+}
+
+#[get("/linear-axis/<name>")]
+fn get_linear_axis(name: &str, pca: &State<Arc<Pca9685>>) -> HttpResult<LinearAxisStatus> {
+    match pca.linear_axis(name) {
+        Ok(status) => Ok(Json(status)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Homes the linear axis onto its home switch (see
+/// [Pca9685::home_linear_axis])
+#[post("/linear-axis/<name>/home?<speed_mm_s>")]
+fn post_linear_axis_home(
+    name: &str,
+    speed_mm_s: Option<f64>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<LinearAxisStatus> {
+    assert_device_available(pca)?;
+
+    match pca.home_linear_axis(name, speed_mm_s) {
+        Ok(status) => Ok(Json(status)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// Moves the (homed) linear axis to `position_mm`
+#[post("/linear-axis/<name>/move?<position_mm>&<speed_mm_s>")]
+fn post_linear_axis_move(
+    name: &str,
+    position_mm: f64,
+    speed_mm_s: Option<f64>,
+    pca: &State<Arc<Pca9685>>,
+) -> HttpResult<LinearAxisStatus> {
+    assert_device_available(pca)?;
+
+    match pca.move_linear_axis(name, position_mm, speed_mm_s) {
+        Ok(status) => Ok(Json(status)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[post("/linear-axis/<name>/stop")]
+fn post_linear_axis_stop(name: &str, pca: &State<Arc<Pca9685>>) -> HttpResult<LinearAxisStatus> {
+    match pca.stop_linear_axis(name) {
+        Ok(status) => Ok(Json(status)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+#[delete("/channel/<channel>")]
+fn delete_channel(channel: u8, pca: &State<Arc<Pca9685>>) -> HttpResult<ChannelConfig> {
+    assert_device_available(pca)?;
+    let channel = Channel::try_from(channel).unwrap();
+
+    // Assert channel is configured/exists
+    get_channel_config(channel, pca)?;
+
+    match pca.configure_channel(&ChannelConfig {
+        channel,
+        current_count: None,
+        custom_limits: None,
+        owner: None,
+        calibration: None,
+        inverted: false,
+        trim_counts: 0,
+        esc: None,
+        tags: Vec::new(),
+        reduced_hold: None,
+        thermal_limits: None,
+        dual_rate: None,
+        failsafe: None,
+        display_unit: None,
+        display_value: None,
+        name: None,
+        feedback: None,
+        pid: None,
+        input_shaping: None,
+        low_pass: None,
+    }) {
+        Ok(config) => Ok(Json(config)),
+        Err(error) => Err(extract_error(&error)),
+    }
+}
+
+/// How often to retry opening the device when it fails to initialize
+const DEVICE_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+fn rocket(
+    config: &Config,
+    config_file_path: &str,
+    mock: bool,
+    log_lines: Arc<LogLines>,
+) -> Rocket<Build> {
+    if !config.devices.is_empty() {
+        log::warn!(
+            target: "server",
+            "Serving {} {:#02x} only; {} further device(s) are unused.",
+            config.device,
+            config.address,
+            config.devices.len()
+        );
+    }
+    let pca9685 = if mock {
+        log::warn!(target: "server", "Using mock PCA9685 driver.");
+        Pca9685::null(config)
+    } else {
+        Pca9685::new_or_degraded(config, DEVICE_RETRY_INTERVAL)
+    };
+    let sequence_storage = SequenceStorage::new(config, config_file_path);
+    sequence_storage.load(&pca9685);
+
+    let rocket = rocket::build()
+        .mount(
+            "/",
+            routes![
+                get_status,
+                get_device,
+                put_device_disabled_output,
+                get_network_policy_denied,
+                post_channel,
+                put_channel,
+                put_channels,
+                put_tagged_channels,
+                put_channel_calibration,
+                delete_channel_calibration,
+                patch_channel,
+                post_channel_transaction,
+                post_channel_clear_fault,
+                post_channel_maintenance,
+                delete_channel_maintenance,
+                post_channel_arm,
+                delete_channel_arm,
+                get_channel_jitter,
+                put_channel_jitter,
+                delete_channel_jitter,
+                put_channel_move,
+                delete_channel_move,
+                get_maintenance,
+                post_relax,
+                post_config_validate,
+                get_config,
+                get_config_diff,
+                get_channels,
+                get_metrics,
+                post_heartbeat,
+                post_pause,
+                post_resume,
+                post_emergency_stop,
+                get_snapshot,
+                post_snapshot,
+                get_debug_state,
+                get_backup,
+                post_restore,
+                post_failsafe_test,
+                put_rate,
+                get_teleop,
+                put_teleop_profile,
+                put_teleop_gamepad,
+                get_mixers,
+                put_mixers,
+                get_mixer,
+                put_mixer,
+                get_virtual_channels,
+                put_virtual_channels,
+                get_sequences,
+                get_sequence,
+                get_sequence_keyframes,
+                post_sequence,
+                put_sequence,
+                delete_sequence,
+                post_sequence_play,
+                post_sequence_validate,
+                post_sequence_seek,
+                post_sequence_rate,
+                put_sequence_loop,
+                delete_sequence_loop,
+                get_playback,
+                post_playback_stop,
+                post_sequence_record,
+                get_recording,
+                post_recording_stop,
+                get_presets,
+                post_preset_apply,
+                post_preset_validate,
+                get_scenes,
+                post_scene_apply,
+                post_scene_release,
+                get_gaits,
+                post_gait_start,
+                post_gait_speed,
+                post_gait_stop,
+                get_linear_axes,
+                get_linear_axis,
+                post_linear_axis_home,
+                post_linear_axis_move,
+                post_linear_axis_stop,
+                get_channel,
+                get_named_channel,
+                put_named_channel_pct,
+                put_group_pct,
+                get_channel_history,
+                get_channel_history_csv,
+                get_channel_pid_stream,
+                post_channel_step_test,
+                get_history_export,
+                get_channel_log,
+                get_channel_stats,
+                delete_channel
+            ],
+        )
+        .manage(Arc::new(pca9685))
+        .manage(config.clone())
+        .manage(sequence_storage)
+        .manage(log_lines)
+        .manage(ProcessStart {
+            at: Instant::now(),
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis() as u64),
+        })
+        .manage(ConfigFile {
+            path: config_file_path.to_string(),
+            autopersist: config.autopersist,
+        })
+        .attach(NetworkPolicy);
+
+    mount_gimbal(rocket)
+}
+
+#[cfg(feature = "gimbal")]
+fn mount_gimbal(rocket: Rocket<Build>) -> Rocket<Build> {
+    rocket.mount("/", routes![get_gimbal, post_gimbal, delete_gimbal])
+}
+
+#[cfg(not(feature = "gimbal"))]
+fn mount_gimbal(rocket: Rocket<Build>) -> Rocket<Build> {
+    rocket
+}
+
+/// The device assumed by `--bootstrap` when no PCA9685 answers the scan
+const BOOTSTRAP_DEVICE: &str = "/dev/i2c-1";
+const BOOTSTRAP_ADDRESS: u8 = 0x40;
+
+/// Writes a starter configuration file (see [Config::write_starter_file])
+/// for the first PCA9685 found on the I2C buses, unless the file exists
+fn bootstrap(config_file_path: &str) {
+    if Path::new(config_file_path).exists() {
+        log::info!(target: "server", "{} exists; not bootstrapping.", config_file_path);
+        return;
+    }
+
+    let found = utils::scan_i2c();
+    let (device, address) = match found.first() {
+        Some((device, address)) => {
+            if found.len() > 1 {
+                log::warn!(target: "server", "Found {} devices {:?}; using the first.", found.len(), found);
+            }
+            (device.as_str(), *address)
+        }
+        None => {
+            log::warn!(target: "server", "No PCA9685 found; assuming {} {:#02x}.", BOOTSTRAP_DEVICE, BOOTSTRAP_ADDRESS);
+            (BOOTSTRAP_DEVICE, BOOTSTRAP_ADDRESS)
+        }
+    };
+
+    Config::write_starter_file(config_file_path, device, address)
+        .unwrap_or_else(|error| panic!("{}", error));
+    log::info!(target: "server", "Wrote starter configuration {}.", config_file_path);
+}
+
+/// Logs the state of the controller (see [Pca9685::debug_report]) each time
+/// the process receives SIGUSR1, e.g. `kill -USR1 $(pidof pca9685-service)`
+async fn report_on_sigusr1(pca: Arc<Pca9685>) {
+    let mut signals = match signal(SignalKind::user_defined1()) {
+        Ok(signals) => signals,
+        Err(error) => {
+            log::warn!(target: "server", "Unable to handle SIGUSR1: {}", error);
+            return;
+        }
+    };
+
+    while signals.recv().await.is_some() {
+        log::info!(target: "server", "State on SIGUSR1:\n{}", pca.debug_report());
+    }
+}
+
+#[rocket::main]
+#[allow(clippy::result_large_err)]
+async fn main() -> Result<(), rocket::Error> {
+    let log_lines = Arc::new(LogLines::default());
+    log_lines::init(log_lines.clone());
+
+    let args = Args::parse();
+    if args.bootstrap {
+        bootstrap(&args.config_file_path);
+    }
+
+    let config: Config = Config::load_from_file(&args.config_file_path);
+
+    // Using conditional compilation..if the architecture is not ARM, use a mock PCA9685
+    let force_mock = cfg!(not(any(target_arch = "arm", target_arch = "aarch64")));
+
+    let rocket = rocket(&config, &args.config_file_path, force_mock, log_lines);
+    rocket::tokio::spawn(report_on_sigusr1(
+        rocket.state::<Arc<Pca9685>>().unwrap().clone(),
+    ));
+    let _rocket = rocket.launch().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod pca9685_server_test {
+    use crate::{
+        Against, ChannelCommands, ChannelTransaction, DeviceResponse, HeartbeatResponse, LogLine,
+        LogLines, PauseResponse, TeleopResponse, TransactionUnit, WaitFor,
+    };
+
+    use super::rocket;
+    use flate2::read::GzDecoder;
+    use pca9685::{
+        AxisMapping, Backup, CalibrationPoint, ChannelCalibration, ChannelCommand, ChannelConfig,
+        ChannelGroup, ChannelHistoryEntry, ChannelHistoryRecord, ChannelLimits, ChannelSelector,
+        ChannelStats, CommandType, Config, ConfigDifference, ConfigValidation, DebugReport,
+        DisabledOutput, DualRate, EscConfig, FailsafeOutput, FeedbackConfig, FeedbackSource,
+        FindingSeverity, GaitConfig, GaitLeg, GaitPattern, GpioPin, HomeSwitch, JitterConfig,
+        Keyframe, LinearAxisConfig, LinearAxisStatus, LoopRegion, MixerConfig, MixerOutput,
+        PauseMode, PercentBasis, PidGains, PidSample, PlaybackClock, PlaybackStatus, PresetConfig,
+        PresetOutput, Rate, RecordingStatus, SceneConfig, SceneLayer, SequenceConfig, Snapshot,
+        StepTest, TeleopConfig, TeleopProfile, WatchdogConfig, PCA_PWM_RESOLUTION,
+    };
+    use pwm_pca9685::Channel;
+    use rocket::error::ErrorKind;
+    use rocket::http::{ContentType, Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::serde::json;
+    use rocket::{Build, Rocket};
+    use std::collections::{BTreeMap, HashMap};
+    use std::io::Read;
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    const TEST_CHANNEL_RAW_VALUE: u8 = 0;
+    const TEST_CONFIG_FILE_PATH: &str = "/nonexistent/pca9685.yaml";
+
+    fn create_test_config() -> ChannelConfig {
+        ChannelConfig {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            current_count: None,
+            custom_limits: Some(ChannelLimits::from_count_limits(1000, 2000)),
+            owner: None,
+            calibration: None,
+            inverted: false,
+            trim_counts: 0,
+            esc: None,
+            tags: Vec::new(),
+            reduced_hold: None,
+            thermal_limits: None,
+            dual_rate: None,
+            failsafe: None,
+            display_unit: None,
+            display_value: None,
+            name: None,
+            feedback: None,
+            pid: None,
+            input_shaping: None,
+            low_pass: None,
+        }
+    }
+
+    fn create_test_device_config() -> Config {
+        Config {
+            device: "/dev/foo".to_owned(),
+            address: 0x40,
+            output_frequency_hz: 200,
+            prescale: None,
+            open_drain: false,
+            disabled_output: DisabledOutput::Low,
+            stagger_on_counts: false,
+            external_clock_hz: None,
+            subaddresses: Vec::new(),
+            all_call_address: Config::default_all_call_address(),
+            channels: Default::default(),
+            devices: Vec::new(),
+            groups: vec![ChannelGroup {
+                name: "legs".to_owned(),
+                channels: vec![0, 1],
+            }],
+            mixers: vec![MixerConfig {
+                name: "elevons".to_owned(),
+                inputs: vec!["pitch".to_owned(), "roll".to_owned()],
+                outputs: vec![
+                    MixerOutput {
+                        channel: 2,
+                        weights: vec![0.5, 0.5],
+                        offset: 0.0,
+                    },
+                    MixerOutput {
+                        channel: 3,
+                        weights: vec![0.5, -0.5],
+                        offset: 0.0,
+                    },
+                ],
+                expo: Default::default(),
+            }],
+            teleop: TeleopConfig {
+                profiles: vec![TeleopProfile {
+                    name: "normal".to_owned(),
+                    axes: vec![AxisMapping {
+                        axis: 0,
+                        mixer: "elevons".to_owned(),
+                        input: "pitch".to_owned(),
+                        expo: 0.0,
+                        rate: 1.0,
+                        reversed: false,
+                    }],
+                    select_buttons: vec![],
+                    rate_button: None,
+                }],
+                default_profile: None,
+            },
+            watchdog: Some(WatchdogConfig {
+                timeout_ms: 60 * 1000,
+                channels: Vec::new(),
+            }),
+            circuit_breaker: None,
+            idle: None,
+            startup: None,
+            gimbal: None,
+            presets: Vec::new(),
+            scenes: Default::default(),
+            gaits: Vec::new(),
+            linear_axes: Vec::new(),
+            rules: Vec::new(),
+            virtual_channels: vec!["pan".to_owned(), "convergence".to_owned()],
+            derived_channels: serde_yaml::from_str(
+                "
+                - { channel: 4, value: !sum [pan, convergence] }
+                - { channel: 5, value: !difference [pan, convergence] }
+                ",
+            )
+            .unwrap(),
+            gpio: None,
+            allow_jitter: false,
+            strict: false,
+            include: Vec::new(),
+            autopersist: false,
+            sequences_dir: None,
+            storage: None,
+            interpolated: BTreeMap::new(),
+        }
+    }
+
+    fn create_mock() -> Rocket<Build> {
+        rocket(
+            &create_test_device_config(),
+            TEST_CONFIG_FILE_PATH,
+            true,
+            Arc::new(LogLines::default()),
+        )
+    }
+
+    #[test]
+    fn get_status() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let response = client.get(uri!(super::get_status)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let status = response.into_json::<json::Value>().unwrap();
+        assert!(status["started_at_ms"].as_u64().unwrap() > 0);
+        assert_eq!(status["uptime_seconds"], 0);
+        assert_eq!(status["reinit_count"], 0);
+        assert_eq!(status["idling"], false);
+        assert!(status.get("last_driver_error").is_none());
+    }
+
+    #[test]
+    fn get_device() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let response = client.get(uri!(super::get_device)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let device = response.into_json::<DeviceResponse>().unwrap();
+        assert_eq!(device.output_frequency_hz, 200);
+        assert_eq!(device.prescale, 30);
+        assert!((device.actual_output_frequency_hz - 196.9).abs() < 0.1);
+        assert_eq!(device.output_type, "TotemPole");
+        assert_eq!(device.disabled_output, DisabledOutput::Low);
+    }
+
+    #[test]
+    fn put_device_disabled_output() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let response = client
+            .put(uri!(super::put_device_disabled_output))
+            .header(ContentType::JSON)
+            .body("\"high_impedance\"")
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let device = response.into_json::<DeviceResponse>().unwrap();
+        assert_eq!(device.disabled_output, DisabledOutput::HighImpedance);
+
+        let response = client
+            .put(uri!(super::put_device_disabled_output))
+            .header(ContentType::JSON)
+            .body("\"hold\"")
+            .dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+
+    #[test]
+    fn degraded() {
+        // "/dev/foo" cannot be opened, so the (non-mock) device fails to
+        // initialize
+        let client = Client::tracked(rocket(
+            &create_test_device_config(),
+            TEST_CONFIG_FILE_PATH,
+            false,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+
+        let status_response = client.get(uri!(super::get_status)).dispatch();
+        assert_eq!(status_response.status(), Status::Ok);
+        let status = status_response.into_json::<json::Value>().unwrap();
+        assert_eq!(status["status"], "DEGRADED");
+        assert!(status["error"].as_str().unwrap().contains("/dev/foo"));
+
+        let get_response = client
+            .get(uri!(super::get_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                wait_for = _,
+                timeout = _
+            )))
+            .dispatch();
+        assert_eq!(get_response.status(), Status::NotFound);
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&create_test_config()).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::ServiceUnavailable);
+
+        // Nor is anything else that changes state
+        let responses = [
+            client
+                .put(uri!(super::put_channel_jitter(
+                    channel = TEST_CHANNEL_RAW_VALUE
+                )))
+                .header(ContentType::JSON)
+                .body(r#"{"amplitude_counts": 10, "bandwidth_hz": 10.0}"#)
+                .dispatch(),
+            client
+                .delete(uri!(super::delete_channel_jitter(
+                    channel = TEST_CHANNEL_RAW_VALUE
+                )))
+                .dispatch(),
+            client
+                .put(uri!(super::put_rate))
+                .header(ContentType::JSON)
+                .body(r#"{"selector": "all", "rate": "low"}"#)
+                .dispatch(),
+            client
+                .put(uri!(super::put_teleop_profile))
+                .header(ContentType::JSON)
+                .body(r#""fine""#)
+                .dispatch(),
+            client
+                .post(uri!(super::post_sequence_seek(name = "wave", t_ms = 100)))
+                .dispatch(),
+            client
+                .post(uri!(super::post_sequence_rate(name = "wave", rate = 2.0)))
+                .dispatch(),
+            client
+                .put(uri!(super::put_sequence_loop(name = "wave")))
+                .header(ContentType::JSON)
+                .body(r#"{"start_ms": 0, "end_ms": 100}"#)
+                .dispatch(),
+            client
+                .delete(uri!(super::delete_sequence_loop(name = "wave")))
+                .dispatch(),
+            client
+                .post(uri!(super::post_gait_speed(name = "walk", speed = 2.0)))
+                .dispatch(),
+            client
+                .post(uri!(super::post_gait_stop(name = "walk")))
+                .dispatch(),
+        ];
+        for response in responses {
+            assert_eq!(response.status(), Status::ServiceUnavailable);
+        }
+        #[cfg(feature = "gimbal")]
+        for response in [
+            client.post(uri!(super::post_gimbal)).dispatch(),
+            client.delete(uri!(super::delete_gimbal)).dispatch(),
+        ] {
+            assert_eq!(response.status(), Status::ServiceUnavailable);
+        }
+    }
+
+    #[test]
+    fn configure_channel() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+
+        let response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response_config = response.into_json::<ChannelConfig>().unwrap();
+
+        assert_eq!(TEST_CHANNEL_RAW_VALUE, response_config.channel as u8);
+        assert_eq!(
+            config.custom_limits.unwrap(),
+            response_config.custom_limits.unwrap()
+        );
+    }
+
+    #[test]
+    fn configure_channel_conflict() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+
+        let initial_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(initial_response.status(), Status::Ok);
+
+        let duplicate_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(duplicate_response.status(), Status::Conflict);
+    }
+
+    #[test]
+    fn get_channel() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let get_response = client
+            .get(uri!(super::get_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                wait_for = _,
+                timeout = _
+            )))
+            .dispatch();
+        assert_eq!(get_response.status(), Status::Ok);
+
+        let response_config = get_response.into_json::<ChannelConfig>().unwrap();
+
+        assert_eq!(TEST_CHANNEL_RAW_VALUE, response_config.channel as u8);
+        assert_eq!(
+            config.custom_limits.unwrap(),
+            response_config.custom_limits.unwrap()
+        );
+    }
+
+    #[test]
+    fn get_channel_etag() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&create_test_config()).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let get = |if_none_match: Option<&str>| {
+            let mut request = client.get(uri!(super::get_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                wait_for = _,
+                timeout = _
+            )));
+            if let Some(if_none_match) = if_none_match {
+                request = request.header(Header::new("If-None-Match", if_none_match.to_string()));
+            }
+            request.dispatch()
+        };
+
+        let get_response = get(None);
+        assert_eq!(get_response.status(), Status::Ok);
+        let etag = get_response.headers().get_one("ETag").unwrap().to_string();
+
+        let get_response = get(Some(&etag));
+        assert_eq!(get_response.status(), Status::NotModified);
+        assert_eq!(get_response.headers().get_one("ETag"), Some(etag.as_str()));
+        assert_eq!(get_response.into_string(), None);
+
+        assert_eq!(
+            get(Some(&format!("\"other\", W/{}", etag))).status(),
+            Status::NotModified
+        );
+        assert_eq!(get(Some("\"other\"")).status(), Status::Ok);
+
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::PulseCount,
+            value: Some(1500.0),
+            basis: None,
+            client_id: None,
+        };
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+
+        let get_response = get(Some(&etag));
+        assert_eq!(get_response.status(), Status::Ok);
+        assert_ne!(get_response.headers().get_one("ETag"), Some(etag.as_str()));
+    }
+
+    #[test]
+    fn get_channel_wait_for_settled() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let get_response = client
+            .get(format!(
+                "/channel/{}?wait_for=settled&timeout=100",
+                TEST_CHANNEL_RAW_VALUE
+            ))
+            .dispatch();
+        assert_eq!(get_response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn get_channel_not_found() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+
+        let get_response = client
+            .get(uri!(super::get_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                wait_for = _,
+                timeout = _
+            )))
+            .dispatch();
+        assert_eq!(get_response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn put_channel_full_on() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::FullOn,
+            value: None,
+            basis: None,
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+
+        let response_config = put_response.into_json::<ChannelConfig>().unwrap();
+
+        assert_eq!(TEST_CHANNEL_RAW_VALUE, response_config.channel as u8);
+        assert_eq!(PCA_PWM_RESOLUTION, response_config.current_count.unwrap());
+    }
+
+    #[test]
+    fn put_channel_full_on_bad_request() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::FullOn,
+            value: Some(3.2),
+            basis: None,
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn put_channel_full_off() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::FullOff,
+            value: None,
+            basis: None,
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+
+        let response_config = put_response.into_json::<ChannelConfig>().unwrap();
+
+        assert_eq!(TEST_CHANNEL_RAW_VALUE, response_config.channel as u8);
+        assert!(response_config.current_count.is_none());
+    }
+
+    #[test]
+    fn put_channel_full_off_bad_request() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::FullOff,
+            value: Some(3.2),
+            basis: None,
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn put_channel_pulse_count() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::PulseCount,
+            value: Some(1500.0),
+            basis: None,
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+
+        let response_config = put_response.into_json::<ChannelConfig>().unwrap();
+
+        assert_eq!(TEST_CHANNEL_RAW_VALUE, response_config.channel as u8);
+        assert_eq!(1500, response_config.current_count.unwrap());
+    }
+
+    #[test]
+    fn put_channel_pulse_count_beyond_limits() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::PulseCount,
+            value: Some(3000.0),
+            basis: None,
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn put_channel_pulse_count_bad_request() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::PulseCount,
+            value: None,
+            basis: None,
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn put_channel_pw_ms() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::PulseWidth,
+            value: Some(1.8606),
+            basis: None,
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+
+        let response_config = put_response.into_json::<ChannelConfig>().unwrap();
+
+        assert_eq!(TEST_CHANNEL_RAW_VALUE, response_config.channel as u8);
+        assert_eq!(1500, response_config.current_count.unwrap());
+    }
+
+    #[test]
+    fn put_channel_pw_ms_bad_request() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::PulseWidth,
+            value: None,
+            basis: None,
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn put_channel_pct() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::Percent,
+            value: Some(0.5),
+            basis: None,
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+
+        let response_config = put_response.into_json::<ChannelConfig>().unwrap();
+
+        assert_eq!(TEST_CHANNEL_RAW_VALUE, response_config.channel as u8);
+        assert_eq!(1500, response_config.current_count.unwrap());
+    }
+
+    #[test]
+    fn put_channel_pct_full_range() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::Percent,
+            value: Some(0.25),
+            basis: Some(PercentBasis::FullRange),
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+
+        let response_config = put_response.into_json::<ChannelConfig>().unwrap();
+
+        assert_eq!(TEST_CHANNEL_RAW_VALUE, response_config.channel as u8);
+        assert_eq!(1024, response_config.current_count.unwrap());
+    }
+
+    #[test]
+    fn put_channel_basis_bad_request() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::PulseCount,
+            value: Some(1500.0),
+            basis: Some(PercentBasis::FullRange),
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn put_channel_pct_bad_request() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::Percent,
+            value: None,
+            basis: None,
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn put_channel_adjust_count() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        for (command_type, value, expected_count) in [
+            (CommandType::PulseCount, 1500.0, 1500),
+            (CommandType::AdjustCount, -50.0, 1450),
+            (CommandType::AdjustPct, 0.1, 1550),
+            (CommandType::AdjustCount, 1000.0, 2000),
+        ] {
+            let command = ChannelCommand {
+                channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+                command_type,
+                value: Some(value),
+                basis: None,
+                client_id: None,
+            };
+
+            let put_response = client
+                .put(uri!(super::put_channel(
+                    channel = TEST_CHANNEL_RAW_VALUE,
+                    preview = _
+                )))
+                .header(ContentType::JSON)
+                .body(json::to_string(&command).unwrap())
+                .dispatch();
+            assert_eq!(put_response.status(), Status::Ok);
+
+            let response_config = put_response.into_json::<ChannelConfig>().unwrap();
+            assert_eq!(expected_count, response_config.current_count.unwrap());
+        }
+    }
+
+    #[test]
+    fn put_channel_adjust_count_inactive() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::AdjustCount,
+            value: Some(10.0),
+            basis: None,
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn put_channel_not_found() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::Percent,
+            value: None,
+            basis: None,
+            client_id: None,
+        };
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn post_channel_transaction() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::Percent,
+            value: Some(0.7),
+            basis: None,
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+
+        // "increase by 5% but not above 80%", applied repeatedly
+        let transaction = ChannelTransaction {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            unit: TransactionUnit::Percent,
+            delta: 0.05,
+            min: None,
+            max: Some(0.8),
+        };
+        for expected_count in [1750, 1800, 1800] {
+            let response = client
+                .post(uri!(super::post_channel_transaction(
+                    channel = TEST_CHANNEL_RAW_VALUE
+                )))
+                .header(ContentType::JSON)
+                .body(json::to_string(&transaction).unwrap())
+                .dispatch();
+            assert_eq!(response.status(), Status::Ok);
+
+            let response_config = response.into_json::<ChannelConfig>().unwrap();
+            assert_eq!(expected_count, response_config.current_count.unwrap());
+        }
+    }
+
+    #[test]
+    fn post_channel_transaction_inactive() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let transaction = ChannelTransaction {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            unit: TransactionUnit::PulseCount,
+            delta: -10.0,
+            min: Some(1200.0),
+            max: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let response = client
+            .post(uri!(super::post_channel_transaction(
+                channel = TEST_CHANNEL_RAW_VALUE
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&transaction).unwrap())
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn put_tagged_channels() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        for (raw_channel, tags) in [(0_u8, vec!["leg", "left"]), (1, vec!["leg", "right"])] {
+            let config = ChannelConfig {
+                channel: Channel::try_from(raw_channel).unwrap(),
+                tags: tags.into_iter().map(String::from).collect(),
+                ..create_test_config()
+            };
+            let post_response = client
+                .post(uri!(super::post_channel(persist = _)))
+                .header(ContentType::JSON)
+                .body(json::to_string(&config).unwrap())
+                .dispatch();
+            assert_eq!(post_response.status(), Status::Ok);
+        }
+
+        let put_tagged = |tag: &str, value: f64| {
+            client
+                .put(format!("/channels?tag={}", tag))
+                .header(ContentType::JSON)
+                .body(format!(
+                    r#"{{"command_type": "PulseCount", "value": {}}}"#,
+                    value
+                ))
+                .dispatch()
+        };
+        let put_response = put_tagged("leg", 1500.0);
+        assert_eq!(put_response.status(), Status::Ok);
+        let configs = put_response.into_json::<Vec<ChannelConfig>>().unwrap();
+        assert_eq!(configs.len(), 2);
+        assert!(configs
+            .iter()
+            .all(|config| config.current_count == Some(1500)));
+
+        let put_response = put_tagged("left", 1200.0);
+        let configs = put_response.into_json::<Vec<ChannelConfig>>().unwrap();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].tags, vec!["leg", "left"]);
+
+        // Beyond the limits of either, neither changes
+        assert_eq!(put_tagged("leg", 2500.0).status(), Status::BadRequest);
+        assert_eq!(put_tagged("arm", 1500.0).status(), Status::NotFound);
+
+        // Without a tag, the body is a batch of commands
+        let put_response = client
+            .put(uri!(super::put_channels()))
+            .header(ContentType::JSON)
+            .body(r#"{"commands": []}"#)
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn put_channels_atomic() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let pose = |atomic, value| ChannelCommands {
+            atomic,
+            commands: vec![
+                ChannelCommand {
+                    channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+                    command_type: CommandType::PulseCount,
+                    value: Some(1500.0),
+                    basis: None,
+                    client_id: None,
+                },
+                ChannelCommand {
+                    channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+                    command_type: CommandType::AdjustCount,
+                    value: Some(value),
+                    basis: None,
+                    client_id: None,
+                },
+            ],
+        };
+
+        // Channels may only be commanded once per atomic batch
+        let put_response = client
+            .put(uri!(super::put_channels()))
+            .header(ContentType::JSON)
+            .body(json::to_string(&pose(true, 100.0)).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::BadRequest);
+
+        let put_response = client
+            .put(uri!(super::put_channels()))
+            .header(ContentType::JSON)
+            .body(json::to_string(&pose(false, 100.0)).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+
+        let response_configs = put_response.into_json::<Vec<ChannelConfig>>().unwrap();
+        assert_eq!(2, response_configs.len());
+        assert_eq!(1600, response_configs[1].current_count.unwrap());
+    }
+
+    #[test]
+    fn put_channels_atomic_beyond_limits() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let mut other_config = create_test_config();
+        other_config.channel = Channel::C1;
+
+        for config in [&config, &other_config] {
+            let post_response = client
+                .post(uri!(super::post_channel(persist = _)))
+                .header(ContentType::JSON)
+                .body(json::to_string(config).unwrap())
+                .dispatch();
+            assert_eq!(post_response.status(), Status::Ok);
+        }
+
+        let batch = ChannelCommands {
+            atomic: true,
+            commands: vec![
+                ChannelCommand {
+                    channel: Channel::C0,
+                    command_type: CommandType::PulseCount,
+                    value: Some(1500.0),
+                    basis: None,
+                    client_id: None,
+                },
+                ChannelCommand {
+                    channel: Channel::C1,
+                    command_type: CommandType::PulseCount,
+                    value: Some(3000.0),
+                    basis: None,
+                    client_id: None,
+                },
+            ],
+        };
+
+        let put_response = client
+            .put(uri!(super::put_channels()))
+            .header(ContentType::JSON)
+            .body(json::to_string(&batch).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::BadRequest);
+
+        // Nothing was written to channel 0
+        let get_response = client
+            .get(uri!(super::get_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                wait_for = _,
+                timeout = _
+            )))
+            .dispatch();
+        let response_config = get_response.into_json::<ChannelConfig>().unwrap();
+        assert!(response_config.current_count.is_none());
+    }
+
+    #[test]
+    fn put_channel_client_attribution() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::PulseCount,
+            value: Some(1500.0),
+            basis: None,
+            client_id: None,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .header(Header::new("X-Client-Id", "operator-1"))
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+
+        let response_config = put_response.into_json::<ChannelConfig>().unwrap();
+        assert_eq!(Some(String::from("operator-1")), response_config.owner);
+
+        // Anonymous commands clear the owner
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        let response_config = put_response.into_json::<ChannelConfig>().unwrap();
+        assert_eq!(None, response_config.owner);
+
+        let get_response = client
+            .get(uri!(super::get_channel_history(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                resolution_ms = _
+            )))
+            .dispatch();
+        let history = get_response
+            .into_json::<Vec<ChannelHistoryEntry>>()
+            .unwrap();
+        assert_eq!(Some(String::from("operator-1")), history[0].client_id);
+        assert_eq!(None, history[1].client_id);
+    }
+
+    #[test]
+    fn post_channel_clear_fault() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let response = client
+            .post(uri!(super::post_channel_clear_fault(
+                channel = TEST_CHANNEL_RAW_VALUE
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(!response.into_json::<ChannelStats>().unwrap().faulted);
+    }
+
+    #[test]
+    fn channel_maintenance() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let response = client
+            .post(uri!(super::post_channel_maintenance(
+                channel = TEST_CHANNEL_RAW_VALUE
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.into_json::<ChannelConfig>().unwrap().current_count,
+            None
+        );
+
+        let response = client.get(uri!(super::get_maintenance)).dispatch();
+        assert_eq!(
+            response.into_json::<Vec<u8>>().unwrap(),
+            vec![TEST_CHANNEL_RAW_VALUE]
+        );
+
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::FullOn,
+            value: None,
+            basis: None,
+            client_id: None,
+        };
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Conflict);
+        assert!(put_response
+            .into_string()
+            .unwrap()
+            .contains("under maintenance"));
+
+        let response = client
+            .delete(uri!(super::delete_channel_maintenance(
+                channel = TEST_CHANNEL_RAW_VALUE
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let response = client.get(uri!(super::get_maintenance)).dispatch();
+        assert!(response.into_json::<Vec<u8>>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn channel_esc() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let mut config = create_test_config();
+        config.esc = Some(EscConfig { arming_ms: 50 });
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::Percent,
+            value: Some(0.5),
+            basis: None,
+            client_id: None,
+        };
+        let put_throttle = |client: &Client| {
+            client
+                .put(uri!(super::put_channel(
+                    channel = TEST_CHANNEL_RAW_VALUE,
+                    preview = _
+                )))
+                .header(ContentType::JSON)
+                .body(json::to_string(&command).unwrap())
+                .dispatch()
+                .status()
+        };
+        assert_eq!(put_throttle(&client), Status::Conflict);
+
+        let response = client
+            .post(uri!(super::post_channel_arm(
+                channel = TEST_CHANNEL_RAW_VALUE
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        assert_eq!(put_throttle(&client), Status::Ok);
+
+        let response = client
+            .delete(uri!(super::delete_channel_arm(
+                channel = TEST_CHANNEL_RAW_VALUE
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(put_throttle(&client), Status::Conflict);
+    }
+
+    #[test]
+    fn channel_jitter() {
+        let jitter = JitterConfig {
+            amplitude_counts: 20,
+            bandwidth_hz: 10.0,
+        };
+        let put_jitter = |client: &Client| {
+            client
+                .put(uri!(super::put_channel_jitter(
+                    channel = TEST_CHANNEL_RAW_VALUE
+                )))
+                .header(ContentType::JSON)
+                .body(json::to_string(&jitter).unwrap())
+                .dispatch()
+                .status()
+        };
+        let mut device_config = create_test_device_config();
+        device_config.channels = vec![create_test_config()];
+
+        // Only when explicitly allowed
+        let client = Client::tracked(rocket(
+            &device_config,
+            TEST_CONFIG_FILE_PATH,
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+        assert_eq!(put_jitter(&client), Status::BadRequest);
+        drop(client);
+
+        device_config.allow_jitter = true;
+        let client = Client::tracked(rocket(
+            &device_config,
+            TEST_CONFIG_FILE_PATH,
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+        assert_eq!(put_jitter(&client), Status::Ok);
+        let get_response = client
+            .get(uri!(super::get_channel_jitter(
+                channel = TEST_CHANNEL_RAW_VALUE
+            )))
+            .dispatch();
+        assert_eq!(get_response.into_json::<JitterConfig>().unwrap(), jitter);
+
+        let delete_response = client
+            .delete(uri!(super::delete_channel_jitter(
+                channel = TEST_CHANNEL_RAW_VALUE
+            )))
+            .dispatch();
+        assert_eq!(delete_response.status(), Status::Ok);
+        let get_response = client
+            .get(uri!(super::get_channel_jitter(
+                channel = TEST_CHANNEL_RAW_VALUE
+            )))
+            .dispatch();
+        assert_eq!(get_response.status(), Status::NotFound);
+    }
+
+    #[cfg(feature = "gimbal")]
+    #[test]
+    fn gimbal() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        assert_eq!(
+            client.get(uri!(super::get_gimbal)).dispatch().status(),
+            Status::NotFound
+        );
+        drop(client);
+
+        let axis = |channel| pca9685::GimbalAxis {
+            channel,
+            gains: pca9685::PidGains {
+                kp: 0.01,
+                ki: 0.0,
+                kd: 0.0,
+            },
+            center_pct: 0.5,
+            min_pct: 0.0,
+            max_pct: 1.0,
+            reversed: false,
+        };
+        let mut device_config = create_test_device_config();
+        device_config.gimbal = Some(pca9685::GimbalConfig {
+            imu: pca9685::ImuConfig {
+                device: "/dev/null".to_string(),
+                model: pca9685::ImuModel::Mpu6050,
+                address: None,
+            },
+            pitch: axis(0),
+            roll: axis(1),
+        });
+        let client = Client::tracked(rocket(
+            &device_config,
+            TEST_CONFIG_FILE_PATH,
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+
+        let status = |response: rocket::local::blocking::LocalResponse| {
+            response.into_json::<pca9685::GimbalStatus>().unwrap()
+        };
+        assert!(!status(client.get(uri!(super::get_gimbal)).dispatch()).enabled);
+        assert!(status(client.post(uri!(super::post_gimbal)).dispatch()).enabled);
+        assert!(status(client.get(uri!(super::get_gimbal)).dispatch()).enabled);
+        assert!(!status(client.delete(uri!(super::delete_gimbal)).dispatch()).enabled);
+    }
+
+    #[test]
+    fn gait() {
+        let mut device_config = create_test_device_config();
+        device_config.channels = vec![create_test_config()];
+        device_config.groups = vec![ChannelGroup {
+            name: "leg".to_string(),
+            channels: vec![TEST_CHANNEL_RAW_VALUE],
+        }];
+        device_config.gaits = vec![GaitConfig {
+            name: "walk".to_string(),
+            pattern: GaitPattern::Trot,
+            legs: vec![GaitLeg {
+                group: "leg".to_string(),
+                reversed: false,
+            }],
+            stride_pct: 0.2,
+            lift_pct: 0.2,
+            period_ms: 1000,
+        }];
+        let client = Client::tracked(rocket(
+            &device_config,
+            TEST_CONFIG_FILE_PATH,
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+
+        let gaits = client
+            .get(uri!(super::get_gaits))
+            .dispatch()
+            .into_json::<Vec<GaitConfig>>()
+            .unwrap();
+        assert_eq!(gaits, device_config.gaits);
+
+        let response = client
+            .post(uri!(super::post_gait_start(name = "run", speed = _)))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+
+        let status = client
+            .post(uri!(super::post_gait_start(name = "walk", speed = _)))
+            .dispatch()
+            .into_json::<PlaybackStatus>()
+            .unwrap();
+        assert_eq!(status.sequence, "gait/walk");
+        assert_eq!(status.rate, 1.0);
+
+        let response = client
+            .post(uri!(super::post_gait_speed(name = "walk", speed = 10.0)))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let status = client
+            .post(uri!(super::post_gait_speed(name = "walk", speed = 0.5)))
+            .dispatch()
+            .into_json::<PlaybackStatus>()
+            .unwrap();
+        assert_eq!(status.rate, 0.5);
+
+        let response = client
+            .post(uri!(super::post_gait_stop(name = "walk")))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let response = client
+            .post(uri!(super::post_gait_stop(name = "walk")))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn linear_axis() {
+        let home_path = std::env::temp_dir().join("pca9685-service-linear-axis-home");
+        std::fs::write(&home_path, "0\n").unwrap();
+        let mut device_config = create_test_device_config();
+        device_config.channels = vec![create_test_config()];
+        device_config.linear_axes = vec![LinearAxisConfig {
+            name: "slider".to_string(),
+            channel: TEST_CHANNEL_RAW_VALUE,
+            max_speed_mm_s: 100.0,
+            min_mm: 0.0,
+            max_mm: 500.0,
+            speed_mm_s: None,
+            home: HomeSwitch {
+                pin: GpioPin::Path(home_path.to_str().unwrap().to_string()),
+                active_low: false,
+            },
+            reversed: false,
+        }];
+        let client = Client::tracked(rocket(
+            &device_config,
+            TEST_CONFIG_FILE_PATH,
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+        let get_status = || {
+            client
+                .get(uri!(super::get_linear_axis(name = "slider")))
+                .dispatch()
+                .into_json::<LinearAxisStatus>()
+                .unwrap()
+        };
+
+        let response = client
+            .get(uri!(super::get_linear_axis(name = "crane")))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        assert_eq!(
+            client
+                .get(uri!(super::get_linear_axes))
+                .dispatch()
+                .into_json::<Vec<LinearAxisStatus>>()
+                .unwrap(),
+            vec![get_status()]
+        );
+
+        // Must be homed before moving
+        let response = client
+            .post(uri!(super::post_linear_axis_move(
+                name = "slider",
+                position_mm = 10.0,
+                speed_mm_s = _
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let status = client
+            .post(uri!(super::post_linear_axis_home(
+                name = "slider",
+                speed_mm_s = _
+            )))
+            .dispatch()
+            .into_json::<LinearAxisStatus>()
+            .unwrap();
+        assert!(status.homing);
+        std::fs::write(&home_path, "1\n").unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while get_status().homing {
+            assert!(Instant::now() < deadline, "not homed");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(get_status().position_mm, Some(0.0));
+
+        let response = client
+            .post(uri!(super::post_linear_axis_move(
+                name = "slider",
+                position_mm = 600.0,
+                speed_mm_s = _
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let status = client
+            .post(uri!(super::post_linear_axis_move(
+                name = "slider",
+                position_mm = 5.0,
+                speed_mm_s = Some(50.0)
+            )))
+            .dispatch()
+            .into_json::<LinearAxisStatus>()
+            .unwrap();
+        assert_eq!(status.target_mm, Some(5.0));
+        while get_status().target_mm.is_some() {
+            assert!(Instant::now() < deadline, "target not reached");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        let position_mm = get_status().position_mm.unwrap();
+        assert!((5.0..5.5).contains(&position_mm), "{}", position_mm);
+
+        let response = client
+            .post(uri!(super::post_linear_axis_stop(name = "slider")))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        std::fs::remove_file(home_path).unwrap();
+    }
+
+    #[test]
+    fn preset_apply() {
+        let mut device_config = create_test_device_config();
+        device_config.channels = vec![create_test_config()];
+        device_config.presets = vec![PresetConfig {
+            name: "open".to_string(),
+            outputs: vec![PresetOutput {
+                channel: TEST_CHANNEL_RAW_VALUE,
+                command_type: CommandType::PulseCount,
+                value: Some(1900.0),
+            }],
+        }];
+        let client = Client::tracked(rocket(
+            &device_config,
+            TEST_CONFIG_FILE_PATH,
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+        let get_channel = || {
+            client
+                .get(uri!(super::get_channel(
+                    channel = TEST_CHANNEL_RAW_VALUE,
+                    wait_for = _,
+                    timeout = _
+                )))
+                .dispatch()
+                .into_json::<ChannelConfig>()
+                .unwrap()
+        };
+
+        let presets = client
+            .get(uri!(super::get_presets))
+            .dispatch()
+            .into_json::<Vec<PresetConfig>>()
+            .unwrap();
+        assert_eq!(presets, device_config.presets);
+
+        let response = client
+            .post(uri!(super::post_preset_apply(name = "closed", fade_ms = _)))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        let response = client
+            .post(uri!(super::post_preset_apply(
+                name = "open",
+                fade_ms = Some(100_000)
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::PulseCount,
+            value: Some(1100.0),
+            basis: None,
+            client_id: None,
+        };
+        let response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let status = client
+            .post(uri!(super::post_preset_apply(
+                name = "open",
+                fade_ms = Some(200)
+            )))
+            .dispatch()
+            .into_json::<PlaybackStatus>()
+            .unwrap();
+        assert_eq!(status.sequence, "preset/open");
+        assert_eq!(status.duration_ms, 200);
+
+        // Passes through intermediate outputs on the way
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut counts = std::collections::HashSet::new();
+        while get_channel().current_count != Some(1900) {
+            assert!(Instant::now() < deadline, "preset not applied");
+            counts.insert(get_channel().current_count.unwrap());
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(counts.iter().any(|count| (1200..1800).contains(count)));
+        assert_eq!(get_channel().owner.as_deref(), Some("sequence/preset/open"));
+
+        // Refused while a sequence plays, rather than cutting it short
+        let sequence = SequenceConfig {
+            name: "wave".to_string(),
+            keyframes: vec![Keyframe {
+                t_ms: 0,
+                channel: TEST_CHANNEL_RAW_VALUE,
+                command_type: CommandType::PulseCount,
+                value: Some(1500.0),
+            }],
+            steps: vec![],
+            duration_ms: None,
+        };
+        client
+            .post(uri!(super::post_sequence(name = "wave")))
+            .header(ContentType::JSON)
+            .body(json::to_string(&sequence).unwrap())
+            .dispatch();
+        let response = client
+            .post(uri!(super::post_sequence_play(
+                name = "wave",
+                sync_timeout_ms = Some(60_000),
+                rate = _,
+                remap = _
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let response = client
+            .post(uri!(super::post_preset_apply(name = "open", fade_ms = _)))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let playback = client
+            .get(uri!(super::get_playback))
+            .dispatch()
+            .into_json::<PlaybackStatus>()
+            .unwrap();
+        assert_eq!(playback.sequence, "wave");
+
+        client.post(uri!(super::post_playback_stop)).dispatch();
+        let response = client
+            .post(uri!(super::post_preset_apply(name = "open", fade_ms = _)))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn scenes() {
+        let preset = |name: &str, count: f64| PresetConfig {
+            name: name.to_string(),
+            outputs: vec![PresetOutput {
+                channel: TEST_CHANNEL_RAW_VALUE,
+                command_type: CommandType::PulseCount,
+                value: Some(count),
+            }],
+        };
+        let mut device_config = create_test_device_config();
+        device_config.channels = vec![create_test_config()];
+        device_config.presets = vec![preset("dim", 1200.0), preset("bright", 1800.0)];
+        device_config.scenes = SceneConfig {
+            htp_channels: vec![TEST_CHANNEL_RAW_VALUE],
+        };
+        let client = Client::tracked(rocket(
+            &device_config,
+            TEST_CONFIG_FILE_PATH,
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+        let wait_for_count = |count: u16| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            loop {
+                let current_count = client
+                    .get(uri!(super::get_channel(
+                        channel = TEST_CHANNEL_RAW_VALUE,
+                        wait_for = _,
+                        timeout = _
+                    )))
+                    .dispatch()
+                    .into_json::<ChannelConfig>()
+                    .unwrap()
+                    .current_count;
+                if current_count == Some(count) {
+                    break;
+                }
+                assert!(
+                    Instant::now() < deadline,
+                    "{:?} != {}",
+                    current_count,
+                    count
+                );
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        };
+        let apply = |scene: &str, at: Option<i32>| {
+            client
+                .post(uri!(super::post_scene_apply(
+                    name = scene,
+                    priority = at,
+                    fade_ms = _
+                )))
+                .dispatch()
+        };
+
+        let response = apply("blackout", None);
+        assert_eq!(response.status(), Status::NotFound);
+        let response = client
+            .post(uri!(super::post_scene_release(name = "dim", fade_ms = _)))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+
+        // The channel is HTP, so the brightest scene wins
+        assert_eq!(apply("bright", None).status(), Status::Ok);
+        wait_for_count(1800);
+        assert_eq!(apply("dim", None).status(), Status::Ok);
+        wait_for_count(1800);
+
+        // ...unless a scene has a higher priority
+        let scenes = apply("dim", Some(5))
+            .into_json::<Vec<SceneLayer>>()
+            .unwrap();
+        assert_eq!(
+            scenes
+                .iter()
+                .map(|scene| scene.priority)
+                .collect::<Vec<_>>(),
+            vec![5, 0]
+        );
+        wait_for_count(1200);
+
+        let scenes = client
+            .post(uri!(super::post_scene_release(name = "dim", fade_ms = _)))
+            .dispatch()
+            .into_json::<Vec<SceneLayer>>()
+            .unwrap();
+        assert_eq!(
+            scenes,
+            client
+                .get(uri!(super::get_scenes))
+                .dispatch()
+                .into_json::<Vec<SceneLayer>>()
+                .unwrap()
+        );
+        assert_eq!(scenes.len(), 1);
+        wait_for_count(1800);
+    }
+
+    #[test]
+    fn pause_resume() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::PulseCount,
+            value: Some(1500.0),
+            basis: None,
+            client_id: None,
+        };
+        let put_channel = |client: &Client| {
+            client
+                .put(uri!(super::put_channel(
+                    channel = TEST_CHANNEL_RAW_VALUE,
+                    preview = _
+                )))
+                .header(ContentType::JSON)
+                .body(json::to_string(&command).unwrap())
+                .dispatch()
+                .status()
+        };
+
+        let response = client
+            .post(uri!(super::post_pause(reject = Some(true))))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.into_json::<PauseResponse>().unwrap().paused,
+            Some(PauseMode::Reject)
+        );
+        assert_eq!(put_channel(&client), Status::Conflict);
+
+        let status = client
+            .get(uri!(super::get_status))
+            .dispatch()
+            .into_json::<json::Value>()
+            .unwrap();
+        assert_eq!(status["paused"], "reject");
+
+        client.post(uri!(super::post_pause(reject = _))).dispatch();
+        assert_eq!(put_channel(&client), Status::Ok);
+
+        let response = client.post(uri!(super::post_resume)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let resumed = response.into_json::<Vec<ChannelConfig>>().unwrap();
+        assert_eq!(resumed.len(), 1);
+        assert_eq!(resumed[0].current_count, Some(1500));
+    }
+
+    #[test]
+    fn emergency_stop() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+
+        let response = client.post(uri!(super::post_emergency_stop)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.into_json::<Vec<ChannelConfig>>().unwrap().len(),
+            16
+        );
+    }
+
+    #[test]
+    fn debug_state() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let command = ChannelCommand {
+            channel: config.channel,
+            command_type: CommandType::PulseCount,
+            value: Some(1500.0),
+            basis: None,
+            client_id: None,
+        };
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .header(Header::new("X-Client-Id", "demo"))
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+
+        let response = client.get(uri!(super::get_debug_state)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let report = response.into_json::<DebugReport>().unwrap();
+        assert_eq!(report.device, "/dev/foo");
+        assert_eq!(report.channels.len(), 16);
+        let channel = &report.channels[TEST_CHANNEL_RAW_VALUE as usize];
+        assert_eq!(channel.config.current_count, Some(1500));
+        assert_eq!(channel.config.owner.as_deref(), Some("demo"));
+        assert!(!channel.moving);
+    }
+
+    #[test]
+    fn snapshot() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let command = ChannelCommand {
+            channel: config.channel,
+            command_type: CommandType::PulseCount,
+            value: Some(1500.0),
+            basis: None,
+            client_id: None,
+        };
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .header(Header::new("X-Client-Id", "demo"))
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+
+        let response = client.get(uri!(super::get_snapshot)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let mut snapshot = response.into_json::<Snapshot>().unwrap();
+        let captured = snapshot
+            .channels
+            .iter_mut()
+            .find(|captured| captured.channel == config.channel)
+            .unwrap();
+        assert_eq!(captured.current_count, Some(1500));
+        assert_eq!(captured.owner.as_deref(), Some("demo"));
+
+        captured.current_count = Some(2000);
+        let response = client
+            .post(uri!(super::post_snapshot))
+            .header(ContentType::JSON)
+            .body(json::to_string(&snapshot).unwrap())
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let restored = response.into_json::<Vec<ChannelConfig>>().unwrap();
+        let restored = restored
+            .iter()
+            .find(|restored| restored.channel == config.channel)
+            .unwrap();
+        assert_eq!(restored.current_count, Some(2000));
+        assert_eq!(restored.owner.as_deref(), Some("demo"));
+
+        snapshot.teleop_profile = Some("no-such-profile".to_string());
+        let response = client
+            .post(uri!(super::post_snapshot))
+            .header(ContentType::JSON)
+            .body(json::to_string(&snapshot).unwrap())
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn get_metrics() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let command = ChannelCommand {
+            channel: config.channel,
+            command_type: CommandType::PulseCount,
+            value: Some(3000.0),
+            basis: None,
+            client_id: None,
+        };
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::BadRequest);
+
+        let response = client.get(uri!(super::get_metrics)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let text = response.into_string().unwrap();
+        assert!(text.contains(
+            "pca9685_channel_commands_total{channel=\"0\",name=\"\",type=\"PulseCount\"} 1\n"
+        ));
+        assert!(
+            text.contains("pca9685_channel_limit_violations_total{channel=\"0\",name=\"\"} 1\n")
+        );
+    }
+
+    #[test]
+    fn named_channel() {
+        let mut device_config = create_test_device_config();
+        let mut config = create_test_config();
+        config.name = Some("pan".to_string());
+        device_config.channels = vec![config];
+        let client = Client::tracked(rocket(
+            &device_config,
+            TEST_CONFIG_FILE_PATH,
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+
+        let response = client
+            .get(uri!(super::get_named_channel(name = "tilt")))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        let config = client
+            .get(uri!(super::get_named_channel(name = "pan")))
+            .dispatch()
+            .into_json::<ChannelConfig>()
+            .unwrap();
+        assert_eq!(config.channel as u8, TEST_CHANNEL_RAW_VALUE);
+
+        let config = client
+            .put(uri!(super::put_named_channel_pct(name = "pan", pct = 1.0)))
+            .dispatch()
+            .into_json::<ChannelConfig>()
+            .unwrap();
+        assert_eq!(config.current_count, Some(config.limits().1));
+        let response = client
+            .put(uri!(super::put_named_channel_pct(name = "pan", pct = 1.5)))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn channel_move() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&create_test_config()).unwrap())
+            .dispatch();
+
+        let response = client
+            .put(uri!(super::put_channel_move(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                pct = 1.5,
+                duration_ms = 100,
+                easing = _
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let config = client
+            .put(uri!(super::put_channel_move(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                pct = 1.0,
+                duration_ms = 0,
+                easing = _
+            )))
+            .dispatch()
+            .into_json::<ChannelConfig>()
+            .unwrap();
+        assert_eq!(config.current_count, Some(config.limits().1));
+
+        let target = client
+            .put(uri!(super::put_channel_move(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                pct = 0.0,
+                duration_ms = 10_000,
+                easing = Some("sine")
+            )))
+            .dispatch()
+            .into_json::<ChannelConfig>()
+            .unwrap();
+        assert_eq!(target.current_count, Some(target.limits().0));
+        let stopped = client
+            .delete(uri!(super::delete_channel_move(
+                channel = TEST_CHANNEL_RAW_VALUE
+            )))
+            .dispatch()
+            .into_json::<ChannelConfig>()
+            .unwrap();
+        assert!(stopped.current_count > target.current_count);
+
+        let response = client
+            .put(uri!(super::put_channel_move(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                pct = 0.0,
+                duration_ms = 100,
+                easing = Some("bounce")
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn group_pct() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+
+        let configs = client
+            .put(uri!(super::put_group_pct(name = "legs", pct = 1.0)))
+            .dispatch()
+            .into_json::<Vec<ChannelConfig>>()
+            .unwrap();
+        assert_eq!(
+            configs
+                .iter()
+                .map(|config| config.channel as u8)
+                .collect::<Vec<u8>>(),
+            vec![0, 1]
+        );
+        assert!(configs
+            .iter()
+            .all(|config| config.current_count == Some(config.limits().1)));
+
+        let response = client
+            .put(uri!(super::put_group_pct(name = "legs", pct = 1.5)))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let response = client
+            .put(uri!(super::put_group_pct(name = "arms", pct = 0.5)))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn get_channel_log() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let mut config = create_test_config();
+        config.name = Some("left_elbow".to_string());
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+        assert_eq!(
+            post_response.into_json::<ChannelConfig>().unwrap().name,
+            config.name
+        );
+
+        // No logger is installed under test, so record directly
+        let log_lines = client.rocket().state::<Arc<LogLines>>().unwrap();
+        log_lines.record("left_elbow", log::Level::Warn, "Stalled".to_string());
+        log_lines.record("Channel C0", log::Level::Info, "Unnamed".to_string());
+
+        let response = client
+            .get(uri!(super::get_channel_log(
+                channel = TEST_CHANNEL_RAW_VALUE
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let lines = response.into_json::<Vec<LogLine>>().unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].message, "Stalled");
+        assert_eq!(lines[0].level, "WARN");
+    }
+
+    #[test]
+    fn get_channel_history() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        for (command_type, client_id) in [
+            (CommandType::FullOn, None),
+            (CommandType::FullOff, None),
+            (CommandType::FullOff, Some(r#"bench, "rig" 2"#)),
+        ] {
+            let command = ChannelCommand {
+                channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+                command_type,
+                value: None,
+                basis: None,
+                client_id: None,
+            };
+            let mut request = client
+                .put(uri!(super::put_channel(
+                    channel = TEST_CHANNEL_RAW_VALUE,
+                    preview = _
+                )))
+                .header(ContentType::JSON)
+                .body(json::to_string(&command).unwrap());
+            if let Some(client_id) = client_id {
+                request = request.header(Header::new("X-Client-Id", client_id));
+            }
+            assert_eq!(request.dispatch().status(), Status::Ok);
+        }
+
+        let get_response = client
+            .get(uri!(super::get_channel_history(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                resolution_ms = _
+            )))
+            .dispatch();
+        assert_eq!(get_response.status(), Status::Ok);
+
+        let history = get_response
+            .into_json::<Vec<ChannelHistoryEntry>>()
+            .unwrap();
+        assert_eq!(3, history.len());
+        assert_eq!(Some(PCA_PWM_RESOLUTION), history[0].count);
+        assert_eq!(None, history[1].count);
+
+        let get_response = client
+            .get(uri!(super::get_channel_history_csv(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                resolution_ms = _
+            )))
+            .dispatch();
+        assert_eq!(get_response.status(), Status::Ok);
+        assert_eq!(get_response.content_type(), Some(ContentType::CSV));
+
+        let csv = get_response.into_string().unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(4, lines.len());
+        assert_eq!("timestamp_ms,count,client_id", lines[0]);
+        assert!(lines[1].ends_with(",4096,"));
+        assert!(lines[2].ends_with(",,"));
+        assert!(lines[3].ends_with(r#",,"bench, ""rig"" 2""#));
+    }
+
+    #[test]
+    fn channel_pid_stream() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let feedback_path = std::env::temp_dir().join("pca9685-service-pid-feedback");
+        std::fs::write(&feedback_path, "500").unwrap();
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&create_test_config()).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let get_stream = || {
+            client
+                .get(uri!(super::get_channel_pid_stream(
+                    channel = TEST_CHANNEL_RAW_VALUE,
+                    interval_ms = Some(5),
+                    limit = Some(2)
+                )))
+                .dispatch()
+        };
+        assert_eq!(get_stream().status(), Status::NotFound);
+
+        let patch_channel = |body: String| {
+            client
+                .patch(uri!(super::patch_channel(
+                    channel = TEST_CHANNEL_RAW_VALUE,
+                    persist = _
+                )))
+                .header(ContentType::new("application", "json-patch+json"))
+                .body(body)
+                .dispatch()
+                .status()
+        };
+        let feedback = FeedbackConfig {
+            source: FeedbackSource::Path(feedback_path.to_str().unwrap().to_owned()),
+            min_raw: Some(0.0),
+            max_raw: Some(1000.0),
+            verify: None,
+        };
+        let pid = PidGains {
+            kp: 0.5,
+            ki: 0.0,
+            kd: 0.0,
+        };
+        assert_eq!(
+            patch_channel(format!(
+                r#"[{{"op": "add", "path": "/feedback", "value": {}}},
+                    {{"op": "add", "path": "/pid", "value": {}}}]"#,
+                json::to_string(&feedback).unwrap(),
+                json::to_string(&pid).unwrap()
+            )),
+            Status::Ok
+        );
+
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::PulseCount,
+            value: Some(1600.0),
+            basis: None,
+            client_id: None,
+        };
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+
+        let stream_response = get_stream();
+        assert_eq!(stream_response.status(), Status::Ok);
+        assert_eq!(
+            stream_response.content_type(),
+            Some(ContentType::EventStream)
+        );
+
+        let body = stream_response.into_string().unwrap();
+        let samples: Vec<PidSample> = body
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(|data| json::from_str(data.trim()).unwrap())
+            .collect();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].setpoint, 1600);
+        assert_eq!(samples[0].feedback, 1500.0);
+        assert_eq!(samples[0].output, 1650);
+
+        // Gains are tuned in place
+        assert_eq!(
+            patch_channel(r#"[{"op": "replace", "path": "/pid/kp", "value": 1.0}]"#.to_string()),
+            Status::Ok
+        );
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let body = get_stream().into_string().unwrap();
+            let mut data = body.lines().filter_map(|line| line.strip_prefix("data:"));
+            let sample: PidSample = json::from_str(data.next_back().unwrap().trim()).unwrap();
+            if sample.output == 1700 {
+                break;
+            }
+            assert!(Instant::now() < deadline, "gains not tuned");
+        }
+    }
+
+    #[test]
+    fn channel_step_test() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let post_step_test = |test: &StepTest| {
+            client
+                .post(uri!(super::post_channel_step_test(
+                    channel = TEST_CHANNEL_RAW_VALUE
+                )))
+                .header(ContentType::JSON)
+                .body(json::to_string(test).unwrap())
+                .dispatch()
+        };
+        let test = StepTest {
+            from_count: 1000,
+            to_count: 2000,
+            settle_ms: 10,
+            duration_ms: 20,
+            sample_interval_us: 5000,
+        };
+
+        assert_eq!(post_step_test(&test).status(), Status::NotFound);
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&create_test_config()).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let post_response = post_step_test(&test);
+        assert_eq!(post_response.status(), Status::Ok);
+        assert_eq!(post_response.content_type(), Some(ContentType::CSV));
+
+        let csv = post_response.into_string().unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!("t_ms,count,feedback", lines[0]);
+        assert!(lines[1].starts_with('-') && lines[1].ends_with(",1000,"));
+        assert!(lines.last().unwrap().ends_with(",2000,"));
+
+        let post_response = post_step_test(&StepTest {
+            to_count: 3000,
+            ..test
+        });
+        assert_eq!(post_response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn get_history_export() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&create_test_config()).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        for command_type in [CommandType::FullOn, CommandType::FullOff] {
+            let command = ChannelCommand {
+                channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+                command_type,
+                value: None,
+                basis: None,
+                client_id: None,
+            };
+            let put_response = client
+                .put(uri!(super::put_channel(
+                    channel = TEST_CHANNEL_RAW_VALUE,
+                    preview = _
+                )))
+                .header(ContentType::JSON)
+                .body(json::to_string(&command).unwrap())
+                .dispatch();
+            assert_eq!(put_response.status(), Status::Ok);
+        }
+
+        let export = |since: Option<u64>| {
+            let get_response = client
+                .get(uri!(super::get_history_export(
+                    since_ms = since,
+                    until_ms = _
+                )))
+                .dispatch();
+            assert_eq!(get_response.status(), Status::Ok);
+            assert_eq!(get_response.content_type(), Some(ContentType::GZIP));
+
+            let mut ndjson = String::new();
+            GzDecoder::new(get_response.into_bytes().unwrap().as_slice())
+                .read_to_string(&mut ndjson)
+                .unwrap();
+            ndjson
+                .lines()
+                .map(|line| json::from_str::<ChannelHistoryRecord>(line).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let records = export(None);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].channel as u8, TEST_CHANNEL_RAW_VALUE);
+        assert_eq!(records[0].count, Some(PCA_PWM_RESOLUTION));
+        assert_eq!(records[1].count, None);
+        assert!(export(Some(records[1].timestamp_ms + 1)).is_empty());
+
+        let get_response = client
+            .get(uri!(super::get_history_export(
+                since_ms = Some(2_u64),
+                until_ms = Some(1_u64)
+            )))
+            .dispatch();
+        assert_eq!(get_response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn delete_channel() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+
+        let initial_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(initial_response.status(), Status::Ok);
+
+        let delete_response = client
+            .delete(uri!(super::delete_channel(
+                channel = TEST_CHANNEL_RAW_VALUE
+            )))
+            .dispatch();
+        assert_eq!(delete_response.status(), Status::Ok);
+
+        let duplicate_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(duplicate_response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn put_channel_calibration() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let calibration = ChannelCalibration {
+            points: [
+                CalibrationPoint {
+                    count: 1000,
+                    measured_pw_ms: 1.25,
+                },
+                CalibrationPoint {
+                    count: 2000,
+                    measured_pw_ms: 2.5,
+                },
+            ],
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel_calibration(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                persist = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&calibration).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+        let response_config = put_response.into_json::<ChannelConfig>().unwrap();
+        assert_eq!(response_config.calibration, Some(calibration));
+
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::PulseWidth,
+            value: Some(1.875),
+            basis: None,
+            client_id: None,
+        };
+        let put_response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+        let response_config = put_response.into_json::<ChannelConfig>().unwrap();
+        assert_eq!(response_config.current_count, Some(1500));
+
+        let delete_response = client
+            .delete(uri!(super::delete_channel_calibration(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                persist = _
+            )))
+            .dispatch();
+        assert_eq!(delete_response.status(), Status::Ok);
+        let response_config = delete_response.into_json::<ChannelConfig>().unwrap();
+        assert_eq!(response_config.calibration, None);
+    }
+
+    #[test]
+    fn patch_channel() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let json_patch = ContentType::new("application", "json-patch+json");
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&create_test_config()).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let patch_response = client
+            .patch(uri!(super::patch_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                persist = _
+            )))
+            .header(json_patch.clone())
+            .body(
+                r#"[{"op": "replace", "path": "/custom_limits/count_limits/max_on_count", "value": 2500},
+                    {"op": "add", "path": "/name", "value": "left_elbow"}]"#,
+            )
+            .dispatch();
+        assert_eq!(patch_response.status(), Status::Ok);
+        let response_config = patch_response.into_json::<ChannelConfig>().unwrap();
+        assert_eq!(response_config.limits(), (1000, 2500));
+        assert_eq!(response_config.name, Some("left_elbow".to_string()));
+
+        // The resulting config is validated; on failure, nothing changes
+        for body in [
+            r#"[{"op": "add", "path": "/custom_limits/pw_limits", "value": {"min_on_ms": 1.0, "max_on_ms": 2.0}}]"#,
+            r#"[{"op": "replace", "path": "/custom_limits/count_limits/max_on_count", "value": "high"}]"#,
+            r#"[{"op": "replace", "path": "/current_count", "value": 1500}]"#,
+            r#"[{"op": "remove", "path": "/no_such_field"}]"#,
+        ] {
+            let patch_response = client
+                .patch(uri!(super::patch_channel(
+                    channel = TEST_CHANNEL_RAW_VALUE,
+                    persist = _
+                )))
+                .header(json_patch.clone())
+                .body(body)
+                .dispatch();
+            assert_eq!(patch_response.status(), Status::BadRequest, "{}", body);
+        }
+
+        let get_response = client
+            .get(uri!(super::get_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                wait_for = _,
+                timeout = _
+            )))
+            .dispatch();
+        assert_eq!(
+            get_response.into_json::<ChannelConfig>().unwrap().limits(),
+            (1000, 2500)
+        );
+    }
+
+    #[test]
+    fn put_channel_calibration_bad_request() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let point = CalibrationPoint {
+            count: 1000,
+            measured_pw_ms: 1.25,
+        };
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_channel_calibration(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                persist = _
+            )))
+            .header(ContentType::JSON)
+            .body(
+                json::to_string(&ChannelCalibration {
+                    points: [point, point],
+                })
+                .unwrap(),
+            )
+            .dispatch();
+        assert_eq!(put_response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn get_channel_stats() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+
+        let get_response = client
+            .get(uri!(super::get_channel_stats(
+                channel = TEST_CHANNEL_RAW_VALUE
+            )))
+            .dispatch();
+        assert_eq!(get_response.status(), Status::NotFound);
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let get_response = client
+            .get(uri!(super::get_channel_stats(
+                channel = TEST_CHANNEL_RAW_VALUE
+            )))
+            .dispatch();
+        assert_eq!(get_response.status(), Status::Ok);
+        let stats = get_response.into_json::<ChannelStats>().unwrap();
+        assert_eq!(stats.channel as u8, TEST_CHANNEL_RAW_VALUE);
+        assert_eq!(stats.travel_counts, 0);
+        assert!(!stats.thermal_warning);
+    }
+
+    #[test]
+    fn put_mixer() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+
+        let put_response = client
+            .put(uri!(super::put_mixer(name = "elevons")))
+            .header(ContentType::JSON)
+            .body(r#"{"pitch": 1.0, "roll": 1.0}"#)
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+        let configs = put_response.into_json::<Vec<ChannelConfig>>().unwrap();
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].current_count, Some(4096));
+        assert_eq!(configs[1].current_count, Some(2048));
+
+        let get_response = client
+            .get(uri!(super::get_mixer(name = "elevons")))
+            .dispatch();
+        assert_eq!(get_response.status(), Status::Ok);
+        assert_eq!(
+            get_response.into_json::<HashMap<String, f64>>().unwrap()["roll"],
+            1.0
+        );
+
+        let put_response = client
+            .put(uri!(super::put_mixer(name = "elevons")))
+            .header(ContentType::JSON)
+            .body(r#"{"yaw": 1.0}"#)
+            .dispatch();
+        assert_eq!(put_response.status(), Status::BadRequest);
+
+        let put_response = client
+            .put(uri!(super::put_mixer(name = "vtail")))
+            .header(ContentType::JSON)
+            .body(r#"{"pitch": 1.0}"#)
+            .dispatch();
+        assert_eq!(put_response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn put_virtual_channels() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+
+        let put_response = client
+            .put(uri!(super::put_virtual_channels))
+            .header(ContentType::JSON)
+            .body(r#"{"convergence": 0.5}"#)
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+        let configs = put_response.into_json::<Vec<ChannelConfig>>().unwrap();
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].current_count, Some(3072));
+        assert_eq!(configs[1].current_count, Some(1024));
+
+        let get_response = client.get(uri!(super::get_virtual_channels)).dispatch();
+        assert_eq!(
+            get_response.into_json::<HashMap<String, f64>>().unwrap()["convergence"],
+            0.5
+        );
+
+        let put_response = client
+            .put(uri!(super::put_virtual_channels))
+            .header(ContentType::JSON)
+            .body(r#"{"tilt": 1.0}"#)
+            .dispatch();
+        assert_eq!(put_response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn put_mixers() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+
+        let get_response = client.get(uri!(super::get_mixers)).dispatch();
+        assert_eq!(get_response.status(), Status::Ok);
+        let mut mixers = get_response.into_json::<Vec<MixerConfig>>().unwrap();
+        assert_eq!(mixers.len(), 1);
+
+        mixers[0].expo.insert("roll".to_owned(), 0.5);
+        let put_response = client
+            .put(uri!(super::put_mixers))
+            .header(ContentType::JSON)
+            .body(json::to_string(&mixers).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+        assert_eq!(
+            put_response.into_json::<Vec<MixerConfig>>().unwrap(),
+            mixers
+        );
+
+        mixers[0].expo.insert("yaw".to_owned(), 0.5);
+        let put_response = client
+            .put(uri!(super::put_mixers))
+            .header(ContentType::JSON)
+            .body(json::to_string(&mixers).unwrap())
+            .dispatch();
+        assert_eq!(put_response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn put_channel_preview() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let channel = Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap();
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let preview = |value: f64| {
+            client
+                .put(uri!(super::put_channel(
+                    channel = TEST_CHANNEL_RAW_VALUE,
+                    preview = Some(true)
+                )))
+                .header(ContentType::JSON)
+                .body(
+                    json::to_string(&ChannelCommand {
+                        channel,
+                        command_type: CommandType::PulseCount,
+                        value: Some(value),
+                        basis: None,
+                        client_id: None,
+                    })
+                    .unwrap(),
+                )
+                .dispatch()
+        };
+
+        let put_response = preview(1500.0);
+        assert_eq!(put_response.status(), Status::Ok);
+        assert_eq!(
+            put_response
+                .into_json::<ChannelConfig>()
+                .unwrap()
+                .current_count,
+            Some(1500)
+        );
+        assert_eq!(preview(2500.0).status(), Status::BadRequest);
+
+        let get_response = client
+            .get(uri!(super::get_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                wait_for = _,
+                timeout = _
+            )))
+            .dispatch();
+        assert_eq!(
+            get_response
+                .into_json::<ChannelConfig>()
+                .unwrap()
+                .current_count,
+            None
+        );
+    }
+
+    #[test]
+    fn get_config_yaml() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let yaml = Header::new("Accept", "application/yaml");
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&create_test_config()).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let response = client.get(uri!(super::get_config)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        assert_eq!(response.into_json::<Config>().unwrap().channels.len(), 1);
+
+        let response = client
+            .get(uri!(super::get_config))
+            .header(yaml.clone())
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.content_type(),
+            Some(ContentType::new("application", "yaml"))
+        );
+        let config: Config = serde_yaml::from_str(&response.into_string().unwrap()).unwrap();
+        assert_eq!(config.device, create_test_device_config().device);
+        assert_eq!(config.channels.len(), 1);
+        assert_eq!(config.channels[0].channel as u8, TEST_CHANNEL_RAW_VALUE);
+        assert_eq!(
+            config.channels[0].custom_limits,
+            create_test_config().custom_limits
+        );
+
+        let response = client.get(uri!(super::get_channels)).dispatch();
+        assert_eq!(response.into_json::<Vec<ChannelConfig>>().unwrap().len(), 1);
+
+        let response = client
+            .get(uri!(super::get_channels))
+            .header(yaml)
+            .dispatch();
+        let yaml = response.into_string().unwrap();
+        assert!(!yaml.contains("current_count"));
+        let channels: Vec<ChannelConfig> = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(channels.len(), 1);
+    }
+
+    #[test]
+    fn backup_restore() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&create_test_config()).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+        let sequence = SequenceConfig {
+            name: "wave".to_string(),
+            keyframes: vec![Keyframe {
+                t_ms: 0,
+                channel: TEST_CHANNEL_RAW_VALUE,
+                command_type: CommandType::PulseCount,
+                value: Some(1500.0),
+            }],
+            steps: vec![],
+            duration_ms: None,
+        };
+        let post_response = client
+            .post(uri!(super::post_sequence(name = "wave")))
+            .header(ContentType::JSON)
+            .body(json::to_string(&sequence).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let response = client.get(uri!(super::get_backup)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::TAR));
+        let tar = response.into_bytes().unwrap();
+
+        // Onto fresh hardware
+        let config_file_path = std::env::temp_dir().join("pca9685-service-restore.yaml");
+        let config_file_path = config_file_path.to_str().unwrap();
+        let client = Client::tracked(rocket(
+            &create_test_device_config(),
+            config_file_path,
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+
+        let response = client
+            .post(uri!(super::post_restore))
+            .body(b"not a backup".as_slice())
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        assert_eq!(
+            client
+                .get(uri!(super::get_sequence(name = "wave")))
+                .dispatch()
+                .status(),
+            Status::NotFound
+        );
+
+        let response = client.post(uri!(super::post_restore)).body(tar).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let configs = response.into_json::<Vec<ChannelConfig>>().unwrap();
+        assert_eq!(
+            configs[TEST_CHANNEL_RAW_VALUE as usize].custom_limits,
+            create_test_config().custom_limits
+        );
+        assert_eq!(
+            client
+                .get(uri!(super::get_sequence(name = "wave")))
+                .dispatch()
+                .status(),
+            Status::Ok
+        );
+        let config = Config::load_from_file(config_file_path);
+        assert_eq!(config.channels.len(), 1);
+        std::fs::remove_file(config_file_path).unwrap();
+    }
+
+    #[test]
+    fn backup_restore_source() {
+        let dir = std::env::temp_dir().join("pca9685-service-restore-source");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("base.yaml"),
+            "address: 0x40
+output_frequency_hz: 50
+channels:
+  - channel: 0
+    custom_limits:
+      count_limits: { min_on_count: 200, max_on_count: 400 }
+",
+        )
+        .unwrap();
+        std::env::set_var("PCA9685_RESTORE_SOURCE_DEVICE", "/dev/i2c-1");
+        let source = "# The bench rig
+include: [base.yaml]
+device: ${PCA9685_RESTORE_SOURCE_DEVICE}
+";
+        let source_path = dir.join("source.yaml");
+        std::fs::write(&source_path, source).unwrap();
+        let source_path = source_path.to_str().unwrap();
+        let client = Client::tracked(rocket(
+            &Config::load_from_file(source_path),
+            source_path,
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+        let response = client.get(uri!(super::get_backup)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let tar = response.into_bytes().unwrap();
+
+        let config_file_path = dir.join("restored.yaml");
+        std::fs::write(&config_file_path, "address: 0x41\n").unwrap();
+        let config_file_path = config_file_path.to_str().unwrap();
+        let client = Client::tracked(rocket(
+            &create_test_device_config(),
+            config_file_path,
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+
+        // A source that cannot be loaded in place changes nothing...
+        let mut backup = Backup::from_tar(&tar).unwrap();
+        backup.source = Some("include: [missing.yaml]\n".to_string());
+        backup.sequences = vec![SequenceConfig {
+            name: "wave".to_string(),
+            keyframes: vec![Keyframe {
+                t_ms: 0,
+                channel: 0,
+                command_type: CommandType::PulseCount,
+                value: Some(300.0),
+            }],
+            steps: vec![],
+            duration_ms: None,
+        }];
+        let response = client
+            .post(uri!(super::post_restore))
+            .body(backup.to_tar().unwrap())
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let body = response.into_string().unwrap();
+        assert!(body.contains("missing.yaml"), "{}", body);
+        assert_eq!(
+            std::fs::read_to_string(config_file_path).unwrap(),
+            "address: 0x41\n"
+        );
+        assert!(!Path::new(&format!("{}.tmp", config_file_path)).exists());
+        assert_eq!(
+            client
+                .get(uri!(super::get_sequence(name = "wave")))
+                .dispatch()
+                .status(),
+            Status::NotFound
+        );
+
+        // ...while the source, as written, is restored verbatim
+        let response = client.post(uri!(super::post_restore)).body(tar).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(std::fs::read_to_string(config_file_path).unwrap(), source);
+        assert_eq!(
+            Config::load_from_file(config_file_path).device,
+            "/dev/i2c-1"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn get_config_diff() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let response = client.get(uri!(super::get_config_diff)).dispatch();
+        assert_eq!(response.status(), Status::InternalServerError);
+
+        let config_file_path = std::env::temp_dir().join("pca9685-service-config-diff.yaml");
+        std::fs::write(
+            &config_file_path,
+            "device: /dev/i2c-1\naddress: 0x40\noutput_frequency_hz: 50\n",
+        )
+        .unwrap();
+        let config = Config::load_from_file(config_file_path.to_str().unwrap());
+        let client = Client::tracked(rocket(
+            &config,
+            config_file_path.to_str().unwrap(),
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+
+        let response = client.get(uri!(super::get_config_diff)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(response
+            .into_json::<Vec<ConfigDifference>>()
+            .unwrap()
+            .is_empty());
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&create_test_config()).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let response = client.get(uri!(super::get_config_diff)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let differences = response.into_json::<Vec<ConfigDifference>>().unwrap();
+        assert_eq!(differences.len(), 1);
+        assert_eq!(differences[0].path, "channels[0].custom_limits");
+
+        std::fs::remove_file(config_file_path).unwrap();
+    }
+
+    #[test]
+    fn post_channel_persist() {
+        let config_file_path = std::env::temp_dir().join("pca9685-service-persist.yaml");
+        let config_file_path = config_file_path.to_str().unwrap();
+        std::fs::write(
+            config_file_path,
+            "device: /dev/i2c-1\naddress: 0x40\noutput_frequency_hz: 50\n",
+        )
+        .unwrap();
+        let client = Client::tracked(rocket(
+            &Config::load_from_file(config_file_path),
+            config_file_path,
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = Some(true))))
+            .header(ContentType::JSON)
+            .body(json::to_string(&create_test_config()).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let persisted = Config::load_from_file(config_file_path);
+        assert_eq!(persisted.channels.len(), 1);
+        assert_eq!(
+            persisted.channels[0].custom_limits,
+            create_test_config().custom_limits
+        );
+
+        let response = client.get(uri!(super::get_config_diff)).dispatch();
+        assert!(response
+            .into_json::<Vec<ConfigDifference>>()
+            .unwrap()
+            .is_empty());
+
+        std::fs::remove_file(config_file_path).unwrap();
+    }
+
+    #[test]
+    fn config_validate() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let validate = |content_type: ContentType, body: &str| {
+            client
+                .post(uri!(super::post_config_validate))
+                .header(content_type)
+                .body(body)
+                .dispatch()
+        };
+
+        let response = validate(
+            ContentType::new("application", "yaml"),
+            "device: /dev/i2c-1\naddress: 0x40\noutput_frequency_hz: 50\n",
+        );
+        assert_eq!(response.status(), Status::Ok);
+        assert!(response.into_json::<ConfigValidation>().unwrap().valid);
+
+        let response = validate(
+            ContentType::JSON,
+            r#"{"device": "/dev/i2c-1", "address": 64, "output_frequency_hz": 50,
+                "channels": [{"channel": 0, "current_count": null, "custom_limits":
+                    {"pw_limits": {"min_on_ms": 1.0, "max_on_ms": 50.0}}}]}"#,
+        );
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+        let validation = response.into_json::<ConfigValidation>().unwrap();
+        assert_eq!(validation.findings.len(), 1);
+        assert_eq!(validation.findings[0].path, "channels[0]");
+
+        let response = validate(ContentType::JSON, "{}");
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+        assert_eq!(
+            response.into_json::<ConfigValidation>().unwrap().findings[0].severity,
+            FindingSeverity::Error
+        );
+    }
+
+    #[test]
+    fn sequence_validate() {
+        let mut device_config = create_test_device_config();
+        device_config.channels = vec![create_test_config()];
+        device_config.presets = vec![PresetConfig {
+            name: "open".to_string(),
+            outputs: vec![PresetOutput {
+                channel: TEST_CHANNEL_RAW_VALUE,
+                command_type: CommandType::PulseCount,
+                value: Some(1900.0),
+            }],
+        }];
+        let client = Client::tracked(rocket(
+            &device_config,
+            TEST_CONFIG_FILE_PATH,
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+
+        let keyframe = |t_ms: u64, value: f64| Keyframe {
+            t_ms,
+            channel: TEST_CHANNEL_RAW_VALUE,
+            command_type: CommandType::PulseCount,
+            value: Some(value),
+        };
+        let sequence = SequenceConfig {
+            name: "wave".to_string(),
+            keyframes: vec![keyframe(0, 1000.0), keyframe(500, 2000.0)],
+            steps: vec![],
+            duration_ms: None,
+        };
+        client
+            .post(uri!(super::post_sequence(name = "wave")))
+            .header(ContentType::JSON)
+            .body(json::to_string(&sequence).unwrap())
+            .dispatch();
+
+        let response = client
+            .post(uri!(super::post_sequence_validate(
+                name = "nod",
+                against = _
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        let response = client
+            .post(uri!(super::post_sequence_validate(
+                name = "wave",
+                against = _
+            )))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        // Another rig, whose channel 0 has narrower limits
+        let rig = "device: /dev/i2c-1\naddress: 0x40\noutput_frequency_hz: 200\nchannels:\n  \
+                   - channel: 0\n    custom_limits:\n      \
+                   count_limits: {min_on_count: 1500, max_on_count: 2000}\n";
+        let validate = |uri: &'static str, body: &str| {
+            client
+                .post(uri)
+                .header(ContentType::new("application", "yaml"))
+                .body(body)
+                .dispatch()
+        };
+        let response = validate("/sequence/wave/validate?against=config", rig);
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+        let validation = response.into_json::<ConfigValidation>().unwrap();
+        assert_eq!(validation.findings.len(), 1);
+        assert_eq!(validation.findings[0].path, "keyframes[0]");
+        let response = validate("/preset/open/validate?against=config", rig);
+        assert_eq!(response.status(), Status::Ok);
+        let response = validate("/preset/open/validate?against=config", "device: [");
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn heartbeat() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+
+        let post_response = client
+            .post(uri!(super::post_heartbeat))
+            .header(Header::new("X-Client-Id", "rover"))
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+        assert_eq!(
+            post_response
+                .into_json::<HeartbeatResponse>()
+                .unwrap()
+                .watchdog_timeout_ms,
+            Some(60 * 1000)
+        );
+    }
+
+    #[test]
+    fn failsafe_test() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let mut config = create_test_config();
+
+        let post_response = client
+            .post(uri!(super::post_failsafe_test(seconds = 0.1)))
+            .dispatch();
+        assert_eq!(post_response.status(), Status::BadRequest);
+
+        config.failsafe = Some(FailsafeOutput::Count(1500));
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let post_response = client
+            .post(uri!(super::post_failsafe_test(seconds = 0.1)))
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+        let configs = post_response.into_json::<Vec<ChannelConfig>>().unwrap();
+        assert_eq!(configs[0].current_count, Some(1500));
+
+        let post_response = client
+            .post(uri!(super::post_failsafe_test(seconds = -1.0)))
+            .dispatch();
+        assert_eq!(post_response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn put_rate() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let mut config = create_test_config();
+        config.dual_rate = Some(DualRate {
+            low: 0.5,
+            high: 1.0,
+            selected: Rate::High,
+        });
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_rate))
+            .header(ContentType::JSON)
+            .body(r#"{"selector": {"group": "legs"}, "rate": "low"}"#)
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+        let configs = put_response.into_json::<Vec<ChannelConfig>>().unwrap();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].dual_rate.unwrap().selected, Rate::Low);
+
+        let put_response = client
+            .put(uri!(super::put_rate))
+            .header(ContentType::JSON)
+            .body(r#"{"selector": {"group": "arms"}, "rate": "low"}"#)
+            .dispatch();
+        assert_eq!(put_response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn teleop() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+
+        let get_response = client.get(uri!(super::get_teleop)).dispatch();
+        assert_eq!(get_response.status(), Status::Ok);
+        let teleop = get_response.into_json::<TeleopResponse>().unwrap();
+        assert_eq!(teleop.active_profile, Some("normal".to_owned()));
+        assert_eq!(teleop.profiles.len(), 1);
+
+        let put_response = client
+            .put(uri!(super::put_teleop_gamepad))
+            .header(ContentType::JSON)
+            .body(r#"{"axes": [1.0]}"#)
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+        let configs = put_response.into_json::<Vec<ChannelConfig>>().unwrap();
+        assert_eq!(configs[0].current_count, Some(3072));
+
+        let put_response = client
+            .put(uri!(super::put_teleop_profile))
+            .header(ContentType::JSON)
+            .body(r#""normal""#)
+            .dispatch();
+        assert_eq!(put_response.status(), Status::Ok);
+
+        let put_response = client
+            .put(uri!(super::put_teleop_profile))
+            .header(ContentType::JSON)
+            .body(r#""aerobatic""#)
+            .dispatch();
+        assert_eq!(put_response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn relax() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+        let config = create_test_config();
+        let channel = Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap();
+
+        let post_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(post_response.status(), Status::Ok);
+
+        let set = |command_type: CommandType, value: Option<f64>| {
+            client
+                .put(uri!(super::put_channel(
+                    channel = TEST_CHANNEL_RAW_VALUE,
+                    preview = _
+                )))
+                .header(ContentType::JSON)
+                .body(
+                    json::to_string(&ChannelCommand {
+                        channel,
+                        command_type,
+                        value,
+                        basis: None,
+                        client_id: None,
+                    })
+                    .unwrap(),
+                )
+                .dispatch()
+                .into_json::<ChannelConfig>()
+                .unwrap()
+        };
+
+        assert_eq!(
+            set(CommandType::PulseCount, Some(1500.0)).current_count,
+            Some(1500)
+        );
+        assert_eq!(set(CommandType::Relax, None).current_count, None);
+
+        set(CommandType::PulseCount, Some(1500.0));
+        let relax_response = client
+            .post(uri!(super::post_relax))
+            .header(ContentType::JSON)
+            .body(json::to_string(&ChannelSelector::Group("legs".to_owned())).unwrap())
+            .dispatch();
+        assert_eq!(relax_response.status(), Status::Ok);
+        let configs = relax_response.into_json::<Vec<ChannelConfig>>().unwrap();
+        assert_eq!(configs.len(), 2);
+        assert!(configs.iter().all(|config| config.current_count.is_none()));
+
+        let relax_response = client
+            .post(uri!(super::post_relax))
+            .header(ContentType::JSON)
+            .body(r#""all""#)
+            .dispatch();
+        assert_eq!(relax_response.status(), Status::Ok);
+        assert_eq!(
+            relax_response
+                .into_json::<Vec<ChannelConfig>>()
+                .unwrap()
+                .len(),
+            16
+        );
+
+        let relax_response = client
+            .post(uri!(super::post_relax))
+            .header(ContentType::JSON)
+            .body(json::to_string(&ChannelSelector::Group("arms".to_owned())).unwrap())
+            .dispatch();
+        assert_eq!(relax_response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn delete_channel_not_found() {
+        let client = Client::tracked(create_mock()).expect("valid rocket instance");
+
+        let delete_response = client
+            .delete(uri!(super::delete_channel(
+                channel = TEST_CHANNEL_RAW_VALUE
+            )))
+            .dispatch();
+        assert_eq!(delete_response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn sequence_crud() {
+        let sequences_dir = std::env::temp_dir().join("pca9685-service-sequences");
+        let _ = std::fs::remove_dir_all(&sequences_dir);
+        let mut device_config = create_test_device_config();
+        device_config.sequences_dir = Some(sequences_dir.to_str().unwrap().to_string());
+        device_config.channels = vec![create_test_config()];
+        let create_client = || {
+            Client::tracked(rocket(
+                &device_config,
+                TEST_CONFIG_FILE_PATH,
+                true,
+                Arc::new(LogLines::default()),
+            ))
+            .expect("valid rocket instance")
+        };
+        let client = create_client();
+
+        let keyframe = |t_ms: u64, value: f64| Keyframe {
+            t_ms,
+            channel: TEST_CHANNEL_RAW_VALUE,
+            command_type: CommandType::PulseCount,
+            value: Some(value),
+        };
+        let mut sequence = SequenceConfig {
+            name: "wave".to_string(),
+            keyframes: vec![keyframe(0, 1000.0), keyframe(500, 2000.0)],
+            steps: vec![],
+            duration_ms: None,
+        };
+        let send = |request: rocket::local::blocking::LocalRequest, sequence: &SequenceConfig| {
+            request
+                .header(ContentType::JSON)
+                .body(json::to_string(sequence).unwrap())
+                .dispatch()
+                .status()
+        };
+
+        let put_status = send(
+            client.put(uri!(super::put_sequence(name = "wave"))),
+            &sequence,
+        );
+        assert_eq!(put_status, Status::NotFound);
+        let post_status = send(
+            client.post(uri!(super::post_sequence(name = "wave"))),
+            &sequence,
+        );
+        assert_eq!(post_status, Status::Ok);
+        let post_status = send(
+            client.post(uri!(super::post_sequence(name = "wave"))),
+            &sequence,
+        );
+        assert_eq!(post_status, Status::Conflict);
+        let post_status = send(
+            client.post(uri!(super::post_sequence(name = "other"))),
+            &sequence,
+        );
+        assert_eq!(post_status, Status::BadRequest);
+        assert!(sequences_dir.join("wave.yaml").exists());
+
+        // Out of limits, and non-monotonic
+        sequence.keyframes[1].value = Some(2500.0);
+        let put_status = send(
+            client.put(uri!(super::put_sequence(name = "wave"))),
+            &sequence,
+        );
+        assert_eq!(put_status, Status::BadRequest);
+        sequence.keyframes[1] = keyframe(250, 1500.0);
+        sequence.keyframes.reverse();
+        let put_status = send(
+            client.put(uri!(super::put_sequence(name = "wave"))),
+            &sequence,
+        );
+        assert_eq!(put_status, Status::BadRequest);
+
+        sequence.keyframes.reverse();
+        let put_status = send(
+            client.put(uri!(super::put_sequence(name = "wave"))),
+            &sequence,
+        );
+        assert_eq!(put_status, Status::Ok);
+
+        let get_response = client
+            .get(uri!(super::get_sequence(name = "wave")))
+            .dispatch();
+        assert_eq!(
+            get_response.into_json::<SequenceConfig>().unwrap(),
+            sequence
+        );
+
+        // Saved sequences are loaded at startup
+        drop(client);
+        let client = create_client();
+        let get_response = client.get(uri!(super::get_sequences)).dispatch();
+        assert_eq!(
+            get_response.into_json::<Vec<SequenceConfig>>().unwrap(),
+            vec![sequence]
+        );
+
+        let delete_response = client
+            .delete(uri!(super::delete_sequence(name = "wave")))
+            .dispatch();
+        assert_eq!(delete_response.status(), Status::Ok);
+        assert!(!sequences_dir.join("wave.yaml").exists());
+        let delete_response = client
+            .delete(uri!(super::delete_sequence(name = "wave")))
+            .dispatch();
+        assert_eq!(delete_response.status(), Status::NotFound);
+
+        std::fs::remove_dir_all(&sequences_dir).unwrap();
+    }
+
+    #[test]
+    fn sequence_record() {
+        let sequences_dir = std::env::temp_dir().join("pca9685-service-recordings");
+        let _ = std::fs::remove_dir_all(&sequences_dir);
+        let mut device_config = create_test_device_config();
+        device_config.sequences_dir = Some(sequences_dir.to_str().unwrap().to_string());
+        device_config.channels = vec![create_test_config()];
+        let client = Client::tracked(rocket(
+            &device_config,
+            TEST_CONFIG_FILE_PATH,
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+
+        let response = client.post(uri!(super::post_recording_stop)).dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let status = client
+            .post(uri!(super::post_sequence_record(name = "wave")))
+            .dispatch()
+            .into_json::<RecordingStatus>()
+            .unwrap();
+        assert_eq!(status.sequence, "wave");
+        let response = client
+            .post(uri!(super::post_sequence_record(name = "nod")))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let command = ChannelCommand {
+            channel: Channel::try_from(TEST_CHANNEL_RAW_VALUE).unwrap(),
+            command_type: CommandType::PulseCount,
+            value: Some(1500.0),
+            basis: None,
+            client_id: None,
+        };
+        let response = client
+            .put(uri!(super::put_channel(
+                channel = TEST_CHANNEL_RAW_VALUE,
+                preview = _
+            )))
+            .header(ContentType::JSON)
+            .body(json::to_string(&command).unwrap())
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let status = client
+            .get(uri!(super::get_recording))
+            .dispatch()
+            .into_json::<RecordingStatus>()
+            .unwrap();
+        assert_eq!(status.keyframes, 1);
+
+        let sequence = client
+            .post(uri!(super::post_recording_stop))
+            .dispatch()
+            .into_json::<SequenceConfig>()
+            .unwrap();
+        assert_eq!(sequence.keyframes.len(), 1);
+        assert_eq!(sequence.keyframes[0].value, Some(1500.0));
+        assert_eq!(
+            client.get(uri!(super::get_recording)).dispatch().status(),
+            Status::NotFound
+        );
+        assert!(sequences_dir.join("wave.yaml").exists());
+        let _ = std::fs::remove_dir_all(&sequences_dir);
+    }
+
+    #[test]
+    fn sequence_playback() {
+        let mut device_config = create_test_device_config();
+        device_config.channels = vec![create_test_config()];
+        let client = Client::tracked(rocket(
+            &device_config,
+            TEST_CONFIG_FILE_PATH,
+            true,
+            Arc::new(LogLines::default()),
+        ))
+        .expect("valid rocket instance");
+
+        let keyframe = |t_ms: u64, value: f64| Keyframe {
+            t_ms,
+            channel: TEST_CHANNEL_RAW_VALUE,
+            command_type: CommandType::PulseCount,
+            value: Some(value),
+        };
+        let sequence = SequenceConfig {
+            name: "wave".to_string(),
+            keyframes: vec![keyframe(0, 1000.0), keyframe(500, 2000.0)],
+            steps: vec![],
+            duration_ms: None,
+        };
+        client
+            .post(uri!(super::post_sequence(name = "wave")))
+            .header(ContentType::JSON)
+            .body(json::to_string(&sequence).unwrap())
+            .dispatch();
+
+        let seek_response = client
+            .post(uri!(super::post_sequence_seek(name = "wave", t_ms = 500)))
+            .dispatch();
+        assert_eq!(seek_response.status(), Status::BadRequest);
+        let play_response = client
+            .post(uri!(super::post_sequence_play(
+                name = "nod",
+                sync_timeout_ms = _,
+                rate = _,
+                remap = _
+            )))
+            .dispatch();
+        assert_eq!(play_response.status(), Status::NotFound);
+
+        let play_response = client
+            .post(uri!(super::post_sequence_play(
+                name = "wave",
+                sync_timeout_ms = Some(60_000),
+                rate = _,
+                remap = _
+            )))
+            .dispatch();
+        assert_eq!(play_response.status(), Status::Ok);
+        assert_eq!(
+            play_response.into_json::<PlaybackStatus>().unwrap(),
+            PlaybackStatus {
+                sequence: "wave".to_string(),
+                clock: PlaybackClock::External { timeout_ms: 60_000 },
+                t_ms: None,
+                duration_ms: 500,
+                rate: 1.0,
+                loop_region: None,
+            }
+        );
+        let seek_response = client
+            .post(uri!(super::post_sequence_seek(name = "wave", t_ms = 500)))
+            .dispatch();
+        assert_eq!(seek_response.status(), Status::Ok);
+
+        let get_response = client.get(uri!(super::get_playback)).dispatch();
+        let status = get_response.into_json::<PlaybackStatus>().unwrap();
+        assert!(status.t_ms >= Some(500));
+
+        let rate_response = client
+            .post(uri!(super::post_sequence_rate(name = "wave", rate = 8.0)))
+            .dispatch();
+        assert_eq!(rate_response.status(), Status::BadRequest);
+        let rate_response = client
+            .post(uri!(super::post_sequence_rate(name = "wave", rate = 0.5)))
+            .dispatch();
+        assert_eq!(
+            rate_response.into_json::<PlaybackStatus>().unwrap().rate,
+            0.5
+        );
+        let region = LoopRegion {
+            start_ms: 0,
+            end_ms: 500,
+        };
+        let loop_response = client
+            .put(uri!(super::put_sequence_loop(name = "wave")))
+            .header(ContentType::JSON)
+            .body(json::to_string(&region).unwrap())
+            .dispatch();
+        assert_eq!(
+            loop_response
+                .into_json::<PlaybackStatus>()
+                .unwrap()
+                .loop_region,
+            Some(region)
+        );
+        let loop_response = client
+            .delete(uri!(super::delete_sequence_loop(name = "wave")))
+            .dispatch();
+        assert_eq!(
+            loop_response
+                .into_json::<PlaybackStatus>()
+                .unwrap()
+                .loop_region,
+            None
+        );
+
+        let stop_response = client.post(uri!(super::post_playback_stop)).dispatch();
+        assert_eq!(stop_response.status(), Status::Ok);
+        let get_response = client.get(uri!(super::get_playback)).dispatch();
+        assert_eq!(get_response.status(), Status::NotFound);
+
+        // Replayed onto another rig's channels, at another pace
+        let play = |pace: Option<f64>, remap: &str| {
+            client
+                .post(uri!(super::post_sequence_play(
+                    name = "wave",
+                    sync_timeout_ms = _,
+                    rate = pace,
+                    remap = Some(remap)
+                )))
+                .dispatch()
+        };
+        assert_eq!(play(None, "0-4").status(), Status::BadRequest);
+        assert_eq!(play(None, "0:16").status(), Status::BadRequest);
+        assert_eq!(play(None, "0:4").status(), Status::BadRequest);
+        let response = play(Some(2.0), " 0:0, ");
+        assert_eq!(response.into_json::<PlaybackStatus>().unwrap().rate, 2.0);
+    }
+
+    fn create_mock_with_allowed_networks(allowed_networks: Vec<&str>) -> Rocket<Build> {
+        create_mock()
+            .configure(rocket::Config::figment().merge(("allowed_networks", allowed_networks)))
+    }
+
+    #[test]
+    fn network_policy() {
+        let client = Client::tracked(create_mock_with_allowed_networks(vec!["10.0.0.0/8"]))
+            .expect("valid rocket instance");
+        let config = create_test_config();
+
+        let denied_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .remote("192.168.1.2:8000".parse().unwrap())
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(denied_response.status(), Status::Forbidden);
+
+        let unknown_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(unknown_response.status(), Status::Forbidden);
+
+        let read_response = client
+            .get(uri!(super::get_status))
+            .remote("192.168.1.2:8000".parse().unwrap())
+            .dispatch();
+        assert_eq!(read_response.status(), Status::Ok);
+
+        let allowed_response = client
+            .post(uri!(super::post_channel(persist = _)))
+            .remote("10.1.2.3:8000".parse().unwrap())
+            .header(ContentType::JSON)
+            .body(json::to_string(&config).unwrap())
+            .dispatch();
+        assert_eq!(allowed_response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn network_policy_interpolated() {
+        std::env::set_var("PCA9685_SERVICE_ALLOWED_NETWORKS", "10.0.0.0/8,192.168.1.7");
+        let client = Client::tracked(create_mock_with_allowed_networks(vec![
+            "${PCA9685_SERVICE_ALLOWED_NETWORKS}",
+        ]))
+        .expect("valid rocket instance");
+
+        for (remote, status) in [
+            ("10.1.2.3:8000", Status::Ok),
+            ("192.168.1.7:8000", Status::Ok),
+            ("192.168.1.8:8000", Status::Forbidden),
+        ] {
+            let response = client
+                .post(uri!(super::post_heartbeat))
+                .remote(remote.parse().unwrap())
+                .dispatch();
+            assert_eq!(response.status(), status);
+        }
+
+        match Client::tracked(create_mock_with_allowed_networks(vec![
+            "${PCA9685_SERVICE_ALLOWED_NETWORKS_UNSET}",
+        ])) {
+            Ok(_) => panic!("unset environment variable accepted"),
+            Err(error) => assert!(matches!(error.kind(), ErrorKind::FailedFairings(_))),
+        }
+    }
+
+    #[test]
+    fn network_policy_invalid() {
+        match Client::tracked(create_mock_with_allowed_networks(vec!["10.0.0/8"])) {
+            Ok(_) => panic!("invalid allowed_networks accepted"),
+            Err(error) => assert!(matches!(error.kind(), ErrorKind::FailedFairings(_))),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use pca9685::ChannelMetrics;
+use std::fmt::Write;
+
+/// Renders `metrics` in the Prometheus text exposition format, each series
+/// labeled by `channel` number and `name` (empty if the Channel has none)
+pub fn render(metrics: &[ChannelMetrics]) -> String {
+    let labels = |metrics: &ChannelMetrics| {
+        format!(
+            "channel=\"{}\",name=\"{}\"",
+            metrics.channel as u8,
+            metrics.name.as_deref().unwrap_or_default()
+        )
+    };
+
+    let mut text = String::new();
+    let mut family = |name: &str, kind: &str, help: &str, samples: Vec<(String, String)>| {
+        let _ = writeln!(text, "# HELP pca9685_channel_{} {}", name, help);
+        let _ = writeln!(text, "# TYPE pca9685_channel_{} {}", name, kind);
+        for (labels, value) in samples {
+            let _ = writeln!(text, "pca9685_channel_{}{{{}}} {}", name, labels, value);
+        }
+    };
+
+    family(
+        "commands_total",
+        "counter",
+        "Commands received, by type.",
+        metrics
+            .iter()
+            .flat_map(|metrics| {
+                metrics
+                    .commands_total
+                    .iter()
+                    .map(move |(command_type, total)| {
+                        (
+                            format!("{},type=\"{}\"", labels(metrics), command_type),
+                            total.to_string(),
+                        )
+                    })
+            })
+            .collect(),
+    );
+    family(
+        "limit_violations_total",
+        "counter",
+        "Commands rejected by the channel's limits.",
+        metrics
+            .iter()
+            .map(|metrics| (labels(metrics), metrics.limit_violations_total.to_string()))
+            .collect(),
+    );
+    family(
+        "time_since_last_command_seconds",
+        "gauge",
+        "Time since the channel was last commanded.",
+        metrics
+            .iter()
+            .filter_map(|metrics| {
+                metrics
+                    .time_since_last_command_s
+                    .map(|seconds| (labels(metrics), format!("{:.3}", seconds)))
+            })
+            .collect(),
+    );
+    family(
+        "current_count",
+        "gauge",
+        "Current output, in counts (0 while full off).",
+        metrics
+            .iter()
+            .map(|metrics| {
+                (
+                    labels(metrics),
+                    metrics.current_count.unwrap_or_default().to_string(),
+                )
+            })
+            .collect(),
+    );
+    family(
+        "motion_in_progress",
+        "gauge",
+        "1 while the channel is moving toward its target.",
+        metrics
+            .iter()
+            .map(|metrics| {
+                (
+                    labels(metrics),
+                    (metrics.motion_in_progress as u8).to_string(),
+                )
+            })
+            .collect(),
+    );
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+    use pca9685::ChannelMetrics;
+    use pwm_pca9685::Channel;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn render_metrics() {
+        let metrics = vec![
+            ChannelMetrics {
+                channel: Channel::C0,
+                name: Some("left_elbow".to_string()),
+                commands_total: BTreeMap::from([("PulseWidth".to_string(), 3)]),
+                limit_violations_total: 1,
+                time_since_last_command_s: Some(1.5),
+                current_count: Some(1500),
+                motion_in_progress: false,
+            },
+            ChannelMetrics {
+                channel: Channel::C1,
+                name: None,
+                commands_total: BTreeMap::new(),
+                limit_violations_total: 0,
+                time_since_last_command_s: None,
+                current_count: None,
+                motion_in_progress: false,
+            },
+        ];
+
+        let text = render(&metrics);
+        assert!(text.contains(
+            "pca9685_channel_commands_total{channel=\"0\",name=\"left_elbow\",type=\"PulseWidth\"} 3\n"
+        ));
+        assert!(text.contains(
+            "pca9685_channel_limit_violations_total{channel=\"0\",name=\"left_elbow\"} 1\n"
+        ));
+        assert!(text.contains(
+            "pca9685_channel_time_since_last_command_seconds{channel=\"0\",name=\"left_elbow\"} 1.500\n"
+        ));
+        assert!(!text.contains("pca9685_channel_time_since_last_command_seconds{channel=\"1\""));
+        assert!(text.contains("pca9685_channel_current_count{channel=\"1\",name=\"\"} 0\n"));
+        assert!(text.contains("# TYPE pca9685_channel_motion_in_progress gauge\n"));
+    }
+}
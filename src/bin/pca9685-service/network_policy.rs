@@ -0,0 +1,207 @@
+use pca9685::utils;
+use rocket::fairing::{self, Fairing, Info, Kind};
+use rocket::http::Method;
+use rocket::serde::Deserialize;
+use rocket::{Build, Data, Request, Rocket};
+use std::net::IpAddr;
+use std::path::Path;
+use std::str::FromStr;
+
+/// An IPv4 or IPv6 network in CIDR notation (e.g., 192.168.1.0/24). A bare
+/// address is treated as a single host.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    pub fn contains(&self, address: &IpAddr) -> bool {
+        let address = match address {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(*address, IpAddr::V4),
+            IpAddr::V4(_) => *address,
+        };
+
+        match (self.network, address) {
+            (IpAddr::V4(network), IpAddr::V4(address)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(address) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(address)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(address) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (address, prefix_len) = match value.split_once('/') {
+            Some((address, prefix_len)) => (address, Some(prefix_len)),
+            None => (value, None),
+        };
+
+        let network = IpAddr::from_str(address.trim())
+            .map_err(|_| format!("Invalid network address '{}'.", value))?;
+        let max_prefix_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => prefix_len
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix_len| *prefix_len <= max_prefix_len)
+                .ok_or(format!("Invalid prefix length in '{}'.", value))?,
+            None => max_prefix_len,
+        };
+
+        Ok(Cidr {
+            network,
+            prefix_len,
+        })
+    }
+}
+
+/// Service configuration, read from Rocket's configuration sources (e.g.,
+/// `rocket.toml` or `ROCKET_ALLOWED_NETWORKS`)
+#[derive(Debug, Default, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct NetworkPolicyConfig {
+    /// Client networks (CIDR) permitted to issue mutating requests. When
+    /// empty, every client is permitted.  Each may refer to environment
+    /// variables or files (see [utils::interpolate]; relative to the working
+    /// directory), which may list several networks, separated by commas.
+    #[serde(default)]
+    allowed_networks: Vec<String>,
+}
+
+struct AllowedNetworks(Vec<Cidr>);
+
+/// Restricts mutating requests (POST, PUT, PATCH, DELETE) to clients within
+/// the configured `allowed_networks`; read-only requests are always served.
+/// Denied requests are rerouted to `GET /network-policy/denied` (403).
+///
+/// The check is made against the peer address of the connection, not
+/// forwarding headers, so this is only meaningful on an isolated network
+/// where the service is reached directly.
+pub struct NetworkPolicy;
+
+#[rocket::async_trait]
+impl Fairing for NetworkPolicy {
+    fn info(&self) -> Info {
+        Info {
+            name: "Network policy",
+            kind: Kind::Ignite | Kind::Request,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let config = match rocket.figment().extract::<NetworkPolicyConfig>() {
+            Ok(config) => config,
+            Err(error) => {
+                log::error!(target: "server", "Invalid network policy: {}", error);
+                return Err(rocket);
+            }
+        };
+
+        let mut allowed_networks = Vec::new();
+        for network in &config.allowed_networks {
+            match utils::interpolate(network, Path::new("")).and_then(|network| {
+                network
+                    .split(',')
+                    .map(Cidr::from_str)
+                    .collect::<Result<Vec<Cidr>, String>>()
+            }) {
+                Ok(cidrs) => allowed_networks.extend(cidrs),
+                Err(error) => {
+                    log::error!(target: "server", "Invalid network policy: {}", error);
+                    return Err(rocket);
+                }
+            }
+        }
+
+        if !allowed_networks.is_empty() {
+            log::info!(target: "server", "Mutating requests restricted to {:?}", config.allowed_networks);
+        }
+
+        Ok(rocket.manage(AllowedNetworks(allowed_networks)))
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        let mutating = matches!(
+            request.method(),
+            Method::Post | Method::Put | Method::Patch | Method::Delete
+        );
+        if !mutating {
+            return;
+        }
+
+        let allowed_networks = match request.rocket().state::<AllowedNetworks>() {
+            Some(allowed_networks) if !allowed_networks.0.is_empty() => allowed_networks,
+            _ => return,
+        };
+
+        let remote = request.remote().map(|remote| remote.ip());
+        let allowed = match remote {
+            Some(remote) => allowed_networks
+                .0
+                .iter()
+                .any(|network| network.contains(&remote)),
+            None => false,
+        };
+
+        if !allowed {
+            log::warn!(target: "server", "Denied {} {} from {:?}", request.method(), request.uri(), remote);
+            request.set_method(Method::Get);
+            request.set_uri(uri!(crate::get_network_policy_denied));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cidr;
+    use std::net::IpAddr;
+    use std::str::FromStr;
+
+    fn ip(address: &str) -> IpAddr {
+        IpAddr::from_str(address).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        assert!(Cidr::from_str("10.0.0.0/8").is_ok());
+        assert!(Cidr::from_str("192.168.1.7").is_ok());
+        assert!(Cidr::from_str("fd00::/8").is_ok());
+        assert!(Cidr::from_str("10.0.0.0/33").is_err());
+        assert!(Cidr::from_str("10.0.0/8").is_err());
+        assert!(Cidr::from_str("10.0.0.0/x").is_err());
+    }
+
+    #[test]
+    fn contains() {
+        let cidr = Cidr::from_str("10.0.0.0/8").unwrap();
+        assert!(cidr.contains(&ip("10.1.2.3")));
+        assert!(cidr.contains(&ip("::ffff:10.1.2.3")));
+        assert!(!cidr.contains(&ip("192.168.1.7")));
+        assert!(!cidr.contains(&ip("fd00::1")));
+
+        let host = Cidr::from_str("192.168.1.7").unwrap();
+        assert!(host.contains(&ip("192.168.1.7")));
+        assert!(!host.contains(&ip("192.168.1.8")));
+
+        let any = Cidr::from_str("0.0.0.0/0").unwrap();
+        assert!(any.contains(&ip("192.168.1.7")));
+
+        let v6 = Cidr::from_str("fd00::/8").unwrap();
+        assert!(v6.contains(&ip("fd12::1")));
+        assert!(!v6.contains(&ip("fe80::1")));
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{I2cBus, I2cError, NoI2c, SharedI2cBus};
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+impl<I2C, E> I2cBus for I2C
+where
+    I2C: Write<Error = E> + WriteRead<Error = E> + Send + 'static,
+    E: Debug + Send + Sync + 'static,
+{
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), I2cError> {
+        Write::write(self, address, bytes).map_err(|error| Box::new(error) as I2cError)
+    }
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), I2cError> {
+        WriteRead::write_read(self, address, bytes, buffer)
+            .map_err(|error| Box::new(error) as I2cError)
+    }
+}
+
+impl Write for NoI2c {
+    type Error = NoI2c;
+
+    fn write(&mut self, _address: u8, _bytes: &[u8]) -> Result<(), NoI2c> {
+        match *self {}
+    }
+}
+
+impl WriteRead for NoI2c {
+    type Error = NoI2c;
+
+    fn write_read(&mut self, _address: u8, _bytes: &[u8], _buffer: &mut [u8]) -> Result<(), NoI2c> {
+        match *self {}
+    }
+}
+
+impl Debug for NoI2c {
+    fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {}
+    }
+}
+
+impl<I2C> Clone for SharedI2cBus<I2C> {
+    fn clone(&self) -> Self {
+        SharedI2cBus(self.0.clone())
+    }
+}
+
+impl<I2C: I2cBus> SharedI2cBus<I2C> {
+    pub(crate) fn new(bus: I2C) -> Self {
+        SharedI2cBus(Arc::new(Mutex::new(bus)))
+    }
+
+    /// Writes `bytes` to the device at `address`, whichever device that is
+    pub(crate) fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), I2cError> {
+        I2cBus::write(&mut *self.0.lock().unwrap(), address, bytes)
+    }
+}
+
+impl<I2C: I2cBus> Write for SharedI2cBus<I2C> {
+    type Error = I2cError;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), I2cError> {
+        SharedI2cBus::write(self, address, bytes)
+    }
+}
+
+impl<I2C: I2cBus> WriteRead for SharedI2cBus<I2C> {
+    type Error = I2cError;
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), I2cError> {
+        I2cBus::write_read(&mut *self.0.lock().unwrap(), address, bytes, buffer)
+    }
+}
+
+/// Waits by sleeping the calling thread, e.g. for the PCA9685's oscillator
+/// to stabilize
+pub(crate) struct ThreadDelay;
+
+impl DelayUs<u16> for ThreadDelay {
+    fn delay_us(&mut self, us: u16) {
+        std::thread::sleep(Duration::from_micros(us as u64));
+    }
+}
@@ -1,40 +1,1081 @@
-use log;
+use crate::feedback::Feedback;
+use crate::filters::DualRateFilter;
+use crate::jitter::Jitter;
+use crate::low_pass::LowPass;
+use crate::pid::Pid;
+use crate::shaping::InputShaper;
 use pwm_pca9685::Channel;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use crate::{
-    ChannelConfig, ChannelLimits, ChannelProxy, Pca9685Error, Pca9685Proxy, Pca9685Result,
-    PcaClockConfig, PCA_PWM_RESOLUTION,
+    ChannelCalibration, ChannelCommand, ChannelConfig, ChannelFilter, ChannelHistoryEntry,
+    ChannelLimits, ChannelMetrics, ChannelProxy, ChannelStats, ChannelTarget, CircuitBreakerConfig,
+    CommandType, DriverError, DriverErrorRecord, FailsafeOutput, InputShaping, JitterConfig,
+    LowPassConfig, PauseMode, Pca9685Error, Pca9685Event, Pca9685Proxy, Pca9685Result,
+    PcaClockConfig, PercentBasis, PidGains, PidSample, PositionErrorRecord, Rate, ThermalLimits,
+    CHANNEL_HISTORY_CAPACITY, PCA_PWM_RESOLUTION,
 };
 
 impl ChannelProxy {
     pub fn new(channel: Channel, clock_config: PcaClockConfig) -> ChannelProxy {
         ChannelProxy {
-            name: String::from(format!("Channel {:?}", channel)),
+            name: format!("Channel {:?}", channel),
             config: ChannelConfig {
-                channel: channel,
+                channel,
                 current_count: None,
                 custom_limits: None,
+                owner: None,
+                calibration: None,
+                inverted: false,
+                trim_counts: 0,
+                esc: None,
+                tags: Vec::new(),
+                reduced_hold: None,
+                thermal_limits: None,
+                dual_rate: None,
+                failsafe: None,
+                display_unit: None,
+                display_value: None,
+                name: None,
+                feedback: None,
+                pid: None,
+                input_shaping: None,
+                low_pass: None,
             },
-            clock_config: clock_config,
+            clock_config,
+            history: VecDeque::with_capacity(CHANNEL_HISTORY_CAPACITY),
+            last_write_at: None,
+            gated_off: false,
+            stats: ChannelStats {
+                channel,
+                energized_ms: 0,
+                travel_counts: 0,
+                duty: 0.0,
+                travel_counts_per_s: 0.0,
+                thermal_warning: false,
+                faulted: false,
+                under_maintenance: false,
+                position_error: false,
+                armed: false,
+            },
+            last_tick_at: None,
+            pending_travel_counts: 0,
+            filters: vec![Arc::new(DualRateFilter)],
+            failsafe_restore: None,
+            circuit_breaker: None,
+            strict: false,
+            driver_errors: VecDeque::new(),
+            pending_events: Vec::new(),
+            paused: None,
+            resume_pending: false,
+            commands_total: BTreeMap::new(),
+            limit_violations_total: 0,
+            last_command_at: None,
+            last_driver_error: None,
+            revision: 0,
+            revised_config: None,
+            jitter: None,
+            pid: None,
+            shaper: None,
+            low_pass: None,
+            pid_sample: None,
+            feedback: None,
+            feedback_error: None,
+            armed_at: None,
+            adjusted_count: None,
+        }
+    }
+
+    /// Arms the channel's ESC: minimum throttle is written, and anything
+    /// above it is refused until held for the ESC's `arming_ms` (see
+    /// [EscConfig]).  Arming an armed ESC re-arms it.
+    pub fn arm(
+        &mut self,
+        now: Instant,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<ChannelConfig> {
+        let Some(esc) = self.config.esc else {
+            return Err(Pca9685Error::InvalidCommand(format!(
+                "{} is not an ESC.",
+                self.name
+            )));
+        };
+
+        self.armed_at = None;
+        let config = self.write(ChannelTarget::Count(self.config.limits().0), None, pca)?;
+        self.armed_at = Some(now + Duration::from_millis(esc.arming_ms));
+        log::info!(target: &self.name, "Arming: holding minimum throttle for {}ms", esc.arming_ms);
+
+        Ok(config)
+    }
+
+    /// Disarms the channel's ESC, returning it to minimum throttle
+    pub fn disarm(&mut self, pca: &mut Box<dyn Pca9685Proxy>) -> Pca9685Result<ChannelConfig> {
+        if self.config.esc.is_none() {
+            return Err(Pca9685Error::InvalidCommand(format!(
+                "{} is not an ESC.",
+                self.name
+            )));
+        }
+
+        self.armed_at = None;
+        log::info!(target: &self.name, "Disarmed");
+        self.write(ChannelTarget::Count(self.config.limits().0), None, pca)
+    }
+
+    /// Returns true if the channel's ESC has held minimum throttle for its
+    /// `arming_ms` as of `now`
+    fn armed(&self, now: Instant) -> bool {
+        matches!(self.armed_at, Some(armed_at) if now >= armed_at)
+    }
+
+    /// Locks the channel out (or, given false, clears the lockout) for
+    /// maintenance.  Entering maintenance drives the channel to its failsafe
+    /// output (or full off); commands are then rejected until cleared.
+    pub fn set_maintenance(
+        &mut self,
+        under_maintenance: bool,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<ChannelConfig> {
+        if under_maintenance && !self.stats.under_maintenance {
+            let target = match self.config.failsafe {
+                Some(FailsafeOutput::Count(pwm_off_count)) => ChannelTarget::Count(pwm_off_count),
+                _ => ChannelTarget::FullOff,
+            };
+            self.write(target, None, pca)?;
+            self.armed_at = None;
+            log::warn!(target: &self.name, "Under maintenance: commands are rejected until cleared");
+        } else if !under_maintenance && self.stats.under_maintenance {
+            log::info!(target: &self.name, "Maintenance cleared");
+        }
+        self.stats.under_maintenance = under_maintenance;
+
+        Ok(self.config())
+    }
+
+    /// Stops writing to the device (see [PauseMode]) until
+    /// [ChannelProxy::resume].
+    pub fn pause(&mut self, mode: PauseMode) {
+        self.paused = Some(mode);
+    }
+
+    /// Resumes writing to the device, writing the channel's latest output if
+    /// it was commanded while paused (returning None if it was not).
+    pub fn resume(
+        &mut self,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<Option<ChannelConfig>> {
+        self.paused = None;
+        if !std::mem::take(&mut self.resume_pending) {
+            return Ok(None);
+        }
+
+        log::info!(target: &self.name, "Resuming output");
+        let owner = self.config.owner.clone();
+        self.write(self.current_target(), owner.as_deref(), pca)
+            .map(Some)
+    }
+
+    pub fn set_circuit_breaker(&mut self, circuit_breaker: Option<CircuitBreakerConfig>) {
+        self.circuit_breaker = circuit_breaker;
+    }
+
+    /// Requires (or, given false, no longer requires) `custom_limits` of the
+    /// channel before it is driven anywhere but full off (see
+    /// [crate::Config::strict])
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Clears a fault (see [CircuitBreakerConfig]), so that the channel
+    /// accepts commands again.
+    pub fn clear_fault(&mut self) -> ChannelStats {
+        if self.stats.faulted {
+            log::info!(target: &self.name, "Fault cleared");
+        }
+        self.stats.faulted = false;
+        self.driver_errors.clear();
+
+        self.stats()
+    }
+
+    /// Selects the channel's low or high rate (see [crate::DualRate]), returning
+    /// false if the channel has no dual rate configured.
+    pub fn select_rate(&mut self, rate: Rate) -> bool {
+        match self.config.dual_rate.as_mut() {
+            Some(dual_rate) => {
+                dual_rate.selected = rate;
+                log::info!(target: &self.name, "Selected {:?} rate", rate);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn add_filter(&mut self, filter: Arc<dyn ChannelFilter>) {
+        self.filters.push(filter);
+    }
+
+    /// Performs time-based behavior (e.g., [ReducedHold], activity tracking)
+    /// as of `now`, appending any resulting events to `events`.  Called
+    /// periodically by the [crate::Pca9685]'s worker.
+    pub fn tick(
+        &mut self,
+        now: Instant,
+        pca: &mut Box<dyn Pca9685Proxy>,
+        events: &mut Vec<Pca9685Event>,
+    ) -> Pca9685Result<()> {
+        self.track_activity(now, events);
+        self.restore_failsafe(now, pca)?;
+        self.reduce_hold(now, pca)?;
+        if self.jitter.is_none() && self.pid.is_none() {
+            self.apply_shaping(now, pca)?;
+        }
+        self.apply_pid(now, pca)?;
+        self.verify_position(now, pca);
+        events.append(&mut self.pending_events);
+        self.apply_jitter(now, pca)
+    }
+
+    /// Sets (or, given None, clears) the jitter superimposed on the channel's
+    /// output (see [JitterConfig]); once cleared, the output is restored.
+    pub fn set_jitter(
+        &mut self,
+        jitter: Option<JitterConfig>,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<ChannelConfig> {
+        if let Some(jitter) = &jitter {
+            jitter.validate()?;
+            if self.config.custom_limits.is_none() {
+                return Err(Pca9685Error::InvalidCommand(format!(
+                    "{} is not configured.",
+                    self.name
+                )));
+            }
+            log::warn!(target: &self.name, "Injecting jitter {:?}", jitter);
+        } else if self.jitter.is_some() {
+            log::info!(target: &self.name, "Jitter cleared");
+        }
+        self.jitter = jitter.map(Jitter::new);
+
+        if self.jitter.is_none() && self.pid.is_none() {
+            self.restore_adjusted_count(Instant::now(), pca)?;
+        }
+
+        Ok(self.config())
+    }
+
+    /// Writes the commanded count in place of an adjusted one (see
+    /// [ChannelProxy::apply_jitter], [ChannelProxy::apply_pid] and
+    /// [ChannelProxy::apply_shaping]), if any
+    fn restore_adjusted_count(
+        &mut self,
+        now: Instant,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<()> {
+        if self.adjusted_count.take().is_none() {
+            return Ok(());
+        }
+
+        if let Some(count) = self.config.current_count {
+            if !self.gated_off && self.paused.is_none() && count < PCA_PWM_RESOLUTION {
+                if let Err(error) =
+                    pca.set_channel_off_count(self.config.channel, self.trimmed(count))
+                {
+                    let error = Pca9685Error::Pca9685DriverError(error);
+                    self.record_driver_error(now, &error, pca);
+                    return Err(error);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the latest iteration of the channel's PID controller, if it
+    /// has one (see [PidGains])
+    pub fn pid_sample(&self) -> Option<PidSample> {
+        self.pid.as_ref().and(self.pid_sample)
+    }
+
+    /// Sets (or, given None, clears) the channel's PID controller, keeping
+    /// its state when only the gains change
+    fn configure_pid(&mut self, gains: Option<PidGains>) {
+        let (min_count, max_count) = self.config.limits();
+        let output_range = (max_count - min_count) as f64;
+
+        match (gains, self.pid.as_mut()) {
+            (Some(gains), Some(pid)) => {
+                if pid.gains() != gains {
+                    log::info!(target: &self.name, "Tuned PID {:?}", gains);
+                }
+                pid.tune(gains, output_range);
+            }
+            (Some(gains), None) => {
+                log::info!(target: &self.name, "Closed the loop with PID {:?}", gains);
+                self.pid = Some(Pid::new(gains, output_range));
+            }
+            (None, _) => {
+                self.pid = None;
+                self.pid_sample = None;
+            }
+        }
+        self.config.pid = gains;
+    }
+
+    /// Sets (or, given None, clears) the channel's input shaper, keeping its
+    /// state unless its configuration changes
+    fn configure_shaping(&mut self, input_shaping: Option<InputShaping>) {
+        if self.shaper.as_ref().map(InputShaper::config) != input_shaping {
+            if let Some(input_shaping) = &input_shaping {
+                log::info!(target: &self.name, "Shaping input with {:?}", input_shaping);
+            }
+            self.shaper = input_shaping.map(InputShaper::new);
         }
+        self.config.input_shaping = input_shaping;
     }
 
+    /// Sets (or, given None, clears) the channel's low-pass filter, keeping
+    /// its state unless its configuration changes
+    fn configure_low_pass(&mut self, low_pass: Option<LowPassConfig>) {
+        if self.low_pass.as_ref().map(LowPass::config) != low_pass {
+            if let Some(low_pass) = &low_pass {
+                log::info!(target: &self.name, "Smoothing with {:?}", low_pass);
+            }
+            self.low_pass = low_pass.map(LowPass::new);
+        }
+        self.config.low_pass = low_pass;
+    }
+
+    /// Returns the count to write in place of `target` as it is shaped (see
+    /// [InputShaping]) or smoothed (see [LowPassConfig]), moving from
+    /// `previous_count`; None if neither
+    fn shape(
+        &mut self,
+        now: Instant,
+        target: ChannelTarget,
+        previous_count: Option<u16>,
+    ) -> Option<u16> {
+        let previous_count = previous_count.filter(|count| *count < PCA_PWM_RESOLUTION);
+        let count = match target {
+            ChannelTarget::Count(count) if count < PCA_PWM_RESOLUTION => Some(count),
+            _ => None,
+        };
+        if let Some(shaper) = self.shaper.as_mut() {
+            match count {
+                Some(count) => shaper.command(now, previous_count, count),
+                None => shaper.clear(),
+            }
+        }
+        if let Some(low_pass) = self.low_pass.as_mut() {
+            match count {
+                Some(count) => low_pass.command(now, previous_count, count),
+                None => low_pass.clear(),
+            }
+        }
+
+        count.and_then(|_| self.shaped_count(now))
+    }
+
+    /// Returns the output of the channel's input shaper or low-pass filter as
+    /// of `now`; None once settled on the commanded count
+    fn shaped_count(&mut self, now: Instant) -> Option<u16> {
+        match (self.shaper.as_mut(), self.low_pass.as_mut()) {
+            (Some(shaper), _) => shaper.output(now),
+            (None, Some(low_pass)) => low_pass.output(now),
+            (None, None) => None,
+        }
+    }
+
+    /// Writes the shaped or smoothed output (see [InputShaping] and
+    /// [LowPassConfig]) in place of the commanded count until it settles,
+    /// then the commanded count.  A PID controller or jitter takes
+    /// precedence.
+    fn apply_shaping(
+        &mut self,
+        now: Instant,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<()> {
+        let shaped_count = match self.config.current_count {
+            Some(count) if count < PCA_PWM_RESOLUTION => self.shaped_count(now),
+            _ => None,
+        };
+        let Some(shaped_count) = shaped_count else {
+            return self.restore_adjusted_count(now, pca);
+        };
+        if self.gated_off
+            || self.stats.faulted
+            || self.stats.under_maintenance
+            || self.paused.is_some()
+            || self.adjusted_count == Some(shaped_count)
+        {
+            return Ok(());
+        }
+
+        if let Err(error) =
+            pca.set_channel_off_count(self.config.channel, self.trimmed(shaped_count))
+        {
+            let error = Pca9685Error::Pca9685DriverError(error);
+            self.record_driver_error(now, &error, pca);
+            return Err(error);
+        }
+        self.adjusted_count = Some(shaped_count);
+
+        Ok(())
+    }
+
+    /// Reads the channel's raw feedback, if it has any (see [FeedbackConfig])
+    pub fn raw_feedback(&mut self) -> Pca9685Result<Option<f64>> {
+        self.feedback.as_mut().map(Feedback::read).transpose()
+    }
+
+    /// Reads the channel's feedback in counts (see [FeedbackConfig::to_count]),
+    /// logging once should it become unreadable
+    fn read_feedback(&mut self) -> Option<f64> {
+        let limits = self.config.limits();
+        let feedback = self.feedback.as_mut()?;
+        let result = feedback
+            .read()
+            .map(|raw| feedback.config().to_count(raw, limits));
+
+        match result {
+            Ok(count) => {
+                if self.feedback_error.take().is_some() {
+                    log::info!(target: &self.name, "Feedback recovered");
+                }
+                count
+            }
+            Err(error) => {
+                let error = error.to_string();
+                if self.feedback_error.as_ref() != Some(&error) {
+                    log::warn!(target: &self.name, "{}", error);
+                    self.feedback_error = Some(error);
+                }
+                None
+            }
+        }
+    }
+
+    /// Compares the channel's feedback with its settled output (see
+    /// [PositionVerification]), raising [Pca9685Event::PositionError] (and
+    /// faulting the channel, if so configured) once they diverge
+    fn verify_position(&mut self, now: Instant, pca: &mut Box<dyn Pca9685Proxy>) {
+        let Some(verify) = self
+            .config
+            .feedback
+            .as_ref()
+            .and_then(|feedback| feedback.verify)
+        else {
+            return;
+        };
+        let Some(commanded) = self
+            .config
+            .current_count
+            .filter(|count| *count < PCA_PWM_RESOLUTION)
+        else {
+            return;
+        };
+        let settled = matches!(self.last_write_at, Some(at) if now.saturating_duration_since(at) >= Duration::from_millis(verify.settle_ms));
+        if !settled
+            || self.gated_off
+            || self.stats.faulted
+            || self.stats.under_maintenance
+            || self.paused.is_some()
+        {
+            return;
+        }
+        let Some(measured) = self.read_feedback() else {
+            return;
+        };
+
+        let diverged = (measured - commanded as f64).abs() > verify.tolerance_counts as f64;
+        if diverged && !self.stats.position_error {
+            log::warn!(target: &self.name, "Position error: commanded {} but measured {:0.1}", commanded, measured);
+            self.pending_events
+                .push(Pca9685Event::PositionError(PositionErrorRecord {
+                    channel: self.config.channel,
+                    commanded,
+                    measured,
+                    timestamp_ms: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map_or(0, |elapsed| elapsed.as_millis() as u64),
+                }));
+            self.stats.position_error = true;
+
+            if verify.fault {
+                log::error!(target: &self.name, "Faulted by position error");
+                self.fault(pca);
+            }
+        } else if !diverged && self.stats.position_error {
+            log::info!(target: &self.name, "Position error cleared");
+            self.stats.position_error = false;
+        }
+    }
+
+    /// Writes the commanded count corrected by the PID controller (if any),
+    /// clamped to the channel's limits, without changing the commanded
+    /// output.  Should the feedback become unreadable, the commanded count is
+    /// written instead until it recovers.
+    fn apply_pid(&mut self, now: Instant, pca: &mut Box<dyn Pca9685Proxy>) -> Pca9685Result<()> {
+        let Some(setpoint) = self.config.current_count else {
+            return Ok(());
+        };
+        if self.pid.is_none()
+            || setpoint == PCA_PWM_RESOLUTION
+            || self.gated_off
+            || self.stats.faulted
+            || self.stats.under_maintenance
+            || self.paused.is_some()
+        {
+            return Ok(());
+        }
+
+        let Some(feedback) = self.read_feedback() else {
+            return self.restore_adjusted_count(now, pca);
+        };
+        let (min_count, max_count) = self.config.limits();
+
+        let error = setpoint as f64 - feedback;
+        let correction = self.pid.as_mut().unwrap().update(error, now);
+        let output = (setpoint as f64 + correction)
+            .round()
+            .clamp(min_count as f64, max_count as f64) as u16;
+        self.pid_sample = Some(PidSample {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|since_epoch| since_epoch.as_millis() as u64)
+                .unwrap_or_default(),
+            setpoint,
+            feedback,
+            error,
+            output,
+        });
+        if self.adjusted_count == Some(output) {
+            return Ok(());
+        }
+
+        if let Err(error) = pca.set_channel_off_count(self.config.channel, self.trimmed(output)) {
+            let error = Pca9685Error::Pca9685DriverError(error);
+            self.record_driver_error(now, &error, pca);
+            return Err(error);
+        }
+        self.adjusted_count = Some(output);
+
+        Ok(())
+    }
+
+    pub fn jitter(&self) -> Option<JitterConfig> {
+        self.jitter.as_ref().map(Jitter::config)
+    }
+
+    /// Writes the commanded count plus the jitter's offset, clamped to the
+    /// channel's limits, without changing the commanded output.  A PID
+    /// controller takes precedence over jitter.
+    fn apply_jitter(&mut self, now: Instant, pca: &mut Box<dyn Pca9685Proxy>) -> Pca9685Result<()> {
+        let (Some(jitter), Some(count)) = (self.jitter.as_mut(), self.config.current_count) else {
+            return Ok(());
+        };
+        if self.pid.is_some()
+            || count == PCA_PWM_RESOLUTION
+            || self.gated_off
+            || self.stats.faulted
+            || self.stats.under_maintenance
+            || self.paused.is_some()
+        {
+            return Ok(());
+        }
+
+        let (min_count, max_count) = self.config.custom_limits.unwrap_or_default().count_limits();
+        let offset = jitter.offset(now, &mut rand::thread_rng());
+        let adjusted_count = (count as f64 + offset)
+            .round()
+            .clamp(min_count as f64, max_count as f64) as u16;
+        if self.adjusted_count == Some(adjusted_count) {
+            return Ok(());
+        }
+
+        if let Err(error) =
+            pca.set_channel_off_count(self.config.channel, self.trimmed(adjusted_count))
+        {
+            let error = Pca9685Error::Pca9685DriverError(error);
+            self.record_driver_error(now, &error, pca);
+            return Err(error);
+        }
+        self.adjusted_count = Some(adjusted_count);
+
+        Ok(())
+    }
+
+    /// Drives the channel to its failsafe output (if configured), returning
+    /// None if it has none.  Given `restore_at`, the prior output is restored
+    /// at that time (see [ChannelProxy::tick]), unless the channel is
+    /// commanded in the meantime.
+    pub fn apply_failsafe(
+        &mut self,
+        restore_at: Option<Instant>,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<Option<ChannelConfig>> {
+        let target = match self.config.failsafe {
+            Some(FailsafeOutput::FullOff) => ChannelTarget::FullOff,
+            Some(FailsafeOutput::Count(pwm_off_count)) => ChannelTarget::Count(pwm_off_count),
+            None => return Ok(None),
+        };
+
+        let previous = self.current_target();
+
+        log::warn!(target: &self.name, "Applying failsafe output {:?}", self.config.failsafe);
+        let config = self.write(target, None, pca)?;
+        self.failsafe_restore = restore_at.map(|restore_at| (restore_at, previous));
+
+        Ok(Some(config))
+    }
+
+    fn restore_failsafe(
+        &mut self,
+        now: Instant,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<()> {
+        match self.failsafe_restore {
+            Some((restore_at, previous)) if now >= restore_at => {
+                self.failsafe_restore = None;
+                log::info!(target: &self.name, "Restoring output after failsafe");
+                self.write(previous, None, pca).map(|_| ())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Rewrites the channel's output as last written, e.g. once the device
+    /// has been reset: full off if gated off (see [ReducedHold]), otherwise
+    /// its adjusted count (see [ChannelProxy::apply_jitter]), if any, else
+    /// its commanded count.  While paused, the output is written on resume.
+    pub fn rewrite(&mut self, pca: &mut Box<dyn Pca9685Proxy>) -> Pca9685Result<()> {
+        if self.paused.is_some() {
+            self.resume_pending = true;
+            return Ok(());
+        }
+
+        let channel = self.config.channel;
+        let result = match (self.config.current_count, self.adjusted_count) {
+            (None, _) => pca.set_channel_full_off(channel),
+            _ if self.gated_off => pca.set_channel_full_off(channel),
+            (Some(PCA_PWM_RESOLUTION), _) => pca.set_channel_full_on(channel),
+            (Some(_), Some(adjusted_count)) => {
+                pca.set_channel_off_count(channel, self.trimmed(adjusted_count))
+            }
+            (Some(count), None) => pca.set_channel_off_count(channel, self.trimmed(count)),
+        };
+        if let Err(error) = result {
+            let error = Pca9685Error::Pca9685DriverError(error);
+            self.record_driver_error(Instant::now(), &error, pca);
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the channel's current output as a [ChannelTarget]
+    fn current_target(&self) -> ChannelTarget {
+        count_target(self.config.current_count)
+    }
+
+    /// Reconfigures the channel per `config` (see [ChannelProxy::configure])
+    /// and writes its `current_count` (or full off) exactly, bypassing the
+    /// channel's filters, attributed to its `owner`.
+    pub fn restore(
+        &mut self,
+        config: &ChannelConfig,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<ChannelConfig> {
+        self.check_restore(config)?;
+        self.configure(config)?;
+
+        self.write(
+            count_target(config.current_count),
+            config.owner.as_deref(),
+            pca,
+        )
+    }
+
+    /// Checks that [ChannelProxy::configure] with `config` would succeed,
+    /// without changing anything, returning the channel as it would be
+    fn check_configure(&self, config: &ChannelConfig) -> Pca9685Result<ChannelProxy> {
+        let mut configured = ChannelProxy::new(self.config.channel, self.clock_config);
+        configured.configure_unchecked(config)?;
+
+        Ok(configured)
+    }
+
+    /// Checks that [ChannelProxy::restore] of `config` would succeed, without
+    /// changing anything.
+    pub fn check_restore(&self, config: &ChannelConfig) -> Pca9685Result<()> {
+        self.check_accepts_commands()?;
+
+        let restored = self.check_configure(config)?;
+        match count_target(config.current_count) {
+            ChannelTarget::Count(pwm_off_count) => restored.check_limits(pwm_off_count),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn stats(&self) -> ChannelStats {
+        ChannelStats {
+            armed: self.armed(Instant::now()),
+            ..self.stats.clone()
+        }
+    }
+
+    pub fn metrics(&self, now: Instant) -> ChannelMetrics {
+        ChannelMetrics {
+            channel: self.config.channel,
+            name: self.config.name.clone(),
+            commands_total: self.commands_total.clone(),
+            limit_violations_total: self.limit_violations_total,
+            time_since_last_command_s: self.last_command_at.map(|last_command_at| {
+                now.saturating_duration_since(last_command_at).as_secs_f64()
+            }),
+            current_count: self.config.current_count,
+            motion_in_progress: !self.is_settled(),
+        }
+    }
+
+    /// Returns the most recent driver error writing the channel, if any
+    pub fn last_driver_error(&self) -> Option<&DriverErrorRecord> {
+        self.last_driver_error.as_ref()
+    }
+
+    /// Returns the error last reading the channel's feedback, if it is failing
+    pub fn feedback_error(&self) -> Option<&String> {
+        self.feedback_error.as_ref()
+    }
+
+    /// Counts a `command_type` command toward the channel's metrics, along
+    /// with any limit violation in its `result`
+    pub fn count_command<T>(&mut self, command_type: CommandType, result: &Pca9685Result<T>) {
+        *self
+            .commands_total
+            .entry(format!("{:?}", command_type))
+            .or_default() += 1;
+        if let Err(Pca9685Error::CustomLimitsError(..)) = result {
+            self.limit_violations_total += 1;
+        }
+        self.last_command_at = Some(Instant::now());
+    }
+
+    fn track_activity(&mut self, now: Instant, events: &mut Vec<Pca9685Event>) {
+        let Some(last_tick_at) = self.last_tick_at.replace(now) else {
+            return;
+        };
+        let elapsed = now.saturating_duration_since(last_tick_at);
+        if elapsed.is_zero() {
+            return;
+        }
+
+        let energized = self.config.current_count.is_some() && !self.gated_off;
+        if energized {
+            self.stats.energized_ms += elapsed.as_millis() as u64;
+        }
+
+        // Exponential moving averages, with time constant window_ms
+        let window_ms = self
+            .config
+            .thermal_limits
+            .map_or(ThermalLimits::default_window_ms(), |limits| {
+                limits.window_ms
+            });
+        let alpha = 1.0 - (-(elapsed.as_secs_f64() * 1000.0) / window_ms.max(1) as f64).exp();
+        let travel_counts_per_s = self.pending_travel_counts as f64 / elapsed.as_secs_f64();
+        self.pending_travel_counts = 0;
+
+        self.stats.duty += alpha * (if energized { 1.0 } else { 0.0 } - self.stats.duty);
+        self.stats.travel_counts_per_s +=
+            alpha * (travel_counts_per_s - self.stats.travel_counts_per_s);
+
+        let exceeded = matches!(
+            self.config.thermal_limits,
+            Some(limits) if limits.is_exceeded(&self.stats)
+        );
+        if exceeded && !self.stats.thermal_warning {
+            log::warn!(
+                target: &self.name,
+                "Thermal warning: duty {:0.3}, travel {:0.1} counts/s",
+                self.stats.duty,
+                self.stats.travel_counts_per_s
+            );
+            events.push(Pca9685Event::ThermalWarning(self.stats()));
+        }
+        self.stats.thermal_warning = exceeded;
+    }
+
+    fn reduce_hold(&mut self, now: Instant, pca: &mut Box<dyn Pca9685Proxy>) -> Pca9685Result<()> {
+        let (Some(reduced_hold), Some(count), Some(last_write_at)) = (
+            self.config.reduced_hold,
+            self.config.current_count,
+            self.last_write_at,
+        ) else {
+            return Ok(());
+        };
+
+        let gate_off = !reduced_hold.is_energized(now.saturating_duration_since(last_write_at));
+        if gate_off == self.gated_off
+            || count == PCA_PWM_RESOLUTION
+            || self.stats.faulted
+            || self.stats.under_maintenance
+            || self.paused.is_some()
+        {
+            return Ok(());
+        }
+
+        let result = if gate_off {
+            pca.set_channel_full_off(self.config.channel)
+        } else {
+            pca.set_channel_off_count(self.config.channel, self.trimmed(count))
+        };
+        if let Err(error) = result {
+            let error = Pca9685Error::Pca9685DriverError(error);
+            self.record_driver_error(now, &error, pca);
+            return Err(error);
+        }
+
+        log::trace!(target: &self.name, "Reduced hold: output {}", if gate_off { "gated off" } else { "restored" });
+        self.gated_off = gate_off;
+        self.adjusted_count = None;
+
+        Ok(())
+    }
+
+    /// Returns the recorded output history, keeping only the most recent
+    /// entry within each `resolution_ms` window (if given).
+    pub fn history(&self, resolution_ms: Option<u64>) -> Vec<ChannelHistoryEntry> {
+        match resolution_ms {
+            Some(resolution_ms) if resolution_ms > 0 => {
+                let mut downsampled: Vec<ChannelHistoryEntry> = Vec::new();
+                for entry in &self.history {
+                    match downsampled.last_mut() {
+                        Some(last)
+                            if last.timestamp_ms / resolution_ms
+                                == entry.timestamp_ms / resolution_ms =>
+                        {
+                            *last = entry.clone()
+                        }
+                        _ => downsampled.push(entry.clone()),
+                    }
+                }
+                downsampled
+            }
+            _ => self.history.iter().cloned().collect(),
+        }
+    }
+
+    /// Returns true if the channel's output has reached its commanded value.
+    /// Commands are written in a single step, so this is the case once a
+    /// command returns, unless its output is still being shaped (see
+    /// [InputShaping]) or smoothed (see [LowPassConfig]).
+    pub fn is_settled(&self) -> bool {
+        let now = Instant::now();
+
+        self.shaper
+            .as_ref()
+            .is_none_or(|shaper| shaper.is_settled(now))
+            && self
+                .low_pass
+                .as_ref()
+                .is_none_or(|low_pass| low_pass.is_settled(now))
+    }
+
+    fn record_history(&mut self) {
+        if self.history.len() == CHANNEL_HISTORY_CAPACITY {
+            self.history.pop_front();
+        }
+
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since_epoch| since_epoch.as_millis() as u64)
+            .unwrap_or_default();
+
+        self.history.push_back(ChannelHistoryEntry {
+            timestamp_ms,
+            count: self.config.current_count,
+            client_id: self.config.owner.clone(),
+        });
+    }
+
+    /// Configures the channel per `config` (all or nothing: an invalid
+    /// `config` leaves the channel unchanged).  The output is unaffected.
     pub fn configure(&mut self, config: &ChannelConfig) -> Pca9685Result<ChannelConfig> {
-        self.configure_limits(&config.custom_limits)
+        self.check_configure(config)?;
+
+        self.configure_unchecked(config)
+    }
+
+    /// Configures the channel per `config`, stopping at the first invalid
+    /// setting (see [ChannelProxy::configure])
+    fn configure_unchecked(&mut self, config: &ChannelConfig) -> Pca9685Result<ChannelConfig> {
+        if let Some(reduced_hold) = &config.reduced_hold {
+            reduced_hold.validate()?;
+        }
+        if let Some(dual_rate) = &config.dual_rate {
+            dual_rate.validate()?;
+        }
+        if let Some(display_unit) = &config.display_unit {
+            display_unit.validate()?;
+        }
+        if let Some(feedback) = &config.feedback {
+            feedback.validate()?;
+        }
+        if let Some(esc) = &config.esc {
+            esc.validate()?;
+            if config.custom_limits.is_none() {
+                return Err(Pca9685Error::InvalidConfiguration(
+                    "ChannelConfig.esc requires custom_limits".to_string(),
+                ));
+            }
+        }
+        if config.trim_counts.unsigned_abs() >= PCA_PWM_RESOLUTION {
+            return Err(Pca9685Error::InvalidConfiguration(format!(
+                "ChannelConfig.trim_counts must be within ±{}",
+                PCA_PWM_RESOLUTION - 1
+            )));
+        }
+        if let Some(input_shaping) = &config.input_shaping {
+            input_shaping.validate()?;
+        }
+        if let Some(low_pass) = &config.low_pass {
+            low_pass.validate()?;
+            if config.input_shaping.is_some() {
+                return Err(Pca9685Error::InvalidConfiguration(
+                    "ChannelConfig.low_pass and input_shaping are mutually exclusive".to_string(),
+                ));
+            }
+        }
+        if let Some(pid) = &config.pid {
+            pid.validate()?;
+            if !matches!(&config.feedback, Some(feedback) if feedback.min_raw.is_some() && feedback.max_raw.is_some())
+            {
+                return Err(Pca9685Error::InvalidConfiguration(
+                    "ChannelConfig.pid requires feedback with min_raw and max_raw".to_string(),
+                ));
+            }
+        }
+        config.validate_name()?;
+        config.validate_tags()?;
+        self.config.name = config.name.clone();
+        self.config.tags = config.tags.clone();
+        self.name = self.config.log_target();
+        self.config.inverted = config.inverted;
+        self.config.trim_counts = config.trim_counts;
+        if self.config.esc != config.esc {
+            self.config.esc = config.esc;
+            self.armed_at = None;
+        }
+        self.config.reduced_hold = config.reduced_hold;
+        self.config.thermal_limits = config.thermal_limits;
+        self.config.dual_rate = config.dual_rate;
+        self.config.display_unit = config.display_unit;
+        if self.config.feedback != config.feedback {
+            self.config.feedback = config.feedback.clone();
+            self.feedback = config.feedback.clone().map(Feedback::new);
+        }
+
+        self.calibrate(config.calibration)?;
+        self.configure_limits(&config.custom_limits)?;
+
+        if let Some(FailsafeOutput::Count(pwm_off_count)) = config.failsafe {
+            self.check_limits(pwm_off_count)?;
+        }
+        self.config.failsafe = config.failsafe;
+        self.configure_pid(config.pid);
+        self.configure_shaping(config.input_shaping);
+        self.configure_low_pass(config.low_pass);
+
+        Ok(self.config())
+    }
+
+    /// Sets (or, given None, clears) the channel's calibration, re-deriving
+    /// any pw_limits from it.
+    pub fn calibrate(
+        &mut self,
+        calibration: Option<ChannelCalibration>,
+    ) -> Pca9685Result<ChannelConfig> {
+        if let Some(calibration) = &calibration {
+            calibration.validate()?;
+            log::info!(target: &self.name, "Configured calibration {:?}", calibration);
+        }
+
+        let previous_calibration = self.config.calibration;
+        self.config.calibration = calibration;
+
+        if let Some(limits) = self
+            .config
+            .custom_limits
+            .filter(|limits| limits.pw_limits.is_some())
+        {
+            let pw_limits = Some(ChannelLimits {
+                count_limits: None,
+                pw_limits: limits.pw_limits,
+            });
+            if let Err(error) = self.configure_limits(&pw_limits) {
+                self.config.calibration = previous_calibration;
+                return Err(error);
+            }
+        }
+
+        Ok(self.config())
+    }
+
+    pub fn log_target(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the Channel's output as a fraction of its limits (as for
+    /// [CommandType::Percent]), or None without output
+    pub fn output_pct(&self) -> Option<f64> {
+        let count = self.config.current_count?;
+        let (min_count, max_count) = self.config.limits();
+        if max_count <= min_count {
+            return Some(0.0);
+        }
+        let fraction = (count as f64 - min_count as f64) / (max_count - min_count) as f64;
+
+        Some(if self.config.inverted {
+            1.0 - fraction
+        } else {
+            fraction
+        })
     }
 
     pub fn config(&self) -> ChannelConfig {
-        ChannelConfig {
-            channel: self.config.channel,
-            current_count: match self.config.current_count {
-                Some(pwm_count) => Some(pwm_count),
-                None => None,
-            },
-            custom_limits: match &self.config.custom_limits {
-                Some(limits) => Some(limits.clone()),
-                None => None,
-            },
+        let mut config = self.config.clone();
+        config.display_value = self.display_value(config.current_count);
+
+        config
+    }
+
+    /// Returns the channel's config along with its revision, which changes
+    /// whenever the config has changed since the previous call
+    pub fn revised_config(&mut self) -> (ChannelConfig, u64) {
+        let config = self.config();
+        if self.revised_config.as_ref() != Some(&config) {
+            self.revision += 1;
+            self.revised_config = Some(config.clone());
         }
+
+        (config, self.revision)
+    }
+
+    /// Returns `count` in the channel's display unit, if it has one
+    fn display_value(&self, count: Option<u16>) -> Option<f64> {
+        let display_unit = self.config.display_unit?;
+        let count = count?;
+
+        Some(display_unit.value(
+            count,
+            self.config.limits(),
+            self.count_to_pw(count),
+            self.config.inverted,
+        ))
     }
 
     pub fn configure_limits(
@@ -56,19 +1097,19 @@ impl ChannelProxy {
                     ));
                 }
 
-                limits.count_limits.map(|count_limits| {
+                if let Some(count_limits) = limits.count_limits {
                     self.config.custom_limits = Some(ChannelLimits::from_count_limits(
                         count_limits.min_on_count,
                         count_limits.max_on_count,
                     ));
-                });
-                limits.pw_limits.map(|pw_limits| {
+                }
+                if let Some(pw_limits) = limits.pw_limits {
                     self.config.custom_limits = Some(ChannelLimits::from_pw_limits(
                         pw_limits.min_on_ms,
                         pw_limits.max_on_ms,
-                        self.clock_config,
-                    ));
-                });
+                        |pw_ms| self.pw_to_count(pw_ms),
+                    )?);
+                }
 
                 log::info!(
                     target: &self.name,
@@ -81,51 +1122,188 @@ impl ChannelProxy {
                 log::info!(target: &self.name, "Configured limits to None");
                 self.config.custom_limits = None;
 
-                Ok(self.config())
-            }
-        }
+                Ok(self.config())
+            }
+        }
+    }
+
+    pub fn full_on(&mut self, pca: &mut Box<dyn Pca9685Proxy>) -> Pca9685Result<ChannelConfig> {
+        let result = self.write(ChannelTarget::FullOn, None, pca);
+        self.count_command(CommandType::FullOn, &result);
+
+        result
+    }
+
+    pub fn full_off(&mut self, pca: &mut Box<dyn Pca9685Proxy>) -> Pca9685Result<ChannelConfig> {
+        let result = self.write(ChannelTarget::FullOff, None, pca);
+        self.count_command(CommandType::FullOff, &result);
+
+        result
+    }
+
+    pub fn set_pw_ms(
+        &mut self,
+        pw_ms: f64,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<ChannelConfig> {
+        let result = self
+            .pw_to_count(pw_ms)
+            .and_then(|pwm_off_count| self.write_count(pwm_off_count, pca));
+        self.count_command(CommandType::PulseWidth, &result);
+
+        result
+    }
+
+    pub fn set_pct(
+        &mut self,
+        pct: f64,
+        basis: PercentBasis,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<ChannelConfig> {
+        let result = self
+            .pct_to_count(pct, basis)
+            .and_then(|pwm_off_count| self.write_count(pwm_off_count, pca));
+        self.count_command(CommandType::Percent, &result);
+
+        result
+    }
+
+    pub fn adjust_count(
+        &mut self,
+        delta: i32,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<ChannelConfig> {
+        let result = self
+            .adjusted_count(delta)
+            .and_then(|pwm_off_count| self.write_count(pwm_off_count, pca));
+        self.count_command(CommandType::AdjustCount, &result);
+
+        result
+    }
+
+    pub fn adjust_pct(
+        &mut self,
+        delta_pct: f64,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<ChannelConfig> {
+        let result = self
+            .adjusted_count(self.pct_delta_to_counts(delta_pct))
+            .and_then(|pwm_off_count| self.write_count(pwm_off_count, pca));
+        self.count_command(CommandType::AdjustPct, &result);
+
+        result
     }
 
-    pub fn full_on(&mut self, pca: &mut Box<dyn Pca9685Proxy>) -> Pca9685Result<ChannelConfig> {
-        self.config.current_count = Some(PCA_PWM_RESOLUTION);
+    /// Returns the channel's configuration as it would be after `command`,
+    /// without writing anything.
+    pub fn preview(&self, command: &ChannelCommand) -> Pca9685Result<ChannelConfig> {
+        let mut config = self.config();
+        config.current_count = match self.resolve(command)? {
+            ChannelTarget::FullOn => Some(PCA_PWM_RESOLUTION),
+            ChannelTarget::FullOff => None,
+            ChannelTarget::Count(pwm_off_count) => Some(pwm_off_count),
+        };
+        config.display_value = self.display_value(config.current_count);
+
+        Ok(config)
+    }
 
-        log::info!(target: &self.name, "Setting output to FULL ON");
+    /// Resolves `command` to the output it would produce, validating it
+    /// against the channel's limits without writing anything.
+    pub fn resolve(&self, command: &ChannelCommand) -> Pca9685Result<ChannelTarget> {
+        self.check_accepts_commands()?;
 
-        match pca.set_channel_full_on(self.config.channel) {
-            Ok(()) => Ok(self.config()),
-            Err(error) => Err(Pca9685Error::Pca9685DriverError(error)),
+        let target = self.filtered(self.unfiltered_target(command)?);
+        self.check_configured(target)?;
+        self.check_armed(target)?;
+        if let ChannelTarget::Count(pwm_off_count) = target {
+            self.check_limits(pwm_off_count)?;
         }
+
+        Ok(target)
     }
 
-    pub fn full_off(&mut self, pca: &mut Box<dyn Pca9685Proxy>) -> Pca9685Result<ChannelConfig> {
-        self.config.current_count = None;
+    /// Validates `command` against the channel's limits, regardless of the
+    /// channel's state (e.g., faulted) and filters
+    pub fn check_command(&self, command: &ChannelCommand) -> Pca9685Result<()> {
+        match self.unfiltered_target(command)? {
+            ChannelTarget::Count(pwm_off_count) => self.check_limits(pwm_off_count),
+            _ => Ok(()),
+        }
+    }
+
+    fn unfiltered_target(&self, command: &ChannelCommand) -> Pca9685Result<ChannelTarget> {
+        let value = command.checked_value()?;
+
+        let target = match command.command_type {
+            CommandType::FullOn => ChannelTarget::FullOn,
+            CommandType::FullOff | CommandType::Relax => ChannelTarget::FullOff,
+            CommandType::PulseCount => ChannelTarget::Count(value as u16),
+            CommandType::PulseWidth => ChannelTarget::Count(self.pw_to_count(value)?),
+            CommandType::Percent => {
+                ChannelTarget::Count(self.pct_to_count(value, command.basis.unwrap_or_default())?)
+            }
+            CommandType::AdjustCount => ChannelTarget::Count(self.adjusted_count(value as i32)?),
+            CommandType::AdjustPct => {
+                ChannelTarget::Count(self.adjusted_count(self.pct_delta_to_counts(value))?)
+            }
+        };
 
-        log::info!(target: &self.name, "Setting output to FULL OFF");
+        Ok(target)
+    }
 
-        match pca.set_channel_full_off(self.config.channel) {
-            Ok(()) => Ok(self.config()),
-            Err(error) => Err(Pca9685Error::Pca9685DriverError(error)),
+    /// Applies the channel's [ChannelFilter]s to a `target` count
+    fn filtered(&self, target: ChannelTarget) -> ChannelTarget {
+        match target {
+            ChannelTarget::Count(requested_count) => {
+                ChannelTarget::Count(self.filters.iter().fold(requested_count, |count, filter| {
+                    filter.transform(self.config.channel, count, &self.config)
+                }))
+            }
+            _ => target,
         }
     }
 
-    pub fn set_pw_ms(
+    pub fn apply_target(
         &mut self,
-        pw_ms: f64,
+        target: ChannelTarget,
+        client_id: Option<&str>,
         pca: &mut Box<dyn Pca9685Proxy>,
     ) -> Pca9685Result<ChannelConfig> {
-        self.set_pwm_count(self.clock_config.pw_to_count(pw_ms)?, pca)
+        if let ChannelTarget::Count(pwm_off_count) = target {
+            self.check_limits(pwm_off_count)?;
+        }
+
+        self.write(target, client_id, pca)
     }
 
-    pub fn set_pct(
+    pub fn execute(
         &mut self,
-        pct: f64,
+        command: &ChannelCommand,
         pca: &mut Box<dyn Pca9685Proxy>,
     ) -> Pca9685Result<ChannelConfig> {
-        let limits = self.config.custom_limits.unwrap_or_default();
+        let result = match self.resolve(command) {
+            Ok(target) => self.apply_target(target, command.client_id.as_deref(), pca),
+            Err(error) => Err(error),
+        };
+        self.count_command(command.command_type, &result);
+
+        result
+    }
 
-        limits
-            .pct_to_count(pct)
-            .and_then(|pwm_off_count| self.set_pwm_count(pwm_off_count, pca))
+    pub fn transaction<F>(
+        &mut self,
+        f: F,
+        client_id: Option<&str>,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<ChannelConfig>
+    where
+        F: FnOnce(&ChannelConfig) -> Pca9685Result<u16>,
+    {
+        let pwm_off_count = f(&self.config)?;
+        let target = self.filtered(ChannelTarget::Count(pwm_off_count));
+
+        self.apply_target(target, client_id, pca)
     }
 
     pub fn set_pwm_count(
@@ -133,44 +1311,342 @@ impl ChannelProxy {
         pwm_off_count: u16,
         pca: &mut Box<dyn Pca9685Proxy>,
     ) -> Pca9685Result<ChannelConfig> {
-        let limits = match self.config.custom_limits {
-            Some(limits) => limits,
-            None => Default::default(),
+        let result = self.write_count(pwm_off_count, pca);
+        self.count_command(CommandType::PulseCount, &result);
+
+        result
+    }
+
+    /// Writes `pwm_off_count`, after the channel's filters, within its limits
+    fn write_count(
+        &mut self,
+        pwm_off_count: u16,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<ChannelConfig> {
+        let target = self.filtered(ChannelTarget::Count(pwm_off_count));
+
+        self.apply_target(target, None, pca)
+    }
+
+    /// Writes `target` to the device, attributing the change to `client_id`
+    /// (None for anonymous/library callers), which becomes the channel owner.
+    fn write(
+        &mut self,
+        target: ChannelTarget,
+        client_id: Option<&str>,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) -> Pca9685Result<ChannelConfig> {
+        self.check_accepts_commands()?;
+        self.check_configured(target)?;
+        self.check_armed(target)?;
+
+        let mut attribution = match client_id {
+            Some(client_id) => format!(" (client: {})", client_id),
+            None => String::new(),
         };
-        if !limits.is_valid(pwm_off_count) {
-            return Err(Pca9685Error::CustomLimitsError(
-                pwm_off_count,
-                limits.clone(),
-            ));
+        if self.paused.is_some() {
+            attribution.push_str(" (paused: written on resume)");
         }
 
-        if pwm_off_count == PCA_PWM_RESOLUTION {
-            self.full_on(pca)
-        } else {
-            match pca.set_channel_off_count(self.config.channel, pwm_off_count) {
-                Ok(()) => {
+        self.failsafe_restore = None;
+
+        let display = match (self.config.display_unit, target) {
+            (Some(display_unit), ChannelTarget::Count(pwm_off_count)) => format!(
+                " = {}",
+                display_unit.format(self.display_value(Some(pwm_off_count)).unwrap())
+            ),
+            _ => String::new(),
+        };
+
+        let previous_count = self.config.current_count;
+        let shaped_count = self.shape(Instant::now(), target, previous_count);
+        let result = match target {
+            ChannelTarget::FullOn | ChannelTarget::Count(PCA_PWM_RESOLUTION) => {
+                self.config.current_count = Some(PCA_PWM_RESOLUTION);
+
+                log::info!(target: &self.name, "Setting output to FULL ON{}", attribution);
+
+                self.device_write(pca, |pca, channel| pca.set_channel_full_on(channel))
+            }
+            ChannelTarget::FullOff => {
+                self.config.current_count = None;
+                self.armed_at = None;
+
+                log::info!(target: &self.name, "Setting output to FULL OFF{}", attribution);
+
+                self.device_write(pca, |pca, channel| pca.set_channel_full_off(channel))
+            }
+            ChannelTarget::Count(pwm_off_count) => {
+                let trimmed_count = self.trimmed(shaped_count.unwrap_or(pwm_off_count));
+                self.device_write(pca, |pca, channel| {
+                    pca.set_channel_off_count(channel, trimmed_count)
+                })
+                .map(|()| {
                     self.config.current_count = Some(pwm_off_count);
 
                     log::info!(
                         target: &self.name,
-                        "Setting output to {} counts ({:0.6}ms)",
+                        "Setting output to {} counts ({:0.6}ms){}{}",
                         pwm_off_count,
-                        pwm_off_count as f64 * pca.single_count_duration_ms()
+                        pwm_off_count as f64 * pca.single_count_duration_ms(),
+                        display,
+                        attribution
                     );
-                    Ok(self.config())
+                })
+            }
+        };
+
+        match result {
+            Ok(()) => {
+                if let (Some(previous_count), Some(count)) =
+                    (previous_count, self.config.current_count)
+                {
+                    let travel = previous_count.abs_diff(count) as u64;
+                    self.stats.travel_counts += travel;
+                    self.pending_travel_counts += travel;
                 }
-                Err(error) => Err(Pca9685Error::Pca9685DriverError(error)),
+
+                self.last_write_at = Some(Instant::now());
+                self.gated_off = false;
+                self.adjusted_count = shaped_count;
+                self.stats.position_error = false;
+                self.config.owner = client_id.map(String::from);
+                self.record_history();
+                Ok(self.config())
+            }
+            Err(error) => {
+                let error = Pca9685Error::Pca9685DriverError(error);
+                self.record_driver_error(Instant::now(), &error, pca);
+                Err(error)
+            }
+        }
+    }
+
+    /// Fails if the channel is faulted, under maintenance, or paused with
+    /// [PauseMode::Reject]
+    fn check_accepts_commands(&self) -> Pca9685Result<()> {
+        if self.stats.faulted {
+            return Err(Pca9685Error::ChannelFaultedError(self.config.channel as u8));
+        }
+        if self.stats.under_maintenance {
+            return Err(Pca9685Error::ChannelMaintenanceError(
+                self.config.channel as u8,
+            ));
+        }
+        if self.paused == Some(PauseMode::Reject) {
+            return Err(Pca9685Error::PausedError);
+        }
+
+        Ok(())
+    }
+
+    /// Rejects any `target` but full off if strict and the channel has no
+    /// `custom_limits` (see [ChannelProxy::set_strict])
+    fn check_configured(&self, target: ChannelTarget) -> Pca9685Result<()> {
+        if self.strict && self.config.custom_limits.is_none() && target != ChannelTarget::FullOff {
+            return Err(Pca9685Error::ChannelUnconfiguredError(
+                self.config.channel as u8,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Rejects any `target` above minimum throttle if the channel is an ESC
+    /// that is not armed (see [ChannelProxy::arm])
+    fn check_armed(&self, target: ChannelTarget) -> Pca9685Result<()> {
+        if self.config.esc.is_none() || self.armed(Instant::now()) {
+            return Ok(());
+        }
+
+        let above_minimum = match target {
+            ChannelTarget::FullOff => false,
+            ChannelTarget::FullOn => true,
+            ChannelTarget::Count(pwm_off_count) => pwm_off_count > self.config.limits().0,
+        };
+        if above_minimum {
+            return Err(Pca9685Error::ChannelDisarmedError(
+                self.config.channel as u8,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns `count` plus the channel's `trim_counts`, as written to the
+    /// device
+    fn trimmed(&self, count: u16) -> u16 {
+        self.config.trimmed(count)
+    }
+
+    /// Performs `write` on the device, unless paused, in which case the
+    /// write is deferred until [ChannelProxy::resume]
+    fn device_write<F>(
+        &mut self,
+        pca: &mut Box<dyn Pca9685Proxy>,
+        write: F,
+    ) -> Result<(), DriverError>
+    where
+        F: FnOnce(&mut Box<dyn Pca9685Proxy>, Channel) -> Result<(), DriverError>,
+    {
+        if self.paused.is_some() {
+            self.resume_pending = true;
+            return Ok(());
+        }
+
+        write(pca, self.config.channel)
+    }
+
+    /// Records a driver `error` as of `now`, faulting the channel once its
+    /// [CircuitBreakerConfig] (if any) trips: the output is driven to its
+    /// failsafe output (or full off), and further commands are rejected.
+    fn record_driver_error(
+        &mut self,
+        now: Instant,
+        error: &Pca9685Error,
+        pca: &mut Box<dyn Pca9685Proxy>,
+    ) {
+        self.last_driver_error = Some(DriverErrorRecord {
+            channel: self.config.channel,
+            error: error.to_string(),
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis() as u64),
+        });
+
+        let Some(circuit_breaker) = self.circuit_breaker else {
+            return;
+        };
+
+        let window = Duration::from_millis(circuit_breaker.window_ms);
+        while matches!(self.driver_errors.front(), Some(at) if now.saturating_duration_since(*at) > window)
+        {
+            self.driver_errors.pop_front();
+        }
+        self.driver_errors.push_back(now);
+        if self.driver_errors.len() < circuit_breaker.max_errors as usize {
+            return;
+        }
+
+        log::error!(
+            target: &self.name,
+            "Faulted after {} driver errors within {}ms",
+            circuit_breaker.max_errors,
+            circuit_breaker.window_ms
+        );
+        self.driver_errors.clear();
+        self.fault(pca);
+    }
+
+    /// Faults the channel: the output is driven to its failsafe output (or
+    /// full off), and further commands are rejected until the fault is
+    /// cleared
+    fn fault(&mut self, pca: &mut Box<dyn Pca9685Proxy>) {
+        self.stats.faulted = true;
+        self.armed_at = None;
+        self.failsafe_restore = None;
+
+        // Best effort, as the output may well be what is failing
+        let result = match self.config.failsafe {
+            Some(FailsafeOutput::Count(pwm_off_count)) => pca
+                .set_channel_off_count(self.config.channel, self.trimmed(pwm_off_count))
+                .map(|()| Some(pwm_off_count)),
+            _ => pca.set_channel_full_off(self.config.channel).map(|()| None),
+        };
+        match result {
+            Ok(count) => self.config.current_count = count,
+            Err(error) => {
+                log::error!(target: &self.name, "Unable to apply safe output: {:?}", error)
             }
         }
+
+        self.pending_events
+            .push(Pca9685Event::ChannelFaulted(self.stats()));
+    }
+
+    fn check_limits(&self, pwm_off_count: u16) -> Pca9685Result<()> {
+        let limits = self.config.custom_limits.unwrap_or_default();
+        if !limits.is_valid(pwm_off_count) {
+            return Err(Pca9685Error::CustomLimitsError(pwm_off_count, limits));
+        }
+
+        Ok(())
+    }
+
+    fn count_to_pw(&self, count: u16) -> f64 {
+        match &self.config.calibration {
+            Some(calibration) => calibration.count_to_pw(count),
+            None => count as f64 * self.clock_config.single_pw_duration_ms,
+        }
+    }
+
+    fn pw_to_count(&self, pw_ms: f64) -> Pca9685Result<u16> {
+        match &self.config.calibration {
+            Some(calibration) => calibration.pw_to_count(pw_ms, self.clock_config.max_pw_ms),
+            None => self.clock_config.pw_to_count(pw_ms),
+        }
+    }
+
+    fn pct_to_count(&self, pct: f64, basis: PercentBasis) -> Pca9685Result<u16> {
+        let limits = match basis {
+            PercentBasis::Limits => self.config.custom_limits.unwrap_or_default(),
+            PercentBasis::FullRange => ChannelLimits::default(),
+        };
+
+        let count = limits.pct_to_count(pct)?;
+        if !self.config.inverted {
+            return Ok(count);
+        }
+        let (min_on_count, max_on_count) = limits.count_limits();
+
+        Ok(min_on_count + max_on_count - count)
+    }
+
+    fn pct_delta_to_counts(&self, delta_pct: f64) -> i32 {
+        let (min_on_count, max_on_count) = self.config.limits();
+        let delta_pct = if self.config.inverted {
+            -delta_pct
+        } else {
+            delta_pct
+        };
+
+        ((max_on_count - min_on_count) as f64 * delta_pct).round() as i32
+    }
+
+    fn adjusted_count(&self, delta: i32) -> Pca9685Result<u16> {
+        let current_count = self
+            .config
+            .current_count
+            .ok_or(Pca9685Error::ChannelInactiveError(
+                self.config.channel as u8,
+            ))?;
+        let (min_on_count, max_on_count) = self.config.limits();
+
+        Ok((current_count as i32 + delta).clamp(min_on_count as i32, max_on_count as i32) as u16)
+    }
+}
+
+/// Returns the [ChannelTarget] that yields `current_count`
+fn count_target(current_count: Option<u16>) -> ChannelTarget {
+    match current_count {
+        Some(PCA_PWM_RESOLUTION) => ChannelTarget::FullOn,
+        Some(pwm_off_count) => ChannelTarget::Count(pwm_off_count),
+        None => ChannelTarget::FullOff,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        ChannelLimits, ChannelProxy, Pca9685Error, Pca9685Proxy, PcaClockConfig, PCA_PWM_RESOLUTION,
+        CalibrationPoint, ChannelCalibration, ChannelLimits, ChannelProxy, ChannelPulseWidthLimits,
+        CircuitBreakerConfig, DisabledOutput, DisplayUnit, DriverError, FailsafeOutput,
+        FeedbackConfig, FeedbackSource, InputShaping, JitterConfig, LowPassConfig, Pca9685Error,
+        Pca9685Event, Pca9685Proxy, PcaClockConfig, PercentBasis, PidGains, PositionVerification,
+        ReducedHold, Shaper, ThermalLimits, TraceWrite, CHANNEL_HISTORY_CAPACITY, INTERNAL_OSC_HZ,
+        PCA_PWM_RESOLUTION,
     };
     use pwm_pca9685::{Channel, OutputDriver};
+    use std::time::{Duration, Instant};
 
     const TEST_OUTPUT_FREQUENCY_HZ: f64 = 200.0;
     const TEST_PCA_MAX_PW_MS: f64 = 1000.0 / TEST_OUTPUT_FREQUENCY_HZ;
@@ -194,6 +1670,121 @@ mod tests {
             TEST_OUTPUT_FREQUENCY_HZ as u16
         }
 
+        fn actual_output_frequency_hz(&self) -> f64 {
+            TEST_OUTPUT_FREQUENCY_HZ
+        }
+
+        fn oscillator_hz(&self) -> f64 {
+            INTERNAL_OSC_HZ
+        }
+
+        fn device(&self) -> String {
+            String::from("/dev/foo")
+        }
+
+        fn address(&self) -> u8 {
+            0x40
+        }
+
+        fn prescale(&self) -> u8 {
+            31
+        }
+
+        fn output_type(&self) -> OutputDriver {
+            OutputDriver::TotemPole
+        }
+
+        fn disabled_output(&self) -> DisabledOutput {
+            DisabledOutput::Low
+        }
+
+        fn set_disabled_output(
+            &mut self,
+            _disabled_output: DisabledOutput,
+        ) -> Result<(), DriverError> {
+            Ok(())
+        }
+
+        fn on_count(&self, _channel: Channel) -> u16 {
+            0
+        }
+
+        fn all_call_address(&self) -> Option<u8> {
+            None
+        }
+
+        fn set_broadcasting(&mut self, _broadcasting: bool) {}
+
+        fn set_channel_off_count(
+            &mut self,
+            _channel: Channel,
+            _off: u16,
+        ) -> Result<(), DriverError> {
+            Ok(())
+        }
+
+        fn set_channel_full_on(&mut self, _channel: Channel) -> Result<(), DriverError> {
+            Ok(())
+        }
+
+        fn set_channel_full_off(&mut self, _channel: Channel) -> Result<(), DriverError> {
+            Ok(())
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+            self
+        }
+
+        fn take_trace(&mut self) -> Vec<TraceWrite> {
+            Vec::new()
+        }
+
+        fn begin_burst(&mut self) {}
+
+        fn end_burst(&mut self) -> Result<(), DriverError> {
+            Ok(())
+        }
+
+        fn sleep(&mut self) -> Result<(), DriverError> {
+            Ok(())
+        }
+
+        fn wake(&mut self) -> Result<(), DriverError> {
+            Ok(())
+        }
+
+        fn asleep(&self) -> bool {
+            false
+        }
+
+        fn reset(&mut self) -> Result<(), DriverError> {
+            Ok(())
+        }
+    }
+
+    /// Fails every write to the device
+    struct FailingPca9685Proxy;
+    impl Pca9685Proxy for FailingPca9685Proxy {
+        fn max_pw_ms(&self) -> f64 {
+            TEST_PCA_MAX_PW_MS
+        }
+
+        fn single_count_duration_ms(&self) -> f64 {
+            TEST_PCA_COUNT_DURATION_MS
+        }
+
+        fn output_frequency_hz(&self) -> u16 {
+            TEST_OUTPUT_FREQUENCY_HZ as u16
+        }
+
+        fn actual_output_frequency_hz(&self) -> f64 {
+            TEST_OUTPUT_FREQUENCY_HZ
+        }
+
+        fn oscillator_hz(&self) -> f64 {
+            INTERNAL_OSC_HZ
+        }
+
         fn device(&self) -> String {
             String::from("/dev/foo")
         }
@@ -206,40 +1797,82 @@ mod tests {
             31
         }
 
-        fn output_type(&self) -> OutputDriver {
-            OutputDriver::TotemPole
+        fn output_type(&self) -> OutputDriver {
+            OutputDriver::TotemPole
+        }
+
+        fn disabled_output(&self) -> DisabledOutput {
+            DisabledOutput::Low
+        }
+
+        fn set_disabled_output(
+            &mut self,
+            _disabled_output: DisabledOutput,
+        ) -> Result<(), DriverError> {
+            Ok(())
+        }
+
+        fn on_count(&self, _channel: Channel) -> u16 {
+            0
+        }
+
+        fn all_call_address(&self) -> Option<u8> {
+            None
+        }
+
+        fn set_broadcasting(&mut self, _broadcasting: bool) {}
+
+        fn set_channel_off_count(
+            &mut self,
+            _channel: Channel,
+            _off: u16,
+        ) -> Result<(), DriverError> {
+            Err(pwm_pca9685::Error::InvalidInputData)
+        }
+
+        fn set_channel_full_on(&mut self, _channel: Channel) -> Result<(), DriverError> {
+            Err(pwm_pca9685::Error::InvalidInputData)
+        }
+
+        fn set_channel_full_off(&mut self, _channel: Channel) -> Result<(), DriverError> {
+            Err(pwm_pca9685::Error::InvalidInputData)
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+            self
+        }
+
+        fn take_trace(&mut self) -> Vec<TraceWrite> {
+            Vec::new()
+        }
+
+        fn begin_burst(&mut self) {}
+
+        fn end_burst(&mut self) -> Result<(), DriverError> {
+            Ok(())
+        }
+
+        fn sleep(&mut self) -> Result<(), DriverError> {
+            Err(pwm_pca9685::Error::InvalidInputData)
         }
 
-        fn set_channel_off_count(
-            &mut self,
-            _channel: Channel,
-            _off: u16,
-        ) -> Result<(), pwm_pca9685::Error<linux_embedded_hal::i2cdev::linux::LinuxI2CError>>
-        {
-            Ok(())
+        fn wake(&mut self) -> Result<(), DriverError> {
+            Err(pwm_pca9685::Error::InvalidInputData)
         }
 
-        fn set_channel_full_on(
-            &mut self,
-            _channel: Channel,
-        ) -> Result<(), pwm_pca9685::Error<linux_embedded_hal::i2cdev::linux::LinuxI2CError>>
-        {
-            Ok(())
+        fn asleep(&self) -> bool {
+            false
         }
 
-        fn set_channel_full_off(
-            &mut self,
-            _channel: Channel,
-        ) -> Result<(), pwm_pca9685::Error<linux_embedded_hal::i2cdev::linux::LinuxI2CError>>
-        {
-            Ok(())
+        fn reset(&mut self) -> Result<(), DriverError> {
+            Err(pwm_pca9685::Error::InvalidInputData)
         }
     }
 
     #[test]
     fn set_pwm_count() -> Result<(), Pca9685Error> {
         let mut channel =
-            ChannelProxy::new(Channel::try_from(0 as u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
 
         let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
 
@@ -259,7 +1892,7 @@ mod tests {
     #[should_panic(expected = "must be within the limits")]
     fn set_pwm_count_too_large() {
         let mut channel =
-            ChannelProxy::new(Channel::try_from(0 as u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
 
         let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
 
@@ -271,7 +1904,7 @@ mod tests {
     #[test]
     fn set_pw_ms() -> Result<(), Pca9685Error> {
         let mut channel = ChannelProxy::new(
-            Channel::try_from(0 as u8).unwrap(),
+            Channel::try_from(0_u8).unwrap(),
             PcaClockConfig {
                 single_pw_duration_ms: TEST_PCA_COUNT_DURATION_MS,
                 max_pw_ms: TEST_PCA_MAX_PW_MS,
@@ -312,7 +1945,7 @@ mod tests {
         // Test a specific value, using formula
         for test_pw_ms in [1.0, 1.5, 2.0] {
             // Hz to to millis, so to speak
-            let expected_count = 1000.0 / TEST_OUTPUT_FREQUENCY_HZ as f64;
+            let expected_count = 1000.0 / TEST_OUTPUT_FREQUENCY_HZ;
 
             // Duration of each count, in millis
             let expected_count = expected_count / 4096.0;
@@ -329,13 +1962,528 @@ mod tests {
             );
         }
 
-        return Ok(());
+        Ok(())
+    }
+
+    #[test]
+    fn set_pw_ms_calibrated() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+
+        channel.configure_limits(&Some(ChannelLimits {
+            count_limits: None,
+            pw_limits: Some(ChannelPulseWidthLimits {
+                min_on_ms: 1.25,
+                max_on_ms: 2.5,
+            }),
+        }))?;
+
+        // Measured: each count is 1.25us, rather than the nominal 1.2207us
+        let config = channel.calibrate(Some(ChannelCalibration {
+            points: [
+                CalibrationPoint {
+                    count: 1000,
+                    measured_pw_ms: 1.25,
+                },
+                CalibrationPoint {
+                    count: 2000,
+                    measured_pw_ms: 2.5,
+                },
+            ],
+        }))?;
+        assert_eq!(config.limits(), (1000, 2000));
+
+        assert_eq!(
+            channel
+                .set_pw_ms(1.875, &mut mock_pca9685_proxy)?
+                .current_count
+                .unwrap(),
+            1500
+        );
+
+        // Clearing the calibration restores the nominal conversion
+        let config = channel.calibrate(None)?;
+        assert_eq!(config.limits(), (1024, 2048));
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "measured at different counts")]
+    fn calibrate_invalid() {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+        let point = CalibrationPoint {
+            count: 1000,
+            measured_pw_ms: 1.25,
+        };
+
+        channel
+            .calibrate(Some(ChannelCalibration {
+                points: [point, point],
+            }))
+            .unwrap();
+    }
+
+    impl ChannelProxy {
+        fn tick_for_test(
+            &mut self,
+            now: Instant,
+            pca: &mut Box<dyn Pca9685Proxy>,
+        ) -> Result<Vec<Pca9685Event>, Pca9685Error> {
+            let mut events = Vec::new();
+            self.tick(now, pca, &mut events)?;
+
+            Ok(events)
+        }
+    }
+
+    #[test]
+    fn thermal_limits() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+
+        let mut config = channel.config();
+        config.thermal_limits = Some(ThermalLimits {
+            window_ms: 1000,
+            max_duty: Some(0.5),
+            max_travel_counts_per_s: None,
+        });
+        channel.configure(&config)?;
+
+        let started_at = Instant::now();
+        let at = |ms: u64| started_at + Duration::from_millis(ms);
+
+        channel.tick_for_test(at(0), &mut mock_pca9685_proxy)?;
+        channel.set_pwm_count(1000, &mut mock_pca9685_proxy)?;
+        channel.set_pwm_count(2000, &mut mock_pca9685_proxy)?;
+
+        // Energized for less than one time constant: duty ~0.4
+        assert!(channel
+            .tick_for_test(at(500), &mut mock_pca9685_proxy)?
+            .is_empty());
+        let stats = channel.stats();
+        assert_eq!(stats.energized_ms, 500);
+        assert_eq!(stats.travel_counts, 1000);
+        assert!(stats.travel_counts_per_s > 0.0);
+        assert!(!stats.thermal_warning);
+
+        // ...and then for longer, raising a single warning
+        let events = channel.tick_for_test(at(1000), &mut mock_pca9685_proxy)?;
+        assert!(matches!(
+            events.as_slice(),
+            [Pca9685Event::ThermalWarning(_)]
+        ));
+        assert!(channel
+            .tick_for_test(at(1500), &mut mock_pca9685_proxy)?
+            .is_empty());
+        assert!(channel.stats().thermal_warning);
+
+        // Relaxed, the duty decays below the threshold
+        channel.full_off(&mut mock_pca9685_proxy)?;
+        channel.tick_for_test(at(5000), &mut mock_pca9685_proxy)?;
+        assert!(!channel.stats().thermal_warning);
+        assert_eq!(channel.stats().energized_ms, 1500);
+
+        Ok(())
+    }
+
+    #[test]
+    fn apply_failsafe() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+        let now = Instant::now();
+        let at = |ms: u64| now + Duration::from_millis(ms);
+
+        assert!(channel
+            .apply_failsafe(None, &mut mock_pca9685_proxy)?
+            .is_none());
+
+        let mut config = channel.config();
+        config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        config.failsafe = Some(FailsafeOutput::Count(2500));
+        assert!(channel.configure(&config).is_err());
+        config.failsafe = Some(FailsafeOutput::Count(1500));
+        channel.configure(&config)?;
+
+        // Rehearsal: the prior output is restored once due...
+        channel.set_pwm_count(1800, &mut mock_pca9685_proxy)?;
+        let failsafe = channel.apply_failsafe(Some(at(1000)), &mut mock_pca9685_proxy)?;
+        assert_eq!(failsafe.unwrap().current_count, Some(1500));
+        channel.tick_for_test(at(999), &mut mock_pca9685_proxy)?;
+        assert_eq!(channel.config().current_count, Some(1500));
+        channel.tick_for_test(at(1000), &mut mock_pca9685_proxy)?;
+        assert_eq!(channel.config().current_count, Some(1800));
+
+        // ...unless the channel is commanded in the meantime
+        channel.apply_failsafe(Some(at(2000)), &mut mock_pca9685_proxy)?;
+        channel.set_pwm_count(1200, &mut mock_pca9685_proxy)?;
+        channel.tick_for_test(at(2000), &mut mock_pca9685_proxy)?;
+        assert_eq!(channel.config().current_count, Some(1200));
+
+        Ok(())
+    }
+
+    #[test]
+    fn circuit_breaker() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+        channel.set_circuit_breaker(Some(CircuitBreakerConfig {
+            max_errors: 3,
+            window_ms: 60 * 1000,
+        }));
+
+        let mut failing_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(FailingPca9685Proxy {});
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+
+        for _ in 0..3 {
+            assert!(matches!(
+                channel.set_pwm_count(2000, &mut failing_pca9685_proxy),
+                Err(Pca9685Error::Pca9685DriverError(_))
+            ));
+        }
+        assert!(channel.stats().faulted);
+        assert_eq!(channel.last_driver_error().unwrap().channel, Channel::C0);
+        assert!(matches!(
+            channel.set_pwm_count(2000, &mut mock_pca9685_proxy),
+            Err(Pca9685Error::ChannelFaultedError(0))
+        ));
+
+        let mut events = Vec::new();
+        channel.tick(Instant::now(), &mut mock_pca9685_proxy, &mut events)?;
+        assert!(matches!(
+            events.as_slice(),
+            [Pca9685Event::ChannelFaulted(stats)] if stats.faulted
+        ));
+
+        assert!(!channel.clear_fault().faulted);
+        assert_eq!(
+            channel
+                .set_pwm_count(2000, &mut mock_pca9685_proxy)?
+                .current_count,
+            Some(2000)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reduced_hold() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+
+        let mut config = channel.config();
+        config.reduced_hold = Some(ReducedHold {
+            after_ms: 1000,
+            period_ms: 100,
+            duty: 0.25,
+        });
+        channel.configure(&config)?;
+
+        channel.set_pwm_count(1500, &mut mock_pca9685_proxy)?;
+        let written_at = channel.last_write_at.unwrap();
+        let at = |ms: u64| written_at + Duration::from_millis(ms);
+
+        // Fully energized until stationary for after_ms...
+        channel.tick_for_test(at(999), &mut mock_pca9685_proxy)?;
+        assert!(!channel.gated_off);
+
+        // ...then energized for only the first 25% of each period
+        channel.tick_for_test(at(1010), &mut mock_pca9685_proxy)?;
+        assert!(!channel.gated_off);
+        channel.tick_for_test(at(1030), &mut mock_pca9685_proxy)?;
+        assert!(channel.gated_off);
+        channel.tick_for_test(at(1110), &mut mock_pca9685_proxy)?;
+        assert!(!channel.gated_off);
+        channel.tick_for_test(at(1160), &mut mock_pca9685_proxy)?;
+        assert!(channel.gated_off);
+
+        // The gating never changes the commanded output, and the next command
+        // re-engages fully
+        assert_eq!(channel.config().current_count, Some(1500));
+        channel.set_pwm_count(1600, &mut mock_pca9685_proxy)?;
+        assert!(!channel.gated_off);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pid() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+        let path = std::env::temp_dir().join("pca9685-channelproxy-pid-feedback");
+        std::fs::write(&path, "400").unwrap();
+
+        let mut config = channel.config();
+        config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        config.feedback = Some(FeedbackConfig {
+            source: FeedbackSource::Path(path.to_str().unwrap().to_owned()),
+            min_raw: None,
+            max_raw: None,
+            verify: None,
+        });
+        config.pid = Some(PidGains {
+            kp: 0.5,
+            ki: 0.0,
+            kd: 0.0,
+        });
+
+        // The feedback must convert to counts
+        assert!(matches!(
+            channel.configure(&config),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+
+        config.feedback.as_mut().unwrap().min_raw = Some(0.0);
+        config.feedback.as_mut().unwrap().max_raw = Some(1000.0);
+        channel.configure(&config)?;
+        channel.set_pwm_count(1500, &mut mock_pca9685_proxy)?;
+
+        // Feedback of 1400 counts is corrected, without changing the
+        // commanded output
+        let now = Instant::now();
+        channel.tick_for_test(now, &mut mock_pca9685_proxy)?;
+        let sample = channel.pid_sample().unwrap();
+        assert_eq!((sample.setpoint, sample.output), (1500, 1550));
+        assert_eq!(sample.feedback, 1400.0);
+        assert_eq!(channel.adjusted_count, Some(1550));
+        assert_eq!(channel.config().current_count, Some(1500));
+
+        // Gains are tunable in place
+        config.pid.as_mut().unwrap().kp = 1.0;
+        channel.configure(&config)?;
+        channel.tick_for_test(now + Duration::from_millis(5), &mut mock_pca9685_proxy)?;
+        assert_eq!(channel.adjusted_count, Some(1600));
+
+        // Unreadable feedback falls back to the commanded output
+        std::fs::remove_file(&path).unwrap();
+        channel.tick_for_test(now + Duration::from_millis(10), &mut mock_pca9685_proxy)?;
+        assert_eq!(channel.adjusted_count, None);
+
+        config.pid = None;
+        channel.configure(&config)?;
+        assert_eq!(channel.pid_sample(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn input_shaping() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+
+        let mut config = channel.config();
+        config.input_shaping = Some(InputShaping {
+            shaper: Shaper::Zv,
+            frequency_hz: 2.0,
+            damping: 1.0,
+        });
+        assert!(matches!(
+            channel.configure(&config),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+        config.input_shaping.as_mut().unwrap().damping = 0.0;
+        channel.configure(&config)?;
+
+        // The first move has nothing to shape from; the next is written half
+        // way, without changing the commanded output, until settled
+        let now = Instant::now();
+        channel.set_pwm_count(1000, &mut mock_pca9685_proxy)?;
+        assert_eq!(channel.adjusted_count, None);
+        channel.set_pwm_count(2000, &mut mock_pca9685_proxy)?;
+        assert_eq!(channel.adjusted_count, Some(1500));
+        assert_eq!(channel.config().current_count, Some(2000));
+        assert!(!channel.is_settled());
+        channel.tick_for_test(now + Duration::from_millis(100), &mut mock_pca9685_proxy)?;
+        assert_eq!(channel.adjusted_count, Some(1500));
+        channel.tick_for_test(now + Duration::from_millis(300), &mut mock_pca9685_proxy)?;
+        assert_eq!(channel.adjusted_count, None);
+
+        // Full on isn't shaped
+        channel.set_pwm_count(1000, &mut mock_pca9685_proxy)?;
+        channel.full_on(&mut mock_pca9685_proxy)?;
+        assert_eq!(channel.adjusted_count, None);
+        channel.tick_for_test(now + Duration::from_millis(305), &mut mock_pca9685_proxy)?;
+        assert_eq!(channel.adjusted_count, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn low_pass() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+
+        let mut config = channel.config();
+        config.low_pass = Some(LowPassConfig {
+            time_constant_ms: 100,
+        });
+        config.input_shaping = Some(InputShaping {
+            shaper: Shaper::Zv,
+            frequency_hz: 2.0,
+            damping: 0.0,
+        });
+        assert!(matches!(
+            channel.configure(&config),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+        config.input_shaping = None;
+        channel.configure(&config)?;
+
+        // The output follows the commanded output, without changing it,
+        // until within half a count
+        let now = Instant::now();
+        channel.set_pwm_count(1000, &mut mock_pca9685_proxy)?;
+        channel.set_pwm_count(2000, &mut mock_pca9685_proxy)?;
+        assert_eq!(channel.adjusted_count, Some(1000));
+        assert_eq!(channel.config().current_count, Some(2000));
+        assert!(!channel.is_settled());
+        channel.tick_for_test(now + Duration::from_millis(100), &mut mock_pca9685_proxy)?;
+        assert!(matches!(channel.adjusted_count, Some(1620..=1640)));
+        channel.tick_for_test(now + Duration::from_secs(2), &mut mock_pca9685_proxy)?;
+        assert_eq!(channel.adjusted_count, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn verify_position() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+        let path = std::env::temp_dir().join("pca9685-channelproxy-verify-feedback");
+        std::fs::write(&path, "500").unwrap();
+
+        let mut config = channel.config();
+        config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        config.feedback = Some(FeedbackConfig {
+            source: FeedbackSource::Path(path.to_str().unwrap().to_owned()),
+            min_raw: Some(0.0),
+            max_raw: Some(1000.0),
+            verify: Some(PositionVerification {
+                tolerance_counts: 50,
+                settle_ms: 100,
+                fault: false,
+            }),
+        });
+        channel.configure(&config)?;
+        channel.set_pwm_count(1500, &mut mock_pca9685_proxy)?;
+
+        // Within tolerance
+        let settled = Instant::now() + Duration::from_millis(100);
+        assert!(channel
+            .tick_for_test(settled, &mut mock_pca9685_proxy)?
+            .is_empty());
+
+        // Diverged (once, until back within tolerance)
+        std::fs::write(&path, "100").unwrap();
+        let events = channel.tick_for_test(settled, &mut mock_pca9685_proxy)?;
+        assert!(matches!(
+            events.as_slice(),
+            [Pca9685Event::PositionError(record)] if record.commanded == 1500 && record.measured == 1100.0
+        ));
+        assert!(channel.stats().position_error);
+        assert!(channel
+            .tick_for_test(settled, &mut mock_pca9685_proxy)?
+            .is_empty());
+        assert!(!channel.stats().faulted);
+
+        // Not until settled
+        config
+            .feedback
+            .as_mut()
+            .unwrap()
+            .verify
+            .as_mut()
+            .unwrap()
+            .fault = true;
+        channel.configure(&config)?;
+        channel.set_pwm_count(1500, &mut mock_pca9685_proxy)?;
+        assert!(!channel.stats().position_error);
+        assert!(channel
+            .tick_for_test(Instant::now(), &mut mock_pca9685_proxy)?
+            .is_empty());
+
+        let settled = Instant::now() + Duration::from_millis(100);
+        let events = channel.tick_for_test(settled, &mut mock_pca9685_proxy)?;
+        assert!(matches!(
+            events.as_slice(),
+            [
+                Pca9685Event::PositionError(_),
+                Pca9685Event::ChannelFaulted(_)
+            ]
+        ));
+        assert!(channel.stats().faulted);
+
+        Ok(())
+    }
+
+    #[test]
+    fn jitter() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+        let jitter = JitterConfig {
+            amplitude_counts: 50,
+            bandwidth_hz: 50.0,
+        };
+
+        // Only configured channels
+        assert!(matches!(
+            channel.set_jitter(Some(jitter), &mut mock_pca9685_proxy),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+
+        let mut config = channel.config();
+        config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        channel.configure(&config)?;
+        channel.set_pwm_count(1990, &mut mock_pca9685_proxy)?;
+        channel.set_jitter(Some(jitter), &mut mock_pca9685_proxy)?;
+        assert_eq!(channel.jitter(), Some(jitter));
+
+        // Bounded by the limits, without changing the commanded output
+        let now = Instant::now();
+        for ms in (0..500).step_by(5) {
+            channel.tick_for_test(now + Duration::from_millis(ms), &mut mock_pca9685_proxy)?;
+            if let Some(count) = channel.adjusted_count {
+                assert!((1940..=2000).contains(&count));
+            }
+        }
+        assert!(channel.adjusted_count.is_some());
+        assert_eq!(channel.config().current_count, Some(1990));
+
+        channel.set_jitter(None, &mut mock_pca9685_proxy)?;
+        assert_eq!(channel.adjusted_count, None);
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "duty must be within")]
+    fn reduced_hold_invalid() {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+
+        let mut config = channel.config();
+        config.reduced_hold = Some(ReducedHold {
+            after_ms: 1000,
+            period_ms: 100,
+            duty: 0.0,
+        });
+        channel.configure(&config).unwrap();
     }
 
     #[test]
     fn set_pct() -> Result<(), Pca9685Error> {
         let mut channel =
-            ChannelProxy::new(Channel::try_from(0 as u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
 
         let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
 
@@ -344,20 +2492,20 @@ mod tests {
             let expected_counts = (4096.0 * pct) as u16;
             assert_eq!(
                 channel
-                    .set_pct(pct, &mut mock_pca9685_proxy)?
+                    .set_pct(pct, PercentBasis::Limits, &mut mock_pca9685_proxy)?
                     .current_count
                     .unwrap(),
                 expected_counts
             );
         }
 
-        return Ok(());
+        Ok(())
     }
 
     #[test]
     fn set_pct_custom_limits() -> Result<(), Pca9685Error> {
         let mut channel =
-            ChannelProxy::new(Channel::try_from(0 as u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
 
         let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
 
@@ -370,21 +2518,235 @@ mod tests {
             let expected_counts = 1000 + (1000.0 * pct) as u16;
             assert_eq!(
                 channel
-                    .set_pct(pct, &mut mock_pca9685_proxy)?
+                    .set_pct(pct, PercentBasis::Limits, &mut mock_pca9685_proxy)?
+                    .current_count
+                    .unwrap(),
+                expected_counts
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_pct_inverted() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+
+        let mut config = channel.config();
+        config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        config.display_unit = Some(DisplayUnit::Degrees(180.0));
+        config.inverted = true;
+        channel.configure(&config)?;
+
+        // Test at percentages of range, mirrored
+        for pct in [0.0, 0.25, 0.5, 0.75, 1.0] {
+            let expected_counts = 2000 - (1000.0 * pct) as u16;
+            let config = channel.set_pct(pct, PercentBasis::Limits, &mut mock_pca9685_proxy)?;
+            assert_eq!(config.current_count.unwrap(), expected_counts);
+            assert_eq!(config.display_value.unwrap(), 180.0 * pct);
+        }
+
+        // Adjustments move the same way as the percentage
+        let config = channel.adjust_pct(-0.5, &mut mock_pca9685_proxy)?;
+        assert_eq!(config.current_count, Some(1500));
+
+        // Counts are unaffected
+        let config = channel.set_pwm_count(1200, &mut mock_pca9685_proxy)?;
+        assert_eq!(config.current_count, Some(1200));
+        assert_eq!(config.display_value.unwrap(), 144.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn display_value() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+
+        channel
+            .configure_limits(&Some(ChannelLimits::from_count_limits(1000, 3000)))
+            .unwrap();
+        assert_eq!(
+            channel
+                .set_pwm_count(2500, &mut mock_pca9685_proxy)?
+                .display_value,
+            None
+        );
+
+        let mut config = channel.config();
+        for (display_unit, expected) in [
+            (DisplayUnit::Counts, 2500.0),
+            (DisplayUnit::Ms, 2500.0 * TEST_PCA_COUNT_DURATION_MS),
+            (
+                DisplayUnit::Us,
+                2500.0 * TEST_PCA_COUNT_DURATION_MS * 1000.0,
+            ),
+            (DisplayUnit::Percent, 75.0),
+            (DisplayUnit::Degrees(180.0), 135.0),
+        ] {
+            config.display_unit = Some(display_unit);
+            assert_eq!(channel.configure(&config)?.display_value, Some(expected));
+        }
+
+        channel.full_off(&mut mock_pca9685_proxy)?;
+        assert_eq!(channel.config().display_value, None);
+
+        config.display_unit = Some(DisplayUnit::Degrees(0.0));
+        assert!(matches!(
+            channel.configure(&config),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_pct_full_range_custom_limits() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+
+        channel
+            .configure_limits(&Some(ChannelLimits::from_count_limits(1000, 2000)))
+            .unwrap();
+
+        // Percentages are relative to [0, 4096], not the custom limits
+        for pct in [0.25, 0.4] {
+            let expected_counts = (4096.0 * pct) as u16;
+            assert_eq!(
+                channel
+                    .set_pct(pct, PercentBasis::FullRange, &mut mock_pca9685_proxy)?
                     .current_count
                     .unwrap(),
                 expected_counts
             );
         }
 
-        return Ok(());
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "must be within the limits")]
+    fn set_pct_full_range_beyond_custom_limits() {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+
+        channel
+            .configure_limits(&Some(ChannelLimits::from_count_limits(1000, 2000)))
+            .unwrap();
+
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+
+        channel
+            .set_pct(0.1, PercentBasis::FullRange, &mut mock_pca9685_proxy)
+            .unwrap();
+    }
+
+    #[test]
+    fn adjust_count_clamped_to_limits() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+
+        channel
+            .configure_limits(&Some(ChannelLimits::from_count_limits(1000, 2000)))
+            .unwrap();
+        channel.set_pwm_count(1500, &mut mock_pca9685_proxy)?;
+
+        let adjust = |channel: &mut ChannelProxy, delta, proxy: &mut Box<dyn Pca9685Proxy>| {
+            channel
+                .adjust_count(delta, proxy)
+                .unwrap()
+                .current_count
+                .unwrap()
+        };
+        assert_eq!(adjust(&mut channel, 100, &mut mock_pca9685_proxy), 1600);
+        assert_eq!(adjust(&mut channel, -250, &mut mock_pca9685_proxy), 1350);
+        assert_eq!(adjust(&mut channel, 5000, &mut mock_pca9685_proxy), 2000);
+        assert_eq!(adjust(&mut channel, -5000, &mut mock_pca9685_proxy), 1000);
+
+        Ok(())
+    }
+
+    #[test]
+    fn adjust_pct_custom_limits() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+
+        channel
+            .configure_limits(&Some(ChannelLimits::from_count_limits(1000, 2000)))
+            .unwrap();
+        channel.set_pwm_count(1500, &mut mock_pca9685_proxy)?;
+
+        // 10% of the [1000, 2000] range is 100 counts
+        assert_eq!(
+            channel
+                .adjust_pct(0.1, &mut mock_pca9685_proxy)?
+                .current_count
+                .unwrap(),
+            1600
+        );
+        assert_eq!(
+            channel
+                .adjust_pct(1.0, &mut mock_pca9685_proxy)?
+                .current_count
+                .unwrap(),
+            2000
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "no current output to adjust")]
+    fn adjust_count_inactive() {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+
+        channel.adjust_count(10, &mut mock_pca9685_proxy).unwrap();
+    }
+
+    #[test]
+    fn history() -> Result<(), Pca9685Error> {
+        let mut channel =
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+
+        let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
+
+        for count in 0..(CHANNEL_HISTORY_CAPACITY as u16 + 10) {
+            channel.set_pwm_count(count, &mut mock_pca9685_proxy)?;
+        }
+        channel.full_off(&mut mock_pca9685_proxy)?;
+
+        let history = channel.history(None);
+        assert_eq!(history.len(), CHANNEL_HISTORY_CAPACITY);
+        assert_eq!(history.first().unwrap().count, Some(11));
+        assert_eq!(history.last().unwrap().count, None);
+
+        // Every entry was recorded within the same (very coarse) window
+        let downsampled = channel.history(Some(u64::MAX));
+        assert_eq!(downsampled.len(), 1);
+        assert_eq!(downsampled[0].count, None);
+
+        Ok(())
     }
 
     #[test]
     #[should_panic(expected = "must be within the limits")]
     fn set_pwm_count_too_small_custom_limits() {
         let mut channel =
-            ChannelProxy::new(Channel::try_from(0 as u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
 
         channel
             .configure_limits(&Some(ChannelLimits::from_count_limits(1000, 2000)))
@@ -399,7 +2761,7 @@ mod tests {
     #[should_panic(expected = "must be within the limits")]
     fn set_pwm_count_too_large_custom_limits() {
         let mut channel =
-            ChannelProxy::new(Channel::try_from(0 as u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
 
         channel
             .configure_limits(&Some(ChannelLimits::from_count_limits(1000, 2000)))
@@ -416,7 +2778,7 @@ mod tests {
     #[should_panic(expected = "must be within the limits")]
     fn set_pw_ms_negative() {
         let mut channel =
-            ChannelProxy::new(Channel::try_from(0 as u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
 
         let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
 
@@ -427,7 +2789,7 @@ mod tests {
     #[should_panic(expected = "must be within the limits")]
     fn set_pw_ms_too_large() {
         let mut channel =
-            ChannelProxy::new(Channel::try_from(0 as u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
+            ChannelProxy::new(Channel::try_from(0_u8).unwrap(), TEST_PCA_CLOCK_CONFIG);
 
         let mut mock_pca9685_proxy: Box<dyn Pca9685Proxy> = Box::new(MockPca9685Proxy {});
 
@@ -0,0 +1,198 @@
+use crate::{
+    ChannelCommand, CommandType, DerivedChannelConfig, DerivedExpression, Pca9685Error,
+    Pca9685Result,
+};
+use pwm_pca9685::Channel;
+use std::collections::{HashMap, HashSet};
+
+/// The virtual channels (see [crate::Config::virtual_channels]), each with
+/// its most recently set value (initially 0.0, i.e. centered), and the
+/// Channels derived from them
+pub(crate) struct VirtualChannels {
+    values: HashMap<String, f64>,
+    derived: Vec<DerivedChannelConfig>,
+}
+
+impl VirtualChannels {
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if a virtual channel appears
+    ///   more than once, a Channel is derived more than once, or a
+    ///   [DerivedChannelConfig] is invalid
+    pub fn new(names: &[String], derived: &[DerivedChannelConfig]) -> Pca9685Result<Self> {
+        let mut values = HashMap::new();
+        for name in names {
+            if name.is_empty() || values.insert(name.clone(), 0.0).is_some() {
+                return Err(Pca9685Error::InvalidConfiguration(format!(
+                    "Virtual channel '{}' is empty or appears more than once",
+                    name
+                )));
+            }
+        }
+
+        let mut channels = HashSet::new();
+        for derived_channel in derived {
+            derived_channel.validate(names)?;
+            if !channels.insert(derived_channel.channel) {
+                return Err(Pca9685Error::InvalidConfiguration(format!(
+                    "Channel {} is derived more than once",
+                    derived_channel.channel
+                )));
+            }
+        }
+
+        Ok(VirtualChannels {
+            values,
+            derived: derived.to_vec(),
+        })
+    }
+
+    pub fn values(&self) -> HashMap<String, f64> {
+        self.values.clone()
+    }
+
+    /// Returns the values that result from applying `updates` to the current
+    /// values; virtual channels absent from `updates` retain their value.
+    pub fn stage(&self, updates: &HashMap<String, f64>) -> Pca9685Result<HashMap<String, f64>> {
+        let mut values = self.values.clone();
+
+        for (name, value) in updates {
+            let Some(staged) = values.get_mut(name) else {
+                return Err(Pca9685Error::InvalidCommand(format!(
+                    "No virtual channel {}.",
+                    name
+                )));
+            };
+            if !(-1.0..=1.0).contains(value) {
+                return Err(Pca9685Error::InvalidCommand(format!(
+                    "Virtual channel {} must be within [-1.0, 1.0] (got {}).",
+                    name, value
+                )));
+            }
+            *staged = *value;
+        }
+
+        Ok(values)
+    }
+
+    /// Returns the commands that position each Channel derived from any of
+    /// `updated` per `values`, ordered by Channel
+    pub fn commands(
+        &self,
+        values: &HashMap<String, f64>,
+        updated: &HashMap<String, f64>,
+    ) -> Vec<ChannelCommand> {
+        let mut commands: Vec<ChannelCommand> = self
+            .derived
+            .iter()
+            .filter(|derived_channel| {
+                derived_channel
+                    .value
+                    .sources()
+                    .any(|source| updated.contains_key(source))
+            })
+            .map(|derived_channel| ChannelCommand {
+                channel: Channel::try_from(derived_channel.channel).unwrap(),
+                command_type: CommandType::Percent,
+                value: Some((derived_channel.value.evaluate(values).clamp(-1.0, 1.0) + 1.0) / 2.0),
+                basis: None,
+                client_id: None,
+            })
+            .collect();
+        commands.sort_by_key(|command| command.channel as u8);
+
+        commands
+    }
+
+    pub fn commit(&mut self, values: HashMap<String, f64>) {
+        self.values = values;
+    }
+}
+
+impl DerivedExpression {
+    /// Returns the names of the virtual channels the expression is over
+    pub fn sources(&self) -> impl Iterator<Item = &String> {
+        let sources: Vec<&String> = match self {
+            DerivedExpression::Sum(names) => names.iter().collect(),
+            DerivedExpression::Difference(minuend, subtrahend) => vec![minuend, subtrahend],
+            DerivedExpression::Scaled { of, .. } => vec![of],
+        };
+
+        sources.into_iter()
+    }
+
+    /// Returns the expression's (unclamped) value, given the virtual
+    /// channels' `values`
+    pub fn evaluate(&self, values: &HashMap<String, f64>) -> f64 {
+        let value = |name: &String| values.get(name).copied().unwrap_or(0.0);
+
+        match self {
+            DerivedExpression::Sum(names) => names.iter().map(value).sum(),
+            DerivedExpression::Difference(minuend, subtrahend) => {
+                value(minuend) - value(subtrahend)
+            }
+            DerivedExpression::Scaled { of, scale, offset } => scale * value(of) + offset,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VirtualChannels;
+    use crate::{DerivedChannelConfig, Pca9685Error};
+    use std::collections::HashMap;
+
+    #[test]
+    fn convergence() {
+        let names = vec!["pan".to_string(), "convergence".to_string()];
+        let derived: Vec<DerivedChannelConfig> = serde_yaml::from_str(
+            "
+            - channel: 4
+              value: !difference [pan, convergence]
+            - channel: 3
+              value: !sum [pan, convergence]
+            - channel: 5
+              value: !scaled { of: pan, scale: -0.5, offset: 0.25 }
+            ",
+        )
+        .unwrap();
+        let mut virtual_channels = VirtualChannels::new(&names, &derived).unwrap();
+
+        let updates = HashMap::from([("convergence".to_string(), 0.2)]);
+        let values = virtual_channels.stage(&updates).unwrap();
+        let commands = virtual_channels.commands(&values, &updates);
+        let outputs: Vec<(u8, f64)> = commands
+            .iter()
+            .map(|command| (command.channel as u8, command.value.unwrap()))
+            .collect();
+
+        // Driven oppositely about the center; channel 5 is unaffected
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0].0, 3);
+        assert!((outputs[0].1 - 0.6).abs() < 1e-9);
+        assert_eq!(outputs[1].0, 4);
+        assert!((outputs[1].1 - 0.4).abs() < 1e-9);
+        virtual_channels.commit(values);
+
+        // Clamped to the limits
+        let updates = HashMap::from([("pan".to_string(), 1.0)]);
+        let values = virtual_channels.stage(&updates).unwrap();
+        let commands = virtual_channels.commands(&values, &updates);
+        assert_eq!(commands.len(), 3);
+        assert_eq!(commands[0].value, Some(1.0));
+        assert!((commands[1].value.unwrap() - 0.9).abs() < 1e-9);
+        assert!((commands[2].value.unwrap() - 0.375).abs() < 1e-9);
+
+        let updates = HashMap::from([("tilt".to_string(), 0.0)]);
+        assert!(matches!(
+            virtual_channels.stage(&updates),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+        let updates = HashMap::from([("pan".to_string(), 1.5)]);
+        assert!(virtual_channels.stage(&updates).is_err());
+
+        let mut duplicated = derived.clone();
+        duplicated[1].channel = 4;
+        assert!(VirtualChannels::new(&names, &duplicated).is_err());
+        assert!(VirtualChannels::new(&names[..1], &derived).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{As5600Config, FeedbackConfig, FeedbackSource, Pca9685Error, Pca9685Result};
+#[cfg(feature = "linux")]
+use linux_embedded_hal::i2cdev::core::I2CDevice;
+#[cfg(feature = "linux")]
+use linux_embedded_hal::i2cdev::linux::LinuxI2CDevice;
+use std::fs;
+
+/// The AS5600 cannot be opened without the `linux` feature
+#[cfg(not(feature = "linux"))]
+type LinuxI2CDevice = std::convert::Infallible;
+
+/// The (fixed) I2C address of the AS5600
+const AS5600_ADDRESS: u16 = 0x36;
+
+/// The AS5600's RAW ANGLE register (12 bits, high byte first)
+#[cfg(feature = "linux")]
+const AS5600_RAW_ANGLE_REGISTER: u8 = 0x0C;
+
+/// The AS5600's resolution (counts per turn)
+const AS5600_RESOLUTION: u16 = 4096;
+
+/// Reads a Channel's feedback from its [FeedbackSource], keeping any device
+/// open between reads (and reopening it after an error).
+pub(crate) struct Feedback {
+    config: FeedbackConfig,
+    as5600: Option<LinuxI2CDevice>,
+}
+
+impl Feedback {
+    pub fn new(config: FeedbackConfig) -> Self {
+        Feedback {
+            config,
+            as5600: None,
+        }
+    }
+
+    pub fn config(&self) -> &FeedbackConfig {
+        &self.config
+    }
+
+    /// Reads the raw feedback value
+    pub fn read(&mut self) -> Pca9685Result<f64> {
+        match &self.config.source {
+            FeedbackSource::Path(path) => read_path(path),
+            FeedbackSource::As5600(as5600) => {
+                let result = read_as5600(as5600, &mut self.as5600);
+                if result.is_err() {
+                    self.as5600 = None;
+                }
+                result.map(|raw_angle| as5600_angle(as5600, raw_angle) as f64)
+            }
+        }
+    }
+}
+
+fn read_path(path: &str) -> Pca9685Result<f64> {
+    let raw = fs::read_to_string(path).map_err(|error| {
+        Pca9685Error::InvalidConfiguration(format!("Unable to read feedback {}: {}", path, error))
+    })?;
+
+    raw.trim().parse().map_err(|_| {
+        Pca9685Error::InvalidConfiguration(format!(
+            "Feedback {} is not a number: '{}'",
+            path,
+            raw.trim()
+        ))
+    })
+}
+
+#[cfg(not(feature = "linux"))]
+fn read_as5600(config: &As5600Config, _device: &mut Option<LinuxI2CDevice>) -> Pca9685Result<u16> {
+    Err(Pca9685Error::InvalidConfiguration(format!(
+        "Unable to read AS5600 at {} ({:#02x}): requires the linux feature",
+        config.device, AS5600_ADDRESS
+    )))
+}
+
+#[cfg(feature = "linux")]
+fn read_as5600(config: &As5600Config, device: &mut Option<LinuxI2CDevice>) -> Pca9685Result<u16> {
+    let error = |error| {
+        Pca9685Error::InvalidConfiguration(format!(
+            "Unable to read AS5600 at {} ({:#02x}): {}",
+            config.device, AS5600_ADDRESS, error
+        ))
+    };
+
+    let device = match device {
+        Some(device) => device,
+        None => device.insert(LinuxI2CDevice::new(&config.device, AS5600_ADDRESS).map_err(error)?),
+    };
+
+    let mut raw_angle = [0_u8; 2];
+    device
+        .write(&[AS5600_RAW_ANGLE_REGISTER])
+        .and_then(|()| device.read(&mut raw_angle))
+        .map_err(error)?;
+
+    Ok(u16::from_be_bytes(raw_angle) & (AS5600_RESOLUTION - 1))
+}
+
+/// Returns `raw_angle` relative to the configured zero offset and direction
+fn as5600_angle(config: &As5600Config, raw_angle: u16) -> u16 {
+    let angle = (raw_angle + AS5600_RESOLUTION - config.zero_offset) % AS5600_RESOLUTION;
+    if config.reversed {
+        (AS5600_RESOLUTION - angle) % AS5600_RESOLUTION
+    } else {
+        angle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{as5600_angle, Feedback};
+    use crate::{As5600Config, FeedbackConfig, FeedbackSource};
+
+    #[test]
+    fn as5600() {
+        let mut config = As5600Config {
+            device: "/dev/foo".to_owned(),
+            zero_offset: 1000,
+            reversed: false,
+        };
+        assert_eq!(as5600_angle(&config, 1000), 0);
+        assert_eq!(as5600_angle(&config, 1100), 100);
+        assert_eq!(as5600_angle(&config, 900), 3996);
+
+        config.reversed = true;
+        assert_eq!(as5600_angle(&config, 1000), 0);
+        assert_eq!(as5600_angle(&config, 900), 100);
+
+        let feedback_config: FeedbackConfig = serde_yaml::from_str(
+            "as5600: { device: /dev/foo, zero_offset: 1000, reversed: true }\nmin_raw: 0\nmax_raw: 1024",
+        )
+        .unwrap();
+        assert_eq!(feedback_config.source, FeedbackSource::As5600(config));
+        assert_eq!(feedback_config.max_raw, Some(1024.0));
+
+        // Without the device
+        let mut feedback = Feedback::new(feedback_config);
+        assert!(feedback.read().is_err());
+    }
+
+    #[test]
+    fn path() {
+        let path = std::env::temp_dir().join("pca9685-feedback-path");
+        std::fs::write(&path, " 1234\n").unwrap();
+        let mut feedback = Feedback::new(FeedbackConfig {
+            source: FeedbackSource::Path(path.to_str().unwrap().to_owned()),
+            min_raw: None,
+            max_raw: None,
+            verify: None,
+        });
+        assert_eq!(feedback.read().unwrap(), 1234.0);
+
+        std::fs::write(&path, "n/a").unwrap();
+        assert!(feedback.read().is_err());
+    }
+}
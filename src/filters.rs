@@ -0,0 +1,190 @@
+//! Built-in [ChannelFilter]s.  Register them with [crate::pca9685::Pca9685::add_filter].
+use crate::utils::apply_expo;
+use crate::{ChannelConfig, ChannelFilter};
+use pwm_pca9685::Channel;
+
+/// Limits how far a Channel may move in a single write: the requested count
+/// is clamped to within `max_step_counts` of the Channel's current count.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct SlewFilter {
+    pub max_step_counts: u16,
+}
+
+impl ChannelFilter for SlewFilter {
+    fn transform(&self, _: Channel, requested_count: u16, state: &ChannelConfig) -> u16 {
+        match state.current_count {
+            Some(current_count) => requested_count.clamp(
+                current_count.saturating_sub(self.max_step_counts),
+                current_count.saturating_add(self.max_step_counts),
+            ),
+            None => requested_count,
+        }
+    }
+}
+
+/// Ignores small changes (e.g., joystick jitter): requests within
+/// `counts` of the Channel's current count leave the Channel where it is.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct DeadbandFilter {
+    pub counts: u16,
+}
+
+impl ChannelFilter for DeadbandFilter {
+    fn transform(&self, _: Channel, requested_count: u16, state: &ChannelConfig) -> u16 {
+        match state.current_count {
+            Some(current_count) if requested_count.abs_diff(current_count) <= self.counts => {
+                current_count
+            }
+            _ => requested_count,
+        }
+    }
+}
+
+/// Applies an exponential response curve about the center of the Channel's
+/// limits, giving finer control near the center: `y = (1 - expo) * x +
+/// expo * x^3`, where `x` is the requested position normalized to [-1.0, 1.0].  `expo`
+/// of 0.0 is linear; 1.0 is fully cubic.  Requests outside the Channel's
+/// limits are passed through unchanged.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ExpoFilter {
+    pub expo: f64,
+}
+
+impl ChannelFilter for ExpoFilter {
+    fn transform(&self, _: Channel, requested_count: u16, state: &ChannelConfig) -> u16 {
+        let (min_on_count, max_on_count) = state.limits();
+        if max_on_count <= min_on_count || !(min_on_count..=max_on_count).contains(&requested_count)
+        {
+            return requested_count;
+        }
+
+        let center = (min_on_count as f64 + max_on_count as f64) / 2.0;
+        let half_range = (max_on_count - min_on_count) as f64 / 2.0;
+        let x = (requested_count as f64 - center) / half_range;
+
+        (center + apply_expo(x, self.expo) * half_range).round() as u16
+    }
+}
+
+/// Scales the requested count's offset from the center of the Channel's
+/// limits by the selected rate of the Channel's [crate::DualRate] (if any).
+///
+/// Registered on every Channel; Channels without a `dual_rate` are unaffected.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct DualRateFilter;
+
+impl ChannelFilter for DualRateFilter {
+    fn transform(&self, _: Channel, requested_count: u16, state: &ChannelConfig) -> u16 {
+        let dual_rate = match &state.dual_rate {
+            Some(dual_rate) => dual_rate,
+            None => return requested_count,
+        };
+
+        let (min_on_count, max_on_count) = state.limits();
+        let center = (min_on_count as f64 + max_on_count as f64) / 2.0;
+
+        (center + (requested_count as f64 - center) * dual_rate.scale()).round() as u16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeadbandFilter, DualRateFilter, ExpoFilter, SlewFilter};
+    use crate::{ChannelConfig, ChannelFilter, ChannelLimits, DualRate, Rate};
+    use pwm_pca9685::Channel;
+
+    fn state(current_count: Option<u16>) -> ChannelConfig {
+        ChannelConfig {
+            channel: Channel::C0,
+            current_count,
+            custom_limits: Some(ChannelLimits::from_count_limits(1000, 3000)),
+            owner: None,
+            calibration: None,
+            inverted: false,
+            trim_counts: 0,
+            esc: None,
+            tags: Vec::new(),
+            reduced_hold: None,
+            thermal_limits: None,
+            dual_rate: None,
+            failsafe: None,
+            display_unit: None,
+            display_value: None,
+            name: None,
+            feedback: None,
+            pid: None,
+            input_shaping: None,
+            low_pass: None,
+        }
+    }
+
+    #[test]
+    fn slew() {
+        let filter = SlewFilter {
+            max_step_counts: 100,
+        };
+        assert_eq!(
+            filter.transform(Channel::C0, 2500, &state(Some(2000))),
+            2100
+        );
+        assert_eq!(
+            filter.transform(Channel::C0, 1500, &state(Some(2000))),
+            1900
+        );
+        assert_eq!(
+            filter.transform(Channel::C0, 2050, &state(Some(2000))),
+            2050
+        );
+        assert_eq!(filter.transform(Channel::C0, 2500, &state(None)), 2500);
+    }
+
+    #[test]
+    fn deadband() {
+        let filter = DeadbandFilter { counts: 10 };
+        assert_eq!(
+            filter.transform(Channel::C0, 2010, &state(Some(2000))),
+            2000
+        );
+        assert_eq!(
+            filter.transform(Channel::C0, 1990, &state(Some(2000))),
+            2000
+        );
+        assert_eq!(
+            filter.transform(Channel::C0, 2011, &state(Some(2000))),
+            2011
+        );
+        assert_eq!(filter.transform(Channel::C0, 2010, &state(None)), 2010);
+    }
+
+    #[test]
+    fn expo() {
+        let filter = ExpoFilter { expo: 1.0 };
+        assert_eq!(filter.transform(Channel::C0, 2000, &state(None)), 2000);
+        assert_eq!(filter.transform(Channel::C0, 1000, &state(None)), 1000);
+        assert_eq!(filter.transform(Channel::C0, 3000, &state(None)), 3000);
+        assert_eq!(filter.transform(Channel::C0, 2500, &state(None)), 2125);
+        assert_eq!(filter.transform(Channel::C0, 1500, &state(None)), 1875);
+        assert_eq!(filter.transform(Channel::C0, 500, &state(None)), 500);
+
+        let linear = ExpoFilter { expo: 0.0 };
+        assert_eq!(linear.transform(Channel::C0, 2500, &state(None)), 2500);
+    }
+
+    #[test]
+    fn dual_rate() {
+        let mut state = state(None);
+        assert_eq!(DualRateFilter.transform(Channel::C0, 3000, &state), 3000);
+
+        state.dual_rate = Some(DualRate {
+            low: 0.5,
+            high: 1.0,
+            selected: Rate::Low,
+        });
+        assert_eq!(DualRateFilter.transform(Channel::C0, 3000, &state), 2500);
+        assert_eq!(DualRateFilter.transform(Channel::C0, 1000, &state), 1500);
+        assert_eq!(DualRateFilter.transform(Channel::C0, 2000, &state), 2000);
+
+        state.dual_rate.as_mut().unwrap().selected = Rate::High;
+        assert_eq!(DualRateFilter.transform(Channel::C0, 3000, &state), 3000);
+    }
+}
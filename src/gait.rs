@@ -0,0 +1,162 @@
+use crate::{CommandType, GaitConfig, GaitPattern, Keyframe, Pca9685Error, Pca9685Result};
+use pwm_pca9685::Channel;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// The interval between a gait's keyframes
+const GAIT_STEP_MS: u64 = 20;
+
+/// The name under which a gait plays (see [crate::PlaybackStatus]), e.g.
+/// `gait/trot`
+pub(crate) fn sequence_name(name: &str) -> String {
+    format!("gait/{}", name)
+}
+
+/// Returns when (as a fraction of the cycle) the `index`th of `legs` legs
+/// starts swinging, and for what fraction of the cycle it swings
+fn leg_timing(pattern: GaitPattern, index: usize, legs: usize) -> (f64, f64) {
+    match pattern {
+        GaitPattern::Trot => ((index % 2) as f64 * 0.5, 0.5),
+        GaitPattern::Wave => (index as f64 / legs as f64, 1.0 / legs as f64),
+    }
+}
+
+/// Returns a leg's (swing, lift) at `phase` (a fraction) through its cycle,
+/// which starts with it swinging for `duty` of the cycle: swing runs from
+/// back (-1.0) to front (1.0) with the leg lifted (at most 1.0) in an arc,
+/// then back again on the ground
+fn leg_position(phase: f64, duty: f64) -> (f64, f64) {
+    if phase < duty {
+        let progress = phase / duty;
+        (2.0 * progress - 1.0, (PI * progress).sin())
+    } else {
+        let progress = (phase - duty) / (1.0 - duty);
+        (1.0 - 2.0 * progress, 0.0)
+    }
+}
+
+/// Generates one cycle of `gait` as keyframes (every [GAIT_STEP_MS], ordered
+/// by `t_ms`, then channel), given the configured `groups`.  Played in a
+/// loop of the gait's `period_ms`, the cycle repeats seamlessly.
+///
+/// Error conditions:
+/// * [Pca9685Error::NoSuchGroupError] if a leg's group doesn't exist
+pub(crate) fn keyframes(
+    gait: &GaitConfig,
+    groups: &HashMap<String, Vec<Channel>>,
+) -> Pca9685Result<Vec<Keyframe>> {
+    let legs = gait
+        .legs
+        .iter()
+        .map(|leg| {
+            groups
+                .get(&leg.group)
+                .map(|channels| (leg, channels))
+                .ok_or_else(|| Pca9685Error::NoSuchGroupError(leg.group.clone()))
+        })
+        .collect::<Pca9685Result<Vec<_>>>()?;
+
+    let keyframe = |t_ms: u64, channel: Channel, value: f64| Keyframe {
+        t_ms,
+        channel: channel as u8,
+        command_type: CommandType::Percent,
+        value: Some(value.clamp(0.0, 1.0)),
+    };
+
+    let mut keyframes = Vec::new();
+    for t_ms in (0..gait.period_ms).step_by(GAIT_STEP_MS as usize) {
+        for (index, (leg, channels)) in legs.iter().enumerate() {
+            let (start, duty) = leg_timing(gait.pattern, index, legs.len());
+            let phase = (t_ms as f64 / gait.period_ms as f64 - start).rem_euclid(1.0);
+            let (swing, lift) = leg_position(phase, duty);
+            let swing = if leg.reversed { -swing } else { swing };
+
+            keyframes.push(keyframe(
+                t_ms,
+                channels[0],
+                0.5 + swing * gait.stride_pct / 2.0,
+            ));
+            if let Some(lift_channel) = channels.get(1) {
+                keyframes.push(keyframe(t_ms, *lift_channel, 0.5 + lift * gait.lift_pct));
+            }
+        }
+    }
+    keyframes.sort_by_key(|keyframe| (keyframe.t_ms, keyframe.channel));
+
+    Ok(keyframes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{keyframes, leg_position, leg_timing};
+    use crate::{GaitConfig, GaitLeg, GaitPattern, Keyframe, Pca9685Error};
+    use pwm_pca9685::Channel;
+    use std::collections::HashMap;
+
+    fn value_at(keyframes: &[Keyframe], t_ms: u64, channel: u8) -> f64 {
+        keyframes
+            .iter()
+            .find(|keyframe| keyframe.t_ms == t_ms && keyframe.channel == channel)
+            .and_then(|keyframe| keyframe.value)
+            .unwrap()
+    }
+
+    #[test]
+    fn timing() {
+        assert_eq!(leg_timing(GaitPattern::Trot, 2, 4), (0.0, 0.5));
+        assert_eq!(leg_timing(GaitPattern::Trot, 3, 4), (0.5, 0.5));
+        assert_eq!(leg_timing(GaitPattern::Wave, 3, 4), (0.75, 0.25));
+
+        assert_eq!(leg_position(0.0, 0.5), (-1.0, 0.0));
+        let (swing, lift) = leg_position(0.25, 0.5);
+        assert!(swing.abs() < 1e-9 && (lift - 1.0).abs() < 1e-9);
+        assert_eq!(leg_position(0.5, 0.5), (1.0, 0.0));
+        assert_eq!(leg_position(0.75, 0.5), (0.0, 0.0));
+    }
+
+    #[test]
+    fn trot() {
+        let groups = HashMap::from([
+            ("front_left".to_string(), vec![Channel::C0, Channel::C1]),
+            ("front_right".to_string(), vec![Channel::C2, Channel::C3]),
+        ]);
+        let mut gait = GaitConfig {
+            name: "trot".to_string(),
+            pattern: GaitPattern::Trot,
+            legs: vec![
+                GaitLeg {
+                    group: "front_left".to_string(),
+                    reversed: false,
+                },
+                GaitLeg {
+                    group: "front_right".to_string(),
+                    reversed: true,
+                },
+            ],
+            stride_pct: 0.4,
+            lift_pct: 0.2,
+            period_ms: 1000,
+        };
+
+        let keyframes = keyframes(&gait, &groups).unwrap();
+        assert_eq!(keyframes.len(), 50 * 4);
+        assert!(keyframes
+            .windows(2)
+            .all(|pair| pair[0].t_ms <= pair[1].t_ms));
+
+        // The first leg swings forward (lifted) while the second pushes back
+        assert!((value_at(&keyframes, 0, 0) - 0.3).abs() < 1e-9);
+        assert!((value_at(&keyframes, 0, 1) - 0.5).abs() < 1e-9);
+        assert!((value_at(&keyframes, 0, 2) - 0.3).abs() < 1e-9);
+        assert!((value_at(&keyframes, 240, 1) - 0.7).abs() < 0.01);
+        assert!((value_at(&keyframes, 240, 3) - 0.5).abs() < 1e-9);
+        assert!((value_at(&keyframes, 500, 0) - 0.7).abs() < 1e-9);
+        assert!((value_at(&keyframes, 740, 3) - 0.7).abs() < 0.01);
+
+        gait.legs[1].group = "rear".to_string();
+        assert!(matches!(
+            super::keyframes(&gait, &groups),
+            Err(Pca9685Error::NoSuchGroupError(group)) if group == "rear"
+        ));
+    }
+}
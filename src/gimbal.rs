@@ -0,0 +1,413 @@
+use crate::pid::Pid;
+use crate::{
+    ChannelCommand, CommandType, GimbalAxis, GimbalConfig, GimbalStatus, ImuConfig, ImuModel,
+    Pca9685Error, Pca9685Result,
+};
+use linux_embedded_hal::i2cdev::core::I2CDevice;
+use linux_embedded_hal::i2cdev::linux::LinuxI2CDevice;
+use pwm_pca9685::Channel;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The client that stabilizing motions are attributed to
+pub(crate) const GIMBAL_CLIENT_ID: &str = "gimbal";
+
+/// How often the IMU is read, and the axes corrected
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// The MPU6050's default I2C address (AD0 low)
+const MPU6050_ADDRESS: u16 = 0x68;
+
+/// The MPU6050's PWR_MGMT_1 register (cleared to wake it from sleep)
+const MPU6050_PWR_MGMT_1_REGISTER: u8 = 0x6B;
+
+/// The first of the MPU6050's 14 measurement registers: accelerometer X, Y,
+/// Z, temperature, then gyroscope X, Y, Z (each 16 bits, high byte first)
+const MPU6050_ACCEL_XOUT_H_REGISTER: u8 = 0x3B;
+
+/// The MPU6050's gyroscope sensitivity at its default range (±250°/s)
+const MPU6050_GYRO_LSB_PER_DPS: f64 = 131.0;
+
+/// The weight of the integrated gyroscope rate (vs. the accelerometer's
+/// angle) in the MPU6050's complementary filter
+const MPU6050_GYRO_WEIGHT: f64 = 0.98;
+
+/// The BNO055's default I2C address (COM3 low)
+const BNO055_ADDRESS: u16 = 0x28;
+
+/// The BNO055's OPR_MODE register, and its NDOF (sensor fusion) mode
+const BNO055_OPR_MODE_REGISTER: u8 = 0x3D;
+const BNO055_NDOF_MODE: u8 = 0x0C;
+
+/// How long the BNO055 takes to switch operating modes
+const BNO055_MODE_SWITCH_DELAY: Duration = Duration::from_millis(20);
+
+/// The first of the BNO055's Euler angle registers: heading, roll, then
+/// pitch (each 16 bits, low byte first)
+const BNO055_EUL_HEADING_LSB_REGISTER: u8 = 0x1A;
+
+/// The BNO055's Euler angle resolution
+const BNO055_LSB_PER_DEGREE: f64 = 16.0;
+
+/// An inertial measurement unit, measuring the camera platform's attitude
+pub(crate) trait Imu: Send {
+    /// Returns the (pitch, roll), in degrees, as of `now`
+    fn attitude(&mut self, now: Instant) -> Pca9685Result<(f64, f64)>;
+}
+
+/// Estimates attitude by fusing the MPU6050's gyroscope rates with its
+/// accelerometer's (gravity) angles
+struct Mpu6050 {
+    config: ImuConfig,
+    device: Option<LinuxI2CDevice>,
+    estimate: Option<(Instant, (f64, f64))>,
+}
+
+impl Imu for Mpu6050 {
+    fn attitude(&mut self, now: Instant) -> Pca9685Result<(f64, f64)> {
+        let mut data = [0_u8; 14];
+        read_registers(
+            &self.config,
+            MPU6050_ADDRESS,
+            &[MPU6050_PWR_MGMT_1_REGISTER, 0],
+            MPU6050_ACCEL_XOUT_H_REGISTER,
+            &mut data,
+            &mut self.device,
+        )?;
+
+        let word = |i: usize| i16::from_be_bytes([data[2 * i], data[2 * i + 1]]) as f64;
+        let accel = mpu6050_accel_attitude(word(0), word(1), word(2));
+        let rates = (
+            word(5) / MPU6050_GYRO_LSB_PER_DPS,
+            word(4) / MPU6050_GYRO_LSB_PER_DPS,
+        );
+
+        let attitude = match self.estimate {
+            Some((at, previous)) if now > at => {
+                mpu6050_fuse(previous, rates, now.duration_since(at).as_secs_f64(), accel)
+            }
+            _ => accel,
+        };
+        self.estimate = Some((now, attitude));
+
+        Ok(attitude)
+    }
+}
+
+/// Reads the BNO055's own (fused) attitude
+struct Bno055 {
+    config: ImuConfig,
+    device: Option<LinuxI2CDevice>,
+}
+
+impl Imu for Bno055 {
+    fn attitude(&mut self, _now: Instant) -> Pca9685Result<(f64, f64)> {
+        let mut data = [0_u8; 6];
+        read_registers(
+            &self.config,
+            BNO055_ADDRESS,
+            &[BNO055_OPR_MODE_REGISTER, BNO055_NDOF_MODE],
+            BNO055_EUL_HEADING_LSB_REGISTER,
+            &mut data,
+            &mut self.device,
+        )?;
+
+        Ok(bno055_attitude(&data))
+    }
+}
+
+/// Reads `data` from the registers starting at `register`, first opening the
+/// device (and writing `init` to it) if needed.  The device is closed after
+/// an error, so that it is reopened (and reinitialized) on the next read.
+fn read_registers(
+    config: &ImuConfig,
+    default_address: u16,
+    init: &[u8],
+    register: u8,
+    data: &mut [u8],
+    device: &mut Option<LinuxI2CDevice>,
+) -> Pca9685Result<()> {
+    let address = config.address.unwrap_or(default_address);
+    let error = |error| {
+        Pca9685Error::InvalidConfiguration(format!(
+            "Unable to read {:?} at {} ({:#02x}): {}",
+            config.model, config.device, address, error
+        ))
+    };
+
+    let result = match device {
+        Some(device) => device.write(&[register]).and_then(|()| device.read(data)),
+        None => {
+            let opened =
+                device.insert(LinuxI2CDevice::new(&config.device, address).map_err(error)?);
+            opened.write(init).and_then(|()| {
+                if config.model == ImuModel::Bno055 {
+                    thread::sleep(BNO055_MODE_SWITCH_DELAY);
+                }
+                opened.write(&[register]).and_then(|()| opened.read(data))
+            })
+        }
+    };
+    if result.is_err() {
+        *device = None;
+    }
+
+    result.map_err(error)
+}
+
+/// Returns the (pitch, roll), in degrees, of gravity measured as
+/// (`ax`, `ay`, `az`)
+fn mpu6050_accel_attitude(ax: f64, ay: f64, az: f64) -> (f64, f64) {
+    (
+        (-ax).atan2((ay * ay + az * az).sqrt()).to_degrees(),
+        ay.atan2(az).to_degrees(),
+    )
+}
+
+/// Returns the `previous` (pitch, roll) advanced by the gyroscope's `rates`
+/// (in °/s) over `dt` seconds, corrected towards the accelerometer's `accel`
+/// attitude
+fn mpu6050_fuse(previous: (f64, f64), rates: (f64, f64), dt: f64, accel: (f64, f64)) -> (f64, f64) {
+    let fuse = |previous: f64, rate: f64, accel: f64| {
+        MPU6050_GYRO_WEIGHT * (previous + rate * dt) + (1.0 - MPU6050_GYRO_WEIGHT) * accel
+    };
+
+    (
+        fuse(previous.0, rates.0, accel.0),
+        fuse(previous.1, rates.1, accel.1),
+    )
+}
+
+/// Returns the (pitch, roll), in degrees, from the BNO055's Euler angle
+/// registers
+fn bno055_attitude(data: &[u8; 6]) -> (f64, f64) {
+    let angle =
+        |i: usize| i16::from_le_bytes([data[i], data[i + 1]]) as f64 / BNO055_LSB_PER_DEGREE;
+
+    (angle(4), angle(2))
+}
+
+/// Stabilizes a [GimbalConfig]'s axes while enabled, correcting each for the
+/// tilt its IMU measures.
+pub(crate) struct Gimbal {
+    config: GimbalConfig,
+    imu: Box<dyn Imu>,
+    enabled: bool,
+    polled_at: Option<Instant>,
+    attitude: Option<(f64, f64)>,
+    pitch: Pid,
+    roll: Pid,
+
+    /// The last IMU error, logged once until the IMU recovers
+    imu_error: Option<String>,
+}
+
+impl Gimbal {
+    pub fn new(config: &GimbalConfig) -> Self {
+        let imu: Box<dyn Imu> = match config.imu.model {
+            ImuModel::Mpu6050 => Box::new(Mpu6050 {
+                config: config.imu.clone(),
+                device: None,
+                estimate: None,
+            }),
+            ImuModel::Bno055 => Box::new(Bno055 {
+                config: config.imu.clone(),
+                device: None,
+            }),
+        };
+
+        Gimbal::with_imu(config, imu)
+    }
+
+    fn with_imu(config: &GimbalConfig, imu: Box<dyn Imu>) -> Self {
+        Gimbal {
+            config: config.clone(),
+            imu,
+            enabled: false,
+            polled_at: None,
+            attitude: None,
+            pitch: Gimbal::pid(&config.pitch),
+            roll: Gimbal::pid(&config.roll),
+            imu_error: None,
+        }
+    }
+
+    fn pid(axis: &GimbalAxis) -> Pid {
+        Pid::new(axis.gains, axis.max_pct - axis.min_pct)
+    }
+
+    pub fn status(&self) -> GimbalStatus {
+        GimbalStatus {
+            enabled: self.enabled,
+            pitch_deg: self.attitude.map(|(pitch, _)| pitch),
+            roll_deg: self.attitude.map(|(_, roll)| roll),
+        }
+    }
+
+    /// Starts or stops stabilizing, starting afresh from the axes' centers
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled != self.enabled {
+            self.enabled = enabled;
+            self.polled_at = None;
+            self.attitude = None;
+            self.pitch = Gimbal::pid(&self.config.pitch);
+            self.roll = Gimbal::pid(&self.config.roll);
+        }
+    }
+
+    /// Returns the corrections due as of `now`: each axis is driven from its
+    /// center to counter its tilt, within its range
+    pub fn poll(&mut self, now: Instant) -> Vec<ChannelCommand> {
+        if !self.enabled || matches!(self.polled_at, Some(at) if now < at + POLL_INTERVAL) {
+            return Vec::new();
+        }
+        self.polled_at = Some(now);
+
+        let (pitch, roll) = match self.imu.attitude(now) {
+            Ok(attitude) => {
+                self.imu_error = None;
+                attitude
+            }
+            Err(error) => {
+                let error = error.to_string();
+                if self.imu_error.as_ref() != Some(&error) {
+                    log::warn!(target: "pca9685", "Gimbal: {}", error);
+                    self.imu_error = Some(error);
+                }
+                return Vec::new();
+            }
+        };
+        self.attitude = Some((pitch, roll));
+
+        [
+            (&self.config.pitch, &mut self.pitch, pitch),
+            (&self.config.roll, &mut self.roll, roll),
+        ]
+        .into_iter()
+        .map(|(axis, pid, tilt)| {
+            let correction = pid.update(-tilt, now);
+            let correction = if axis.reversed {
+                -correction
+            } else {
+                correction
+            };
+
+            ChannelCommand {
+                channel: Channel::try_from(axis.channel).unwrap(),
+                command_type: CommandType::Percent,
+                value: Some((axis.center_pct + correction).clamp(axis.min_pct, axis.max_pct)),
+                basis: None,
+                client_id: Some(GIMBAL_CLIENT_ID.to_string()),
+            }
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bno055_attitude, mpu6050_accel_attitude, mpu6050_fuse, Gimbal, Imu};
+    use crate::{
+        GimbalAxis, GimbalConfig, ImuConfig, ImuModel, Pca9685Error, Pca9685Result, PidGains,
+    };
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    struct MockImu(Arc<Mutex<Pca9685Result<(f64, f64)>>>);
+
+    impl Imu for MockImu {
+        fn attitude(&mut self, _now: Instant) -> Pca9685Result<(f64, f64)> {
+            match &*self.0.lock().unwrap() {
+                Ok(attitude) => Ok(*attitude),
+                Err(error) => Err(Pca9685Error::InvalidConfiguration(error.to_string())),
+            }
+        }
+    }
+
+    fn axis(channel: u8) -> GimbalAxis {
+        GimbalAxis {
+            channel,
+            gains: PidGains {
+                kp: 0.01,
+                ki: 0.0,
+                kd: 0.0,
+            },
+            center_pct: 0.5,
+            min_pct: 0.2,
+            max_pct: 0.8,
+            reversed: false,
+        }
+    }
+
+    #[test]
+    fn attitude() {
+        let (pitch, roll) = mpu6050_accel_attitude(0.0, 0.0, 16384.0);
+        assert!(pitch.abs() < 1e-9 && roll.abs() < 1e-9);
+        let (pitch, roll) = mpu6050_accel_attitude(0.0, 16384.0, 16384.0);
+        assert!(pitch.abs() < 1e-9 && (roll - 45.0).abs() < 1e-9);
+        let (pitch, _) = mpu6050_accel_attitude(-16384.0, 0.0, 16384.0);
+        assert!((pitch - 45.0).abs() < 1e-9);
+
+        // Mostly the integrated rate, corrected slightly towards gravity
+        let (pitch, roll) = mpu6050_fuse((10.0, 0.0), (100.0, 0.0), 0.1, (0.0, 0.0));
+        assert!((pitch - 19.6).abs() < 1e-9);
+        assert_eq!(roll, 0.0);
+
+        // Heading 0°, roll -2.5°, pitch 10°
+        assert_eq!(
+            bno055_attitude(&[0x00, 0x00, 0xD8, 0xFF, 0xA0, 0x00]),
+            (10.0, -2.5)
+        );
+    }
+
+    #[test]
+    fn poll() {
+        let config = GimbalConfig {
+            imu: ImuConfig {
+                device: "/dev/i2c-1".to_string(),
+                model: ImuModel::Mpu6050,
+                address: None,
+            },
+            pitch: axis(0),
+            roll: GimbalAxis {
+                reversed: true,
+                ..axis(1)
+            },
+        };
+        let attitude = Arc::new(Mutex::new(Ok((10.0, 5.0))));
+        let mut gimbal = Gimbal::with_imu(&config, Box::new(MockImu(attitude.clone())));
+        let now = Instant::now();
+
+        // Disabled
+        assert!(gimbal.poll(now).is_empty());
+        assert_eq!(gimbal.status().pitch_deg, None);
+
+        gimbal.set_enabled(true);
+        let commands = gimbal.poll(now);
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].channel as u8, 0);
+        assert!((commands[0].value.unwrap() - 0.4).abs() < 1e-9);
+        assert_eq!(commands[0].client_id.as_deref(), Some("gimbal"));
+        assert_eq!(commands[1].channel as u8, 1);
+        assert!((commands[1].value.unwrap() - 0.55).abs() < 1e-9);
+        assert_eq!(gimbal.status().pitch_deg, Some(10.0));
+        assert_eq!(gimbal.status().roll_deg, Some(5.0));
+
+        // Polled at intervals
+        assert!(gimbal.poll(now + Duration::from_millis(10)).is_empty());
+
+        // Corrections are limited to the axis' range
+        *attitude.lock().unwrap() = Ok((-90.0, 0.0));
+        let commands = gimbal.poll(now + Duration::from_millis(20));
+        assert_eq!(commands[0].value, Some(0.8));
+
+        // IMU errors skip corrections
+        *attitude.lock().unwrap() = Err(Pca9685Error::InvalidConfiguration("gone".to_string()));
+        assert!(gimbal.poll(now + Duration::from_millis(40)).is_empty());
+
+        gimbal.set_enabled(false);
+        assert!(gimbal.poll(now + Duration::from_millis(60)).is_empty());
+        assert!(!gimbal.status().enabled);
+        assert_eq!(gimbal.status().pitch_deg, None);
+    }
+}
@@ -0,0 +1,194 @@
+//! Implementations of [GpioInput]
+use crate::{GpioBackend, GpioInput, GpioPin, Pca9685Error, Pca9685Result};
+use std::fs;
+
+/// The character device whose lines are numbered by [GpioPin::Pin]
+#[cfg(feature = "gpio-cdev")]
+const DEFAULT_GPIO_CHIP: &str = "/dev/gpiochip0";
+
+fn gpio_error(pin: &GpioPin, error: impl ToString) -> Pca9685Error {
+    Pca9685Error::InvalidConfiguration(format!("Unable to read {}: {}", pin, error.to_string()))
+}
+
+/// Reads a file holding `1` when the pin is high, e.g. one exported through
+/// sysfs
+pub struct SysfsInput {
+    path: String,
+}
+
+impl SysfsInput {
+    pub fn new(path: &str) -> SysfsInput {
+        SysfsInput {
+            path: path.to_string(),
+        }
+    }
+}
+
+impl GpioInput for SysfsInput {
+    fn is_high(&mut self) -> Pca9685Result<bool> {
+        let value = fs::read_to_string(&self.path)
+            .map_err(|error| gpio_error(&GpioPin::Path(self.path.clone()), error))?;
+
+        Ok(value.trim() == "1")
+    }
+}
+
+/// Reads a pin of a Raspberry Pi through rppal
+#[cfg(feature = "rppal")]
+pub struct RppalInput {
+    pin: rppal::gpio::InputPin,
+}
+
+#[cfg(feature = "rppal")]
+impl RppalInput {
+    /// Opens the pin numbered `bcm` (BCM numbering) as an input.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if the pin is unavailable
+    pub fn open(bcm: u8) -> Pca9685Result<RppalInput> {
+        let pin = rppal::gpio::Gpio::new()
+            .and_then(|gpio| gpio.get(bcm))
+            .map_err(|error| gpio_error(&GpioPin::Pin(bcm), error))?;
+
+        Ok(RppalInput {
+            pin: pin.into_input(),
+        })
+    }
+}
+
+#[cfg(feature = "rppal")]
+impl GpioInput for RppalInput {
+    fn is_high(&mut self) -> Pca9685Result<bool> {
+        Ok(self.pin.is_high())
+    }
+}
+
+/// Reads a line of a GPIO character device through gpio-cdev
+#[cfg(feature = "gpio-cdev")]
+pub struct CdevInput {
+    pin: GpioPin,
+    handle: gpio_cdev::LineHandle,
+}
+
+#[cfg(feature = "gpio-cdev")]
+impl CdevInput {
+    /// Opens `line` of the character device `chip` as an input.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if the line is unavailable
+    pub fn open(chip: &str, line: u32) -> Pca9685Result<CdevInput> {
+        let pin = GpioPin::Line {
+            chip: chip.to_string(),
+            line,
+        };
+        let handle = gpio_cdev::Chip::new(chip)
+            .and_then(|mut chip| chip.get_line(line))
+            .and_then(|line| line.request(gpio_cdev::LineRequestFlags::INPUT, 0, "pca9685"))
+            .map_err(|error| gpio_error(&pin, error))?;
+
+        Ok(CdevInput { pin, handle })
+    }
+}
+
+#[cfg(feature = "gpio-cdev")]
+impl GpioInput for CdevInput {
+    fn is_high(&mut self) -> Pca9685Result<bool> {
+        self.handle
+            .get_value()
+            .map(|value| value == 1)
+            .map_err(|error| gpio_error(&self.pin, error))
+    }
+}
+
+/// Chooses the backend of a [GpioPin::Pin] when none is configured: rppal on
+/// a Raspberry Pi (given the `rppal` feature), otherwise gpio-cdev
+fn detect_backend() -> GpioBackend {
+    #[cfg(feature = "rppal")]
+    if rppal::system::DeviceInfo::new().is_ok() {
+        return GpioBackend::Rppal;
+    }
+
+    GpioBackend::Cdev
+}
+
+#[cfg(feature = "rppal")]
+fn open_rppal(bcm: u8) -> Pca9685Result<Box<dyn GpioInput>> {
+    Ok(Box::new(RppalInput::open(bcm)?))
+}
+
+#[cfg(not(feature = "rppal"))]
+fn open_rppal(bcm: u8) -> Pca9685Result<Box<dyn GpioInput>> {
+    Err(gpio_error(&GpioPin::Pin(bcm), "requires the rppal feature"))
+}
+
+#[cfg(feature = "gpio-cdev")]
+fn open_cdev(chip: Option<&str>, line: u32) -> Pca9685Result<Box<dyn GpioInput>> {
+    Ok(Box::new(CdevInput::open(
+        chip.unwrap_or(DEFAULT_GPIO_CHIP),
+        line,
+    )?))
+}
+
+#[cfg(not(feature = "gpio-cdev"))]
+fn open_cdev(chip: Option<&str>, line: u32) -> Pca9685Result<Box<dyn GpioInput>> {
+    let pin = match chip {
+        Some(chip) => GpioPin::Line {
+            chip: chip.to_string(),
+            line,
+        },
+        None => GpioPin::Pin(line as u8),
+    };
+    Err(gpio_error(&pin, "requires the gpio-cdev feature"))
+}
+
+impl GpioPin {
+    /// Opens the pin as an input: a [GpioPin::Path] as a file, a
+    /// [GpioPin::Line] through gpio-cdev, and a [GpioPin::Pin] through
+    /// `backend` (by default, as detected; see [crate::Config::gpio]).
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if the pin cannot be opened, or
+    ///   its backend's feature isn't enabled
+    pub fn open_input(&self, backend: Option<GpioBackend>) -> Pca9685Result<Box<dyn GpioInput>> {
+        match self {
+            GpioPin::Path(path) => Ok(Box::new(SysfsInput::new(path))),
+            GpioPin::Line { chip, line } => open_cdev(Some(chip), *line),
+            GpioPin::Pin(pin) => match backend.unwrap_or_else(detect_backend) {
+                GpioBackend::Rppal => open_rppal(*pin),
+                GpioBackend::Cdev => open_cdev(None, *pin as u32),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GpioBackend, GpioPin, Pca9685Error};
+
+    #[test]
+    fn open_input() {
+        let path = std::env::temp_dir().join("pca9685-gpio");
+        std::fs::write(&path, "1\n").unwrap();
+        let mut input = GpioPin::Path(path.to_str().unwrap().to_string())
+            .open_input(Some(GpioBackend::Rppal))
+            .unwrap();
+        assert!(input.is_high().unwrap());
+        std::fs::write(&path, "0\n").unwrap();
+        assert!(!input.is_high().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            input.is_high(),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+
+        // No such chip
+        let line = GpioPin::Line {
+            chip: "/nonexistent/gpiochip".to_string(),
+            line: 17,
+        };
+        assert!(matches!(
+            line.open_input(None),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+    }
+}
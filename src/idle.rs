@@ -0,0 +1,113 @@
+use crate::{ChannelCommand, CommandType, IdleBehavior, IdleConfig};
+use pwm_pca9685::Channel;
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+/// The client that idle motions are attributed to
+pub(crate) const IDLE_CLIENT_ID: &str = "idle";
+
+/// Tracks when commands were last received, and plays an [IdleConfig]'s
+/// behaviors once none have arrived for its `after_ms`.  Fed by every
+/// command, idling stops as soon as a real command arrives.
+pub(crate) struct Idle {
+    after: Duration,
+    behaviors: Vec<IdleBehavior>,
+    fed_at: Instant,
+
+    /// When each behavior next moves, once idling
+    next_motion_at: Vec<Option<Instant>>,
+}
+
+impl Idle {
+    pub fn new(config: &IdleConfig, now: Instant) -> Self {
+        Idle {
+            after: Duration::from_millis(config.after_ms),
+            behaviors: config.behaviors.clone(),
+            fed_at: now,
+            next_motion_at: vec![None; config.behaviors.len()],
+        }
+    }
+
+    pub fn feed(&mut self, now: Instant) {
+        self.fed_at = now;
+        self.next_motion_at.fill(None);
+    }
+
+    pub fn is_idling(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.fed_at) >= self.after
+    }
+
+    /// Returns the motions due as of `now`: each behavior moves to a random
+    /// position within its range on idling, and again after each of its
+    /// random intervals
+    pub fn poll<R: Rng>(&mut self, now: Instant, rng: &mut R) -> Vec<ChannelCommand> {
+        if !self.is_idling(now) {
+            return Vec::new();
+        }
+
+        let mut commands = Vec::new();
+        for (behavior, next_motion_at) in self.behaviors.iter().zip(&mut self.next_motion_at) {
+            if matches!(next_motion_at, Some(at) if now < *at) {
+                continue;
+            }
+
+            commands.push(ChannelCommand {
+                channel: Channel::try_from(behavior.channel).unwrap(),
+                command_type: CommandType::Percent,
+                value: Some(rng.gen_range(behavior.min_pct..=behavior.max_pct)),
+                basis: None,
+                client_id: Some(IDLE_CLIENT_ID.to_string()),
+            });
+            *next_motion_at = Some(
+                now + Duration::from_millis(
+                    rng.gen_range(behavior.min_interval_ms..=behavior.max_interval_ms),
+                ),
+            );
+        }
+
+        commands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Idle;
+    use crate::{IdleBehavior, IdleConfig};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn poll() {
+        let config = IdleConfig {
+            after_ms: 1000,
+            behaviors: vec![IdleBehavior {
+                channel: 3,
+                min_pct: 0.4,
+                max_pct: 0.6,
+                min_interval_ms: 200,
+                max_interval_ms: 400,
+            }],
+        };
+        let now = Instant::now();
+        let at = |ms: u64| now + Duration::from_millis(ms);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut idle = Idle::new(&config, now);
+
+        assert!(idle.poll(at(999), &mut rng).is_empty());
+        let commands = idle.poll(at(1000), &mut rng);
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].channel as u8, 3);
+        assert!((0.4..=0.6).contains(&commands[0].value.unwrap()));
+
+        // Moves again after a random interval
+        assert!(idle.poll(at(1199), &mut rng).is_empty());
+        assert_eq!(idle.poll(at(1400), &mut rng).len(), 1);
+
+        // Yields to commands
+        idle.feed(at(1500));
+        assert!(!idle.is_idling(at(2499)));
+        assert!(idle.poll(at(2499), &mut rng).is_empty());
+        assert_eq!(idle.poll(at(2500), &mut rng).len(), 1);
+    }
+}
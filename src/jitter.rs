@@ -0,0 +1,84 @@
+use crate::JitterConfig;
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+/// Band-limited random noise within ±`amplitude_counts` (see
+/// [JitterConfig]): a new random offset is chosen every half period of
+/// `bandwidth_hz`, and the offset moves linearly from one to the next.
+pub(crate) struct Jitter {
+    config: JitterConfig,
+    from: f64,
+    to: f64,
+    segment_started_at: Option<Instant>,
+}
+
+impl Jitter {
+    pub fn new(config: JitterConfig) -> Self {
+        Jitter {
+            config,
+            from: 0.0,
+            to: 0.0,
+            segment_started_at: None,
+        }
+    }
+
+    pub fn config(&self) -> JitterConfig {
+        self.config
+    }
+
+    fn segment(&self) -> Duration {
+        Duration::from_secs_f64(0.5 / self.config.bandwidth_hz)
+    }
+
+    /// Returns the offset (in counts) as of `now`
+    pub fn offset<R: Rng>(&mut self, now: Instant, rng: &mut R) -> f64 {
+        let amplitude = self.config.amplitude_counts as f64;
+        let segment = self.segment();
+
+        let started_at = match self.segment_started_at {
+            Some(started_at) if now.saturating_duration_since(started_at) < segment => started_at,
+            Some(_) | None => {
+                self.from = self.to;
+                self.to = rng.gen_range(-amplitude..=amplitude);
+                self.segment_started_at = Some(now);
+                now
+            }
+        };
+
+        let progress =
+            now.saturating_duration_since(started_at).as_secs_f64() / segment.as_secs_f64();
+
+        self.from + (self.to - self.from) * progress
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Jitter;
+    use crate::JitterConfig;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn offset() {
+        let mut jitter = Jitter::new(JitterConfig {
+            amplitude_counts: 20,
+            bandwidth_hz: 5.0,
+        });
+        let now = Instant::now();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // Starts from the setpoint, and never exceeds the amplitude
+        assert_eq!(jitter.offset(now, &mut rng), 0.0);
+        let mut previous = 0.0;
+        for ms in (5..2000).step_by(5) {
+            let offset = jitter.offset(now + Duration::from_millis(ms), &mut rng);
+            assert!(offset.abs() <= 20.0);
+
+            // Band-limited: moves at most 2 * amplitude per 100ms segment
+            assert!((offset - previous).abs() <= 40.0 * 5.0 / 100.0 + 1e-9);
+            previous = offset;
+        }
+    }
+}
@@ -1,21 +1,69 @@
 use crate::utils::{deserialize_channel, serialize_channel};
-use linux_embedded_hal::i2cdev::linux::LinuxI2CError;
 use pwm_pca9685::Channel;
 use pwm_pca9685::OutputDriver;
 use serde::Deserialize;
 use serde::Serialize;
-use std::collections::HashMap;
-use std::sync::Mutex;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::marker::PhantomData;
+use std::sync::atomic::AtomicU32;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use strum::EnumString;
 
+mod backup;
+mod bus;
 mod channelproxy;
+mod derived;
+mod feedback;
+pub mod filters;
+mod gait;
+#[cfg(feature = "gimbal")]
+mod gimbal;
+pub mod gpio;
+mod idle;
+mod jitter;
+mod linear_axis;
+mod loopback;
+mod low_pass;
+mod manager;
+mod mixer;
+mod motion;
 pub mod pca9685;
 mod pca9685_proxy;
+mod pid;
+mod preset;
+mod recorder;
+mod rules;
+mod scene;
+mod sequence;
+mod shaping;
+pub mod storage;
+#[cfg(feature = "stress")]
+pub mod stress;
+mod teleop;
 pub mod utils;
+mod vectors;
+mod watchdog;
 
 /// The PCA9685 has 4096 steps/counts (12-bit PWM) of resolution
 pub const PCA_PWM_RESOLUTION: u16 = 4096;
 
-#[derive(Debug, Deserialize)]
+/// Nominal frequency of the PCA9685's internal oscillator (25 MHz)
+pub const INTERNAL_OSC_HZ: f64 = 25.0 * 1000.0 * 1000.0;
+
+/// The highest frequency the PCA9685 accepts on its EXTCLK pin (50 MHz)
+pub const MAX_EXTERNAL_CLOCK_HZ: u32 = 50 * 1000 * 1000;
+
+/// Output frequencies (Hz) that are safe for typical analog and digital
+/// servos; used to bound diagnostics such as [utils::frequency_sweep]
+pub const SERVO_SAFE_FREQUENCY_HZ: std::ops::RangeInclusive<u16> = 40..=400;
+
+/// Output frequencies (Hz) the PCA9685 can produce from its internal
+/// oscillator, from a prescale of 255 down to 3
+pub const PCA_FREQUENCY_HZ: std::ops::RangeInclusive<u16> = 24..=1526;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 /// An immutable YAML-based configuration of a [Pca9685] device.
 pub struct Config {
     /// Path to I2C device file (e.g, /dev/i2c-1)
@@ -24,15 +72,909 @@ pub struct Config {
     /// Address of PCA9685 (e.g, 0x40)
     pub address: u8,
 
-    /// PWM output frequency
+    /// PWM output frequency (mutually exclusive with `prescale`)
+    #[serde(default)]
     pub output_frequency_hz: u16,
 
+    /// Explicit value of the PRE_SCALE register, from which the output
+    /// frequency is derived (mutually exclusive with `output_frequency_hz`)
+    #[serde(default)]
+    pub prescale: Option<u8>,
+
+    /// Frequency (in Hz) of an oscillator driving the EXTCLK pin, which then
+    /// clocks the device in place of its internal oscillator (see
+    /// [INTERNAL_OSC_HZ]); the prescale and pulse widths are computed against
+    /// it.  Once selected, EXTCLK remains the clock until the device is power
+    /// cycled.
+    #[serde(default)]
+    pub external_clock_hz: Option<u32>,
+
+    /// I2C sub-addresses (at most 3) to which the device also responds,
+    /// programmed into SUBADR1-3 at init, e.g. to address a group of boards
+    #[serde(default)]
+    pub subaddresses: Vec<u8>,
+
+    /// The address to which every board responds together (see
+    /// [Pca9685::broadcast]), programmed into ALLCALLADR at init: by default
+    /// the PCA9685's power-on 0x70, while `null` disables all-call
+    #[serde(default = "Config::default_all_call_address")]
+    pub all_call_address: Option<u8>,
+
     /// Open drain (if not set, use Totem pole)
     #[serde(default)]
     pub open_drain: bool,
 
+    /// What every output does while the OE pin disables the outputs (e.g., on
+    /// fault, if wired to a kill switch); see [Pca9685::set_disabled_output]
+    #[serde(default)]
+    pub disabled_output: DisabledOutput,
+
+    /// Stagger the count at which each Channel's output rises within the
+    /// cycle (see [utils::staggered_on_count]), rather than raising every
+    /// output at once, so that servos don't all draw current at the same
+    /// instant
+    #[serde(default)]
+    pub stagger_on_counts: bool,
+
     #[serde(default)]
     pub channels: Vec<ChannelConfig>,
+
+    /// Further PCA9685s (e.g., chained boards) driven alongside this one by
+    /// a [Pca9685Manager]
+    #[serde(default)]
+    pub devices: Vec<DeviceConfig>,
+
+    #[serde(default)]
+    pub groups: Vec<ChannelGroup>,
+
+    #[serde(default)]
+    pub mixers: Vec<MixerConfig>,
+
+    #[serde(default)]
+    pub teleop: TeleopConfig,
+
+    #[serde(default)]
+    pub watchdog: Option<WatchdogConfig>,
+
+    #[serde(default)]
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+
+    #[serde(default)]
+    pub idle: Option<IdleConfig>,
+
+    #[serde(default)]
+    pub startup: Option<StartupConfig>,
+
+    /// Requires the `gimbal` feature (otherwise, ignored)
+    #[serde(default)]
+    pub gimbal: Option<GimbalConfig>,
+
+    #[serde(default)]
+    pub presets: Vec<PresetConfig>,
+
+    #[serde(default)]
+    pub scenes: SceneConfig,
+
+    #[serde(default)]
+    pub gaits: Vec<GaitConfig>,
+
+    #[serde(default)]
+    pub linear_axes: Vec<LinearAxisConfig>,
+
+    #[serde(default)]
+    pub rules: Vec<RuleConfig>,
+
+    /// Named channels without outputs of their own, from which
+    /// `derived_channels` are computed (see [Pca9685::set_virtual_channels])
+    #[serde(default)]
+    pub virtual_channels: Vec<String>,
+
+    #[serde(default)]
+    pub derived_channels: Vec<DerivedChannelConfig>,
+
+    /// How GPIO pins given by number are read (see [GpioBackend]); by
+    /// default, through rppal on a Raspberry Pi, and otherwise the GPIO
+    /// character device
+    #[serde(default)]
+    pub gpio: Option<GpioBackend>,
+
+    /// Permit jitter to be injected into Channels for testing (see
+    /// [Pca9685::set_jitter]); leave unset on anything but a test bench
+    #[serde(default)]
+    pub allow_jitter: bool,
+
+    /// Reject commands (other than full off) to Channels without
+    /// `custom_limits`, so that nothing moves until it has been configured
+    #[serde(default)]
+    pub strict: bool,
+
+    /// Further configuration files (e.g., a shared base file beneath a
+    /// per-robot file of calibrations) merged beneath this one by
+    /// [Config::load_from_file]; relative paths are relative to this file.
+    /// Empty once loaded.
+    #[serde(default)]
+    pub include: Vec<String>,
+
+    /// Save Channels configured through the service into the configuration
+    /// file (as if requested with `persist=true`; see [Config::persist_channel])
+    #[serde(default)]
+    pub autopersist: bool,
+    /// Directory in which the service saves sequences (see [SequenceConfig]),
+    /// one `<name>.yaml` each, and from which it loads them at startup;
+    /// relative paths are relative to this file.  Shorthand for `storage`
+    /// of files kept in the directory itself; at most one may be given.
+    #[serde(default)]
+    pub sequences_dir: Option<String>,
+
+    /// Where the service saves what it persists at runtime (see [Storage]),
+    /// e.g. `storage: { sqlite: { path: pca9685.sqlite } }`
+    #[serde(default, with = "serde_yaml::with::singleton_map")]
+    pub storage: Option<StorageConfig>,
+
+    /// The strings interpolated by [Config::load_from_file], each as written
+    /// (e.g., `${file:api.key}`) by the value it resolved to, so that the
+    /// configuration is serialized (e.g., by [Config::to_yaml] and
+    /// [Config::uninterpolated]) without the secrets it refers to
+    #[serde(skip)]
+    pub interpolated: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+/// Selects a [Storage] (see [Config::open_storage]); relative paths are
+/// relative to the configuration file.
+pub enum StorageConfig {
+    /// Human-readable YAML files beneath `dir` (see [storage::FileStorage])
+    Files { dir: String },
+
+    /// A SQLite database at `path`, safe for concurrent access (see
+    /// [storage::SqliteStorage]); requires the `sqlite` feature
+    Sqlite { path: String },
+}
+
+/// Persists what is saved at runtime (e.g., sequences authored through the
+/// service), so that it survives a restart.
+pub trait Storage: Send + Sync {
+    /// Returns every saved sequence, in no particular order
+    fn sequences(&self) -> Pca9685Result<Vec<SequenceConfig>>;
+
+    /// Saves `sequence`, replacing any saved under its name
+    fn save_sequence(&self, sequence: &SequenceConfig) -> Pca9685Result<()>;
+
+    /// Removes the sequence saved as `name`, if any
+    fn remove_sequence(&self, name: &str) -> Pca9685Result<()>;
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A further PCA9685 driven from the same [Config] (see [Pca9685Manager]),
+/// e.g. a chained board at 0x41.  It shares the Config's output settings
+/// (frequency, prescale, output driver, circuit breaker and startup), but
+/// none of its
+/// behaviors (e.g., groups, sequences), which apply only to the first device.
+pub struct DeviceConfig {
+    /// Path to I2C device file (e.g, /dev/i2c-1)
+    pub device: String,
+
+    pub address: u8,
+
+    #[serde(default)]
+    pub channels: Vec<ChannelConfig>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// A write to the PCA9685 captured by a tracing [Pca9685] (see
+/// [Pca9685::tracing]): the `count` written to `channel`, None if it was set
+/// full off ([PCA_PWM_RESOLUTION] if full on).
+pub struct TraceWrite {
+    pub channel: u8,
+    pub count: Option<u16>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+/// Commands along with the writes each is expected to produce (see
+/// [TestVectors::verify]), e.g. to pin the counts produced for given pulse
+/// widths and percentages across upgrades of this crate.
+pub struct TestVectors {
+    /// The configuration the commands are executed against
+    pub config: Config,
+    pub vectors: Vec<TestVector>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+/// A [ChannelCommand] of [TestVectors], along with the writes it must
+/// produce, in order, or whether it must be `rejected`
+pub struct TestVector {
+    pub command: ChannelCommand,
+    #[serde(default)]
+    pub writes: Vec<TraceWrite>,
+    #[serde(default)]
+    pub rejected: bool,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A [TestVector] (by its `index`) whose outcome differs from that expected
+pub struct VectorFailure {
+    pub index: usize,
+    pub message: String,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingSeverity {
+    /// The [Config] would be rejected
+    Error,
+
+    /// The [Config] would be accepted, but is likely a mistake
+    Warning,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A problem found by [Pca9685::validate_config], located by `path` within
+/// the [Config] (e.g., `channels[2]`)
+pub struct ConfigFinding {
+    pub severity: FindingSeverity,
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A setting whose runtime value differs from its value in a [Config] (see
+/// [Pca9685::diff_config]), e.g. `channels[3].custom_limits`.  A value is
+/// null where the setting is absent.
+pub struct ConfigDifference {
+    pub path: String,
+    pub file: serde_yaml::Value,
+    pub runtime: serde_yaml::Value,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// The outcome of [Pca9685::validate_config]: `valid` unless any finding is
+/// an [FindingSeverity::Error]
+pub struct ConfigValidation {
+    pub valid: bool,
+    pub findings: Vec<ConfigFinding>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// Drives Channels to their [FailsafeOutput] when commands stop arriving:
+/// every Channel with a failsafe output once no command (or heartbeat, see
+/// [Pca9685::heartbeat]) has been received from any client for `timeout_ms`,
+/// and the Channels owned by a client once that client has been silent for
+/// `timeout_ms`.  Moves, linear axes and any sequence (or gait) driving those
+/// Channels are stopped first.
+pub struct WatchdogConfig {
+    pub timeout_ms: u64,
+
+    /// Channels driven full off when the watchdog trips if they have no
+    /// failsafe output of their own (e.g., a rover's drive motors)
+    #[serde(default)]
+    pub channels: Vec<u8>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// Marks a Channel faulted once it has accumulated `max_errors` driver errors
+/// within `window_ms`: its output is driven to its [FailsafeOutput] (or full
+/// off), and commands are rejected until [Pca9685::clear_fault] is called.
+pub struct CircuitBreakerConfig {
+    pub max_errors: u32,
+    pub window_ms: u64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// Eases the Channels into their initial positions at boot, to avoid
+/// brown-outs and violent first movements: once the device is enabled, and
+/// after a further `delay_ms`, each Channel configured with a
+/// `current_count` ramps to it over `ramp_ms`, from the lowest count within
+/// its limits (i.e., from off, absent custom limits).  Without a
+/// [StartupConfig], configured `current_count`s are not written at boot.
+pub struct StartupConfig {
+    #[serde(default)]
+    pub delay_ms: u64,
+    #[serde(default)]
+    pub ramp_ms: u64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// Keeps characters lifelike (e.g., blinking, breathing, swaying) while no
+/// commands arrive: once none (nor a heartbeat, see [Pca9685::heartbeat])
+/// has been received for `after_ms`, and no sequence is playing, each
+/// [IdleBehavior] moves its Channel at random.  The next command ends idling.
+pub struct IdleConfig {
+    pub after_ms: u64,
+    pub behaviors: Vec<IdleBehavior>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// Moves a Channel to a random position within [`min_pct`, `max_pct`] of its
+/// limits, every `min_interval_ms` to `max_interval_ms` (chosen at random).
+/// See [IdleConfig].
+pub struct IdleBehavior {
+    pub channel: u8,
+    pub min_pct: f64,
+    pub max_pct: f64,
+    pub min_interval_ms: u64,
+    pub max_interval_ms: u64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A small automation, evaluated as the [Pca9685] runs: `then` is carried out
+/// (attributed to the client `rules`) as `when` comes to hold, e.g. setting
+/// a Channel full on once another has been above 80% for 5 seconds, or
+/// applying a safe preset on an emergency stop.
+pub struct RuleConfig {
+    pub name: String,
+    pub when: RuleCondition,
+    pub then: RuleAction,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+/// When a [RuleConfig] acts
+pub enum RuleCondition {
+    /// Once a Channel's output has been above the threshold for its
+    /// `for_ms`, and again only after falling to or below it
+    Above(RuleThreshold),
+
+    /// Once a Channel's output has been below the threshold for its
+    /// `for_ms`, and again only after rising to or above it
+    Below(RuleThreshold),
+
+    /// Each time such an event is published (see [Pca9685::subscribe])
+    Event(RuleEvent),
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// A Channel's output, as a fraction (`pct`) of its limits (see
+/// [CommandType::Percent]), held for `for_ms`.  A Channel without output is
+/// neither above nor below any threshold.
+pub struct RuleThreshold {
+    pub channel: u8,
+    pub pct: f64,
+    #[serde(default)]
+    pub for_ms: u64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+/// The kinds of [Pca9685Event] a [RuleCondition] can await
+pub enum RuleEvent {
+    EmergencyStop,
+    WatchdogTripped,
+    ChannelFaulted,
+    ThermalWarning,
+    PositionError,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+/// What a [RuleConfig] does
+pub enum RuleAction {
+    FullOn(u8),
+    FullOff(u8),
+
+    /// Sets the Channel to `pct` of its limits
+    SetPct {
+        channel: u8,
+        pct: f64,
+    },
+
+    /// Applies the named preset's outputs (see [PresetConfig]) immediately
+    Preset(String),
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// Holds a camera level: an IMU on the camera platform measures its pitch and
+/// roll, each of which its [GimbalAxis] corrects.  Stabilization starts
+/// disabled (see `Pca9685::set_gimbal_enabled`, with the `gimbal` feature).
+pub struct GimbalConfig {
+    pub imu: ImuConfig,
+    pub pitch: GimbalAxis,
+    pub roll: GimbalAxis,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// An IMU on the I2C bus `device` (e.g., `/dev/i2c-1`), at `address` (by
+/// default, the model's usual address)
+pub struct ImuConfig {
+    pub device: String,
+    pub model: ImuModel,
+    #[serde(default)]
+    pub address: Option<u16>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ImuModel {
+    /// Attitude is estimated from its accelerometer and gyroscope
+    Mpu6050,
+
+    /// Attitude is read from its sensor fusion
+    Bno055,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// Drives `channel` to hold one axis of a [GimbalConfig] level: from
+/// `center_pct` of the Channel's range (level, as mounted), its `gains` act
+/// on the tilt (in degrees) to correct the output (as a fraction of the
+/// Channel's range), within [`min_pct`, `max_pct`].  Give
+/// `reversed` if increasing the Channel's output increases the tilt.
+pub struct GimbalAxis {
+    pub channel: u8,
+    pub gains: PidGains,
+    #[serde(default = "GimbalAxis::default_center_pct")]
+    pub center_pct: f64,
+    #[serde(default)]
+    pub min_pct: f64,
+    #[serde(default = "GimbalAxis::default_max_pct")]
+    pub max_pct: f64,
+    #[serde(default)]
+    pub reversed: bool,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// Whether a [GimbalConfig] is stabilizing, along with the latest attitude
+/// (in degrees) measured while it is
+pub struct GimbalStatus {
+    pub enabled: bool,
+    pub pitch_deg: Option<f64>,
+    pub roll_deg: Option<f64>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// Random noise superimposed on a Channel's output for testing, e.g. to shake
+/// out mechanical resonances or loose linkages (see [Pca9685::set_jitter]):
+/// up to ±`amplitude_counts`, varying at up to `bandwidth_hz`, and always
+/// within the Channel's limits.
+pub struct JitterConfig {
+    pub amplitude_counts: u16,
+    pub bandwidth_hz: f64,
+}
+
+/// The highest `bandwidth_hz` of a [JitterConfig], as jitter is updated every
+/// 5ms
+pub const MAX_JITTER_BANDWIDTH_HZ: f64 = 100.0;
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A named set of Channels (e.g., "left_arm") that can be addressed together
+/// by a [ChannelSelector].
+pub struct ChannelGroup {
+    pub name: String,
+    pub channels: Vec<u8>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A named animation: [Keyframe]s, in order of `t_ms`, along with `steps`
+/// that play other sequences within it.  See [Pca9685::configure_sequence].
+pub struct SequenceConfig {
+    pub name: String,
+    #[serde(default)]
+    pub keyframes: Vec<Keyframe>,
+    #[serde(default)]
+    pub steps: Vec<SequenceStep>,
+
+    /// Length of the sequence when repeated by a [SequenceStep] (e.g., to
+    /// hold after the last keyframe); by default, the time of its last
+    /// keyframe
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// Plays another sequence `repeat` times, back to back, starting `t_ms`
+/// milliseconds into the including sequence.  Given a `group` (see
+/// [ChannelGroup]), only the keyframes of its Channels are played, so that
+/// steps may run in parallel as tracks on different Channels.
+pub struct SequenceStep {
+    pub t_ms: u64,
+    pub sequence: String,
+    #[serde(default = "SequenceStep::default_repeat")]
+    pub repeat: u32,
+    #[serde(default)]
+    pub group: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+/// What advances the playhead of a sequence being played (see
+/// [Pca9685::play_sequence])
+pub enum PlaybackClock {
+    /// The playhead advances in real time from the start of playback
+    #[default]
+    Internal,
+
+    /// The playhead follows an external source, e.g. the timecode of an
+    /// audio track played elsewhere, as reported by [Pca9685::seek_sequence].
+    /// It waits for the first report, advances in real time from each one,
+    /// and holds once none has arrived for `timeout_ms` (e.g., the audio
+    /// stopped).  Playback continues until stopped.
+    External { timeout_ms: u64 },
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// A region of a sequence being played that repeats (see
+/// [Pca9685::set_loop_region]): on reaching `end_ms`, the playhead returns to
+/// `start_ms`
+pub struct LoopRegion {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// The sequence being played (see [Pca9685::playback]): its playhead `t_ms`
+/// (None until an external clock first reports it), and `duration_ms`, the
+/// time of its last (resolved) keyframe.  The playhead advances at `rate`
+/// times real time, repeating the `loop_region`, if any.
+pub struct PlaybackStatus {
+    pub sequence: String,
+    pub clock: PlaybackClock,
+    pub t_ms: Option<u64>,
+    pub duration_ms: u64,
+    pub rate: f64,
+    pub loop_region: Option<LoopRegion>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// The macro being recorded (see [Pca9685::start_recording]): the
+/// `sequence` it will be saved as, the time `t_ms` since recording started,
+/// and the number of `keyframes` recorded so far.
+pub struct RecordingStatus {
+    pub sequence: String,
+    pub t_ms: u64,
+    pub keyframes: usize,
+}
+
+/// The range of playback rates accepted by [Pca9685::set_playback_rate]
+pub const PLAYBACK_RATES: std::ops::RangeInclusive<f64> = 0.25..=4.0;
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// Adapts a sequence, as it is played (see [Pca9685::replay_sequence]), to a
+/// rig with different wiring or pace: each keyframe of a Channel in
+/// `channel_map` plays on the Channel it maps to instead, and the playhead
+/// starts out advancing at `rate` (within [PLAYBACK_RATES]) times real time.
+pub struct Replay {
+    #[serde(default)]
+    pub channel_map: BTreeMap<u8, u8>,
+    #[serde(default = "Replay::default_rate")]
+    pub rate: f64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// The output of a Channel `t_ms` milliseconds into a [SequenceConfig], as
+/// an absolute command (i.e., not `AdjustCount`/`AdjustPct`).
+pub struct Keyframe {
+    pub t_ms: u64,
+    pub channel: u8,
+    pub command_type: CommandType,
+    #[serde(default)]
+    pub value: Option<f64>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A named scene: the output of each of several Channels, applied together
+/// (see [Pca9685::apply_preset]), e.g. a lighting state or an animatronic
+/// pose.
+pub struct PresetConfig {
+    pub name: String,
+    pub outputs: Vec<PresetOutput>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// The output of a Channel within a [PresetConfig], as an absolute command
+/// (i.e., not `AdjustCount`/`AdjustPct`)
+pub struct PresetOutput {
+    pub channel: u8,
+    pub command_type: CommandType,
+    #[serde(default)]
+    pub value: Option<f64>,
+}
+
+/// The longest fade accepted by [Pca9685::apply_preset]
+pub const MAX_PRESET_FADE_MS: u64 = 60_000;
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Default)]
+/// How presets layered as scenes (see [Pca9685::apply_scene]) combine, as on
+/// a lighting console: each Channel takes its output from the
+/// highest-priority scenes including it.  Among those, `htp_channels` (e.g.,
+/// dimmers) take the highest output (HTP), and other Channels the output of
+/// the latest applied (LTP).
+pub struct SceneConfig {
+    #[serde(default)]
+    pub htp_channels: Vec<u8>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A preset applied as a scene, at `priority` (see [SceneConfig])
+pub struct SceneLayer {
+    pub preset: String,
+    pub priority: i32,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A walking gait for a legged robot (see [Pca9685::start_gait]).  Each of
+/// its `legs`, in order around the body, swings fore and aft by
+/// `stride_pct` of its range about the center, lifting by `lift_pct` while
+/// swinging forward, once every `period_ms`; the `pattern` staggers them.
+pub struct GaitConfig {
+    pub name: String,
+    pub pattern: GaitPattern,
+    pub legs: Vec<GaitLeg>,
+    #[serde(default = "GaitConfig::default_stride_pct")]
+    pub stride_pct: f64,
+    #[serde(default = "GaitConfig::default_lift_pct")]
+    pub lift_pct: f64,
+    #[serde(default = "GaitConfig::default_period_ms")]
+    pub period_ms: u64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum GaitPattern {
+    /// Alternate legs swing together (diagonal pairs of a quadruped, or a
+    /// hexapod's tripods), each for half the period
+    Trot,
+
+    /// One leg swings at a time, in turn
+    Wave,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A leg of a [GaitConfig]: the [ChannelGroup] whose first Channel swings it
+/// fore and aft and whose second (if any) lifts it.  Give `reversed` if
+/// increasing the swing Channel's output moves the leg backwards (e.g., on
+/// the other side of the body).
+pub struct GaitLeg {
+    pub group: String,
+    #[serde(default)]
+    pub reversed: bool,
+}
+
+/// The shortest [GaitConfig] `period_ms`
+pub const MIN_GAIT_PERIOD_MS: u64 = 200;
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A carriage (e.g., of a camera slider or plotter) driven along a rail by a
+/// speed-controlled Channel, e.g. a continuous-rotation servo, which is
+/// stopped at the center of its range and travels `max_speed_mm_s` at
+/// either limit (towards `max_mm` at the upper limit, unless `reversed`).
+///
+/// Positions are in millimeters, estimated from the speed and time traveled
+/// since homing onto the `home` switch at `min_mm`, and must lie within
+/// [`min_mm`, `max_mm`].  See [Pca9685::move_linear_axis].
+pub struct LinearAxisConfig {
+    pub name: String,
+    pub channel: u8,
+    pub max_speed_mm_s: f64,
+    #[serde(default)]
+    pub min_mm: f64,
+    pub max_mm: f64,
+
+    /// Speed of moves (and homing) unless given otherwise; by default,
+    /// `max_speed_mm_s`
+    #[serde(default)]
+    pub speed_mm_s: Option<f64>,
+    pub home: HomeSwitch,
+    #[serde(default)]
+    pub reversed: bool,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// The switch a [LinearAxisConfig]'s carriage closes at `min_mm`, read from
+/// its [GpioPin] as high when closed (or low, if `active_low`)
+pub struct HomeSwitch {
+    #[serde(flatten)]
+    pub pin: GpioPin,
+    #[serde(default)]
+    pub active_low: bool,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+/// A GPIO pin read by a safety feature (e.g., a [HomeSwitch]); see
+/// [GpioPin::open_input].
+pub enum GpioPin {
+    /// A file reading `1` when high, e.g. a pin exported through sysfs
+    /// (`/sys/class/gpio/gpio17/value`)
+    Path(String),
+
+    /// A pin by its number (BCM numbering, on a Raspberry Pi), read through
+    /// the [Config] `gpio` backend
+    Pin(u8),
+
+    /// A line of a GPIO character device (e.g., `/dev/gpiochip1`), read
+    /// through gpio-cdev
+    Line { chip: String, line: u32 },
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+/// How a [GpioPin::Pin] is read: through rppal (Raspberry Pi only), or the
+/// Linux GPIO character device (any board).  Each requires the feature of
+/// the same name (`rppal`, `gpio-cdev`).
+pub enum GpioBackend {
+    Rppal,
+    Cdev,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// Statistics (in milliseconds) of the pulse widths or periods measured by
+/// [Pca9685::measure_loopback]
+pub struct PulseStats {
+    pub min_ms: f64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+    pub std_dev_ms: f64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// The output of a Channel as measured through a GPIO input it is looped
+/// back to (see [Pca9685::measure_loopback]): the pulse widths and periods
+/// of `cycles` cycles against those expected of the Channel's output, the
+/// error of the output frequency (i.e., of the PCA9685's oscillator), and
+/// the number of `glitches`: cycles whose pulse width or period strays from
+/// the median by more than [LOOPBACK_GLITCH_TOLERANCE].  Measurements are
+/// only as fine as the `sample_interval_us` at which the input was polled.
+pub struct LoopbackReport {
+    #[serde(
+        serialize_with = "serialize_channel",
+        deserialize_with = "deserialize_channel"
+    )]
+    pub channel: Channel,
+    pub cycles: usize,
+    pub expected_pulse_width_ms: f64,
+    pub expected_period_ms: f64,
+    pub pulse_width: PulseStats,
+    pub period: PulseStats,
+    pub frequency_error_pct: f64,
+    pub glitches: usize,
+    pub sample_interval_us: f64,
+}
+
+/// How far (as a fraction of the median) a cycle's pulse width or period may
+/// stray before it counts as a glitch (see [LoopbackReport])
+pub const LOOPBACK_GLITCH_TOLERANCE: f64 = 0.1;
+
+/// A GPIO pin opened as an input (see [GpioPin::open_input])
+pub trait GpioInput: Send {
+    /// Returns whether the pin is high
+    fn is_high(&mut self) -> Pca9685Result<bool>;
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// The state of a [LinearAxisConfig]: its estimated `position_mm` (None
+/// until homed), the `target_mm` it is moving to (if any), and the speed it
+/// is moving at.
+pub struct LinearAxisStatus {
+    pub name: String,
+    pub homing: bool,
+    pub position_mm: Option<f64>,
+    pub target_mm: Option<f64>,
+    pub speed_mm_s: f64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// Combines named virtual inputs (e.g., "pitch" and "roll") into physical
+/// Channels (e.g., left and right elevons).  See [Pca9685::set_mixer_inputs].
+///
+/// Inputs range over [-1.0, 1.0].  Each output's position is its `offset`
+/// plus the sum of each input multiplied by the corresponding weight,
+/// clamped to [-1.0, 1.0] and mapped onto the Channel's limits (-1.0 is the
+/// minimum, 0.0 the center, and 1.0 the maximum).
+///
+/// `expo` optionally shapes individual inputs (by name) before they are
+/// mixed, as [filters::ExpoFilter] does for a Channel.
+pub struct MixerConfig {
+    pub name: String,
+    pub inputs: Vec<String>,
+    pub outputs: Vec<MixerOutput>,
+    #[serde(default)]
+    pub expo: HashMap<String, f64>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// One row of a [MixerConfig]'s weight matrix: `weights` holds one weight per
+/// input, in the order of [MixerConfig::inputs].
+pub struct MixerOutput {
+    pub channel: u8,
+    pub weights: Vec<f64>,
+    #[serde(default)]
+    pub offset: f64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A Channel whose position is computed from virtual channels (see
+/// [Config::virtual_channels]) by `value`, e.g. a camera convergence control
+/// driving two Channels oppositely.  Like a mixer output (see [MixerConfig]),
+/// the position is clamped to [-1.0, 1.0] and mapped onto the Channel's
+/// limits; it's recomputed whenever one of its sources is set.
+pub struct DerivedChannelConfig {
+    pub channel: u8,
+    pub value: DerivedExpression,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+/// An expression over virtual channels (by name), each within [-1.0, 1.0]
+pub enum DerivedExpression {
+    Sum(Vec<String>),
+
+    /// The first less the second
+    Difference(String, String),
+
+    /// `scale` times `of`, plus `offset`
+    Scaled {
+        of: String,
+        scale: f64,
+        #[serde(default)]
+        offset: f64,
+    },
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Default)]
+/// Teleoperation: named [TeleopProfile]s mapping gamepad axes onto mixer
+/// inputs (virtual channels), like the model memories of an RC transmitter.
+/// See [Pca9685::teleop].
+pub struct TeleopConfig {
+    #[serde(default)]
+    pub profiles: Vec<TeleopProfile>,
+
+    /// Profile active at start-up (if not set, the first profile)
+    #[serde(default)]
+    pub default_profile: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A named set of [AxisMapping]s
+pub struct TeleopProfile {
+    pub name: String,
+    pub axes: Vec<AxisMapping>,
+
+    /// Buttons which, pressed together, select this profile (if empty, the
+    /// profile may only be selected via [Pca9685::select_teleop_profile])
+    #[serde(default)]
+    pub select_buttons: Vec<usize>,
+
+    /// Button which toggles every Channel between its low and high rates
+    /// (see [DualRate])
+    #[serde(default)]
+    pub rate_button: Option<usize>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// Maps a gamepad axis (in [-1.0, 1.0]) onto an input of a [MixerConfig].
+/// The axis is shaped by `expo` (see [filters::ExpoFilter]), then scaled by
+/// `rate` (and inverted if `reversed`).
+pub struct AxisMapping {
+    pub axis: usize,
+    pub mixer: String,
+    pub input: String,
+    #[serde(default)]
+    pub expo: f64,
+    #[serde(default = "AxisMapping::default_rate")]
+    pub rate: f64,
+    #[serde(default)]
+    pub reversed: bool,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Default)]
+/// A snapshot of a gamepad, e.g. as reported by a browser's Gamepad API:
+/// each axis in [-1.0, 1.0], and whether each button is pressed.
+pub struct GamepadState {
+    pub axes: Vec<f64>,
+    #[serde(default)]
+    pub buttons: Vec<bool>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+/// Selects the Channels affected by an operation such as [Pca9685::relax].
+pub enum ChannelSelector {
+    Channel(u8),
+    /// A Channel by its [ChannelConfig] `name`
+    Name(String),
+    Group(String),
+    /// Every Channel whose [ChannelConfig] `tags` include the tag
+    Tag(String),
+    All,
 }
 
 #[derive(Deserialize, Serialize, PartialEq, Clone, Copy)]
@@ -69,14 +1011,49 @@ pub struct ChannelPulseWidthLimits {
     pub max_on_ms: f64,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// A pulse width measured (e.g., with an oscilloscope or logic analyzer) on a
+/// Channel while it was outputting `count`.
+pub struct CalibrationPoint {
+    pub count: u16,
+    pub measured_pw_ms: f64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// Two measurements, taken at different counts, from which a per-Channel
+/// correction is derived to compensate for error in the PCA9685's internal
+/// oscillator.
+///
+/// When present, pulse widths (e.g., [Pca9685::set_pw_ms] and pw_limits) are
+/// converted to counts using the measured relationship rather than the
+/// nominal clock.
+pub struct ChannelCalibration {
+    pub points: [CalibrationPoint; 2],
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+/// Selects the range that a percentage command (e.g., [Pca9685::set_pct_of])
+/// is relative to.
+pub enum PercentBasis {
+    /// 0.0 and 1.0 correspond to the channel's configured limits (if any)
+    #[default]
+    Limits,
+
+    /// 0.0 and 1.0 correspond to the full PWM cycle (e.g., the entire 20ms
+    /// frame at 50Hz), regardless of the channel's configured limits
+    FullRange,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
 /// Represents the desired and/or actual configuration of a Channel.
 ///
 /// As an input, sets the `ChannelCountLimits` on the associated Channel (in
-/// which case `current_count` is not used).
+/// which case `current_count` and `owner` are not used).
 ///
-/// As an output, describes the current PWM count (`current_count`) and
-/// configured limits (`custom_limits`), if any.
+/// As an output, describes the current PWM count (`current_count`),
+/// configured limits (`custom_limits`) and `calibration`, if any, and the
+/// client (`owner`) responsible for the current output, if known.
 pub struct ChannelConfig {
     #[serde(
         serialize_with = "serialize_channel",
@@ -85,8 +1062,620 @@ pub struct ChannelConfig {
     pub channel: Channel,
     pub current_count: Option<u16>,
     pub custom_limits: Option<ChannelLimits>,
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub calibration: Option<ChannelCalibration>,
+
+    /// Mirrors percent commands (and the `display_value` in percent or
+    /// degrees) around the Channel's range, e.g. for the servo of a left leg
+    /// mounted opposite that of the right: 0.0 is then its upper limit
+    #[serde(default)]
+    pub inverted: bool,
+
+    /// Added to every count written to the Channel, after limit checking,
+    /// e.g. to center a servo whose neutral is not quite 1.5ms.  Commanded
+    /// counts (and `current_count`) are reported untrimmed.
+    #[serde(default)]
+    pub trim_counts: i16,
+
+    /// Makes the Channel a brushless motor's ESC, refusing throttle until it
+    /// is armed (see [EscConfig])
+    #[serde(default)]
+    pub esc: Option<EscConfig>,
+    #[serde(default)]
+    pub reduced_hold: Option<ReducedHold>,
+    #[serde(default)]
+    pub thermal_limits: Option<ThermalLimits>,
+    #[serde(default)]
+    pub dual_rate: Option<DualRate>,
+    #[serde(default)]
+    pub failsafe: Option<FailsafeOutput>,
+    #[serde(default)]
+    pub display_unit: Option<DisplayUnit>,
+
+    /// As an output, `current_count` expressed in the `display_unit` (if
+    /// any); ignored as an input
+    #[serde(default)]
+    pub display_value: Option<f64>,
+
+    /// Identifies the Channel (e.g., `left_elbow`) in place of its number,
+    /// including as the log target of its messages
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Free-form labels (e.g., `leg`, `left`, `led`) by which Channels are
+    /// selected together (see [ChannelSelector::Tag])
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    #[serde(default)]
+    pub feedback: Option<FeedbackConfig>,
+
+    /// Closes the loop around `feedback` (which must then give `min_raw` and
+    /// `max_raw`), correcting the output so the feedback follows
+    /// `current_count`
+    #[serde(default)]
+    pub pid: Option<PidGains>,
+
+    /// Shapes changes of `current_count`, suppressing the residual vibration
+    /// of a flexible mechanism (e.g., a long camera boom)
+    #[serde(default)]
+    pub input_shaping: Option<InputShaping>,
+
+    /// Smooths changes of `current_count`, e.g. for setpoints derived from a
+    /// noisy analog input; exclusive of `input_shaping`
+    #[serde(default)]
+    pub low_pass: Option<LowPassConfig>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A Channel's position feedback, read as a raw value from its
+/// [FeedbackSource] (e.g., `path: /sys/...`).
+///
+/// `min_raw`/`max_raw` are the raw values read at the Channel's minimum and
+/// maximum limits, by which feedback is converted to counts.
+pub struct FeedbackConfig {
+    #[serde(flatten)]
+    pub source: FeedbackSource,
+    #[serde(default)]
+    pub min_raw: Option<f64>,
+    #[serde(default)]
+    pub max_raw: Option<f64>,
+    #[serde(default)]
+    pub verify: Option<PositionVerification>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+/// Where a Channel's feedback (see [FeedbackConfig]) is read from.
+pub enum FeedbackSource {
+    /// A number read from a file: typically a Linux IIO channel of an ADC
+    /// that a servo's potentiometer is wired to, such as
+    /// `/sys/bus/iio/devices/iio:device0/in_voltage0_raw`
+    Path(String),
+
+    /// An AS5600 magnetic angle sensor
+    As5600(As5600Config),
 }
 
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// An AS5600 magnetic angle sensor on the I2C bus `device` (e.g.,
+/// `/dev/i2c-1`), read as [0, 4096) counts per turn: relative to the
+/// `zero_offset` raw angle, and counting down instead if `reversed`.
+pub struct As5600Config {
+    pub device: String,
+    #[serde(default)]
+    pub zero_offset: u16,
+    #[serde(default)]
+    pub reversed: bool,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// Verifies a Channel's position against its feedback (which must then give
+/// `min_raw` and `max_raw`): once its output has been unchanged for
+/// `settle_ms`, feedback diverging from it by more than `tolerance_counts`
+/// (e.g., a stall, broken linkage, or slipping horn) raises
+/// [Pca9685Event::PositionError], and, given `fault`, faults the Channel as
+/// its [CircuitBreakerConfig] would.
+pub struct PositionVerification {
+    pub tolerance_counts: u16,
+    #[serde(default = "PositionVerification::default_settle_ms")]
+    pub settle_ms: u64,
+    #[serde(default)]
+    pub fault: bool,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// The gains of a Channel's PID controller, acting on the error (in counts)
+/// between its commanded output and its feedback.  See
+/// [Pca9685::pid_sample].
+pub struct PidGains {
+    pub kp: f64,
+    pub ki: f64,
+    pub kd: f64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// An input shaper, splitting each change of a Channel's output into steps
+/// timed to cancel the residual vibration of a mechanism resonating at
+/// `frequency_hz` with `damping` ratio (within [0.0, 1.0)), which can be
+/// measured by a step test (see [Pca9685::step_test]).  The output settles
+/// half a damped period (ZV) or a whole one (ZVD) after each change.  A PID
+/// controller or jitter takes precedence.
+pub struct InputShaping {
+    pub shaper: Shaper,
+    pub frequency_hz: f64,
+    #[serde(default)]
+    pub damping: f64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// A first-order low-pass filter, through which a Channel's output follows
+/// changes of its commanded output: each `time_constant_ms`, it closes 63% of
+/// the remaining gap.  Lighter-weight than a motion profile (see
+/// [Pca9685::move_to]); a PID controller or jitter takes precedence.
+pub struct LowPassConfig {
+    pub time_constant_ms: u64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+/// The impulses of an [InputShaping]
+pub enum Shaper {
+    /// Zero vibration: two impulses, half a damped period apart
+    Zv,
+
+    /// Zero vibration and derivative: three impulses, more robust to error
+    /// in the `frequency_hz` and `damping`, but twice as slow
+    Zvd,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// An iteration of a Channel's PID controller (see [PidGains]), in counts:
+/// the commanded `setpoint`, the `feedback`, their `error`, and the `output`
+/// written to correct it.  `timestamp_ms` is since the UNIX epoch.
+pub struct PidSample {
+    pub timestamp_ms: u64,
+    pub setpoint: u16,
+    pub feedback: f64,
+    pub error: f64,
+    pub output: u16,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+/// The unit a Channel's output is displayed in (e.g., in logs, and the
+/// `display_value` of its [ChannelConfig]), to suit what is attached to it.
+pub enum DisplayUnit {
+    Counts,
+
+    /// Pulse width in milliseconds
+    Ms,
+
+    /// Pulse width in microseconds
+    Us,
+
+    /// Percent [0.0, 100.0] of the Channel's limits
+    Percent,
+
+    /// Degrees, where the Channel's limits span [0.0, `range`] degrees
+    /// (e.g., `degrees: 180` for a typical servo)
+    Degrees(f64),
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+/// The output a Channel is driven to when control is lost (e.g., neutral
+/// throttle), which must be within the Channel's limits.  See
+/// [Pca9685::rehearse_failsafe].
+pub enum FailsafeOutput {
+    FullOff,
+    Count(u16),
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+/// How a timed move (see [Pca9685::move_to_eased]) progresses from its start
+/// to its target over its duration
+pub enum Easing {
+    /// At constant speed
+    #[default]
+    Linear,
+
+    /// Accelerating, then decelerating, quadratically
+    EaseInOut,
+
+    /// Accelerating, then decelerating, cubically (more gently at either end
+    /// than [Easing::EaseInOut])
+    Cubic,
+
+    /// Following half a cosine wave
+    Sine,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+/// Selects one of a [DualRate]'s scales
+pub enum Rate {
+    Low,
+    #[default]
+    High,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// Low and high rates for a Channel, each a scale (0.0, 1.0] of the Channel's
+/// throw about the center of its limits, e.g. for fine control versus full
+/// throw.  Applied by [filters::DualRateFilter]; the `selected` rate may be
+/// changed at runtime via [Pca9685::select_rate].
+pub struct DualRate {
+    pub low: f64,
+    #[serde(default = "DualRate::default_high")]
+    pub high: f64,
+    #[serde(default)]
+    pub selected: Rate,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// Thresholds on a Channel's recent activity (see [ChannelStats]), as a proxy
+/// for servo thermal stress.  Exceeding either threshold raises a
+/// [Pca9685Event::ThermalWarning].
+pub struct ThermalLimits {
+    /// Time constant (in milliseconds) over which activity is averaged
+    #[serde(default = "ThermalLimits::default_window_ms")]
+    pub window_ms: u64,
+
+    /// Fraction [0.0, 1.0] of time energized
+    pub max_duty: Option<f64>,
+
+    /// Rate of travel, in counts per second
+    pub max_travel_counts_per_s: Option<f64>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// Accumulated and recent activity of a Channel.
+///
+/// `duty` (fraction of time energized) and `travel_counts_per_s` are moving
+/// averages over the channel's [ThermalLimits] `window_ms` (by default, one
+/// minute); `thermal_warning` is true while either exceeds its threshold.
+/// `faulted` is true while the Channel is tripped by its
+/// [CircuitBreakerConfig], and `under_maintenance` while it is locked out (see
+/// [Pca9685::set_maintenance]).  `position_error` is true while its feedback
+/// diverges from its output (see [PositionVerification]).
+pub struct ChannelStats {
+    #[serde(
+        serialize_with = "serialize_channel",
+        deserialize_with = "deserialize_channel"
+    )]
+    pub channel: Channel,
+    pub energized_ms: u64,
+    pub travel_counts: u64,
+    pub duty: f64,
+    pub travel_counts_per_s: f64,
+    pub thermal_warning: bool,
+    #[serde(default)]
+    pub faulted: bool,
+    #[serde(default)]
+    pub under_maintenance: bool,
+    #[serde(default)]
+    pub position_error: bool,
+
+    /// Whether the Channel's ESC (if any) is armed (see [EscConfig])
+    #[serde(default)]
+    pub armed: bool,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A driver error writing a Channel (see [Pca9685::last_driver_error]), at
+/// `timestamp_ms` (milliseconds since the UNIX epoch)
+pub struct DriverErrorRecord {
+    #[serde(
+        serialize_with = "serialize_channel",
+        deserialize_with = "deserialize_channel"
+    )]
+    pub channel: Channel,
+    pub error: String,
+    pub timestamp_ms: u64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// Command statistics of a Channel (see [Pca9685::metrics]): the commands it
+/// has received, by [CommandType], how many of them its limits rejected, and
+/// the time since the last one (None if there hasn't been one).
+pub struct ChannelMetrics {
+    #[serde(
+        serialize_with = "serialize_channel",
+        deserialize_with = "deserialize_channel"
+    )]
+    pub channel: Channel,
+    pub name: Option<String>,
+    pub commands_total: BTreeMap<String, u64>,
+    pub limit_violations_total: u64,
+    pub time_since_last_command_s: Option<f64>,
+    pub current_count: Option<u16>,
+    pub motion_in_progress: bool,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+/// Notable occurrences published to subscribers of [Pca9685::subscribe].
+pub enum Pca9685Event {
+    /// A Channel's activity exceeded its [ThermalLimits]
+    ThermalWarning(ChannelStats),
+
+    /// The watchdog timed out waiting on every client (None) or on the given
+    /// client, and applied failsafe outputs (see [WatchdogConfig])
+    WatchdogTripped(Option<String>),
+
+    /// A Channel tripped its [CircuitBreakerConfig], or failed its
+    /// [PositionVerification]
+    ChannelFaulted(ChannelStats),
+
+    /// A Channel's feedback diverged from its output (see
+    /// [PositionVerification])
+    PositionError(PositionErrorRecord),
+
+    /// An emergency stop was requested (see [Pca9685::emergency_stop])
+    EmergencyStop,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A Channel's feedback (in counts) diverging from its `commanded` output,
+/// at `timestamp_ms` (milliseconds since the UNIX epoch)
+pub struct PositionErrorRecord {
+    #[serde(
+        serialize_with = "serialize_channel",
+        deserialize_with = "deserialize_channel"
+    )]
+    pub channel: Channel,
+    pub commanded: u16,
+    pub measured: f64,
+    pub timestamp_ms: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+/// The runtime state of a [Pca9685] (see [Pca9685::snapshot]): every
+/// Channel's configuration and output (including its owner), the inputs of
+/// each mixer, and the active teleop profile.
+pub struct Snapshot {
+    pub channels: Vec<ChannelConfig>,
+    #[serde(default)]
+    pub mixer_inputs: HashMap<String, HashMap<String, f64>>,
+    #[serde(default)]
+    pub teleop_profile: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+/// Everything needed to move a configured [Pca9685] to fresh hardware (see
+/// [Pca9685::backup]): its running configuration (including its presets),
+/// its sequences and its runtime state.  Bundled as a single tar archive by
+/// [Backup::to_tar].
+pub struct Backup {
+    pub config: Config,
+
+    /// The configuration file `config` was loaded from, as written (e.g.,
+    /// with its `include`s and references to secrets), if known; restoring
+    /// it rather than `config` keeps those intact
+    pub source: Option<String>,
+
+    pub sequences: Vec<SequenceConfig>,
+    pub snapshot: Snapshot,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// The state of a Channel, as reported by [Pca9685::debug_report]: its
+/// configuration and output (including its owner), its statistics, whether
+/// it is moving (see [Pca9685::move_to]), and its most recent errors.
+pub struct ChannelReport {
+    pub config: ChannelConfig,
+    pub stats: ChannelStats,
+    pub metrics: ChannelMetrics,
+    pub moving: bool,
+    pub last_driver_error: Option<DriverErrorRecord>,
+    pub feedback_error: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// Everything worth knowing when debugging a [Pca9685] (see
+/// [Pca9685::debug_report]): the device and the state of its bus, what it is
+/// doing, and the state of each Channel.  Displays as a human-readable
+/// report.
+pub struct DebugReport {
+    pub device: String,
+    pub address: u8,
+    pub prescale: u8,
+    pub actual_output_frequency_hz: f64,
+    pub device_error: Option<String>,
+    pub reinit_count: u32,
+    pub last_driver_error: Option<DriverErrorRecord>,
+    pub paused: Option<PauseMode>,
+    pub playback: Option<PlaybackStatus>,
+    pub recording: Option<RecordingStatus>,
+    pub channels: Vec<ChannelReport>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+/// How commands are handled while the [Pca9685] is paused (see
+/// [Pca9685::pause]); either way, nothing is written to the device.
+pub enum PauseMode {
+    /// Commands are accepted, and the latest output of each Channel is
+    /// written on [Pca9685::resume]
+    #[default]
+    Queue,
+
+    /// Commands are rejected with [Pca9685Error::PausedError]
+    Reject,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+/// What every output does while the outputs are disabled by the OE pin being
+/// pulled high (MODE2's OUTNE bits).  The PCA9685 can't hold the last output:
+/// a servo whose signal goes low (or floats) simply stops being driven.
+pub enum DisabledOutput {
+    /// Outputs are driven low (the power-up default)
+    #[default]
+    Low,
+
+    /// Outputs are driven high, if totem pole; if open drain, they are high
+    /// impedance
+    High,
+
+    /// Outputs are high impedance (e.g., to let another controller drive
+    /// the servos)
+    HighImpedance,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// Drives a Channel as an ESC (electronic speed controller) of a brushless
+/// motor, its lower limit being minimum throttle.  Anything above minimum
+/// throttle is refused with [Pca9685Error::ChannelDisarmedError] until the
+/// ESC is armed (see [Pca9685::arm]) by holding minimum throttle for
+/// `arming_ms`.  Setting the Channel full off (no pulses) disarms it.
+pub struct EscConfig {
+    #[serde(default = "EscConfig::default_arming_ms")]
+    pub arming_ms: u64,
+}
+
+/// The longest `arming_ms` of an [EscConfig]
+pub const MAX_ESC_ARMING_MS: u64 = 30_000;
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// Reduces holding current (and heat) of a stationary servo: once a Channel's
+/// output has not changed for `after_ms`, its output is gated off for part of
+/// every `period_ms`, remaining energized for a fraction `duty` (0.0, 1.0] of
+/// each period.  The next command re-engages the Channel fully.
+pub struct ReducedHold {
+    pub after_ms: u64,
+    pub period_ms: u64,
+    pub duty: f64,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, EnumString, Serialize, Deserialize)]
+/// The kinds of command accepted by [Pca9685::execute] and [Pca9685::apply].
+pub enum CommandType {
+    FullOn,
+    PulseCount,
+    PulseWidth,
+    Percent,
+    AdjustCount,
+    AdjustPct,
+    FullOff,
+    Relax,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+/// A single command for a Channel.
+///
+/// `value` is required for every `command_type` except `FullOn`/`FullOff`
+/// (which must not supply one), and `basis` may only accompany `Percent`.
+pub struct ChannelCommand {
+    #[serde(
+        serialize_with = "serialize_channel",
+        deserialize_with = "deserialize_channel"
+    )]
+    pub channel: Channel,
+    pub command_type: CommandType,
+    pub value: Option<f64>,
+    #[serde(default)]
+    pub basis: Option<PercentBasis>,
+
+    /// Identifies the client issuing the command, for attribution in the
+    /// channel's `owner`, history, and logs
+    #[serde(skip)]
+    pub client_id: Option<String>,
+}
+
+/// The output a [ChannelCommand] resolves to, once validated
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum ChannelTarget {
+    FullOn,
+    FullOff,
+    Count(u16),
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// The commanded output (`count`, None when full off) of a Channel at a point
+/// in time (milliseconds since the UNIX epoch), and the client that
+/// commanded it, if known.
+pub struct ChannelHistoryEntry {
+    pub timestamp_ms: u64,
+    pub count: Option<u16>,
+    pub client_id: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+/// A [ChannelHistoryEntry] along with the Channel (and its `name`, if any) it
+/// belongs to; see [Pca9685::history_records].
+pub struct ChannelHistoryRecord {
+    #[serde(
+        serialize_with = "serialize_channel",
+        deserialize_with = "deserialize_channel"
+    )]
+    pub channel: Channel,
+    pub name: Option<String>,
+    pub timestamp_ms: u64,
+    pub count: Option<u16>,
+    pub client_id: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// The output frequency actually achievable for a `requested_hz`, given that
+/// the prescale register only holds whole numbers. `error_pct` is the
+/// deviation of `actual_hz` from `requested_hz`, in percent.
+pub struct FrequencyStep {
+    pub requested_hz: u16,
+    pub prescale: u8,
+    pub actual_hz: f64,
+    pub error_pct: f64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// A row of a Channel's conversion table (see [Pca9685::conversion_table]):
+/// an `input` (in the table's unit), the `count` it yields, and the pulse
+/// width `pw_ms` that count actually produces.
+pub struct ConversionRow {
+    pub input: f64,
+    pub count: u16,
+    pub pw_ms: f64,
+}
+
+/// The most rows of a [Pca9685::conversion_table], i.e. one per count
+pub const CONVERSION_TABLE_MAX_ROWS: usize = PCA_PWM_RESOLUTION as usize + 1;
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// A step between two counts whose response is recorded (see
+/// [Pca9685::step_test]): the Channel is held at `from_count` for
+/// `settle_ms`, then stepped to `to_count`, sampling every
+/// `sample_interval_us` throughout, until `duration_ms` after the step.
+pub struct StepTest {
+    pub from_count: u16,
+    pub to_count: u16,
+    #[serde(default = "StepTest::default_settle_ms")]
+    pub settle_ms: u64,
+    #[serde(default = "StepTest::default_duration_ms")]
+    pub duration_ms: u64,
+    #[serde(default = "StepTest::default_sample_interval_us")]
+    pub sample_interval_us: u64,
+}
+
+/// The longest `settle_ms` + `duration_ms` of a [StepTest]
+pub const MAX_STEP_TEST_MS: u64 = 10_000;
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+/// A sample of a [StepTest]: `t_ms` since the step (negative while
+/// settling), the commanded count, and the raw feedback (if the Channel has
+/// a [FeedbackConfig]).
+pub struct StepSample {
+    pub t_ms: f64,
+    pub count: u16,
+    pub feedback: Option<f64>,
+}
+
+/// The number of [ChannelHistoryEntry] retained per Channel
+pub const CHANNEL_HISTORY_CAPACITY: usize = 1024;
+
 #[derive(PartialEq, Debug, Clone, Copy)]
 struct PcaClockConfig {
     max_pw_ms: f64,
@@ -97,15 +1686,106 @@ struct ChannelProxy {
     name: String,
     config: ChannelConfig,
     clock_config: PcaClockConfig,
+    history: VecDeque<ChannelHistoryEntry>,
+    last_write_at: Option<Instant>,
+    gated_off: bool,
+    stats: ChannelStats,
+    last_tick_at: Option<Instant>,
+    pending_travel_counts: u64,
+    filters: Vec<Arc<dyn ChannelFilter>>,
+    failsafe_restore: Option<(Instant, ChannelTarget)>,
+    circuit_breaker: Option<CircuitBreakerConfig>,
+    strict: bool,
+    driver_errors: VecDeque<Instant>,
+    pending_events: Vec<Pca9685Event>,
+    paused: Option<PauseMode>,
+    resume_pending: bool,
+    commands_total: BTreeMap<String, u64>,
+    limit_violations_total: u64,
+    last_command_at: Option<Instant>,
+    last_driver_error: Option<DriverErrorRecord>,
+    revision: u64,
+    revised_config: Option<ChannelConfig>,
+    jitter: Option<jitter::Jitter>,
+    pid: Option<pid::Pid>,
+    pid_sample: Option<PidSample>,
+    shaper: Option<shaping::InputShaper>,
+    low_pass: Option<low_pass::LowPass>,
+    feedback: Option<feedback::Feedback>,
+    feedback_error: Option<String>,
+
+    /// When the channel's ESC (if any) is (or was) armed, minimum throttle
+    /// having been held since [ChannelProxy::arm]
+    armed_at: Option<Instant>,
+
+    /// The count written in place of the commanded output, by jitter, PID,
+    /// input shaping or a low-pass filter
+    adjusted_count: Option<u16>,
+}
+
+/// Transforms the count requested of a Channel, before it is validated
+/// against the Channel's limits and written, e.g. to implement custom
+/// clamping, smoothing, or mixing.  See [filters] for built-in filters, and
+/// [Pca9685::add_filter] to register one.
+///
+/// `state` is the Channel's configuration (including `current_count`) prior
+/// to the write; filters are applied in the order they were registered.
+pub trait ChannelFilter: Send + Sync {
+    fn transform(&self, channel: Channel, requested_count: u16, state: &ChannelConfig) -> u16;
+}
+
+/// An error of the I2C bus the PCA9685 is on, as reported by whichever
+/// [embedded_hal] implementation drives it (e.g., a Linux I2C device's)
+pub type I2cError = Box<dyn std::fmt::Debug + Send + Sync>;
+
+/// An error of the underlying [pwm_pca9685] driver
+pub type DriverError = pwm_pca9685::Error<I2cError>;
+
+/// An I2C bus on which a [Pca9685] can be created (see [Pca9685::with_i2c]),
+/// implemented for every blocking [embedded_hal] I2C implementation, e.g. a
+/// microcontroller's, or a mock in host-side tests.
+///
+/// The bound is embedded-hal 0.2's `blocking::i2c::{Write, WriteRead}`, not
+/// embedded-hal 1.0's `i2c::I2c`, because [pwm_pca9685] 0.3 requires it: a
+/// HAL implementing only 1.0 must be adapted to 0.2 to be driven.
+pub trait I2cBus: Send + 'static {
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), I2cError>;
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), I2cError>;
 }
 
-trait Pca9685Proxy {
+/// The I2C bus a [Pca9685] is on, shared by the [pwm_pca9685] driver (see
+/// [Pca9685::with_raw]) and the writes made to other addresses on the bus
+/// (e.g., the general call)
+pub struct SharedI2cBus<I2C>(Arc<Mutex<I2C>>);
+
+/// The I2C bus of a [Pca9685] created from [Config::device] (see
+/// [Pca9685::new])
+#[cfg(feature = "linux")]
+pub type DefaultI2c = linux_embedded_hal::I2cdev;
+
+/// The I2C bus of a [Pca9685] not created on one, which without the `linux`
+/// feature is only ever null (see [Pca9685::null])
+#[cfg(not(feature = "linux"))]
+pub type DefaultI2c = NoI2c;
+
+/// An I2C bus that cannot exist, on which the null [Pca9685]s are (not)
+/// driven
+pub enum NoI2c {}
+
+trait Pca9685Proxy: Send {
     fn max_pw_ms(&self) -> f64;
 
     fn single_count_duration_ms(&self) -> f64;
 
     fn output_frequency_hz(&self) -> u16;
 
+    fn actual_output_frequency_hz(&self) -> f64;
+
+    /// Returns the frequency (in Hz) of the oscillator clocking the device
+    /// (see [Config::oscillator_hz])
+    fn oscillator_hz(&self) -> f64;
+
     fn device(&self) -> String;
 
     fn address(&self) -> u8;
@@ -114,39 +1794,128 @@ trait Pca9685Proxy {
 
     fn output_type(&self) -> OutputDriver;
 
-    fn set_channel_off_count(
-        &mut self,
-        channel: Channel,
-        off: u16,
-    ) -> Result<(), pwm_pca9685::Error<LinuxI2CError>>;
+    fn disabled_output(&self) -> DisabledOutput;
 
-    fn set_channel_full_on(
-        &mut self,
-        channel: Channel,
-    ) -> Result<(), pwm_pca9685::Error<LinuxI2CError>>;
+    /// Programs what every output does while disabled by the OE pin (see
+    /// [Config::disabled_output]), kept across [Pca9685Proxy::reset]
+    fn set_disabled_output(&mut self, disabled_output: DisabledOutput) -> Result<(), DriverError>;
 
-    fn set_channel_full_off(
-        &mut self,
-        channel: Channel,
-    ) -> Result<(), pwm_pca9685::Error<LinuxI2CError>>;
+    /// Returns the count at which `channel`'s output rises each cycle (see
+    /// [Config::stagger_on_counts])
+    fn on_count(&self, channel: Channel) -> u16;
+
+    /// Returns the all-call address to which the device responds, if any
+    /// (see [Config::all_call_address])
+    fn all_call_address(&self) -> Option<u8>;
+
+    /// Directs the writes that follow to the all-call address (rather than
+    /// the device's own), until unset; see [Pca9685::broadcast]
+    fn set_broadcasting(&mut self, broadcasting: bool);
+
+    fn set_channel_off_count(&mut self, channel: Channel, off: u16) -> Result<(), DriverError>;
+
+    fn set_channel_full_on(&mut self, channel: Channel) -> Result<(), DriverError>;
+
+    fn set_channel_full_off(&mut self, channel: Channel) -> Result<(), DriverError>;
+
+    /// Returns the proxy as [std::any::Any], from which [Pca9685::with_raw]
+    /// recovers the driver on its bus, if any
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any;
+
+    /// Returns (and clears) the writes traced since last taken, if tracing
+    fn take_trace(&mut self) -> Vec<TraceWrite>;
+
+    /// Defers writes until [Pca9685Proxy::end_burst], so that they reach
+    /// every Channel at once
+    fn begin_burst(&mut self);
+
+    /// Makes the writes deferred since [Pca9685Proxy::begin_burst]: in a
+    /// single I2C transaction if every Channel's registers are then known,
+    /// otherwise one by one
+    fn end_burst(&mut self) -> Result<(), DriverError>;
+
+    /// Stops the oscillator (and so every output), keeping the Channels'
+    /// registers so that [Pca9685Proxy::wake] restarts them
+    fn sleep(&mut self) -> Result<(), DriverError>;
+
+    /// Restarts the oscillator, and the outputs stopped by
+    /// [Pca9685Proxy::sleep]
+    fn wake(&mut self) -> Result<(), DriverError>;
+
+    fn asleep(&self) -> bool;
+
+    /// Resets every PCA9685 on the bus to its power-up defaults (the I2C
+    /// general call SWRST), then reprograms the prescale, output driver and
+    /// disabled output.  Every Channel's registers are then full off.
+    fn reset(&mut self) -> Result<(), DriverError>;
 }
 
 /// Provides access to a PCA9685 controller, with the ability to customize the
 /// range of each Channel, and set each Channel's value using raw counts,
-/// pulse width in milliseconds, or percent of max pulse width.
-pub struct Pca9685 {
-    inner: Mutex<Box<dyn Pca9685Proxy>>,
-    channels: Mutex<HashMap<u8, ChannelProxy>>,
+/// pulse width in milliseconds, or percent of max pulse width.  `I2C` is the
+/// bus it is created on (see [Pca9685::with_i2c]).
+pub struct Pca9685<I2C = DefaultI2c> {
+    inner: Arc<Mutex<Box<dyn Pca9685Proxy>>>,
+    bus: PhantomData<fn() -> I2C>,
+    channels: Arc<Mutex<HashMap<u8, ChannelProxy>>>,
+    groups: HashMap<String, Vec<Channel>>,
+    mixers: Mutex<HashMap<String, mixer::Mixer>>,
+    teleop: Mutex<teleop::Teleop>,
+    watchdog: Arc<Mutex<Option<watchdog::Watchdog>>>,
+    idle: Arc<Mutex<Option<idle::Idle>>>,
+    allow_jitter: bool,
+    subscribers: Arc<Mutex<Vec<Sender<Pca9685Event>>>>,
+    device_error: Arc<Mutex<Option<String>>>,
+    paused: Mutex<Option<PauseMode>>,
+    reinit_count: Arc<AtomicU32>,
+    sequences: Mutex<HashMap<String, SequenceConfig>>,
+    playback: Arc<Mutex<Option<sequence::Playback>>>,
+    presets: HashMap<String, PresetConfig>,
+    scenes: Mutex<scene::SceneStack>,
+    recorder: Mutex<Option<recorder::Recorder>>,
+    gaits: HashMap<String, GaitConfig>,
+    linear_axes: Arc<Mutex<HashMap<String, linear_axis::LinearAxis>>>,
+    motions: Arc<Mutex<motion::Motions>>,
+    rules: Arc<Mutex<rules::Rules>>,
+    virtual_channels: Mutex<derived::VirtualChannels>,
+    #[cfg(feature = "gimbal")]
+    gimbal: Arc<Mutex<Option<gimbal::Gimbal>>>,
+}
+
+/// Drives several PCA9685s from one [Config] (see [Config::devices]): its
+/// first device, then each of the [DeviceConfig]s, in order.  Channels are
+/// numbered consecutively across the devices, so that Channel 3 of the second
+/// device is Channel 19.
+pub struct Pca9685Manager {
+    devices: Vec<Pca9685>,
 }
 
 /// Represents the possible errors that may occur when commanding the [Pca9685].
 pub enum Pca9685Error {
     NoSuchChannelError(u8),
+    NoSuchChannelNameError(String),
+    NoSuchGroupError(String),
+    NoSuchTagError(String),
+    NoSuchMixerError(String),
+    NoSuchProfileError(String),
+    NoSuchSequenceError(String),
+    NoSuchPresetError(String),
+    NoSuchGaitError(String),
+    NoSuchLinearAxisError(String),
     PulseWidthRangeError(f64, f64),
     CustomLimitsError(u16, ChannelLimits),
     InvalidConfiguration(String),
+    InvalidCommand(String),
     PercentOfRangeError(f64),
-    Pca9685DriverError(pwm_pca9685::Error<LinuxI2CError>),
+    ChannelInactiveError(u8),
+    ChannelFaultedError(u8),
+    ChannelMaintenanceError(u8),
+    ChannelUnconfiguredError(u8),
+    ChannelDisarmedError(u8),
+    PausedError,
+    DeviceInitError(String),
+    StorageError(String),
+    Pca9685DriverError(DriverError),
 }
 
 /// Customized [Result], where the error type is [Pca9685Error]
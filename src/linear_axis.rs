@@ -0,0 +1,304 @@
+use crate::{
+    ChannelCommand, CommandType, GpioBackend, GpioInput, LinearAxisConfig, LinearAxisStatus,
+    Pca9685Error, Pca9685Result,
+};
+use pwm_pca9685::Channel;
+use std::time::Instant;
+
+/// How far (as a multiple of its travel) an axis may run while homing before
+/// its switch is presumed missing
+const HOMING_OVERTRAVEL: f64 = 1.2;
+
+/// How close to its target an axis must be to have arrived
+const POSITION_TOLERANCE_MM: f64 = 0.01;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Motion {
+    Stopped,
+    Homing { traveled_mm: f64 },
+    Moving { target_mm: f64 },
+}
+
+/// Drives a [LinearAxisConfig]'s Channel at the speed needed to home the
+/// axis or reach a target, estimating its position from the speed commanded
+/// over time.
+pub(crate) struct LinearAxis {
+    config: LinearAxisConfig,
+    position_mm: Option<f64>,
+    motion: Motion,
+    speed_mm_s: f64,
+
+    /// The (signed) velocity last commanded
+    velocity_mm_s: f64,
+    polled_at: Option<Instant>,
+
+    /// The home switch, opened (through `gpio`) when first read
+    switch: Option<Box<dyn GpioInput>>,
+    gpio: Option<GpioBackend>,
+
+    /// The last error reading the home switch, logged once until it recovers
+    switch_error: Option<String>,
+}
+
+impl LinearAxis {
+    pub fn new(config: &LinearAxisConfig, gpio: Option<GpioBackend>) -> Self {
+        LinearAxis {
+            config: config.clone(),
+            position_mm: None,
+            motion: Motion::Stopped,
+            speed_mm_s: config.speed_mm_s.unwrap_or(config.max_speed_mm_s),
+            velocity_mm_s: 0.0,
+            polled_at: None,
+            switch: None,
+            gpio,
+            switch_error: None,
+        }
+    }
+
+    /// The client that the axis' motions are attributed to, e.g.
+    /// `linear_axis/slider`
+    pub fn client_id(&self) -> String {
+        format!("linear_axis/{}", self.config.name)
+    }
+
+    pub fn channel(&self) -> Channel {
+        Channel::try_from(self.config.channel).unwrap()
+    }
+
+    pub fn status(&self) -> LinearAxisStatus {
+        LinearAxisStatus {
+            name: self.config.name.clone(),
+            homing: matches!(self.motion, Motion::Homing { .. }),
+            position_mm: self.position_mm,
+            target_mm: match self.motion {
+                Motion::Moving { target_mm } => Some(target_mm),
+                _ => None,
+            },
+            speed_mm_s: self.speed_mm_s,
+        }
+    }
+
+    /// Starts homing at `speed_mm_s` (by default, the configured speed),
+    /// forgetting the axis' position until its switch closes
+    pub fn home(&mut self, speed_mm_s: Option<f64>) -> Pca9685Result<()> {
+        self.set_speed(speed_mm_s)?;
+        self.position_mm = None;
+        self.motion = Motion::Homing { traveled_mm: 0.0 };
+
+        Ok(())
+    }
+
+    /// Starts moving to `target_mm` at `speed_mm_s` (by default, the
+    /// configured speed)
+    pub fn move_to(&mut self, target_mm: f64, speed_mm_s: Option<f64>) -> Pca9685Result<()> {
+        if self.position_mm.is_none() {
+            return Err(Pca9685Error::InvalidCommand(format!(
+                "Linear axis {} must be homed first.",
+                self.config.name
+            )));
+        }
+        if !(self.config.min_mm..=self.config.max_mm).contains(&target_mm) {
+            return Err(Pca9685Error::InvalidCommand(format!(
+                "Linear axis {}: position {}mm is outside [{}, {}]mm.",
+                self.config.name, target_mm, self.config.min_mm, self.config.max_mm
+            )));
+        }
+        self.set_speed(speed_mm_s)?;
+        self.motion = Motion::Moving { target_mm };
+
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        self.motion = Motion::Stopped;
+    }
+
+    fn set_speed(&mut self, speed_mm_s: Option<f64>) -> Pca9685Result<()> {
+        if let Some(speed_mm_s) = speed_mm_s {
+            self.config.validate_speed(speed_mm_s)?;
+            self.speed_mm_s = speed_mm_s;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the command (if any) needed as of `now`, having advanced the
+    /// position by the velocity commanded since last polled
+    pub fn poll(&mut self, now: Instant) -> Option<ChannelCommand> {
+        let dt = self
+            .polled_at
+            .map_or(0.0, |at| now.saturating_duration_since(at).as_secs_f64());
+        self.polled_at = Some(now);
+
+        let homing = matches!(self.motion, Motion::Homing { .. });
+        let switch = match (homing || self.velocity_mm_s < 0.0).then(|| self.read_switch()) {
+            Some(Ok(closed)) => {
+                self.switch_error = None;
+                closed
+            }
+            Some(Err(error)) => {
+                let error = error.to_string();
+                if self.switch_error.as_ref() != Some(&error) {
+                    log::warn!(target: "pca9685", "Linear axis {}: {}", self.config.name, error);
+                    self.switch_error = Some(error);
+                }
+                // Without its switch, homing can't be trusted to stop
+                if homing {
+                    self.motion = Motion::Stopped;
+                }
+                false
+            }
+            None => false,
+        };
+
+        self.step(dt, switch)
+    }
+
+    fn read_switch(&mut self) -> Pca9685Result<bool> {
+        let switch = match &mut self.switch {
+            Some(switch) => switch,
+            None => self
+                .switch
+                .insert(self.config.home.pin.open_input(self.gpio)?),
+        };
+
+        Ok(switch.is_high()? != self.config.home.active_low)
+    }
+
+    /// Advances the position by the velocity commanded over the last `dt`
+    /// seconds and, given whether the home `switch` is closed, returns the
+    /// command to change velocity (if it needs to change)
+    fn step(&mut self, dt: f64, switch: bool) -> Option<ChannelCommand> {
+        let traveled_mm = self.velocity_mm_s * dt;
+        if let Some(position_mm) = self.position_mm.as_mut() {
+            *position_mm += traveled_mm;
+        }
+
+        // Reaching home re-zeroes the position, whether homing or not
+        if switch && self.velocity_mm_s <= 0.0 {
+            if self.motion != Motion::Stopped {
+                self.position_mm = Some(self.config.min_mm);
+            }
+            let arrived = match self.motion {
+                Motion::Stopped => false,
+                Motion::Homing { .. } => true,
+                Motion::Moving { target_mm } => target_mm <= self.config.min_mm,
+            };
+            if arrived {
+                self.motion = Motion::Stopped;
+            }
+        }
+
+        let velocity_mm_s = match self.motion {
+            Motion::Stopped => 0.0,
+            Motion::Homing {
+                traveled_mm: homed_mm,
+            } => {
+                let homed_mm = homed_mm + traveled_mm.abs();
+                if homed_mm > (self.config.max_mm - self.config.min_mm) * HOMING_OVERTRAVEL {
+                    log::warn!(
+                        target: "pca9685",
+                        "Linear axis {}: home switch not found",
+                        self.config.name
+                    );
+                    self.motion = Motion::Stopped;
+                    0.0
+                } else {
+                    self.motion = Motion::Homing {
+                        traveled_mm: homed_mm,
+                    };
+                    -self.speed_mm_s
+                }
+            }
+            Motion::Moving { target_mm } => {
+                let remaining_mm = target_mm - self.position_mm.unwrap_or(target_mm);
+                let passed = remaining_mm * self.velocity_mm_s < 0.0;
+                if passed || remaining_mm.abs() < POSITION_TOLERANCE_MM {
+                    self.motion = Motion::Stopped;
+                    0.0
+                } else {
+                    self.speed_mm_s.copysign(remaining_mm)
+                }
+            }
+        };
+
+        if velocity_mm_s == self.velocity_mm_s {
+            return None;
+        }
+        self.velocity_mm_s = velocity_mm_s;
+
+        let direction = if self.config.reversed { -1.0 } else { 1.0 };
+        Some(ChannelCommand {
+            channel: Channel::try_from(self.config.channel).unwrap(),
+            command_type: CommandType::Percent,
+            value: Some(0.5 + 0.5 * direction * velocity_mm_s / self.config.max_speed_mm_s),
+            basis: None,
+            client_id: Some(self.client_id()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LinearAxis;
+    use crate::{GpioPin, HomeSwitch, LinearAxisConfig, Pca9685Error};
+
+    #[test]
+    fn step() {
+        let config = LinearAxisConfig {
+            name: "slider".to_string(),
+            channel: 3,
+            max_speed_mm_s: 100.0,
+            min_mm: 0.0,
+            max_mm: 500.0,
+            speed_mm_s: Some(50.0),
+            home: HomeSwitch {
+                pin: GpioPin::Path("/dev/null".to_string()),
+                active_low: false,
+            },
+            reversed: true,
+        };
+        let mut axis = LinearAxis::new(&config, None);
+
+        assert!(matches!(
+            axis.move_to(100.0, None),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+
+        // Homes towards min_mm until the switch closes
+        axis.home(None).unwrap();
+        let command = axis.step(0.0, false).unwrap();
+        assert_eq!(command.channel as u8, 3);
+        assert_eq!(command.value, Some(0.75));
+        assert_eq!(command.client_id.as_deref(), Some("linear_axis/slider"));
+        assert!(axis.step(1.0, false).is_none());
+        assert!(axis.status().homing);
+        assert_eq!(axis.step(1.0, true).unwrap().value, Some(0.5));
+        assert_eq!(axis.status().position_mm, Some(0.0));
+
+        // Moves to the target at the given speed, within the travel limits
+        assert!(axis.move_to(600.0, None).is_err());
+        assert!(axis.move_to(100.0, Some(200.0)).is_err());
+        axis.move_to(100.0, Some(100.0)).unwrap();
+        assert_eq!(axis.step(0.0, false).unwrap().value, Some(0.0));
+        assert!(axis.step(0.5, false).is_none());
+        assert_eq!(axis.status().position_mm, Some(50.0));
+        assert_eq!(axis.step(0.5, false).unwrap().value, Some(0.5));
+        assert_eq!(axis.status().position_mm, Some(100.0));
+        assert_eq!(axis.status().target_mm, None);
+
+        // Stops on request
+        axis.move_to(0.0, None).unwrap();
+        assert_eq!(axis.step(0.0, false).unwrap().value, Some(1.0));
+        axis.stop();
+        assert_eq!(axis.step(1.0, false).unwrap().value, Some(0.5));
+        assert_eq!(axis.status().position_mm, Some(0.0));
+
+        // Gives up homing without finding the switch
+        axis.home(None).unwrap();
+        axis.step(0.0, false);
+        assert!(axis.step(12.0, false).is_some());
+        assert!(!axis.status().homing);
+        assert_eq!(axis.status().position_mm, None);
+    }
+}
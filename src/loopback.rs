@@ -0,0 +1,177 @@
+//! Measures a Channel's output looped back to a GPIO input
+use crate::{
+    GpioInput, LoopbackReport, Pca9685Error, Pca9685Result, PulseStats, LOOPBACK_GLITCH_TOLERANCE,
+};
+use pwm_pca9685::Channel;
+use std::time::{Duration, Instant};
+
+/// A change of the input's level: when (in ms since sampling began), and
+/// whether it rose
+type Edge = (f64, bool);
+
+/// Polls `input` as fast as it can be read until `cycles` full cycles (each
+/// from one rising edge to the next) have been seen, or `timeout` elapses.
+/// Returns the edges seen and the mean interval (in us) between samples.
+fn capture(
+    input: &mut dyn GpioInput,
+    cycles: usize,
+    timeout: Duration,
+) -> Pca9685Result<(Vec<Edge>, f64)> {
+    let started = Instant::now();
+    let mut edges = Vec::new();
+    let mut rising_edges = 0;
+    let mut samples: u64 = 0;
+    let mut level = input.is_high()?;
+
+    while rising_edges <= cycles && started.elapsed() < timeout {
+        let high = input.is_high()?;
+        samples += 1;
+        if high != level {
+            level = high;
+            edges.push((started.elapsed().as_secs_f64() * 1000.0, high));
+            rising_edges += high as usize;
+        }
+    }
+
+    let sample_interval_us = started.elapsed().as_secs_f64() * 1e6 / samples.max(1) as f64;
+    Ok((edges, sample_interval_us))
+}
+
+fn stats(values: &[f64]) -> PulseStats {
+    let mean_ms = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values
+        .iter()
+        .map(|value| (value - mean_ms).powi(2))
+        .sum::<f64>()
+        / values.len() as f64;
+
+    PulseStats {
+        min_ms: values.iter().cloned().fold(f64::INFINITY, f64::min),
+        mean_ms,
+        max_ms: values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        std_dev_ms: variance.sqrt(),
+    }
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable_by(f64::total_cmp);
+
+    sorted[sorted.len() / 2]
+}
+
+/// Returns the pulse width and period of each full cycle among `edges`
+fn cycles(edges: &[Edge]) -> Vec<(f64, f64)> {
+    let rising: Vec<usize> = edges
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, high))| *high)
+        .map(|(index, _)| index)
+        .collect();
+
+    rising
+        .windows(2)
+        .filter_map(|pair| {
+            let (rose_ms, _) = edges[pair[0]];
+            let (next_rose_ms, _) = edges[pair[1]];
+            let (fell_ms, _) = edges.get(pair[0] + 1).filter(|_| pair[0] + 1 < pair[1])?;
+
+            Some((fell_ms - rose_ms, next_rose_ms - rose_ms))
+        })
+        .collect()
+}
+
+/// Summarizes the full cycles among `edges` against the expected pulse
+/// width and period
+fn summarize(
+    channel: Channel,
+    edges: &[Edge],
+    expected_pulse_width_ms: f64,
+    expected_period_ms: f64,
+    sample_interval_us: f64,
+) -> Pca9685Result<LoopbackReport> {
+    let cycles = cycles(edges);
+    if cycles.is_empty() {
+        return Err(Pca9685Error::InvalidCommand(format!(
+            "No full cycles of Channel {} were seen on its loopback input.",
+            channel as u8
+        )));
+    }
+
+    let pulse_widths: Vec<f64> = cycles.iter().map(|(width, _)| *width).collect();
+    let periods: Vec<f64> = cycles.iter().map(|(_, period)| *period).collect();
+    let (median_width, median_period) = (median(&pulse_widths), median(&periods));
+    let glitches = cycles
+        .iter()
+        .filter(|(width, period)| {
+            (width - median_width).abs() > LOOPBACK_GLITCH_TOLERANCE * median_width
+                || (period - median_period).abs() > LOOPBACK_GLITCH_TOLERANCE * median_period
+        })
+        .count();
+    let period = stats(&periods);
+
+    Ok(LoopbackReport {
+        channel,
+        cycles: cycles.len(),
+        expected_pulse_width_ms,
+        expected_period_ms,
+        pulse_width: stats(&pulse_widths),
+        period,
+        frequency_error_pct: 100.0 * (expected_period_ms / period.mean_ms - 1.0),
+        glitches,
+        sample_interval_us,
+    })
+}
+
+/// Measures `cycles` cycles of `channel`'s output on `input`, which it is
+/// looped back to (see [crate::Pca9685::measure_loopback]), giving up after
+/// twice as long as they should take (and at least a second)
+pub(crate) fn measure(
+    channel: Channel,
+    input: &mut dyn GpioInput,
+    cycles: usize,
+    expected_pulse_width_ms: f64,
+    expected_period_ms: f64,
+) -> Pca9685Result<LoopbackReport> {
+    let timeout =
+        Duration::from_secs_f64((2.0 * (cycles + 1) as f64 * expected_period_ms / 1000.0).max(1.0));
+    let (edges, sample_interval_us) = capture(input, cycles, timeout)?;
+
+    summarize(
+        channel,
+        &edges,
+        expected_pulse_width_ms,
+        expected_period_ms,
+        sample_interval_us,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::summarize;
+    use pwm_pca9685::Channel;
+
+    #[test]
+    fn summary() {
+        // Starting mid-pulse, 1.5ms pulses every 20.2ms, one of them long,
+        // and the last cycle incomplete
+        let mut edges = vec![(0.5, false)];
+        for cycle in 0..5 {
+            let rose_ms = 1.0 + cycle as f64 * 20.2;
+            let width_ms = if cycle == 2 { 2.0 } else { 1.5 };
+            edges.push((rose_ms, true));
+            edges.push((rose_ms + width_ms, false));
+        }
+
+        let report = summarize(Channel::C3, &edges, 1.5, 20.0, 1.0).unwrap();
+        assert_eq!(report.cycles, 4);
+        assert_eq!(report.glitches, 1);
+        assert_eq!(report.pulse_width.min_ms, 1.5);
+        assert_eq!(report.pulse_width.max_ms, 2.0);
+        assert!((report.period.mean_ms - 20.2).abs() < 1e-9);
+        assert!(report.period.std_dev_ms < 1e-9);
+        assert!((report.frequency_error_pct + 0.990).abs() < 0.001);
+
+        assert!(summarize(Channel::C3, &edges[..2], 1.5, 20.0, 1.0).is_err());
+    }
+}
@@ -0,0 +1,113 @@
+use crate::LowPassConfig;
+use std::time::{Duration, Instant};
+
+/// A first-order low-pass filter (see [LowPassConfig]): the output decays
+/// exponentially toward the setpoint, closing 63% of the gap each time
+/// constant.
+pub(crate) struct LowPass {
+    config: LowPassConfig,
+    setpoint: f64,
+
+    /// The filtered output (in counts) and when it was, until it reaches the
+    /// setpoint
+    output: Option<(Instant, f64)>,
+}
+
+impl LowPass {
+    /// `config` must be valid (see [LowPassConfig::validate])
+    pub fn new(config: LowPassConfig) -> Self {
+        LowPass {
+            config,
+            setpoint: 0.0,
+            output: None,
+        }
+    }
+
+    pub fn config(&self) -> LowPassConfig {
+        self.config
+    }
+
+    /// Returns the output as of `now`, filtered from `output` as of `at`
+    fn decay(&self, now: Instant, (at, output): (Instant, f64)) -> f64 {
+        let time_constant = Duration::from_millis(self.config.time_constant_ms);
+        let decay =
+            (-now.saturating_duration_since(at).as_secs_f64() / time_constant.as_secs_f64()).exp();
+
+        self.setpoint + (output - self.setpoint) * decay
+    }
+
+    /// Filters the move from `previous` to `count`, commanded at `now`;
+    /// without a `previous` count to move from, the output isn't filtered
+    pub fn command(&mut self, now: Instant, previous: Option<u16>, count: u16) {
+        let Some(previous) = previous else {
+            return self.clear();
+        };
+
+        let output = self
+            .output
+            .map_or(previous as f64, |output| self.decay(now, output));
+        self.output = Some((now, output));
+        self.setpoint = count as f64;
+    }
+
+    /// Stops filtering, e.g. once the output is full on or full off
+    pub fn clear(&mut self) {
+        self.output = None;
+    }
+
+    /// Returns true unless the output is still short of the setpoint (by
+    /// half a count or more) as of `now`
+    pub fn is_settled(&self, now: Instant) -> bool {
+        self.output
+            .is_none_or(|output| (self.decay(now, output) - self.setpoint).abs() < 0.5)
+    }
+
+    /// Returns the filtered output as of `now`, or None once it has reached
+    /// the setpoint
+    pub fn output(&mut self, now: Instant) -> Option<u16> {
+        let output = self.decay(now, self.output?);
+        if (output - self.setpoint).abs() < 0.5 {
+            self.output = None;
+            return None;
+        }
+        self.output = Some((now, output));
+
+        Some(output.round() as u16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LowPass;
+    use crate::LowPassConfig;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn output() {
+        let now = Instant::now();
+        let at = |ms: u64| now + Duration::from_millis(ms);
+        let mut low_pass = LowPass::new(LowPassConfig {
+            time_constant_ms: 100,
+        });
+
+        // Closes 63% of the gap each time constant
+        low_pass.command(now, Some(1000), 2000);
+        assert_eq!(low_pass.output(at(0)), Some(1000));
+        assert_eq!(low_pass.output(at(100)), Some(1632));
+        assert!(!low_pass.is_settled(at(200)));
+        assert_eq!(low_pass.output(at(200)), Some(1865));
+
+        // A new setpoint is approached from wherever the output is
+        low_pass.command(at(200), Some(2000), 1000);
+        assert_eq!(low_pass.output(at(300)), Some(1318));
+
+        // ...until within half a count
+        assert!(low_pass.is_settled(at(1200)));
+        assert_eq!(low_pass.output(at(1200)), None);
+        assert_eq!(low_pass.output(at(1300)), None);
+
+        // Nothing to filter from
+        low_pass.command(at(1300), None, 2000);
+        assert_eq!(low_pass.output(at(1300)), None);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{ChannelConfig, Config, Pca9685, Pca9685Error, Pca9685Manager, Pca9685Result};
+use pwm_pca9685::Channel;
+use std::collections::HashSet;
+
+/// The number of Channels of each device
+const DEVICE_CHANNELS: u8 = 16;
+
+/// The number of devices whose Channels can all be numbered by a u8
+const MAX_DEVICES: usize = 16;
+
+impl Pca9685Manager {
+    /// Creates a new [Pca9685Manager] utilizing the given [Config], as by
+    /// [Pca9685::new] for each of its devices.
+    #[cfg(feature = "linux")]
+    pub fn new(config: &Config) -> Pca9685Manager {
+        Pca9685Manager::try_build(config, |config| Ok(Pca9685::new(config)))
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a new [Pca9685Manager] utilizing the given [Config], failing
+    /// if any of its devices cannot be initialized (see [Pca9685::try_new]).
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if two devices share a bus and
+    ///   address, or two Channels share a name
+    /// * [Pca9685Error::DeviceInitError] if a device cannot be initialized
+    #[cfg(feature = "linux")]
+    pub fn try_new(config: &Config) -> Pca9685Result<Pca9685Manager> {
+        Pca9685Manager::try_build(config, Pca9685::try_new)
+    }
+
+    /// Creates a [Pca9685Manager] whose devices write nothing (see
+    /// [Pca9685::null]), e.g. for a dry run.
+    pub fn null(config: &Config) -> Pca9685Manager {
+        Pca9685Manager::try_build(config, |config| Ok(Pca9685::null(config)))
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    fn try_build<F>(config: &Config, build: F) -> Pca9685Result<Pca9685Manager>
+    where
+        F: Fn(&Config) -> Pca9685Result<Pca9685>,
+    {
+        let configs = config.device_configs();
+        if configs.len() > MAX_DEVICES {
+            return Err(Pca9685Error::InvalidConfiguration(format!(
+                "Config.devices: at most {} devices are supported",
+                MAX_DEVICES
+            )));
+        }
+
+        let mut addresses = HashSet::new();
+        let mut names = HashSet::new();
+        for config in &configs {
+            if !addresses.insert((config.device.as_str(), config.address)) {
+                return Err(Pca9685Error::InvalidConfiguration(format!(
+                    "Config.devices: {} {:#02x} appears more than once",
+                    config.device, config.address
+                )));
+            }
+            for name in config.channels.iter().filter_map(|ch| ch.name.as_ref()) {
+                if !names.insert(name) {
+                    return Err(Pca9685Error::InvalidConfiguration(format!(
+                        "ChannelConfig.name '{}' appears more than once",
+                        name
+                    )));
+                }
+            }
+        }
+
+        Ok(Pca9685Manager {
+            devices: configs.iter().map(build).collect::<Pca9685Result<_>>()?,
+        })
+    }
+
+    /// Returns the devices, in order
+    pub fn devices(&self) -> &[Pca9685] {
+        &self.devices
+    }
+
+    /// Returns the number of Channels across all devices
+    pub fn channel_count(&self) -> usize {
+        self.devices.len() * DEVICE_CHANNELS as usize
+    }
+
+    /// Returns the device driving the numbered `channel`, along with the
+    /// Channel of that device, e.g. for any operation not routed by the
+    /// [Pca9685Manager] itself.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchChannelError] if no device drives `channel`
+    pub fn route(&self, channel: u8) -> Pca9685Result<(&Pca9685, Channel)> {
+        let device = self
+            .devices
+            .get((channel / DEVICE_CHANNELS) as usize)
+            .ok_or(Pca9685Error::NoSuchChannelError(channel))?;
+
+        Ok((
+            device,
+            Channel::try_from(channel % DEVICE_CHANNELS).unwrap(),
+        ))
+    }
+
+    /// Returns the number of the Channel named `name` (see [ChannelConfig]).
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchChannelNameError] if no Channel is so named
+    pub fn channel_by_name(&self, name: &str) -> Pca9685Result<u8> {
+        self.devices
+            .iter()
+            .enumerate()
+            .find_map(|(index, device)| {
+                let channel = device.channel_by_name(name).ok()?;
+                Some(index as u8 * DEVICE_CHANNELS + channel as u8)
+            })
+            .ok_or_else(|| Pca9685Error::NoSuchChannelNameError(name.to_string()))
+    }
+
+    /// Returns the configuration of the numbered `channel`, as by
+    /// [Pca9685::config].
+    pub fn config(&self, channel: u8) -> Pca9685Result<ChannelConfig> {
+        let (device, channel) = self.route(channel)?;
+        device.config(channel)
+    }
+
+    /// Sets the output of the numbered `channel`, as by [Pca9685::set_pct].
+    pub fn set_pct(&self, channel: u8, pct: f64) -> Pca9685Result<ChannelConfig> {
+        let (device, channel) = self.route(channel)?;
+        device.set_pct(channel, pct)
+    }
+
+    /// Sets the output of the numbered `channel`, as by
+    /// [Pca9685::set_pw_ms].
+    pub fn set_pw_ms(&self, channel: u8, pw_ms: f64) -> Pca9685Result<ChannelConfig> {
+        let (device, channel) = self.route(channel)?;
+        device.set_pw_ms(channel, pw_ms)
+    }
+
+    /// Sets the output of the numbered `channel`, as by
+    /// [Pca9685::set_pwm_count].
+    pub fn set_pwm_count(&self, channel: u8, count: u16) -> Pca9685Result<ChannelConfig> {
+        let (device, channel) = self.route(channel)?;
+        device.set_pwm_count(channel, count)
+    }
+
+    /// Sets the numbered `channel` off, as by [Pca9685::full_off].
+    pub fn full_off(&self, channel: u8) -> Pca9685Result<ChannelConfig> {
+        let (device, channel) = self.route(channel)?;
+        device.full_off(channel)
+    }
+
+    /// Sets the output of the Channel named `name`, as by
+    /// [Pca9685::set_pct].
+    pub fn set_pct_by_name(&self, name: &str, pct: f64) -> Pca9685Result<ChannelConfig> {
+        self.set_pct(self.channel_by_name(name)?, pct)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ChannelConfig, ChannelLimits, Config, DeviceConfig, Pca9685Error, Pca9685Manager};
+    use pwm_pca9685::Channel;
+
+    fn channel(channel: Channel, name: &str) -> ChannelConfig {
+        ChannelConfig {
+            channel,
+            current_count: None,
+            custom_limits: Some(ChannelLimits::from_count_limits(1000, 2000)),
+            owner: None,
+            calibration: None,
+            inverted: false,
+            trim_counts: 0,
+            esc: None,
+            tags: Vec::new(),
+            reduced_hold: None,
+            thermal_limits: None,
+            dual_rate: None,
+            failsafe: None,
+            display_unit: None,
+            display_value: None,
+            name: Some(name.to_string()),
+            feedback: None,
+            pid: None,
+            input_shaping: None,
+            low_pass: None,
+        }
+    }
+
+    #[test]
+    fn route() {
+        let mut config: Config =
+            serde_yaml::from_str("device: /dev/i2c-1\naddress: 0x40\noutput_frequency_hz: 200\n")
+                .unwrap();
+        config.channels = vec![channel(Channel::C0, "pan")];
+        config.devices = vec![DeviceConfig {
+            device: "/dev/i2c-1".to_string(),
+            address: 0x41,
+            channels: vec![channel(Channel::C3, "tilt")],
+        }];
+
+        let manager = Pca9685Manager::null(&config);
+        assert_eq!(manager.devices().len(), 2);
+        assert_eq!(manager.channel_count(), 32);
+        assert_eq!(manager.devices()[1].address(), 0x41);
+        assert_eq!(manager.devices()[1].output_frequency_hz(), 200);
+
+        assert_eq!(manager.channel_by_name("tilt").unwrap(), 19);
+        let tilt = manager.set_pct_by_name("tilt", 1.0).unwrap();
+        assert_eq!(tilt.channel, Channel::C3);
+        assert_eq!(tilt.current_count, Some(2000));
+        assert_eq!(manager.config(19).unwrap().current_count, Some(2000));
+        assert_eq!(manager.config(3).unwrap().current_count, None);
+        assert!(matches!(
+            manager.set_pct(32, 0.5),
+            Err(Pca9685Error::NoSuchChannelError(32))
+        ));
+
+        config.devices[0].address = 0x40;
+        #[cfg(feature = "linux")]
+        assert!(matches!(
+            Pca9685Manager::try_new(&config),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+    }
+}
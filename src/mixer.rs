@@ -0,0 +1,129 @@
+use crate::{ChannelCommand, CommandType, MixerConfig, Pca9685Error, Pca9685Result};
+use pwm_pca9685::Channel;
+use std::collections::HashMap;
+
+/// A [MixerConfig] together with the most recently applied value of each of
+/// its inputs (initially 0.0, i.e. centered)
+pub(crate) struct Mixer {
+    config: MixerConfig,
+    inputs: Vec<f64>,
+    applied: bool,
+}
+
+/// Builds a Mixer from each of `configs`.  Inputs of a mixer that is also in
+/// `previous` retain their value (if the input is still present).
+///
+/// Error conditions:
+/// * [Pca9685Error::InvalidConfiguration] if a config is invalid or two share
+///   a name
+pub(crate) fn build_mixers(
+    configs: &[MixerConfig],
+    previous: &HashMap<String, Mixer>,
+) -> Pca9685Result<HashMap<String, Mixer>> {
+    let mut mixers = HashMap::new();
+
+    for config in configs {
+        let mut mixer = Mixer::new(config)?;
+        if let Some(previous) = previous.get(&config.name) {
+            let previous_inputs = previous.inputs();
+            for (name, value) in config.inputs.iter().zip(mixer.inputs.iter_mut()) {
+                if let Some(previous_value) = previous_inputs.get(name) {
+                    *value = *previous_value;
+                }
+            }
+            mixer.applied = previous.applied;
+        }
+
+        if mixers.insert(config.name.clone(), mixer).is_some() {
+            return Err(Pca9685Error::InvalidConfiguration(format!(
+                "Mixer {} appears more than once",
+                config.name
+            )));
+        }
+    }
+
+    Ok(mixers)
+}
+
+impl Mixer {
+    pub fn new(config: &MixerConfig) -> Pca9685Result<Self> {
+        config.validate()?;
+
+        Ok(Mixer {
+            config: config.clone(),
+            inputs: vec![0.0; config.inputs.len()],
+            applied: false,
+        })
+    }
+
+    pub fn config(&self) -> &MixerConfig {
+        &self.config
+    }
+
+    /// Returns true if the mixer's outputs have been positioned, i.e. by
+    /// [Mixer::commit]
+    pub fn is_applied(&self) -> bool {
+        self.applied
+    }
+
+    /// Returns the current inputs, e.g. to re-position the outputs
+    pub fn current(&self) -> Vec<f64> {
+        self.inputs.clone()
+    }
+
+    pub fn inputs(&self) -> HashMap<String, f64> {
+        self.config
+            .inputs
+            .iter()
+            .cloned()
+            .zip(self.inputs.iter().copied())
+            .collect()
+    }
+
+    /// Returns the inputs that result from applying `updates` to the current
+    /// inputs; inputs absent from `updates` retain their current value.
+    pub fn stage(&self, updates: &HashMap<String, f64>) -> Pca9685Result<Vec<f64>> {
+        let mut inputs = self.inputs.clone();
+
+        for (name, value) in updates {
+            let index = match self.config.inputs.iter().position(|input| input == name) {
+                Some(index) => index,
+                None => {
+                    return Err(Pca9685Error::InvalidCommand(format!(
+                        "Mixer {} has no input {}.",
+                        self.config.name, name
+                    )))
+                }
+            };
+            if !(-1.0..=1.0).contains(value) {
+                return Err(Pca9685Error::InvalidCommand(format!(
+                    "Mixer input {} must be within [-1.0, 1.0] (got {}).",
+                    name, value
+                )));
+            }
+            inputs[index] = *value;
+        }
+
+        Ok(inputs)
+    }
+
+    /// Returns the commands that position each output Channel per `inputs`
+    pub fn commands(&self, inputs: &[f64]) -> Vec<ChannelCommand> {
+        self.config
+            .mix(inputs)
+            .into_iter()
+            .map(|(raw_channel, position)| ChannelCommand {
+                channel: Channel::try_from(raw_channel).unwrap(),
+                command_type: CommandType::Percent,
+                value: Some((position + 1.0) / 2.0),
+                basis: None,
+                client_id: None,
+            })
+            .collect()
+    }
+
+    pub fn commit(&mut self, inputs: Vec<f64>) {
+        self.inputs = inputs;
+        self.applied = true;
+    }
+}
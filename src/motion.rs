@@ -0,0 +1,189 @@
+use crate::{ChannelCommand, CommandType, Easing, PCA_PWM_RESOLUTION};
+use pwm_pca9685::Channel;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// The client that motions are attributed to
+pub(crate) const MOTION_CLIENT_ID: &str = "motion";
+
+/// A Channel's ramp from one count to another over `duration`, progressing
+/// per its `easing`
+struct Ramp {
+    from: u16,
+    to: u16,
+    started: Instant,
+    duration: Duration,
+    easing: Easing,
+
+    /// The count last commanded, if any
+    commanded: Option<u16>,
+}
+
+impl Ramp {
+    /// Returns the count due as of `now`
+    fn count(&self, now: Instant) -> u16 {
+        if self.duration.is_zero() {
+            return self.to;
+        }
+        let progress = self.easing.apply(
+            now.saturating_duration_since(self.started).as_secs_f64() / self.duration.as_secs_f64(),
+        );
+
+        (self.from as f64 + (self.to as f64 - self.from as f64) * progress).round() as u16
+    }
+
+    fn finished(&self, now: Instant) -> bool {
+        now >= self.started + self.duration
+    }
+}
+
+/// The Channels moving (see [crate::Pca9685::move_to]), each ramping towards
+/// its target count
+pub(crate) struct Motions {
+    ramps: BTreeMap<u8, Ramp>,
+}
+
+impl Motions {
+    pub fn new() -> Self {
+        Motions {
+            ramps: BTreeMap::new(),
+        }
+    }
+
+    /// Starts ramping `channel` from the count `from` to `to` over
+    /// `duration` per `easing`, replacing any ramp it was already following.
+    /// Nothing is commanded until `now` (which may be in the future).
+    pub fn start(
+        &mut self,
+        channel: Channel,
+        (from, to): (u16, u16),
+        duration: Duration,
+        easing: Easing,
+        now: Instant,
+    ) {
+        self.ramps.insert(
+            channel as u8,
+            Ramp {
+                from,
+                to,
+                started: now,
+                duration,
+                easing,
+                commanded: None,
+            },
+        );
+    }
+
+    /// Stops ramping `channel`, returning whether it was moving
+    pub fn stop(&mut self, channel: Channel) -> bool {
+        self.ramps.remove(&(channel as u8)).is_some()
+    }
+
+    /// Stops ramping every Channel
+    pub fn stop_all(&mut self) {
+        self.ramps.clear();
+    }
+
+    pub fn moving(&self, channel: Channel) -> bool {
+        self.ramps.contains_key(&(channel as u8))
+    }
+
+    /// Returns the commands (ordered by Channel) needed as of `now` to follow
+    /// each ramp, forgetting those that have arrived
+    pub fn poll(&mut self, now: Instant) -> Vec<ChannelCommand> {
+        let mut commands = Vec::new();
+        for (raw_channel, ramp) in self.ramps.iter_mut() {
+            if now < ramp.started {
+                continue;
+            }
+            let count = ramp.count(now);
+            if ramp.commanded == Some(count) {
+                continue;
+            }
+            ramp.commanded = Some(count);
+
+            let (command_type, value) = match count {
+                PCA_PWM_RESOLUTION => (CommandType::FullOn, None),
+                count => (CommandType::PulseCount, Some(count as f64)),
+            };
+            commands.push(ChannelCommand {
+                channel: Channel::try_from(*raw_channel).unwrap(),
+                command_type,
+                value,
+                basis: None,
+                client_id: Some(MOTION_CLIENT_ID.to_string()),
+            });
+        }
+        self.ramps.retain(|_, ramp| !ramp.finished(now));
+
+        commands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Motions;
+    use crate::{CommandType, Easing};
+    use pwm_pca9685::Channel;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn poll() {
+        let now = Instant::now();
+        let mut motions = Motions::new();
+        let ms = Duration::from_millis;
+        let at = |t_ms: u64| now + ms(t_ms);
+        let counts = |motions: &mut Motions, ms: u64| -> Vec<(u8, Option<f64>)> {
+            motions
+                .poll(at(ms))
+                .iter()
+                .map(|command| (command.channel as u8, command.value))
+                .collect()
+        };
+
+        let linear = Easing::Linear;
+        motions.start(Channel::C1, (1000, 2000), ms(100), linear, now);
+        motions.start(Channel::C0, (4000, 4096), ms(200), linear, now);
+        assert!(motions.moving(Channel::C1));
+
+        assert_eq!(
+            counts(&mut motions, 0),
+            vec![(0, Some(4000.0)), (1, Some(1000.0))]
+        );
+        // Unchanged counts aren't commanded again
+        assert_eq!(counts(&mut motions, 0), vec![]);
+        assert_eq!(
+            counts(&mut motions, 50),
+            vec![(0, Some(4024.0)), (1, Some(1500.0))]
+        );
+
+        // Arriving ends the ramp, on its target
+        assert_eq!(
+            counts(&mut motions, 150),
+            vec![(0, Some(4072.0)), (1, Some(2000.0))]
+        );
+        assert!(!motions.moving(Channel::C1));
+        let commands = motions.poll(at(250));
+        assert_eq!(commands[0].command_type, CommandType::FullOn);
+        assert_eq!(commands[0].client_id.as_deref(), Some("motion"));
+        assert!(!motions.moving(Channel::C0));
+
+        motions.start(Channel::C2, (0, 100), ms(100), linear, now);
+        assert!(motions.stop(Channel::C2));
+        assert!(!motions.stop(Channel::C2));
+        assert!(motions.poll(at(50)).is_empty());
+
+        // Eased ramps start slower
+        motions.start(Channel::C3, (1000, 2000), ms(100), Easing::Cubic, now);
+        assert_eq!(counts(&mut motions, 25), vec![(3, Some(1063.0))]);
+        assert_eq!(counts(&mut motions, 50), vec![(3, Some(1500.0))]);
+        motions.stop(Channel::C3);
+
+        // Ramps may start later, or take no time at all
+        motions.start(Channel::C4, (0, 2000), ms(0), linear, at(100));
+        assert!(motions.poll(at(50)).is_empty());
+        assert!(motions.moving(Channel::C4));
+        assert_eq!(counts(&mut motions, 100), vec![(4, Some(2000.0))]);
+        assert!(!motions.moving(Channel::C4));
+    }
+}
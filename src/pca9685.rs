@@ -1,36 +1,425 @@
+use crate::derived::VirtualChannels;
+use crate::gait;
+#[cfg(feature = "gimbal")]
+use crate::gimbal::Gimbal;
+use crate::idle::Idle;
+use crate::linear_axis::LinearAxis;
+use crate::loopback;
+use crate::mixer;
+use crate::motion::Motions;
 use crate::pca9685_proxy::Pca9685ProxyImpl;
+use crate::preset;
+use crate::recorder::Recorder;
+use crate::rules::Rules;
+use crate::scene::{SceneOutput, SceneStack};
+use crate::sequence;
+use crate::teleop::Teleop;
+use crate::watchdog::{Watchdog, WatchdogTrip};
 use crate::{
-    ChannelConfig, ChannelProxy, Config, Pca9685, Pca9685Error, Pca9685Proxy, Pca9685Result,
-    PcaClockConfig,
+    Backup, ChannelCalibration, ChannelCommand, ChannelConfig, ChannelFilter, ChannelHistoryEntry,
+    ChannelHistoryRecord, ChannelMetrics, ChannelProxy, ChannelReport, ChannelSelector,
+    ChannelStats, CircuitBreakerConfig, CommandType, Config, ConfigDifference, ConfigFinding,
+    ConfigValidation, ConversionRow, DebugReport, DisabledOutput, DisplayUnit, DriverError,
+    DriverErrorRecord, Easing, FindingSeverity, GaitConfig, GamepadState, GimbalConfig, GpioInput,
+    I2cBus, IdleConfig, JitterConfig, Keyframe, LinearAxisStatus, LoopRegion, LoopbackReport,
+    MixerConfig, PauseMode, Pca9685, Pca9685Error, Pca9685Event, Pca9685Proxy, Pca9685Result,
+    PcaClockConfig, PercentBasis, PidSample, PlaybackClock, PlaybackStatus, PresetConfig,
+    PresetOutput, Rate, RecordingStatus, Replay, SceneLayer, SequenceConfig, SharedI2cBus,
+    Snapshot, StartupConfig, StepSample, StepTest, TeleopProfile, TraceWrite, WatchdogConfig,
+    CONVERSION_TABLE_MAX_ROWS, MAX_PRESET_FADE_MS, PCA_PWM_RESOLUTION, PLAYBACK_RATES,
+    SERVO_SAFE_FREQUENCY_HZ,
 };
 use log;
 use pwm_pca9685::{Channel, OutputDriver};
-use std::collections::HashMap;
-use std::sync::Mutex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
-unsafe impl Send for Pca9685 {}
-unsafe impl Sync for Pca9685 {}
+/// How often the worker performs the channels' time-based behavior
+const WORKER_TICK_INTERVAL: Duration = Duration::from_millis(5);
+
+/// [ChannelConfig] fields which reflect a Channel's output, rather than its
+/// configuration
+const CHANNEL_OUTPUT_FIELDS: [&str; 3] = ["current_count", "owner", "display_value"];
+
+fn difference<F: Serialize, R: Serialize>(path: String, file: F, runtime: R) -> ConfigDifference {
+    ConfigDifference {
+        path,
+        file: serde_yaml::to_value(file).unwrap(),
+        runtime: serde_yaml::to_value(runtime).unwrap(),
+    }
+}
+
+/// Compares the configuration fields (see [CHANNEL_OUTPUT_FIELDS]) of `file`
+/// and `runtime`
+fn diff_channel(
+    path: &str,
+    file: &ChannelConfig,
+    runtime: &ChannelConfig,
+) -> Vec<ConfigDifference> {
+    let (serde_yaml::Value::Mapping(file), serde_yaml::Value::Mapping(runtime)) = (
+        serde_yaml::to_value(file).unwrap(),
+        serde_yaml::to_value(runtime).unwrap(),
+    ) else {
+        return Vec::new();
+    };
+
+    file.iter()
+        .filter_map(|(field, file_value)| {
+            let field = field.as_str()?;
+            let runtime_value = runtime.get(field).unwrap_or(&serde_yaml::Value::Null);
+            if CHANNEL_OUTPUT_FIELDS.contains(&field) || file_value == runtime_value {
+                return None;
+            }
+
+            Some(difference(
+                format!("{}.{}", path, field),
+                file_value,
+                runtime_value,
+            ))
+        })
+        .collect()
+}
+
+unsafe impl<I2C> Send for Pca9685<I2C> {}
+unsafe impl<I2C> Sync for Pca9685<I2C> {}
 
 impl Pca9685 {
     /// Creates a new [Pca9685] utilizing the given [Config].
+    #[cfg(feature = "linux")]
     pub fn new(config: &Config) -> Pca9685 {
-        return Pca9685::init(config, Pca9685ProxyImpl::new(config));
+        Pca9685::init(config, Pca9685ProxyImpl::new(config))
+    }
+
+    /// Creates a new [Pca9685] utilizing the given [Config], failing with
+    /// [Pca9685Error::DeviceInitError] if the device cannot be initialized.
+    #[cfg(feature = "linux")]
+    pub fn try_new(config: &Config) -> Pca9685Result<Pca9685> {
+        Ok(Pca9685::init(config, Pca9685ProxyImpl::try_new(config)?))
+    }
+
+    /// Creates a new [Pca9685] utilizing the given [Config].  If the device
+    /// cannot be initialized, the [Pca9685] starts **degraded**: it behaves
+    /// as [Pca9685::null] and reports the failure via
+    /// [Pca9685::device_error], while initialization is retried every
+    /// `retry_interval` in the background until it succeeds.
+    #[cfg(feature = "linux")]
+    pub fn new_or_degraded(config: &Config, retry_interval: Duration) -> Pca9685 {
+        let error = match Pca9685ProxyImpl::try_new(config) {
+            Ok(inner) => return Pca9685::init(config, inner),
+            Err(error) => error,
+        };
+
+        log::error!(target: "pca9685", "{}", error);
+        let pca = Pca9685::init(config, Pca9685ProxyImpl::null(config));
+        *pca.device_error.lock().unwrap() = Some(error.to_string());
+
+        let inner = Arc::downgrade(&pca.inner);
+        let device_error = Arc::downgrade(&pca.device_error);
+        let reinit_count = pca.reinit_count.clone();
+        let config = config.clone();
+        thread::spawn(move || loop {
+            thread::sleep(retry_interval);
+
+            let (Some(inner), Some(device_error)) = (inner.upgrade(), device_error.upgrade())
+            else {
+                return;
+            };
+
+            match Pca9685ProxyImpl::try_new(&config) {
+                Ok(proxy) => {
+                    *inner.lock().unwrap() = proxy;
+                    *device_error.lock().unwrap() = None;
+                    reinit_count.fetch_add(1, Ordering::Relaxed);
+                    log::info!(target: "pca9685", "Device {} initialized.", config.device);
+                    return;
+                }
+                Err(error) => {
+                    log::debug!(target: "pca9685", "{}", error);
+                    *device_error.lock().unwrap() = Some(error.to_string());
+                }
+            }
+        });
+
+        pca
     }
 
     /// Creates a **null** [Pca9685] utilizing the given [Config].  Commands
     /// which *should* affect the PCA9685 output (e.g., [Pca9685::set_pwm_count],
     /// [Pca9685::set_pw_ms], and [Pca9685::set_pct]) actually have no effect.
     pub fn null(config: &Config) -> Pca9685 {
-        return Pca9685::init(config, Pca9685ProxyImpl::null(config));
+        Pca9685::init(config, Pca9685ProxyImpl::null(config))
+    }
+
+    /// Creates a **null** [Pca9685] (see [Pca9685::null]) which captures
+    /// the writes it would make to the PCA9685, e.g. to verify the counts
+    /// produced by commands (see [Pca9685::take_trace]).
+    pub fn tracing(config: &Config) -> Pca9685 {
+        Pca9685::init(config, Pca9685ProxyImpl::tracing(config))
+    }
+
+    /// Validates `config` in full, as [Pca9685::new] would (including each
+    /// ChannelConfig against the configured output frequency), without
+    /// touching any device.
+    pub fn validate_config(config: &Config) -> ConfigValidation {
+        let mut findings = Vec::new();
+        let mut add = |severity: FindingSeverity, path: String, error: Pca9685Error| {
+            findings.push(ConfigFinding {
+                severity,
+                path,
+                message: error.to_string(),
+            })
+        };
+
+        // Accepted, but suspect
+        if let Err(error) = config.validate_clock() {
+            add(FindingSeverity::Error, "prescale".to_string(), error);
+        }
+        if let Err(error) = config.validate_addresses() {
+            add(FindingSeverity::Error, "subaddresses".to_string(), error);
+        }
+        let output_frequency_hz = config.effective_output_frequency_hz();
+        if !SERVO_SAFE_FREQUENCY_HZ.contains(&output_frequency_hz) {
+            add(
+                FindingSeverity::Warning,
+                "output_frequency_hz".to_string(),
+                Pca9685Error::InvalidConfiguration(format!(
+                    "{}Hz is outside the range safe for servos ({:?}Hz)",
+                    output_frequency_hz, SERVO_SAFE_FREQUENCY_HZ
+                )),
+            );
+        }
+
+        let clock_config =
+            PcaClockConfig::from_prescale(config.oscillator_hz(), config.effective_prescale());
+        let mut configured = HashSet::new();
+        let mut names = HashSet::new();
+        for (index, channel_config) in config.channels.iter().enumerate() {
+            let path = format!("channels[{}]", index);
+            let raw_channel = channel_config.channel as u8;
+            if !configured.insert(raw_channel) {
+                add(
+                    FindingSeverity::Error,
+                    path.clone(),
+                    Pca9685Error::InvalidConfiguration(format!(
+                        "Channel {} appears more than once",
+                        raw_channel
+                    )),
+                );
+            }
+            if let Some(name) = &channel_config.name {
+                if !names.insert(name) {
+                    add(
+                        FindingSeverity::Error,
+                        path.clone(),
+                        Pca9685Error::InvalidConfiguration(format!(
+                            "ChannelConfig.name '{}' appears more than once",
+                            name
+                        )),
+                    );
+                }
+            }
+            if let Err(error) =
+                ChannelProxy::new(channel_config.channel, clock_config).configure(channel_config)
+            {
+                add(FindingSeverity::Error, path, error);
+            }
+        }
+
+        let mut addresses = HashSet::from([(config.device.as_str(), config.address)]);
+        for (index, device_config) in config.devices.iter().enumerate() {
+            let path = format!("devices[{}]", index);
+            if !addresses.insert((device_config.device.as_str(), device_config.address)) {
+                add(
+                    FindingSeverity::Error,
+                    path.clone(),
+                    Pca9685Error::InvalidConfiguration(format!(
+                        "{} {:#02x} appears more than once",
+                        device_config.device, device_config.address
+                    )),
+                );
+            }
+
+            let mut configured = HashSet::new();
+            for (channel_index, channel_config) in device_config.channels.iter().enumerate() {
+                let path = format!("{}.channels[{}]", path, channel_index);
+                if !configured.insert(channel_config.channel as u8) {
+                    add(
+                        FindingSeverity::Error,
+                        path.clone(),
+                        Pca9685Error::InvalidConfiguration(format!(
+                            "Channel {} appears more than once",
+                            channel_config.channel as u8
+                        )),
+                    );
+                }
+                if let Some(name) = &channel_config.name {
+                    if !names.insert(name) {
+                        add(
+                            FindingSeverity::Error,
+                            path.clone(),
+                            Pca9685Error::InvalidConfiguration(format!(
+                                "ChannelConfig.name '{}' appears more than once",
+                                name
+                            )),
+                        );
+                    }
+                }
+                if let Err(error) = ChannelProxy::new(channel_config.channel, clock_config)
+                    .configure(channel_config)
+                {
+                    add(FindingSeverity::Error, path, error);
+                }
+            }
+        }
+
+        for (index, group) in config.groups.iter().enumerate() {
+            for raw_channel in &group.channels {
+                if Channel::try_from(*raw_channel).is_err() {
+                    add(
+                        FindingSeverity::Error,
+                        format!("groups[{}]", index),
+                        Pca9685Error::NoSuchChannelError(*raw_channel),
+                    );
+                }
+            }
+        }
+
+        if let Err(error) = mixer::build_mixers(&config.mixers, &HashMap::new()) {
+            add(FindingSeverity::Error, "mixers".to_string(), error);
+        }
+        if let Err(error) = config.teleop.validate(&config.mixers) {
+            add(FindingSeverity::Error, "teleop".to_string(), error);
+        }
+        if let Err(error) = VirtualChannels::new(&config.virtual_channels, &config.derived_channels)
+        {
+            add(
+                FindingSeverity::Error,
+                "derived_channels".to_string(),
+                error,
+            );
+        }
+        if let Some(Err(error)) = config.watchdog.as_ref().map(WatchdogConfig::validate) {
+            add(FindingSeverity::Error, "watchdog".to_string(), error);
+        }
+        if let Some(Err(error)) = config
+            .circuit_breaker
+            .as_ref()
+            .map(CircuitBreakerConfig::validate)
+        {
+            add(FindingSeverity::Error, "circuit_breaker".to_string(), error);
+        }
+        if let Some(Err(error)) = config.idle.as_ref().map(IdleConfig::validate) {
+            add(FindingSeverity::Error, "idle".to_string(), error);
+        }
+        for (index, preset) in config.presets.iter().enumerate() {
+            if let Err(error) = preset.validate() {
+                add(FindingSeverity::Error, format!("presets[{}]", index), error);
+            }
+        }
+        if let Err(error) = config.scenes.validate() {
+            add(FindingSeverity::Error, "scenes".to_string(), error);
+        }
+        for (index, rule) in config.rules.iter().enumerate() {
+            if let Err(error) = rule.validate(&config.presets) {
+                add(FindingSeverity::Error, format!("rules[{}]", index), error);
+            }
+        }
+        for (index, axis) in config.linear_axes.iter().enumerate() {
+            if let Err(error) = axis.validate() {
+                add(
+                    FindingSeverity::Error,
+                    format!("linear_axes[{}]", index),
+                    error,
+                );
+            }
+        }
+        for (index, gait) in config.gaits.iter().enumerate() {
+            if let Err(error) = gait.validate(&config.groups) {
+                add(FindingSeverity::Error, format!("gaits[{}]", index), error);
+            }
+        }
+        if let Some(Err(error)) = config.gimbal.as_ref().map(GimbalConfig::validate) {
+            add(FindingSeverity::Error, "gimbal".to_string(), error);
+        } else if config.gimbal.is_some() && !cfg!(feature = "gimbal") {
+            add(
+                FindingSeverity::Warning,
+                "gimbal".to_string(),
+                Pca9685Error::InvalidConfiguration(
+                    "Config.gimbal is ignored without the gimbal feature".to_string(),
+                ),
+            );
+        }
+
+        ConfigValidation {
+            valid: !findings
+                .iter()
+                .any(|finding| finding.severity == FindingSeverity::Error),
+            findings,
+        }
+    }
+}
+
+impl<I2C: I2cBus> Pca9685<I2C> {
+    /// Creates a new [Pca9685] utilizing the given [Config] on `i2c`, any
+    /// blocking [embedded_hal] 0.2 I2C implementation (see [I2cBus]; e.g., a
+    /// microcontroller's, or a mock in host-side tests), rather than opening
+    /// [Config::device].
+    /// Fails with [Pca9685Error::DeviceInitError] if the device cannot be
+    /// initialized.
+    pub fn with_i2c(config: &Config, i2c: I2C) -> Pca9685Result<Pca9685<I2C>> {
+        Ok(Pca9685::init(
+            config,
+            Pca9685ProxyImpl::with_i2c(config, i2c)?,
+        ))
+    }
+
+    /// Calls `f` with exclusive access to the underlying
+    /// [pwm_pca9685::Pca9685] driver, for register-level operations not
+    /// (yet) wrapped by this crate.  No other command is processed while `f`
+    /// runs.
+    ///
+    /// Channel state is not updated to reflect anything `f` writes, so
+    /// [Pca9685::config] and friends may disagree with the device until the
+    /// affected Channels are next commanded.  Fails with
+    /// [Pca9685Error::DeviceInitError] if there is no device (i.e., the
+    /// [Pca9685] is null or degraded).
+    pub fn with_raw<F, T>(&self, f: F) -> Pca9685Result<T>
+    where
+        F: FnOnce(&mut pwm_pca9685::Pca9685<SharedI2cBus<I2C>>) -> Result<T, DriverError>,
+    {
+        let mut inner = self.inner.lock().unwrap();
+        let device = inner.device();
+
+        let raw = inner
+            .as_any_mut()
+            .downcast_mut::<Pca9685ProxyImpl<I2C>>()
+            .and_then(Pca9685ProxyImpl::raw);
+        match raw {
+            Some(raw) => f(raw).map_err(Pca9685Error::Pca9685DriverError),
+            None => Err(Pca9685Error::DeviceInitError(format!(
+                "Device {} is not available.",
+                device
+            ))),
+        }
     }
+}
 
-    fn init(config: &Config, inner: Box<dyn Pca9685Proxy>) -> Pca9685 {
+impl<I2C> Pca9685<I2C> {
+    fn init(config: &Config, inner: Box<dyn Pca9685Proxy>) -> Pca9685<I2C> {
         let pca_single_pw_duration_ms = inner.single_count_duration_ms();
         let pca_max_pw_ms = inner.max_pw_ms();
 
         log::info!(target: "pca9685", "Device:           {}", config.device);
         log::info!(target: "pca9685", "Address:          {:#02x}", config.address);
-        log::info!(target: "pca9685", "Output frequency: {}Hz", config.output_frequency_hz);
+        log::info!(target: "pca9685", "Oscillator:       {}Hz", inner.oscillator_hz());
+        log::info!(target: "pca9685", "Output frequency: {}Hz", inner.output_frequency_hz());
+        log::info!(target: "pca9685", "Actual frequency: {:0.3}Hz (prescale {})", inner.actual_output_frequency_hz(), inner.prescale());
         log::info!(target: "pca9685", "Max PW:           {:0.4}ms", pca_max_pw_ms);
         log::info!(target: "pca9685", "Each count:       {:0.4}ms", pca_single_pw_duration_ms);
 
@@ -41,21 +430,356 @@ impl Pca9685 {
         };
         for ch in 0..16 {
             let channel = Channel::try_from(ch).unwrap();
-            channels.insert(ch, ChannelProxy::new(channel, clock_config));
+            let mut channel_proxy = ChannelProxy::new(channel, clock_config);
+            channel_proxy.set_circuit_breaker(config.circuit_breaker);
+            channel_proxy.set_strict(config.strict);
+            channels.insert(ch, channel_proxy);
+        }
+
+        let mut groups = HashMap::new();
+        for group in &config.groups {
+            let group_channels = group
+                .channels
+                .iter()
+                .map(|raw_channel| {
+                    Channel::try_from(*raw_channel).unwrap_or_else(|_| {
+                        panic!("{}", Pca9685Error::NoSuchChannelError(*raw_channel))
+                    })
+                })
+                .collect();
+            groups.insert(group.name.clone(), group_channels);
+        }
+
+        let mixers = mixer::build_mixers(&config.mixers, &HashMap::new())
+            .unwrap_or_else(|error| panic!("{}", error));
+        config
+            .teleop
+            .validate(&config.mixers)
+            .unwrap_or_else(|error| panic!("{}", error));
+        if let Some(watchdog) = &config.watchdog {
+            watchdog
+                .validate()
+                .unwrap_or_else(|error| panic!("{}", error));
         }
+        if let Some(circuit_breaker) = &config.circuit_breaker {
+            circuit_breaker
+                .validate()
+                .unwrap_or_else(|error| panic!("{}", error));
+        }
+        if let Some(idle) = &config.idle {
+            idle.validate().unwrap_or_else(|error| panic!("{}", error));
+        }
+        if let Some(gimbal) = &config.gimbal {
+            gimbal
+                .validate()
+                .unwrap_or_else(|error| panic!("{}", error));
+        }
+        let mut presets = HashMap::new();
+        for preset in &config.presets {
+            preset
+                .validate()
+                .unwrap_or_else(|error| panic!("{}", error));
+            presets.insert(preset.name.clone(), preset.clone());
+        }
+        config
+            .scenes
+            .validate()
+            .unwrap_or_else(|error| panic!("{}", error));
+        let mut gaits = HashMap::new();
+        for gait in &config.gaits {
+            gait.validate(&config.groups)
+                .unwrap_or_else(|error| panic!("{}", error));
+            gaits.insert(gait.name.clone(), gait.clone());
+        }
+        let mut linear_axes = HashMap::new();
+        for axis in &config.linear_axes {
+            axis.validate().unwrap_or_else(|error| panic!("{}", error));
+            linear_axes.insert(axis.name.clone(), LinearAxis::new(axis, config.gpio));
+        }
+        for rule in &config.rules {
+            rule.validate(&config.presets)
+                .unwrap_or_else(|error| panic!("{}", error));
+        }
+        let rules = Rules::new(&config.rules, &presets);
+        let virtual_channels =
+            VirtualChannels::new(&config.virtual_channels, &config.derived_channels)
+                .unwrap_or_else(|error| panic!("{}", error));
 
         let pca = Pca9685 {
-            inner: Mutex::new(inner),
-            channels: Mutex::new(channels),
+            inner: Arc::new(Mutex::new(inner)),
+            bus: PhantomData,
+            channels: Arc::new(Mutex::new(channels)),
+            groups,
+            mixers: Mutex::new(mixers),
+            teleop: Mutex::new(Teleop::new(&config.teleop)),
+            watchdog: Arc::new(Mutex::new(config.watchdog.as_ref().map(Watchdog::new))),
+            idle: Arc::new(Mutex::new(
+                config
+                    .idle
+                    .as_ref()
+                    .map(|idle| Idle::new(idle, Instant::now())),
+            )),
+            allow_jitter: config.allow_jitter,
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+            device_error: Arc::new(Mutex::new(None)),
+            paused: Mutex::new(None),
+            reinit_count: Arc::new(AtomicU32::new(0)),
+            sequences: Mutex::new(HashMap::new()),
+            playback: Arc::new(Mutex::new(None)),
+            presets,
+            scenes: Mutex::new(SceneStack::new(&config.scenes)),
+            recorder: Mutex::new(None),
+            gaits,
+            linear_axes: Arc::new(Mutex::new(linear_axes)),
+            motions: Arc::new(Mutex::new(Motions::new())),
+            rules: Arc::new(Mutex::new(rules)),
+            virtual_channels: Mutex::new(virtual_channels),
+            #[cfg(feature = "gimbal")]
+            gimbal: Arc::new(Mutex::new(config.gimbal.as_ref().map(Gimbal::new))),
         };
 
         for c in &config.channels {
-            pca.configure_channel(&c).unwrap();
+            pca.configure_channel(c).unwrap();
+        }
+        if let Some(startup) = &config.startup {
+            pca.soft_start(&config.channels, startup);
         }
 
+        pca.spawn_worker();
+
         pca
     }
 
+    /// Ramps each Channel configured with a `current_count` to it, as
+    /// described by [StartupConfig].  Channels whose `current_count` is
+    /// outside their limits are left off.
+    fn soft_start(&self, configs: &[ChannelConfig], startup: &StartupConfig) {
+        let started = Instant::now() + Duration::from_millis(startup.delay_ms);
+        let channels = self.channels.lock().unwrap();
+        let mut motions = self.motions.lock().unwrap();
+
+        for config in configs {
+            let Some(to) = config.current_count else {
+                continue;
+            };
+            let ch = &channels[&(config.channel as u8)];
+            let (command_type, value) = match to {
+                PCA_PWM_RESOLUTION => (CommandType::FullOn, None),
+                to => (CommandType::PulseCount, Some(to as f64)),
+            };
+            let command = ChannelCommand {
+                channel: config.channel,
+                command_type,
+                value,
+                basis: None,
+                client_id: None,
+            };
+            if let Err(error) = ch.preview(&command) {
+                log::warn!(target: ch.log_target(), "Not started: {}", error);
+                continue;
+            }
+
+            motions.start(
+                config.channel,
+                (ch.config().limits().0, to),
+                Duration::from_millis(startup.ramp_ms),
+                Easing::Linear,
+                started,
+            );
+        }
+    }
+
+    /// Spawns the thread that performs the channels' time-based behavior
+    /// (see [ChannelProxy::tick]), which exits once the [Pca9685] is dropped.
+    fn spawn_worker(&self) {
+        let inner = Arc::downgrade(&self.inner);
+        let channels = Arc::downgrade(&self.channels);
+        let subscribers = Arc::downgrade(&self.subscribers);
+        let watchdog = Arc::downgrade(&self.watchdog);
+        let playback = Arc::downgrade(&self.playback);
+        let idle = Arc::downgrade(&self.idle);
+        let linear_axes = Arc::downgrade(&self.linear_axes);
+        let motions = Arc::downgrade(&self.motions);
+        let rules = Arc::downgrade(&self.rules);
+        #[cfg(feature = "gimbal")]
+        let gimbal = Arc::downgrade(&self.gimbal);
+
+        thread::spawn(move || loop {
+            thread::sleep(WORKER_TICK_INTERVAL);
+
+            let (
+                Some(inner),
+                Some(channels),
+                Some(subscribers),
+                Some(watchdog),
+                Some(playback),
+                Some(idle),
+                Some(linear_axes),
+                Some(motions),
+                Some(rules),
+            ) = (
+                inner.upgrade(),
+                channels.upgrade(),
+                subscribers.upgrade(),
+                watchdog.upgrade(),
+                playback.upgrade(),
+                idle.upgrade(),
+                linear_axes.upgrade(),
+                motions.upgrade(),
+                rules.upgrade(),
+            )
+            else {
+                return;
+            };
+            #[cfg(feature = "gimbal")]
+            let Some(gimbal) = gimbal.upgrade() else {
+                return;
+            };
+
+            let mut events = Vec::new();
+            {
+                let mut locked_pca_impl = inner.lock().unwrap();
+                let mut channels = channels.lock().unwrap();
+                let now = Instant::now();
+                for ch in channels.values_mut() {
+                    if let Err(error) = ch.tick(now, &mut locked_pca_impl, &mut events) {
+                        log::warn!(target: "pca9685", "{}", error);
+                    }
+                }
+
+                let mut watchdog = watchdog.lock().unwrap();
+                let trips = match watchdog.as_mut() {
+                    Some(watchdog) => watchdog.poll(now),
+                    None => Vec::new(),
+                };
+                for trip in trips {
+                    log::warn!(target: "pca9685", "Watchdog tripped: {:?}", trip);
+                    let affected = |ch: &ChannelProxy| match &trip {
+                        WatchdogTrip::All => true,
+                        WatchdogTrip::Client(client_id) => {
+                            ch.config().owner.as_ref() == Some(client_id)
+                        }
+                    };
+                    let affected_channels: Vec<Channel> = channels
+                        .values()
+                        .filter(|ch| affected(ch))
+                        .map(|ch| ch.config().channel)
+                        .collect();
+
+                    // Stop whatever else drives the affected Channels, lest
+                    // it override the failsafe
+                    let mut motions = motions.lock().unwrap();
+                    for channel in &affected_channels {
+                        motions.stop(*channel);
+                    }
+                    drop(motions);
+                    let mut playback = playback.lock().unwrap();
+                    if let Some(active) = playback.as_ref() {
+                        if affected_channels
+                            .iter()
+                            .any(|channel| active.drives(*channel))
+                        {
+                            log::warn!(target: "pca9685", "Stopped playing sequence {}", active.name());
+                            *playback = None;
+                        }
+                    }
+                    drop(playback);
+                    for axis in linear_axes.lock().unwrap().values_mut() {
+                        if affected_channels.contains(&axis.channel()) {
+                            axis.stop();
+                        }
+                    }
+
+                    for ch in channels.values_mut().filter(|ch| affected(ch)) {
+                        let full_off = watchdog
+                            .as_ref()
+                            .is_some_and(|watchdog| watchdog.full_off(ch.config().channel as u8));
+                        let result = match ch.apply_failsafe(None, &mut locked_pca_impl) {
+                            Ok(None) if full_off => ch.full_off(&mut locked_pca_impl).map(Some),
+                            result => result,
+                        };
+                        if let Err(error) = result {
+                            log::warn!(target: "pca9685", "{}", error);
+                        }
+                    }
+                    events.push(Pca9685Event::WatchdogTripped(match trip {
+                        WatchdogTrip::All => None,
+                        WatchdogTrip::Client(client_id) => Some(client_id),
+                    }));
+                }
+                drop(watchdog);
+
+                let commands = rules.lock().unwrap().poll(
+                    now,
+                    |raw_channel| channels[&raw_channel].output_pct(),
+                    &events,
+                );
+                for command in commands {
+                    let ch = channels.get_mut(&(command.channel as u8)).unwrap();
+                    if let Err(error) = ch.execute(&command, &mut locked_pca_impl) {
+                        log::warn!(target: "pca9685", "Rules: {}", error);
+                    }
+                }
+
+                let mut playback = playback.lock().unwrap();
+                if let Some(active) = playback.as_mut() {
+                    for command in active.poll(now) {
+                        let ch = channels.get_mut(&(command.channel as u8)).unwrap();
+                        if let Err(error) = ch.execute(&command, &mut locked_pca_impl) {
+                            log::warn!(target: "pca9685", "Sequence {}: {}", active.name(), error);
+                        }
+                    }
+                    if active.finished() {
+                        log::info!(target: "pca9685", "Finished playing sequence {}", active.name());
+                        *playback = None;
+                    }
+                }
+
+                // A playing sequence counts as commands, keeping idling at bay
+                if let Some(idle) = idle.lock().unwrap().as_mut() {
+                    if playback.is_some() {
+                        idle.feed(now);
+                    }
+                    for command in idle.poll(now, &mut rand::thread_rng()) {
+                        let ch = channels.get_mut(&(command.channel as u8)).unwrap();
+                        if let Err(error) = ch.execute(&command, &mut locked_pca_impl) {
+                            log::warn!(target: "pca9685", "Idle: {}", error);
+                        }
+                    }
+                }
+
+                for axis in linear_axes.lock().unwrap().values_mut() {
+                    if let Some(command) = axis.poll(now) {
+                        let ch = channels.get_mut(&(command.channel as u8)).unwrap();
+                        if let Err(error) = ch.execute(&command, &mut locked_pca_impl) {
+                            log::warn!(target: "pca9685", "Linear axis: {}", error);
+                        }
+                    }
+                }
+
+                for command in motions.lock().unwrap().poll(now) {
+                    let ch = channels.get_mut(&(command.channel as u8)).unwrap();
+                    if let Err(error) = ch.execute(&command, &mut locked_pca_impl) {
+                        log::warn!(target: "pca9685", "Motion: {}", error);
+                    }
+                }
+
+                #[cfg(feature = "gimbal")]
+                if let Some(gimbal) = gimbal.lock().unwrap().as_mut() {
+                    for command in gimbal.poll(now) {
+                        if let Some(ch) = channels.get_mut(&(command.channel as u8)) {
+                            if let Err(error) = ch.execute(&command, &mut locked_pca_impl) {
+                                log::warn!(target: "pca9685", "Gimbal: {}", error);
+                            }
+                        }
+                    }
+                }
+            }
+
+            Self::publish(&subscribers, events);
+        });
+    }
+
     /// Returns the maximum pulse width (in milliseconds) given the configured
     /// output frequency of the [Pca9685].
     pub fn max_pw_ms(&self) -> f64 {
@@ -73,6 +797,20 @@ impl Pca9685 {
         return self.inner.lock().unwrap().output_frequency_hz();
     }
 
+    /// Returns the output frequency (in Hz) actually produced by the
+    /// [Pca9685], which differs slightly from the configured output frequency
+    /// because the prescale value is rounded to a whole number.
+    pub fn actual_output_frequency_hz(&self) -> f64 {
+        self.inner.lock().unwrap().actual_output_frequency_hz()
+    }
+
+    /// Returns the frequency (in Hz) of the oscillator clocking the
+    /// [Pca9685]: the configured external clock, if any, or else the
+    /// internal oscillator's (see [Config::oscillator_hz]).
+    pub fn oscillator_hz(&self) -> f64 {
+        self.inner.lock().unwrap().oscillator_hz()
+    }
+
     /// Returns the configured [Pca9685] device (e.g., `/dev/i2c-1`).
     pub fn device(&self) -> String {
         return self.inner.lock().unwrap().device();
@@ -95,159 +833,4941 @@ impl Pca9685 {
         return self.inner.lock().unwrap().output_type();
     }
 
-    /// Returns the [ChannelConfig] of the requested `channel`.
-    pub fn config(&self, channel: Channel) -> Pca9685Result<ChannelConfig> {
+    /// Returns what every output does while disabled by the OE pin (see
+    /// [Config::disabled_output]).
+    pub fn disabled_output(&self) -> DisabledOutput {
+        self.inner.lock().unwrap().disabled_output()
+    }
+
+    /// Changes what every output does while disabled by the OE pin (see
+    /// [Config::disabled_output]), e.g., to let servos go limp rather than be
+    /// driven low on fault.  Kept across [Pca9685::reset].
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn set_disabled_output(&self, disabled_output: DisabledOutput) -> Pca9685Result<()> {
+        log::info!(target: "pca9685", "Disabled output: {:?}", disabled_output);
+        self.inner
+            .lock()
+            .unwrap()
+            .set_disabled_output(disabled_output)
+            .map_err(Pca9685Error::Pca9685DriverError)
+    }
+
+    /// Returns the count at which the `channel` output rises each cycle: 0,
+    /// unless staggered (see [Config::stagger_on_counts]).  Its output falls
+    /// the Channel's count later, wrapping around the end of the cycle.
+    pub fn on_count(&self, channel: Channel) -> u16 {
+        self.inner.lock().unwrap().on_count(channel)
+    }
+
+    /// Puts the device to sleep, stopping its oscillator and so every output
+    /// (e.g., to save power between uses), until [Pca9685::wake].  Channels
+    /// keep their configuration and registers; commands are still accepted,
+    /// taking effect once awake.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn sleep(&self) -> Pca9685Result<()> {
+        log::info!(target: "pca9685", "Sleeping");
+        self.inner
+            .lock()
+            .unwrap()
+            .sleep()
+            .map_err(Pca9685Error::Pca9685DriverError)
+    }
+
+    /// Wakes the device put to sleep by [Pca9685::sleep], restarting the
+    /// outputs it stopped once the oscillator has stabilized (500us).
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn wake(&self) -> Pca9685Result<()> {
+        log::info!(target: "pca9685", "Waking");
+        self.inner
+            .lock()
+            .unwrap()
+            .wake()
+            .map_err(Pca9685Error::Pca9685DriverError)
+    }
+
+    /// Returns whether the device was put to sleep (see [Pca9685::sleep]).
+    pub fn is_asleep(&self) -> bool {
+        self.inner.lock().unwrap().asleep()
+    }
+
+    /// Recovers the device from a reset to its power-up defaults (e.g., by
+    /// a brown-out): resets it by the I2C general call SWRST, reprograms its
+    /// prescale and output driver, then rewrites every Channel's last known
+    /// output.  **Every** PCA9685 on the bus is reset, not just this one.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error (rewriting a Channel, once every Channel has been
+    ///   attempted)
+    pub fn reset(&self) -> Pca9685Result<()> {
+        log::warn!(target: "pca9685", "Resetting device {}", self.device());
+
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+        locked_pca_impl
+            .reset()
+            .map_err(Pca9685Error::Pca9685DriverError)?;
+
+        let mut channels = self.channels.lock().unwrap();
+        let mut raw_channels: Vec<u8> = channels.keys().cloned().collect();
+        raw_channels.sort_unstable();
+
+        let mut result = Ok(());
+        for raw_channel in raw_channels {
+            if let Some(ch) = channels.get_mut(&raw_channel) {
+                result = result.and(ch.rewrite(&mut locked_pca_impl));
+            }
+        }
+
+        result
+    }
+
+    /// Returns a receiver of the [Pca9685Event]s published from now on.
+    /// Dropping the receiver unsubscribes.
+    pub fn subscribe(&self) -> Receiver<Pca9685Event> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(sender);
+
+        receiver
+    }
+
+    fn publish(subscribers: &Mutex<Vec<Sender<Pca9685Event>>>, events: Vec<Pca9685Event>) {
+        if events.is_empty() {
+            return;
+        }
+
+        let mut subscribers = subscribers.lock().unwrap();
+        for event in events {
+            subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+        }
+    }
+
+    /// Stops everything under way (the sequence or gait playing, timed moves
+    /// and linear axes) and publishes [Pca9685Event::EmergencyStop],
+    /// carrying out at once the rules awaiting it (see [crate::RuleConfig]).
+    /// Channels otherwise retain their current output.
+    pub fn emergency_stop(&self) {
+        log::warn!(target: "pca9685", "Emergency stop");
+        *self.playback.lock().unwrap() = None;
+        for axis in self.linear_axes.lock().unwrap().values_mut() {
+            axis.stop();
+        }
+        self.motions.lock().unwrap().stop_all();
+
+        let events = vec![Pca9685Event::EmergencyStop];
+        {
+            let mut locked_pca_impl = self.inner.lock().unwrap();
+            let mut channels = self.channels.lock().unwrap();
+            let commands = self.rules.lock().unwrap().poll(
+                Instant::now(),
+                |raw_channel| channels[&raw_channel].output_pct(),
+                &events,
+            );
+            for command in commands {
+                let ch = channels.get_mut(&(command.channel as u8)).unwrap();
+                if let Err(error) = ch.execute(&command, &mut locked_pca_impl) {
+                    log::warn!(target: "pca9685", "Rules: {}", error);
+                }
+            }
+        }
+
+        Self::publish(&self.subscribers, events);
+    }
+
+    /// Returns the [ChannelStats] of the requested `channel`.
+    pub fn stats(&self, channel: Channel) -> Pca9685Result<ChannelStats> {
         let raw_channel = channel as u8;
 
         match self.channels.lock().unwrap().get(&raw_channel) {
-            Some(ch) => Ok(ch.config()),
+            Some(ch) => Ok(ch.stats()),
             None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
         }
     }
 
-    /// Configures a channel given a [ChannelConfig].
-    pub fn configure_channel(&self, config: &ChannelConfig) -> Pca9685Result<ChannelConfig> {
-        let raw_channel = config.channel as u8;
+    /// Returns the most recent driver error writing any Channel, if any
+    pub fn last_driver_error(&self) -> Option<DriverErrorRecord> {
+        self.channels
+            .lock()
+            .unwrap()
+            .values()
+            .filter_map(ChannelProxy::last_driver_error)
+            .max_by_key(|record| record.timestamp_ms)
+            .cloned()
+    }
 
-        match self.channels.lock().unwrap().get_mut(&raw_channel) {
-            Some(ch) => ch.configure(&config),
-            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+    /// Returns how many times the device has been initialized after starting
+    /// degraded (see [Pca9685::new_or_degraded])
+    pub fn reinit_count(&self) -> u32 {
+        self.reinit_count.load(Ordering::Relaxed)
+    }
+
+    /// Returns the [ChannelMetrics] of every Channel, ordered by Channel.
+    pub fn metrics(&self) -> Vec<ChannelMetrics> {
+        let now = Instant::now();
+        let locked_channels = self.channels.lock().unwrap();
+        let motions = self.motions.lock().unwrap();
+        let mut metrics: Vec<ChannelMetrics> = locked_channels
+            .values()
+            .map(|ch| Self::channel_metrics(ch, &motions, now))
+            .collect();
+        drop(motions);
+        drop(locked_channels);
+        metrics.sort_unstable_by_key(|metrics| metrics.channel as u8);
+
+        metrics
+    }
+
+    /// Returns the [ChannelMetrics] of `ch`, in motion while it is moving (see
+    /// [Pca9685::move_to]) as well as while its output is being shaped
+    fn channel_metrics(ch: &ChannelProxy, motions: &Motions, now: Instant) -> ChannelMetrics {
+        let metrics = ch.metrics(now);
+
+        ChannelMetrics {
+            motion_in_progress: metrics.motion_in_progress || motions.moving(metrics.channel),
+            ..metrics
         }
     }
 
-    /// Sets `channel` to full/continuous output, returning the resulting
-    /// [ChannelConfig] containing the updated `current_count`.
-    ///
-    /// Ignores any configured ChannelCountLimits, if applicable.
-    pub fn full_on(&self, channel: Channel) -> Pca9685Result<ChannelConfig> {
-        let mut locked_pca_impl = self.inner.lock().unwrap();
+    /// Reports the state of the [Pca9685] (see [DebugReport]), with its
+    /// Channels ordered by Channel, e.g. to log when diagnosing a rig.
+    pub fn debug_report(&self) -> DebugReport {
+        let now = Instant::now();
+        let locked_channels = self.channels.lock().unwrap();
+        let motions = self.motions.lock().unwrap();
+        let mut channels: Vec<ChannelReport> = locked_channels
+            .values()
+            .map(|ch| {
+                let config = ch.config();
+                ChannelReport {
+                    moving: motions.moving(config.channel),
+                    config,
+                    stats: ch.stats(),
+                    metrics: Self::channel_metrics(ch, &motions, now),
+                    last_driver_error: ch.last_driver_error().cloned(),
+                    feedback_error: ch.feedback_error().cloned(),
+                }
+            })
+            .collect();
+        drop(motions);
+        drop(locked_channels);
+        channels.sort_unstable_by_key(|report| report.config.channel as u8);
 
+        DebugReport {
+            device: self.device(),
+            address: self.address(),
+            prescale: self.prescale(),
+            actual_output_frequency_hz: self.actual_output_frequency_hz(),
+            device_error: self.device_error(),
+            reinit_count: self.reinit_count(),
+            last_driver_error: channels
+                .iter()
+                .filter_map(|report| report.last_driver_error.as_ref())
+                .max_by_key(|record| record.timestamp_ms)
+                .cloned(),
+            paused: self.paused(),
+            playback: self.playback(),
+            recording: self.recording(),
+            channels,
+        }
+    }
+
+    /// Clears the fault of the requested `channel` (see
+    /// [CircuitBreakerConfig]), so that it accepts commands again.  Its
+    /// output is left as is (i.e., at its safe output) until next commanded.
+    pub fn clear_fault(&self, channel: Channel) -> Pca9685Result<ChannelStats> {
         let raw_channel = channel as u8;
 
         match self.channels.lock().unwrap().get_mut(&raw_channel) {
-            Some(ch) => ch.full_on(&mut locked_pca_impl),
+            Some(ch) => Ok(ch.clear_fault()),
             None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
         }
     }
 
-    /// Sets `channel` to off (no output), returning the resulting
-    /// [ChannelConfig] containing the updated `current_count` as None.
-    ///
-    /// Ignores any configured ChannelCountLimits, if applicable.
-    ///
-    /// Error conditions:
-    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
-    /// yields an error
-    pub fn full_off(&self, channel: Channel) -> Pca9685Result<ChannelConfig> {
-        let mut locked_pca_impl = self.inner.lock().unwrap();
+    /// Returns the reason the device could not be initialized, if the
+    /// [Pca9685] is degraded (see [Pca9685::new_or_degraded]).
+    pub fn device_error(&self) -> Option<String> {
+        self.device_error.lock().unwrap().clone()
+    }
+
+    /// Returns (and clears) the writes captured since last taken, in order,
+    /// if the [Pca9685] is tracing (see [Pca9685::tracing]).
+    pub fn take_trace(&self) -> Vec<TraceWrite> {
+        self.inner.lock().unwrap().take_trace()
+    }
+
+    /// Returns the [ChannelConfig] of the requested `channel`.
+    pub fn config(&self, channel: Channel) -> Pca9685Result<ChannelConfig> {
+        let raw_channel = channel as u8;
 
+        match self.channels.lock().unwrap().get(&raw_channel) {
+            Some(ch) => Ok(ch.config()),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        }
+    }
+
+    /// Returns the configuration of the requested `channel` along with its
+    /// revision, which changes whenever the configuration (including
+    /// `current_count` and `owner`) does, e.g. to serve as an HTTP ETag.
+    pub fn revised_config(&self, channel: Channel) -> Pca9685Result<(ChannelConfig, u64)> {
         let raw_channel = channel as u8;
 
         match self.channels.lock().unwrap().get_mut(&raw_channel) {
-            Some(ch) => ch.full_off(&mut locked_pca_impl),
+            Some(ch) => Ok(ch.revised_config()),
             None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
         }
     }
 
-    /// Sets the `channel` output to `count` pulse counts, returning the resulting
-    /// [ChannelConfig] containing the updated `current_count`.
-    ///
-    /// Error conditions:
-    /// * [Pca9685Error::PulseWidthRangeError] if `count` is not within the
-    /// limits of the PCA9685
-    /// * [Pca9685Error::CustomLimitsError] if `count` is not within the channel's
-    /// configured limits
-    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
-    /// yields an error
-    pub fn set_pwm_count(&self, channel: Channel, count: u16) -> Pca9685Result<ChannelConfig> {
-        let mut locked_pca_impl = self.inner.lock().unwrap();
+    /// Returns true if the requested `channel` has finished moving to its
+    /// commanded output, including any move (see [Pca9685::move_to]).
+    pub fn is_settled(&self, channel: Channel) -> Pca9685Result<bool> {
+        let raw_channel = channel as u8;
+
+        match self.channels.lock().unwrap().get(&raw_channel) {
+            Some(ch) => Ok(ch.is_settled() && !self.moving(channel)),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        }
+    }
 
+    /// Returns the recent output history of the requested `channel`, oldest
+    /// first.  When `resolution_ms` is given, only the most recent entry
+    /// within each window of that many milliseconds is returned.
+    pub fn history(
+        &self,
+        channel: Channel,
+        resolution_ms: Option<u64>,
+    ) -> Pca9685Result<Vec<ChannelHistoryEntry>> {
         let raw_channel = channel as u8;
 
-        match self.channels.lock().unwrap().get_mut(&raw_channel) {
-            Some(ch) => ch.set_pwm_count(count, &mut locked_pca_impl),
+        match self.channels.lock().unwrap().get(&raw_channel) {
+            Some(ch) => Ok(ch.history(resolution_ms)),
             None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
         }
     }
 
-    /// Sets the `channel` output to `pw_ms` pulse width in milliseconds,
-    /// returning the resulting [ChannelConfig] containing the updated
-    /// `current_count`.
+    /// Returns the output history of every Channel, oldest first, limited to
+    /// entries at or after `since_ms` and at or before `until_ms` (both
+    /// milliseconds since the UNIX epoch), when given.
+    pub fn history_records(
+        &self,
+        since_ms: Option<u64>,
+        until_ms: Option<u64>,
+    ) -> Vec<ChannelHistoryRecord> {
+        let range = since_ms.unwrap_or(0)..=until_ms.unwrap_or(u64::MAX);
+        let channels = self.channels.lock().unwrap();
+        let mut raw_channels: Vec<&u8> = channels.keys().collect();
+        raw_channels.sort_unstable();
+
+        let mut records: Vec<ChannelHistoryRecord> = raw_channels
+            .into_iter()
+            .flat_map(|raw_channel| {
+                let ch = &channels[raw_channel];
+                let config = ch.config();
+                ch.history(None)
+                    .into_iter()
+                    .filter(|entry| range.contains(&entry.timestamp_ms))
+                    .map(move |entry| ChannelHistoryRecord {
+                        channel: config.channel,
+                        name: config.name.clone(),
+                        timestamp_ms: entry.timestamp_ms,
+                        count: entry.count,
+                        client_id: entry.client_id,
+                    })
+            })
+            .collect();
+        // Stable, so that simultaneous entries remain in Channel order
+        records.sort_by_key(|record| record.timestamp_ms);
+
+        records
+    }
+
+    /// Configures a channel given a [ChannelConfig].
     ///
     /// Error conditions:
-    /// * [Pca9685Error::PulseWidthRangeError] if `pw_ms` is not within the
-    /// limits of the PCA9685 (based on the configured output frequency)
-    /// * [Pca9685Error::CustomLimitsError] if `pw_ms` is not within the channel's
-    /// configured limits
-    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
-    /// yields an error
-    pub fn set_pw_ms(&self, channel: Channel, pw_ms: f64) -> Pca9685Result<ChannelConfig> {
-        let mut locked_pca_impl = self.inner.lock().unwrap();
+    /// * [Pca9685Error::InvalidConfiguration] if the `name` is used by
+    ///   another channel
+    pub fn configure_channel(&self, config: &ChannelConfig) -> Pca9685Result<ChannelConfig> {
+        let raw_channel = config.channel as u8;
+        let mut channels = self.channels.lock().unwrap();
 
-        let raw_channel = channel as u8;
+        if let Some(name) = &config.name {
+            if let Some(other) = channels.values().find(|ch| {
+                ch.config().channel != config.channel && ch.config().name.as_ref() == Some(name)
+            }) {
+                return Err(Pca9685Error::InvalidConfiguration(format!(
+                    "ChannelConfig.name '{}' is already used by channel {}",
+                    name,
+                    other.config().channel as u8
+                )));
+            }
+        }
 
-        match self.channels.lock().unwrap().get_mut(&raw_channel) {
-            Some(ch) => ch.set_pw_ms(pw_ms, &mut locked_pca_impl),
+        match channels.get_mut(&raw_channel) {
+            Some(ch) => ch.configure(config),
             None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
         }
     }
 
-    /// Sets the `channel` output to `pct` percent duty cycle (based on the
-    /// channel's configured ChannelCountLimits, if applicable),
-    /// returning the resulting [ChannelConfig] containing the updated
-    /// `current_count`.
+    /// Returns the Channel named `name` (see [ChannelConfig]).
     ///
     /// Error conditions:
-    /// * [Pca9685Error::PercentOfRangeError] if `pct` is not within [0.0, 1.0]
-    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
-    /// yields an error
-    pub fn set_pct(&self, channel: Channel, pct: f64) -> Pca9685Result<ChannelConfig> {
-        let mut locked_pca_impl = self.inner.lock().unwrap();
+    /// * [Pca9685Error::NoSuchChannelNameError] if no Channel is so named
+    pub fn channel_by_name(&self, name: &str) -> Pca9685Result<Channel> {
+        self.channels
+            .lock()
+            .unwrap()
+            .values()
+            .map(|ch| ch.config())
+            .find(|config| config.name.as_deref() == Some(name))
+            .map(|config| config.channel)
+            .ok_or_else(|| Pca9685Error::NoSuchChannelNameError(name.to_string()))
+    }
+
+    /// Returns the configuration of the Channel named `name`, as by
+    /// [Pca9685::config].
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchChannelNameError] if no Channel is so named
+    pub fn config_by_name(&self, name: &str) -> Pca9685Result<ChannelConfig> {
+        self.config(self.channel_by_name(name)?)
+    }
 
+    /// Returns the log target of the requested `channel`'s messages: its
+    /// name, if it has one (e.g., `left_elbow`), otherwise e.g. `Channel C3`.
+    pub fn log_target(&self, channel: Channel) -> Pca9685Result<String> {
         let raw_channel = channel as u8;
 
-        match self.channels.lock().unwrap().get_mut(&raw_channel) {
-            Some(ch) => ch.set_pct(pct, &mut locked_pca_impl),
+        match self.channels.lock().unwrap().get(&raw_channel) {
+            Some(ch) => Ok(ch.log_target().to_string()),
             None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::{Config, Pca9685};
-    use pwm_pca9685::OutputDriver;
+    /// Returns the Channels selected by `selector`.
+    pub fn select(&self, selector: &ChannelSelector) -> Pca9685Result<Vec<Channel>> {
+        match selector {
+            ChannelSelector::Channel(raw_channel) => Channel::try_from(*raw_channel)
+                .map(|channel| vec![channel])
+                .map_err(|_| Pca9685Error::NoSuchChannelError(*raw_channel)),
+            ChannelSelector::Name(name) => Ok(vec![self.channel_by_name(name)?]),
+            ChannelSelector::Group(name) => self
+                .groups
+                .get(name)
+                .cloned()
+                .ok_or_else(|| Pca9685Error::NoSuchGroupError(name.clone())),
+            ChannelSelector::Tag(tag) => {
+                let channels = self.channels.lock().unwrap();
+                let mut tagged: Vec<Channel> = channels
+                    .values()
+                    .map(ChannelProxy::config)
+                    .filter(|config| config.tags.contains(tag))
+                    .map(|config| config.channel)
+                    .collect();
+                tagged.sort_unstable_by_key(|channel| *channel as u8);
 
-    fn create_mock(output_frequency_hz: u16) -> (Config, Pca9685) {
-        let config = Config {
-            device: "/dev/foo".to_owned(),
-            address: 0x40,
-            output_frequency_hz: output_frequency_hz,
-            open_drain: false,
-            channels: Default::default(),
-        };
+                match tagged.is_empty() {
+                    true => Err(Pca9685Error::NoSuchTagError(tag.clone())),
+                    false => Ok(tagged),
+                }
+            }
+            ChannelSelector::All => Ok((0..16_u8)
+                .map(|raw_channel| Channel::try_from(raw_channel).unwrap())
+                .collect()),
+        }
+    }
 
-        let pca = Pca9685::null(&config);
+    /// Registers `filter` on the selected Channels, to be applied (after any
+    /// previously registered filters) to every count subsequently requested
+    /// of them.
+    pub fn add_filter(
+        &self,
+        selector: &ChannelSelector,
+        filter: Arc<dyn ChannelFilter>,
+    ) -> Pca9685Result<()> {
+        let selected = self.select(selector)?;
+        let mut channels = self.channels.lock().unwrap();
 
-        return (config, pca);
+        for channel in selected {
+            let raw_channel = channel as u8;
+            match channels.get_mut(&raw_channel) {
+                Some(ch) => ch.add_filter(filter.clone()),
+                None => return Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the configuration of every mixer, ordered by name.
+    pub fn mixers(&self) -> Vec<MixerConfig> {
+        let mut configs: Vec<MixerConfig> = self
+            .mixers
+            .lock()
+            .unwrap()
+            .values()
+            .map(|mixer| mixer.config().clone())
+            .collect();
+        configs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        configs
+    }
+
+    /// Replaces the configuration of every mixer (see [MixerConfig]) without
+    /// interrupting control: same-named mixers retain their input values,
+    /// and mixers whose outputs had been positioned are re-positioned per
+    /// their new configuration (atomically, per mixer).
+    ///
+    /// The new configuration is validated in full before any of it is applied.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if a config is invalid or two
+    ///   share a name
+    /// * Any error documented by [Pca9685::apply] when re-positioning outputs,
+    ///   in which case the new configuration is nonetheless retained
+    pub fn configure_mixers(&self, configs: &[MixerConfig]) -> Pca9685Result<Vec<MixerConfig>> {
+        let mut mixers = self.mixers.lock().unwrap();
+        *mixers = mixer::build_mixers(configs, &mixers)?;
+        log::info!(target: "pca9685", "Configured {} mixer(s)", mixers.len());
+
+        for mixer in mixers.values().filter(|mixer| mixer.is_applied()) {
+            self.apply(&mixer.commands(&mixer.current()), true)?;
+        }
+        drop(mixers);
+
+        Ok(self.mixers())
+    }
+
+    /// Returns every sequence (see [Pca9685::configure_sequence]), by name.
+    pub fn sequences(&self) -> Vec<SequenceConfig> {
+        let mut sequences: Vec<SequenceConfig> =
+            self.sequences.lock().unwrap().values().cloned().collect();
+        sequences.sort_by(|a, b| a.name.cmp(&b.name));
+
+        sequences
+    }
+
+    /// Returns the named sequence.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchSequenceError] if no such sequence is configured
+    pub fn sequence(&self, name: &str) -> Pca9685Result<SequenceConfig> {
+        match self.sequences.lock().unwrap().get(name) {
+            Some(sequence) => Ok(sequence.clone()),
+            None => Err(Pca9685Error::NoSuchSequenceError(name.to_string())),
+        }
+    }
+
+    /// Validates a sequence, resolved with its steps (see
+    /// [crate::SequenceStep]), against the current configuration of its
+    /// Channels: each must be configured, and each keyframe within its limits.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if the sequence is invalid (see
+    ///   [SequenceConfig::validate]), can't be resolved, or names an
+    ///   unconfigured Channel
+    /// * [Pca9685Error::NoSuchSequenceError] or [Pca9685Error::NoSuchGroupError]
+    ///   if a step names a sequence or group that doesn't exist
+    /// * [Pca9685Error::InvalidCommand], [Pca9685Error::CustomLimitsError],
+    ///   [Pca9685Error::PulseWidthRangeError] or
+    ///   [Pca9685Error::PercentOfRangeError] if a keyframe's value is invalid
+    pub fn validate_sequence(&self, sequence: &SequenceConfig) -> Pca9685Result<()> {
+        sequence.validate()?;
+
+        let mut sequences = self.sequences.lock().unwrap().clone();
+        sequences.insert(sequence.name.clone(), sequence.clone());
+        self.check_keyframes(
+            sequence,
+            &sequence::resolve(sequence, &sequences, &self.groups)?,
+        )
+    }
+
+    /// Returns the named sequence's keyframes once resolved with its steps
+    /// (see [crate::SequenceStep]), ordered by `t_ms`, i.e. as it would be
+    /// played.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchSequenceError] if no such sequence is configured
+    /// * Any error documented by [Pca9685::validate_sequence], as the
+    ///   sequences or Channels involved may have changed since
+    pub fn resolve_sequence(&self, name: &str) -> Pca9685Result<Vec<Keyframe>> {
+        self.resolve_remapped(name, &BTreeMap::new())
+    }
+
+    /// Resolves the named sequence as by [Pca9685::resolve_sequence], but
+    /// validated against the Channels its keyframes are remapped onto (see
+    /// [Replay])
+    fn resolve_remapped(
+        &self,
+        name: &str,
+        channel_map: &BTreeMap<u8, u8>,
+    ) -> Pca9685Result<Vec<Keyframe>> {
+        let sequences = self.sequences.lock().unwrap().clone();
+        let sequence = sequences
+            .get(name)
+            .ok_or_else(|| Pca9685Error::NoSuchSequenceError(name.to_string()))?;
+
+        let keyframes = sequence::remap(
+            sequence::resolve(sequence, &sequences, &self.groups)?,
+            channel_map,
+        )?;
+        self.check_keyframes(sequence, &keyframes)?;
+
+        Ok(keyframes)
+    }
+
+    fn check_keyframes(
+        &self,
+        sequence: &SequenceConfig,
+        keyframes: &[Keyframe],
+    ) -> Pca9685Result<()> {
+        let channels = self.channels.lock().unwrap();
+        for keyframe in keyframes {
+            let ch = &channels[&keyframe.channel];
+            if ch.config().custom_limits.is_none() {
+                return Err(Pca9685Error::InvalidConfiguration(format!(
+                    "Sequence {}: channel {} is not configured",
+                    sequence.name, keyframe.channel
+                )));
+            }
+
+            ch.check_command(&keyframe.command())?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks every keyframe of the named sequence, resolved with its steps
+    /// (see [Pca9685::resolve_sequence]), against the Channels of `against`
+    /// (by default, the running configuration), e.g. before taking a show to
+    /// another rig.  Each keyframe's Channel must be configured, and its
+    /// output valid within the Channel's limits; each violation is reported
+    /// as a finding located within the resolved timeline (e.g.,
+    /// `keyframes[12]`).
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchSequenceError] if no such sequence is configured
+    /// * Any error documented by [Pca9685::validate_sequence] resolving the
+    ///   sequence's steps
+    pub fn check_sequence(
+        &self,
+        name: &str,
+        against: Option<&Config>,
+    ) -> Pca9685Result<ConfigValidation> {
+        let sequences = self.sequences.lock().unwrap().clone();
+        let sequence = sequences
+            .get(name)
+            .ok_or_else(|| Pca9685Error::NoSuchSequenceError(name.to_string()))?;
+        let keyframes = sequence::resolve(sequence, &sequences, &self.groups)?;
+
+        Ok(self.check_outputs(
+            against,
+            keyframes
+                .iter()
+                .enumerate()
+                .map(|(index, keyframe)| (format!("keyframes[{}]", index), keyframe.command())),
+        ))
+    }
+
+    /// Checks every output of the named preset against the Channels of
+    /// `against`, as by [Pca9685::check_sequence] (e.g., `outputs[2]`).
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchPresetError] if no such preset is configured
+    pub fn check_preset(
+        &self,
+        name: &str,
+        against: Option<&Config>,
+    ) -> Pca9685Result<ConfigValidation> {
+        let preset = self.preset(name)?;
+
+        Ok(self.check_outputs(
+            against,
+            preset
+                .outputs
+                .iter()
+                .enumerate()
+                .map(|(index, output)| (format!("outputs[{}]", index), output.command())),
+        ))
+    }
+
+    fn check_outputs(
+        &self,
+        against: Option<&Config>,
+        commands: impl Iterator<Item = (String, ChannelCommand)>,
+    ) -> ConfigValidation {
+        let check = |channels: &HashMap<u8, ChannelProxy>| -> Vec<ConfigFinding> {
+            commands
+                .filter_map(|(path, command)| {
+                    let raw_channel = command.channel as u8;
+                    let ch = &channels[&raw_channel];
+                    let result = match ch.config().custom_limits {
+                        Some(_) => ch.check_command(&command),
+                        None => Err(Pca9685Error::InvalidConfiguration(format!(
+                            "channel {} is not configured",
+                            raw_channel
+                        ))),
+                    };
+
+                    result.err().map(|error| ConfigFinding {
+                        severity: FindingSeverity::Error,
+                        path,
+                        message: error.to_string(),
+                    })
+                })
+                .collect()
+        };
+
+        let findings = match against {
+            Some(config) => {
+                let clock_config = PcaClockConfig::from_prescale(
+                    config.oscillator_hz(),
+                    config.effective_prescale(),
+                );
+                let mut channels: HashMap<u8, ChannelProxy> = (0..16_u8)
+                    .map(|raw_channel| {
+                        let channel = Channel::try_from(raw_channel).unwrap();
+                        (raw_channel, ChannelProxy::new(channel, clock_config))
+                    })
+                    .collect();
+                // Channels whose configuration is invalid are left unconfigured
+                for channel_config in &config.channels {
+                    let ch = channels.get_mut(&(channel_config.channel as u8)).unwrap();
+                    if ch.configure(channel_config).is_err() {
+                        *ch = ChannelProxy::new(channel_config.channel, clock_config);
+                    }
+                }
+                check(&channels)
+            }
+            None => check(&self.channels.lock().unwrap()),
+        };
+
+        ConfigValidation {
+            valid: findings.is_empty(),
+            findings,
+        }
+    }
+
+    /// Adds a sequence (see [SequenceConfig]), replacing any of the same name.
+    ///
+    /// Error conditions:
+    /// * Any error documented by [Pca9685::validate_sequence]
+    pub fn configure_sequence(&self, sequence: &SequenceConfig) -> Pca9685Result<SequenceConfig> {
+        self.validate_sequence(sequence)?;
+
+        self.sequences
+            .lock()
+            .unwrap()
+            .insert(sequence.name.clone(), sequence.clone());
+        log::info!(
+            target: "pca9685",
+            "Configured sequence {} ({} keyframes, {} steps)",
+            sequence.name,
+            sequence.keyframes.len(),
+            sequence.steps.len()
+        );
+
+        Ok(sequence.clone())
+    }
+
+    /// Removes the named sequence, returning it.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchSequenceError] if no such sequence is configured
+    pub fn remove_sequence(&self, name: &str) -> Pca9685Result<SequenceConfig> {
+        match self.sequences.lock().unwrap().remove(name) {
+            Some(sequence) => {
+                log::info!(target: "pca9685", "Removed sequence {}", name);
+                Ok(sequence)
+            }
+            None => Err(Pca9685Error::NoSuchSequenceError(name.to_string())),
+        }
+    }
+
+    /// Plays the named sequence, resolved as by [Pca9685::resolve_sequence],
+    /// in place of any sequence already playing.  The worker applies each
+    /// keyframe once the playhead (see [PlaybackClock]) reaches it,
+    /// attributed to the client `sequence/<name>`.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if an external clock's `timeout_ms`
+    ///   is zero
+    /// * Any error documented by [Pca9685::resolve_sequence]
+    pub fn play_sequence(&self, name: &str, clock: PlaybackClock) -> Pca9685Result<PlaybackStatus> {
+        self.replay_sequence(name, clock, &Replay::default())
+    }
+
+    /// Plays the named sequence as by [Pca9685::play_sequence], adapted by
+    /// `replay` to this rig, e.g. to reuse a sequence authored on another
+    /// with different wiring.  Its keyframes are validated against the
+    /// Channels they are remapped onto.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if `replay.rate` is out of range, or
+    ///   `replay.channel_map` would play two Channels on the same one
+    /// * [Pca9685Error::NoSuchChannelError] if `replay.channel_map` maps onto
+    ///   an invalid Channel
+    /// * Any error documented by [Pca9685::play_sequence]
+    pub fn replay_sequence(
+        &self,
+        name: &str,
+        clock: PlaybackClock,
+        replay: &Replay,
+    ) -> Pca9685Result<PlaybackStatus> {
+        if clock == (PlaybackClock::External { timeout_ms: 0 }) {
+            return Err(Pca9685Error::InvalidCommand(
+                "External clock timeout_ms must be greater than 0.".to_string(),
+            ));
+        }
+        Self::validate_playback_rate(replay.rate)?;
+
+        let keyframes = self.resolve_remapped(name, &replay.channel_map)?;
+        let now = Instant::now();
+        let mut playback = sequence::Playback::new(name, keyframes, clock, now);
+        playback.set_rate(replay.rate, now);
+        let status = playback.status(now);
+
+        log::info!(
+            target: "pca9685",
+            "Playing sequence {} ({:?} clock, {:?})",
+            name,
+            clock,
+            replay
+        );
+        *self.playback.lock().unwrap() = Some(playback);
+
+        Ok(status)
+    }
+
+    /// Moves the playhead of the named sequence, which must be playing, to
+    /// `t_ms`; the worker then applies each Channel's latest keyframe at or
+    /// before it.  This is how an external clock (see [PlaybackClock])
+    /// reports its time, e.g. the position of an audio track.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchSequenceError] if no such sequence is configured
+    /// * [Pca9685Error::InvalidCommand] if the sequence isn't playing
+    pub fn seek_sequence(&self, name: &str, t_ms: u64) -> Pca9685Result<PlaybackStatus> {
+        self.with_playback(name, |playback, now| playback.seek(t_ms, now))
+    }
+
+    /// Sets the rate, within [PLAYBACK_RATES], at which the named sequence,
+    /// which must be playing, advances relative to real time (e.g., 0.5 to
+    /// rehearse at half speed).  The playhead continues from where it is.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchSequenceError] if no such sequence is configured
+    /// * [Pca9685Error::InvalidCommand] if `rate` is out of range, or the
+    ///   sequence isn't playing
+    pub fn set_playback_rate(&self, name: &str, rate: f64) -> Pca9685Result<PlaybackStatus> {
+        Self::validate_playback_rate(rate)?;
+
+        self.with_playback(name, |playback, now| playback.set_rate(rate, now))
+    }
+
+    fn validate_playback_rate(rate: f64) -> Pca9685Result<()> {
+        if !PLAYBACK_RATES.contains(&rate) {
+            return Err(Pca9685Error::InvalidCommand(format!(
+                "Playback rate {} is outside {:?}.",
+                rate, PLAYBACK_RATES
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Sets (or, given None, clears) the [LoopRegion] of the named sequence,
+    /// which must be playing.  Playback repeats the region until it is
+    /// cleared or playback is stopped.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchSequenceError] if no such sequence is configured
+    /// * [Pca9685Error::InvalidCommand] if the region is empty, or the
+    ///   sequence isn't playing
+    pub fn set_loop_region(
+        &self,
+        name: &str,
+        loop_region: Option<LoopRegion>,
+    ) -> Pca9685Result<PlaybackStatus> {
+        if let Some(region) = &loop_region {
+            region.validate()?;
+        }
+
+        self.with_playback(name, |playback, _| playback.set_loop_region(loop_region))
+    }
+
+    fn with_playback<F>(&self, name: &str, f: F) -> Pca9685Result<PlaybackStatus>
+    where
+        F: FnOnce(&mut sequence::Playback, Instant),
+    {
+        self.sequence(name)?;
+
+        match self.playback.lock().unwrap().as_mut() {
+            Some(playback) if playback.name() == name => {
+                let now = Instant::now();
+                f(playback, now);
+                Ok(playback.status(now))
+            }
+            _ => Err(Pca9685Error::InvalidCommand(format!(
+                "Sequence {} is not playing.",
+                name
+            ))),
+        }
+    }
+
+    /// Returns the sequence being played, if any (see
+    /// [Pca9685::play_sequence])
+    pub fn playback(&self) -> Option<PlaybackStatus> {
+        self.playback
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|playback| playback.status(Instant::now()))
+    }
+
+    /// Stops the sequence being played, if any, returning its status as of
+    /// stopping.  Channels retain their current output.
+    pub fn stop_sequence(&self) -> Option<PlaybackStatus> {
+        let playback = self.playback.lock().unwrap().take()?;
+        log::info!(target: "pca9685", "Stopped sequence {}", playback.name());
+
+        Some(playback.status(Instant::now()))
+    }
+
+    /// Starts recording a macro: until [Pca9685::stop_recording], the
+    /// outputs resulting from manual commands (e.g., [Pca9685::execute],
+    /// [Pca9685::apply], [Pca9685::teleop]) are captured, timed from now, as
+    /// keyframes of the sequence `name`.  Outputs driven by the worker (e.g.,
+    /// sequences, gaits) aren't recorded.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if already recording
+    /// * [Pca9685Error::InvalidConfiguration] if `name` isn't a valid
+    ///   sequence name
+    pub fn start_recording(&self, name: &str) -> Pca9685Result<RecordingStatus> {
+        SequenceConfig::validate_name(name)?;
+
+        let mut recorder = self.recorder.lock().unwrap();
+        if let Some(recording) = recorder.as_ref() {
+            return Err(Pca9685Error::InvalidCommand(format!(
+                "Already recording sequence {}.",
+                recording.status(Instant::now()).sequence
+            )));
+        }
+
+        let now = Instant::now();
+        let recording = Recorder::new(name, now);
+        let status = recording.status(now);
+        *recorder = Some(recording);
+        log::info!(target: "pca9685", "Recording sequence {}", name);
+
+        Ok(status)
+    }
+
+    /// Returns the macro being recorded, if any (see
+    /// [Pca9685::start_recording])
+    pub fn recording(&self) -> Option<RecordingStatus> {
+        self.recorder
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|recording| recording.status(Instant::now()))
+    }
+
+    /// Stops recording the macro, adding the recorded sequence (lasting until
+    /// now) as by [Pca9685::configure_sequence], replacing any of the same
+    /// name, and returning it.  It may then be played, or refined like any
+    /// other sequence.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if not recording
+    /// * Any error documented by [Pca9685::configure_sequence], e.g. if
+    ///   nothing was recorded
+    pub fn stop_recording(&self) -> Pca9685Result<SequenceConfig> {
+        let recording = self
+            .recorder
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or_else(|| Pca9685Error::InvalidCommand("Not recording.".to_string()))?;
+
+        self.configure_sequence(&recording.finish(Instant::now()))
+    }
+
+    /// Records the output resulting from a manual command, if recording a
+    /// macro (see [Pca9685::start_recording])
+    fn record(&self, result: Pca9685Result<ChannelConfig>) -> Pca9685Result<ChannelConfig> {
+        if let (Ok(config), Some(recording)) = (&result, self.recorder.lock().unwrap().as_mut()) {
+            recording.record(config, Instant::now());
+        }
+
+        result
+    }
+
+    /// Records the outputs resulting from manual commands, as by
+    /// [Pca9685::record]
+    fn record_all(
+        &self,
+        results: Pca9685Result<Vec<ChannelConfig>>,
+    ) -> Pca9685Result<Vec<ChannelConfig>> {
+        if let (Ok(configs), Some(recording)) = (&results, self.recorder.lock().unwrap().as_mut()) {
+            let now = Instant::now();
+            for config in configs {
+                recording.record(config, now);
+            }
+        }
+
+        results
+    }
+
+    /// Returns the configured presets, ordered by name
+    pub fn presets(&self) -> Vec<PresetConfig> {
+        let mut presets: Vec<PresetConfig> = self.presets.values().cloned().collect();
+        presets.sort_by(|a, b| a.name.cmp(&b.name));
+
+        presets
+    }
+
+    /// Applies the named preset (see [PresetConfig]) in place of any fade
+    /// already playing: each of its Channels moves linearly from its current
+    /// output to the preset's over `fade` (or, given zero, immediately).  It
+    /// plays as the sequence `preset/<name>` (see [Pca9685::playback]).
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchPresetError] if no such preset is configured
+    /// * [Pca9685Error::InvalidCommand] if `fade` exceeds
+    ///   [MAX_PRESET_FADE_MS], or a sequence (other than a fade) is playing
+    /// * Any error documented by [Pca9685::preview] for an output
+    pub fn apply_preset(&self, name: &str, fade: Duration) -> Pca9685Result<PlaybackStatus> {
+        let preset = self.preset(name)?;
+        let fade_ms = Self::validate_fade(fade)?;
+
+        let keyframes = {
+            let channels = self.channels.lock().unwrap();
+            let fades = preset
+                .outputs
+                .iter()
+                .map(|output| {
+                    let ch = &channels[&output.channel];
+                    Ok(preset::Fade {
+                        output,
+                        from: ch.config().current_count,
+                        to: ch.preview(&output.command())?.current_count.unwrap_or(0),
+                    })
+                })
+                .collect::<Pca9685Result<Vec<_>>>()?;
+
+            preset::keyframes(&fades, fade_ms)
+        };
+
+        let status = self.play_fade(&preset::sequence_name(name), keyframes)?;
+        log::info!(target: "pca9685", "Applying preset {} over {}ms", name, fade_ms);
+        Ok(status)
+    }
+
+    /// Returns the presets applied as scenes (see [crate::SceneConfig]),
+    /// highest priority (then latest applied) first
+    pub fn scenes(&self) -> Vec<SceneLayer> {
+        self.scenes.lock().unwrap().layers()
+    }
+
+    /// Layers the named preset as a scene at `priority` (replacing it if
+    /// already applied), then fades each of its Channels over `fade` to its
+    /// output resolved among the scenes (see [crate::SceneConfig]).  The fade
+    /// plays in place of any fade already playing, as the sequence
+    /// `scene/<name>` (see [Pca9685::playback]).
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchPresetError] if no such preset is configured
+    /// * [Pca9685Error::InvalidCommand] if `fade` exceeds
+    ///   [MAX_PRESET_FADE_MS], or a sequence (other than a fade) is playing
+    /// * Any error documented by [Pca9685::preview] for an output
+    pub fn apply_scene(
+        &self,
+        name: &str,
+        priority: i32,
+        fade: Duration,
+    ) -> Pca9685Result<Vec<SceneLayer>> {
+        let preset = self.preset(name)?;
+        let fade_ms = Self::validate_fade(fade)?;
+        Self::check_fade(&self.playback.lock().unwrap())?;
+
+        let outputs = {
+            let channels = self.channels.lock().unwrap();
+            preset
+                .outputs
+                .iter()
+                .map(|output| {
+                    let ch = &channels[&output.channel];
+                    Ok(SceneOutput {
+                        output: output.clone(),
+                        count: ch.preview(&output.command())?.current_count.unwrap_or(0),
+                    })
+                })
+                .collect::<Pca9685Result<Vec<_>>>()?
+        };
+        let layer = SceneLayer {
+            preset: name.to_string(),
+            priority,
+        };
+
+        let mut scenes = self.scenes.lock().unwrap();
+        scenes.apply(layer, outputs);
+        let channels: Vec<u8> = preset.outputs.iter().map(|output| output.channel).collect();
+        self.fade_scenes(&scenes, name, &channels, fade_ms)?;
+
+        log::info!(target: "pca9685", "Applied scene {} at priority {} over {}ms", name, priority, fade_ms);
+        Ok(scenes.layers())
+    }
+
+    /// Removes the named scene, then fades each of its Channels over `fade`
+    /// to its output resolved among the remaining scenes (see
+    /// [crate::SceneConfig]), or off if none includes it.  The fade plays as
+    /// described by [Pca9685::apply_scene].
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchPresetError] if no such preset is configured
+    /// * [Pca9685Error::InvalidCommand] if the preset isn't applied as a
+    ///   scene, `fade` exceeds [MAX_PRESET_FADE_MS], or a sequence (other
+    ///   than a fade) is playing
+    pub fn release_scene(&self, name: &str, fade: Duration) -> Pca9685Result<Vec<SceneLayer>> {
+        self.preset(name)?;
+        let fade_ms = Self::validate_fade(fade)?;
+        Self::check_fade(&self.playback.lock().unwrap())?;
+
+        let mut scenes = self.scenes.lock().unwrap();
+        let channels = scenes.release(name).ok_or_else(|| {
+            Pca9685Error::InvalidCommand(format!("Scene {} is not applied.", name))
+        })?;
+        self.fade_scenes(&scenes, name, &channels, fade_ms)?;
+
+        log::info!(target: "pca9685", "Released scene {} over {}ms", name, fade_ms);
+        Ok(scenes.layers())
+    }
+
+    /// Fades `channels` over `fade_ms` to their outputs resolved among
+    /// `scenes` (or off), as the sequence `scene/<name>`
+    fn fade_scenes(
+        &self,
+        scenes: &SceneStack,
+        name: &str,
+        channels: &[u8],
+        fade_ms: u64,
+    ) -> Pca9685Result<()> {
+        let outputs: Vec<(PresetOutput, u16)> = channels
+            .iter()
+            .map(|channel| match scenes.resolve(*channel) {
+                Some(resolved) => (resolved.output.clone(), resolved.count),
+                None => (
+                    PresetOutput {
+                        channel: *channel,
+                        command_type: CommandType::FullOff,
+                        value: None,
+                    },
+                    0,
+                ),
+            })
+            .collect();
+
+        let keyframes = {
+            let channels = self.channels.lock().unwrap();
+            let fades: Vec<preset::Fade> = outputs
+                .iter()
+                .map(|(output, to)| preset::Fade {
+                    output,
+                    from: channels[&output.channel].config().current_count,
+                    to: *to,
+                })
+                .collect();
+
+            preset::keyframes(&fades, fade_ms)
+        };
+        self.play_fade(&format!("scene/{}", name), keyframes)
+            .map(|_| ())
+    }
+
+    fn preset(&self, name: &str) -> Pca9685Result<&PresetConfig> {
+        self.presets
+            .get(name)
+            .ok_or_else(|| Pca9685Error::NoSuchPresetError(name.to_string()))
+    }
+
+    fn validate_fade(fade: Duration) -> Pca9685Result<u64> {
+        let fade_ms = fade.as_millis() as u64;
+        if fade_ms > MAX_PRESET_FADE_MS {
+            return Err(Pca9685Error::InvalidCommand(format!(
+                "Fade must not exceed {}ms.",
+                MAX_PRESET_FADE_MS
+            )));
+        }
+
+        Ok(fade_ms)
+    }
+
+    /// Plays a fade's `keyframes` as the sequence `name`, in place of any
+    /// fade already playing
+    fn play_fade(&self, name: &str, keyframes: Vec<Keyframe>) -> Pca9685Result<PlaybackStatus> {
+        let mut active = self.playback.lock().unwrap();
+        Self::check_fade(&active)?;
+
+        let now = Instant::now();
+        let playback = sequence::Playback::new(name, keyframes, PlaybackClock::Internal, now);
+        let status = playback.status(now);
+        *active = Some(playback);
+
+        Ok(status)
+    }
+
+    /// Fails with [Pca9685Error::InvalidCommand] if a sequence other than a
+    /// fade is playing, which a fade would otherwise cut short
+    fn check_fade(active: &Option<sequence::Playback>) -> Pca9685Result<()> {
+        match active {
+            Some(playback) if !playback.finished() && !preset::is_fade(playback.name()) => {
+                Err(Pca9685Error::InvalidCommand(format!(
+                    "Sequence {} is playing; stop it before fading.",
+                    playback.name()
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the configured gaits, ordered by name
+    pub fn gaits(&self) -> Vec<GaitConfig> {
+        let mut gaits: Vec<GaitConfig> = self.gaits.values().cloned().collect();
+        gaits.sort_by(|a, b| a.name.cmp(&b.name));
+
+        gaits
+    }
+
+    /// Starts walking the named gait (see [GaitConfig]), in place of any
+    /// sequence already playing, at `speed` (within [PLAYBACK_RATES]) times
+    /// its configured pace.  It plays as the looping sequence `gait/<name>`
+    /// (see [Pca9685::playback]) until stopped.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchGaitError] if no such gait is configured
+    /// * [Pca9685Error::InvalidCommand] if `speed` is out of range
+    pub fn start_gait(&self, name: &str, speed: f64) -> Pca9685Result<PlaybackStatus> {
+        let gait = self.gait(name)?;
+        Self::validate_gait_speed(speed)?;
+
+        let keyframes = gait::keyframes(gait, &self.groups)?;
+        let now = Instant::now();
+        let mut playback = sequence::Playback::new(
+            &gait::sequence_name(name),
+            keyframes,
+            PlaybackClock::Internal,
+            now,
+        );
+        playback.set_loop_region(Some(LoopRegion {
+            start_ms: 0,
+            end_ms: gait.period_ms,
+        }));
+        playback.set_rate(speed, now);
+        let status = playback.status(now);
+
+        log::info!(target: "pca9685", "Walking gait {} at {}x", name, speed);
+        *self.playback.lock().unwrap() = Some(playback);
+
+        Ok(status)
+    }
+
+    /// Changes the speed (see [Pca9685::start_gait]) of the named gait, which
+    /// must be walking, continuing from where it is in its cycle.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchGaitError] if no such gait is configured
+    /// * [Pca9685Error::InvalidCommand] if `speed` is out of range, or the
+    ///   gait isn't walking
+    pub fn set_gait_speed(&self, name: &str, speed: f64) -> Pca9685Result<PlaybackStatus> {
+        Self::validate_gait_speed(speed)?;
+
+        self.with_gait(name, |playback, now| {
+            playback.set_rate(speed, now);
+            playback.status(now)
+        })
+    }
+
+    /// Stops the named gait, which must be walking, returning its status as
+    /// of stopping.  Channels retain their current output.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchGaitError] if no such gait is configured
+    /// * [Pca9685Error::InvalidCommand] if the gait isn't walking
+    pub fn stop_gait(&self, name: &str) -> Pca9685Result<PlaybackStatus> {
+        let status = self.with_gait(name, |playback, now| playback.status(now))?;
+        self.stop_sequence();
+
+        Ok(status)
+    }
+
+    fn gait(&self, name: &str) -> Pca9685Result<&GaitConfig> {
+        self.gaits
+            .get(name)
+            .ok_or_else(|| Pca9685Error::NoSuchGaitError(name.to_string()))
+    }
+
+    fn validate_gait_speed(speed: f64) -> Pca9685Result<()> {
+        if !PLAYBACK_RATES.contains(&speed) {
+            return Err(Pca9685Error::InvalidCommand(format!(
+                "Gait speed {} is outside {:?}.",
+                speed, PLAYBACK_RATES
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn with_gait<F>(&self, name: &str, f: F) -> Pca9685Result<PlaybackStatus>
+    where
+        F: FnOnce(&mut sequence::Playback, Instant) -> PlaybackStatus,
+    {
+        self.gait(name)?;
+
+        match self.playback.lock().unwrap().as_mut() {
+            Some(playback) if playback.name() == gait::sequence_name(name) => {
+                Ok(f(playback, Instant::now()))
+            }
+            _ => Err(Pca9685Error::InvalidCommand(format!(
+                "Gait {} is not walking.",
+                name
+            ))),
+        }
+    }
+
+    /// Returns the state of each configured linear axis (see
+    /// [crate::LinearAxisConfig]), ordered by name
+    pub fn linear_axes(&self) -> Vec<LinearAxisStatus> {
+        let mut axes: Vec<LinearAxisStatus> = self
+            .linear_axes
+            .lock()
+            .unwrap()
+            .values()
+            .map(LinearAxis::status)
+            .collect();
+        axes.sort_by(|a, b| a.name.cmp(&b.name));
+
+        axes
+    }
+
+    /// Returns the state of the named linear axis.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchLinearAxisError] if no such axis is configured
+    pub fn linear_axis(&self, name: &str) -> Pca9685Result<LinearAxisStatus> {
+        self.with_linear_axis(name, |_| Ok(()))
+    }
+
+    /// Starts homing the named linear axis: it travels towards `min_mm` at
+    /// `speed_mm_s` (by default, its configured speed) until its home switch
+    /// closes, establishing its position.  Homing stops if the switch isn't
+    /// found within its travel.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchLinearAxisError] if no such axis is configured
+    /// * [Pca9685Error::InvalidCommand] if `speed_mm_s` exceeds the axis'
+    ///   `max_speed_mm_s`
+    pub fn home_linear_axis(
+        &self,
+        name: &str,
+        speed_mm_s: Option<f64>,
+    ) -> Pca9685Result<LinearAxisStatus> {
+        self.with_linear_axis(name, |axis| axis.home(speed_mm_s))
+    }
+
+    /// Starts moving the named linear axis, which must have been homed, to
+    /// `position_mm` at `speed_mm_s` (by default, its configured speed).
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchLinearAxisError] if no such axis is configured
+    /// * [Pca9685Error::InvalidCommand] if the axis isn't homed,
+    ///   `position_mm` is outside its travel limits, or `speed_mm_s` exceeds
+    ///   its `max_speed_mm_s`
+    pub fn move_linear_axis(
+        &self,
+        name: &str,
+        position_mm: f64,
+        speed_mm_s: Option<f64>,
+    ) -> Pca9685Result<LinearAxisStatus> {
+        self.with_linear_axis(name, |axis| axis.move_to(position_mm, speed_mm_s))
+    }
+
+    /// Stops the named linear axis where it is (abandoning any homing).
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchLinearAxisError] if no such axis is configured
+    pub fn stop_linear_axis(&self, name: &str) -> Pca9685Result<LinearAxisStatus> {
+        self.with_linear_axis(name, |axis| {
+            axis.stop();
+            Ok(())
+        })
+    }
+
+    fn with_linear_axis<F>(&self, name: &str, f: F) -> Pca9685Result<LinearAxisStatus>
+    where
+        F: FnOnce(&mut LinearAxis) -> Pca9685Result<()>,
+    {
+        match self.linear_axes.lock().unwrap().get_mut(name) {
+            Some(axis) => {
+                f(axis)?;
+                Ok(axis.status())
+            }
+            None => Err(Pca9685Error::NoSuchLinearAxisError(name.to_string())),
+        }
+    }
+
+    /// Returns the current value of each input of the named mixer.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchMixerError] if no such mixer is configured
+    pub fn mixer_inputs(&self, name: &str) -> Pca9685Result<HashMap<String, f64>> {
+        match self.mixers.lock().unwrap().get(name) {
+            Some(mixer) => Ok(mixer.inputs()),
+            None => Err(Pca9685Error::NoSuchMixerError(name.to_string())),
+        }
+    }
+
+    /// Updates inputs of the named mixer (see [crate::MixerConfig]) and positions
+    /// each of its output Channels accordingly, returning the resulting
+    /// [ChannelConfig]s.  Inputs absent from `inputs` retain their previous
+    /// value.  The outputs are applied atomically (see [Pca9685::apply]) and
+    /// pass through each Channel's filters and limits like any other command.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchMixerError] if no such mixer is configured
+    /// * [Pca9685Error::InvalidCommand] if an input is unknown to the mixer or
+    ///   outside [-1.0, 1.0]
+    /// * Any error documented by [Pca9685::apply]
+    pub fn set_mixer_inputs(
+        &self,
+        name: &str,
+        inputs: &HashMap<String, f64>,
+    ) -> Pca9685Result<Vec<ChannelConfig>> {
+        let mut mixers = self.mixers.lock().unwrap();
+        let mixer = match mixers.get_mut(name) {
+            Some(mixer) => mixer,
+            None => return Err(Pca9685Error::NoSuchMixerError(name.to_string())),
+        };
+
+        let staged = mixer.stage(inputs)?;
+        let configs = self.apply(&mixer.commands(&staged), true)?;
+        mixer.commit(staged);
+
+        Ok(configs)
+    }
+
+    /// Returns the current value of each virtual channel (see
+    /// [crate::Config::virtual_channels]).
+    pub fn virtual_channels(&self) -> HashMap<String, f64> {
+        self.virtual_channels.lock().unwrap().values()
+    }
+
+    /// Updates virtual channels and positions each Channel derived from them
+    /// (see [crate::DerivedChannelConfig]) accordingly, returning the
+    /// resulting [ChannelConfig]s.  Virtual channels absent from `values`
+    /// retain their previous value.  The outputs are applied atomically (see
+    /// [Pca9685::apply]) and pass through each Channel's filters and limits
+    /// like any other command.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if a virtual channel is unknown or a
+    ///   value is outside [-1.0, 1.0]
+    /// * Any error documented by [Pca9685::apply]
+    pub fn set_virtual_channels(
+        &self,
+        values: &HashMap<String, f64>,
+    ) -> Pca9685Result<Vec<ChannelConfig>> {
+        let mut virtual_channels = self.virtual_channels.lock().unwrap();
+
+        let staged = virtual_channels.stage(values)?;
+        let configs = self.apply(&virtual_channels.commands(&staged, values), true)?;
+        virtual_channels.commit(staged);
+
+        Ok(configs)
+    }
+
+    /// Feeds the watchdog (see [crate::WatchdogConfig]) on behalf of
+    /// `client_id` without changing any output, e.g. for a client that holds a
+    /// position for longer than the watchdog's timeout.  Every command feeds
+    /// the watchdog likewise.
+    pub fn heartbeat(&self, client_id: Option<&str>) {
+        self.feed_watchdog(client_id);
+    }
+
+    /// Returns the watchdog's timeout, if a watchdog is configured
+    pub fn watchdog_timeout(&self) -> Option<Duration> {
+        self.watchdog
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(Watchdog::timeout)
+    }
+
+    /// Feeds the watchdog, and ends idling (see [crate::IdleConfig])
+    fn feed_watchdog(&self, client_id: Option<&str>) {
+        let now = Instant::now();
+        if let Some(watchdog) = self.watchdog.lock().unwrap().as_mut() {
+            watchdog.feed(client_id, now);
+        }
+        if let Some(idle) = self.idle.lock().unwrap().as_mut() {
+            idle.feed(now);
+        }
+    }
+
+    /// Returns true while idle behaviors are playing (see
+    /// [crate::IdleConfig])
+    pub fn idling(&self) -> bool {
+        let now = Instant::now();
+        let idling =
+            matches!(self.idle.lock().unwrap().as_ref(), Some(idle) if idle.is_idling(now));
+
+        idling && self.playback().is_none()
+    }
+
+    /// Returns the gimbal's status, if configured (see [GimbalConfig])
+    #[cfg(feature = "gimbal")]
+    pub fn gimbal(&self) -> Option<crate::GimbalStatus> {
+        self.gimbal.lock().unwrap().as_ref().map(Gimbal::status)
+    }
+
+    /// Starts or stops stabilizing the camera (see [GimbalConfig]).  Once
+    /// stopped, the gimbal's Channels hold their last output.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if no gimbal is configured
+    #[cfg(feature = "gimbal")]
+    pub fn set_gimbal_enabled(&self, enabled: bool) -> Pca9685Result<crate::GimbalStatus> {
+        match self.gimbal.lock().unwrap().as_mut() {
+            Some(gimbal) => {
+                gimbal.set_enabled(enabled);
+                log::info!(target: "pca9685", "Gimbal {}", if enabled { "enabled" } else { "disabled" });
+                Ok(gimbal.status())
+            }
+            None => Err(Pca9685Error::InvalidCommand(
+                "No gimbal is configured (see Config.gimbal).".to_string(),
+            )),
+        }
+    }
+
+    /// Rehearses loss of control: drives every Channel with a configured
+    /// failsafe output (see [crate::FailsafeOutput]) to it for `duration`,
+    /// then restores each Channel's prior output (unless commanded in the
+    /// meantime), returning the [ChannelConfig]s as of the failsafe.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if `duration` is zero or no Channel
+    ///   has a failsafe output configured
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn rehearse_failsafe(&self, duration: Duration) -> Pca9685Result<Vec<ChannelConfig>> {
+        if duration.is_zero() {
+            return Err(Pca9685Error::InvalidCommand(
+                "Failsafe rehearsal duration must be greater than 0.".to_string(),
+            ));
+        }
+
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+        let mut channels = self.channels.lock().unwrap();
+        let restore_at = Instant::now() + duration;
+
+        let mut motions = self.motions.lock().unwrap();
+        let mut configs = Vec::new();
+        for raw_channel in 0..16 {
+            if let Some(ch) = channels.get_mut(&raw_channel) {
+                if let Some(config) = ch.apply_failsafe(Some(restore_at), &mut locked_pca_impl)? {
+                    motions.stop(config.channel);
+                    configs.push(config);
+                }
+            }
+        }
+
+        if configs.is_empty() {
+            return Err(Pca9685Error::InvalidCommand(
+                "No failsafe outputs are configured.".to_string(),
+            ));
+        }
+        log::warn!(target: "pca9685", "Rehearsing failsafe for {:?}", duration);
+
+        Ok(configs)
+    }
+
+    /// Reads the raw feedback of `channel`, or None if it has none (see
+    /// [crate::FeedbackConfig])
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if the feedback cannot be read
+    pub fn feedback(&self, channel: Channel) -> Pca9685Result<Option<f64>> {
+        let raw_channel = channel as u8;
+
+        match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => ch.raw_feedback(),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        }
+    }
+
+    /// Commands `channel` through a [StepTest], returning a [StepSample] every
+    /// `sample_interval_us` (along with the Channel's raw feedback, if it has
+    /// a [crate::FeedbackConfig]) from the start of settling until
+    /// `duration_ms` after the step.  Blocks for the duration of the test;
+    /// the Channel is left at `to_count`.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if `test` is invalid
+    /// * [Pca9685Error::CustomLimitsError] if either count is not within the
+    ///   channel's configured limits
+    /// * [Pca9685Error::InvalidConfiguration] if the feedback cannot be read
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn step_test(&self, channel: Channel, test: &StepTest) -> Pca9685Result<Vec<StepSample>> {
+        test.validate()?;
+        self.preview(
+            channel,
+            &ChannelCommand {
+                channel,
+                command_type: CommandType::PulseCount,
+                value: Some(test.to_count as f64),
+                basis: None,
+                client_id: None,
+            },
+        )?;
+
+        self.set_pwm_count(channel, test.from_count)?;
+        log::info!(target: &self.log_target(channel)?, "Step test {:?}", test);
+
+        let started = Instant::now();
+        let stepped_at = started + Duration::from_millis(test.settle_ms);
+        let finished_at = stepped_at + Duration::from_millis(test.duration_ms);
+        let sample_interval = Duration::from_micros(test.sample_interval_us);
+
+        let mut samples = Vec::new();
+        let mut count = test.from_count;
+        let mut deadline = started;
+        while deadline <= finished_at {
+            // Sleep until the scheduled time (rather than for a fixed duration)
+            // so that timing errors don't accumulate
+            thread::sleep(deadline.saturating_duration_since(Instant::now()));
+            if count != test.to_count && deadline >= stepped_at {
+                count = test.to_count;
+                self.set_pwm_count(channel, count)?;
+            }
+
+            let now = Instant::now();
+            let t_ms = if now >= stepped_at {
+                (now - stepped_at).as_secs_f64() * 1000.0
+            } else {
+                -(stepped_at - now).as_secs_f64() * 1000.0
+            };
+            samples.push(StepSample {
+                t_ms,
+                count,
+                feedback: self.feedback(channel)?,
+            });
+
+            deadline += sample_interval;
+        }
+
+        Ok(samples)
+    }
+
+    /// Measures `cycles` cycles of `channel`'s current output on `input`, a
+    /// GPIO input the output is looped back to (see
+    /// [crate::GpioPin::open_input]),
+    /// reporting the pulse widths and periods seen against those expected,
+    /// e.g. to quantify the error of the PCA9685's oscillator and glitches
+    /// induced by the I2C bus.  Blocks (polling `input`) until the cycles have
+    /// been seen, or twice as long as they should take.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if the Channel has no pulses to
+    ///   measure (it is full on or off), or none were seen on `input`
+    /// * [Pca9685Error::InvalidConfiguration] if `input` cannot be read
+    pub fn measure_loopback(
+        &self,
+        channel: Channel,
+        input: &mut dyn GpioInput,
+        cycles: usize,
+    ) -> Pca9685Result<LoopbackReport> {
+        let config = self.config(channel)?;
+        let count = match config.current_count {
+            Some(count) if count < PCA_PWM_RESOLUTION => config.trimmed(count),
+            _ => {
+                return Err(Pca9685Error::InvalidCommand(format!(
+                    "Channel {} has no pulses to measure (set a pulse width first).",
+                    channel as u8
+                )))
+            }
+        };
+
+        log::info!(target: &self.log_target(channel)?, "Measuring {} cycles on its loopback input", cycles);
+        loopback::measure(
+            channel,
+            input,
+            cycles,
+            count as f64 * self.single_count_duration_ms(),
+            self.max_pw_ms(),
+        )
+    }
+
+    /// Selects the low or high rate (see [crate::DualRate]) of the selected
+    /// Channels, returning the [ChannelConfig]s of those with a dual rate
+    /// configured (others are unaffected).  The rate applies from each
+    /// Channel's next command.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchGroupError] if `selector` names an unknown group
+    pub fn select_rate(
+        &self,
+        selector: &ChannelSelector,
+        rate: Rate,
+    ) -> Pca9685Result<Vec<ChannelConfig>> {
+        let selected = self.select(selector)?;
+        let mut channels = self.channels.lock().unwrap();
+
+        let mut configs = Vec::new();
+        for channel in selected {
+            let raw_channel = channel as u8;
+            match channels.get_mut(&raw_channel) {
+                Some(ch) => {
+                    if ch.select_rate(rate) {
+                        configs.push(ch.config());
+                    }
+                }
+                None => return Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+            }
+        }
+        drop(channels);
+
+        if *selector == ChannelSelector::All {
+            self.teleop.lock().unwrap().set_rate(rate);
+        }
+
+        Ok(configs)
+    }
+
+    /// Returns the configured teleop profiles (see [crate::TeleopConfig])
+    pub fn teleop_profiles(&self) -> Vec<TeleopProfile> {
+        self.teleop.lock().unwrap().profiles().to_vec()
+    }
+
+    /// Returns the name of the active teleop profile, if any
+    pub fn teleop_profile(&self) -> Option<String> {
+        self.teleop
+            .lock()
+            .unwrap()
+            .active()
+            .map(|profile| profile.name.clone())
+    }
+
+    /// Activates the named teleop profile; subsequent calls to
+    /// [Pca9685::teleop] map axes per this profile.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchProfileError] if no such profile is configured
+    pub fn select_teleop_profile(&self, name: &str) -> Pca9685Result<()> {
+        if !self.teleop.lock().unwrap().select(name) {
+            return Err(Pca9685Error::NoSuchProfileError(name.to_string()));
+        }
+        log::info!(target: "pca9685", "Teleop profile {} selected", name);
+
+        Ok(())
+    }
+
+    /// Applies a gamepad snapshot: first selects the profile whose button
+    /// combo (if any) has just been pressed, and toggles the rate of every
+    /// Channel (see [Pca9685::select_rate]) if the active profile's
+    /// `rate_button` has just been pressed.  Then maps each axis of the
+    /// active profile onto its mixer input (see [Pca9685::set_mixer_inputs]),
+    /// returning the resulting [ChannelConfig]s.
+    ///
+    /// Error conditions:
+    /// * Any error documented by [Pca9685::set_mixer_inputs]
+    pub fn teleop(&self, state: &GamepadState) -> Pca9685Result<Vec<ChannelConfig>> {
+        let (inputs, toggled_rate) = {
+            let mut teleop = self.teleop.lock().unwrap();
+            teleop.select_by_buttons(state);
+            (teleop.map(state), teleop.toggle_rate_by_button(state))
+        };
+
+        if let Some(rate) = toggled_rate {
+            self.select_rate(&ChannelSelector::All, rate)?;
+        }
+
+        let mut names: Vec<&String> = inputs.keys().collect();
+        names.sort();
+
+        let mut configs = Vec::new();
+        for name in names {
+            configs.extend(self.set_mixer_inputs(name, &inputs[name])?);
+        }
+
+        Ok(configs)
+    }
+
+    /// Sets the selected Channels to off (no output), de-energizing typical
+    /// analog servos so an articulated figure can be posed by hand, returning
+    /// the resulting [ChannelConfig]s.  Unlike an emergency stop, the next
+    /// command to a Channel re-engages it as usual.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchGroupError] if `selector` names an unknown group
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn relax(&self, selector: &ChannelSelector) -> Pca9685Result<Vec<ChannelConfig>> {
+        self.feed_watchdog(None);
+
+        let selected = self.select(selector)?;
+        self.cancel_moves(selected.iter().copied());
+
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+        let mut channels = self.channels.lock().unwrap();
+
+        log::info!(target: "pca9685", "Relaxing {:?}", selector);
+
+        let results = selected
+            .iter()
+            .map(|channel| {
+                let raw_channel = *channel as u8;
+                match channels.get_mut(&raw_channel) {
+                    Some(ch) => ch.full_off(&mut locked_pca_impl),
+                    None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+                }
+            })
+            .collect();
+
+        self.record_all(results)
+    }
+
+    /// Captures the runtime state of the [Pca9685] (see [Snapshot]), e.g. to
+    /// checkpoint a demo setup before experimenting with it.
+    pub fn snapshot(&self) -> Snapshot {
+        let channels = self.channels.lock().unwrap();
+        let mut channel_configs: Vec<ChannelConfig> =
+            channels.values().map(ChannelProxy::config).collect();
+        channel_configs.sort_unstable_by_key(|config| config.channel as u8);
+
+        Snapshot {
+            channels: channel_configs,
+            mixer_inputs: self
+                .mixers
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(name, mixer)| (name.clone(), mixer.inputs()))
+                .collect(),
+            teleop_profile: self.teleop_profile(),
+        }
+    }
+
+    /// Reapplies a [Snapshot] (see [Pca9685::snapshot]), returning the
+    /// resulting [ChannelConfig]s.  Each Channel is reconfigured and its
+    /// output written exactly (bypassing filters); mixer inputs are restored
+    /// without moving their outputs.  The whole snapshot is validated before
+    /// anything is applied.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if a Channel appears more than once
+    /// * [Pca9685Error::NoSuchMixerError] or [Pca9685Error::NoSuchProfileError]
+    ///   if the snapshot names a mixer or teleop profile that isn't configured
+    /// * Any error documented by [Pca9685::configure_channel], or
+    ///   [Pca9685Error::CustomLimitsError] if an output is outside its limits
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn restore(&self, snapshot: &Snapshot) -> Pca9685Result<Vec<ChannelConfig>> {
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+        let mut channels = self.channels.lock().unwrap();
+        let mut mixers = self.mixers.lock().unwrap();
+        let mut teleop = self.teleop.lock().unwrap();
+
+        let staged = Self::check_snapshot(snapshot, &channels, &mixers, &teleop)?;
+
+        log::info!(target: "pca9685", "Restoring snapshot of {} channel(s)", snapshot.channels.len());
+        let mut motions = self.motions.lock().unwrap();
+        for config in &snapshot.channels {
+            motions.stop(config.channel);
+        }
+        drop(motions);
+
+        let configs = snapshot
+            .channels
+            .iter()
+            .map(|config| {
+                channels
+                    .get_mut(&(config.channel as u8))
+                    .unwrap()
+                    .restore(config, &mut locked_pca_impl)
+            })
+            .collect::<Pca9685Result<Vec<ChannelConfig>>>()?;
+
+        for (name, inputs) in staged {
+            mixers.get_mut(name).unwrap().commit(inputs);
+        }
+        if let Some(name) = &snapshot.teleop_profile {
+            teleop.select(name);
+        }
+
+        Ok(configs)
+    }
+
+    /// Validates `snapshot` as [Pca9685::restore] would, returning the inputs
+    /// staged for each of its mixers
+    fn check_snapshot<'a>(
+        snapshot: &'a Snapshot,
+        channels: &HashMap<u8, ChannelProxy>,
+        mixers: &HashMap<String, mixer::Mixer>,
+        teleop: &Teleop,
+    ) -> Pca9685Result<Vec<(&'a String, Vec<f64>)>> {
+        let mut restored = HashSet::new();
+        for config in &snapshot.channels {
+            let raw_channel = config.channel as u8;
+            if !restored.insert(raw_channel) {
+                return Err(Pca9685Error::InvalidCommand(format!(
+                    "Channel {} appears more than once.",
+                    raw_channel
+                )));
+            }
+            match channels.get(&raw_channel) {
+                Some(ch) => ch.check_restore(config)?,
+                None => return Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+            }
+        }
+
+        let mut staged = Vec::new();
+        for (name, inputs) in &snapshot.mixer_inputs {
+            match mixers.get(name) {
+                Some(mixer) => staged.push((name, mixer.stage(inputs)?)),
+                None => return Err(Pca9685Error::NoSuchMixerError(name.to_string())),
+            }
+        }
+
+        if let Some(name) = &snapshot.teleop_profile {
+            if !teleop
+                .profiles()
+                .iter()
+                .any(|profile| &profile.name == name)
+            {
+                return Err(Pca9685Error::NoSuchProfileError(name.to_string()));
+            }
+        }
+
+        Ok(staged)
+    }
+
+    /// Captures everything needed to recreate this [Pca9685] on fresh
+    /// hardware (see [Backup]), given the `config` it was created from.
+    pub fn backup(&self, config: &Config) -> Backup {
+        Backup {
+            config: self.running_config(config),
+            source: None,
+            sequences: self.sequences(),
+            snapshot: self.snapshot(),
+        }
+    }
+
+    /// Applies a [Backup] (see [Pca9685::backup]), returning the resulting
+    /// [ChannelConfig]s: its state is restored as by [Pca9685::restore] (the
+    /// inputs of mixers, and the teleop profile, only where also configured
+    /// here) and its sequences replace those configured.  Its configuration
+    /// is only validated; it takes effect once the [Pca9685] is recreated
+    /// from it.  The whole backup is validated before anything is applied.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if the configuration fails
+    ///   [Pca9685::validate_config]
+    /// * Any error documented by [Pca9685::configure_sequence] if a sequence
+    ///   is invalid against the backup's configuration
+    /// * Any error documented by [Pca9685::restore]
+    pub fn restore_backup(&self, backup: &Backup) -> Pca9685Result<Vec<ChannelConfig>> {
+        let (staged, snapshot) = self.stage_backup(backup)?;
+
+        let configs = self.restore(&snapshot)?;
+
+        *self.sequences.lock().unwrap() = staged.sequences.lock().unwrap().clone();
+        log::info!(
+            target: "pca9685",
+            "Restored backup of {} channel(s) and {} sequence(s)",
+            configs.len(),
+            backup.sequences.len()
+        );
+
+        Ok(configs)
+    }
+
+    /// Validates `backup` in full, as [Pca9685::restore_backup] would, without
+    /// applying any of it, e.g. before also persisting it.
+    ///
+    /// Error conditions:
+    /// * Any error documented by [Pca9685::restore_backup], other than driver
+    ///   errors
+    pub fn check_backup(&self, backup: &Backup) -> Pca9685Result<()> {
+        let (_, snapshot) = self.stage_backup(backup)?;
+
+        let channels = self.channels.lock().unwrap();
+        let mixers = self.mixers.lock().unwrap();
+        let teleop = self.teleop.lock().unwrap();
+        Self::check_snapshot(&snapshot, &channels, &mixers, &teleop).map(|_| ())
+    }
+
+    /// Validates the configuration and sequences of `backup`, returning a
+    /// null [Pca9685] with the sequences configured, and the snapshot to
+    /// restore (less mixers and teleop profiles not configured here)
+    fn stage_backup(&self, backup: &Backup) -> Pca9685Result<(Pca9685, Snapshot)> {
+        let errors: Vec<String> = Pca9685::validate_config(&backup.config)
+            .findings
+            .into_iter()
+            .filter(|finding| finding.severity == FindingSeverity::Error)
+            .map(|finding| format!("{}: {}", finding.path, finding.message))
+            .collect();
+        if !errors.is_empty() {
+            return Err(Pca9685Error::InvalidConfiguration(errors.join("; ")));
+        }
+
+        // Sequences may include one another, so retry those that fail until
+        // no more can be configured
+        let staged = Pca9685::null(&backup.config);
+        let mut pending: Vec<&SequenceConfig> = backup.sequences.iter().collect();
+        loop {
+            let count = pending.len();
+            pending.retain(|sequence| staged.configure_sequence(sequence).is_err());
+            if pending.is_empty() || pending.len() == count {
+                break;
+            }
+        }
+        if let Some(sequence) = pending.first() {
+            staged.configure_sequence(sequence)?;
+        }
+
+        let mixers: HashSet<String> = self.mixers().into_iter().map(|mixer| mixer.name).collect();
+        let profiles: HashSet<String> = self
+            .teleop_profiles()
+            .into_iter()
+            .map(|profile| profile.name)
+            .collect();
+        let snapshot = Snapshot {
+            channels: backup.snapshot.channels.clone(),
+            mixer_inputs: backup
+                .snapshot
+                .mixer_inputs
+                .iter()
+                .filter(|(name, _)| mixers.contains(*name))
+                .map(|(name, inputs)| (name.clone(), inputs.clone()))
+                .collect(),
+            teleop_profile: backup
+                .snapshot
+                .teleop_profile
+                .clone()
+                .filter(|name| profiles.contains(name)),
+        };
+
+        Ok((staged, snapshot))
+    }
+
+    /// Reports how the runtime configuration differs from `config` (e.g., the
+    /// configuration file the [Pca9685] was created from): a differing
+    /// prescale or disabled output, Channels whose settings (limits,
+    /// calibration, ...) have been changed, or which have been configured, at
+    /// runtime, and mixers that have been reconfigured.  No differences means
+    /// that recreating the [Pca9685] from `config` would not change its
+    /// behavior.  Outputs and owners are not compared.
+    ///
+    /// Error conditions:
+    /// * Any error documented by [Config::validate_clock] or
+    ///   [Pca9685::configure_channel] if `config` is invalid
+    pub fn diff_config(&self, config: &Config) -> Pca9685Result<Vec<ConfigDifference>> {
+        config.validate_clock()?;
+
+        let mut differences = Vec::new();
+        let prescale = self.prescale();
+        if config.effective_prescale() != prescale {
+            differences.push(difference(
+                "prescale".to_string(),
+                config.effective_prescale(),
+                prescale,
+            ));
+        }
+        let disabled_output = self.disabled_output();
+        if config.disabled_output != disabled_output {
+            differences.push(difference(
+                "disabled_output".to_string(),
+                config.disabled_output,
+                disabled_output,
+            ));
+        }
+
+        let clock_config =
+            PcaClockConfig::from_prescale(config.oscillator_hz(), config.effective_prescale());
+        let channels = self.channels.lock().unwrap();
+        let mut raw_channels: Vec<&u8> = channels.keys().collect();
+        raw_channels.sort_unstable();
+        for raw_channel in raw_channels {
+            let runtime = channels[raw_channel].config();
+            let mut file = ChannelProxy::new(runtime.channel, clock_config);
+            for channel_config in config
+                .channels
+                .iter()
+                .filter(|channel_config| channel_config.channel == runtime.channel)
+            {
+                file.configure(channel_config)?;
+            }
+
+            differences.extend(diff_channel(
+                &format!("channels[{}]", raw_channel),
+                &file.config(),
+                &runtime,
+            ));
+        }
+        drop(channels);
+
+        let runtime_mixers = self.mixers();
+        let mut names: Vec<&String> = config
+            .mixers
+            .iter()
+            .chain(runtime_mixers.iter())
+            .map(|mixer| &mixer.name)
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        for name in names {
+            let file = config.mixers.iter().find(|mixer| &mixer.name == name);
+            let runtime = runtime_mixers.iter().find(|mixer| &mixer.name == name);
+            if file != runtime {
+                differences.push(difference(format!("mixers[{}]", name), file, runtime));
+            }
+        }
+
+        Ok(differences)
+    }
+
+    /// Returns `config` (e.g., as loaded at startup) updated with the runtime
+    /// configuration: the configured Channels (without their outputs) and
+    /// the mixers
+    pub fn running_config(&self, config: &Config) -> Config {
+        let mut channel_configs: Vec<ChannelConfig> = self
+            .channels
+            .lock()
+            .unwrap()
+            .values()
+            .map(ChannelProxy::config)
+            .filter(|channel_config| channel_config.custom_limits.is_some())
+            .map(|channel_config| ChannelConfig {
+                current_count: None,
+                owner: None,
+                display_value: None,
+                custom_limits: channel_config.given_limits(),
+                ..channel_config
+            })
+            .collect();
+        channel_configs.sort_unstable_by_key(|channel_config| channel_config.channel as u8);
+
+        Config {
+            channels: channel_configs,
+            mixers: self.mixers(),
+            include: Vec::new(),
+            ..config.clone()
+        }
+    }
+
+    /// Locks the requested `channel` out for maintenance (or, given false,
+    /// clears the lockout), returning its resulting [ChannelConfig].  A
+    /// Channel under maintenance is driven to its failsafe output (or full
+    /// off), and every command to it is rejected with
+    /// [Pca9685Error::ChannelMaintenanceError] until cleared.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn set_maintenance(
+        &self,
+        channel: Channel,
+        under_maintenance: bool,
+    ) -> Pca9685Result<ChannelConfig> {
+        self.cancel_moves([channel]);
+
+        let raw_channel = channel as u8;
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+
+        match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => ch.set_maintenance(under_maintenance, &mut locked_pca_impl),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        }
+    }
+
+    /// Arms the ESC of the requested `channel` (see [crate::EscConfig]),
+    /// returning its resulting [ChannelConfig]: minimum throttle is written,
+    /// and anything above it is refused with
+    /// [Pca9685Error::ChannelDisarmedError] until held for the ESC's
+    /// `arming_ms`.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if the Channel is not an ESC
+    /// * Any error documented by [Pca9685::set_pwm_count]
+    pub fn arm(&self, channel: Channel) -> Pca9685Result<ChannelConfig> {
+        self.feed_watchdog(None);
+
+        let raw_channel = channel as u8;
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+
+        match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => {
+                self.cancel_moves([channel]);
+                ch.arm(Instant::now(), &mut locked_pca_impl)
+            }
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        }
+    }
+
+    /// Disarms the ESC of the requested `channel`, returning it to minimum
+    /// throttle (see [Pca9685::arm]).
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if the Channel is not an ESC
+    /// * Any error documented by [Pca9685::set_pwm_count]
+    pub fn disarm(&self, channel: Channel) -> Pca9685Result<ChannelConfig> {
+        self.cancel_moves([channel]);
+
+        let raw_channel = channel as u8;
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+
+        match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => ch.disarm(&mut locked_pca_impl),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        }
+    }
+
+    /// Returns the Channels under maintenance (see [Pca9685::set_maintenance]).
+    pub fn maintenance(&self) -> Vec<Channel> {
+        let channels = self.channels.lock().unwrap();
+
+        let mut under_maintenance: Vec<Channel> = channels
+            .values()
+            .map(ChannelProxy::stats)
+            .filter(|stats| stats.under_maintenance)
+            .map(|stats| stats.channel)
+            .collect();
+        under_maintenance.sort_unstable_by_key(|channel| *channel as u8);
+
+        under_maintenance
+    }
+
+    /// Pauses writing to the device, e.g. while working on the mechanism with
+    /// the [Pca9685] still running: commands are queued or rejected per
+    /// `mode`, and nothing (including time-based behavior such as
+    /// [crate::ReducedHold]) is written until [Pca9685::resume].
+    pub fn pause(&self, mode: PauseMode) {
+        let mut channels = self.channels.lock().unwrap();
+
+        log::warn!(target: "pca9685", "Paused ({:?})", mode);
+        for ch in channels.values_mut() {
+            ch.pause(mode);
+        }
+        *self.paused.lock().unwrap() = Some(mode);
+    }
+
+    /// Returns the [PauseMode] if the [Pca9685] is paused (see
+    /// [Pca9685::pause]).
+    pub fn paused(&self) -> Option<PauseMode> {
+        *self.paused.lock().unwrap()
+    }
+
+    /// Resumes writing to the device, writing the latest output of each
+    /// Channel commanded while paused, and returning their resulting
+    /// [ChannelConfig]s.  Every Channel is resumed even if writing one fails.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error (the first one is returned)
+    pub fn resume(&self) -> Pca9685Result<Vec<ChannelConfig>> {
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+        let mut channels = self.channels.lock().unwrap();
+
+        log::info!(target: "pca9685", "Resumed");
+        *self.paused.lock().unwrap() = None;
+
+        let mut raw_channels: Vec<u8> = channels.keys().copied().collect();
+        raw_channels.sort_unstable();
+
+        let mut configs = Vec::new();
+        let mut first_error = None;
+        for raw_channel in raw_channels {
+            match channels
+                .get_mut(&raw_channel)
+                .unwrap()
+                .resume(&mut locked_pca_impl)
+            {
+                Ok(Some(config)) => configs.push(config),
+                Ok(None) => {}
+                Err(error) => {
+                    log::error!(target: "pca9685", "{}", error);
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+
+        match first_error {
+            Some(error) => Err(error),
+            None => Ok(configs),
+        }
+    }
+
+    /// Superimposes random jitter on the output of `channel` (or, given None,
+    /// stops, restoring its output), returning the resulting [ChannelConfig].
+    /// The commanded output (i.e., `current_count`) is unaffected.  Only
+    /// available given [Config]'s `allow_jitter`.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if jitter isn't allowed, `jitter` is
+    ///   invalid (see [JitterConfig::validate]), or the channel isn't
+    ///   configured
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error restoring the output
+    pub fn set_jitter(
+        &self,
+        channel: Channel,
+        jitter: Option<JitterConfig>,
+    ) -> Pca9685Result<ChannelConfig> {
+        if jitter.is_some() && !self.allow_jitter {
+            return Err(Pca9685Error::InvalidCommand(
+                "Jitter is not allowed (see Config.allow_jitter).".to_string(),
+            ));
+        }
+
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+
+        let raw_channel = channel as u8;
+
+        match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => ch.set_jitter(jitter, &mut locked_pca_impl),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        }
+    }
+
+    /// Returns the jitter superimposed on `channel`, if any (see
+    /// [Pca9685::set_jitter])
+    pub fn jitter(&self, channel: Channel) -> Pca9685Result<Option<JitterConfig>> {
+        let raw_channel = channel as u8;
+
+        match self.channels.lock().unwrap().get(&raw_channel) {
+            Some(ch) => Ok(ch.jitter()),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        }
+    }
+
+    /// Returns the latest iteration of the PID controller of `channel` (see
+    /// [crate::PidGains]), or None if it has none (or has yet to run)
+    pub fn pid_sample(&self, channel: Channel) -> Pca9685Result<Option<PidSample>> {
+        let raw_channel = channel as u8;
+
+        match self.channels.lock().unwrap().get(&raw_channel) {
+            Some(ch) => Ok(ch.pid_sample()),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        }
+    }
+
+    /// Sets (or, given None, clears) the [ChannelCalibration] of `channel`,
+    /// returning the resulting [ChannelConfig].  Any pw_limits are
+    /// re-derived using the new calibration.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if `calibration` is unusable
+    /// * [Pca9685Error::PulseWidthRangeError] if the channel's pw_limits
+    ///   cannot be produced with the new calibration
+    pub fn calibrate(
+        &self,
+        channel: Channel,
+        calibration: Option<ChannelCalibration>,
+    ) -> Pca9685Result<ChannelConfig> {
+        let raw_channel = channel as u8;
+
+        match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => ch.calibrate(calibration),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        }
+    }
+
+    /// Sets `channel` to full/continuous output, returning the resulting
+    /// [ChannelConfig] containing the updated `current_count`.
+    ///
+    /// Ignores any configured ChannelCountLimits, if applicable.
+    pub fn full_on(&self, channel: Channel) -> Pca9685Result<ChannelConfig> {
+        self.feed_watchdog(None);
+        self.cancel_moves([channel]);
+
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+
+        let raw_channel = channel as u8;
+
+        let result = match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => ch.full_on(&mut locked_pca_impl),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        };
+
+        self.record(result)
+    }
+
+    /// Sets `channel` to off (no output), returning the resulting
+    /// [ChannelConfig] containing the updated `current_count` as None.
+    ///
+    /// Ignores any configured ChannelCountLimits, if applicable.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn full_off(&self, channel: Channel) -> Pca9685Result<ChannelConfig> {
+        self.feed_watchdog(None);
+        self.cancel_moves([channel]);
+
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+
+        let raw_channel = channel as u8;
+
+        let result = match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => ch.full_off(&mut locked_pca_impl),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        };
+
+        self.record(result)
+    }
+
+    /// Sets the `channel` output to `count` pulse counts, returning the resulting
+    /// [ChannelConfig] containing the updated `current_count`.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::PulseWidthRangeError] if `count` is not within the
+    ///   limits of the PCA9685
+    /// * [Pca9685Error::CustomLimitsError] if `count` is not within the channel's
+    ///   configured limits
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn set_pwm_count(&self, channel: Channel, count: u16) -> Pca9685Result<ChannelConfig> {
+        self.feed_watchdog(None);
+        self.cancel_moves([channel]);
+
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+
+        let raw_channel = channel as u8;
+
+        let result = match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => ch.set_pwm_count(count, &mut locked_pca_impl),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        };
+
+        self.record(result)
+    }
+
+    /// Adjusts the `channel` output by `delta` pulse counts relative to its
+    /// current output, clamped to the channel's configured limits, returning
+    /// the resulting [ChannelConfig] containing the updated `current_count`.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::ChannelInactiveError] if the channel has no current
+    ///   output (e.g., it has never been set, or is full off)
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn adjust_count(&self, channel: Channel, delta: i32) -> Pca9685Result<ChannelConfig> {
+        self.feed_watchdog(None);
+        self.cancel_moves([channel]);
+
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+
+        let raw_channel = channel as u8;
+
+        let result = match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => ch.adjust_count(delta, &mut locked_pca_impl),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        };
+
+        self.record(result)
+    }
+
+    /// Adjusts the `channel` output by `delta_pct` of the channel's configured
+    /// range (e.g., `-0.05` moves down by 5% of the range), clamped to the
+    /// channel's configured limits, returning the resulting [ChannelConfig]
+    /// containing the updated `current_count`.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::ChannelInactiveError] if the channel has no current
+    ///   output (e.g., it has never been set, or is full off)
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn adjust_pct(&self, channel: Channel, delta_pct: f64) -> Pca9685Result<ChannelConfig> {
+        self.feed_watchdog(None);
+        self.cancel_moves([channel]);
+
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+
+        let raw_channel = channel as u8;
+
+        let result = match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => ch.adjust_pct(delta_pct, &mut locked_pca_impl),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        };
+
+        self.record(result)
+    }
+
+    /// Executes a single [ChannelCommand], returning the resulting
+    /// [ChannelConfig] containing the updated `current_count`.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if `value`/`basis` are missing or
+    ///   unexpected for the `command_type`
+    /// * Any error documented by the corresponding `set_*`/`adjust_*` method
+    pub fn execute(&self, command: &ChannelCommand) -> Pca9685Result<ChannelConfig> {
+        self.feed_watchdog(command.client_id.as_deref());
+        self.cancel_moves([command.channel]);
+
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+
+        let raw_channel = command.channel as u8;
+
+        let result = match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => ch.execute(command, &mut locked_pca_impl),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        };
+
+        self.record(result)
+    }
+
+    /// Executes a single [ChannelCommand] as [Pca9685::execute] would, but
+    /// writes the result to the all-call address (see
+    /// [Config::all_call_address]), so every board listening there changes
+    /// output together. Each board must have register auto-increment enabled
+    /// (as by any previous multi-register write), and later writes (e.g., by
+    /// motions or filters) reach this board only.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if the device has no all-call address
+    /// * Any error documented by [Pca9685::execute]
+    pub fn broadcast(&self, command: &ChannelCommand) -> Pca9685Result<ChannelConfig> {
+        self.feed_watchdog(command.client_id.as_deref());
+        self.cancel_moves([command.channel]);
+
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+        if locked_pca_impl.all_call_address().is_none() {
+            return Err(Pca9685Error::InvalidCommand(
+                "The device has no all-call address.".to_string(),
+            ));
+        }
+
+        let raw_channel = command.channel as u8;
+
+        locked_pca_impl.set_broadcasting(true);
+        let result = match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => ch.execute(command, &mut locked_pca_impl),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        };
+        locked_pca_impl.set_broadcasting(false);
+
+        self.record(result)
+    }
+
+    /// Evaluates `command` for `channel` as [Pca9685::execute] would (all
+    /// validation, filters, and limits), returning the resulting
+    /// [ChannelConfig] without writing anything to the device.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if `command` is for another channel
+    /// * Any error documented by [Pca9685::execute], other than driver errors
+    pub fn preview(
+        &self,
+        channel: Channel,
+        command: &ChannelCommand,
+    ) -> Pca9685Result<ChannelConfig> {
+        if command.channel != channel {
+            return Err(Pca9685Error::InvalidCommand(format!(
+                "Command is for channel {}, not {}.",
+                command.channel as u8, channel as u8
+            )));
+        }
+
+        let raw_channel = channel as u8;
+
+        match self.channels.lock().unwrap().get(&raw_channel) {
+            Some(ch) => ch.preview(command),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        }
+    }
+
+    /// Returns the mapping of inputs in `unit` (every `step`, across the
+    /// channel's limits) to the count each yields and the pulse width that
+    /// count actually produces, as evaluated by [Pca9685::preview] under the
+    /// channel's current configuration and the device's output frequency.
+    /// Inputs are rounded to 6 decimal places, so the table is the same on
+    /// every run.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if `step` is not greater than 0.0, or
+    ///   yields more than [CONVERSION_TABLE_MAX_ROWS] rows
+    /// * Any error documented by [Pca9685::preview]
+    pub fn conversion_table(
+        &self,
+        channel: Channel,
+        unit: DisplayUnit,
+        step: f64,
+    ) -> Pca9685Result<Vec<ConversionRow>> {
+        unit.validate()?;
+        if !(step.is_finite() && step > 0.0) {
+            return Err(Pca9685Error::InvalidCommand(format!(
+                "Table step {} must be greater than 0.0.",
+                step
+            )));
+        }
+
+        let config = self.config(channel)?;
+        let count_duration_ms = self.single_count_duration_ms();
+        let limits = config.limits();
+        let value = |count: u16| {
+            unit.value(
+                count,
+                limits,
+                count as f64 * count_duration_ms,
+                config.inverted,
+            )
+        };
+        let (from, to) = match (value(limits.0), value(limits.1)) {
+            (from, to) if from <= to => (from, to),
+            (to, from) => (from, to),
+        };
+
+        let rows = ((to - from) / step + 1e-9).floor() as usize + 1;
+        if rows > CONVERSION_TABLE_MAX_ROWS {
+            return Err(Pca9685Error::InvalidCommand(format!(
+                "Table step {} yields {} rows (at most {}).",
+                step, rows, CONVERSION_TABLE_MAX_ROWS
+            )));
+        }
+
+        (0..rows)
+            .map(|index| {
+                let input = ((from + index as f64 * step) * 1e6).round() / 1e6;
+                let (command_type, value) = match unit {
+                    DisplayUnit::Counts => (CommandType::PulseCount, input.round()),
+                    DisplayUnit::Ms => (CommandType::PulseWidth, input),
+                    DisplayUnit::Us => (CommandType::PulseWidth, input / 1000.0),
+                    DisplayUnit::Percent => (CommandType::Percent, input / 100.0),
+                    DisplayUnit::Degrees(range) => (CommandType::Percent, input / range),
+                };
+                let command = ChannelCommand {
+                    channel,
+                    command_type,
+                    value: Some(value),
+                    basis: None,
+                    client_id: None,
+                };
+
+                let count = self.preview(channel, &command)?.current_count.unwrap_or(0);
+                Ok(ConversionRow {
+                    input,
+                    count,
+                    pw_ms: count as f64 * count_duration_ms,
+                })
+            })
+            .collect()
+    }
+
+    /// Executes `commands` in order while holding the device lock, returning
+    /// the resulting [ChannelConfig] of each command.
+    ///
+    /// When `atomic` is true, every command is validated against its
+    /// channel's limits before anything is written, so an error leaves every
+    /// channel untouched (each channel may then appear only once), and the
+    /// outputs are written at once: in a single I2C transaction, once every
+    /// channel has been written (and none is full on).  When
+    /// `atomic` is false, execution stops at the first error and commands
+    /// preceding it remain applied.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidCommand] if `atomic` and a channel appears more
+    ///   than once
+    /// * Any error documented by [Pca9685::execute]
+    /// * [Pca9685Error::Pca9685DriverError] if `atomic` and the outputs could
+    ///   not be written
+    pub fn apply(
+        &self,
+        commands: &[ChannelCommand],
+        atomic: bool,
+    ) -> Pca9685Result<Vec<ChannelConfig>> {
+        for command in commands {
+            self.feed_watchdog(command.client_id.as_deref());
+        }
+        self.cancel_moves(commands.iter().map(|command| command.channel));
+
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+        let mut channels = self.channels.lock().unwrap();
+
+        if !atomic {
+            let results = commands
+                .iter()
+                .map(|command| {
+                    let raw_channel = command.channel as u8;
+                    match channels.get_mut(&raw_channel) {
+                        Some(ch) => ch.execute(command, &mut locked_pca_impl),
+                        None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+                    }
+                })
+                .collect();
+
+            return self.record_all(results);
+        }
+
+        let mut seen = HashSet::new();
+        let mut targets = Vec::with_capacity(commands.len());
+        for command in commands {
+            let raw_channel = command.channel as u8;
+            if !seen.insert(raw_channel) {
+                return Err(Pca9685Error::InvalidCommand(format!(
+                    "Channel {} appears more than once in an atomic batch.",
+                    raw_channel
+                )));
+            }
+
+            match channels.get_mut(&raw_channel) {
+                Some(ch) => {
+                    let target = ch.resolve(command);
+                    ch.count_command(command.command_type, &target);
+                    targets.push((raw_channel, target?));
+                }
+                None => return Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+            }
+        }
+
+        locked_pca_impl.begin_burst();
+        let results = commands
+            .iter()
+            .zip(targets)
+            .map(|(command, (raw_channel, target))| {
+                channels.get_mut(&raw_channel).unwrap().apply_target(
+                    target,
+                    command.client_id.as_deref(),
+                    &mut locked_pca_impl,
+                )
+            })
+            .collect();
+        locked_pca_impl
+            .end_burst()
+            .map_err(Pca9685Error::Pca9685DriverError)?;
+
+        self.record_all(results)
+    }
+
+    /// Atomically updates the `channel` output: `f` receives the channel's
+    /// current [ChannelConfig] and returns the pulse count to write, all while
+    /// the channel is locked, so no other command can interleave between the
+    /// read and the write.  The write is attributed to `client_id`.  Returns the resulting [ChannelConfig] containing
+    /// the updated `current_count`.
+    ///
+    /// Error conditions:
+    /// * Any error returned by `f`, in which case nothing is written
+    /// * [Pca9685Error::CustomLimitsError] if the returned count is not within
+    ///   the channel's configured limits
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn transaction<F>(
+        &self,
+        channel: Channel,
+        client_id: Option<&str>,
+        f: F,
+    ) -> Pca9685Result<ChannelConfig>
+    where
+        F: FnOnce(&ChannelConfig) -> Pca9685Result<u16>,
+    {
+        self.feed_watchdog(client_id);
+        self.cancel_moves([channel]);
+
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+
+        let raw_channel = channel as u8;
+
+        let result = match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => ch.transaction(f, client_id, &mut locked_pca_impl),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        };
+
+        self.record(result)
+    }
+
+    /// Sets the `channel` output to `pw_ms` pulse width in milliseconds,
+    /// returning the resulting [ChannelConfig] containing the updated
+    /// `current_count`.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::PulseWidthRangeError] if `pw_ms` is not within the
+    ///   limits of the PCA9685 (based on the configured output frequency)
+    /// * [Pca9685Error::CustomLimitsError] if `pw_ms` is not within the channel's
+    ///   configured limits
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn set_pw_ms(&self, channel: Channel, pw_ms: f64) -> Pca9685Result<ChannelConfig> {
+        self.feed_watchdog(None);
+        self.cancel_moves([channel]);
+
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+
+        let raw_channel = channel as u8;
+
+        let result = match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => ch.set_pw_ms(pw_ms, &mut locked_pca_impl),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        };
+
+        self.record(result)
+    }
+
+    /// Sets the `channel` output to `pct` percent duty cycle (based on the
+    /// channel's configured ChannelCountLimits, if applicable),
+    /// returning the resulting [ChannelConfig] containing the updated
+    /// `current_count`.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::PercentOfRangeError] if `pct` is not within [0.0, 1.0]
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn set_pct(&self, channel: Channel, pct: f64) -> Pca9685Result<ChannelConfig> {
+        self.set_pct_of(channel, pct, PercentBasis::Limits)
+    }
+
+    /// Sets the output of the Channel named `name` as by [Pca9685::set_pct],
+    /// e.g. `set_pct_by_name("pan", 0.5)`.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchChannelNameError] if no Channel is so named
+    /// * Any error documented by [Pca9685::set_pct]
+    pub fn set_pct_by_name(&self, name: &str, pct: f64) -> Pca9685Result<ChannelConfig> {
+        self.set_pct(self.channel_by_name(name)?, pct)
+    }
+
+    /// Sets the output of the Channel named `name` as by
+    /// [Pca9685::set_pw_ms].
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchChannelNameError] if no Channel is so named
+    /// * Any error documented by [Pca9685::set_pw_ms]
+    pub fn set_pw_ms_by_name(&self, name: &str, pw_ms: f64) -> Pca9685Result<ChannelConfig> {
+        self.set_pw_ms(self.channel_by_name(name)?, pw_ms)
+    }
+
+    /// Sets every Channel of the group `name` (see [ChannelGroup]) to `pct`
+    /// percent duty cycle, as by [Pca9685::set_pct], while holding the
+    /// device lock once, so the Channels move together.  Every output is
+    /// validated before any is written (see [Pca9685::apply]), returning
+    /// the resulting [ChannelConfig]s in the group's order.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchGroupError] if no group is so named
+    /// * Any error documented by [Pca9685::set_pct], in which case no
+    ///   Channel of the group is changed
+    pub fn set_group_pct(&self, name: &str, pct: f64) -> Pca9685Result<Vec<ChannelConfig>> {
+        self.set_group(name, CommandType::Percent, pct)
+    }
+
+    /// Sets every Channel of the group `name` to `pw_ms` pulse width in
+    /// milliseconds, as by [Pca9685::set_group_pct].
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchGroupError] if no group is so named
+    /// * Any error documented by [Pca9685::set_pw_ms], in which case no
+    ///   Channel of the group is changed
+    pub fn set_group_pw_ms(&self, name: &str, pw_ms: f64) -> Pca9685Result<Vec<ChannelConfig>> {
+        self.set_group(name, CommandType::PulseWidth, pw_ms)
+    }
+
+    fn set_group(
+        &self,
+        name: &str,
+        command_type: CommandType,
+        value: f64,
+    ) -> Pca9685Result<Vec<ChannelConfig>> {
+        self.command_selected(
+            &ChannelSelector::Group(name.to_string()),
+            command_type,
+            Some(value),
+            None,
+        )
+    }
+
+    /// Applies the same command (`command_type`, `value` and `basis`, as of a
+    /// [ChannelCommand]) to every Channel selected by `selector` (e.g., a
+    /// [ChannelSelector::Tag]) while holding the device lock once, so the
+    /// Channels move together, returning their resulting [ChannelConfig]s
+    /// in the selection's order.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::NoSuchGroupError] or [Pca9685Error::NoSuchTagError]
+    ///   if `selector` selects nothing
+    /// * Any error documented by [Pca9685::apply] (atomically), in which case
+    ///   no selected Channel is changed
+    pub fn command_selected(
+        &self,
+        selector: &ChannelSelector,
+        command_type: CommandType,
+        value: Option<f64>,
+        basis: Option<PercentBasis>,
+    ) -> Pca9685Result<Vec<ChannelConfig>> {
+        let commands: Vec<ChannelCommand> = self
+            .select(selector)?
+            .into_iter()
+            .map(|channel| ChannelCommand {
+                channel,
+                command_type,
+                value,
+                basis,
+                client_id: None,
+            })
+            .collect();
+
+        self.apply(&commands, true)
+    }
+
+    /// Moves the `channel` output to `target_pct` percent duty cycle (as by
+    /// [Pca9685::set_pct]) over `duration`, commanding the intermediate
+    /// counts in the background rather than jumping to the target, and
+    /// returning the [ChannelConfig] the Channel will arrive at.  The move
+    /// replaces any the Channel was making, and continues until it arrives, is
+    /// stopped (see [Pca9685::stop_move]), or the Channel is otherwise
+    /// commanded (e.g., by [Pca9685::set_pct] or [Pca9685::relax]).  A Channel without an output
+    /// to move from, or a zero `duration`, takes the target immediately.
+    ///
+    /// Error conditions:
+    /// * Any error documented by [Pca9685::set_pct], in which case the
+    ///   Channel doesn't move
+    pub fn move_to(
+        &self,
+        channel: Channel,
+        target_pct: f64,
+        duration: Duration,
+    ) -> Pca9685Result<ChannelConfig> {
+        self.move_to_eased(channel, target_pct, duration, Easing::Linear)
+    }
+
+    /// Moves the `channel` output to `target_pct` as by [Pca9685::move_to],
+    /// progressing per `easing`, e.g. [Easing::EaseInOut] to start and stop
+    /// smoothly.
+    ///
+    /// Error conditions:
+    /// * Any error documented by [Pca9685::set_pct], in which case the
+    ///   Channel doesn't move
+    pub fn move_to_eased(
+        &self,
+        channel: Channel,
+        target_pct: f64,
+        duration: Duration,
+        easing: Easing,
+    ) -> Pca9685Result<ChannelConfig> {
+        let command = ChannelCommand {
+            channel,
+            command_type: CommandType::Percent,
+            value: Some(target_pct),
+            basis: None,
+            client_id: None,
+        };
+        let target = self.preview(channel, &command)?;
+        let from = self.config(channel)?.current_count;
+
+        let mut motions = self.motions.lock().unwrap();
+        motions.stop(channel);
+        match (from, target.current_count) {
+            (Some(from), Some(to)) if !duration.is_zero() => {
+                motions.start(channel, (from, to), duration, easing, Instant::now());
+                drop(motions);
+                self.feed_watchdog(None);
+                Ok(target)
+            }
+            _ => {
+                drop(motions);
+                self.set_pct(channel, target_pct)
+            }
+        }
+    }
+
+    /// Stops any move of the `channels` (see [Pca9685::move_to]) where they
+    /// are, so that a command to them isn't overridden by the move's next
+    /// step
+    fn cancel_moves(&self, channels: impl IntoIterator<Item = Channel>) {
+        let mut motions = self.motions.lock().unwrap();
+        for channel in channels {
+            motions.stop(channel);
+        }
+    }
+
+    /// Returns true while `channel` is moving (see [Pca9685::move_to])
+    pub fn moving(&self, channel: Channel) -> bool {
+        self.motions.lock().unwrap().moving(channel)
+    }
+
+    /// Stops any move of `channel` (see [Pca9685::move_to]) where it is,
+    /// returning its [ChannelConfig].
+    pub fn stop_move(&self, channel: Channel) -> Pca9685Result<ChannelConfig> {
+        self.motions.lock().unwrap().stop(channel);
+
+        self.config(channel)
+    }
+
+    /// Sets the `channel` output to `pct` percent duty cycle of the range
+    /// selected by `basis`, returning the resulting [ChannelConfig] containing
+    /// the updated `current_count`.
+    ///
+    /// With [PercentBasis::FullRange], the resulting count must still be
+    /// within the channel's configured limits.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::PercentOfRangeError] if `pct` is not within [0.0, 1.0]
+    /// * [Pca9685Error::CustomLimitsError] if the resulting count is not within
+    ///   the channel's configured limits
+    /// * [Pca9685Error::Pca9685DriverError] if the underlying PCA 9685 driver
+    ///   yields an error
+    pub fn set_pct_of(
+        &self,
+        channel: Channel,
+        pct: f64,
+        basis: PercentBasis,
+    ) -> Pca9685Result<ChannelConfig> {
+        self.feed_watchdog(None);
+        self.cancel_moves([channel]);
+
+        let mut locked_pca_impl = self.inner.lock().unwrap();
+
+        let raw_channel = channel as u8;
+
+        let result = match self.channels.lock().unwrap().get_mut(&raw_channel) {
+            Some(ch) => ch.set_pct(pct, basis, &mut locked_pca_impl),
+            None => Err(Pca9685Error::NoSuchChannelError(raw_channel)),
+        };
+
+        self.record(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        filters::SlewFilter, AxisMapping, ChannelCommand, ChannelGroup, ChannelLimits,
+        ChannelPulseWidthLimits, ChannelSelector, CommandType, Config, ConfigValidation,
+        DeviceConfig, DisabledOutput, DisplayUnit, DualRate, EscConfig, FailsafeOutput,
+        FeedbackConfig, FeedbackSource, FindingSeverity, GaitConfig, GaitLeg, GaitPattern,
+        GamepadState, GpioInput, IdleBehavior, IdleConfig, Keyframe, MixerConfig, MixerOutput,
+        PauseMode, Pca9685, Pca9685Error, Pca9685Event, Pca9685Result, PlaybackClock, Rate, Replay,
+        RuleAction, SequenceConfig, StartupConfig, StepTest, TeleopConfig, TeleopProfile,
+        ThermalLimits, TraceWrite, WatchdogConfig, PCA_PWM_RESOLUTION,
+    };
+    use embedded_hal::blocking::i2c::{Write, WriteRead};
+    use pwm_pca9685::{Channel, OutputDriver};
+    use std::collections::{BTreeMap, HashMap};
+    use std::convert::Infallible;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    fn create_mock(output_frequency_hz: u16) -> (Config, Pca9685) {
+        let axis = |axis: usize, input: &str, rate: f64| AxisMapping {
+            axis,
+            mixer: "elevons".to_owned(),
+            input: input.to_owned(),
+            expo: 0.0,
+            rate,
+            reversed: false,
+        };
+        let config = Config {
+            device: "/dev/foo".to_owned(),
+            address: 0x40,
+            output_frequency_hz,
+            prescale: None,
+            open_drain: false,
+            disabled_output: DisabledOutput::Low,
+            stagger_on_counts: false,
+            external_clock_hz: None,
+            subaddresses: Vec::new(),
+            all_call_address: Config::default_all_call_address(),
+            channels: Default::default(),
+            devices: Vec::new(),
+            groups: Default::default(),
+            mixers: vec![MixerConfig {
+                name: "elevons".to_owned(),
+                inputs: vec!["pitch".to_owned(), "roll".to_owned()],
+                outputs: vec![
+                    MixerOutput {
+                        channel: 2,
+                        weights: vec![0.5, 0.5],
+                        offset: 0.0,
+                    },
+                    MixerOutput {
+                        channel: 3,
+                        weights: vec![0.5, -0.5],
+                        offset: 0.0,
+                    },
+                ],
+                expo: Default::default(),
+            }],
+            teleop: TeleopConfig {
+                profiles: vec![
+                    TeleopProfile {
+                        name: "normal".to_owned(),
+                        axes: vec![axis(0, "pitch", 1.0), axis(1, "roll", 1.0)],
+                        select_buttons: vec![0],
+                        rate_button: Some(3),
+                    },
+                    TeleopProfile {
+                        name: "fine".to_owned(),
+                        axes: vec![axis(0, "pitch", 0.5), axis(1, "roll", 0.5)],
+                        select_buttons: vec![1, 2],
+                        rate_button: None,
+                    },
+                ],
+                default_profile: None,
+            },
+            watchdog: None,
+            circuit_breaker: None,
+            idle: None,
+            startup: None,
+            gimbal: None,
+            presets: Vec::new(),
+            scenes: Default::default(),
+            gaits: Vec::new(),
+            linear_axes: Vec::new(),
+            rules: Vec::new(),
+            virtual_channels: Vec::new(),
+            derived_channels: Vec::new(),
+            gpio: None,
+            allow_jitter: false,
+            strict: false,
+            include: Vec::new(),
+            autopersist: false,
+            sequences_dir: None,
+            storage: None,
+            interpolated: BTreeMap::new(),
+        };
+
+        let pca = Pca9685::null(&config);
+
+        (config, pca)
+    }
+
+    #[test]
+    fn init() {
+        let test_output_frequency_hz = 200;
+
+        let (config, pca) = create_mock(test_output_frequency_hz);
+
+        let expected_prescale = 30; // per PCA9685 documented example using 200Hz
+        let expected_actual_output_frequency_hz = 25_000_000.0 / (4096.0 * 31.0);
+        let expected_max_pw_ms = 1000.0 / expected_actual_output_frequency_hz;
+        let single_count_duration_ms = expected_max_pw_ms / 4096.0;
+
+        assert_eq!(
+            pca.actual_output_frequency_hz(),
+            expected_actual_output_frequency_hz
+        );
+        assert_eq!(pca.max_pw_ms(), expected_max_pw_ms);
+        assert_eq!(pca.single_count_duration_ms(), single_count_duration_ms);
+        assert_eq!(pca.device(), config.device);
+        assert_eq!(pca.address(), config.address);
+        assert_eq!(pca.output_frequency_hz(), config.output_frequency_hz);
+        assert_eq!(pca.prescale(), expected_prescale);
+        assert_eq!(pca.output_type(), OutputDriver::TotemPole);
+        assert_eq!(pca.device_error(), None);
+    }
+
+    #[test]
+    fn subscribe() {
+        let (_, pca) = create_mock(200);
+        let channel = Channel::try_from(0_u8).unwrap();
+        let events = pca.subscribe();
+
+        let mut config = pca.config(channel).unwrap();
+        config.thermal_limits = Some(ThermalLimits {
+            window_ms: 1,
+            max_duty: Some(0.5),
+            max_travel_counts_per_s: None,
+        });
+        pca.configure_channel(&config).unwrap();
+        pca.set_pwm_count(channel, 1500).unwrap();
+
+        match events.recv_timeout(Duration::from_secs(5)) {
+            Ok(Pca9685Event::ThermalWarning(stats)) => assert_eq!(stats.channel, channel),
+            other => panic!("expected a thermal warning, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn add_filter() {
+        let (_, pca) = create_mock(200);
+        let channel = Channel::try_from(0_u8).unwrap();
+
+        pca.set_pwm_count(channel, 1000).unwrap();
+        pca.add_filter(
+            &ChannelSelector::All,
+            Arc::new(SlewFilter {
+                max_step_counts: 100,
+            }),
+        )
+        .unwrap();
+
+        pca.set_pwm_count(channel, 2000).unwrap();
+        assert_eq!(pca.config(channel).unwrap().current_count, Some(1100));
+
+        let commands = [ChannelCommand {
+            channel,
+            command_type: CommandType::PulseCount,
+            value: Some(0.0),
+            basis: None,
+            client_id: None,
+        }];
+        pca.apply(&commands, true).unwrap();
+        assert_eq!(pca.config(channel).unwrap().current_count, Some(1000));
+
+        assert!(matches!(
+            pca.add_filter(
+                &ChannelSelector::Group("arms".to_string()),
+                Arc::new(SlewFilter { max_step_counts: 1 })
+            ),
+            Err(Pca9685Error::NoSuchGroupError(_))
+        ));
+    }
+
+    #[test]
+    fn set_mixer_inputs() {
+        let (_, pca) = create_mock(200);
+        let left = Channel::try_from(2_u8).unwrap();
+        let right = Channel::try_from(3_u8).unwrap();
+        let inputs = |pairs: &[(&str, f64)]| {
+            pairs
+                .iter()
+                .map(|(name, value)| (name.to_string(), *value))
+                .collect::<HashMap<String, f64>>()
+        };
+
+        pca.configure_channel(&crate::ChannelConfig {
+            channel: left,
+            current_count: None,
+            custom_limits: Some(ChannelLimits::from_count_limits(1000, 3000)),
+            owner: None,
+            calibration: None,
+            inverted: false,
+            trim_counts: 0,
+            esc: None,
+            tags: Vec::new(),
+            reduced_hold: None,
+            thermal_limits: None,
+            dual_rate: None,
+            failsafe: None,
+            display_unit: None,
+            display_value: None,
+            name: None,
+            feedback: None,
+            pid: None,
+            input_shaping: None,
+            low_pass: None,
+        })
+        .unwrap();
+        pca.configure_channel(&crate::ChannelConfig {
+            channel: right,
+            current_count: None,
+            custom_limits: Some(ChannelLimits::from_count_limits(1000, 3000)),
+            owner: None,
+            calibration: None,
+            inverted: false,
+            trim_counts: 0,
+            esc: None,
+            tags: Vec::new(),
+            reduced_hold: None,
+            thermal_limits: None,
+            dual_rate: None,
+            failsafe: None,
+            display_unit: None,
+            display_value: None,
+            name: None,
+            feedback: None,
+            pid: None,
+            input_shaping: None,
+            low_pass: None,
+        })
+        .unwrap();
+
+        let configs = pca
+            .set_mixer_inputs("elevons", &inputs(&[("pitch", 1.0)]))
+            .unwrap();
+        assert_eq!(configs.len(), 2);
+        assert_eq!(pca.config(left).unwrap().current_count, Some(2500));
+        assert_eq!(pca.config(right).unwrap().current_count, Some(2500));
+
+        pca.set_mixer_inputs("elevons", &inputs(&[("roll", 1.0)]))
+            .unwrap();
+        assert_eq!(pca.config(left).unwrap().current_count, Some(3000));
+        assert_eq!(pca.config(right).unwrap().current_count, Some(2000));
+        assert_eq!(
+            pca.mixer_inputs("elevons").unwrap(),
+            inputs(&[("pitch", 1.0), ("roll", 1.0)])
+        );
+
+        assert!(matches!(
+            pca.set_mixer_inputs("elevons", &inputs(&[("yaw", 1.0)])),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+        assert!(matches!(
+            pca.set_mixer_inputs("elevons", &inputs(&[("roll", 1.5)])),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+        assert!(matches!(
+            pca.set_mixer_inputs("vtail", &inputs(&[])),
+            Err(Pca9685Error::NoSuchMixerError(_))
+        ));
+        assert_eq!(pca.config(left).unwrap().current_count, Some(3000));
+    }
+
+    #[test]
+    fn configure_mixers() {
+        let (_, pca) = create_mock(200);
+        let left = Channel::try_from(2_u8).unwrap();
+        let right = Channel::try_from(3_u8).unwrap();
+
+        let mut mixers = pca.mixers();
+        assert_eq!(mixers.len(), 1);
+
+        pca.set_mixer_inputs("elevons", &HashMap::from([("pitch".to_owned(), 1.0)]))
+            .unwrap();
+        assert_eq!(pca.config(left).unwrap().current_count, Some(3072));
+
+        mixers[0].outputs[1].weights = vec![1.0, -1.0];
+        mixers[0].expo.insert("pitch".to_owned(), 1.0);
+        assert_eq!(pca.configure_mixers(&mixers).unwrap(), mixers);
+        assert_eq!(pca.config(left).unwrap().current_count, Some(3072));
+        assert_eq!(pca.config(right).unwrap().current_count, Some(4096));
+        assert_eq!(pca.mixer_inputs("elevons").unwrap()["pitch"], 1.0);
+
+        let duplicated = vec![mixers[0].clone(), mixers[0].clone()];
+        assert!(matches!(
+            pca.configure_mixers(&duplicated),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+        mixers[0].outputs[0].weights.pop();
+        assert!(matches!(
+            pca.configure_mixers(&mixers),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+        assert_eq!(pca.mixers()[0].outputs[1].weights, vec![1.0, -1.0]);
+    }
+
+    #[test]
+    fn configure_sequence() {
+        let (_, pca) = create_mock(200);
+        let channel = Channel::try_from(0_u8).unwrap();
+        let mut config = pca.config(channel).unwrap();
+        config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        pca.configure_channel(&config).unwrap();
+
+        let keyframe = |t_ms: u64, channel: u8, value: f64| Keyframe {
+            t_ms,
+            channel,
+            command_type: CommandType::PulseCount,
+            value: Some(value),
+        };
+        let mut sequence = SequenceConfig {
+            name: "wave".to_owned(),
+            keyframes: vec![keyframe(0, 0, 1000.0), keyframe(500, 0, 2000.0)],
+            steps: vec![],
+            duration_ms: None,
+        };
+        assert_eq!(pca.configure_sequence(&sequence).unwrap(), sequence);
+        assert_eq!(pca.sequences(), vec![sequence.clone()]);
+        assert_eq!(pca.sequence("wave").unwrap(), sequence);
+
+        sequence.keyframes[1].value = Some(2500.0);
+        assert!(matches!(
+            pca.configure_sequence(&sequence),
+            Err(Pca9685Error::CustomLimitsError(_, _))
+        ));
+        sequence.keyframes[1] = keyframe(500, 1, 1500.0);
+        assert!(matches!(
+            pca.configure_sequence(&sequence),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+        sequence.keyframes[1] = keyframe(0, 0, 1500.0);
+        assert!(matches!(
+            pca.validate_sequence(&sequence),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+        sequence.keyframes.reverse();
+        sequence.keyframes[0].t_ms = 100;
+        assert!(matches!(
+            pca.validate_sequence(&sequence),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+        assert_eq!(
+            pca.sequence("wave").unwrap().keyframes[1].value,
+            Some(2000.0)
+        );
+
+        assert!(pca.remove_sequence("wave").is_ok());
+        assert!(matches!(
+            pca.remove_sequence("wave"),
+            Err(Pca9685Error::NoSuchSequenceError(_))
+        ));
+        assert!(pca.sequences().is_empty());
+    }
+
+    #[test]
+    fn play_sequence() {
+        let (_, pca) = create_mock(200);
+        let channel = Channel::try_from(0_u8).unwrap();
+        let mut config = pca.config(channel).unwrap();
+        config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        pca.configure_channel(&config).unwrap();
+
+        let keyframe = |t_ms: u64, value: f64| Keyframe {
+            t_ms,
+            channel: 0,
+            command_type: CommandType::PulseCount,
+            value: Some(value),
+        };
+        pca.configure_sequence(&SequenceConfig {
+            name: "wave".to_owned(),
+            keyframes: vec![keyframe(0, 1000.0), keyframe(500, 2000.0)],
+            steps: vec![],
+            duration_ms: None,
+        })
+        .unwrap();
+        let wait_for = |count: u16| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while pca.config(channel).unwrap().current_count != Some(count) {
+                assert!(Instant::now() < deadline, "keyframe not applied");
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        };
+
+        assert!(matches!(
+            pca.seek_sequence("wave", 0),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+        assert!(matches!(
+            pca.play_sequence("nod", PlaybackClock::Internal),
+            Err(Pca9685Error::NoSuchSequenceError(_))
+        ));
+
+        // An external clock waits for, then follows, seeks
+        let clock = PlaybackClock::External { timeout_ms: 60_000 };
+        let status = pca.play_sequence("wave", clock).unwrap();
+        assert_eq!(status.t_ms, None);
+        assert_eq!(status.duration_ms, 500);
+        assert!(pca.seek_sequence("wave", 500).unwrap().t_ms >= Some(500));
+        wait_for(2000);
+        pca.seek_sequence("wave", 0).unwrap();
+        wait_for(1000);
+        assert_eq!(
+            pca.config(channel).unwrap().owner,
+            Some("sequence/wave".to_owned())
+        );
+        assert_eq!(pca.stop_sequence().unwrap().clock, clock);
+        assert_eq!(pca.playback(), None);
+
+        // An internal clock finishes after the last keyframe
+        pca.play_sequence("wave", PlaybackClock::Internal).unwrap();
+        wait_for(2000);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pca.playback().is_some() {
+            assert!(Instant::now() < deadline, "playback not finished");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        // Replayed onto another Channel, which must be configured
+        let replay = Replay {
+            channel_map: BTreeMap::from([(0, 4)]),
+            rate: 2.0,
+        };
+        assert!(matches!(
+            pca.replay_sequence("wave", PlaybackClock::Internal, &replay),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+        config.channel = Channel::C4;
+        pca.configure_channel(&config).unwrap();
+        let status = pca
+            .replay_sequence("wave", PlaybackClock::Internal, &replay)
+            .unwrap();
+        assert_eq!(status.rate, 2.0);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pca.config(Channel::C4).unwrap().current_count != Some(2000) {
+            assert!(Instant::now() < deadline, "keyframe not replayed");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(matches!(
+            pca.replay_sequence(
+                "wave",
+                PlaybackClock::Internal,
+                &Replay {
+                    rate: 8.0,
+                    ..Replay::default()
+                }
+            ),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+    }
+
+    #[test]
+    fn check_sequence() {
+        let (mut config, pca) = create_mock(200);
+        let mut channel_config = pca.config(Channel::C0).unwrap();
+        channel_config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        pca.configure_channel(&channel_config).unwrap();
+
+        let keyframe = |t_ms: u64, value: f64| Keyframe {
+            t_ms,
+            channel: 0,
+            command_type: CommandType::PulseCount,
+            value: Some(value),
+        };
+        pca.configure_sequence(&SequenceConfig {
+            name: "wave".to_owned(),
+            keyframes: vec![keyframe(0, 1000.0), keyframe(500, 2000.0)],
+            steps: vec![],
+            duration_ms: None,
+        })
+        .unwrap();
+
+        assert!(matches!(
+            pca.check_sequence("nod", None),
+            Err(Pca9685Error::NoSuchSequenceError(_))
+        ));
+        assert!(pca.check_sequence("wave", None).unwrap().valid);
+
+        // Another rig, whose channel 0 has narrower limits
+        let validation = pca.check_sequence("wave", Some(&config)).unwrap();
+        assert!(!validation.valid);
+        assert_eq!(validation.findings.len(), 2);
+        channel_config.custom_limits = Some(ChannelLimits::from_count_limits(1200, 2000));
+        config.channels = vec![channel_config];
+        let validation = pca.check_sequence("wave", Some(&config)).unwrap();
+        assert_eq!(validation.findings.len(), 1);
+        assert_eq!(validation.findings[0].path, "keyframes[0]");
+        assert_eq!(validation.findings[0].severity, FindingSeverity::Error);
+    }
+
+    #[test]
+    fn record_macro() {
+        let (_, pca) = create_mock(200);
+        let channel = Channel::try_from(0_u8).unwrap();
+        let mut config = pca.config(channel).unwrap();
+        config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        pca.configure_channel(&config).unwrap();
+
+        assert!(matches!(
+            pca.stop_recording(),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+        assert!(matches!(
+            pca.start_recording("a wave"),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+
+        // Outputs before recording starts aren't captured
+        pca.set_pwm_count(channel, 1000).unwrap();
+        assert_eq!(pca.start_recording("wave").unwrap().keyframes, 0);
+        assert!(pca.start_recording("nod").is_err());
+        pca.adjust_count(channel, 500).unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        pca.full_off(channel).unwrap();
+        assert!(pca.set_pwm_count(channel, 3000).is_err());
+        assert_eq!(pca.recording().unwrap().keyframes, 2);
+
+        let sequence = pca.stop_recording().unwrap();
+        assert_eq!(pca.recording(), None);
+        assert_eq!(pca.sequence("wave").unwrap(), sequence);
+        assert_eq!(sequence.keyframes.len(), 2);
+        assert_eq!(sequence.keyframes[0].command_type, CommandType::PulseCount);
+        assert_eq!(sequence.keyframes[0].value, Some(1500.0));
+        assert!(sequence.keyframes[1].t_ms >= 50);
+        assert_eq!(sequence.keyframes[1].command_type, CommandType::FullOff);
+        assert!(sequence.duration_ms >= Some(sequence.keyframes[1].t_ms));
+
+        // Nothing recorded, nothing saved
+        pca.start_recording("nod").unwrap();
+        assert!(matches!(
+            pca.stop_recording(),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+        assert!(pca.sequence("nod").is_err());
+    }
+
+    #[test]
+    fn teleop() {
+        let (_, pca) = create_mock(200);
+        let left = Channel::try_from(2_u8).unwrap();
+        let gamepad = |axes: Vec<f64>, buttons: Vec<bool>| GamepadState { axes, buttons };
+
+        assert_eq!(pca.teleop_profile(), Some("normal".to_owned()));
+        assert_eq!(pca.teleop_profiles().len(), 2);
+
+        let configs = pca.teleop(&gamepad(vec![1.0, 1.0], vec![])).unwrap();
+        assert_eq!(configs.len(), 2);
+        assert_eq!(pca.config(left).unwrap().current_count, Some(4096));
+
+        // The "fine" profile's combo, then its rates
+        pca.teleop(&gamepad(vec![1.0, 1.0], vec![false, true, true]))
+            .unwrap();
+        assert_eq!(pca.teleop_profile(), Some("fine".to_owned()));
+        assert_eq!(pca.config(left).unwrap().current_count, Some(3072));
+
+        // A held combo does not override a profile selected via REST
+        pca.select_teleop_profile("normal").unwrap();
+        pca.teleop(&gamepad(vec![1.0, 1.0], vec![false, true, true]))
+            .unwrap();
+        assert_eq!(pca.teleop_profile(), Some("normal".to_owned()));
+
+        assert!(matches!(
+            pca.select_teleop_profile("aerobatic"),
+            Err(Pca9685Error::NoSuchProfileError(_))
+        ));
+    }
+
+    #[test]
+    fn select_rate() {
+        let (_, pca) = create_mock(200);
+        let left = Channel::try_from(2_u8).unwrap();
+        let gamepad = |buttons: Vec<bool>| GamepadState {
+            axes: vec![1.0],
+            buttons,
+        };
+
+        let mut config = pca.config(left).unwrap();
+        config.dual_rate = Some(DualRate {
+            low: 0.5,
+            high: 1.0,
+            selected: Rate::High,
+        });
+        pca.configure_channel(&config).unwrap();
+
+        let configs = pca.select_rate(&ChannelSelector::All, Rate::Low).unwrap();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].dual_rate.unwrap().selected, Rate::Low);
+
+        // pitch 1.0 positions the left elevon at 0.5 (3072), scaled by half
+        pca.teleop(&gamepad(vec![])).unwrap();
+        assert_eq!(pca.config(left).unwrap().current_count, Some(2560));
+
+        // The rate button toggles back to high (once per press)
+        let rate_button = vec![false, false, false, true];
+        pca.teleop(&gamepad(rate_button.clone())).unwrap();
+        pca.teleop(&gamepad(rate_button)).unwrap();
+        assert_eq!(pca.config(left).unwrap().current_count, Some(3072));
+
+        config.dual_rate.as_mut().unwrap().low = 0.0;
+        assert!(matches!(
+            pca.configure_channel(&config),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
     }
 
     #[test]
-    fn init() {
-        let test_output_frequency_hz = 200;
+    fn rehearse_failsafe() {
+        let (_, pca) = create_mock(200);
+        let channel = Channel::try_from(0_u8).unwrap();
 
-        let (config, pca) = create_mock(test_output_frequency_hz);
+        assert!(matches!(
+            pca.rehearse_failsafe(Duration::from_millis(10)),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
 
-        let expected_max_pw_ms = 1000.0 / test_output_frequency_hz as f64;
-        let single_count_duration_ms = expected_max_pw_ms / 4096.0;
-        let expected_prescale = 30; // per PCA9685 documented example using 200Hz
+        let mut config = pca.config(channel).unwrap();
+        config.failsafe = Some(FailsafeOutput::FullOff);
+        pca.configure_channel(&config).unwrap();
+        pca.set_pwm_count(channel, 1500).unwrap();
 
-        assert_eq!(pca.max_pw_ms(), expected_max_pw_ms);
-        assert_eq!(pca.single_count_duration_ms(), single_count_duration_ms);
-        assert_eq!(pca.device(), config.device);
-        assert_eq!(pca.address(), config.address);
+        let configs = pca.rehearse_failsafe(Duration::from_millis(10)).unwrap();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].current_count, None);
+
+        // The worker restores the prior output
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pca.config(channel).unwrap().current_count != Some(1500) {
+            assert!(Instant::now() < deadline, "prior output not restored");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        assert!(matches!(
+            pca.rehearse_failsafe(Duration::ZERO),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+    }
+
+    #[test]
+    fn step_test() {
+        let (_, pca) = create_mock(200);
+        let channel = Channel::try_from(0_u8).unwrap();
+        let test = StepTest {
+            from_count: 1000,
+            to_count: 2000,
+            settle_ms: 20,
+            duration_ms: 30,
+            sample_interval_us: 5000,
+        };
+
+        let samples = pca.step_test(channel, &test).unwrap();
+        assert!(samples.len() >= 2);
+        assert_eq!(samples[0].count, 1000);
+        assert!(samples[0].t_ms < 0.0);
+        assert_eq!(samples.last().unwrap().count, 2000);
+        assert!(samples.iter().all(|sample| sample.feedback.is_none()));
+        assert_eq!(pca.config(channel).unwrap().current_count, Some(2000));
+
+        let path = std::env::temp_dir().join("pca9685-step-test-feedback");
+        std::fs::write(&path, "1234\n").unwrap();
+        let mut config = pca.config(channel).unwrap();
+        config.feedback = Some(FeedbackConfig {
+            source: FeedbackSource::Path(path.to_str().unwrap().to_owned()),
+            min_raw: None,
+            max_raw: None,
+            verify: None,
+        });
+        pca.configure_channel(&config).unwrap();
+
+        let samples = pca.step_test(channel, &test).unwrap();
+        assert!(samples.iter().all(|sample| sample.feedback == Some(1234.0)));
+
+        assert!(matches!(
+            pca.step_test(
+                channel,
+                &StepTest {
+                    duration_ms: 0,
+                    ..test
+                }
+            ),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+        assert!(pca
+            .step_test(
+                channel,
+                &StepTest {
+                    to_count: 5000,
+                    ..test
+                }
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn watchdog() {
+        let (mut config, _) = create_mock(200);
+        config.watchdog = Some(WatchdogConfig {
+            timeout_ms: 50,
+            channels: vec![2],
+        });
+        let pca = Pca9685::null(&config);
+        let events = pca.subscribe();
+        let owned = Channel::try_from(0_u8).unwrap();
+        let unowned = Channel::try_from(1_u8).unwrap();
+        let throttle = Channel::try_from(2_u8).unwrap();
+
+        assert_eq!(pca.watchdog_timeout(), Some(Duration::from_millis(50)));
+        for channel in [owned, unowned] {
+            let mut channel_config = pca.config(channel).unwrap();
+            channel_config.failsafe = Some(FailsafeOutput::Count(1500));
+            pca.configure_channel(&channel_config).unwrap();
+        }
+
+        // The silent client's (owned) channels fail safe while another client
+        // keeps the watchdog fed
+        pca.transaction(owned, Some("rover"), |_| Ok(2000)).unwrap();
+        pca.set_pwm_count(unowned, 2000).unwrap();
+        pca.set_pwm_count(throttle, 2000).unwrap();
+        let deadline = Instant::now() + Duration::from_millis(200);
+        while Instant::now() < deadline {
+            pca.heartbeat(Some("operator"));
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(pca.config(owned).unwrap().current_count, Some(1500));
+        assert_eq!(pca.config(unowned).unwrap().current_count, Some(2000));
+
+        // Once every client is silent, every channel fails safe
+        let mut tripped = Vec::new();
+        while tripped.len() < 3 {
+            match events.recv_timeout(Duration::from_secs(5)) {
+                Ok(Pca9685Event::WatchdogTripped(client_id)) => tripped.push(client_id),
+                other => panic!("expected the watchdog to trip, got {:?}", other),
+            }
+        }
+        assert!(tripped.contains(&None));
+        assert_eq!(pca.config(unowned).unwrap().current_count, Some(1500));
+
+        // Selected channels without a failsafe output are driven full off
+        assert_eq!(pca.config(throttle).unwrap().current_count, None);
+
+        // A move is stopped, rather than overriding the failsafe
+        pca.set_pwm_count(unowned, 1000).unwrap();
+        pca.move_to(unowned, 1.0, Duration::from_secs(10)).unwrap();
+        loop {
+            match events.recv_timeout(Duration::from_secs(5)) {
+                Ok(Pca9685Event::WatchdogTripped(None)) => break,
+                Ok(_) => (),
+                Err(error) => panic!("expected the watchdog to trip, got {:?}", error),
+            }
+        }
+        assert!(!pca.moving(unowned));
+        assert_eq!(pca.config(unowned).unwrap().current_count, Some(1500));
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(pca.config(unowned).unwrap().current_count, Some(1500));
+    }
+
+    #[test]
+    fn idle() {
+        let (mut config, _) = create_mock(200);
+        config.idle = Some(IdleConfig {
+            after_ms: 100,
+            behaviors: vec![IdleBehavior {
+                channel: 0,
+                min_pct: 0.25,
+                max_pct: 0.75,
+                min_interval_ms: 10,
+                max_interval_ms: 20,
+            }],
+        });
+        let pca = Pca9685::null(&config);
+        let channel = Channel::try_from(0_u8).unwrap();
+        let mut channel_config = pca.config(channel).unwrap();
+        channel_config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        pca.configure_channel(&channel_config).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pca.config(channel).unwrap().owner != Some("idle".to_owned()) {
+            assert!(Instant::now() < deadline, "idle behavior not played");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(pca.idling());
+        let count = pca.config(channel).unwrap().current_count.unwrap();
+        assert!((1250..=1750).contains(&count));
+
+        // Yields to commands
+        pca.set_pwm_count(channel, 1100).unwrap();
+        assert!(!pca.idling());
+        assert_eq!(pca.config(channel).unwrap().current_count, Some(1100));
+
+        config.idle.as_mut().unwrap().behaviors[0].min_pct = 0.8;
+        assert!(!Pca9685::validate_config(&config).valid);
+    }
+
+    #[test]
+    fn rules() {
+        let (mut config, _) = create_mock(200);
+        config.presets = serde_yaml::from_str(
+            "
+            - name: safe
+              outputs: [{ channel: 1, command_type: FullOff }]
+            ",
+        )
+        .unwrap();
+        config.rules = serde_yaml::from_str(
+            "
+            - name: overdrive
+              when: !above { channel: 0, pct: 0.8, for_ms: 50 }
+              then: !full_on 7
+            - name: estop
+              when: !event emergency_stop
+              then: !preset safe
+            ",
+        )
+        .unwrap();
+        let pca = Pca9685::null(&config);
+        let events = pca.subscribe();
+        let channel = |raw_channel: u8| Channel::try_from(raw_channel).unwrap();
+
+        pca.set_pct(channel(0), 0.9).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pca.config(channel(7)).unwrap().owner != Some("rules".to_owned()) {
+            assert!(Instant::now() < deadline, "rule not carried out");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(
+            pca.config(channel(7)).unwrap().current_count,
+            Some(PCA_PWM_RESOLUTION)
+        );
+
+        // Carried out before returning
+        pca.set_pwm_count(channel(1), 1500).unwrap();
+        pca.emergency_stop();
+        assert_eq!(pca.config(channel(1)).unwrap().current_count, None);
+        assert!(matches!(
+            events.recv_timeout(Duration::from_secs(1)),
+            Ok(Pca9685Event::EmergencyStop)
+        ));
+
+        config.rules[1].then = RuleAction::Preset("unsafe".to_string());
+        assert!(!Pca9685::validate_config(&config).valid);
+    }
+
+    #[test]
+    fn startup() {
+        let (mut config, _) = create_mock(200);
+        config.startup = Some(StartupConfig {
+            delay_ms: 50,
+            ramp_ms: 200,
+        });
+        let channel = |channel: Channel, current_count: u16| {
+            let mut channel_config = Pca9685::null(&config).config(channel).unwrap();
+            channel_config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+            channel_config.current_count = Some(current_count);
+            channel_config
+        };
+        config.channels = vec![channel(Channel::C0, 2000), channel(Channel::C1, 3000)];
+        let pca = Pca9685::null(&config);
+
+        // Off until the delay has passed, then ramping up from the lower limit
+        assert_eq!(pca.config(Channel::C0).unwrap().current_count, None);
+        assert!(pca.moving(Channel::C0));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut counts = Vec::new();
+        while pca.moving(Channel::C0) {
+            assert!(Instant::now() < deadline, "startup ramp not finished");
+            counts.extend(pca.config(Channel::C0).unwrap().current_count);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(counts.first().unwrap() < &1500);
+        assert_eq!(pca.config(Channel::C0).unwrap().current_count, Some(2000));
+
+        // Outside its limits
+        assert!(!pca.moving(Channel::C1));
+        assert_eq!(pca.config(Channel::C1).unwrap().current_count, None);
+    }
+
+    #[test]
+    fn trim() {
+        let (config, _) = create_mock(200);
+        let pca = Pca9685::tracing(&config);
+        let channel = Channel::C0;
+        let mut channel_config = pca.config(channel).unwrap();
+        channel_config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        channel_config.trim_counts = 25;
+        pca.configure_channel(&channel_config).unwrap();
+        pca.take_trace();
+
+        // Limits apply to the commanded count, the trim to what is written
+        let config = pca.set_pwm_count(channel, 2000).unwrap();
+        assert_eq!(config.current_count, Some(2000));
+        assert_eq!(
+            pca.take_trace(),
+            vec![TraceWrite {
+                channel: 0,
+                count: Some(2025)
+            }]
+        );
+        assert!(pca.set_pwm_count(channel, 2010).is_err());
+
+        // Within ±4095
+        channel_config.trim_counts = -4096;
+        assert!(pca.configure_channel(&channel_config).is_err());
+    }
+
+    #[test]
+    fn move_to_not_settled() {
+        let (_, pca) = create_mock(200);
+        let channel = Channel::C0;
+        let mut config = pca.config(channel).unwrap();
+        config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        pca.configure_channel(&config).unwrap();
+        pca.set_pwm_count(channel, 1000).unwrap();
+        assert!(pca.is_settled(channel).unwrap());
+
+        pca.move_to(channel, 1.0, Duration::from_millis(200))
+            .unwrap();
+        assert!(!pca.is_settled(channel).unwrap());
+        assert!(pca.metrics()[0].motion_in_progress);
+
+        // Settled only once the move has arrived
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !pca.is_settled(channel).unwrap() {
+            assert!(Instant::now() < deadline, "move not finished");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(pca.config(channel).unwrap().current_count, Some(2000));
+        assert!(!pca.metrics()[0].motion_in_progress);
+    }
+
+    #[test]
+    fn move_to() {
+        let (_, pca) = create_mock(200);
+        let channel = Channel::C0;
+        let mut config = pca.config(channel).unwrap();
+        config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        pca.configure_channel(&config).unwrap();
+
+        // Without an output to move from, the target is taken immediately
+        let config = pca.move_to(channel, 0.0, Duration::from_secs(1)).unwrap();
+        assert_eq!(config.current_count, Some(1000));
+        assert!(!pca.moving(channel));
+
+        assert!(matches!(
+            pca.move_to(channel, 1.5, Duration::from_millis(100)),
+            Err(Pca9685Error::PercentOfRangeError(_))
+        ));
+        assert!(!pca.moving(channel));
+
+        let target = pca
+            .move_to(channel, 1.0, Duration::from_millis(200))
+            .unwrap();
+        assert_eq!(target.current_count, Some(2000));
+        assert!(pca.moving(channel));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut intermediate = false;
+        while pca.moving(channel) {
+            assert!(Instant::now() < deadline, "move not finished");
+            let count = pca.config(channel).unwrap().current_count.unwrap();
+            intermediate |= count > 1000 && count < 2000;
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(intermediate);
+        assert_eq!(pca.config(channel).unwrap().current_count, Some(2000));
+        assert_eq!(
+            pca.config(channel).unwrap().owner.as_deref(),
+            Some("motion")
+        );
+
+        pca.move_to(channel, 0.0, Duration::from_secs(10)).unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+        let stopped = pca.stop_move(channel).unwrap().current_count.unwrap();
+        assert!(!pca.moving(channel));
+        assert!(stopped < 2000 && stopped > 1000);
+
+        // Commanding a moving Channel directly ends the move
+        pca.move_to(channel, 1.0, Duration::from_secs(10)).unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(pca.full_off(channel).unwrap().current_count, None);
+        assert!(!pca.moving(channel));
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(pca.config(channel).unwrap().current_count, None);
+
+        pca.set_pwm_count(channel, 1000).unwrap();
+        pca.move_to(channel, 1.0, Duration::from_secs(10)).unwrap();
+        pca.relax(&ChannelSelector::Channel(0)).unwrap();
+        assert!(!pca.moving(channel));
+    }
+
+    #[test]
+    fn conversion_table() {
+        let (config, _) = create_mock(200);
+        let pca = Pca9685::null(&config);
+        let mut channel_config = pca.config(Channel::C3).unwrap();
+        channel_config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        pca.configure_channel(&channel_config).unwrap();
+
+        let table = pca
+            .conversion_table(Channel::C3, DisplayUnit::Degrees(180.0), 45.0)
+            .unwrap();
+        assert_eq!(
+            table
+                .iter()
+                .map(|row| (row.input, row.count))
+                .collect::<Vec<(f64, u16)>>(),
+            vec![
+                (0.0, 1000),
+                (45.0, 1250),
+                (90.0, 1500),
+                (135.0, 1750),
+                (180.0, 2000)
+            ]
+        );
+        assert_eq!(table[2].pw_ms, 1500.0 * pca.single_count_duration_ms());
+
+        let table = pca
+            .conversion_table(Channel::C3, DisplayUnit::Percent, 30.0)
+            .unwrap();
+        assert_eq!(table.len(), 4);
+        assert_eq!(table[3].input, 90.0);
+        assert_eq!(table[3].count, 1900);
+
+        assert!(matches!(
+            pca.conversion_table(Channel::C3, DisplayUnit::Ms, 0.0),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+        assert!(matches!(
+            pca.conversion_table(Channel::C3, DisplayUnit::Counts, 0.1),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+    }
+
+    #[test]
+    fn command_selected() {
+        let (_, pca) = create_mock(200);
+        for (channel, tags) in [
+            (Channel::C0, ["leg", "left"]),
+            (Channel::C2, ["leg", "right"]),
+        ] {
+            let mut config = pca.config(channel).unwrap();
+            config.tags = tags.into_iter().map(String::from).collect();
+            pca.configure_channel(&config).unwrap();
+        }
+
+        let leg = ChannelSelector::Tag("leg".to_string());
+        assert_eq!(pca.select(&leg).unwrap(), vec![Channel::C0, Channel::C2]);
+        let configs = pca
+            .command_selected(&leg, CommandType::PulseCount, Some(1024.0), None)
+            .unwrap();
+        assert_eq!(configs.len(), 2);
+        assert!(configs
+            .iter()
+            .all(|config| config.current_count == Some(1024)));
+        assert!(matches!(
+            pca.command_selected(
+                &ChannelSelector::Tag("arm".to_string()),
+                CommandType::FullOff,
+                None,
+                None
+            ),
+            Err(Pca9685Error::NoSuchTagError(tag)) if tag == "arm"
+        ));
+
+        // Tags must be usable in a selector
+        let mut config = pca.config(Channel::C1).unwrap();
+        config.tags = vec!["left leg".to_string()];
+        assert!(matches!(
+            pca.configure_channel(&config),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+    }
+
+    #[test]
+    fn set_group_pct() {
+        let (mut config, _) = create_mock(200);
+        config.groups = vec![ChannelGroup {
+            name: "legs".to_owned(),
+            channels: vec![0, 1],
+        }];
+        let pca = Pca9685::null(&config);
+
+        let configs = pca.set_group_pct("legs", 0.5).unwrap();
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].current_count, configs[1].current_count);
+        assert!(configs[0].current_count.is_some());
+        assert!(matches!(
+            pca.set_group_pct("arms", 0.5),
+            Err(Pca9685Error::NoSuchGroupError(_))
+        ));
+
+        // One Channel out of its limits leaves the whole group untouched
+        let mut channel_config = pca.config(Channel::C1).unwrap();
+        channel_config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        pca.configure_channel(&channel_config).unwrap();
+        assert!(matches!(
+            pca.set_group_pw_ms("legs", 4.0),
+            Err(Pca9685Error::CustomLimitsError(..))
+        ));
+        assert_eq!(
+            pca.config(Channel::C0).unwrap().current_count,
+            configs[0].current_count
+        );
+    }
+
+    #[test]
+    fn gait() {
+        let (mut config, _) = create_mock(200);
+        config.groups = vec![ChannelGroup {
+            name: "leg".to_owned(),
+            channels: vec![0, 1],
+        }];
+        config.gaits = vec![GaitConfig {
+            name: "walk".to_owned(),
+            pattern: GaitPattern::Wave,
+            legs: vec![GaitLeg {
+                group: "leg".to_owned(),
+                reversed: false,
+            }],
+            stride_pct: 0.2,
+            lift_pct: 0.2,
+            period_ms: 400,
+        }];
+        let pca = Pca9685::null(&config);
+        let channel = Channel::try_from(0_u8).unwrap();
+
+        assert_eq!(pca.gaits().len(), 1);
+        assert!(matches!(
+            pca.start_gait("run", 1.0),
+            Err(Pca9685Error::NoSuchGaitError(_))
+        ));
+        assert!(matches!(
+            pca.start_gait("walk", 8.0),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+        assert!(matches!(
+            pca.set_gait_speed("walk", 2.0),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+
+        let status = pca.start_gait("walk", 1.0).unwrap();
+        assert_eq!(status.sequence, "gait/walk");
+        assert_eq!(status.loop_region.unwrap().end_ms, 400);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pca.config(channel).unwrap().owner != Some("sequence/gait/walk".to_owned()) {
+            assert!(Instant::now() < deadline, "gait not walked");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        // Walks until stopped
+        std::thread::sleep(Duration::from_millis(450));
+        assert_eq!(pca.set_gait_speed("walk", 2.0).unwrap().rate, 2.0);
+        assert!(pca.stop_gait("walk").is_ok());
+        assert!(pca.playback().is_none());
+
+        config.gaits[0].legs[0].group = "arm".to_owned();
+        assert!(!Pca9685::validate_config(&config).valid);
+    }
+
+    #[test]
+    fn preview() {
+        let (_, pca) = create_mock(200);
+        let channel = Channel::try_from(0_u8).unwrap();
+        let command = |command_type: CommandType, value: Option<f64>| ChannelCommand {
+            channel,
+            command_type,
+            value,
+            basis: None,
+            client_id: None,
+        };
+
+        pca.configure_channel(&crate::ChannelConfig {
+            channel,
+            current_count: None,
+            custom_limits: Some(ChannelLimits::from_count_limits(1000, 2000)),
+            owner: None,
+            calibration: None,
+            inverted: false,
+            trim_counts: 0,
+            esc: None,
+            tags: Vec::new(),
+            reduced_hold: None,
+            thermal_limits: None,
+            dual_rate: None,
+            failsafe: None,
+            display_unit: None,
+            display_value: None,
+            name: None,
+            feedback: None,
+            pid: None,
+            input_shaping: None,
+            low_pass: None,
+        })
+        .unwrap();
+        pca.set_pwm_count(channel, 1200).unwrap();
+
+        let preview = pca
+            .preview(channel, &command(CommandType::Percent, Some(0.5)))
+            .unwrap();
+        assert_eq!(preview.current_count, Some(1500));
+        assert_eq!(
+            pca.preview(channel, &command(CommandType::FullOff, None))
+                .unwrap()
+                .current_count,
+            None
+        );
+        assert_eq!(pca.config(channel).unwrap().current_count, Some(1200));
+
+        assert!(matches!(
+            pca.preview(channel, &command(CommandType::PulseCount, Some(2500.0))),
+            Err(Pca9685Error::CustomLimitsError(_, _))
+        ));
+        assert!(matches!(
+            pca.preview(
+                Channel::try_from(1_u8).unwrap(),
+                &command(CommandType::PulseCount, Some(1500.0))
+            ),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+    }
+
+    #[test]
+    fn revised_config() {
+        let (_, pca) = create_mock(200);
+        let channel = Channel::try_from(0_u8).unwrap();
+
+        let (config, revision) = pca.revised_config(channel).unwrap();
+        assert_eq!(pca.revised_config(channel).unwrap(), (config, revision));
+
+        pca.set_pwm_count(channel, 1500).unwrap();
+        let (config, next_revision) = pca.revised_config(channel).unwrap();
+        assert_eq!(config.current_count, Some(1500));
+        assert!(next_revision > revision);
+
+        // Unchanged by an identical write
+        pca.set_pwm_count(channel, 1500).unwrap();
+        assert_eq!(pca.revised_config(channel).unwrap().1, next_revision);
+    }
+
+    #[test]
+    fn validate_config() {
+        let (mut config, _) = create_mock(200);
+        assert_eq!(
+            Pca9685::validate_config(&config),
+            ConfigValidation {
+                valid: true,
+                findings: vec![]
+            }
+        );
+
+        let mut channel_config = Pca9685::null(&config)
+            .config(Channel::try_from(0_u8).unwrap())
+            .unwrap();
+        channel_config.custom_limits = Some(ChannelLimits {
+            count_limits: None,
+            pw_limits: Some(ChannelPulseWidthLimits {
+                min_on_ms: 1.0,
+                max_on_ms: 50.0,
+            }),
+        });
+        config.channels = vec![channel_config.clone(), channel_config];
+        config.output_frequency_hz = 1000;
+        config.watchdog = Some(WatchdogConfig {
+            timeout_ms: 0,
+            channels: Vec::new(),
+        });
+        config.devices = vec![DeviceConfig {
+            device: config.device.clone(),
+            address: config.address,
+            channels: Vec::new(),
+        }];
+
+        let validation = Pca9685::validate_config(&config);
+        assert!(!validation.valid);
+        let paths: Vec<(FindingSeverity, &str)> = validation
+            .findings
+            .iter()
+            .map(|finding| (finding.severity, finding.path.as_str()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                (FindingSeverity::Warning, "output_frequency_hz"),
+                (FindingSeverity::Error, "channels[0]"),
+                (FindingSeverity::Error, "channels[1]"),
+                (FindingSeverity::Error, "channels[1]"),
+                (FindingSeverity::Error, "devices[0]"),
+                (FindingSeverity::Error, "watchdog"),
+            ]
+        );
+    }
+
+    #[test]
+    fn init_prescale() {
+        let (mut config, _) = create_mock(200);
+        config.output_frequency_hz = 0;
+        config.prescale = Some(121);
+
+        let pca = Pca9685::null(&config);
+        assert_eq!(pca.prescale(), 121);
+        assert_eq!(pca.output_frequency_hz(), 50);
+        assert_eq!(pca.max_pw_ms(), 1000.0 * 4096.0 * 122.0 / 25_000_000.0);
+
+        config.output_frequency_hz = 50;
+        let validation = Pca9685::validate_config(&config);
+        assert!(!validation.valid);
+        assert_eq!(validation.findings[0].path, "prescale");
+    }
+
+    #[test]
+    #[cfg(feature = "linux")]
+    fn degraded() {
+        let (config, _) = create_mock(200);
+
+        assert!(matches!(
+            Pca9685::try_new(&config),
+            Err(Pca9685Error::DeviceInitError(_))
+        ));
+
+        let pca = Pca9685::new_or_degraded(&config, Duration::from_secs(60));
+        assert!(pca.device_error().unwrap().contains("/dev/foo"));
         assert_eq!(pca.output_frequency_hz(), config.output_frequency_hz);
-        assert_eq!(pca.prescale(), expected_prescale);
-        assert_eq!(pca.output_type(), OutputDriver::TotemPole);
+    }
+
+    #[test]
+    fn configure_channel_name() {
+        let (_, pca) = create_mock(200);
+        let mut config = pca.config(Channel::C3).unwrap();
+        assert_eq!(pca.log_target(Channel::C3).unwrap(), "Channel C3");
+
+        config.name = Some("left_elbow".to_string());
+        pca.configure_channel(&config).unwrap();
+        assert_eq!(pca.log_target(Channel::C3).unwrap(), "left_elbow");
+        assert_eq!(pca.config(Channel::C3).unwrap().name, config.name);
+
+        config.channel = Channel::C4;
+        assert!(matches!(
+            pca.configure_channel(&config),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+
+        config.name = Some("left elbow".to_string());
+        assert!(matches!(
+            pca.configure_channel(&config),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+
+        // Named Channels may be looked up and commanded by name
+        assert_eq!(pca.channel_by_name("left_elbow").unwrap(), Channel::C3);
+        assert_eq!(
+            pca.select(&ChannelSelector::Name("left_elbow".to_string()))
+                .unwrap(),
+            vec![Channel::C3]
+        );
+        assert_eq!(
+            pca.config_by_name("left_elbow").unwrap().name.as_deref(),
+            Some("left_elbow")
+        );
+        let config = pca.set_pct_by_name("left_elbow", 1.0).unwrap();
+        assert_eq!(config.channel, Channel::C3);
+        assert_eq!(config.current_count, Some(config.limits().1));
+        assert!(matches!(
+            pca.set_pct_by_name("right_elbow", 0.5),
+            Err(Pca9685Error::NoSuchChannelNameError(name)) if name == "right_elbow"
+        ));
+    }
+
+    /// A Channel's output looped back to an input: pulses `width` long every
+    /// `period` since `started`
+    struct SimulatedLoopback {
+        started: Instant,
+        period: Duration,
+        width: Duration,
+    }
+
+    impl GpioInput for SimulatedLoopback {
+        fn is_high(&mut self) -> Pca9685Result<bool> {
+            let elapsed = self.started.elapsed().as_nanos() % self.period.as_nanos();
+            Ok(elapsed < self.width.as_nanos())
+        }
+    }
+
+    #[test]
+    fn measure_loopback() {
+        // Periods long enough that the scheduler rarely costs a whole cycle
+        let (_, pca) = create_mock(100);
+        let mut input = SimulatedLoopback {
+            started: Instant::now(),
+            period: Duration::from_secs_f64(pca.max_pw_ms() * 1.02 / 1000.0),
+            width: Duration::from_secs_f64(1024.0 * pca.single_count_duration_ms() / 1000.0),
+        };
+        assert!(pca.measure_loopback(Channel::C0, &mut input, 30).is_err());
+
+        // The oscillator running 2% slow; as the input is sampled in real
+        // time, cycles lost to the scheduler only ever make it seem slower
+        pca.set_pwm_count(Channel::C0, 1024).unwrap();
+        let report = pca.measure_loopback(Channel::C0, &mut input, 30).unwrap();
+        assert_eq!(report.cycles, 30);
+        assert_eq!(
+            report.expected_pulse_width_ms,
+            1024.0 * pca.single_count_duration_ms()
+        );
+        assert_eq!(report.expected_period_ms, pca.max_pw_ms());
+        assert!(report.period.min_ms <= report.period.mean_ms);
+        assert!(report.period.mean_ms <= report.period.max_ms);
+        assert!(report.frequency_error_pct < -1.5, "{:?}", report);
+    }
+
+    #[test]
+    fn broadcast() {
+        let (mut config, _) = create_mock(200);
+        let pca = Pca9685::tracing(&config);
+        let command = ChannelCommand {
+            channel: Channel::C0,
+            command_type: CommandType::PulseCount,
+            value: Some(1024.0),
+            basis: None,
+            client_id: None,
+        };
+
+        // Written as any other command, but to the all-call address
+        let result = pca.broadcast(&command).unwrap();
+        assert_eq!(result.current_count, Some(1024));
+        assert_eq!(
+            pca.take_trace(),
+            vec![TraceWrite {
+                channel: 0,
+                count: Some(1024)
+            }]
+        );
+
+        config.all_call_address = None;
+        let pca = Pca9685::tracing(&config);
+        assert!(matches!(
+            pca.broadcast(&command),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+        assert!(pca.take_trace().is_empty());
+    }
+
+    #[test]
+    fn disabled_output() {
+        let (mut config, _) = create_mock(200);
+        config.disabled_output = DisabledOutput::High;
+        let pca = Pca9685::tracing(&config);
+        assert_eq!(pca.disabled_output(), DisabledOutput::High);
+
+        // Kept across a reset
+        pca.set_disabled_output(DisabledOutput::HighImpedance)
+            .unwrap();
+        pca.reset().unwrap();
+        assert_eq!(pca.disabled_output(), DisabledOutput::HighImpedance);
+
+        let differences = pca.diff_config(&config).unwrap();
+        assert_eq!(differences.len(), 1);
+        assert_eq!(differences[0].path, "disabled_output");
+    }
+
+    #[test]
+    fn reset() {
+        let (config, _) = create_mock(200);
+        let pca = Pca9685::tracing(&config);
+        pca.set_pwm_count(Channel::C0, 1024).unwrap();
+        pca.full_on(Channel::C1).unwrap();
+        pca.take_trace();
+
+        // Every Channel's output is rewritten
+        pca.reset().unwrap();
+        let trace = pca.take_trace();
+        assert_eq!(trace.len(), 16);
+        assert_eq!(
+            &trace[..3],
+            &[
+                TraceWrite {
+                    channel: 0,
+                    count: Some(1024)
+                },
+                TraceWrite {
+                    channel: 1,
+                    count: Some(PCA_PWM_RESOLUTION)
+                },
+                TraceWrite {
+                    channel: 2,
+                    count: None
+                },
+            ]
+        );
+
+        // ...once resumed, if paused
+        pca.pause(PauseMode::Queue);
+        pca.reset().unwrap();
+        assert!(pca.take_trace().is_empty());
+        pca.resume().unwrap();
+        assert_eq!(pca.take_trace(), trace);
+    }
+
+    #[test]
+    fn sleep() {
+        let (_, pca) = create_mock(200);
+        assert!(!pca.is_asleep());
+        pca.sleep().unwrap();
+        assert!(pca.is_asleep());
+
+        // Commands are accepted while asleep
+        pca.set_pwm_count(Channel::C0, 1024).unwrap();
+        assert_eq!(pca.config(Channel::C0).unwrap().current_count, Some(1024));
+
+        pca.wake().unwrap();
+        assert!(!pca.is_asleep());
+        assert_eq!(pca.config(Channel::C0).unwrap().current_count, Some(1024));
+    }
+
+    #[test]
+    fn esc() {
+        let (_, pca) = create_mock(200);
+        let mut config = pca.config(Channel::C1).unwrap();
+        config.esc = Some(EscConfig { arming_ms: 50 });
+        assert!(pca.configure_channel(&config).is_err());
+        config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        pca.configure_channel(&config).unwrap();
+        assert!(pca.arm(Channel::C0).is_err());
+
+        // Throttle is refused until armed, minimum throttle is not
+        assert!(matches!(
+            pca.set_pct(Channel::C1, 0.5),
+            Err(Pca9685Error::ChannelDisarmedError(1))
+        ));
+        assert!(pca.set_pct(Channel::C1, 0.0).is_ok());
+        let config = pca.arm(Channel::C1).unwrap();
+        assert_eq!(config.current_count, Some(1000));
+        assert!(pca.set_pct(Channel::C1, 0.5).is_err());
+        assert!(!pca.stats(Channel::C1).unwrap().armed);
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(pca.stats(Channel::C1).unwrap().armed);
+        assert_eq!(
+            pca.set_pct(Channel::C1, 0.5).unwrap().current_count,
+            Some(1500)
+        );
+
+        // Disarmed, returning to minimum throttle, as by losing its signal
+        assert_eq!(pca.disarm(Channel::C1).unwrap().current_count, Some(1000));
+        assert!(pca.set_pct(Channel::C1, 0.5).is_err());
+        pca.arm(Channel::C1).unwrap();
+        std::thread::sleep(Duration::from_millis(60));
+        pca.full_off(Channel::C1).unwrap();
+        assert!(!pca.stats(Channel::C1).unwrap().armed);
+        assert!(pca.set_pct(Channel::C1, 0.5).is_err());
+    }
+
+    #[test]
+    fn set_maintenance() {
+        let (_, pca) = create_mock(200);
+        let mut config = pca.config(Channel::C1).unwrap();
+        config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 3000));
+        config.failsafe = Some(FailsafeOutput::Count(1500));
+        pca.configure_channel(&config).unwrap();
+        pca.set_pwm_count(Channel::C1, 2500).unwrap();
+
+        let config = pca.set_maintenance(Channel::C1, true).unwrap();
+        assert_eq!(config.current_count, Some(1500));
+        assert_eq!(pca.maintenance(), vec![Channel::C1]);
+        assert!(pca.stats(Channel::C1).unwrap().under_maintenance);
+        assert!(matches!(
+            pca.set_pwm_count(Channel::C1, 2000),
+            Err(Pca9685Error::ChannelMaintenanceError(1))
+        ));
+
+        pca.set_maintenance(Channel::C1, false).unwrap();
+        assert!(pca.maintenance().is_empty());
+        assert_eq!(
+            pca.set_pwm_count(Channel::C1, 2000).unwrap().current_count,
+            Some(2000)
+        );
+    }
+
+    #[test]
+    fn pause() {
+        let (_, pca) = create_mock(200);
+        let channel = Channel::try_from(0_u8).unwrap();
+
+        pca.pause(PauseMode::Queue);
+        assert_eq!(pca.paused(), Some(PauseMode::Queue));
+        assert_eq!(
+            pca.set_pwm_count(channel, 2000).unwrap().current_count,
+            Some(2000)
+        );
+
+        let resumed = pca.resume().unwrap();
+        assert_eq!(pca.paused(), None);
+        assert_eq!(resumed.len(), 1);
+        assert_eq!(resumed[0].current_count, Some(2000));
+
+        pca.pause(PauseMode::Reject);
+        assert!(matches!(
+            pca.set_pwm_count(channel, 1000),
+            Err(Pca9685Error::PausedError)
+        ));
+        assert!(pca.resume().unwrap().is_empty());
+        assert_eq!(
+            pca.set_pwm_count(channel, 1000).unwrap().current_count,
+            Some(1000)
+        );
+    }
+
+    #[test]
+    fn snapshot() {
+        let (_, pca) = create_mock(200);
+        let channel = Channel::try_from(0_u8).unwrap();
+
+        pca.set_pwm_count(channel, 2000).unwrap();
+        let snapshot = pca.snapshot();
+        assert_eq!(snapshot.channels[0].current_count, Some(2000));
+
+        pca.set_pwm_count(channel, 1000).unwrap();
+        let restored = pca.restore(&snapshot).unwrap();
+        assert_eq!(restored[0].current_count, Some(2000));
+        assert_eq!(pca.config(channel).unwrap().current_count, Some(2000));
+
+        let mut duplicated = snapshot.clone();
+        duplicated.channels.push(snapshot.channels[0].clone());
+        assert!(matches!(
+            pca.restore(&duplicated),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+
+        let mut out_of_limits = snapshot;
+        out_of_limits.channels[0].custom_limits = Some(ChannelLimits::from_count_limits(100, 200));
+        assert!(matches!(
+            pca.restore(&out_of_limits),
+            Err(Pca9685Error::CustomLimitsError(..))
+        ));
+        assert_eq!(pca.config(channel).unwrap().current_count, Some(2000));
+    }
+
+    #[test]
+    fn debug_report() {
+        let (_, pca) = create_mock(200);
+        let mut config = pca.config(Channel::C1).unwrap();
+        config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        config.name = Some("pan".to_string());
+        pca.configure_channel(&config).unwrap();
+
+        pca.execute(&ChannelCommand {
+            channel: Channel::C0,
+            command_type: CommandType::PulseCount,
+            value: Some(1500.0),
+            basis: None,
+            client_id: Some("teleop".to_string()),
+        })
+        .unwrap();
+        pca.set_pct(Channel::C1, 0.0).unwrap();
+        pca.move_to(Channel::C1, 1.0, Duration::from_secs(10))
+            .unwrap();
+        pca.pause(PauseMode::Reject);
+
+        let report = pca.debug_report();
+        assert_eq!(report.channels.len(), 16);
+        assert_eq!(report.paused, Some(PauseMode::Reject));
+        assert_eq!(report.last_driver_error, None);
+        assert_eq!(report.channels[0].config.owner.as_deref(), Some("teleop"));
+        assert_eq!(report.channels[0].metrics.commands_total["PulseCount"], 1);
+        assert!(!report.channels[0].moving);
+        assert!(report.channels[1].moving);
+
+        let text = report.to_string();
+        assert!(text.contains("paused: Reject"));
+        assert!(text.contains("Channel 0: count 1500, owned by teleop"));
+        assert!(text.contains("Channel 1 (pan): count 1000"));
+        assert!(text.contains(", moving"));
+    }
+
+    #[test]
+    fn restore_backup() {
+        let (config, pca) = create_mock(200);
+        let channel = Channel::try_from(0_u8).unwrap();
+        let mut channel_config = pca.config(channel).unwrap();
+        channel_config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        pca.configure_channel(&channel_config).unwrap();
+        pca.set_pwm_count(channel, 1500).unwrap();
+        pca.configure_sequence(&SequenceConfig {
+            name: "wave".to_owned(),
+            keyframes: vec![Keyframe {
+                t_ms: 0,
+                channel: 0,
+                command_type: CommandType::PulseCount,
+                value: Some(2000.0),
+            }],
+            steps: vec![],
+            duration_ms: None,
+        })
+        .unwrap();
+        let backup = pca.backup(&config);
+        assert_eq!(backup.config.channels.len(), 1);
+
+        // Onto fresh hardware
+        let (_, fresh) = create_mock(200);
+        let restored = fresh.restore_backup(&backup).unwrap();
+        assert_eq!(restored[0].current_count, Some(1500));
+        assert_eq!(
+            fresh.config(channel).unwrap().custom_limits,
+            channel_config.custom_limits
+        );
+        assert_eq!(fresh.sequences(), backup.sequences);
+
+        // Nothing is applied unless everything is valid
+        let (_, fresh) = create_mock(200);
+        let mut invalid = backup.clone();
+        invalid.sequences[0].keyframes[0].value = Some(2500.0);
+        assert!(matches!(
+            fresh.restore_backup(&invalid),
+            Err(Pca9685Error::CustomLimitsError(_, _))
+        ));
+        let mut invalid = backup;
+        invalid.config.output_frequency_hz = 0;
+        assert!(matches!(
+            fresh.restore_backup(&invalid),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+        assert_eq!(fresh.config(channel).unwrap().current_count, None);
+        assert!(fresh.sequences().is_empty());
+    }
+
+    #[test]
+    fn diff_config() {
+        let (config, pca) = create_mock(200);
+        assert!(pca.diff_config(&config).unwrap().is_empty());
+
+        // Outputs aren't configuration
+        pca.set_pwm_count(Channel::C0, 2000).unwrap();
+        assert!(pca.diff_config(&config).unwrap().is_empty());
+
+        let mut channel_config = pca.config(Channel::C1).unwrap();
+        channel_config.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        pca.configure_channel(&channel_config).unwrap();
+        let mut mixers = pca.mixers();
+        mixers[0].expo.insert("roll".to_owned(), 0.5);
+        pca.configure_mixers(&mixers).unwrap();
+
+        let differences = pca.diff_config(&config).unwrap();
+        let paths: Vec<&str> = differences
+            .iter()
+            .map(|difference| difference.path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["channels[1].custom_limits", "mixers[elevons]"]);
+        assert_eq!(differences[0].file, serde_yaml::Value::Null);
+
+        let mut slower = config;
+        slower.output_frequency_hz = 50;
+        assert_eq!(pca.diff_config(&slower).unwrap()[0].path, "prescale");
+    }
+
+    #[test]
+    fn with_raw() {
+        let (_, pca) = create_mock(200);
+
+        assert!(matches!(
+            pca.with_raw(|raw| raw.set_channel_full_on(Channel::C0, 0)),
+            Err(Pca9685Error::DeviceInitError(_))
+        ));
+    }
+
+    /// Each write's address and bytes
+    type I2cWrites = Vec<(u8, Vec<u8>)>;
+
+    /// An I2C bus recording every write made to it, for host-side tests
+    #[derive(Clone, Default)]
+    struct RecordingI2c(Arc<Mutex<I2cWrites>>);
+
+    impl Write for RecordingI2c {
+        type Error = Infallible;
+
+        fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Infallible> {
+            self.0.lock().unwrap().push((address, bytes.to_vec()));
+            Ok(())
+        }
+    }
+
+    impl WriteRead for RecordingI2c {
+        type Error = Infallible;
+
+        fn write_read(
+            &mut self,
+            address: u8,
+            bytes: &[u8],
+            buffer: &mut [u8],
+        ) -> Result<(), Infallible> {
+            self.0.lock().unwrap().push((address, bytes.to_vec()));
+            buffer.fill(0);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn with_i2c() {
+        let (config, _) = create_mock(200);
+        let i2c = RecordingI2c::default();
+        let pca: Pca9685<RecordingI2c> = Pca9685::with_i2c(&config, i2c.clone()).unwrap();
+        let take = || std::mem::take(&mut *i2c.0.lock().unwrap());
+
+        // Configured at the device's address
+        let writes = take();
+        assert!(writes.iter().all(|(address, _)| *address == config.address));
+        assert!(writes.contains(&(config.address, vec![0xfe, 0x1e])));
+
+        // C0's LED0_ON_L..LED0_OFF_H, rising at 0 and falling at 1024
+        pca.set_pwm_count(Channel::C0, 1024).unwrap();
+        assert_eq!(
+            take().last(),
+            Some(&(config.address, vec![0x06, 0x00, 0x00, 0x00, 0x04]))
+        );
+
+        // SWRST to the general call address, ahead of reconfiguring
+        pca.reset().unwrap();
+        assert_eq!(take().first(), Some(&(0x00, vec![0x06])));
+
+        // The driver, on the same bus
+        pca.with_raw(|raw| raw.set_channel_full_on(Channel::C1, 0))
+            .unwrap();
+        assert_eq!(
+            take().last(),
+            Some(&(config.address, vec![0x0a, 0x00, 0x10]))
+        );
+    }
+
+    #[test]
+    fn transaction() {
+        let (_, pca) = create_mock(200);
+        let channel = Channel::try_from(0_u8).unwrap();
+
+        pca.set_pwm_count(channel, 100).unwrap();
+
+        let config = pca
+            .transaction(
+                channel,
+                None,
+                |config| Ok(config.current_count.unwrap() * 2),
+            )
+            .unwrap();
+        assert_eq!(config.current_count.unwrap(), 200);
+
+        // A failing transaction leaves the output unchanged
+        assert!(pca
+            .transaction(channel, None, |_| {
+                Err(Pca9685Error::PercentOfRangeError(2.0))
+            })
+            .is_err());
+        assert_eq!(pca.config(channel).unwrap().current_count.unwrap(), 200);
+    }
+
+    #[test]
+    fn apply_atomic() {
+        let (_, pca) = create_mock(200);
+        let command = |channel: u8, value: f64| ChannelCommand {
+            channel: Channel::try_from(channel).unwrap(),
+            command_type: CommandType::PulseCount,
+            value: Some(value),
+            basis: None,
+            client_id: None,
+        };
+
+        pca.configure_channel(&crate::ChannelConfig {
+            channel: Channel::try_from(1_u8).unwrap(),
+            current_count: None,
+            custom_limits: Some(ChannelLimits::from_count_limits(1000, 2000)),
+            owner: None,
+            calibration: None,
+            inverted: false,
+            trim_counts: 0,
+            esc: None,
+            tags: Vec::new(),
+            reduced_hold: None,
+            thermal_limits: None,
+            dual_rate: None,
+            failsafe: None,
+            display_unit: None,
+            display_value: None,
+            name: None,
+            feedback: None,
+            pid: None,
+            input_shaping: None,
+            low_pass: None,
+        })
+        .unwrap();
+
+        // The second command violates channel 1's limits, so nothing is written
+        assert!(pca
+            .apply(&[command(0, 100.0), command(1, 3000.0)], true)
+            .is_err());
+        assert!(pca.config(Channel::C0).unwrap().current_count.is_none());
+
+        // Channels may only appear once in an atomic batch
+        assert!(pca
+            .apply(&[command(0, 100.0), command(0, 200.0)], true)
+            .is_err());
+
+        let configs = pca
+            .apply(&[command(0, 100.0), command(1, 1500.0)], true)
+            .unwrap();
+        assert_eq!(configs[0].current_count.unwrap(), 100);
+        assert_eq!(configs[1].current_count.unwrap(), 1500);
+    }
+
+    #[test]
+    fn apply_non_atomic() {
+        let (_, pca) = create_mock(200);
+        let command = |channel: u8, value: f64| ChannelCommand {
+            channel: Channel::try_from(channel).unwrap(),
+            command_type: CommandType::PulseCount,
+            value: Some(value),
+            basis: None,
+            client_id: None,
+        };
+
+        // Commands preceding the failure remain applied
+        assert!(pca
+            .apply(&[command(0, 100.0), command(1, 5000.0)], false)
+            .is_err());
+        assert_eq!(pca.config(Channel::C0).unwrap().current_count.unwrap(), 100);
     }
 }
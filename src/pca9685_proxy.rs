@@ -1,131 +1,645 @@
-use crate::{Config, Pca9685Proxy, PCA_PWM_RESOLUTION};
-use linux_embedded_hal::i2cdev::linux::LinuxI2CError;
+use crate::bus::ThreadDelay;
+use crate::utils;
+use crate::{
+    Config, DisabledOutput, DriverError, I2cBus, NoI2c, Pca9685Error, Pca9685Proxy, Pca9685Result,
+    PcaClockConfig, SharedI2cBus, TraceWrite, PCA_PWM_RESOLUTION,
+};
+#[cfg(feature = "linux")]
 use linux_embedded_hal::I2cdev;
-use pwm_pca9685::{Address, Channel, Error, OutputDriver, Pca9685 as Pca9685Impl};
+use pwm_pca9685::{
+    Address, Channel, DisabledOutputValue, Error, OutputDriver, Pca9685 as Pca9685Impl,
+    ProgrammableAddress,
+};
 
-const INTERNAL_OSC_HZ: f64 = 25.0 * 1000.0 * 1000.0; // 25 MHz
-
-pub(super) struct Pca9685ProxyImpl {
+pub(super) struct Pca9685ProxyImpl<I2C> {
     max_pw_ms: f64,
     single_count_duration_ms: f64,
     device: String,
     address: u8,
     output_frequency_hz: u16,
     prescale: u8,
+    external_clock: bool,
+    oscillator_hz: f64,
     output_type: OutputDriver,
-    inner: Option<Pca9685Impl<I2cdev>>,
+    disabled_output: DisabledOutput,
+    stagger_on_counts: bool,
+    subaddresses: Vec<u8>,
+    all_call_address: Option<u8>,
+    inner: Option<Pca9685Impl<SharedI2cBus<I2C>>>,
+
+    /// The bus the device is on, shared with `inner`, for writes to other
+    /// addresses
+    bus: Option<SharedI2cBus<I2C>>,
+
+    /// The writes captured in place of a device, if tracing
+    trace: Option<Vec<TraceWrite>>,
+
+    /// The (ON, OFF) registers last written to each Channel, where known and
+    /// reproducible by [Pca9685Impl::set_all_on_off] (i.e., not full on)
+    registers: [Option<(u16, u16)>; CHANNEL_COUNT],
+
+    /// The writes deferred until [Pca9685Proxy::end_burst], if bursting
+    burst: Option<Vec<(Channel, Write)>>,
+
+    /// Whether put to sleep by [Pca9685Proxy::sleep]
+    asleep: bool,
+
+    /// Whether writes go to the all-call address (see
+    /// [Pca9685Proxy::set_broadcasting])
+    broadcasting: bool,
+}
+
+/// The number of Channels (excluding [Channel::All])
+const CHANNEL_COUNT: usize = 16;
+
+/// The I2C general call address, and its software reset (SWRST) command
+const GENERAL_CALL_ADDRESS: u8 = 0x00;
+const SWRST: u8 = 0x06;
+
+/// The registers holding the sub-addresses (SUBADR1, then SUBADR2 and
+/// SUBADR3) and the all-call address (ALLCALLADR)
+const SUBADR1: u8 = 0x02;
+const ALLCALLADR: u8 = 0x05;
+
+/// The first of Channel 0's registers (LED0_ON_L), each Channel's four
+/// following the last's, and the first of [Channel::All]'s (ALL_LED_ON_L)
+const LED0_ON_L: u8 = 0x06;
+const ALL_LED_ON_L: u8 = 0xfa;
+
+/// The bit of LEDn_ON_H / LEDn_OFF_H (as part of a 16-bit count) driving the
+/// output full on / full off
+const FULL_BIT: u16 = 0x1000;
+
+/// A write to a Channel's registers
+#[derive(Debug, Clone, Copy)]
+enum Write {
+    OffCount(u16),
+    FullOn,
+    FullOff,
+}
+
+impl Write {
+    /// Returns the (ON, OFF) registers equivalent to the write, rising at
+    /// `on`, where [Pca9685Impl::set_all_on_off] can reproduce it
+    fn registers(self, on: u16) -> Option<(u16, u16)> {
+        match self {
+            // OFF before ON wraps around the end of the cycle
+            Write::OffCount(off) => Some((on, (on + off) % PCA_PWM_RESOLUTION)),
+            Write::FullOn => None,
+            // ON and OFF being equal, the output never goes high
+            Write::FullOff => Some((0, 0)),
+        }
+    }
+
+    /// Returns the bytes of the LEDn_ON_L to LEDn_OFF_H registers making the
+    /// write, rising at `on`
+    fn led_registers(self, on: u16) -> [u8; 4] {
+        let (on, off) = match self {
+            Write::OffCount(off) => (on, (on + off) % PCA_PWM_RESOLUTION),
+            Write::FullOn => (FULL_BIT, 0),
+            Write::FullOff => (0, FULL_BIT),
+        };
+
+        [on as u8, (on >> 8) as u8, off as u8, (off >> 8) as u8]
+    }
+
+    fn count(self) -> Option<u16> {
+        match self {
+            Write::OffCount(off) => Some(off),
+            Write::FullOn => Some(PCA_PWM_RESOLUTION),
+            Write::FullOff => None,
+        }
+    }
+}
+
+/// Returns the count at which `channel`'s output rises, staggered or not
+/// (the Channels of [Channel::All] share its registers, so can't be
+/// staggered)
+fn on_count(channel: Channel, stagger_on_counts: bool) -> u16 {
+    match (channel, stagger_on_counts) {
+        (Channel::All, _) | (_, false) => 0,
+        (channel, true) => utils::staggered_on_count(channel),
+    }
+}
+
+/// Returns the registers of every Channel once `writes` are made over
+/// `registers`, if all are then known
+fn burst_registers(
+    registers: &[Option<(u16, u16)>; CHANNEL_COUNT],
+    writes: &[(Channel, Write)],
+    stagger_on_counts: bool,
+) -> Option<([u16; CHANNEL_COUNT], [u16; CHANNEL_COUNT])> {
+    let mut registers = *registers;
+    for (channel, write) in writes {
+        let on = on_count(*channel, stagger_on_counts);
+        remember(&mut registers, *channel, write.registers(on));
+    }
+
+    let mut on = [0; CHANNEL_COUNT];
+    let mut off = [0; CHANNEL_COUNT];
+    for (index, channel_registers) in registers.iter().enumerate() {
+        (on[index], off[index]) = (*channel_registers)?;
+    }
+
+    Some((on, off))
+}
+
+/// Makes `write` to `channel`, rising at `on`, at the all-call address of
+/// `bus`, reaching every board listening there with one write (relying on
+/// each having register auto-increment enabled)
+fn broadcast<I2C: I2cBus>(
+    bus: &mut SharedI2cBus<I2C>,
+    all_call_address: u8,
+    channel: Channel,
+    write: Write,
+    on: u16,
+) -> Result<(), DriverError> {
+    let register = match channel {
+        Channel::All => ALL_LED_ON_L,
+        channel => LED0_ON_L + 4 * channel as u8,
+    };
+    let mut bytes = vec![register];
+    bytes.extend(write.led_registers(on));
+
+    bus.write(all_call_address, &bytes).map_err(Error::I2C)
+}
+
+/// Returns the driver's equivalent of `disabled_output`
+fn disabled_output_value(disabled_output: DisabledOutput) -> DisabledOutputValue {
+    match disabled_output {
+        DisabledOutput::Low => DisabledOutputValue::Zero,
+        DisabledOutput::High => DisabledOutputValue::OutputDriver,
+        DisabledOutput::HighImpedance => DisabledOutputValue::HighImpedance,
+    }
+}
+
+fn remember(
+    registers: &mut [Option<(u16, u16)>; CHANNEL_COUNT],
+    channel: Channel,
+    channel_registers: Option<(u16, u16)>,
+) {
+    match registers.get_mut(channel as usize) {
+        Some(known) => *known = channel_registers,
+        None => registers.fill(channel_registers),
+    }
 }
 
-impl Pca9685Proxy for Pca9685ProxyImpl {
+impl<I2C: I2cBus> Pca9685Proxy for Pca9685ProxyImpl<I2C> {
     fn max_pw_ms(&self) -> f64 {
-        return self.max_pw_ms;
+        self.max_pw_ms
     }
 
     fn single_count_duration_ms(&self) -> f64 {
-        return self.single_count_duration_ms;
+        self.single_count_duration_ms
     }
 
     fn output_frequency_hz(&self) -> u16 {
-        return self.output_frequency_hz;
+        self.output_frequency_hz
+    }
+
+    fn actual_output_frequency_hz(&self) -> f64 {
+        utils::prescale_output_frequency_hz(self.oscillator_hz, self.prescale)
+    }
+
+    fn oscillator_hz(&self) -> f64 {
+        self.oscillator_hz
     }
 
     fn device(&self) -> String {
-        return self.device.clone();
+        self.device.clone()
     }
 
     fn address(&self) -> u8 {
-        return self.address;
+        self.address
     }
 
     fn prescale(&self) -> u8 {
-        return self.prescale;
+        self.prescale
     }
 
     fn output_type(&self) -> OutputDriver {
-        return self.output_type;
-    }
-
-    fn set_channel_off_count(
-        &mut self,
-        channel: Channel,
-        off: u16,
-    ) -> Result<(), Error<LinuxI2CError>> {
-        match &mut self.inner {
-            Some(inner) => {
-                log::info!("Calling set_channel_on_off({:?}, 0, {})", channel, off);
-                inner.set_channel_on_off(channel, 0, off)
+        self.output_type
+    }
+
+    fn disabled_output(&self) -> DisabledOutput {
+        self.disabled_output
+    }
+
+    fn set_disabled_output(&mut self, disabled_output: DisabledOutput) -> Result<(), DriverError> {
+        if let Some(inner) = &mut self.inner {
+            log::info!("Calling set_disabled_output_value({:?})", disabled_output);
+            inner.set_disabled_output_value(disabled_output_value(disabled_output))?;
+        }
+        self.disabled_output = disabled_output;
+
+        Ok(())
+    }
+
+    fn on_count(&self, channel: Channel) -> u16 {
+        on_count(channel, self.stagger_on_counts)
+    }
+
+    fn all_call_address(&self) -> Option<u8> {
+        self.all_call_address
+    }
+
+    fn set_broadcasting(&mut self, broadcasting: bool) {
+        self.broadcasting = broadcasting;
+    }
+
+    fn set_channel_off_count(&mut self, channel: Channel, off: u16) -> Result<(), DriverError> {
+        self.write(channel, Write::OffCount(off))
+    }
+
+    fn set_channel_full_on(&mut self, channel: Channel) -> Result<(), DriverError> {
+        self.write(channel, Write::FullOn)
+    }
+
+    fn set_channel_full_off(&mut self, channel: Channel) -> Result<(), DriverError> {
+        self.write(channel, Write::FullOff)
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn take_trace(&mut self) -> Vec<TraceWrite> {
+        self.trace.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    fn begin_burst(&mut self) {
+        self.burst = Some(Vec::new());
+    }
+
+    fn end_burst(&mut self) -> Result<(), DriverError> {
+        let Some(writes) = self.burst.take() else {
+            return Ok(());
+        };
+
+        if let (Some(inner), true) = (&mut self.inner, writes.len() > 1) {
+            if let Some((on, off)) =
+                burst_registers(&self.registers, &writes, self.stagger_on_counts)
+            {
+                log::info!("Calling set_all_on_off({:?}, {:?})", on, off);
+                match inner.set_all_on_off(&on, &off) {
+                    Ok(()) => {
+                        for (channel, write) in &writes {
+                            let on = on_count(*channel, self.stagger_on_counts);
+                            remember(&mut self.registers, *channel, write.registers(on));
+                        }
+                        return Ok(());
+                    }
+                    Err(error) => {
+                        log::warn!("Burst write failed ({:?}); writing one by one", error)
+                    }
+                }
             }
-            None => Ok(()),
         }
+
+        writes
+            .into_iter()
+            .map(|(channel, write)| self.write(channel, write))
+            .fold(Ok(()), Result::and)
+    }
+
+    fn sleep(&mut self) -> Result<(), DriverError> {
+        if let Some(inner) = &mut self.inner {
+            log::info!("Calling enable_restart_and_disable()");
+            inner.enable_restart_and_disable()?;
+        }
+        self.asleep = true;
+
+        Ok(())
     }
 
-    fn set_channel_full_on(&mut self, channel: Channel) -> Result<(), Error<LinuxI2CError>> {
-        match &mut self.inner {
-            Some(inner) => inner.set_channel_full_on(channel, 0),
-            None => Ok(()),
+    fn wake(&mut self) -> Result<(), DriverError> {
+        if let Some(inner) = &mut self.inner {
+            // restart() only wakes the device if a Channel was running when
+            // it was put to sleep (i.e., the RESTART bit is set), otherwise it
+            // must be enabled explicitly
+            log::info!("Calling restart()");
+            inner.restart(&mut ThreadDelay)?;
+            inner.enable()?;
         }
+        self.asleep = false;
+
+        Ok(())
+    }
+
+    fn asleep(&self) -> bool {
+        self.asleep
     }
 
-    fn set_channel_full_off(&mut self, channel: Channel) -> Result<(), Error<LinuxI2CError>> {
-        match &mut self.inner {
-            Some(inner) => inner.set_channel_full_off(channel),
-            None => Ok(()),
+    fn reset(&mut self) -> Result<(), DriverError> {
+        self.registers = [None; CHANNEL_COUNT];
+        self.asleep = false;
+        let (Some(inner), Some(bus)) = (&mut self.inner, &mut self.bus) else {
+            return Ok(());
+        };
+
+        log::info!("Writing SWRST to the general call address");
+        bus.write(GENERAL_CALL_ADDRESS, &[SWRST])
+            .map_err(Error::I2C)?;
+
+        // As the device's MODE1 register is back to its defaults, so must the
+        // driver's copy be
+        inner.reset_internal_driver_state();
+        if self.external_clock {
+            inner.use_external_clock()?;
         }
+        inner.set_prescale(self.prescale)?;
+        inner.set_output_driver(self.output_type)?;
+        inner.set_disabled_output_value(disabled_output_value(self.disabled_output))?;
+        self.program_addresses()?;
+        self.inner.as_mut().map_or(Ok(()), Pca9685Impl::enable)
     }
 }
 
-impl Pca9685ProxyImpl {
+#[cfg(feature = "linux")]
+impl Pca9685ProxyImpl<I2cdev> {
+    #[allow(clippy::new_ret_no_self)]
     pub(super) fn new(config: &Config) -> Box<dyn Pca9685Proxy> {
-        let dev = I2cdev::new(&config.device)
-            .unwrap_or_else(|_| panic!("Unable to load I2C device file: {}", config.device));
+        Pca9685ProxyImpl::try_new(config).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub(super) fn try_new(config: &Config) -> Pca9685Result<Box<dyn Pca9685Proxy>> {
+        let dev = I2cdev::new(&config.device).map_err(|error| {
+            Pca9685Error::DeviceInitError(format!(
+                "Unable to load I2C device file {}: {}",
+                config.device, error
+            ))
+        })?;
+
+        Pca9685ProxyImpl::with_i2c(config, dev)
+    }
+}
+
+impl Pca9685ProxyImpl<NoI2c> {
+    pub(super) fn null(config: &Config) -> Box<dyn Pca9685Proxy> {
+        Box::new(Pca9685ProxyImpl::<NoI2c>::init(config, None))
+    }
+
+    pub(super) fn tracing(config: &Config) -> Box<dyn Pca9685Proxy> {
+        let mut pca = Pca9685ProxyImpl::<NoI2c>::init(config, None);
+        pca.trace = Some(Vec::new());
+
+        Box::new(pca)
+    }
+}
+
+impl<I2C: I2cBus> Pca9685ProxyImpl<I2C> {
+    pub(super) fn with_i2c(config: &Config, bus: I2C) -> Pca9685Result<Box<dyn Pca9685Proxy>> {
+        let driver_error = |error: DriverError| {
+            Pca9685Error::DeviceInitError(format!(
+                "Unable to configure device at {} ({:#02x}): {:?}",
+                config.device, config.address, error
+            ))
+        };
 
+        let bus = SharedI2cBus::new(bus);
         let mut pca = Pca9685ProxyImpl::init(
             config,
-            Some(Pca9685Impl::new(dev, Address::from(config.address)).unwrap()),
+            Some(
+                Pca9685Impl::new(bus.clone(), Address::from(config.address))
+                    .map_err(driver_error)?,
+            ),
         );
+        pca.bus = Some(bus);
 
-        match &mut pca.inner {
-            Some(pca_impl) => {
-                pca_impl.set_prescale(pca.prescale).unwrap();
-                pca_impl.set_output_driver(pca.output_type).unwrap();
-                pca_impl.enable().unwrap();
+        if let Some(pca_impl) = &mut pca.inner {
+            if pca.external_clock {
+                log::info!("Calling use_external_clock()");
+                pca_impl.use_external_clock().map_err(driver_error)?;
             }
-            None => {}
+            pca_impl.set_prescale(pca.prescale).map_err(driver_error)?;
+            pca_impl
+                .set_output_driver(pca.output_type)
+                .map_err(driver_error)?;
+            pca_impl
+                .set_disabled_output_value(disabled_output_value(pca.disabled_output))
+                .map_err(driver_error)?;
+        }
+        pca.program_addresses().map_err(driver_error)?;
+        if let Some(pca_impl) = &mut pca.inner {
+            pca_impl.enable().map_err(driver_error)?;
         }
 
-        return Box::new(pca);
+        Ok(Box::new(pca))
     }
 
-    pub(super) fn null(config: &Config) -> Box<dyn Pca9685Proxy> {
-        return Box::new(Pca9685ProxyImpl::init(&config, None));
+    /// Returns the driver, if there is a device
+    pub(super) fn raw(&mut self) -> Option<&mut Pca9685Impl<SharedI2cBus<I2C>>> {
+        // Anything may be written through the driver
+        self.registers = [None; CHANNEL_COUNT];
+        self.inner.as_mut()
+    }
+
+    /// Programs the sub-addresses and all-call address into the device (see
+    /// [Config::subaddresses]), enabling those configured and disabling the
+    /// rest
+    fn program_addresses(&mut self) -> Result<(), DriverError> {
+        let (Some(inner), Some(bus)) = (&mut self.inner, &mut self.bus) else {
+            return Ok(());
+        };
+
+        let programmable = [
+            (ProgrammableAddress::Subaddress1, self.subaddresses.first()),
+            (ProgrammableAddress::Subaddress2, self.subaddresses.get(1)),
+            (ProgrammableAddress::Subaddress3, self.subaddresses.get(2)),
+            (ProgrammableAddress::AllCall, self.all_call_address.as_ref()),
+        ];
+        for (index, (programmable_address, address)) in programmable.into_iter().enumerate() {
+            let Some(address) = address else {
+                log::info!(
+                    "Calling disable_programmable_address({:?})",
+                    programmable_address
+                );
+                inner.disable_programmable_address(programmable_address)?;
+                continue;
+            };
+
+            // The driver would write the address unshifted (and refuses the
+            // default all-call address), but it occupies bits 7:1
+            let register = match programmable_address {
+                ProgrammableAddress::AllCall => ALLCALLADR,
+                _ => SUBADR1 + index as u8,
+            };
+            log::info!("Writing {:#02x} to register {:#02x}", address, register);
+            bus.write(self.address, &[register, address << 1])
+                .map_err(Error::I2C)?;
+            log::info!(
+                "Calling enable_programmable_address({:?})",
+                programmable_address
+            );
+            inner.enable_programmable_address(programmable_address)?;
+        }
+
+        Ok(())
     }
 
-    fn init(config: &Config, inner: Option<Pca9685Impl<I2cdev>>) -> Pca9685ProxyImpl {
-        let cycle_duration_ms = 1000.0 / config.output_frequency_hz as f64;
-        let single_count_duration_ms = cycle_duration_ms / PCA_PWM_RESOLUTION as f64;
+    fn write(&mut self, channel: Channel, write: Write) -> Result<(), DriverError> {
+        let (Some(inner), Some(bus)) = (&mut self.inner, &mut self.bus) else {
+            self.trace(channel, write.count());
+            return Ok(());
+        };
+        if let Some(burst) = &mut self.burst {
+            burst.push((channel, write));
+            return Ok(());
+        }
+
+        let on = on_count(channel, self.stagger_on_counts);
+        let result = match (write, self.all_call_address) {
+            (write, Some(all_call_address)) if self.broadcasting => {
+                log::info!(
+                    "Broadcasting {:?} to {:?} at {:#02x}",
+                    write,
+                    channel,
+                    all_call_address
+                );
+                broadcast(bus, all_call_address, channel, write, on)
+            }
+            (Write::OffCount(off), _) => {
+                let off = (on + off) % PCA_PWM_RESOLUTION;
+                log::info!("Calling set_channel_on_off({:?}, {}, {})", channel, on, off);
+                inner.set_channel_on_off(channel, on, off)
+            }
+            (Write::FullOn, _) => inner.set_channel_full_on(channel, 0),
+            (Write::FullOff, _) => inner.set_channel_full_off(channel),
+        };
+        let registers = result.as_ref().ok().and_then(|_| write.registers(on));
+        remember(&mut self.registers, channel, registers);
+
+        result
+    }
+
+    fn trace(&mut self, channel: Channel, count: Option<u16>) {
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceWrite {
+                channel: channel as u8,
+                count,
+            });
+        }
+    }
+
+    fn init(
+        config: &Config,
+        inner: Option<Pca9685Impl<SharedI2cBus<I2C>>>,
+    ) -> Pca9685ProxyImpl<I2C> {
+        config
+            .validate_clock()
+            .unwrap_or_else(|error| panic!("{}", error));
+        config
+            .validate_addresses()
+            .unwrap_or_else(|error| panic!("{}", error));
+
+        // The prescale register only holds whole numbers, so the actual output
+        // frequency (and thus pulse widths) deviates slightly from the
+        // configured output frequency
+        let prescale = config.effective_prescale();
+        let clock_config = PcaClockConfig::from_prescale(config.oscillator_hz(), prescale);
 
         Pca9685ProxyImpl {
-            max_pw_ms: cycle_duration_ms,
-            single_count_duration_ms,
+            max_pw_ms: clock_config.max_pw_ms,
+            single_count_duration_ms: clock_config.single_pw_duration_ms,
             device: config.device.clone(),
             address: config.address,
-            output_frequency_hz: config.output_frequency_hz,
-            prescale: Pca9685ProxyImpl::calculate_prescale(config.output_frequency_hz),
+            output_frequency_hz: config.effective_output_frequency_hz(),
+            prescale,
+            external_clock: config.external_clock_hz.is_some(),
+            oscillator_hz: config.oscillator_hz(),
             output_type: if config.open_drain {
                 OutputDriver::OpenDrain
             } else {
                 OutputDriver::TotemPole
             },
-            inner: inner,
+            disabled_output: config.disabled_output,
+            stagger_on_counts: config.stagger_on_counts,
+            subaddresses: config.subaddresses.clone(),
+            all_call_address: config.all_call_address,
+            inner,
+            bus: None,
+            trace: None,
+            registers: [None; CHANNEL_COUNT],
+            burst: None,
+            asleep: false,
+            broadcasting: false,
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{burst_registers, Write, CHANNEL_COUNT};
+    use crate::utils;
+    use pwm_pca9685::Channel;
+
+    #[test]
+    fn burst() {
+        let channel = |raw: u8| Channel::try_from(raw).unwrap();
+        let mut registers = [Some((0, 1500)); CHANNEL_COUNT];
+        registers[3] = None;
+
+        // Unless every Channel's registers are known, writes are made one by
+        // one
+        let writes = [(channel(0), Write::OffCount(1000))];
+        assert!(burst_registers(&registers, &writes, false).is_none());
+        let writes = [
+            (channel(0), Write::OffCount(1000)),
+            (channel(3), Write::FullOff),
+        ];
+        let (on, off) = burst_registers(&registers, &writes, false).unwrap();
+        assert_eq!(on, [0; CHANNEL_COUNT]);
+        assert_eq!(off[0], 1000);
+        assert_eq!(off[1], 1500);
+        assert_eq!(off[3], 0);
+
+        // Full on can't be written in a burst
+        let writes = [(channel(3), Write::FullOn)];
+        assert!(burst_registers(&registers, &writes, false).is_none());
+        let writes = [(Channel::All, Write::OffCount(2000))];
+        assert_eq!(
+            burst_registers(&registers, &writes, false).unwrap().1,
+            [2000; CHANNEL_COUNT]
+        );
+    }
+
+    #[test]
+    fn led_registers() {
+        // LEDn_ON_L, LEDn_ON_H, LEDn_OFF_L, LEDn_OFF_H
+        assert_eq!(
+            Write::OffCount(1500).led_registers(0),
+            [0x00, 0x00, 0xdc, 0x05]
+        );
+        assert_eq!(
+            Write::OffCount(500).led_registers(3840),
+            [0x00, 0x0f, 0xf4, 0x00]
+        );
+        assert_eq!(Write::FullOn.led_registers(256), [0x00, 0x10, 0x00, 0x00]);
+        assert_eq!(Write::FullOff.led_registers(256), [0x00, 0x00, 0x00, 0x10]);
+    }
+
+    #[test]
+    fn stagger() {
+        let channel = |raw: u8| Channel::try_from(raw).unwrap();
+        assert_eq!(utils::staggered_on_count(channel(0)), 0);
+        assert_eq!(utils::staggered_on_count(channel(15)), 3840);
 
-    fn calculate_prescale(output_frequency_hz: u16) -> u8 {
-        // Per PCA 9685 Datasheet, 7.3.5 PWM frequency PRE_SCALE:
-        //    prescale_value = round(internal_osc/(4096 * output_frequency_hz)) - 1
-        let value = INTERNAL_OSC_HZ / (PCA_PWM_RESOLUTION as f64 * output_frequency_hz as f64);
-        let value = value.round() as u8 - 1;
+        // Each Channel rises 256 counts after the last, wrapping OFF around
+        // the end of the cycle where needed
+        let writes: Vec<(Channel, Write)> = (0..16_u8)
+            .map(|raw| (channel(raw), Write::OffCount(500)))
+            .collect();
+        let registers = [None; CHANNEL_COUNT];
+        let (on, off) = burst_registers(&registers, &writes, true).unwrap();
+        assert_eq!(on[1], 256);
+        assert_eq!(off[1], 756);
+        assert_eq!(on[15], 3840);
+        assert_eq!(off[15], 244);
 
-        return value;
+        // ...but Channels written together as Channel::All can't be
+        let writes = [(Channel::All, Write::OffCount(500))];
+        let (on, off) = burst_registers(&registers, &writes, true).unwrap();
+        assert_eq!(on, [0; CHANNEL_COUNT]);
+        assert_eq!(off, [500; CHANNEL_COUNT]);
     }
 }
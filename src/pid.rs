@@ -0,0 +1,108 @@
+use crate::PidGains;
+use std::time::Instant;
+
+/// A PID controller (see [PidGains]), whose integral is limited to what can
+/// contribute at most `output_range` to its output, preventing windup while
+/// the output is saturated.
+pub(crate) struct Pid {
+    gains: PidGains,
+    output_range: f64,
+    integral: f64,
+    previous: Option<(Instant, f64)>,
+}
+
+impl Pid {
+    pub fn new(gains: PidGains, output_range: f64) -> Self {
+        Pid {
+            gains,
+            output_range,
+            integral: 0.0,
+            previous: None,
+        }
+    }
+
+    pub fn gains(&self) -> PidGains {
+        self.gains
+    }
+
+    /// Changes the gains without resetting the controller's state, so that
+    /// they can be tuned while it runs
+    pub fn tune(&mut self, gains: PidGains, output_range: f64) {
+        self.gains = gains;
+        self.output_range = output_range;
+        self.integral = self.clamp_integral(self.integral);
+    }
+
+    fn clamp_integral(&self, integral: f64) -> f64 {
+        match self.gains.ki {
+            ki if ki > 0.0 => {
+                let limit = self.output_range / ki;
+                integral.clamp(-limit, limit)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Returns the correction (in counts) for `error` as of `now`
+    pub fn update(&mut self, error: f64, now: Instant) -> f64 {
+        let (integral, derivative) = match self.previous {
+            Some((previous_at, previous_error)) if now > previous_at => {
+                let dt = now.duration_since(previous_at).as_secs_f64();
+                (self.integral + error * dt, (error - previous_error) / dt)
+            }
+            _ => (self.integral, 0.0),
+        };
+        self.integral = self.clamp_integral(integral);
+        self.previous = Some((now, error));
+
+        self.gains.kp * error + self.gains.ki * self.integral + self.gains.kd * derivative
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pid;
+    use crate::PidGains;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn update() {
+        let now = Instant::now();
+        let mut pid = Pid::new(
+            PidGains {
+                kp: 0.5,
+                ki: 0.0,
+                kd: 0.0,
+            },
+            1000.0,
+        );
+        assert_eq!(pid.update(100.0, now), 50.0);
+
+        // The integral accumulates over time, up to the output range
+        pid.tune(
+            PidGains {
+                kp: 0.0,
+                ki: 10.0,
+                kd: 0.0,
+            },
+            1000.0,
+        );
+        assert_eq!(pid.gains().ki, 10.0);
+        let correction = pid.update(100.0, now + Duration::from_millis(500));
+        assert!((correction - 500.0).abs() < 1e-9);
+        let correction = pid.update(100.0, now + Duration::from_secs(5));
+        assert!((correction - 1000.0).abs() < 1e-9);
+
+        // The derivative acts on the change in error
+        pid.tune(
+            PidGains {
+                kp: 0.0,
+                ki: 0.0,
+                kd: 0.1,
+            },
+            1000.0,
+        );
+        let correction = pid.update(50.0, now + Duration::from_millis(5500));
+        assert!((correction - -10.0).abs() < 1e-9);
+    }
+}
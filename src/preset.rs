@@ -0,0 +1,106 @@
+use crate::{CommandType, Keyframe, PresetOutput};
+
+/// The interval between a fade's keyframes
+const FADE_STEP_MS: u64 = 20;
+
+/// The name under which a preset plays (see [crate::PlaybackStatus]), e.g.
+/// `preset/sunset`
+pub(crate) fn sequence_name(name: &str) -> String {
+    format!("preset/{}", name)
+}
+
+/// Returns true if `sequence` is a fade, i.e. a preset or scene playing
+/// (see [sequence_name])
+pub(crate) fn is_fade(sequence: &str) -> bool {
+    sequence.starts_with("preset/") || sequence.starts_with("scene/")
+}
+
+/// A Channel's part in applying a preset: its `output`, reached from the
+/// count `from` (None if the Channel has no output yet) at the count `to`
+pub(crate) struct Fade<'a> {
+    pub output: &'a PresetOutput,
+    pub from: Option<u16>,
+    pub to: u16,
+}
+
+/// Generates keyframes (every [FADE_STEP_MS], ordered by `t_ms`, then
+/// channel) moving each Channel linearly from its current count to its
+/// output over `fade_ms`, ending on the output itself.  Channels without an
+/// output to fade from take theirs immediately.
+pub(crate) fn keyframes(fades: &[Fade], fade_ms: u64) -> Vec<Keyframe> {
+    let mut keyframes = Vec::new();
+    for fade in fades {
+        let output = |t_ms: u64| Keyframe {
+            t_ms,
+            channel: fade.output.channel,
+            command_type: fade.output.command_type,
+            value: fade.output.value,
+        };
+
+        let Some(from) = fade.from else {
+            keyframes.push(output(0));
+            continue;
+        };
+        for t_ms in (0..fade_ms).step_by(FADE_STEP_MS as usize) {
+            let fraction = t_ms as f64 / fade_ms as f64;
+            keyframes.push(Keyframe {
+                t_ms,
+                channel: fade.output.channel,
+                command_type: CommandType::PulseCount,
+                value: Some((from as f64 + (fade.to as f64 - from as f64) * fraction).round()),
+            });
+        }
+        keyframes.push(output(fade_ms));
+    }
+    keyframes.sort_by_key(|keyframe| (keyframe.t_ms, keyframe.channel));
+
+    keyframes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{keyframes, Fade};
+    use crate::{CommandType, PresetOutput};
+
+    #[test]
+    fn fade() {
+        let output = |channel: u8, command_type: CommandType, value: Option<f64>| PresetOutput {
+            channel,
+            command_type,
+            value,
+        };
+        let dim = output(0, CommandType::Percent, Some(0.25));
+        let on = output(1, CommandType::FullOn, None);
+        let fades = [
+            Fade {
+                output: &dim,
+                from: Some(2000),
+                to: 1000,
+            },
+            Fade {
+                output: &on,
+                from: None,
+                to: 4096,
+            },
+        ];
+
+        let keyframes = keyframes(&fades, 100);
+        assert_eq!(keyframes.len(), 5 + 1 + 1);
+        assert_eq!(keyframes[0].channel, 0);
+        assert_eq!(keyframes[0].value, Some(2000.0));
+        assert_eq!(keyframes[1].channel, 1);
+        assert_eq!(keyframes[1].command_type, CommandType::FullOn);
+        assert_eq!(keyframes[3].t_ms, 40);
+        assert_eq!(keyframes[3].command_type, CommandType::PulseCount);
+        assert_eq!(keyframes[3].value, Some(1600.0));
+        let last = keyframes.last().unwrap();
+        assert_eq!(last.t_ms, 100);
+        assert_eq!(last.command_type, CommandType::Percent);
+        assert_eq!(last.value, Some(0.25));
+
+        // Without a fade, every output is taken immediately
+        let keyframes = super::keyframes(&fades, 0);
+        assert_eq!(keyframes.len(), 2);
+        assert!(keyframes.iter().all(|keyframe| keyframe.t_ms == 0));
+    }
+}
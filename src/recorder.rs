@@ -0,0 +1,120 @@
+use crate::{
+    ChannelConfig, CommandType, Keyframe, RecordingStatus, SequenceConfig, PCA_PWM_RESOLUTION,
+};
+use std::time::Instant;
+
+/// Captures the outputs resulting from manual commands as keyframes of a new
+/// sequence, timed from when recording started
+pub(crate) struct Recorder {
+    name: String,
+    started: Instant,
+    keyframes: Vec<Keyframe>,
+}
+
+impl Recorder {
+    pub fn new(name: &str, now: Instant) -> Self {
+        Recorder {
+            name: name.to_string(),
+            started: now,
+            keyframes: Vec::new(),
+        }
+    }
+
+    pub fn status(&self, now: Instant) -> RecordingStatus {
+        RecordingStatus {
+            sequence: self.name.clone(),
+            t_ms: self.elapsed_ms(now),
+            keyframes: self.keyframes.len(),
+        }
+    }
+
+    /// Records the output of `config` as of `now`, replacing any keyframe of
+    /// its Channel recorded in the same millisecond
+    pub fn record(&mut self, config: &ChannelConfig, now: Instant) {
+        let t_ms = self.elapsed_ms(now);
+        let channel = config.channel as u8;
+        let (command_type, value) = match config.current_count {
+            None => (CommandType::FullOff, None),
+            Some(PCA_PWM_RESOLUTION) => (CommandType::FullOn, None),
+            Some(count) => (CommandType::PulseCount, Some(count as f64)),
+        };
+
+        self.keyframes
+            .retain(|keyframe| keyframe.t_ms != t_ms || keyframe.channel != channel);
+        self.keyframes.push(Keyframe {
+            t_ms,
+            channel,
+            command_type,
+            value,
+        });
+    }
+
+    /// Returns the recorded sequence, lasting until `now`
+    pub fn finish(self, now: Instant) -> SequenceConfig {
+        SequenceConfig {
+            duration_ms: Some(self.elapsed_ms(now)),
+            name: self.name,
+            keyframes: self.keyframes,
+            steps: Vec::new(),
+        }
+    }
+
+    fn elapsed_ms(&self, now: Instant) -> u64 {
+        now.saturating_duration_since(self.started).as_millis() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Recorder;
+    use crate::{ChannelConfig, CommandType, PCA_PWM_RESOLUTION};
+    use pwm_pca9685::Channel;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn record() {
+        let now = Instant::now();
+        let mut recorder = Recorder::new("wave", now);
+        let output = |channel: Channel, current_count: Option<u16>| ChannelConfig {
+            channel,
+            current_count,
+            custom_limits: None,
+            owner: None,
+            calibration: None,
+            inverted: false,
+            trim_counts: 0,
+            esc: None,
+            tags: Vec::new(),
+            reduced_hold: None,
+            thermal_limits: None,
+            dual_rate: None,
+            failsafe: None,
+            display_unit: None,
+            display_value: None,
+            name: None,
+            feedback: None,
+            pid: None,
+            input_shaping: None,
+            low_pass: None,
+        };
+
+        recorder.record(&output(Channel::C1, Some(1000)), now);
+        recorder.record(&output(Channel::C1, Some(1100)), now);
+        recorder.record(&output(Channel::C2, Some(PCA_PWM_RESOLUTION)), now);
+        let later = now + Duration::from_millis(250);
+        recorder.record(&output(Channel::C1, None), later);
+
+        let status = recorder.status(later);
+        assert_eq!(status.sequence, "wave");
+        assert_eq!(status.t_ms, 250);
+        assert_eq!(status.keyframes, 3);
+
+        let sequence = recorder.finish(now + Duration::from_millis(400));
+        assert!(sequence.validate().is_ok());
+        assert_eq!(sequence.duration_ms, Some(400));
+        assert_eq!(sequence.keyframes[0].value, Some(1100.0));
+        assert_eq!(sequence.keyframes[1].command_type, CommandType::FullOn);
+        assert_eq!(sequence.keyframes[2].t_ms, 250);
+        assert_eq!(sequence.keyframes[2].command_type, CommandType::FullOff);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{
+    ChannelCommand, CommandType, Pca9685Event, PresetConfig, RuleAction, RuleCondition, RuleConfig,
+    RuleEvent,
+};
+use pwm_pca9685::Channel;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// The client that rules' commands are attributed to
+pub(crate) const RULES_CLIENT_ID: &str = "rules";
+
+/// Where a threshold rule stands
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Threshold {
+    /// The threshold isn't crossed
+    Clear,
+
+    /// The threshold has been crossed since the given instant
+    Crossed(Instant),
+
+    /// The rule has acted, and won't again until the threshold is cleared
+    Acted,
+}
+
+/// Evaluates the configured [RuleConfig]s: threshold rules against each
+/// Channel's output as polled, and event rules against the events published
+/// since the last poll
+pub(crate) struct Rules {
+    rules: Vec<RuleConfig>,
+    presets: HashMap<String, PresetConfig>,
+    thresholds: Vec<Threshold>,
+}
+
+impl Rules {
+    /// `rules` must have been validated against `presets` (see
+    /// [RuleConfig::validate])
+    pub fn new(rules: &[RuleConfig], presets: &HashMap<String, PresetConfig>) -> Self {
+        Rules {
+            rules: rules.to_vec(),
+            presets: presets.clone(),
+            thresholds: vec![Threshold::Clear; rules.len()],
+        }
+    }
+
+    /// Returns the commands carrying out each rule acting as of `now`, given
+    /// each Channel's output as a fraction of its limits (by `output`; None
+    /// without output) and the `events` published since the last poll
+    pub fn poll<F>(
+        &mut self,
+        now: Instant,
+        output: F,
+        events: &[Pca9685Event],
+    ) -> Vec<ChannelCommand>
+    where
+        F: Fn(u8) -> Option<f64>,
+    {
+        let mut commands = Vec::new();
+        for (rule, threshold) in self.rules.iter().zip(&mut self.thresholds) {
+            let acts = match &rule.when {
+                RuleCondition::Above(when) | RuleCondition::Below(when) => {
+                    let crossed = output(when.channel).is_some_and(|pct| match rule.when {
+                        RuleCondition::Above(_) => pct > when.pct,
+                        _ => pct < when.pct,
+                    });
+                    *threshold = match (*threshold, crossed) {
+                        (_, false) => Threshold::Clear,
+                        (Threshold::Clear, true) => Threshold::Crossed(now),
+                        (threshold, true) => threshold,
+                    };
+
+                    match *threshold {
+                        Threshold::Crossed(since)
+                            if now.saturating_duration_since(since)
+                                >= Duration::from_millis(when.for_ms) =>
+                        {
+                            *threshold = Threshold::Acted;
+                            true
+                        }
+                        _ => false,
+                    }
+                }
+                RuleCondition::Event(when) => events.iter().any(|event| is(event, *when)),
+            };
+            if !acts {
+                continue;
+            }
+
+            log::info!(target: "pca9685", "Rule {}: {:?}", rule.name, rule.then);
+            commands.extend(Rules::commands(&rule.then, &self.presets));
+        }
+
+        commands
+    }
+
+    fn commands(
+        action: &RuleAction,
+        presets: &HashMap<String, PresetConfig>,
+    ) -> Vec<ChannelCommand> {
+        let command = |channel: u8, command_type: CommandType, value: Option<f64>| ChannelCommand {
+            channel: Channel::try_from(channel).unwrap(),
+            command_type,
+            value,
+            basis: None,
+            client_id: Some(RULES_CLIENT_ID.to_string()),
+        };
+
+        match action {
+            RuleAction::FullOn(channel) => vec![command(*channel, CommandType::FullOn, None)],
+            RuleAction::FullOff(channel) => vec![command(*channel, CommandType::FullOff, None)],
+            RuleAction::SetPct { channel, pct } => {
+                vec![command(*channel, CommandType::Percent, Some(*pct))]
+            }
+            RuleAction::Preset(name) => presets[name]
+                .outputs
+                .iter()
+                .map(|output| command(output.channel, output.command_type, output.value))
+                .collect(),
+        }
+    }
+}
+
+/// Whether `event` is of the `kind` awaited
+fn is(event: &Pca9685Event, kind: RuleEvent) -> bool {
+    matches!(
+        (event, kind),
+        (Pca9685Event::EmergencyStop, RuleEvent::EmergencyStop)
+            | (Pca9685Event::WatchdogTripped(_), RuleEvent::WatchdogTripped)
+            | (Pca9685Event::ChannelFaulted(_), RuleEvent::ChannelFaulted)
+            | (Pca9685Event::ThermalWarning(_), RuleEvent::ThermalWarning)
+            | (Pca9685Event::PositionError(_), RuleEvent::PositionError)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rules;
+    use crate::{CommandType, Pca9685Event, PresetConfig, RuleConfig};
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn poll() {
+        let rules: Vec<RuleConfig> = serde_yaml::from_str(
+            "
+            - name: overdrive
+              when: !above { channel: 0, pct: 0.8, for_ms: 5000 }
+              then: !full_on 7
+            - name: safe
+              when: !event emergency_stop
+              then: !preset safe
+            ",
+        )
+        .unwrap();
+        let preset: PresetConfig = serde_yaml::from_str(
+            "
+            name: safe
+            outputs:
+              - { channel: 1, command_type: FullOff }
+              - { channel: 2, command_type: Percent, value: 0.5 }
+            ",
+        )
+        .unwrap();
+        let presets = HashMap::from([("safe".to_string(), preset)]);
+        let now = Instant::now();
+        let at = |ms: u64| now + Duration::from_millis(ms);
+        let mut rules = Rules::new(&rules, &presets);
+
+        // Held above 80% for 5s, then once only until it falls back
+        assert!(rules.poll(at(0), |_| Some(0.9), &[]).is_empty());
+        assert!(rules.poll(at(4999), |_| Some(0.9), &[]).is_empty());
+        let commands = rules.poll(at(5000), |_| Some(0.9), &[]);
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].channel as u8, 7);
+        assert_eq!(commands[0].command_type, CommandType::FullOn);
+        assert_eq!(commands[0].client_id.as_deref(), Some("rules"));
+        assert!(rules.poll(at(20000), |_| Some(0.9), &[]).is_empty());
+
+        // Dipping restarts the timer, as does having no output
+        assert!(rules.poll(at(21000), |_| Some(0.8), &[]).is_empty());
+        assert!(rules.poll(at(22000), |_| Some(0.9), &[]).is_empty());
+        assert!(rules.poll(at(23000), |_| None, &[]).is_empty());
+        assert!(rules.poll(at(24000), |_| Some(0.9), &[]).is_empty());
+        assert_eq!(rules.poll(at(29000), |_| Some(0.9), &[]).len(), 1);
+
+        // Each event applies the preset
+        let commands = rules.poll(at(30000), |_| None, &[Pca9685Event::EmergencyStop]);
+        let outputs: Vec<(u8, CommandType, Option<f64>)> = commands
+            .iter()
+            .map(|command| (command.channel as u8, command.command_type, command.value))
+            .collect();
+        assert_eq!(
+            outputs,
+            vec![
+                (1, CommandType::FullOff, None),
+                (2, CommandType::Percent, Some(0.5))
+            ]
+        );
+        assert!(rules
+            .poll(at(30010), |_| None, &[Pca9685Event::WatchdogTripped(None)])
+            .is_empty());
+        assert_eq!(
+            rules
+                .poll(at(30020), |_| None, &[Pca9685Event::EmergencyStop])
+                .len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{PresetOutput, SceneConfig, SceneLayer};
+use std::collections::HashSet;
+
+/// An output of a scene, along with the count it yields
+pub(crate) struct SceneOutput {
+    pub output: PresetOutput,
+    pub count: u16,
+}
+
+struct Scene {
+    layer: SceneLayer,
+    outputs: Vec<SceneOutput>,
+
+    /// When (in order of application) the scene was applied
+    applied: u64,
+}
+
+/// The presets applied as scenes, each Channel's output resolved among them
+/// as described by [SceneConfig]
+pub(crate) struct SceneStack {
+    htp_channels: HashSet<u8>,
+    scenes: Vec<Scene>,
+    applied: u64,
+}
+
+impl SceneStack {
+    pub fn new(config: &SceneConfig) -> Self {
+        SceneStack {
+            htp_channels: config.htp_channels.iter().copied().collect(),
+            scenes: Vec::new(),
+            applied: 0,
+        }
+    }
+
+    /// Layers `outputs` as the scene `layer`, replacing it if already applied
+    /// (it then counts as the latest applied)
+    pub fn apply(&mut self, layer: SceneLayer, outputs: Vec<SceneOutput>) {
+        self.release(&layer.preset);
+        self.applied += 1;
+        self.scenes.push(Scene {
+            layer,
+            outputs,
+            applied: self.applied,
+        });
+    }
+
+    /// Removes the named scene, returning the Channels it included (None if
+    /// it isn't applied)
+    pub fn release(&mut self, preset: &str) -> Option<Vec<u8>> {
+        let index = self
+            .scenes
+            .iter()
+            .position(|scene| scene.layer.preset == preset)?;
+        let scene = self.scenes.remove(index);
+
+        Some(
+            scene
+                .outputs
+                .iter()
+                .map(|output| output.output.channel)
+                .collect(),
+        )
+    }
+
+    /// Returns the output of `channel` resolved among the scenes including
+    /// it, if any
+    pub fn resolve(&self, channel: u8) -> Option<&SceneOutput> {
+        let htp = self.htp_channels.contains(&channel);
+
+        self.scenes
+            .iter()
+            .filter_map(|scene| {
+                scene
+                    .outputs
+                    .iter()
+                    .find(|output| output.output.channel == channel)
+                    .map(|output| (scene, output))
+            })
+            .max_by_key(|(scene, output)| {
+                (
+                    scene.layer.priority,
+                    if htp { output.count } else { 0 },
+                    scene.applied,
+                )
+            })
+            .map(|(_, output)| output)
+    }
+
+    /// Returns the applied scenes, highest priority (then latest applied)
+    /// first
+    pub fn layers(&self) -> Vec<SceneLayer> {
+        let mut scenes: Vec<&Scene> = self.scenes.iter().collect();
+        scenes.sort_by_key(|scene| std::cmp::Reverse((scene.layer.priority, scene.applied)));
+
+        scenes
+            .into_iter()
+            .map(|scene| scene.layer.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SceneOutput, SceneStack};
+    use crate::{CommandType, PresetOutput, SceneConfig, SceneLayer};
+
+    fn outputs(counts: &[(u8, u16)]) -> Vec<SceneOutput> {
+        counts
+            .iter()
+            .map(|(channel, count)| SceneOutput {
+                output: PresetOutput {
+                    channel: *channel,
+                    command_type: CommandType::PulseCount,
+                    value: Some(*count as f64),
+                },
+                count: *count,
+            })
+            .collect()
+    }
+
+    fn layer(preset: &str, priority: i32) -> SceneLayer {
+        SceneLayer {
+            preset: preset.to_string(),
+            priority,
+        }
+    }
+
+    #[test]
+    fn resolve() {
+        let mut stack = SceneStack::new(&SceneConfig {
+            htp_channels: vec![0],
+        });
+        let count = |stack: &SceneStack, channel: u8| stack.resolve(channel).map(|o| o.count);
+
+        stack.apply(layer("wash", 0), outputs(&[(0, 3000), (1, 100)]));
+        stack.apply(layer("spot", 0), outputs(&[(0, 1000), (1, 200)]));
+
+        // HTP takes the highest; LTP the latest
+        assert_eq!(count(&stack, 0), Some(3000));
+        assert_eq!(count(&stack, 1), Some(200));
+        assert_eq!(count(&stack, 2), None);
+
+        // A higher priority overrides either
+        stack.apply(layer("blackout", 10), outputs(&[(0, 0)]));
+        assert_eq!(count(&stack, 0), Some(0));
+        assert_eq!(count(&stack, 1), Some(200));
+
+        // Reapplying makes a scene the latest
+        stack.apply(layer("wash", 0), outputs(&[(0, 3000), (1, 100)]));
+        assert_eq!(count(&stack, 1), Some(100));
+        assert_eq!(
+            stack.layers(),
+            vec![layer("blackout", 10), layer("wash", 0), layer("spot", 0)]
+        );
+
+        assert_eq!(stack.release("blackout"), Some(vec![0]));
+        assert_eq!(stack.release("blackout"), None);
+        assert_eq!(count(&stack, 0), Some(3000));
+    }
+}
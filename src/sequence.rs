@@ -0,0 +1,488 @@
+use crate::{
+    ChannelCommand, Keyframe, LoopRegion, Pca9685Error, Pca9685Result, PlaybackClock,
+    PlaybackStatus, SequenceConfig,
+};
+use pwm_pca9685::Channel;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+/// The most keyframes a sequence may resolve to (e.g., given large `repeat`s)
+pub(crate) const MAX_RESOLVED_KEYFRAMES: usize = 100_000;
+
+/// Resolves `sequence` into a single timeline of keyframes, ordered by `t_ms`
+/// (then channel), by expanding its steps from `sequences`.
+///
+/// Within a step, a later repetition's keyframe replaces an earlier one at
+/// the same time (e.g., where a loop ends where it starts); otherwise, two
+/// keyframes of the same Channel at the same time conflict.
+///
+/// Error conditions:
+/// * [Pca9685Error::NoSuchSequenceError] or [Pca9685Error::NoSuchGroupError]
+///   if a step names a sequence or group that doesn't exist
+/// * [Pca9685Error::InvalidConfiguration] if a sequence includes itself,
+///   keyframes conflict, or the timeline exceeds [MAX_RESOLVED_KEYFRAMES]
+pub(crate) fn resolve(
+    sequence: &SequenceConfig,
+    sequences: &HashMap<String, SequenceConfig>,
+    groups: &HashMap<String, Vec<Channel>>,
+) -> Pca9685Result<Vec<Keyframe>> {
+    resolve_within(sequence, sequences, groups, &mut Vec::new())
+}
+
+/// Moves the `keyframes` of each Channel in `channel_map` onto the Channel it
+/// maps to, keeping them ordered by `t_ms` (then channel).
+///
+/// Error conditions:
+/// * [Pca9685Error::NoSuchChannelError] if a Channel maps to an invalid one
+/// * [Pca9685Error::InvalidCommand] if two of the keyframes' Channels would
+///   play on the same Channel
+pub(crate) fn remap(
+    mut keyframes: Vec<Keyframe>,
+    channel_map: &BTreeMap<u8, u8>,
+) -> Pca9685Result<Vec<Keyframe>> {
+    if channel_map.is_empty() {
+        return Ok(keyframes);
+    }
+
+    let mut sources: BTreeMap<u8, u8> = BTreeMap::new();
+    for keyframe in &mut keyframes {
+        let source = keyframe.channel;
+        let target = *channel_map.get(&source).unwrap_or(&source);
+        if Channel::try_from(target).is_err() {
+            return Err(Pca9685Error::NoSuchChannelError(target));
+        }
+        match sources.insert(target, source) {
+            Some(other) if other != source => {
+                return Err(Pca9685Error::InvalidCommand(format!(
+                    "Channels {} and {} would both play on channel {}.",
+                    other.min(source),
+                    other.max(source),
+                    target
+                )))
+            }
+            _ => keyframe.channel = target,
+        }
+    }
+    keyframes.sort_by_key(|keyframe| (keyframe.t_ms, keyframe.channel));
+
+    Ok(keyframes)
+}
+
+fn resolve_within(
+    sequence: &SequenceConfig,
+    sequences: &HashMap<String, SequenceConfig>,
+    groups: &HashMap<String, Vec<Channel>>,
+    including: &mut Vec<String>,
+) -> Pca9685Result<Vec<Keyframe>> {
+    let invalid = |reason: String| {
+        Pca9685Error::InvalidConfiguration(format!("Sequence {}: {}", sequence.name, reason))
+    };
+
+    if including.contains(&sequence.name) {
+        return Err(invalid(format!(
+            "includes itself (via {})",
+            including.join(" > ")
+        )));
+    }
+    including.push(sequence.name.clone());
+
+    let mut tracks = vec![sequence.keyframes.clone()];
+    for step in &sequence.steps {
+        let included = sequences
+            .get(&step.sequence)
+            .ok_or_else(|| Pca9685Error::NoSuchSequenceError(step.sequence.clone()))?;
+        let channels = match &step.group {
+            Some(group) => Some(
+                groups
+                    .get(group)
+                    .ok_or_else(|| Pca9685Error::NoSuchGroupError(group.clone()))?,
+            ),
+            None => None,
+        };
+
+        let keyframes = resolve_within(included, sequences, groups, including)?;
+        let duration_ms = included
+            .duration_ms
+            .or_else(|| keyframes.last().map(|keyframe| keyframe.t_ms))
+            .unwrap_or_default();
+        if keyframes.len().saturating_mul(step.repeat as usize) > MAX_RESOLVED_KEYFRAMES {
+            return Err(invalid(format!(
+                "resolves to more than {} keyframes",
+                MAX_RESOLVED_KEYFRAMES
+            )));
+        }
+
+        let mut track = BTreeMap::new();
+        for repetition in 0..step.repeat as u64 {
+            for keyframe in &keyframes {
+                if let Some(channels) = channels {
+                    if !channels.contains(&Channel::try_from(keyframe.channel).unwrap()) {
+                        continue;
+                    }
+                }
+
+                let t_ms = repetition
+                    .checked_mul(duration_ms)
+                    .and_then(|offset_ms| offset_ms.checked_add(step.t_ms))
+                    .and_then(|offset_ms| offset_ms.checked_add(keyframe.t_ms))
+                    .ok_or_else(|| invalid("t_ms overflows".to_string()))?;
+                track.insert(
+                    (t_ms, keyframe.channel),
+                    Keyframe {
+                        t_ms,
+                        ..keyframe.clone()
+                    },
+                );
+            }
+        }
+        tracks.push(track.into_values().collect());
+    }
+    including.pop();
+
+    let mut timeline = BTreeMap::new();
+    for keyframe in tracks.into_iter().flatten() {
+        let key = (keyframe.t_ms, keyframe.channel);
+        if timeline.insert(key, keyframe).is_some() {
+            return Err(invalid(format!(
+                "conflicting keyframes for channel {} at t_ms {}",
+                key.1, key.0
+            )));
+        }
+    }
+    if timeline.len() > MAX_RESOLVED_KEYFRAMES {
+        return Err(invalid(format!(
+            "resolves to more than {} keyframes",
+            MAX_RESOLVED_KEYFRAMES
+        )));
+    }
+
+    Ok(timeline.into_values().collect())
+}
+
+/// A sequence being played: its resolved keyframes, and a playhead driven by
+/// its [PlaybackClock]
+pub(crate) struct Playback {
+    name: String,
+    keyframes: Vec<Keyframe>,
+    clock: PlaybackClock,
+
+    /// The playhead as of an instant (when playback started, or the last
+    /// seek or change of rate); None while awaiting an external clock
+    anchor: Option<(Instant, u64)>,
+
+    /// When an external clock last reported its time
+    synced_at: Option<Instant>,
+
+    rate: f64,
+    loop_region: Option<LoopRegion>,
+
+    /// The number of keyframes at or before the playhead when last polled
+    cursor: usize,
+
+    /// Index (into `keyframes`) of the keyframe last applied to each Channel
+    applied: HashMap<u8, usize>,
+}
+
+impl Playback {
+    pub fn new(name: &str, keyframes: Vec<Keyframe>, clock: PlaybackClock, now: Instant) -> Self {
+        Playback {
+            name: name.to_string(),
+            keyframes,
+            clock,
+            anchor: match clock {
+                PlaybackClock::Internal => Some((now, 0)),
+                PlaybackClock::External { .. } => None,
+            },
+            synced_at: None,
+            rate: 1.0,
+            loop_region: None,
+            cursor: 0,
+            applied: HashMap::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The client that keyframes are attributed to, e.g. `sequence/wave`
+    pub fn client_id(&self) -> String {
+        format!("sequence/{}", self.name)
+    }
+
+    pub fn seek(&mut self, t_ms: u64, now: Instant) {
+        self.anchor = Some((now, t_ms));
+        self.synced_at = Some(now);
+    }
+
+    /// Changes the rate from `now` on, without moving the playhead
+    pub fn set_rate(&mut self, rate: f64, now: Instant) {
+        self.anchor = self.position(now).map(|t_ms| (now, t_ms));
+        self.rate = rate;
+    }
+
+    pub fn set_loop_region(&mut self, loop_region: Option<LoopRegion>) {
+        self.loop_region = loop_region;
+    }
+
+    /// Returns the playhead as of `now`.  It wraps around the loop region
+    /// once it reaches the end, unless it was already past the end (e.g.,
+    /// having seeked beyond it).
+    pub fn position(&self, now: Instant) -> Option<u64> {
+        let (anchored_at, anchor_ms) = self.anchor?;
+        let until = match (self.clock, self.synced_at) {
+            (PlaybackClock::External { timeout_ms }, Some(synced_at)) => {
+                now.min(synced_at + Duration::from_millis(timeout_ms))
+            }
+            _ => now,
+        };
+        let elapsed_ms = until.saturating_duration_since(anchored_at).as_secs_f64() * 1000.0;
+        let t_ms = anchor_ms.saturating_add((elapsed_ms * self.rate) as u64);
+
+        match self.loop_region {
+            Some(region) if anchor_ms < region.end_ms && t_ms >= region.end_ms => {
+                Some(region.start_ms + (t_ms - region.start_ms) % (region.end_ms - region.start_ms))
+            }
+            _ => Some(t_ms),
+        }
+    }
+
+    pub fn status(&self, now: Instant) -> PlaybackStatus {
+        PlaybackStatus {
+            sequence: self.name.clone(),
+            clock: self.clock,
+            t_ms: self.position(now),
+            duration_ms: self
+                .keyframes
+                .last()
+                .map(|keyframe| keyframe.t_ms)
+                .unwrap_or_default(),
+            rate: self.rate,
+            loop_region: self.loop_region,
+        }
+    }
+
+    /// Returns the commands due as of `now`: for each Channel, its latest
+    /// keyframe at or before the playhead, unless already applied.  Having
+    /// moved backwards (i.e., seeked), Channels are restored to the keyframe
+    /// they would have reached by then.
+    pub fn poll(&mut self, now: Instant) -> Vec<ChannelCommand> {
+        let Some(t_ms) = self.position(now) else {
+            return Vec::new();
+        };
+
+        let end = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.t_ms <= t_ms);
+        let start = if end >= self.cursor { self.cursor } else { 0 };
+        self.cursor = end;
+
+        let mut latest = BTreeMap::new();
+        for (index, keyframe) in self.keyframes[start..end].iter().enumerate() {
+            latest.insert(keyframe.channel, start + index);
+        }
+
+        let client_id = self.client_id();
+        latest
+            .into_iter()
+            .filter(|(channel, index)| self.applied.insert(*channel, *index) != Some(*index))
+            .map(|(_, index)| {
+                let keyframe = &self.keyframes[index];
+                ChannelCommand {
+                    channel: Channel::try_from(keyframe.channel).unwrap(),
+                    command_type: keyframe.command_type,
+                    value: keyframe.value,
+                    basis: None,
+                    client_id: Some(client_id.clone()),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns true if any keyframe commands `channel`
+    pub fn drives(&self, channel: Channel) -> bool {
+        self.keyframes
+            .iter()
+            .any(|keyframe| keyframe.channel == channel as u8)
+    }
+
+    /// Returns true once an internal clock has passed the last keyframe
+    /// (never while looping)
+    pub fn finished(&self) -> bool {
+        self.clock == PlaybackClock::Internal
+            && self.loop_region.is_none()
+            && self.cursor == self.keyframes.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{remap, resolve, Playback};
+    use crate::{
+        CommandType, Keyframe, LoopRegion, Pca9685Error, PlaybackClock, SequenceConfig,
+        SequenceStep,
+    };
+    use pwm_pca9685::Channel;
+    use std::collections::{BTreeMap, HashMap};
+    use std::time::{Duration, Instant};
+
+    fn keyframe(t_ms: u64, channel: u8, value: f64) -> Keyframe {
+        Keyframe {
+            t_ms,
+            channel,
+            command_type: CommandType::PulseCount,
+            value: Some(value),
+        }
+    }
+
+    fn step(t_ms: u64, sequence: &str, repeat: u32, group: Option<&str>) -> SequenceStep {
+        SequenceStep {
+            t_ms,
+            sequence: sequence.to_owned(),
+            repeat,
+            group: group.map(String::from),
+        }
+    }
+
+    fn sequence(name: &str, keyframes: Vec<Keyframe>, steps: Vec<SequenceStep>) -> SequenceConfig {
+        SequenceConfig {
+            name: name.to_owned(),
+            keyframes,
+            steps,
+            duration_ms: None,
+        }
+    }
+
+    #[test]
+    fn resolve_steps() {
+        let wave = sequence(
+            "wave",
+            vec![
+                keyframe(0, 0, 1000.0),
+                keyframe(0, 1, 1000.0),
+                keyframe(500, 0, 2000.0),
+                keyframe(1000, 0, 1000.0),
+            ],
+            vec![],
+        );
+        let sequences = HashMap::from([("wave".to_owned(), wave)]);
+        let groups = HashMap::from([("left".to_owned(), vec![Channel::C0])]);
+
+        let show = sequence(
+            "show",
+            vec![keyframe(0, 2, 1500.0)],
+            vec![step(100, "wave", 2, Some("left"))],
+        );
+        let timeline: Vec<(u64, u8, Option<f64>)> = resolve(&show, &sequences, &groups)
+            .unwrap()
+            .into_iter()
+            .map(|keyframe| (keyframe.t_ms, keyframe.channel, keyframe.value))
+            .collect();
+        assert_eq!(
+            timeline,
+            vec![
+                (0, 2, Some(1500.0)),
+                (100, 0, Some(1000.0)),
+                (600, 0, Some(2000.0)),
+                (1100, 0, Some(1000.0)),
+                (1600, 0, Some(2000.0)),
+                (2100, 0, Some(1000.0)),
+            ]
+        );
+
+        // Parallel steps on the same Channel conflict
+        let conflicting = sequence(
+            "conflicting",
+            vec![keyframe(100, 0, 1500.0)],
+            vec![step(100, "wave", 1, None)],
+        );
+        assert!(matches!(
+            resolve(&conflicting, &sequences, &groups),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+
+        let missing = sequence("missing", vec![], vec![step(0, "nod", 1, None)]);
+        assert!(matches!(
+            resolve(&missing, &sequences, &groups),
+            Err(Pca9685Error::NoSuchSequenceError(_))
+        ));
+
+        let looping = sequence("loop", vec![], vec![step(0, "loop", 1, None)]);
+        let sequences = HashMap::from([("loop".to_owned(), looping.clone())]);
+        assert!(matches!(
+            resolve(&looping, &sequences, &groups),
+            Err(Pca9685Error::InvalidConfiguration(_))
+        ));
+    }
+
+    #[test]
+    fn remap_channels() {
+        let keyframes = vec![
+            keyframe(0, 0, 1000.0),
+            keyframe(0, 4, 1500.0),
+            keyframe(500, 0, 2000.0),
+        ];
+        let channels = |keyframes: Vec<Keyframe>| -> Vec<u8> {
+            keyframes.iter().map(|keyframe| keyframe.channel).collect()
+        };
+
+        let swapped = BTreeMap::from([(0, 4), (4, 0)]);
+        assert_eq!(
+            channels(remap(keyframes.clone(), &swapped).unwrap()),
+            vec![0, 4, 4]
+        );
+        assert_eq!(
+            channels(remap(keyframes.clone(), &BTreeMap::new()).unwrap()),
+            vec![0, 4, 0]
+        );
+
+        let merged = BTreeMap::from([(0, 4)]);
+        assert!(matches!(
+            remap(keyframes.clone(), &merged),
+            Err(Pca9685Error::InvalidCommand(_))
+        ));
+        let invalid = BTreeMap::from([(0, 16)]);
+        assert!(matches!(
+            remap(keyframes, &invalid),
+            Err(Pca9685Error::NoSuchChannelError(16))
+        ));
+    }
+
+    #[test]
+    fn playback_position() {
+        let keyframes = vec![
+            keyframe(0, 0, 1000.0),
+            keyframe(500, 0, 2000.0),
+            keyframe(1000, 0, 1000.0),
+        ];
+        let now = Instant::now();
+        let at = |ms: u64| now + Duration::from_millis(ms);
+        let values = |commands: Vec<crate::ChannelCommand>| -> Vec<Option<f64>> {
+            commands.into_iter().map(|command| command.value).collect()
+        };
+
+        let mut playback = Playback::new("wave", keyframes.clone(), PlaybackClock::Internal, now);
+        assert_eq!(values(playback.poll(at(0))), vec![Some(1000.0)]);
+        assert_eq!(values(playback.poll(at(100))), vec![]);
+        playback.set_rate(2.0, at(100));
+        assert_eq!(playback.position(at(300)), Some(500));
+        assert_eq!(values(playback.poll(at(300))), vec![Some(2000.0)]);
+
+        // Wraps around the loop region, restoring the keyframe at its start
+        playback.set_loop_region(Some(LoopRegion {
+            start_ms: 250,
+            end_ms: 750,
+        }));
+        assert_eq!(playback.position(at(450)), Some(300));
+        assert_eq!(values(playback.poll(at(450))), vec![Some(1000.0)]);
+        assert!(!playback.finished());
+
+        // An external clock holds once reports stop
+        let clock = PlaybackClock::External { timeout_ms: 100 };
+        let mut playback = Playback::new("wave", keyframes, clock, now);
+        assert_eq!(playback.position(at(1000)), None);
+        playback.seek(400, at(1000));
+        assert_eq!(playback.position(at(1050)), Some(450));
+        assert_eq!(playback.position(at(5000)), Some(500));
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{InputShaping, Shaper};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// An input shaper (see [InputShaping]): the output is the setpoint
+/// convolved with a few impulses, timed and weighted so that the vibration
+/// each excites at the mechanism's resonance cancels that of the others.
+pub(crate) struct InputShaper {
+    config: InputShaping,
+
+    /// Each impulse's delay and amplitude, the amplitudes summing to 1.0
+    impulses: Vec<(Duration, f64)>,
+
+    /// The setpoint (in counts) before the oldest of `steps`
+    baseline: f64,
+
+    /// The setpoints (in counts) commanded within the shaper's duration,
+    /// oldest first, with when each was commanded
+    steps: VecDeque<(Instant, f64)>,
+}
+
+impl InputShaper {
+    /// `config` must be valid (see [InputShaping::validate])
+    pub fn new(config: InputShaping) -> Self {
+        let damped = (1.0 - config.damping * config.damping).sqrt();
+        let half_period = Duration::from_secs_f64(0.5 / (config.frequency_hz * damped));
+        let k = (-config.damping * std::f64::consts::PI / damped).exp();
+
+        let impulses = match config.shaper {
+            Shaper::Zv => vec![
+                (Duration::ZERO, 1.0 / (1.0 + k)),
+                (half_period, k / (1.0 + k)),
+            ],
+            Shaper::Zvd => {
+                let sum = (1.0 + k) * (1.0 + k);
+                vec![
+                    (Duration::ZERO, 1.0 / sum),
+                    (half_period, 2.0 * k / sum),
+                    (2 * half_period, k * k / sum),
+                ]
+            }
+        };
+
+        InputShaper {
+            config,
+            impulses,
+            baseline: 0.0,
+            steps: VecDeque::new(),
+        }
+    }
+
+    pub fn config(&self) -> InputShaping {
+        self.config
+    }
+
+    /// Returns how long after the last setpoint change the output settles
+    fn duration(&self) -> Duration {
+        self.impulses
+            .last()
+            .map_or(Duration::ZERO, |impulse| impulse.0)
+    }
+
+    /// Shapes the move from `previous` to `count`, commanded at `now`;
+    /// without a `previous` count to move from, the output isn't shaped
+    pub fn command(&mut self, now: Instant, previous: Option<u16>, count: u16) {
+        match previous {
+            Some(previous) if self.steps.is_empty() => self.baseline = previous as f64,
+            Some(_) => (),
+            None => return self.clear(),
+        }
+
+        self.steps.push_back((now, count as f64));
+    }
+
+    /// Stops shaping, e.g. once the output is full on or full off
+    pub fn clear(&mut self) {
+        self.steps.clear();
+    }
+
+    /// Returns true unless a setpoint change is still being shaped as of
+    /// `now`
+    pub fn is_settled(&self, now: Instant) -> bool {
+        self.steps
+            .back()
+            .is_none_or(|(at, _)| now.saturating_duration_since(*at) >= self.duration())
+    }
+
+    /// Returns the shaped output as of `now`, or None once it has settled
+    /// on the latest setpoint
+    pub fn output(&mut self, now: Instant) -> Option<u16> {
+        while let Some((at, count)) = self.steps.front().copied() {
+            if now.saturating_duration_since(at) < self.duration() {
+                break;
+            }
+            self.baseline = count;
+            self.steps.pop_front();
+        }
+        if self.steps.is_empty() {
+            return None;
+        }
+
+        let setpoint = |delay: Duration| {
+            self.steps
+                .iter()
+                .rev()
+                .find(|(at, _)| now.saturating_duration_since(*at) >= delay)
+                .map_or(self.baseline, |(_, count)| *count)
+        };
+        let output: f64 = self
+            .impulses
+            .iter()
+            .map(|(delay, amplitude)| amplitude * setpoint(*delay))
+            .sum();
+
+        Some(output.round() as u16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InputShaper;
+    use crate::{InputShaping, Shaper};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn shape() {
+        let now = Instant::now();
+        let at = |ms: u64| now + Duration::from_millis(ms);
+
+        // Undamped at 2Hz, ZV splits the step in half, 250ms apart
+        let mut shaper = InputShaper::new(InputShaping {
+            shaper: Shaper::Zv,
+            frequency_hz: 2.0,
+            damping: 0.0,
+        });
+        assert_eq!(shaper.output(now), None);
+        shaper.command(now, Some(1000), 2000);
+        assert_eq!(shaper.output(at(0)), Some(1500));
+        assert_eq!(shaper.output(at(249)), Some(1500));
+        assert!(!shaper.is_settled(at(249)));
+        assert_eq!(shaper.output(at(250)), None);
+        assert!(shaper.is_settled(at(250)));
+
+        // ...and ZVD in quarters, over a whole period
+        let mut shaper = InputShaper::new(InputShaping {
+            shaper: Shaper::Zvd,
+            frequency_hz: 2.0,
+            damping: 0.0,
+        });
+        shaper.command(now, Some(1000), 2000);
+        assert_eq!(shaper.output(at(0)), Some(1250));
+        assert_eq!(shaper.output(at(250)), Some(1750));
+        assert_eq!(shaper.output(at(499)), Some(1750));
+        assert_eq!(shaper.output(at(500)), None);
+
+        // Moves overlap, each shaped from the setpoint before it
+        shaper.command(at(1000), Some(2000), 1000);
+        shaper.command(at(1250), Some(1000), 2000);
+        assert_eq!(shaper.output(at(1250)), Some(1500));
+        assert_eq!(shaper.output(at(1500)), Some(1750));
+        assert_eq!(shaper.output(at(1750)), None);
+
+        // Damping weights the first impulse more heavily, and lengthens the
+        // damped period
+        let mut shaper = InputShaper::new(InputShaping {
+            shaper: Shaper::Zv,
+            frequency_hz: 2.0,
+            damping: 0.1,
+        });
+        shaper.command(now, Some(1000), 2000);
+        assert_eq!(shaper.output(at(0)), Some(1578));
+        assert_eq!(shaper.output(at(251)), Some(1578));
+        assert_eq!(shaper.output(at(252)), None);
+
+        // Nothing to shape from
+        shaper.command(at(1000), None, 1000);
+        assert_eq!(shaper.output(at(1000)), None);
+    }
+}
@@ -0,0 +1,296 @@
+//! Implementations of [Storage]
+use crate::{Config, Pca9685Error, Pca9685Result, SequenceConfig, Storage, StorageConfig};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "sqlite")]
+use std::sync::Mutex;
+
+fn storage_error(location: &Path, error: impl ToString) -> Pca9685Error {
+    Pca9685Error::StorageError(format!("{}: {}", location.display(), error.to_string()))
+}
+
+/// Keeps each sequence as `<name>.yaml` in a directory, e.g. to be read and
+/// edited by hand, or committed to git
+pub struct FileStorage {
+    sequences_dir: PathBuf,
+}
+
+impl FileStorage {
+    /// Creates a [FileStorage] beneath `dir` (sequences in `dir/sequences`)
+    pub fn new(dir: &Path) -> FileStorage {
+        FileStorage::with_sequences_dir(&dir.join("sequences"))
+    }
+
+    /// Creates a [FileStorage] keeping sequences in `sequences_dir` itself
+    /// (see [Config::sequences_dir])
+    pub fn with_sequences_dir(sequences_dir: &Path) -> FileStorage {
+        FileStorage {
+            sequences_dir: sequences_dir.to_path_buf(),
+        }
+    }
+
+    fn sequence_path(&self, name: &str) -> PathBuf {
+        self.sequences_dir.join(format!("{}.yaml", name))
+    }
+}
+
+impl Storage for FileStorage {
+    /// Returns the sequences in the directory (none if it doesn't exist),
+    /// skipping (with a warning) any that can't be read
+    fn sequences(&self) -> Pca9685Result<Vec<SequenceConfig>> {
+        let entries = match fs::read_dir(&self.sequences_dir) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(storage_error(&self.sequences_dir, error)),
+        };
+
+        let mut sequences = Vec::new();
+        for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            if path.extension().and_then(|extension| extension.to_str()) != Some("yaml") {
+                continue;
+            }
+
+            match fs::read_to_string(&path)
+                .map_err(|error| error.to_string())
+                .and_then(|yaml| serde_yaml::from_str(&yaml).map_err(|error| error.to_string()))
+            {
+                Ok(sequence) => sequences.push(sequence),
+                Err(error) => {
+                    log::warn!(target: "pca9685", "Skipping {}: {}", path.display(), error)
+                }
+            }
+        }
+
+        Ok(sequences)
+    }
+
+    fn save_sequence(&self, sequence: &SequenceConfig) -> Pca9685Result<()> {
+        let path = self.sequence_path(&sequence.name);
+        let yaml = serde_yaml::to_string(sequence).map_err(|error| storage_error(&path, error))?;
+
+        fs::create_dir_all(&self.sequences_dir)
+            .and_then(|_| fs::write(&path, yaml))
+            .map_err(|error| storage_error(&path, error))
+    }
+
+    fn remove_sequence(&self, name: &str) -> Pca9685Result<()> {
+        let path = self.sequence_path(name);
+
+        match fs::remove_file(&path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(storage_error(&path, error)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Keeps sequences (as YAML, by name) in a SQLite database, which tolerates
+/// concurrent access and never leaves an entry half-written
+#[cfg(feature = "sqlite")]
+pub struct SqliteStorage {
+    path: PathBuf,
+    connection: Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteStorage {
+    /// Opens (creating, if need be) the database at `path`.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::StorageError] if the database cannot be opened
+    pub fn open(path: &Path) -> Pca9685Result<SqliteStorage> {
+        let connection =
+            rusqlite::Connection::open(path).map_err(|error| storage_error(path, error))?;
+        connection
+            .execute(
+                "CREATE TABLE IF NOT EXISTS sequences (name TEXT PRIMARY KEY, yaml TEXT NOT NULL)",
+                (),
+            )
+            .map_err(|error| storage_error(path, error))?;
+
+        Ok(SqliteStorage {
+            path: path.to_path_buf(),
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl Storage for SqliteStorage {
+    /// Returns the saved sequences, skipping (with a warning) any that can't
+    /// be parsed
+    fn sequences(&self) -> Pca9685Result<Vec<SequenceConfig>> {
+        let connection = self.connection.lock().unwrap();
+        let error = |error: rusqlite::Error| storage_error(&self.path, error);
+        let mut statement = connection
+            .prepare("SELECT name, yaml FROM sequences")
+            .map_err(error)?;
+        let rows = statement
+            .query_map((), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(error)?;
+
+        let mut sequences = Vec::new();
+        for row in rows {
+            let (name, yaml) = row.map_err(error)?;
+            match serde_yaml::from_str(&yaml) {
+                Ok(sequence) => sequences.push(sequence),
+                Err(error) => log::warn!(
+                    target: "pca9685",
+                    "Skipping sequence {} of {}: {}",
+                    name,
+                    self.path.display(),
+                    error
+                ),
+            }
+        }
+
+        Ok(sequences)
+    }
+
+    fn save_sequence(&self, sequence: &SequenceConfig) -> Pca9685Result<()> {
+        let yaml =
+            serde_yaml::to_string(sequence).map_err(|error| storage_error(&self.path, error))?;
+
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT OR REPLACE INTO sequences (name, yaml) VALUES (?1, ?2)",
+                (&sequence.name, &yaml),
+            )
+            .map(|_| ())
+            .map_err(|error| storage_error(&self.path, error))
+    }
+
+    fn remove_sequence(&self, name: &str) -> Pca9685Result<()> {
+        self.connection
+            .lock()
+            .unwrap()
+            .execute("DELETE FROM sequences WHERE name = ?1", (name,))
+            .map(|_| ())
+            .map_err(|error| storage_error(&self.path, error))
+    }
+}
+
+impl Config {
+    /// Opens the configured [Storage] (`storage`, or `sequences_dir`), if
+    /// any, relative paths being relative to `config_file_path`.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if both `storage` and
+    ///   `sequences_dir` are given, or SQLite storage is configured without
+    ///   the `sqlite` feature
+    /// * [Pca9685Error::StorageError] if the storage cannot be opened
+    pub fn open_storage(&self, config_file_path: &str) -> Pca9685Result<Option<Box<dyn Storage>>> {
+        let base_dir = Path::new(config_file_path)
+            .parent()
+            .unwrap_or_else(|| Path::new(""));
+
+        match (&self.storage, &self.sequences_dir) {
+            (Some(_), Some(_)) => Err(Pca9685Error::InvalidConfiguration(
+                "Config.storage and Config.sequences_dir are mutually exclusive".to_string(),
+            )),
+            (None, Some(dir)) => Ok(Some(Box::new(FileStorage::with_sequences_dir(
+                &base_dir.join(dir),
+            )))),
+            (Some(StorageConfig::Files { dir }), None) => {
+                Ok(Some(Box::new(FileStorage::new(&base_dir.join(dir)))))
+            }
+            #[cfg(feature = "sqlite")]
+            (Some(StorageConfig::Sqlite { path }), None) => {
+                Ok(Some(Box::new(SqliteStorage::open(&base_dir.join(path))?)))
+            }
+            #[cfg(not(feature = "sqlite"))]
+            (Some(StorageConfig::Sqlite { .. }), None) => Err(Pca9685Error::InvalidConfiguration(
+                "Config.storage.sqlite requires the sqlite feature".to_string(),
+            )),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FileStorage;
+    use crate::{CommandType, Config, Keyframe, SequenceConfig, Storage, StorageConfig};
+
+    fn sequence(name: &str) -> SequenceConfig {
+        SequenceConfig {
+            name: name.to_string(),
+            keyframes: vec![Keyframe {
+                t_ms: 0,
+                channel: 0,
+                command_type: CommandType::PulseCount,
+                value: Some(1000.0),
+            }],
+            steps: Vec::new(),
+            duration_ms: None,
+        }
+    }
+
+    /// Saves, replaces, lists and removes sequences
+    fn round_trip(storage: &dyn Storage) {
+        assert!(storage.sequences().unwrap().is_empty());
+
+        storage.save_sequence(&sequence("wave")).unwrap();
+        storage.save_sequence(&sequence("nod")).unwrap();
+        let mut updated = sequence("wave");
+        updated.duration_ms = Some(500);
+        storage.save_sequence(&updated).unwrap();
+
+        let mut sequences = storage.sequences().unwrap();
+        sequences.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(sequences, vec![sequence("nod"), updated]);
+
+        storage.remove_sequence("nod").unwrap();
+        storage.remove_sequence("nod").unwrap();
+        assert_eq!(storage.sequences().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn files() {
+        let dir = std::env::temp_dir().join("pca9685-storage-files");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        round_trip(&FileStorage::new(&dir));
+        assert!(dir.join("sequences/wave.yaml").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite() {
+        let path = std::env::temp_dir().join("pca9685-storage.sqlite");
+        let _ = std::fs::remove_file(&path);
+
+        round_trip(&super::SqliteStorage::open(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn open_storage() {
+        let mut config: Config =
+            serde_yaml::from_str("device: /dev/i2c-1\naddress: 0x40\n").unwrap();
+        let config_file_path = std::env::temp_dir().join("pca9685.yaml");
+        let config_file_path = config_file_path.to_str().unwrap();
+        assert!(config.open_storage(config_file_path).unwrap().is_none());
+
+        config.storage = Some(StorageConfig::Files {
+            dir: "pca9685-storage-open".to_string(),
+        });
+        assert!(config.open_storage(config_file_path).unwrap().is_some());
+
+        config.sequences_dir = Some("sequences".to_string());
+        assert!(config.open_storage(config_file_path).is_err());
+
+        config.storage = Some(StorageConfig::Sqlite {
+            path: "pca9685-storage-open.sqlite".to_string(),
+        });
+        config.sequences_dir = None;
+        let storage = config.open_storage(config_file_path);
+        assert_eq!(storage.is_ok(), cfg!(feature = "sqlite"));
+        let _ = std::fs::remove_file(std::env::temp_dir().join("pca9685-storage-open.sqlite"));
+    }
+}
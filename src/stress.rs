@@ -0,0 +1,353 @@
+//! Stresses a [Pca9685] with concurrent clients issuing randomized (valid)
+//! commands, verifying that none deadlock and that every Channel ends in a
+//! state some client commanded.  Requires the `stress` feature.
+use crate::{ChannelCommand, ChannelConfig, CommandType, Config, Pca9685, Pca9685Result};
+use pwm_pca9685::Channel;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The most errors (and inconsistencies) kept verbatim in a [StressReport]
+const MAX_REPORTED: usize = 10;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// `clients` threads each issuing `commands` randomized commands (from
+/// `seed`, offset by the client's index), declared deadlocked if none
+/// completes for `stall_timeout`.
+pub struct StressTest {
+    pub clients: usize,
+    pub commands: usize,
+    pub seed: u64,
+    pub stall_timeout: Duration,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// A command issued by a client
+pub enum StressCommand {
+    SetCount,
+    SetPct,
+    Batch,
+    ReadConfig,
+    ReadStats,
+    Snapshot,
+    DebugReport,
+}
+
+const STRESS_COMMANDS: [StressCommand; 7] = [
+    StressCommand::SetCount,
+    StressCommand::SetPct,
+    StressCommand::Batch,
+    StressCommand::ReadConfig,
+    StressCommand::ReadStats,
+    StressCommand::Snapshot,
+    StressCommand::DebugReport,
+];
+
+#[derive(Debug, PartialEq, Clone, Default)]
+/// The outcome of a [StressTest]: the commands completed (and failed, the
+/// first few verbatim), the final states no client commanded, and, if
+/// deadlocked, the command each stalled client was blocked in.
+pub struct StressReport {
+    pub completed: usize,
+    pub elapsed: Duration,
+    pub error_count: usize,
+    pub errors: Vec<String>,
+    pub inconsistencies: Vec<String>,
+    pub stalled: Vec<(usize, Option<StressCommand>)>,
+}
+
+impl StressReport {
+    pub fn passed(&self) -> bool {
+        self.error_count == 0 && self.inconsistencies.is_empty() && self.stalled.is_empty()
+    }
+}
+
+/// What a client saw: every count (None for full off) a write left each
+/// Channel at, and its errors
+#[derive(Default)]
+struct ClientResult {
+    counts: HashMap<u8, HashSet<Option<u16>>>,
+    errors: Vec<String>,
+}
+
+/// Returns the Channels which accept commands: configured (if `config` is
+/// strict), not ESCs, and not under maintenance
+pub fn commandable_channels(pca: &Pca9685, config: &Config) -> Vec<Channel> {
+    let under_maintenance = pca.maintenance();
+
+    (0..16_u8)
+        .map(|raw_channel| Channel::try_from(raw_channel).unwrap())
+        .filter(|channel| !under_maintenance.contains(channel))
+        .filter(|channel| {
+            pca.config(*channel).is_ok_and(|channel_config| {
+                channel_config.esc.is_none()
+                    && (channel_config.custom_limits.is_some() || !config.strict)
+            })
+        })
+        .collect()
+}
+
+impl StressTest {
+    /// Runs the test against `pca`, commanding only `channels`.  Clients
+    /// still blocked once the test is declared deadlocked are abandoned.
+    pub fn run(&self, pca: &Arc<Pca9685>, channels: &[Channel]) -> StressReport {
+        let started = Instant::now();
+        let mut initial: HashMap<u8, HashSet<Option<u16>>> = HashMap::new();
+        for channel in channels {
+            if let Ok(channel_config) = pca.config(*channel) {
+                initial
+                    .entry(*channel as u8)
+                    .or_default()
+                    .insert(channel_config.current_count);
+            }
+        }
+
+        let progress = Arc::new(AtomicUsize::new(0));
+        let in_flight: Arc<Vec<Mutex<Option<StressCommand>>>> =
+            Arc::new((0..self.clients).map(|_| Mutex::new(None)).collect());
+        let (sender, receiver) = mpsc::channel();
+        for client in 0..self.clients {
+            let (pca, progress, in_flight, sender) = (
+                pca.clone(),
+                progress.clone(),
+                in_flight.clone(),
+                sender.clone(),
+            );
+            let (channels, test) = (channels.to_vec(), *self);
+            thread::spawn(move || {
+                let mut rng = StdRng::seed_from_u64(test.seed.wrapping_add(client as u64));
+                let mut result = ClientResult::default();
+                for _ in 0..test.commands {
+                    let command = STRESS_COMMANDS[rng.gen_range(0..STRESS_COMMANDS.len())];
+                    *in_flight[client].lock().unwrap() = Some(command);
+                    if let Err(error) = issue(&pca, &channels, command, &mut rng, &mut result) {
+                        result.errors.push(format!("{:?}: {}", command, error));
+                    }
+                    progress.fetch_add(1, Ordering::Relaxed);
+                }
+                *in_flight[client].lock().unwrap() = None;
+                let _ = sender.send((client, result));
+            });
+        }
+        drop(sender);
+
+        let mut report = StressReport::default();
+        let mut finished = HashSet::new();
+        let (mut last_progress, mut last_progress_at) = (0, Instant::now());
+        while finished.len() < self.clients {
+            match receiver.recv_timeout(Duration::from_millis(100)) {
+                Ok((client, result)) => {
+                    finished.insert(client);
+                    report.error_count += result.errors.len();
+                    report.errors.extend(result.errors);
+                    for (raw_channel, counts) in result.counts {
+                        initial.entry(raw_channel).or_default().extend(counts);
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                // Every client panicked or finished
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+
+            let completed = progress.load(Ordering::Relaxed);
+            if completed != last_progress {
+                (last_progress, last_progress_at) = (completed, Instant::now());
+            } else if last_progress_at.elapsed() >= self.stall_timeout {
+                report.stalled = (0..self.clients)
+                    .filter(|client| !finished.contains(client))
+                    .map(|client| (client, *in_flight[client].lock().unwrap()))
+                    .collect();
+                break;
+            }
+        }
+        if report.stalled.is_empty() {
+            for client in (0..self.clients).filter(|client| !finished.contains(client)) {
+                report.error_count += 1;
+                report.errors.push(format!("Client {} panicked", client));
+            }
+        }
+        report.completed = progress.load(Ordering::Relaxed);
+        report.errors.truncate(MAX_REPORTED);
+
+        // A deadlocked Pca9685 would block the checks, too
+        if report.stalled.is_empty() {
+            report.inconsistencies = inconsistencies(pca, channels, &initial);
+        }
+        report.elapsed = started.elapsed();
+
+        report
+    }
+}
+
+/// Issues `command` to a random selection of `channels`, noting the counts
+/// written in `result`
+fn issue(
+    pca: &Pca9685,
+    channels: &[Channel],
+    command: StressCommand,
+    rng: &mut StdRng,
+    result: &mut ClientResult,
+) -> Pca9685Result<()> {
+    let channel = channels[rng.gen_range(0..channels.len())];
+    let mut note = |config: ChannelConfig| {
+        result
+            .counts
+            .entry(config.channel as u8)
+            .or_default()
+            .insert(config.current_count);
+    };
+
+    match command {
+        StressCommand::SetCount => {
+            let (min_count, max_count) = pca.config(channel)?.limits();
+            note(pca.set_pwm_count(channel, rng.gen_range(min_count..=max_count))?);
+        }
+        StressCommand::SetPct => note(pca.set_pct(channel, rng.gen_range(0.0..=1.0))?),
+        StressCommand::Batch => {
+            let mut selected: Vec<Channel> = channels
+                .iter()
+                .filter(|_| rng.gen_bool(0.5))
+                .cloned()
+                .collect();
+            if !selected.contains(&channel) {
+                selected.push(channel);
+            }
+
+            let commands: Vec<ChannelCommand> = selected
+                .iter()
+                .map(|channel| ChannelCommand {
+                    channel: *channel,
+                    command_type: CommandType::Percent,
+                    value: Some(rng.gen_range(0.0..=1.0)),
+                    basis: None,
+                    client_id: None,
+                })
+                .collect();
+            for config in pca.apply(&commands, true)? {
+                note(config);
+            }
+        }
+        StressCommand::ReadConfig => {
+            pca.config(channel)?;
+        }
+        StressCommand::ReadStats => {
+            pca.stats(channel)?;
+        }
+        StressCommand::Snapshot => {
+            pca.snapshot();
+        }
+        StressCommand::DebugReport => {
+            pca.debug_report();
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns a description of each of `channels` whose final state either no
+/// command produced (per `counts`), is beyond its limits, or disagrees
+/// between [Pca9685::config] and [Pca9685::snapshot]
+fn inconsistencies(
+    pca: &Pca9685,
+    channels: &[Channel],
+    counts: &HashMap<u8, HashSet<Option<u16>>>,
+) -> Vec<String> {
+    let snapshot = pca.snapshot();
+    let mut inconsistencies = Vec::new();
+
+    for channel in channels {
+        let raw_channel = *channel as u8;
+        let Ok(config) = pca.config(*channel) else {
+            inconsistencies.push(format!("Channel {} vanished", raw_channel));
+            continue;
+        };
+
+        if !counts
+            .get(&raw_channel)
+            .is_some_and(|counts| counts.contains(&config.current_count))
+        {
+            inconsistencies.push(format!(
+                "Channel {} ended at {:?}, which no command produced",
+                raw_channel, config.current_count
+            ));
+        }
+
+        let (min_count, max_count) = config.limits();
+        if config
+            .current_count
+            .is_some_and(|count| count < min_count || count > max_count)
+        {
+            inconsistencies.push(format!(
+                "Channel {} ended at {:?}, beyond its limits [{}, {}]",
+                raw_channel, config.current_count, min_count, max_count
+            ));
+        }
+
+        let snapshot_count = snapshot
+            .channels
+            .iter()
+            .find(|snapshot_config| snapshot_config.channel == *channel)
+            .map(|snapshot_config| snapshot_config.current_count);
+        if snapshot_count != Some(config.current_count) {
+            inconsistencies.push(format!(
+                "Channel {} is at {:?}, but its snapshot says {:?}",
+                raw_channel,
+                config.current_count,
+                snapshot_count.flatten()
+            ));
+        }
+    }
+    inconsistencies.truncate(MAX_REPORTED);
+
+    inconsistencies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{commandable_channels, StressTest};
+    use crate::{Config, Pca9685};
+    use pwm_pca9685::Channel;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn run() {
+        let config: Config = serde_yaml::from_str(
+            "
+            device: /dev/null
+            address: 0x40
+            output_frequency_hz: 50
+            strict: true
+            channels:
+              - channel: 0
+                custom_limits:
+                  count_limits: { min_on_count: 200, max_on_count: 400 }
+              - channel: 1
+                custom_limits:
+                  count_limits: { min_on_count: 300, max_on_count: 500 }
+              - channel: 2
+                custom_limits:
+                  count_limits: { min_on_count: 100, max_on_count: 600 }
+            ",
+        )
+        .unwrap();
+        let pca = Arc::new(Pca9685::null(&config));
+        let channels = commandable_channels(&pca, &config);
+        assert_eq!(channels, vec![Channel::C0, Channel::C1, Channel::C2]);
+
+        let test = StressTest {
+            clients: 4,
+            commands: 200,
+            seed: 7,
+            stall_timeout: Duration::from_secs(10),
+        };
+        let report = test.run(&pca, &channels);
+        assert!(report.passed(), "{:?}", report);
+        assert_eq!(report.completed, 800);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{GamepadState, Rate, TeleopConfig, TeleopProfile};
+use std::collections::HashMap;
+
+/// A [TeleopConfig] together with the active profile
+pub(crate) struct Teleop {
+    config: TeleopConfig,
+    active: Option<String>,
+    pressed_combo: Option<String>,
+    rate: Rate,
+    rate_button_pressed: bool,
+}
+
+impl Teleop {
+    pub fn new(config: &TeleopConfig) -> Self {
+        let active = config
+            .default_profile
+            .clone()
+            .or_else(|| config.profiles.first().map(|profile| profile.name.clone()));
+
+        Teleop {
+            config: config.clone(),
+            active,
+            pressed_combo: None,
+            rate: Rate::default(),
+            rate_button_pressed: false,
+        }
+    }
+
+    pub fn profiles(&self) -> &[TeleopProfile] {
+        &self.config.profiles
+    }
+
+    pub fn active(&self) -> Option<&TeleopProfile> {
+        let active = self.active.as_ref()?;
+
+        self.config
+            .profiles
+            .iter()
+            .find(|profile| &profile.name == active)
+    }
+
+    /// Returns true if `name` is a known profile (and is now active)
+    pub fn select(&mut self, name: &str) -> bool {
+        if !self
+            .config
+            .profiles
+            .iter()
+            .any(|profile| profile.name == name)
+        {
+            return false;
+        }
+
+        self.active = Some(name.to_string());
+        true
+    }
+
+    /// Selects the profile whose button combo is pressed in `state`, if any.
+    /// A combo selects its profile once, when it is first pressed, so that a
+    /// profile selected via REST isn't immediately overridden by a held combo.
+    pub fn select_by_buttons(&mut self, state: &GamepadState) {
+        let pressed = |button: &usize| matches!(state.buttons.get(*button), Some(true));
+        let combo = self
+            .config
+            .profiles
+            .iter()
+            .filter(|profile| !profile.select_buttons.is_empty())
+            .find(|profile| profile.select_buttons.iter().all(pressed))
+            .map(|profile| profile.name.clone());
+
+        if combo.is_some() && combo != self.pressed_combo {
+            log::info!(target: "pca9685", "Teleop profile {:?} selected by button combo", combo);
+            self.active = combo.clone();
+        }
+        self.pressed_combo = combo;
+    }
+
+    pub fn set_rate(&mut self, rate: Rate) {
+        self.rate = rate;
+    }
+
+    /// Returns the rate to select if the active profile's `rate_button` has
+    /// just been pressed in `state`
+    pub fn toggle_rate_by_button(&mut self, state: &GamepadState) -> Option<Rate> {
+        let rate_button = self.active().and_then(|profile| profile.rate_button)?;
+        let pressed = matches!(state.buttons.get(rate_button), Some(true));
+        let just_pressed = pressed && !self.rate_button_pressed;
+        self.rate_button_pressed = pressed;
+
+        if !just_pressed {
+            return None;
+        }
+
+        self.rate = match self.rate {
+            Rate::Low => Rate::High,
+            Rate::High => Rate::Low,
+        };
+        Some(self.rate)
+    }
+
+    /// Returns the mixer inputs, by mixer, that the active profile maps
+    /// `state`'s axes onto.  Axes absent from `state` are ignored.
+    pub fn map(&self, state: &GamepadState) -> HashMap<String, HashMap<String, f64>> {
+        let mut inputs: HashMap<String, HashMap<String, f64>> = HashMap::new();
+
+        if let Some(profile) = self.active() {
+            for mapping in &profile.axes {
+                if let Some(axis_value) = state.axes.get(mapping.axis) {
+                    inputs
+                        .entry(mapping.mixer.clone())
+                        .or_default()
+                        .insert(mapping.input.clone(), mapping.map(*axis_value));
+                }
+            }
+        }
+
+        inputs
+    }
+}
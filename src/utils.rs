@@ -1,37 +1,1965 @@
+#[cfg(feature = "linux")]
+use linux_embedded_hal::i2cdev::core::I2CDevice;
+#[cfg(feature = "linux")]
+use linux_embedded_hal::i2cdev::linux::LinuxI2CDevice;
 use pwm_pca9685::Channel;
 use serde::de::{self, Visitor};
 use serde::{Deserializer, Serializer};
+use serde_yaml::{Mapping, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use std::{fmt, fs};
 
 use crate::{
-    ChannelConfig, ChannelCountLimits, ChannelLimits, ChannelPulseWidthLimits, Config,
-    Pca9685Error, Pca9685Result, PcaClockConfig, PCA_PWM_RESOLUTION,
+    AxisMapping, ChannelCalibration, ChannelCommand, ChannelConfig, ChannelCountLimits,
+    ChannelGroup, ChannelLimits, ChannelPulseWidthLimits, ChannelStats, CircuitBreakerConfig,
+    CommandType, Config, ConversionRow, DebugReport, DerivedChannelConfig, DerivedExpression,
+    DisplayUnit, DualRate, Easing, EscConfig, FeedbackConfig, FeedbackSource, FindingSeverity,
+    FrequencyStep, GaitConfig, GimbalAxis, GimbalConfig, GpioPin, IdleConfig, InputShaping,
+    JitterConfig, Keyframe, LinearAxisConfig, LoopRegion, LowPassConfig, MixerConfig, Pca9685,
+    Pca9685Error, Pca9685Result, PcaClockConfig, PidGains, PositionVerification, PresetConfig,
+    PresetOutput, Rate, ReducedHold, Replay, RuleAction, RuleCondition, RuleConfig, SceneConfig,
+    SequenceConfig, SequenceStep, StepSample, StepTest, TeleopConfig, ThermalLimits,
+    WatchdogConfig, INTERNAL_OSC_HZ, MAX_ESC_ARMING_MS, MAX_EXTERNAL_CLOCK_HZ,
+    MAX_JITTER_BANDWIDTH_HZ, MAX_STEP_TEST_MS, MIN_GAIT_PERIOD_MS, PCA_PWM_RESOLUTION,
+    SERVO_SAFE_FREQUENCY_HZ,
 };
 
+/// The smallest value the PCA9685 accepts in its prescale register
+const MIN_PRESCALE: f64 = 3.0;
+
+/// The 7-bit I2C addresses that aren't reserved (e.g., for the general call)
+const I2C_ADDRESSES: std::ops::RangeInclusive<u8> = 0x08..=0x77;
+
+/// The all-call address the PCA9685 responds to at power-on (ALLCALLADR)
+const DEFAULT_ALL_CALL_ADDRESS: u8 = 0x70;
+
+/// Returns the prescale value that yields the output frequency closest to
+/// `output_frequency_hz` from an oscillator of `oscillator_hz` (see
+/// [Config::oscillator_hz]).
+pub fn calculate_prescale(oscillator_hz: f64, output_frequency_hz: u16) -> u8 {
+    // Per PCA 9685 Datasheet, 7.3.5 PWM frequency PRE_SCALE:
+    //    prescale_value = round(osc_clock/(4096 * output_frequency_hz)) - 1
+    let value = oscillator_hz / (PCA_PWM_RESOLUTION as f64 * output_frequency_hz as f64);
+
+    (value.round() - 1.0).clamp(MIN_PRESCALE, u8::MAX as f64) as u8
+}
+
+/// Returns the output frequency (in Hz) produced by `prescale` from an
+/// oscillator of `oscillator_hz`.
+pub fn prescale_output_frequency_hz(oscillator_hz: f64, prescale: u8) -> f64 {
+    oscillator_hz / (PCA_PWM_RESOLUTION as f64 * (prescale as f64 + 1.0))
+}
+
+/// Returns the count at which `channel`'s output rises each cycle when
+/// staggered (see [Config::stagger_on_counts]): the Channels' rising edges
+/// are spread evenly across the cycle, 256 counts apart.
+pub fn staggered_on_count(channel: Channel) -> u16 {
+    (channel as u16 % 16) * (PCA_PWM_RESOLUTION / 16)
+}
+
+/// Steps the output frequency from `from_hz` to `to_hz` (inclusive) by
+/// `step_hz`, reporting the prescale chosen for each requested frequency and
+/// the rounding error of the resulting output frequency, from the internal
+/// oscillator.
+pub fn frequency_sweep(from_hz: u16, to_hz: u16, step_hz: u16) -> Vec<FrequencyStep> {
+    (from_hz..=to_hz)
+        .step_by(step_hz.max(1) as usize)
+        .map(|requested_hz| {
+            let prescale = calculate_prescale(INTERNAL_OSC_HZ, requested_hz);
+            let actual_hz = prescale_output_frequency_hz(INTERNAL_OSC_HZ, prescale);
+
+            FrequencyStep {
+                requested_hz,
+                prescale,
+                actual_hz,
+                error_pct: 100.0 * (actual_hz - requested_hz as f64) / requested_hz as f64,
+            }
+        })
+        .collect()
+}
+
+/// The MODE1 register, present on every PCA9685
+#[cfg(feature = "linux")]
+const MODE1_REGISTER: u8 = 0x00;
+
+/// The LED All Call address, answered by every PCA9685 by default
+#[cfg(feature = "linux")]
+const ALLCALL_ADDRESS: u8 = 0x70;
+
+/// Probes each I2C bus (`/dev/i2c-*`) for devices answering at the addresses
+/// a PCA9685 may be strapped to (0x40 to 0x7f, less the LED All Call
+/// address), by reading their MODE1 register.  Returns each (bus, address)
+/// found, in order.  Addresses claimed by a kernel driver are skipped.
+#[cfg(feature = "linux")]
+pub fn scan_i2c() -> Vec<(String, u8)> {
+    let mut buses: Vec<String> = fs::read_dir("/dev")
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().to_string_lossy().to_string())
+        .filter(|path| path.starts_with("/dev/i2c-"))
+        .collect();
+    buses.sort();
+
+    let mut found = Vec::new();
+    for bus in buses {
+        for address in (0x40..=0x7f).filter(|address| *address != ALLCALL_ADDRESS) {
+            let answered = LinuxI2CDevice::new(&bus, address as u16)
+                .and_then(|mut device| device.smbus_read_byte_data(MODE1_REGISTER))
+                .is_ok();
+            if answered {
+                found.push((bus.clone(), address));
+            }
+        }
+    }
+
+    found
+}
+
+/// Renders the samples of a [StepTest] as CSV (`t_ms,count,feedback`), with
+/// an empty `feedback` if the Channel has none.
+pub fn step_response_csv(samples: &[StepSample]) -> String {
+    let mut csv = String::from("t_ms,count,feedback\n");
+    for sample in samples {
+        let feedback = sample.feedback.map(|feedback| feedback.to_string());
+        csv.push_str(&format!(
+            "{:0.3},{},{}\n",
+            sample.t_ms,
+            sample.count,
+            feedback.unwrap_or_default()
+        ));
+    }
+
+    csv
+}
+
+/// Renders a [crate::Pca9685::conversion_table] as CSV (`input,count,pw_ms`)
+pub fn conversion_table_csv(rows: &[ConversionRow]) -> String {
+    let mut csv = String::from("input,count,pw_ms\n");
+    for row in rows {
+        csv.push_str(&format!("{},{},{:0.4}\n", row.input, row.count, row.pw_ms));
+    }
+
+    csv
+}
+
+/// Reads the configuration file at `path`, interpolating its strings (see
+/// [interpolate]), each of which is recorded in `interpolated` (see
+/// [Config::interpolated])
+fn read_config_value(
+    path: &Path,
+    interpolated: &mut BTreeMap<String, String>,
+) -> Pca9685Result<Value> {
+    let yaml = fs::read_to_string(path).map_err(|error| {
+        Pca9685Error::InvalidConfiguration(format!("Unable to read {}: {}", path.display(), error))
+    })?;
+
+    let mut root = serde_yaml::from_str(&yaml).map_err(|error| {
+        Pca9685Error::InvalidConfiguration(format!("Unable to parse {}: {}", path.display(), error))
+    })?;
+    interpolate_values(
+        &mut root,
+        path.parent().unwrap_or(Path::new("")),
+        interpolated,
+    )
+    .map_err(|error| {
+        Pca9685Error::InvalidConfiguration(format!("Unable to parse {}: {}", path.display(), error))
+    })?;
+
+    Ok(root)
+}
+
+/// Interpolates (see [interpolate]) every string within `value`, recording
+/// each that changes in `interpolated`, by its interpolated value
+fn interpolate_values(
+    value: &mut Value,
+    dir: &Path,
+    interpolated: &mut BTreeMap<String, String>,
+) -> Result<(), String> {
+    match value {
+        Value::String(string) => {
+            let resolved = interpolate(string, dir)?;
+            if resolved != *string {
+                interpolated.insert(resolved.clone(), mem::replace(string, resolved));
+            }
+        }
+        Value::Sequence(sequence) => {
+            for value in sequence {
+                interpolate_values(value, dir, interpolated)?;
+            }
+        }
+        Value::Mapping(mapping) => {
+            for (_, value) in mapping.iter_mut() {
+                interpolate_values(value, dir, interpolated)?;
+            }
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Restores each string within `value` that was interpolated (see
+/// [Config::interpolated]) to the text it was interpolated from
+fn uninterpolate_values(value: &mut Value, interpolated: &BTreeMap<String, String>) {
+    match value {
+        Value::String(string) => {
+            if let Some(source) = interpolated.get(string.as_str()) {
+                *string = source.clone();
+            }
+        }
+        Value::Sequence(sequence) => sequence
+            .iter_mut()
+            .for_each(|value| uninterpolate_values(value, interpolated)),
+        Value::Mapping(mapping) => mapping
+            .values_mut()
+            .for_each(|value| uninterpolate_values(value, interpolated)),
+        _ => (),
+    }
+}
+
+/// Replaces each `${NAME}` in `value` with the environment variable `NAME`,
+/// and each `${file:PATH}` with the content of the file `PATH` (relative to
+/// `dir`, trailing whitespace removed), so that secrets can be kept out of
+/// the configuration file; `$${` yields a literal `${`.
+///
+/// Error conditions:
+/// * A description of the reference that cannot be resolved (e.g., an unset
+///   environment variable), or of the unterminated `${`
+pub fn interpolate(value: &str, dir: &Path) -> Result<String, String> {
+    let mut interpolated = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let (before, after) = rest.split_at(start);
+        if let Some(before) = before.strip_suffix('$') {
+            interpolated.push_str(before);
+            interpolated.push_str("${");
+            rest = &after[2..];
+            continue;
+        }
+
+        let end = after
+            .find('}')
+            .ok_or(format!("Unterminated '${{' in '{}'", value))?;
+        let reference = &after[2..end];
+        let replacement = match reference.strip_prefix("file:") {
+            Some(file) => fs::read_to_string(dir.join(file))
+                .map(|content| content.trim_end().to_string())
+                .map_err(|error| format!("Unable to read {}: {}", file, error))?,
+            None => env::var(reference)
+                .map_err(|_| format!("Environment variable {} is not set", reference))?,
+        };
+
+        interpolated.push_str(before);
+        interpolated.push_str(&replacement);
+        rest = &after[end + 1..];
+    }
+    interpolated.push_str(rest);
+
+    Ok(interpolated)
+}
+
+/// Merges the files `include`d by the configuration `root` (read from `path`)
+/// beneath it (see [Config::load_from_file]); `including` holds the files
+/// being resolved, to detect cycles
+fn resolve_includes(
+    mut root: Value,
+    path: &Path,
+    including: &mut Vec<PathBuf>,
+    interpolated: &mut BTreeMap<String, String>,
+) -> Pca9685Result<Value> {
+    let invalid = |message: String| {
+        Pca9685Error::InvalidConfiguration(format!("{}: {}", path.display(), message))
+    };
+
+    let includes = match root
+        .as_mapping_mut()
+        .and_then(|root| root.remove("include"))
+    {
+        Some(includes) => serde_yaml::from_value::<Vec<String>>(includes)
+            .map_err(|_| invalid("include must be a list of paths".to_string()))?,
+        None => return Ok(root),
+    };
+
+    let canonical_path = path
+        .canonicalize()
+        .map_err(|error| invalid(error.to_string()))?;
+    if including.contains(&canonical_path) {
+        return Err(invalid("includes itself".to_string()));
+    }
+    including.push(canonical_path);
+
+    let mut merged = Value::Mapping(Mapping::new());
+    for include in includes {
+        let include_path = path.parent().unwrap_or(Path::new("")).join(include);
+        let included = read_config_value(&include_path, interpolated)?;
+        merged = merge_config_values(
+            merged,
+            resolve_includes(included, &include_path, including, interpolated)?,
+        );
+    }
+    including.pop();
+
+    Ok(merge_config_values(merged, root))
+}
+
+/// Returns the `channel` (or else the `name`) by which list entries are merged
+fn config_entry_key(entry: &Value) -> Option<&Value> {
+    entry.get("channel").or_else(|| entry.get("name"))
+}
+
+/// Removes unset (null) fields from `value`'s mappings, recursively
+fn remove_nulls(value: &mut Value) {
+    match value {
+        Value::Mapping(mapping) => {
+            mapping.retain(|_, value| !value.is_null());
+            mapping.values_mut().for_each(remove_nulls);
+        }
+        Value::Sequence(sequence) => sequence.iter_mut().for_each(remove_nulls),
+        _ => (),
+    }
+}
+
+/// Merges `overlay` over `base` (see [Config::load_from_file])
+fn merge_config_values(base: Value, overlay: Value) -> Value {
+    match (base, overlay) {
+        (Value::Mapping(mut base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => *existing = merge_config_values(mem::take(existing), value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+            Value::Mapping(base)
+        }
+        (Value::Sequence(mut base), Value::Sequence(overlay))
+            if base
+                .iter()
+                .chain(overlay.iter())
+                .all(|entry| config_entry_key(entry).is_some()) =>
+        {
+            for entry in overlay {
+                let existing = base
+                    .iter()
+                    .position(|existing| config_entry_key(existing) == config_entry_key(&entry));
+                match existing {
+                    Some(index) => {
+                        base[index] = merge_config_values(mem::take(&mut base[index]), entry)
+                    }
+                    None => base.push(entry),
+                }
+            }
+            Value::Sequence(base)
+        }
+        (_, overlay) => overlay,
+    }
+}
+
+/// Returns `yaml` with the top-level `channels` entry for `raw_channel`
+/// replaced by (or, absent one, followed by) `entry`, leaving the rest of the
+/// text as written; `None` if `channels` isn't a block sequence
+fn splice_channel_entry(yaml: &str, raw_channel: u8, entry: &Value) -> Option<String> {
+    let lines: Vec<&str> = yaml.lines().collect();
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let is_content = |line: &str| !line.trim().is_empty() && !line.trim_start().starts_with('#');
+    let render = |item_indent: usize| {
+        let rendered = serde_yaml::to_string(&Value::Sequence(vec![entry.clone()])).ok()?;
+        Some(
+            rendered
+                .lines()
+                .map(|line| format!("{}{}", " ".repeat(item_indent), line))
+                .collect::<Vec<String>>(),
+        )
+    };
+    let join = |lines: Vec<String>| lines.join("\n") + "\n";
+
+    let key_index = lines.iter().position(|line| {
+        line.strip_prefix("channels:")
+            .is_some_and(|rest| !is_content(rest))
+    });
+    let Some(key_index) = key_index else {
+        if lines.iter().any(|line| line.starts_with("channels")) {
+            return None;
+        }
+        let mut spliced: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        spliced.push("channels:".to_string());
+        spliced.extend(render(2)?);
+        return Some(join(spliced));
+    };
+
+    // The items of the sequence: the content lines from `channels:` up to the
+    // next line outdented from them (or at their indent, but not an item)
+    let first = (key_index + 1..lines.len()).find(|index| is_content(lines[*index]));
+    let item_indent = match first {
+        Some(index) if lines[index].trim_start().starts_with('-') => indent(lines[index]),
+        Some(index) if indent(lines[index]) == 0 => 2,
+        Some(_) => return None,
+        None => 2,
+    };
+    let mut items: Vec<(usize, usize)> = Vec::new();
+    for (index, line) in lines.iter().enumerate().skip(key_index + 1) {
+        if !is_content(line) {
+            continue;
+        }
+        if indent(line) < item_indent
+            || (indent(line) == item_indent && !line.trim_start().starts_with('-'))
+        {
+            break;
+        }
+        if indent(line) == item_indent {
+            items.push((index, index + 1));
+        } else {
+            items.last_mut()?.1 = index + 1;
+        }
+    }
+
+    let existing = items.iter().find(|(start, end)| {
+        let item: Option<Value> = serde_yaml::from_str(&lines[*start..*end].join("\n")).ok();
+        item.as_ref()
+            .and_then(|item| item.get(0))
+            .and_then(|item| item.get("channel"))
+            == Some(&Value::from(raw_channel))
+    });
+    let (start, end) = match existing {
+        Some(&(start, end)) => (start, end),
+        None => {
+            let end = items.last().map_or(key_index + 1, |(_, end)| *end);
+            (end, end)
+        }
+    };
+
+    let mut spliced: Vec<String> = lines[..start].iter().map(|line| line.to_string()).collect();
+    spliced.extend(render(item_indent)?);
+    spliced.extend(lines[end..].iter().map(|line| line.to_string()));
+    Some(join(spliced))
+}
+
 impl Config {
-    pub fn load_from_file(path: &String) -> Config {
-        let config = fs::read_to_string(path).unwrap();
+    /// Loads the YAML configuration file at `path`, merged over the files it
+    /// `include`s (see [Config::include]).  Strings may refer to environment
+    /// variables (`${NAME}`) or to files (`${file:PATH}`), e.g. to keep
+    /// secrets out of the configuration.  Included files are merged in
+    /// order, each overriding those before it, and the including file
+    /// overrides them all: mappings are merged key by key, lists of Channels
+    /// (or of named entries, e.g. groups and mixers) entry by entry on their
+    /// `channel` (or `name`), and any other value is replaced.
+    pub fn load_from_file(path: &str) -> Config {
+        Config::try_load_from_file(path).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// As [Config::load_from_file], failing with
+    /// [Pca9685Error::InvalidConfiguration] if a file cannot be read or
+    /// parsed, includes itself, or refers to an unset environment variable
+    pub fn try_load_from_file(path: &str) -> Pca9685Result<Config> {
+        let mut interpolated = BTreeMap::new();
+        let root = read_config_value(Path::new(path), &mut interpolated)?;
+        let root = resolve_includes(root, Path::new(path), &mut Vec::new(), &mut interpolated)?;
+
+        let config: Config = serde_yaml::from_value(root).map_err(|error| {
+            Pca9685Error::InvalidConfiguration(format!("Unable to parse {}: {}", path, error))
+        })?;
+
+        Ok(Config {
+            interpolated,
+            ..config
+        })
+    }
+
+    /// Returns the configuration with each interpolated string (see
+    /// [Config::interpolated]) restored to the references it was written
+    /// with, e.g. to serve it without disclosing the secrets they refer to.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if the configuration cannot be
+    ///   serialized
+    pub fn uninterpolated(&self) -> Pca9685Result<Config> {
+        let invalid = |error: String| {
+            Pca9685Error::InvalidConfiguration(format!("Unable to serialize: {}", error))
+        };
+
+        let mut root = serde_yaml::to_value(self).map_err(|error| invalid(error.to_string()))?;
+        uninterpolate_values(&mut root, &self.interpolated);
+
+        serde_yaml::from_value(root).map_err(|error| invalid(error.to_string()))
+    }
+
+    /// Returns the Configs of the devices driven by a [crate::Pca9685Manager]:
+    /// this one (without its `devices`), then one for each
+    /// [crate::DeviceConfig],
+    /// sharing this one's output settings
+    pub fn device_configs(&self) -> Vec<Config> {
+        let first = Config {
+            devices: Vec::new(),
+            ..self.clone()
+        };
+        let further = self.devices.iter().map(|device| Config {
+            device: device.device.clone(),
+            address: device.address,
+            output_frequency_hz: self.output_frequency_hz,
+            prescale: self.prescale,
+            open_drain: self.open_drain,
+            disabled_output: self.disabled_output,
+            stagger_on_counts: self.stagger_on_counts,
+            external_clock_hz: self.external_clock_hz,
+            subaddresses: self.subaddresses.clone(),
+            all_call_address: self.all_call_address,
+            channels: device.channels.clone(),
+            devices: Vec::new(),
+            groups: Vec::new(),
+            mixers: Vec::new(),
+            teleop: Default::default(),
+            watchdog: None,
+            circuit_breaker: self.circuit_breaker,
+            idle: None,
+            startup: self.startup,
+            gimbal: None,
+            presets: Vec::new(),
+            scenes: Default::default(),
+            gaits: Vec::new(),
+            linear_axes: Vec::new(),
+            rules: Vec::new(),
+            virtual_channels: Vec::new(),
+            derived_channels: Vec::new(),
+            gpio: self.gpio,
+            allow_jitter: self.allow_jitter,
+            strict: self.strict,
+            include: Vec::new(),
+            autopersist: false,
+            sequences_dir: None,
+            storage: None,
+            interpolated: self.interpolated.clone(),
+        });
+
+        std::iter::once(first).chain(further).collect()
+    }
+
+    /// Saves the configuration (not the output or owner) of `config`'s
+    /// Channel into the YAML configuration file at `path`, replacing any
+    /// existing entry for the Channel, so that it survives a restart (and
+    /// overrides any files the configuration includes).  Only the Channel's
+    /// entry is rewritten, so comments and formatting elsewhere in the file
+    /// are kept, unless `channels` is not a block sequence (e.g. is written in
+    /// flow style), in which case the whole file is rewritten without them.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if the file cannot be read,
+    ///   parsed or written, or wouldn't pass [Pca9685::validate_config] once
+    ///   updated
+    pub fn persist_channel(path: &str, config: &ChannelConfig) -> Pca9685Result<()> {
+        let invalid = |error: String| {
+            Pca9685Error::InvalidConfiguration(format!("Unable to persist to {}: {}", path, error))
+        };
+
+        let yaml = fs::read_to_string(path).map_err(|error| invalid(error.to_string()))?;
+        let mut root: Value =
+            serde_yaml::from_str(&yaml).map_err(|error| invalid(error.to_string()))?;
+        let channels = root
+            .as_mapping_mut()
+            .ok_or_else(|| invalid("configuration is not a mapping".to_string()))?
+            .entry(Value::from("channels"))
+            .or_insert_with(|| Value::Sequence(Vec::new()))
+            .as_sequence_mut()
+            .ok_or_else(|| invalid("channels is not a sequence".to_string()))?;
+
+        let entry = config.file_entry()?;
+        let raw_channel = Value::from(config.channel as u8);
+        match channels
+            .iter_mut()
+            .find(|existing| existing.get("channel") == Some(&raw_channel))
+        {
+            Some(existing) => *existing = entry.clone(),
+            None => channels.push(entry.clone()),
+        }
+
+        let updated = resolve_includes(
+            root.clone(),
+            Path::new(path),
+            &mut Vec::new(),
+            &mut BTreeMap::new(),
+        )?;
+        let updated: Config =
+            serde_yaml::from_value(updated).map_err(|error| invalid(error.to_string()))?;
+        let errors: Vec<String> = Pca9685::validate_config(&updated)
+            .findings
+            .into_iter()
+            .filter(|finding| finding.severity == FindingSeverity::Error)
+            .map(|finding| format!("{}: {}", finding.path, finding.message))
+            .collect();
+        if !errors.is_empty() {
+            return Err(invalid(errors.join("; ")));
+        }
+
+        // Edit the Channel's entry in place where the edit reads back as the
+        // updated configuration, and otherwise rewrite the whole file
+        let yaml = match splice_channel_entry(&yaml, config.channel as u8, &entry)
+            .filter(|spliced| serde_yaml::from_str::<Value>(spliced).ok().as_ref() == Some(&root))
+        {
+            Some(spliced) => spliced,
+            None => serde_yaml::to_string(&root).map_err(|error| invalid(error.to_string()))?,
+        };
+
+        // Write alongside, then rename, so the file is never left half-written
+        let staged_path = format!("{}.tmp", path);
+        fs::write(&staged_path, yaml)
+            .and_then(|_| fs::rename(&staged_path, path))
+            .map_err(|error| invalid(error.to_string()))
+    }
+
+    /// Renders the configuration as the YAML of a configuration file:
+    /// Channels as per [ChannelConfig::file_entry], unset fields omitted, and
+    /// interpolated strings as written (see [Config::uninterpolated])
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if the configuration cannot be
+    ///   serialized
+    pub fn to_yaml(&self) -> Pca9685Result<String> {
+        let invalid = |error: String| {
+            Pca9685Error::InvalidConfiguration(format!("Unable to serialize: {}", error))
+        };
+
+        let mut root = match serde_yaml::to_value(self) {
+            Ok(Value::Mapping(root)) => root,
+            Ok(_) => return Err(invalid("configuration is not a mapping".to_string())),
+            Err(error) => return Err(invalid(error.to_string())),
+        };
+        root.insert(
+            Value::from("channels"),
+            Value::Sequence(
+                self.channels
+                    .iter()
+                    .map(ChannelConfig::file_entry)
+                    .collect::<Pca9685Result<_>>()?,
+            ),
+        );
+
+        let mut root = Value::Mapping(root);
+        remove_nulls(&mut root);
+        uninterpolate_values(&mut root, &self.interpolated);
+        serde_yaml::to_string(&root).map_err(|error| invalid(error.to_string()))
+    }
+
+    /// Replaces the configuration file at `path` with the configuration (as
+    /// by [Config::to_yaml]), never leaving it half-written.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if the configuration cannot be
+    ///   serialized, or the file written
+    pub fn save_to_file(&self, path: &str) -> Pca9685Result<()> {
+        let yaml = self.to_yaml()?;
+
+        let staged_path = format!("{}.tmp", path);
+        fs::write(&staged_path, yaml)
+            .and_then(|_| fs::rename(&staged_path, path))
+            .map_err(|error| {
+                Pca9685Error::InvalidConfiguration(format!("Unable to save to {}: {}", path, error))
+            })
+    }
+
+    /// Writes a commented starter configuration for the PCA9685 at `address`
+    /// on `device` to `path`, with all 16 Channels listed but unconfigured,
+    /// and in `strict` mode, so that nothing moves until each Channel is
+    /// given its limits.  Returns the configuration written.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if the file cannot be written
+    pub fn write_starter_file(path: &str, device: &str, address: u8) -> Pca9685Result<Config> {
+        let yaml = Config::starter_yaml(device, address, 50, &[]);
+
+        fs::write(path, &yaml).map_err(|error| {
+            Pca9685Error::InvalidConfiguration(format!("Unable to write {}: {}", path, error))
+        })?;
+
+        serde_yaml::from_str(&yaml).map_err(|error| {
+            Pca9685Error::InvalidConfiguration(format!("Unable to parse {}: {}", path, error))
+        })
+    }
+
+    /// Returns the commented starter configuration (see
+    /// [Config::write_starter_file]) for the PCA9685 at `address` on
+    /// `device`, output at `output_frequency_hz`, with each Channel of
+    /// `names` given its name.
+    pub fn starter_yaml(
+        device: &str,
+        address: u8,
+        output_frequency_hz: u16,
+        names: &[(u8, String)],
+    ) -> String {
+        let frequency_comment = if SERVO_SAFE_FREQUENCY_HZ.contains(&output_frequency_hz) {
+            "Suits most analog servos; LEDs may prefer a higher frequency"
+        } else {
+            "Suits LEDs; too high (or low) for most servos"
+        };
+        let mut yaml = format!(
+            "# Starter configuration for the PCA9685 at {:#02x} on {}.\n\
+             # See README.md for everything else that can be configured.\n\
+             device: {}\n\
+             address: {:#02x}\n\
+             \n\
+             # {}\n\
+             output_frequency_hz: {}\n\
+             \n\
+             # Commands to a channel are rejected until it has custom_limits\n\
+             strict: true\n\
+             \n\
+             # Configure each channel before use, e.g.:\n\
+             #  - channel: 0\n\
+             #    name: pan\n\
+             #    custom_limits:\n\
+             #      pw_limits: {{ min_on_ms: 1.0, max_on_ms: 2.0 }}\n\
+             channels:\n",
+            address, device, device, address, frequency_comment, output_frequency_hz
+        );
+        for channel in 0..16 {
+            yaml.push_str(&format!("  - channel: {}\n", channel));
+            if let Some((_, name)) = names.iter().find(|(named, _)| *named == channel) {
+                yaml.push_str(&format!("    name: {}\n", name));
+            }
+        }
+
+        yaml
+    }
+
+    /// Validates that exactly one of `output_frequency_hz` or `prescale` is
+    /// given, that any `prescale` is one the PCA9685 accepts, and that any
+    /// `external_clock_hz` is within what EXTCLK accepts
+    pub fn validate_clock(&self) -> Pca9685Result<()> {
+        if let Some(external_clock_hz) = self.external_clock_hz {
+            if external_clock_hz == 0 || external_clock_hz > MAX_EXTERNAL_CLOCK_HZ {
+                return Err(Pca9685Error::InvalidConfiguration(format!(
+                    "Config.external_clock_hz must be greater than 0, and at most {}",
+                    MAX_EXTERNAL_CLOCK_HZ
+                )));
+            }
+        }
+
+        match (self.output_frequency_hz, self.prescale) {
+            (0, None) => Err(Pca9685Error::InvalidConfiguration(
+                "One of Config.output_frequency_hz or Config.prescale is required".to_string(),
+            )),
+            (0, Some(prescale)) if (prescale as f64) < MIN_PRESCALE => {
+                Err(Pca9685Error::InvalidConfiguration(format!(
+                    "Config.prescale must be at least {}",
+                    MIN_PRESCALE
+                )))
+            }
+            (0, Some(_)) => Ok(()),
+            (_, None) => Ok(()),
+            (_, Some(_)) => Err(Pca9685Error::InvalidConfiguration(
+                "Config.output_frequency_hz and Config.prescale are mutually exclusive".to_string(),
+            )),
+        }
+    }
+
+    pub fn default_all_call_address() -> Option<u8> {
+        Some(DEFAULT_ALL_CALL_ADDRESS)
+    }
+
+    /// Validates that there are at most 3 `subaddresses`, and that they and
+    /// any `all_call_address` are distinct, usable 7-bit addresses other than
+    /// the device's own
+    pub fn validate_addresses(&self) -> Pca9685Result<()> {
+        if self.subaddresses.len() > 3 {
+            return Err(Pca9685Error::InvalidConfiguration(
+                "Config.subaddresses holds at most 3 addresses".to_string(),
+            ));
+        }
+
+        let mut addresses = HashSet::from([self.address]);
+        for address in self.subaddresses.iter().chain(&self.all_call_address) {
+            if !I2C_ADDRESSES.contains(address) || !addresses.insert(*address) {
+                return Err(Pca9685Error::InvalidConfiguration(format!(
+                    "{:#02x} is reserved, or appears more than once among Config.address, \
+                     Config.subaddresses and Config.all_call_address",
+                    address
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the frequency (in Hz) of the oscillator clocking the device:
+    /// `external_clock_hz`, if given, or else the internal oscillator's
+    pub fn oscillator_hz(&self) -> f64 {
+        self.external_clock_hz
+            .map_or(INTERNAL_OSC_HZ, |external_clock_hz| {
+                external_clock_hz as f64
+            })
+    }
+
+    /// Returns the prescale value: the explicit `prescale`, if given, or
+    /// else the one calculated from `output_frequency_hz`
+    pub fn effective_prescale(&self) -> u8 {
+        self.prescale
+            .unwrap_or_else(|| calculate_prescale(self.oscillator_hz(), self.output_frequency_hz))
+    }
+
+    /// Returns `output_frequency_hz`, or (given an explicit `prescale`) the
+    /// output frequency derived from it, rounded to the nearest Hz
+    pub fn effective_output_frequency_hz(&self) -> u16 {
+        match self.prescale {
+            Some(prescale) => {
+                prescale_output_frequency_hz(self.oscillator_hz(), prescale).round() as u16
+            }
+            None => self.output_frequency_hz,
+        }
+    }
+}
+
+impl ChannelConfig {
+    pub fn limits(&self) -> (u16, u16) {
+        match self.custom_limits {
+            Some(limits) => limits.count_limits(),
+            None => (0, PCA_PWM_RESOLUTION),
+        }
+    }
+
+    /// Returns `count` plus the Channel's `trim_counts`, i.e. the count
+    /// written to the device for it
+    pub fn trimmed(&self, count: u16) -> u16 {
+        (count as i32 + self.trim_counts as i32).clamp(0, PCA_PWM_RESOLUTION as i32 - 1) as u16
+    }
+
+    /// Checks that `name` (if any) is usable as a log target: non-empty, and
+    /// only letters, digits, '_' and '-'
+    pub fn validate_name(&self) -> Pca9685Result<()> {
+        match &self.name {
+            Some(name)
+                if name.is_empty()
+                    || !name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') =>
+            {
+                Err(Pca9685Error::InvalidConfiguration(format!(
+                    "ChannelConfig.name '{}' must be non-empty and contain only letters, digits, '_' or '-'",
+                    name
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that every tag is usable in a selector (e.g., `?tag=left`):
+    /// non-empty, and only letters, digits, '_' and '-'
+    pub fn validate_tags(&self) -> Pca9685Result<()> {
+        match self.tags.iter().find(|tag| {
+            tag.is_empty()
+                || !tag
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        }) {
+            Some(tag) => Err(Pca9685Error::InvalidConfiguration(format!(
+                "ChannelConfig.tags '{}' must be non-empty and contain only letters, digits, '_' or '-'",
+                tag
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the log target of the Channel's messages: its `name`, if any
+    pub fn log_target(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("Channel {:?}", self.channel),
+        }
+    }
+
+    /// Returns the Channel as an entry of a configuration file: with its
+    /// [ChannelConfig::given_limits], and without output fields (e.g.,
+    /// `current_count`) or unset fields
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if the Channel cannot be
+    ///   serialized
+    pub fn file_entry(&self) -> Pca9685Result<Value> {
+        let mut config = self.clone();
+        config.custom_limits = self.given_limits();
+
+        let mut entry = match serde_yaml::to_value(&config) {
+            Ok(Value::Mapping(entry)) => entry,
+            _ => {
+                return Err(Pca9685Error::InvalidConfiguration(format!(
+                    "Unable to serialize Channel {:?}",
+                    self.channel
+                )))
+            }
+        };
+        for field in ["current_count", "owner", "display_value"] {
+            entry.remove(field);
+        }
+        if self.tags.is_empty() {
+            entry.remove("tags");
+        }
+
+        let mut entry = Value::Mapping(entry);
+        remove_nulls(&mut entry);
+        Ok(entry)
+    }
+
+    /// Returns `custom_limits` as they would be given to configure the
+    /// Channel: the pw_limits where present (count_limits are then derived
+    /// from them, as on recalibration), otherwise the count_limits
+    pub fn given_limits(&self) -> Option<ChannelLimits> {
+        self.custom_limits.map(|limits| match limits.pw_limits {
+            Some(_) => ChannelLimits {
+                count_limits: None,
+                ..limits
+            },
+            None => limits,
+        })
+    }
+}
+
+impl CommandType {
+    /// Returns true if commands of this type must supply a `value`
+    pub fn requires_value(&self) -> bool {
+        !matches!(
+            self,
+            CommandType::FullOn | CommandType::FullOff | CommandType::Relax
+        )
+    }
+}
+
+impl ChannelCommand {
+    /// Returns the command's `value` (or 0.0 for commands that take none),
+    /// after checking that `value` and `basis` are present only when
+    /// meaningful for the `command_type`.
+    pub fn checked_value(&self) -> Pca9685Result<f64> {
+        const VALUE_COMMAND_TYPES: &str =
+            "PulseCount | PulseWidth | Percent | AdjustCount | AdjustPct";
+
+        if self.basis.is_some() && self.command_type != CommandType::Percent {
+            return Err(Pca9685Error::InvalidCommand(String::from(
+                "Command body may only contain 'basis' when command_type is Percent.",
+            )));
+        }
+
+        match (self.command_type.requires_value(), self.value) {
+            (true, Some(value)) => Ok(value),
+            (true, None) => Err(Pca9685Error::InvalidCommand(format!(
+                "Command body must contain 'value' when command_type is {}.",
+                VALUE_COMMAND_TYPES
+            ))),
+            (false, Some(_)) => Err(Pca9685Error::InvalidCommand(format!(
+                "Command body may only contain 'value' when command_type is {}.",
+                VALUE_COMMAND_TYPES
+            ))),
+            (false, None) => Ok(0.0),
+        }
+    }
+}
+
+impl PcaClockConfig {
+    /// Returns the pulse widths produced by `prescale` from an oscillator of
+    /// `oscillator_hz`
+    pub fn from_prescale(oscillator_hz: f64, prescale: u8) -> Self {
+        let max_pw_ms = 1000.0 / prescale_output_frequency_hz(oscillator_hz, prescale);
+
+        PcaClockConfig {
+            max_pw_ms,
+            single_pw_duration_ms: max_pw_ms / PCA_PWM_RESOLUTION as f64,
+        }
+    }
+
+    pub fn pw_to_count(&self, pw_ms: f64) -> Result<u16, Pca9685Error> {
+        if pw_ms < 0.0 || pw_ms > self.max_pw_ms {
+            return Err(Pca9685Error::PulseWidthRangeError(pw_ms, self.max_pw_ms));
+        }
+
+        Ok((pw_ms / self.single_pw_duration_ms) as u16)
+    }
+}
+
+impl ChannelCalibration {
+    /// Checks that the points describe a usable correction: distinct counts,
+    /// and pulse widths that increase with count.
+    pub fn validate(&self) -> Pca9685Result<()> {
+        let [first, second] = self.points;
+
+        if first.count == second.count {
+            return Err(Pca9685Error::InvalidConfiguration(
+                "ChannelConfig.calibration points must be measured at different counts".to_string(),
+            ));
+        }
+        if self.count_duration_ms() <= 0.0 {
+            return Err(Pca9685Error::InvalidConfiguration(
+                "ChannelConfig.calibration measured_pw_ms must increase with count".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// The measured duration (in milliseconds) of a single count
+    fn count_duration_ms(&self) -> f64 {
+        let [first, second] = self.points;
+
+        (second.measured_pw_ms - first.measured_pw_ms) / (second.count as f64 - first.count as f64)
+    }
+
+    /// Returns the pulse width (in milliseconds) that (per the measurements)
+    /// `count` yields
+    pub(crate) fn count_to_pw(&self, count: u16) -> f64 {
+        let first = self.points[0];
+
+        first.measured_pw_ms + (count as f64 - first.count as f64) * self.count_duration_ms()
+    }
+
+    /// Returns the count that (per the measurements) yields `pw_ms`
+    pub(crate) fn pw_to_count(&self, pw_ms: f64, max_pw_ms: f64) -> Pca9685Result<u16> {
+        if pw_ms < 0.0 || pw_ms > max_pw_ms {
+            return Err(Pca9685Error::PulseWidthRangeError(pw_ms, max_pw_ms));
+        }
+
+        let first = self.points[0];
+        let count = first.count as f64 + (pw_ms - first.measured_pw_ms) / self.count_duration_ms();
+        if !(0.0..=PCA_PWM_RESOLUTION as f64).contains(&count) {
+            return Err(Pca9685Error::PulseWidthRangeError(pw_ms, max_pw_ms));
+        }
+
+        Ok(count as u16)
+    }
+}
+
+impl ThermalLimits {
+    /// One minute
+    pub fn default_window_ms() -> u64 {
+        60 * 1000
+    }
+
+    /// Returns true if `stats` exceed either threshold
+    pub fn is_exceeded(&self, stats: &ChannelStats) -> bool {
+        matches!(self.max_duty, Some(max_duty) if stats.duty > max_duty)
+            || matches!(
+                self.max_travel_counts_per_s,
+                Some(max_travel) if stats.travel_counts_per_s > max_travel
+            )
+    }
+}
+
+/// Applies an exponential response curve to `x` (in [-1.0, 1.0]):
+/// `(1 - expo) * x + expo * x^3`
+pub(crate) fn apply_expo(x: f64, expo: f64) -> f64 {
+    let expo = expo.clamp(0.0, 1.0);
+
+    (1.0 - expo) * x + expo * x.powi(3)
+}
+
+impl SequenceConfig {
+    /// Validates a sequence's name, which must be non-empty and contain only
+    /// letters, digits, '_' or '-'
+    pub fn validate_name(name: &str) -> Pca9685Result<()> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(Pca9685Error::InvalidConfiguration(format!(
+                "Sequence {}: name must be non-empty and contain only letters, digits, '_' or '-'",
+                name
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Validates the sequence on its own (see [Pca9685::validate_sequence] for
+    /// validation against the Channels' configuration)
+    pub fn validate(&self) -> Pca9685Result<()> {
+        let invalid = |reason: String| {
+            Err(Pca9685Error::InvalidConfiguration(format!(
+                "Sequence {}: {}",
+                self.name, reason
+            )))
+        };
+
+        SequenceConfig::validate_name(&self.name)?;
+        if self.keyframes.is_empty() && self.steps.is_empty() {
+            return invalid("no keyframes or steps".to_string());
+        }
+        if let (Some(duration_ms), Some(last)) = (self.duration_ms, self.keyframes.last()) {
+            if duration_ms < last.t_ms {
+                return invalid(format!(
+                    "duration_ms {} precedes the last keyframe",
+                    duration_ms
+                ));
+            }
+        }
+        if let Some(index) = self.steps.iter().position(|step| step.repeat == 0) {
+            return invalid(format!("steps[{}]: repeat must be at least 1", index));
+        }
+
+        let mut keyframe_times = HashSet::new();
+        for (index, keyframe) in self.keyframes.iter().enumerate() {
+            if Channel::try_from(keyframe.channel).is_err() {
+                return invalid(format!(
+                    "keyframes[{}]: invalid channel {}",
+                    index, keyframe.channel
+                ));
+            }
+            if matches!(
+                keyframe.command_type,
+                CommandType::AdjustCount | CommandType::AdjustPct
+            ) {
+                return invalid(format!(
+                    "keyframes[{}]: {:?} is not an absolute command",
+                    index, keyframe.command_type
+                ));
+            }
+            if index > 0 && keyframe.t_ms < self.keyframes[index - 1].t_ms {
+                return invalid(format!(
+                    "keyframes[{}]: t_ms {} precedes that of the previous keyframe",
+                    index, keyframe.t_ms
+                ));
+            }
+            if !keyframe_times.insert((keyframe.channel, keyframe.t_ms)) {
+                return invalid(format!(
+                    "keyframes[{}]: channel {} already has a keyframe at t_ms {}",
+                    index, keyframe.channel, keyframe.t_ms
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SequenceStep {
+    pub fn default_repeat() -> u32 {
+        1
+    }
+}
+
+impl MixerConfig {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        let invalid = |reason: String| {
+            Err(Pca9685Error::InvalidConfiguration(format!(
+                "Mixer {}: {}",
+                self.name, reason
+            )))
+        };
+
+        let mut inputs = HashSet::new();
+        if let Some(input) = self.inputs.iter().find(|input| !inputs.insert(*input)) {
+            return invalid(format!("input {} appears more than once", input));
+        }
+
+        let mut channels = HashSet::new();
+        for output in &self.outputs {
+            if Channel::try_from(output.channel).is_err() {
+                return invalid(format!("invalid channel {}", output.channel));
+            }
+            if !channels.insert(output.channel) {
+                return invalid(format!("channel {} appears more than once", output.channel));
+            }
+            if output.weights.len() != self.inputs.len() {
+                return invalid(format!(
+                    "channel {} has {} weights, expected {}",
+                    output.channel,
+                    output.weights.len(),
+                    self.inputs.len()
+                ));
+            }
+        }
+
+        for (input, expo) in &self.expo {
+            if !self.inputs.contains(input) {
+                return invalid(format!("expo given for unknown input {}", input));
+            }
+            if !(0.0..=1.0).contains(expo) {
+                return invalid(format!("expo of input {} must be within [0.0, 1.0]", input));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns each output Channel's position, in [-1.0, 1.0], given `inputs`
+    /// (one value per input, in the order of [MixerConfig::inputs]).
+    pub fn mix(&self, inputs: &[f64]) -> Vec<(u8, f64)> {
+        let inputs: Vec<f64> = self
+            .inputs
+            .iter()
+            .zip(inputs)
+            .map(|(name, input)| match self.expo.get(name) {
+                Some(expo) => apply_expo(*input, *expo),
+                None => *input,
+            })
+            .collect();
+
+        self.outputs
+            .iter()
+            .map(|output| {
+                let position = output
+                    .weights
+                    .iter()
+                    .zip(&inputs)
+                    .fold(output.offset, |sum, (weight, input)| sum + weight * input);
+                (output.channel, position.clamp(-1.0, 1.0))
+            })
+            .collect()
+    }
+}
+
+impl AxisMapping {
+    pub fn default_rate() -> f64 {
+        1.0
+    }
+
+    /// Returns the mixer input value corresponding to `axis_value`
+    pub fn map(&self, axis_value: f64) -> f64 {
+        let value = apply_expo(axis_value.clamp(-1.0, 1.0), self.expo) * self.rate;
+        let value = if self.reversed { -value } else { value };
+
+        value.clamp(-1.0, 1.0)
+    }
+}
+
+impl TeleopConfig {
+    /// Validates the profiles, including that each mapping targets an input
+    /// of one of `mixers`
+    pub fn validate(&self, mixers: &[MixerConfig]) -> Pca9685Result<()> {
+        let mut names = HashSet::new();
+        for profile in &self.profiles {
+            let invalid = |reason: String| {
+                Err(Pca9685Error::InvalidConfiguration(format!(
+                    "Teleop profile {}: {}",
+                    profile.name, reason
+                )))
+            };
+
+            if !names.insert(&profile.name) {
+                return invalid("appears more than once".to_string());
+            }
+            for mapping in &profile.axes {
+                let known = mixers.iter().any(|mixer| {
+                    mixer.name == mapping.mixer && mixer.inputs.contains(&mapping.input)
+                });
+                if !known {
+                    return invalid(format!(
+                        "axis {} maps to unknown input {}.{}",
+                        mapping.axis, mapping.mixer, mapping.input
+                    ));
+                }
+                if !(0.0..=1.0).contains(&mapping.expo) {
+                    return invalid(format!(
+                        "expo of axis {} must be within [0.0, 1.0]",
+                        mapping.axis
+                    ));
+                }
+                if mapping.rate.is_nan() || mapping.rate <= 0.0 {
+                    return invalid(format!(
+                        "rate of axis {} must be greater than 0.0",
+                        mapping.axis
+                    ));
+                }
+            }
+        }
+
+        if let Some(default_profile) = &self.default_profile {
+            if !names.contains(default_profile) {
+                return Err(Pca9685Error::NoSuchProfileError(default_profile.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl DualRate {
+    pub fn default_high() -> f64 {
+        1.0
+    }
+
+    pub fn validate(&self) -> Pca9685Result<()> {
+        for (name, rate) in [("low", self.low), ("high", self.high)] {
+            if !(rate > 0.0 && rate <= 1.0) {
+                return Err(Pca9685Error::InvalidConfiguration(format!(
+                    "ChannelConfig.dual_rate.{} must be within (0.0, 1.0]",
+                    name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the scale of the selected rate
+    pub fn scale(&self) -> f64 {
+        match self.selected {
+            Rate::Low => self.low,
+            Rate::High => self.high,
+        }
+    }
+}
+
+impl Easing {
+    /// Returns the progress [0.0, 1.0] of a move at the `fraction` [0.0, 1.0]
+    /// of its duration
+    pub fn apply(&self, fraction: f64) -> f64 {
+        let t = fraction.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut if t < 0.5 => 2.0 * t * t,
+            Easing::EaseInOut => 1.0 - (2.0 - 2.0 * t).powi(2) / 2.0,
+            Easing::Cubic if t < 0.5 => 4.0 * t.powi(3),
+            Easing::Cubic => 1.0 - (2.0 - 2.0 * t).powi(3) / 2.0,
+            Easing::Sine => (1.0 - (std::f64::consts::PI * t).cos()) / 2.0,
+        }
+    }
+}
+
+impl DisplayUnit {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        if let DisplayUnit::Degrees(range) = self {
+            if !(range.is_finite() && *range > 0.0) {
+                return Err(Pca9685Error::InvalidConfiguration(
+                    "ChannelConfig.display_unit.degrees must be greater than 0.0".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns `count` in this unit, given the Channel's count `limits`, the
+    /// pulse width (in milliseconds) `count` yields, and whether the Channel
+    /// is `inverted` (see [ChannelConfig::inverted])
+    pub(crate) fn value(&self, count: u16, limits: (u16, u16), pw_ms: f64, inverted: bool) -> f64 {
+        let (min_on_count, max_on_count) = limits;
+        let fraction = if max_on_count > min_on_count {
+            (count as f64 - min_on_count as f64) / (max_on_count - min_on_count) as f64
+        } else {
+            0.0
+        };
+        let fraction = if inverted { 1.0 - fraction } else { fraction };
+
+        match self {
+            DisplayUnit::Counts => count as f64,
+            DisplayUnit::Ms => pw_ms,
+            DisplayUnit::Us => pw_ms * 1000.0,
+            DisplayUnit::Percent => fraction * 100.0,
+            DisplayUnit::Degrees(range) => fraction * range,
+        }
+    }
+
+    /// Formats `value` (in this unit) for display, e.g. `90.0°`
+    pub fn format(&self, value: f64) -> String {
+        match self {
+            DisplayUnit::Counts => format!("{:.0} counts", value),
+            DisplayUnit::Ms => format!("{:0.4}ms", value),
+            DisplayUnit::Us => format!("{:.0}µs", value),
+            DisplayUnit::Percent => format!("{:0.1}%", value),
+            DisplayUnit::Degrees(_) => format!("{:0.1}°", value),
+        }
+    }
+}
+
+impl IdleConfig {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        let invalid = |reason: String| {
+            Err(Pca9685Error::InvalidConfiguration(format!(
+                "Config.idle: {}",
+                reason
+            )))
+        };
+
+        if self.after_ms == 0 {
+            return invalid("after_ms must be greater than 0".to_string());
+        }
+        for (index, behavior) in self.behaviors.iter().enumerate() {
+            if Channel::try_from(behavior.channel).is_err() {
+                return invalid(format!(
+                    "behaviors[{}]: no such channel {}",
+                    index, behavior.channel
+                ));
+            }
+            if !(0.0..=1.0).contains(&behavior.min_pct)
+                || !(0.0..=1.0).contains(&behavior.max_pct)
+                || behavior.min_pct > behavior.max_pct
+            {
+                return invalid(format!(
+                    "behaviors[{}]: min_pct and max_pct must be ordered within [0.0, 1.0]",
+                    index
+                ));
+            }
+            if behavior.min_interval_ms == 0 || behavior.min_interval_ms > behavior.max_interval_ms
+            {
+                return invalid(format!(
+                    "behaviors[{}]: min_interval_ms must be greater than 0, and at most max_interval_ms",
+                    index
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl DerivedChannelConfig {
+    /// Validates the derived Channel, whose value may be over any of
+    /// `virtual_channels`
+    pub fn validate(&self, virtual_channels: &[String]) -> Pca9685Result<()> {
+        let invalid = |reason: String| {
+            Err(Pca9685Error::InvalidConfiguration(format!(
+                "Derived channel {}: {}",
+                self.channel, reason
+            )))
+        };
+
+        if Channel::try_from(self.channel).is_err() {
+            return invalid("no such channel".to_string());
+        }
+        if let Some(source) = self
+            .value
+            .sources()
+            .find(|source| !virtual_channels.contains(source))
+        {
+            return invalid(format!("no such virtual channel {}", source));
+        }
+        match &self.value {
+            DerivedExpression::Sum(names) if names.is_empty() => {
+                invalid("sum must not be empty".to_string())
+            }
+            DerivedExpression::Scaled { scale, offset, .. }
+                if !scale.is_finite() || !offset.is_finite() =>
+            {
+                invalid("scale and offset must be finite".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl RuleConfig {
+    /// Validates the rule, whose `then` may apply any of `presets`
+    pub fn validate(&self, presets: &[PresetConfig]) -> Pca9685Result<()> {
+        let invalid = |reason: String| {
+            Err(Pca9685Error::InvalidConfiguration(format!(
+                "Rule {}: {}",
+                self.name, reason
+            )))
+        };
+
+        if self.name.is_empty() {
+            return invalid("name must not be empty".to_string());
+        }
+        if let RuleCondition::Above(threshold) | RuleCondition::Below(threshold) = &self.when {
+            if Channel::try_from(threshold.channel).is_err() {
+                return invalid(format!("when: no such channel {}", threshold.channel));
+            }
+            if !(0.0..=1.0).contains(&threshold.pct) {
+                return invalid("when: pct must be within [0.0, 1.0]".to_string());
+            }
+        }
+        match &self.then {
+            RuleAction::FullOn(channel)
+            | RuleAction::FullOff(channel)
+            | RuleAction::SetPct { channel, .. }
+                if Channel::try_from(*channel).is_err() =>
+            {
+                invalid(format!("then: no such channel {}", channel))
+            }
+            RuleAction::SetPct { pct, .. } if !(0.0..=1.0).contains(pct) => {
+                invalid("then: pct must be within [0.0, 1.0]".to_string())
+            }
+            RuleAction::Preset(name) if !presets.iter().any(|preset| &preset.name == name) => {
+                invalid(format!("then: no such preset {}", name))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl PresetConfig {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        let invalid = |reason: String| {
+            Err(Pca9685Error::InvalidConfiguration(format!(
+                "Preset {}: {}",
+                self.name, reason
+            )))
+        };
+
+        if self.name.is_empty() {
+            return invalid("name must not be empty".to_string());
+        }
+        if self.outputs.is_empty() {
+            return invalid("no outputs".to_string());
+        }
+
+        let mut channels = HashSet::new();
+        for (index, output) in self.outputs.iter().enumerate() {
+            if Channel::try_from(output.channel).is_err() {
+                return invalid(format!(
+                    "outputs[{}]: invalid channel {}",
+                    index, output.channel
+                ));
+            }
+            if matches!(
+                output.command_type,
+                CommandType::AdjustCount | CommandType::AdjustPct
+            ) {
+                return invalid(format!(
+                    "outputs[{}]: {:?} is not an absolute command",
+                    index, output.command_type
+                ));
+            }
+            if !channels.insert(output.channel) {
+                return invalid(format!(
+                    "outputs[{}]: channel {} already has an output",
+                    index, output.channel
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SceneConfig {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        match self
+            .htp_channels
+            .iter()
+            .find(|channel| Channel::try_from(**channel).is_err())
+        {
+            Some(channel) => Err(Pca9685Error::InvalidConfiguration(format!(
+                "Config.scenes.htp_channels: invalid channel {}",
+                channel
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Keyframe {
+    /// Returns the command yielding this keyframe's output
+    pub fn command(&self) -> ChannelCommand {
+        ChannelCommand {
+            channel: Channel::try_from(self.channel).unwrap(),
+            command_type: self.command_type,
+            value: self.value,
+            basis: None,
+            client_id: None,
+        }
+    }
+}
+
+impl PresetOutput {
+    /// Returns the command yielding this output
+    pub fn command(&self) -> ChannelCommand {
+        ChannelCommand {
+            channel: Channel::try_from(self.channel).unwrap(),
+            command_type: self.command_type,
+            value: self.value,
+            basis: None,
+            client_id: None,
+        }
+    }
+}
+
+impl GaitConfig {
+    pub fn default_stride_pct() -> f64 {
+        0.2
+    }
+
+    pub fn default_lift_pct() -> f64 {
+        0.2
+    }
+
+    pub fn default_period_ms() -> u64 {
+        1000
+    }
+
+    /// Validates the gait against the configured `groups`
+    pub fn validate(&self, groups: &[ChannelGroup]) -> Pca9685Result<()> {
+        let invalid = |reason: String| {
+            Err(Pca9685Error::InvalidConfiguration(format!(
+                "Gait {}: {}",
+                self.name, reason
+            )))
+        };
+
+        if self.name.is_empty() {
+            return invalid("name must not be empty".to_string());
+        }
+        if self.legs.is_empty() {
+            return invalid("has no legs".to_string());
+        }
+        for leg in &self.legs {
+            match groups.iter().find(|group| group.name == leg.group) {
+                None => return Err(Pca9685Error::NoSuchGroupError(leg.group.clone())),
+                Some(group) if !(1..=2).contains(&group.channels.len()) => {
+                    return invalid(format!(
+                        "leg {} must have a swing and, optionally, a lift channel",
+                        leg.group
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+        for (name, pct) in [("stride_pct", self.stride_pct), ("lift_pct", self.lift_pct)] {
+            if !(0.0..=1.0).contains(&pct) {
+                return invalid(format!("{} must be within [0.0, 1.0]", name));
+            }
+        }
+        if self.period_ms < MIN_GAIT_PERIOD_MS {
+            return invalid(format!("period_ms must be at least {}", MIN_GAIT_PERIOD_MS));
+        }
 
-        serde_yaml::from_str(&config).unwrap()
+        Ok(())
     }
 }
 
-impl ChannelConfig {
-    pub fn limits(&self) -> (u16, u16) {
-        match self.custom_limits {
-            Some(limits) => limits.count_limits(),
-            None => (0, PCA_PWM_RESOLUTION),
+impl LinearAxisConfig {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        let invalid = |reason: String| {
+            Err(Pca9685Error::InvalidConfiguration(format!(
+                "Linear axis {}: {}",
+                self.name, reason
+            )))
+        };
+
+        if self.name.is_empty() {
+            return invalid("name must not be empty".to_string());
+        }
+        if Channel::try_from(self.channel).is_err() {
+            return Err(Pca9685Error::NoSuchChannelError(self.channel));
+        }
+        if !(self.max_speed_mm_s.is_finite() && self.max_speed_mm_s > 0.0) {
+            return invalid("max_speed_mm_s must be greater than 0.0".to_string());
+        }
+        if !(self.min_mm.is_finite() && self.max_mm.is_finite() && self.min_mm < self.max_mm) {
+            return invalid("min_mm must be less than max_mm".to_string());
+        }
+        if matches!(self.speed_mm_s, Some(speed_mm_s) if self.validate_speed(speed_mm_s).is_err()) {
+            return invalid("speed_mm_s must be within (0.0, max_speed_mm_s]".to_string());
+        }
+        match &self.home.pin {
+            GpioPin::Path(path) if path.is_empty() => {
+                return invalid("home.path must not be empty".to_string());
+            }
+            GpioPin::Line { chip, .. } if chip.is_empty() => {
+                return invalid("home.chip must not be empty".to_string());
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Validates a speed at which to move the axis
+    pub fn validate_speed(&self, speed_mm_s: f64) -> Pca9685Result<()> {
+        if !(speed_mm_s > 0.0 && speed_mm_s <= self.max_speed_mm_s) {
+            return Err(Pca9685Error::InvalidCommand(format!(
+                "Linear axis {}: speed must be within (0.0, {}]mm/s",
+                self.name, self.max_speed_mm_s
+            )));
         }
+
+        Ok(())
     }
 }
 
-impl PcaClockConfig {
-    pub fn pw_to_count(&self, pw_ms: f64) -> Result<u16, Pca9685Error> {
-        if pw_ms < 0.0 || pw_ms > self.max_pw_ms {
-            return Err(Pca9685Error::PulseWidthRangeError(pw_ms, self.max_pw_ms));
+impl Replay {
+    pub fn default_rate() -> f64 {
+        1.0
+    }
+}
+
+impl Default for Replay {
+    fn default() -> Self {
+        Self {
+            channel_map: BTreeMap::new(),
+            rate: Replay::default_rate(),
         }
+    }
+}
 
-        Ok((pw_ms / self.single_pw_duration_ms) as u16)
+impl GimbalAxis {
+    pub fn default_center_pct() -> f64 {
+        0.5
+    }
+
+    pub fn default_max_pct() -> f64 {
+        1.0
+    }
+}
+
+impl GimbalConfig {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        let invalid = |reason: String| {
+            Err(Pca9685Error::InvalidConfiguration(format!(
+                "Config.gimbal: {}",
+                reason
+            )))
+        };
+
+        if self.imu.device.is_empty() {
+            return invalid("imu.device must not be empty".to_string());
+        }
+        if self.pitch.channel == self.roll.channel {
+            return invalid("pitch and roll must be different channels".to_string());
+        }
+        for (name, axis) in [("pitch", &self.pitch), ("roll", &self.roll)] {
+            if Channel::try_from(axis.channel).is_err() {
+                return invalid(format!("{}: no such channel {}", name, axis.channel));
+            }
+            if !(0.0..=1.0).contains(&axis.min_pct)
+                || !(0.0..=1.0).contains(&axis.max_pct)
+                || !(axis.min_pct..=axis.max_pct).contains(&axis.center_pct)
+            {
+                return invalid(format!(
+                    "{}: min_pct, center_pct and max_pct must be ordered within [0.0, 1.0]",
+                    name
+                ));
+            }
+            if axis.gains.validate().is_err() {
+                return invalid(format!("{}: gains must be non-negative numbers", name));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl JitterConfig {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        if self.amplitude_counts == 0
+            || !(self.bandwidth_hz > 0.0 && self.bandwidth_hz <= MAX_JITTER_BANDWIDTH_HZ)
+        {
+            return Err(Pca9685Error::InvalidCommand(format!(
+                "Jitter amplitude_counts must be greater than 0, and bandwidth_hz within (0.0, {}].",
+                MAX_JITTER_BANDWIDTH_HZ
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl FeedbackConfig {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        match &self.source {
+            FeedbackSource::Path(path) if path.is_empty() => {
+                return Err(Pca9685Error::InvalidConfiguration(
+                    "ChannelConfig.feedback.path must not be empty".to_string(),
+                ));
+            }
+            FeedbackSource::As5600(as5600)
+                if as5600.device.is_empty() || as5600.zero_offset >= 4096 =>
+            {
+                return Err(Pca9685Error::InvalidConfiguration(
+                    "ChannelConfig.feedback.as5600 requires a device, and a zero_offset within [0, 4096)"
+                        .to_string(),
+                ));
+            }
+            _ => (),
+        }
+        if let (Some(min_raw), Some(max_raw)) = (self.min_raw, self.max_raw) {
+            if !min_raw.is_finite() || !max_raw.is_finite() || min_raw == max_raw {
+                return Err(Pca9685Error::InvalidConfiguration(
+                    "ChannelConfig.feedback min_raw and max_raw must be distinct numbers"
+                        .to_string(),
+                ));
+            }
+        }
+        if self.verify.is_some() && (self.min_raw.is_none() || self.max_raw.is_none()) {
+            return Err(Pca9685Error::InvalidConfiguration(
+                "ChannelConfig.feedback.verify requires min_raw and max_raw".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Converts a `raw` feedback value to counts within `limits` (see
+    /// [FeedbackConfig]), returning None without `min_raw` and `max_raw`
+    pub fn to_count(&self, raw: f64, limits: (u16, u16)) -> Option<f64> {
+        let (min_raw, max_raw) = (self.min_raw?, self.max_raw?);
+        let (min_count, max_count) = (limits.0 as f64, limits.1 as f64);
+
+        Some(min_count + (raw - min_raw) / (max_raw - min_raw) * (max_count - min_count))
+    }
+}
+
+impl PositionVerification {
+    pub fn default_settle_ms() -> u64 {
+        500
+    }
+}
+
+impl PidGains {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        if [self.kp, self.ki, self.kd]
+            .iter()
+            .any(|gain| !(gain.is_finite() && *gain >= 0.0))
+        {
+            return Err(Pca9685Error::InvalidConfiguration(
+                "ChannelConfig.pid gains must be non-negative numbers".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl InputShaping {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        if !(self.frequency_hz.is_finite() && self.frequency_hz > 0.0) {
+            return Err(Pca9685Error::InvalidConfiguration(
+                "ChannelConfig.input_shaping.frequency_hz must be greater than 0.0".to_string(),
+            ));
+        }
+        if !(0.0..1.0).contains(&self.damping) {
+            return Err(Pca9685Error::InvalidConfiguration(
+                "ChannelConfig.input_shaping.damping must be within [0.0, 1.0)".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl LowPassConfig {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        if self.time_constant_ms == 0 {
+            return Err(Pca9685Error::InvalidConfiguration(
+                "ChannelConfig.low_pass.time_constant_ms must be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl StepTest {
+    pub fn default_settle_ms() -> u64 {
+        500
+    }
+
+    pub fn default_duration_ms() -> u64 {
+        1000
+    }
+
+    pub fn default_sample_interval_us() -> u64 {
+        1000
+    }
+
+    pub fn validate(&self) -> Pca9685Result<()> {
+        if self.duration_ms == 0 || self.sample_interval_us == 0 {
+            return Err(Pca9685Error::InvalidCommand(
+                "Step test duration_ms and sample_interval_us must be greater than 0.".to_string(),
+            ));
+        }
+        if self.settle_ms.saturating_add(self.duration_ms) > MAX_STEP_TEST_MS {
+            return Err(Pca9685Error::InvalidCommand(format!(
+                "Step test settle_ms + duration_ms must not exceed {}.",
+                MAX_STEP_TEST_MS
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl LoopRegion {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        if self.start_ms >= self.end_ms {
+            return Err(Pca9685Error::InvalidCommand(format!(
+                "Loop region start_ms ({}) must precede end_ms ({}).",
+                self.start_ms, self.end_ms
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl WatchdogConfig {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        if self.timeout_ms == 0 {
+            return Err(Pca9685Error::InvalidConfiguration(
+                "Config.watchdog.timeout_ms must be greater than 0".to_string(),
+            ));
+        }
+        if let Some(channel) = self
+            .channels
+            .iter()
+            .find(|channel| Channel::try_from(**channel).is_err())
+        {
+            return Err(Pca9685Error::NoSuchChannelError(*channel));
+        }
+
+        Ok(())
+    }
+}
+
+impl CircuitBreakerConfig {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        if self.max_errors == 0 || self.window_ms == 0 {
+            return Err(Pca9685Error::InvalidConfiguration(
+                "Config.circuit_breaker.max_errors and window_ms must be greater than 0"
+                    .to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl EscConfig {
+    pub fn default_arming_ms() -> u64 {
+        3000
+    }
+
+    pub fn validate(&self) -> Pca9685Result<()> {
+        if self.arming_ms == 0 || self.arming_ms > MAX_ESC_ARMING_MS {
+            return Err(Pca9685Error::InvalidConfiguration(format!(
+                "ChannelConfig.esc.arming_ms must be within (0, {}]",
+                MAX_ESC_ARMING_MS
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl ReducedHold {
+    pub fn validate(&self) -> Pca9685Result<()> {
+        if self.period_ms == 0 {
+            return Err(Pca9685Error::InvalidConfiguration(
+                "ChannelConfig.reduced_hold.period_ms must be greater than 0".to_string(),
+            ));
+        }
+        if !(self.duty > 0.0 && self.duty <= 1.0) {
+            return Err(Pca9685Error::InvalidConfiguration(
+                "ChannelConfig.reduced_hold.duty must be within (0.0, 1.0]".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if a Channel stationary for `stationary` should be
+    /// energized, i.e. within the `duty` portion of the current period.
+    pub fn is_energized(&self, stationary: Duration) -> bool {
+        let after = Duration::from_millis(self.after_ms);
+        if stationary < after {
+            return true;
+        }
+
+        let period_ms = self.period_ms as f64;
+        let phase_ms = (stationary - after).as_secs_f64() * 1000.0 % period_ms;
+
+        phase_ms < period_ms * self.duty
     }
 }
 
@@ -39,8 +1967,8 @@ impl ChannelLimits {
     pub fn from_count_limits(min_on_count: u16, max_on_count: u16) -> Self {
         Self {
             count_limits: Some(ChannelCountLimits {
-                min_on_count: min_on_count,
-                max_on_count: max_on_count,
+                min_on_count,
+                max_on_count,
             }),
             pw_limits: None,
         }
@@ -49,18 +1977,18 @@ impl ChannelLimits {
     pub(crate) fn from_pw_limits(
         min_on_pw_ms: f64,
         max_on_pw_ms: f64,
-        clock_config: PcaClockConfig,
-    ) -> Self {
-        Self {
+        pw_to_count: impl Fn(f64) -> Pca9685Result<u16>,
+    ) -> Pca9685Result<Self> {
+        Ok(Self {
             count_limits: Some(ChannelCountLimits {
-                min_on_count: clock_config.pw_to_count(min_on_pw_ms).unwrap(),
-                max_on_count: clock_config.pw_to_count(max_on_pw_ms).unwrap(),
+                min_on_count: pw_to_count(min_on_pw_ms)?,
+                max_on_count: pw_to_count(max_on_pw_ms)?,
             }),
             pw_limits: Some(ChannelPulseWidthLimits {
                 min_on_ms: min_on_pw_ms,
                 max_on_ms: max_on_pw_ms,
             }),
-        }
+        })
     }
 
     /// Returns true if `value` is within [`min_on_count`, `max_on_count`]
@@ -78,7 +2006,7 @@ impl ChannelLimits {
     }
 
     pub fn pct_to_count(&self, pct: f64) -> Pca9685Result<u16> {
-        if pct < 0.0 || pct > 1.0 {
+        if !(0.0..=1.0).contains(&pct) {
             return Err(Pca9685Error::PercentOfRangeError(pct));
         }
 
@@ -137,12 +2065,27 @@ impl Default for ChannelLimits {
 
 impl fmt::Debug for Pca9685Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match &*self {
+        match self {
             Pca9685Error::NoSuchChannelError(channel) => write!(
                 f,
                 "Invalid channel: {}.  Valid channels are [0,16).",
                 channel
             ),
+            Pca9685Error::NoSuchChannelNameError(name) => {
+                write!(f, "No channel is named {}.", name)
+            }
+            Pca9685Error::NoSuchGroupError(name) => write!(f, "Invalid group: {}.", name),
+            Pca9685Error::NoSuchTagError(tag) => write!(f, "No channel is tagged {}.", tag),
+            Pca9685Error::NoSuchMixerError(name) => write!(f, "Invalid mixer: {}.", name),
+            Pca9685Error::NoSuchProfileError(name) => {
+                write!(f, "Invalid teleop profile: {}.", name)
+            }
+            Pca9685Error::NoSuchSequenceError(name) => write!(f, "Invalid sequence: {}.", name),
+            Pca9685Error::NoSuchPresetError(name) => write!(f, "Invalid preset: {}.", name),
+            Pca9685Error::NoSuchGaitError(name) => write!(f, "Invalid gait: {}.", name),
+            Pca9685Error::NoSuchLinearAxisError(name) => {
+                write!(f, "Invalid linear axis: {}.", name)
+            }
             Pca9685Error::PulseWidthRangeError(value, max_pw_ms) => write!(
                 f,
                 "Pulse width value ({}ms) must be within the limits [0, {}].",
@@ -156,11 +2099,44 @@ impl fmt::Debug for Pca9685Error {
                 limits.count_limits().1
             ),
             Pca9685Error::InvalidConfiguration(msg) => write!(f, "Invalid configuration: {}", msg),
+            Pca9685Error::InvalidCommand(msg) => write!(f, "{}", msg),
             Pca9685Error::PercentOfRangeError(value) => write!(
                 f,
                 "Percentage value ({:0.4}) must be within the limits [0.0, 1.0]",
                 value
             ),
+            Pca9685Error::ChannelInactiveError(channel) => write!(
+                f,
+                "Channel {} has no current output to adjust (set an absolute value first).",
+                channel
+            ),
+            Pca9685Error::ChannelFaultedError(channel) => write!(
+                f,
+                "Channel {} is faulted after repeated driver errors (clear the fault to resume).",
+                channel
+            ),
+            Pca9685Error::ChannelMaintenanceError(channel) => write!(
+                f,
+                "Channel {} is under maintenance (clear maintenance to resume).",
+                channel
+            ),
+            Pca9685Error::ChannelUnconfiguredError(channel) => write!(
+                f,
+                "Channel {} has no custom_limits (configure it before commanding it).",
+                channel
+            ),
+            Pca9685Error::ChannelDisarmedError(channel) => write!(
+                f,
+                "Channel {} is an ESC that is not armed (arm it before applying throttle).",
+                channel
+            ),
+            Pca9685Error::PausedError => {
+                write!(f, "Commands are paused (resume to continue).")
+            }
+            Pca9685Error::DeviceInitError(msg) => {
+                write!(f, "Unable to initialize the PCA9685: {}", msg)
+            }
+            Pca9685Error::StorageError(msg) => write!(f, "Storage error: {}", msg),
             Pca9685Error::Pca9685DriverError(error) => {
                 write!(
                     f,
@@ -178,6 +2154,119 @@ impl fmt::Display for Pca9685Error {
     }
 }
 
+impl fmt::Display for GpioPin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GpioPin::Path(path) => write!(f, "{}", path),
+            GpioPin::Pin(pin) => write!(f, "GPIO {}", pin),
+            GpioPin::Line { chip, line } => write!(f, "{} line {}", chip, line),
+        }
+    }
+}
+
+impl fmt::Display for DebugReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PCA9685 {} {:#02x}: prescale {} ({:.2} Hz)",
+            self.device, self.address, self.prescale, self.actual_output_frequency_hz
+        )?;
+        writeln!(
+            f,
+            "  device error: {}",
+            self.device_error.as_deref().unwrap_or("none")
+        )?;
+        writeln!(f, "  reinitialized: {} time(s)", self.reinit_count)?;
+        match &self.last_driver_error {
+            Some(record) => writeln!(
+                f,
+                "  last driver error: {} (channel {}, at {} ms)",
+                record.error, record.channel as u8, record.timestamp_ms
+            )?,
+            None => writeln!(f, "  last driver error: none")?,
+        }
+        match self.paused {
+            Some(mode) => writeln!(f, "  paused: {:?}", mode)?,
+            None => writeln!(f, "  paused: no")?,
+        }
+        match &self.playback {
+            Some(playback) => writeln!(
+                f,
+                "  playing: {} at {} of {} ms (rate {})",
+                playback.sequence,
+                playback
+                    .t_ms
+                    .map_or("-".to_string(), |t_ms| t_ms.to_string()),
+                playback.duration_ms,
+                playback.rate
+            )?,
+            None => writeln!(f, "  playing: none")?,
+        }
+        match &self.recording {
+            Some(recording) => writeln!(
+                f,
+                "  recording: {} at {} ms ({} keyframes)",
+                recording.sequence, recording.t_ms, recording.keyframes
+            )?,
+            None => writeln!(f, "  recording: none")?,
+        }
+
+        for report in &self.channels {
+            let config = &report.config;
+            write!(f, "Channel {}", config.channel as u8)?;
+            if let Some(name) = &config.name {
+                write!(f, " ({})", name)?;
+            }
+            match config.current_count {
+                Some(count) => write!(f, ": count {}", count)?,
+                None => write!(f, ": off")?,
+            }
+            if let Some(owner) = &config.owner {
+                write!(f, ", owned by {}", owner)?;
+            }
+            if report.moving {
+                write!(f, ", moving")?;
+            }
+            writeln!(
+                f,
+                ", {} command(s), {} limit violation(s)",
+                report.metrics.commands_total.values().sum::<u64>(),
+                report.metrics.limit_violations_total
+            )?;
+
+            let stats = &report.stats;
+            write!(
+                f,
+                "  duty {:.2}, travel {:.1} counts/s",
+                stats.duty, stats.travel_counts_per_s
+            )?;
+            for (flag, label) in [
+                (stats.thermal_warning, "thermal warning"),
+                (stats.faulted, "faulted"),
+                (stats.under_maintenance, "under maintenance"),
+                (stats.position_error, "position error"),
+            ] {
+                if flag {
+                    write!(f, ", {}", label)?;
+                }
+            }
+            writeln!(f)?;
+            if let Some(record) = &report.last_driver_error {
+                writeln!(
+                    f,
+                    "  last driver error: {} (at {} ms)",
+                    record.error, record.timestamp_ms
+                )?;
+            }
+            if let Some(error) = &report.feedback_error {
+                writeln!(f, "  feedback error: {}", error)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
 pub fn serialize_channel<S>(channel: &Channel, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -233,3 +2322,452 @@ pub mod built_info {
     // The file has been placed there by the build script.
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{calculate_prescale, frequency_sweep, prescale_output_frequency_hz};
+    use crate::{
+        ChannelConfig, ChannelLimits, ChannelPulseWidthLimits, Config, Easing, MixerConfig,
+        MixerOutput, Pca9685, Pca9685Error, PcaClockConfig, INTERNAL_OSC_HZ, MAX_EXTERNAL_CLOCK_HZ,
+    };
+    use pwm_pca9685::Channel;
+
+    #[test]
+    fn prescale() {
+        // per PCA9685 documented example using 200Hz
+        assert_eq!(calculate_prescale(INTERNAL_OSC_HZ, 200), 30);
+        assert_eq!(calculate_prescale(INTERNAL_OSC_HZ, 1), u8::MAX);
+        assert_eq!(calculate_prescale(INTERNAL_OSC_HZ, u16::MAX), 3);
+        assert!((prescale_output_frequency_hz(INTERNAL_OSC_HZ, 30) - 196.9).abs() < 0.1);
+    }
+
+    #[test]
+    fn external_clock() {
+        let mut config: Config = serde_yaml::from_str(
+            "device: /dev/i2c-1\naddress: 0x40\noutput_frequency_hz: 50\nexternal_clock_hz: 16000000\n",
+        )
+        .unwrap();
+        assert!(config.validate_clock().is_ok());
+        assert_eq!(config.oscillator_hz(), 16_000_000.0);
+
+        // round(16MHz / (4096 x 50Hz)) - 1, rather than 121 from 25MHz
+        assert_eq!(config.effective_prescale(), 77);
+        let clock_config = PcaClockConfig::from_prescale(config.oscillator_hz(), 77);
+        assert!((clock_config.max_pw_ms - 19.968).abs() < 0.001);
+
+        config.external_clock_hz = Some(0);
+        assert!(config.validate_clock().is_err());
+        config.external_clock_hz = Some(MAX_EXTERNAL_CLOCK_HZ + 1);
+        assert!(config.validate_clock().is_err());
+    }
+
+    #[test]
+    fn addresses() {
+        let mut config: Config =
+            serde_yaml::from_str("device: /dev/i2c-1\naddress: 0x40\nsubaddresses: [0x71, 0x72]\n")
+                .unwrap();
+        assert!(config.validate_addresses().is_ok());
+        assert_eq!(config.all_call_address, Some(0x70));
+
+        config.subaddresses.push(0x70);
+        assert!(config.validate_addresses().is_err());
+        config.all_call_address = None;
+        assert!(config.validate_addresses().is_ok());
+
+        config.subaddresses.push(0x73);
+        assert!(config.validate_addresses().is_err());
+        config.subaddresses = vec![0x40];
+        assert!(config.validate_addresses().is_err());
+        config.subaddresses = vec![0x78];
+        assert!(config.validate_addresses().is_err());
+    }
+
+    #[test]
+    fn config_prescale() {
+        let mut config: Config =
+            serde_yaml::from_str("device: /dev/i2c-1\naddress: 0x40\nprescale: 121\n").unwrap();
+        assert!(config.validate_clock().is_ok());
+        assert_eq!(config.effective_prescale(), 121);
+        assert_eq!(config.effective_output_frequency_hz(), 50);
+
+        config.output_frequency_hz = 50;
+        assert!(config.validate_clock().is_err());
+
+        config.prescale = None;
+        assert!(config.validate_clock().is_ok());
+        assert_eq!(config.effective_prescale(), 121);
+
+        config.output_frequency_hz = 0;
+        assert!(config.validate_clock().is_err());
+
+        config.prescale = Some(2);
+        assert!(config.validate_clock().is_err());
+    }
+
+    #[test]
+    fn include() {
+        let dir = std::env::temp_dir().join("pca9685-include");
+        std::fs::create_dir_all(dir.join("robots")).unwrap();
+        std::fs::write(
+            dir.join("base.yaml"),
+            "device: /dev/i2c-1
+address: 0x40
+output_frequency_hz: 50
+channels:
+  - channel: 0
+    current_count: null
+    name: left_elbow
+    custom_limits:
+      count_limits: {min_on_count: 100, max_on_count: 200}
+groups:
+  - name: arm
+    channels: [0]
+",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("robots/robot-1.yaml"),
+            "include: [../base.yaml]
+address: 0x41
+channels:
+  - channel: 0
+    custom_limits:
+      count_limits: {min_on_count: 150, max_on_count: 250}
+  - channel: 1
+    current_count: null
+groups:
+  - name: arm
+    channels: [0, 1]
+",
+        )
+        .unwrap();
+
+        let config =
+            Config::try_load_from_file(dir.join("robots/robot-1.yaml").to_str().unwrap()).unwrap();
+        assert_eq!(config.device, "/dev/i2c-1");
+        assert_eq!(config.address, 0x41);
+        assert!(config.include.is_empty());
+        assert_eq!(config.channels.len(), 2);
+        assert_eq!(config.channels[0].name.as_deref(), Some("left_elbow"));
+        assert_eq!(
+            config.channels[0].custom_limits,
+            Some(ChannelLimits::from_count_limits(150, 250))
+        );
+        assert_eq!(config.groups[0].channels, vec![0, 1]);
+
+        std::fs::write(dir.join("base.yaml"), "include: [robots/robot-1.yaml]\n").unwrap();
+        assert!(
+            Config::try_load_from_file(dir.join("robots/robot-1.yaml").to_str().unwrap()).is_err()
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn interpolate() {
+        let dir = std::env::temp_dir();
+        std::fs::write(dir.join("pca9685-interpolate.secret"), "s3cr3t\n").unwrap();
+        std::env::set_var("PCA9685_INTERPOLATE_DEVICE", "/dev/i2c-7");
+
+        assert_eq!(
+            super::interpolate("${PCA9685_INTERPOLATE_DEVICE}", &dir).unwrap(),
+            "/dev/i2c-7"
+        );
+        assert_eq!(
+            super::interpolate("key=${file:pca9685-interpolate.secret};", &dir).unwrap(),
+            "key=s3cr3t;"
+        );
+        assert_eq!(super::interpolate("$${HOME}", &dir).unwrap(), "${HOME}");
+        assert!(super::interpolate("${PCA9685_INTERPOLATE_UNSET}", &dir).is_err());
+        assert!(super::interpolate("${PCA9685_INTERPOLATE_DEVICE", &dir).is_err());
+
+        std::fs::remove_file(dir.join("pca9685-interpolate.secret")).unwrap();
+    }
+
+    #[test]
+    fn uninterpolated() {
+        let dir = std::env::temp_dir();
+        let path = dir.join("pca9685-uninterpolated.yaml");
+        std::fs::write(dir.join("pca9685-uninterpolated.secret"), "s3cr3t\n").unwrap();
+        std::env::set_var("PCA9685_UNINTERPOLATED_DEVICE", "/dev/i2c-7");
+        std::fs::write(
+            &path,
+            "device: ${PCA9685_UNINTERPOLATED_DEVICE}
+address: 0x40
+output_frequency_hz: 50
+channels:
+  - channel: 0
+    name: ${file:pca9685-uninterpolated.secret}
+",
+        )
+        .unwrap();
+
+        // Resolved when loaded...
+        let config = Config::try_load_from_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(config.device, "/dev/i2c-7");
+        assert_eq!(config.channels[0].name.as_deref(), Some("s3cr3t"));
+
+        // ...but serialized as written
+        let yaml = config.to_yaml().unwrap();
+        assert!(yaml.contains("${PCA9685_UNINTERPOLATED_DEVICE}"));
+        assert!(yaml.contains("${file:pca9685-uninterpolated.secret}"));
+        assert!(!yaml.contains("s3cr3t"));
+        let uninterpolated = config.uninterpolated().unwrap();
+        assert_eq!(uninterpolated.device, "${PCA9685_UNINTERPOLATED_DEVICE}");
+        assert_eq!(
+            uninterpolated.channels[0].name.as_deref(),
+            Some("${file:pca9685-uninterpolated.secret}")
+        );
+
+        std::fs::remove_file(dir.join("pca9685-uninterpolated.secret")).unwrap();
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn starter_file() {
+        let path = std::env::temp_dir().join("pca9685-starter.yaml");
+        let path = path.to_str().unwrap();
+        let written = Config::write_starter_file(path, "/dev/i2c-1", 0x41).unwrap();
+
+        let config = Config::load_from_file(path);
+        assert_eq!(config.device, "/dev/i2c-1");
+        assert_eq!(config.address, 0x41);
+        assert!(config.strict);
+        assert_eq!(config.channels.len(), 16);
+        assert!(config.channels.iter().all(|ch| ch.custom_limits.is_none()));
+        assert_eq!(written.channels, config.channels);
+
+        // Nothing moves until configured
+        let pca = Pca9685::null(&config);
+        assert!(matches!(
+            pca.set_pct(Channel::C0, 0.5),
+            Err(Pca9685Error::ChannelUnconfiguredError(0))
+        ));
+        assert!(pca.full_off(Channel::C0).is_ok());
+        let mut channel = config.channels[0].clone();
+        channel.custom_limits = Some(ChannelLimits::from_count_limits(1000, 2000));
+        pca.configure_channel(&channel).unwrap();
+        assert_eq!(
+            pca.set_pct(Channel::C0, 0.5).unwrap().current_count,
+            Some(1500)
+        );
+    }
+
+    #[test]
+    fn persist_channel() {
+        let path = std::env::temp_dir().join("pca9685-persist-channel.yaml");
+        let path = path.to_str().unwrap();
+        std::fs::write(
+            path,
+            "# Bench rig
+device: /dev/i2c-1
+address: 0x40 # A0 bridged
+output_frequency_hz: 50
+channels:
+  # Pan servo
+  - channel: 0
+    current_count: null
+    custom_limits:
+      count_limits: {min_on_count: 100, max_on_count: 200}
+
+# Keep the watchdog short
+watchdog: {timeout_ms: 500}
+",
+        )
+        .unwrap();
+
+        let mut config: ChannelConfig = serde_yaml::from_str(
+            "channel: 3
+current_count: 300
+owner: operator-1
+custom_limits:
+  count_limits: {min_on_count: 205, max_on_count: 410}
+  pw_limits: {min_on_ms: 1.0, max_on_ms: 2.0}
+",
+        )
+        .unwrap();
+        Config::persist_channel(path, &config).unwrap();
+        let persisted = Config::load_from_file(path);
+        assert_eq!(persisted.channels.len(), 2);
+        assert_eq!(persisted.channels[1].channel as u8, 3);
+        assert_eq!(persisted.channels[1].current_count, None);
+        assert_eq!(persisted.channels[1].owner, None);
+        let limits = persisted.channels[1].custom_limits.unwrap();
+        assert_eq!(limits.count_limits, None);
+        assert_eq!(limits.pw_limits, config.custom_limits.unwrap().pw_limits);
+        let persisted = std::fs::read_to_string(path).unwrap();
+        assert!(persisted.starts_with(
+            "# Bench rig
+device: /dev/i2c-1
+address: 0x40 # A0 bridged
+output_frequency_hz: 50
+channels:
+  # Pan servo
+  - channel: 0
+    current_count: null
+    custom_limits:
+      count_limits: {min_on_count: 100, max_on_count: 200}
+  - channel: 3
+"
+        ));
+        assert!(persisted.ends_with(
+            "
+# Keep the watchdog short
+watchdog: {timeout_ms: 500}
+"
+        ));
+
+        config.channel = Channel::C0;
+        Config::persist_channel(path, &config).unwrap();
+        assert_eq!(Config::load_from_file(path).channels.len(), 2);
+        let persisted = std::fs::read_to_string(path).unwrap();
+        assert!(persisted.contains("channels:\n  # Pan servo\n  - channel: 0\n    custom_limits:"));
+        assert!(persisted.contains("# Keep the watchdog short"));
+        assert_eq!(persisted.matches("- channel: ").count(), 2);
+
+        config.custom_limits = Some(ChannelLimits {
+            count_limits: None,
+            pw_limits: Some(ChannelPulseWidthLimits {
+                min_on_ms: 1.0,
+                max_on_ms: 50.0,
+            }),
+        });
+        assert!(Config::persist_channel(path, &config).is_err());
+        assert_eq!(
+            Config::load_from_file(path).channels[0].custom_limits,
+            Some(limits)
+        );
+
+        // Flow-style channels can't be edited in place, so are rewritten
+        std::fs::write(
+            path,
+            "# Bench rig\ndevice: /dev/i2c-1\naddress: 0x40\noutput_frequency_hz: 50\nchannels: []\n",
+        )
+        .unwrap();
+        config.custom_limits = Some(limits);
+        Config::persist_channel(path, &config).unwrap();
+        assert_eq!(Config::load_from_file(path).channels.len(), 1);
+        assert!(!std::fs::read_to_string(path).unwrap().contains('#'));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn to_yaml() {
+        let config: Config = serde_yaml::from_str(
+            "device: /dev/i2c-1
+address: 0x40
+prescale: 121
+channels:
+  - channel: 3
+    current_count: 300
+    owner: operator-1
+    custom_limits:
+      count_limits: {min_on_count: 205, max_on_count: 410}
+      pw_limits: {min_on_ms: 1.0, max_on_ms: 2.0}
+",
+        )
+        .unwrap();
+
+        let yaml = config.to_yaml().unwrap();
+        assert!(!yaml.contains("current_count"));
+        assert!(!yaml.contains("owner"));
+        assert!(!yaml.contains("count_limits"));
+        assert!(!yaml.contains("null"));
+
+        let reloaded: Config = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(reloaded.prescale, Some(121));
+        assert_eq!(reloaded.channels.len(), 1);
+        assert_eq!(
+            reloaded.channels[0].custom_limits.unwrap().pw_limits,
+            config.channels[0].custom_limits.unwrap().pw_limits
+        );
+        assert!(Pca9685::validate_config(&reloaded).findings.is_empty());
+    }
+
+    #[test]
+    fn easing() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseInOut,
+            Easing::Cubic,
+            Easing::Sine,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert!((easing.apply(0.5) - 0.5).abs() < 1e-9);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert_eq!(easing.apply(2.0), 1.0);
+        }
+
+        // Eased moves start (and end) slower
+        assert_eq!(Easing::Linear.apply(0.25), 0.25);
+        assert_eq!(Easing::EaseInOut.apply(0.25), 0.125);
+        assert_eq!(Easing::Cubic.apply(0.25), 0.0625);
+        assert!((Easing::Sine.apply(0.25) - 0.146447).abs() < 1e-6);
+        assert_eq!(Easing::Cubic.apply(0.75), 0.9375);
+    }
+
+    #[test]
+    fn sweep() {
+        let steps = frequency_sweep(40, 60, 10);
+
+        assert_eq!(
+            steps
+                .iter()
+                .map(|step| step.requested_hz)
+                .collect::<Vec<_>>(),
+            vec![40, 50, 60]
+        );
+        for step in steps {
+            assert_eq!(
+                step.prescale,
+                calculate_prescale(INTERNAL_OSC_HZ, step.requested_hz)
+            );
+            assert!(step.error_pct.abs() < 1.0);
+        }
+    }
+
+    #[test]
+    fn mixer() {
+        let output = |channel: u8, weights: Vec<f64>| MixerOutput {
+            channel,
+            weights,
+            offset: 0.0,
+        };
+        let mut mixer = MixerConfig {
+            name: "vtail".to_owned(),
+            inputs: vec!["pitch".to_owned(), "yaw".to_owned()],
+            outputs: vec![output(0, vec![1.0, 1.0]), output(1, vec![1.0, -1.0])],
+            expo: Default::default(),
+        };
+
+        assert!(mixer.validate().is_ok());
+        assert_eq!(mixer.mix(&[0.5, 0.25]), vec![(0, 0.75), (1, 0.25)]);
+        assert_eq!(mixer.mix(&[1.0, 1.0]), vec![(0, 1.0), (1, 0.0)]);
+
+        mixer.expo.insert("pitch".to_owned(), 1.0);
+        assert!(mixer.validate().is_ok());
+        assert_eq!(mixer.mix(&[0.5, 0.0]), vec![(0, 0.125), (1, 0.125)]);
+
+        mixer.expo.insert("pitch".to_owned(), 1.5);
+        assert!(mixer.validate().is_err());
+
+        mixer.expo.clear();
+        mixer.expo.insert("roll".to_owned(), 0.5);
+        assert!(mixer.validate().is_err());
+        mixer.expo.clear();
+
+        mixer.outputs.push(output(1, vec![1.0, 0.0]));
+        assert!(mixer.validate().is_err());
+
+        mixer.outputs[2] = output(16, vec![1.0, 0.0]);
+        assert!(mixer.validate().is_err());
+
+        mixer.outputs[2] = output(2, vec![1.0]);
+        assert!(mixer.validate().is_err());
+
+        mixer.outputs.pop();
+        mixer.inputs = vec!["pitch".to_owned(), "pitch".to_owned()];
+        assert!(mixer.validate().is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{Pca9685, Pca9685Error, Pca9685Result, TestVectors, TraceWrite, VectorFailure};
+use std::fs;
+
+/// Formats `writes` compactly, e.g. `[0:1638, 1:off, 2:on]`
+fn format_writes(writes: &[TraceWrite]) -> String {
+    let writes: Vec<String> = writes
+        .iter()
+        .map(|write| match write.count {
+            None => format!("{}:off", write.channel),
+            Some(crate::PCA_PWM_RESOLUTION) => format!("{}:on", write.channel),
+            Some(count) => format!("{}:{}", write.channel, count),
+        })
+        .collect();
+
+    format!("[{}]", writes.join(", "))
+}
+
+impl TestVectors {
+    /// Loads the YAML test vectors file at `path`.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if the file cannot be read or
+    ///   parsed
+    pub fn load_from_file(path: &str) -> Pca9685Result<TestVectors> {
+        let yaml = fs::read_to_string(path).map_err(|error| {
+            Pca9685Error::InvalidConfiguration(format!("Unable to read {}: {}", path, error))
+        })?;
+
+        serde_yaml::from_str(&yaml).map_err(|error| {
+            Pca9685Error::InvalidConfiguration(format!("Unable to parse {}: {}", path, error))
+        })
+    }
+
+    /// Executes each vector's command, in order, against a tracing
+    /// [Pca9685] (see [Pca9685::tracing]) of the `config`, returning the
+    /// vectors whose writes (or rejection) differ from those expected.
+    ///
+    /// Error conditions:
+    /// * [Pca9685Error::InvalidConfiguration] if the `config` is invalid
+    pub fn verify(&self) -> Pca9685Result<Vec<VectorFailure>> {
+        let errors: Vec<String> = Pca9685::validate_config(&self.config)
+            .findings
+            .into_iter()
+            .filter(|finding| finding.severity == crate::FindingSeverity::Error)
+            .map(|finding| format!("{}: {}", finding.path, finding.message))
+            .collect();
+        if !errors.is_empty() {
+            return Err(Pca9685Error::InvalidConfiguration(errors.join("; ")));
+        }
+
+        let pca = Pca9685::tracing(&self.config);
+        pca.take_trace();
+
+        let mut failures = Vec::new();
+        for (index, vector) in self.vectors.iter().enumerate() {
+            let result = pca.execute(&vector.command);
+            let writes = pca.take_trace();
+
+            let message = match result {
+                Err(error) if !vector.rejected => Some(format!("rejected: {}", error)),
+                Ok(_) if vector.rejected => Some(format!(
+                    "accepted (expected rejection), writing {}",
+                    format_writes(&writes)
+                )),
+                _ if writes != vector.writes => Some(format!(
+                    "wrote {}, expected {}",
+                    format_writes(&writes),
+                    format_writes(&vector.writes)
+                )),
+                _ => None,
+            };
+            if let Some(message) = message {
+                failures.push(VectorFailure { index, message });
+            }
+        }
+
+        Ok(failures)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{TestVectors, VectorFailure};
+
+    #[test]
+    fn verify() {
+        let mut vectors: TestVectors = serde_yaml::from_str(
+            r#"
+config:
+  device: /dev/null
+  address: 0x40
+  output_frequency_hz: 50
+  channels:
+    - channel: 1
+      custom_limits:
+        count_limits: { min_on_count: 200, max_on_count: 400 }
+vectors:
+  - command: { channel: 0, command_type: PulseWidth, value: 1.5 }
+    writes: [{ channel: 0, count: 307 }]
+  - command: { channel: 1, command_type: Percent, value: 0.5 }
+    writes: [{ channel: 1, count: 300 }]
+  - command: { channel: 0, command_type: FullOn }
+    writes: [{ channel: 0, count: 4096 }]
+  - command: { channel: 1, command_type: PulseCount, value: 500 }
+    rejected: true
+"#,
+        )
+        .unwrap();
+        assert_eq!(vectors.verify().unwrap(), vec![]);
+
+        vectors.vectors[0].writes[0].count = Some(308);
+        vectors.vectors[3].rejected = false;
+        let failures = vectors.verify().unwrap();
+        assert_eq!(
+            failures.iter().map(|f| f.index).collect::<Vec<usize>>(),
+            vec![0, 3]
+        );
+        assert_eq!(
+            failures[0],
+            VectorFailure {
+                index: 0,
+                message: "wrote [0:307], expected [0:308]".to_string()
+            }
+        );
+
+        vectors.config.output_frequency_hz = 0;
+        assert!(vectors.verify().is_err());
+    }
+}
@@ -0,0 +1,113 @@
+use crate::WatchdogConfig;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// What a [Watchdog] has timed out on
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) enum WatchdogTrip {
+    /// No command or heartbeat from any client
+    All,
+
+    /// No command or heartbeat from the given client
+    Client(String),
+}
+
+/// Tracks when commands (or heartbeats) were last received, overall and per
+/// client.  Armed by the first feed; once tripped, disarmed until fed again.
+pub(crate) struct Watchdog {
+    timeout: Duration,
+    full_off_channels: HashSet<u8>,
+    fed_at: Option<Instant>,
+    clients: HashMap<String, Instant>,
+}
+
+impl Watchdog {
+    pub fn new(config: &WatchdogConfig) -> Self {
+        Watchdog {
+            timeout: Duration::from_millis(config.timeout_ms),
+            full_off_channels: config.channels.iter().copied().collect(),
+            fed_at: None,
+            clients: HashMap::new(),
+        }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Whether `channel` is driven full off on a trip, lacking a failsafe
+    /// output (see [WatchdogConfig::channels])
+    pub fn full_off(&self, channel: u8) -> bool {
+        self.full_off_channels.contains(&channel)
+    }
+
+    pub fn feed(&mut self, client_id: Option<&str>, now: Instant) {
+        self.fed_at = Some(now);
+        if let Some(client_id) = client_id {
+            self.clients.insert(client_id.to_string(), now);
+        }
+    }
+
+    /// Returns the trips that have occurred as of `now`
+    pub fn poll(&mut self, now: Instant) -> Vec<WatchdogTrip> {
+        let timeout = self.timeout;
+        let expired = |fed_at: &Instant| now.saturating_duration_since(*fed_at) >= timeout;
+        let mut trips = Vec::new();
+
+        if matches!(&self.fed_at, Some(fed_at) if expired(fed_at)) {
+            self.fed_at = None;
+            trips.push(WatchdogTrip::All);
+        }
+
+        let mut expired_clients: Vec<String> = self
+            .clients
+            .iter()
+            .filter(|(_, fed_at)| expired(fed_at))
+            .map(|(client_id, _)| client_id.clone())
+            .collect();
+        expired_clients.sort();
+        for client_id in expired_clients {
+            self.clients.remove(&client_id);
+            trips.push(WatchdogTrip::Client(client_id));
+        }
+
+        trips
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Watchdog, WatchdogTrip};
+    use crate::WatchdogConfig;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn poll() {
+        let mut watchdog = Watchdog::new(&WatchdogConfig {
+            timeout_ms: 100,
+            channels: vec![2],
+        });
+        let now = Instant::now();
+        let at = |ms: u64| now + Duration::from_millis(ms);
+
+        assert!(watchdog.full_off(2));
+        assert!(!watchdog.full_off(1));
+
+        // Not armed until fed
+        assert!(watchdog.poll(at(1000)).is_empty());
+
+        watchdog.feed(Some("rover"), at(0));
+        watchdog.feed(None, at(50));
+        assert!(watchdog.poll(at(99)).is_empty());
+        assert_eq!(
+            watchdog.poll(at(100)),
+            vec![WatchdogTrip::Client("rover".to_string())]
+        );
+        assert_eq!(watchdog.poll(at(150)), vec![WatchdogTrip::All]);
+
+        // Trips once, until fed again
+        assert!(watchdog.poll(at(500)).is_empty());
+        watchdog.feed(None, at(500));
+        assert_eq!(watchdog.poll(at(600)), vec![WatchdogTrip::All]);
+    }
+}